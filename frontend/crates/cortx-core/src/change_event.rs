@@ -0,0 +1,112 @@
+//! Notifications about storage mutations.
+//!
+//! `Storage` reports every successful save of a project, service, project
+//! script, global script or the settings to the observers registered with
+//! `Storage::subscribe`. Events only carry ids; observers fetch what they
+//! need. Observers run after the storage locks are released, so they may
+//! call back into `Storage`.
+//!
+//! A failed save is reported too (`SaveStatus`), as is the retry that
+//! writes it after all; `Storage::pending_saves` lists what's unsaved.
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// One saved mutation. Serializes to the event payload (the ids and the
+/// kind, or `null` for settings and save status).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ChangeEvent {
+    Project {
+        id: String,
+        change: ChangeKind,
+    },
+    #[serde(rename_all = "camelCase")]
+    Service {
+        project_id: String,
+        id: String,
+        change: ChangeKind,
+    },
+    /// A script of a project
+    #[serde(rename_all = "camelCase")]
+    Script {
+        project_id: String,
+        id: String,
+        change: ChangeKind,
+    },
+    GlobalScript {
+        id: String,
+        change: ChangeKind,
+    },
+    Settings,
+    /// A data file failed to save, or saved after failing before
+    SaveStatus,
+}
+
+impl ChangeEvent {
+    /// Name of the event the GUI listens for
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeEvent::Project { .. } => "project-changed",
+            ChangeEvent::Service { .. } => "service-changed",
+            ChangeEvent::Script { .. } => "script-changed",
+            ChangeEvent::GlobalScript { .. } => "global-script-changed",
+            ChangeEvent::Settings => "settings-changed",
+            ChangeEvent::SaveStatus => "save-status-changed",
+        }
+    }
+}
+
+type Observer = Arc<dyn Fn(ChangeEvent) + Send + Sync>;
+
+/// The observers of one `Storage`.
+#[derive(Default)]
+pub struct ChangeObservers {
+    observers: RwLock<Vec<Observer>>,
+}
+
+impl ChangeObservers {
+    pub fn subscribe(&self, observer: impl Fn(ChangeEvent) + Send + Sync + 'static) {
+        self.observers.write().push(Arc::new(observer));
+    }
+
+    /// Call every observer with `events`, in order. The list is copied
+    /// first, so an observer may subscribe another one.
+    pub fn notify(&self, events: impl IntoIterator<Item = ChangeEvent>) {
+        let observers = self.observers.read().clone();
+        if observers.is_empty() {
+            return;
+        }
+        for event in events {
+            for observer in &observers {
+                observer(event.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payloads_carry_only_ids() {
+        let event = ChangeEvent::Service { project_id: "p".into(), id: "s".into(), change: ChangeKind::Deleted };
+        assert_eq!(event.name(), "service-changed");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "projectId": "p", "id": "s", "change": "deleted" })
+        );
+        assert_eq!(serde_json::to_value(ChangeEvent::Settings).unwrap(), serde_json::Value::Null);
+    }
+}
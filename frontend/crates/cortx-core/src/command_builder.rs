@@ -1,7 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-use crate::models::GlobalScript;
+use thiserror::Error;
+
+use crate::models::EnvMode;
+use crate::models::{ExecutionRecord, GlobalScript, ParameterPreset, ScriptParameter};
 use crate::models::ScriptParamType;
+use crate::models::{ShellConfig, ShellKind};
 
 /// Build `(program, args)` from a GlobalScript, parameter values, and extra arguments.
 ///
@@ -17,6 +21,8 @@ pub fn build_command(
     } else {
         script.command.clone()
     };
+    let base_command =
+        interpolate_command(&base_command, &script.env_mode, script.env_vars.as_ref());
 
     // 2. Split into program + base args
     let mut tokens: Vec<String> = base_command.split_whitespace().map(|s| s.to_string()).collect();
@@ -26,12 +32,20 @@ pub fn build_command(
     let program = tokens.remove(0);
     let mut args = tokens;
 
-    // 3. Append parameter values in definition order
+    // 3. Append parameter values in definition order; only the first set
+    //    member of each mutex group is emitted
+    let mut used_groups = HashSet::new();
     for param_def in &script.parameters {
         if let Some(value) = param_values.get(&param_def.name) {
             if value.is_empty() {
                 continue;
             }
+            if let Some(group) = param_def.mutex_group.as_deref() {
+                let is_set = param_def.param_type != ScriptParamType::Bool || value == "true";
+                if is_set && !used_groups.insert(group) {
+                    continue;
+                }
+            }
             if param_def.param_type == ScriptParamType::Bool {
                 if value == "true" {
                     if let Some(ref flag) = param_def.long_flag {
@@ -47,11 +61,9 @@ pub fn build_command(
                 } else if let Some(ref flag) = param_def.short_flag {
                     args.push(flag.clone());
                 }
-                // Push value(s)
+                // Push value(s): one argument per word for nargs parameters
                 if param_def.nargs.is_some() {
-                    for v in value.split_whitespace() {
-                        args.push(v.to_string());
-                    }
+                    args.extend(nargs_values(value));
                 } else {
                     // Strip surrounding quotes if present
                     let clean = value
@@ -71,10 +83,624 @@ pub fn build_command(
     Some((program, args))
 }
 
+/// A `--param` / `--flag` value that doesn't fit the script's declared parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParamError {
+    #[error("Expected key=value, got '{0}'")]
+    Malformed(String),
+    #[error("Unknown parameter '{name}' (valid: {})", valid_list(.valid))]
+    Unknown { name: String, valid: Vec<String> },
+    #[error("Parameter '{0}' is not a bool; pass it with --param {0}=<value>")]
+    NotAFlag(String),
+    #[error("Parameter '{name}' expects a number, got '{value}'")]
+    NotANumber { name: String, value: String },
+    #[error("Parameter '{name}' expects true or false, got '{value}'")]
+    NotABool { name: String, value: String },
+    #[error("Parameter '{name}' must be one of {}, got '{value}'", .allowed.join(", "))]
+    NotInEnum { name: String, value: String, allowed: Vec<String> },
+    #[error("Missing required parameter{}: {}", plural_s(.0), .0.join(", "))]
+    MissingRequired(Vec<String>),
+    #[error("Parameters {} are mutually exclusive; pass only one", .0.join(", "))]
+    MutuallyExclusive(Vec<String>),
+    #[error("Parameter '{name}' takes {expected} values, got {got}")]
+    WrongValueCount { name: String, expected: usize, got: usize },
+}
+
+fn plural_s(names: &[String]) -> &'static str {
+    if names.len() == 1 { "" } else { "s" }
+}
+
+fn valid_list(names: &[String]) -> String {
+    if names.is_empty() {
+        "this script has no parameters".to_string()
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Other parameters in `name`'s mutex group, in definition order.
+pub fn mutex_siblings<'a>(script: &'a GlobalScript, name: &str) -> Vec<&'a ScriptParameter> {
+    let group = script
+        .parameters
+        .iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.mutex_group.as_deref());
+    match group {
+        Some(group) => script
+            .parameters
+            .iter()
+            .filter(|p| p.name != name && p.mutex_group.as_deref() == Some(group))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Enable or disable `name` in a run form's per-parameter `enabled` map.
+/// Enabling a member of a mutex group disables its (optional) siblings.
+pub fn set_param_enabled(script: &GlobalScript, enabled: &mut HashMap<String, bool>, name: &str, on: bool) {
+    if on {
+        for sibling in mutex_siblings(script, name) {
+            if !sibling.required {
+                enabled.insert(sibling.name.clone(), false);
+            }
+        }
+    }
+    enabled.insert(name.to_string(), on);
+}
+
+/// Values a preset contributes: the parameters it enables (required ones
+/// always are) that it has a value for. Presets saved before per-parameter
+/// enabling existed enable whatever they have a value for.
+pub fn preset_values(script: &GlobalScript, preset: &ParameterPreset) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for param in &script.parameters {
+        let is_enabled = if !preset.enabled.is_empty() {
+            preset.enabled.get(&param.name).copied().unwrap_or(false) || param.required
+        } else {
+            preset.values.contains_key(&param.name)
+        };
+        if !is_enabled {
+            continue;
+        }
+        if let Some(value) = preset.values.get(&param.name) {
+            values.insert(param.name.clone(), value.clone());
+        }
+    }
+    values
+}
+
+/// Resolve the parameter values for a non-interactive run: the preset's
+/// values first, then `--param key=value` and `--flag name` on top, then
+/// defaults for required parameters that are still unset. Names are matched
+/// case-insensitively; every value is checked against its declared type.
+pub fn resolve_param_values(
+    script: &GlobalScript,
+    preset: Option<&ParameterPreset>,
+    params: &[String],
+    flags: &[String],
+) -> Result<HashMap<String, String>, ParamError> {
+    let mut values = preset.map(|p| preset_values(script, p)).unwrap_or_default();
+    let mut explicit: Vec<&ScriptParameter> = Vec::new();
+
+    for entry in params {
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| ParamError::Malformed(entry.clone()))?;
+        let param = find_param(script, key.trim())?;
+        values.insert(param.name.clone(), value.to_string());
+        explicit.push(param);
+    }
+    for flag in flags {
+        let param = find_param(script, flag.trim())?;
+        if param.param_type != ScriptParamType::Bool {
+            return Err(ParamError::NotAFlag(param.name.clone()));
+        }
+        values.insert(param.name.clone(), "true".to_string());
+        explicit.push(param);
+    }
+
+    // A mutex group member given on the command line replaces whichever
+    // sibling the preset set; two given at once is an error
+    for param in &explicit {
+        let siblings = mutex_siblings(script, &param.name);
+        let clashing: Vec<String> = siblings
+            .iter()
+            .filter(|s| explicit.iter().any(|e| e.name == s.name))
+            .map(|s| s.name.clone())
+            .collect();
+        if !clashing.is_empty() {
+            let mut names = vec![param.name.clone()];
+            names.extend(clashing);
+            names.sort();
+            return Err(ParamError::MutuallyExclusive(names));
+        }
+        for sibling in siblings {
+            values.remove(&sibling.name);
+        }
+    }
+
+    let mut missing = Vec::new();
+    for param in &script.parameters {
+        let is_set = values.get(&param.name).is_some_and(|v| !v.is_empty());
+        if !is_set && param.required {
+            match param.default_value.as_deref().filter(|d| !d.is_empty()) {
+                Some(default) => {
+                    values.insert(param.name.clone(), default.to_string());
+                }
+                None => missing.push(param.name.clone()),
+            }
+        }
+    }
+    if !missing.is_empty() {
+        return Err(ParamError::MissingRequired(missing));
+    }
+
+    for param in &script.parameters {
+        if let Some(value) = values.get(&param.name) {
+            check_value(param, value)?;
+        }
+    }
+    Ok(values)
+}
+
+/// Fingerprint of a script's parameter definitions (names, types, flags),
+/// stored on execution records to tell whether they changed since a run.
+pub fn parameters_signature(script: &GlobalScript) -> String {
+    script
+        .parameters
+        .iter()
+        .map(|p| {
+            format!(
+                "{}:{:?}:{}:{}:{}:{}",
+                p.name,
+                p.param_type,
+                p.long_flag.as_deref().unwrap_or(""),
+                p.short_flag.as_deref().unwrap_or(""),
+                p.nargs.as_deref().unwrap_or(""),
+                p.enum_values.join("|")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Why a past execution can't be replayed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ReplayError {
+    #[error("Script {0} no longer exists")]
+    ScriptMissing(String),
+    #[error("Recorded parameters no longer fit the script: {0}")]
+    Params(#[from] ParamError),
+    #[error("Empty command")]
+    EmptyCommand,
+}
+
+/// A past execution rebuilt against the script as it is now.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayPlan {
+    pub program: String,
+    pub args: Vec<String>,
+    pub param_values: HashMap<String, String>,
+    /// `None` for records made before working dirs were recorded
+    pub working_dir: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Rebuild the command of `record` with its exact parameter values. Every
+/// recorded value must still name a parameter and fit its type; a changed
+/// parameter definition that still fits is only a warning.
+pub fn plan_replay(record: &ExecutionRecord, script: Option<&GlobalScript>) -> Result<ReplayPlan, ReplayError> {
+    let script = script
+        .filter(|s| s.id == record.script_id)
+        .ok_or_else(|| ReplayError::ScriptMissing(record.script_id.clone()))?;
+
+    for (name, value) in &record.parameters_used {
+        let param = script
+            .parameters
+            .iter()
+            .find(|p| p.name == *name)
+            .ok_or_else(|| ParamError::Unknown {
+                name: name.clone(),
+                valid: script.parameters.iter().map(|p| p.name.clone()).collect(),
+            })?;
+        check_value(param, value)?;
+    }
+    let missing: Vec<String> = script
+        .parameters
+        .iter()
+        .filter(|p| p.required && !record.parameters_used.get(&p.name).is_some_and(|v| !v.is_empty()))
+        .map(|p| p.name.clone())
+        .collect();
+    if !missing.is_empty() {
+        return Err(ParamError::MissingRequired(missing).into());
+    }
+
+    let mut warnings = Vec::new();
+    if record
+        .parameters_signature
+        .as_deref()
+        .is_some_and(|sig| sig != parameters_signature(script))
+    {
+        warnings.push(format!("The parameters of '{}' changed since this run", script.name));
+    }
+
+    let (program, args) =
+        build_command(script, &record.parameters_used, &record.extra_args).ok_or(ReplayError::EmptyCommand)?;
+    Ok(ReplayPlan {
+        program,
+        args,
+        param_values: record.parameters_used.clone(),
+        working_dir: record.working_dir.clone(),
+        warnings,
+    })
+}
+
+fn find_param<'a>(script: &'a GlobalScript, name: &str) -> Result<&'a ScriptParameter, ParamError> {
+    script
+        .parameters
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| ParamError::Unknown {
+            name: name.to_string(),
+            valid: script.parameters.iter().map(|p| p.name.clone()).collect(),
+        })
+}
+
+/// The separate values of an `nargs` parameter: its words, where quotes
+/// keep a value with spaces together (`"New York" Paris`).
+pub fn nargs_values(value: &str) -> Vec<String> {
+    split_args(value)
+}
+
+/// Check that parameters with a fixed `nargs` count (e.g. `2`) got exactly
+/// that many values. Unset and empty values aren't checked.
+pub fn check_value_counts(script: &GlobalScript, values: &HashMap<String, String>) -> Result<(), ParamError> {
+    for param in &script.parameters {
+        if let Some(value) = values.get(&param.name).filter(|v| !v.is_empty()) {
+            check_value_count(param, value)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_value_count(param: &ScriptParameter, value: &str) -> Result<(), ParamError> {
+    let Some(expected) = param.nargs.as_deref().and_then(|n| n.trim().parse::<usize>().ok()) else {
+        return Ok(());
+    };
+    let got = nargs_values(value).len();
+    if got != expected {
+        return Err(ParamError::WrongValueCount { name: param.name.clone(), expected, got });
+    }
+    Ok(())
+}
+
+/// Type-check one value (each word separately for `nargs` parameters, whose
+/// fixed count must match).
+fn check_value(param: &ScriptParameter, value: &str) -> Result<(), ParamError> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    let words: Vec<String> = if param.nargs.is_some() {
+        check_value_count(param, value)?;
+        nargs_values(value)
+    } else {
+        vec![value.trim().to_string()]
+    };
+    for word in words {
+        let word = word.as_str();
+        match param.param_type {
+            ScriptParamType::Number if word.parse::<f64>().is_err() => {
+                return Err(ParamError::NotANumber { name: param.name.clone(), value: word.to_string() });
+            }
+            ScriptParamType::Bool if word != "true" && word != "false" => {
+                return Err(ParamError::NotABool { name: param.name.clone(), value: word.to_string() });
+            }
+            ScriptParamType::Enum
+                if !param.enum_values.is_empty() && !param.enum_values.iter().any(|v| v == word) =>
+            {
+                return Err(ParamError::NotInEnum {
+                    name: param.name.clone(),
+                    value: word.to_string(),
+                    allowed: param.enum_values.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Pull `--param key=value` / `--flag name` (or the `--param=...` forms) out
+/// of raw shortcut arguments (`cortx <script> ...`). Returns `(params, flags,
+/// rest)`; everything after a literal `--` is passed through untouched.
+pub fn extract_param_args(args: &[String]) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let (mut params, mut flags, mut rest) = (Vec::new(), Vec::new(), Vec::new());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            rest.extend(iter.cloned());
+            break;
+        }
+        if let Some(value) = arg.strip_prefix("--param=") {
+            params.push(value.to_string());
+        } else if let Some(value) = arg.strip_prefix("--flag=") {
+            flags.push(value.to_string());
+        } else if arg == "--param" || arg == "--flag" {
+            match iter.next() {
+                Some(value) if arg == "--param" => params.push(value.clone()),
+                Some(value) => flags.push(value.clone()),
+                None => rest.push(arg.clone()),
+            }
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    (params, flags, rest)
+}
+
+/// Quoting rules of the shell a command line is written for.
+///
+/// Global scripts are spawned without a shell, so their `(program, args)`
+/// need no quoting; it matters once they're rendered as one line (copied,
+/// shown, logged) or joined into a command a shell will parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    /// `sh` / `bash` / `zsh`: single quotes, nothing expands inside them
+    Posix,
+    /// `cmd.exe`: double quotes, `""` for a quote, `^%` for a percent sign
+    Cmd,
+    /// PowerShell: single quotes, `''` for a quote
+    PowerShell,
+}
+
+impl QuoteStyle {
+    /// What the platform's default shell expects
+    pub fn native() -> Self {
+        if cfg!(windows) { QuoteStyle::Cmd } else { QuoteStyle::Posix }
+    }
+
+    /// What `shell` (from `AppSettings::shell`) expects
+    pub fn for_shell(shell: &ShellConfig) -> Self {
+        let custom = shell.custom_path.as_deref().map(str::trim).filter(|p| !p.is_empty());
+        match (shell.kind, custom) {
+            (ShellKind::Sh | ShellKind::Bash | ShellKind::Zsh, _) => QuoteStyle::Posix,
+            (ShellKind::Cmd, _) => QuoteStyle::Cmd,
+            (ShellKind::Powershell, _) => QuoteStyle::PowerShell,
+            (ShellKind::Custom, Some(path)) => {
+                let stem = std::path::Path::new(path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_lowercase();
+                match stem.as_str() {
+                    "cmd" => QuoteStyle::Cmd,
+                    "powershell" | "pwsh" => QuoteStyle::PowerShell,
+                    _ => QuoteStyle::Posix,
+                }
+            }
+            (ShellKind::Default, _) | (ShellKind::Custom, None) => QuoteStyle::native(),
+        }
+    }
+}
+
+/// `value` as one literal word for a `style` shell: left as is when it has
+/// no whitespace or special characters, quoted otherwise.
+pub fn shell_quote(value: &str, style: QuoteStyle) -> String {
+    let safe = match style {
+        QuoteStyle::Posix => "_-.,/:@+=%",
+        QuoteStyle::Cmd => "_-./:@+\\",
+        QuoteStyle::PowerShell => "_-./:\\",
+    };
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || safe.contains(c)) {
+        return value.to_string();
+    }
+    match style {
+        QuoteStyle::Posix => format!("'{}'", value.replace('\'', r"'\''")),
+        QuoteStyle::PowerShell => format!("'{}'", value.replace('\'', "''")),
+        QuoteStyle::Cmd => {
+            // `%VAR%` expands even inside quotes: step out to escape it
+            let mut quoted = String::from("\"");
+            for c in value.chars() {
+                match c {
+                    '"' => quoted.push_str("\"\""),
+                    '%' => quoted.push_str("\"^%\""),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+    }
+}
+
+/// `program` and `args` as one command line for a `style` shell
+pub fn shell_join(program: &str, args: &[String], style: QuoteStyle) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(|word| shell_quote(word, style))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split a typed argument line (extra args) into words the way a POSIX
+/// shell would, without expanding anything: whitespace separates, `'...'` is
+/// literal, `"..."` is literal apart from `\"` and `\\`, and a backslash
+/// escapes a quote, backslash or space. Other backslashes are kept, so
+/// Windows paths survive.
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' if matches!(chars.peek(), Some('"' | '\\')) => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' if matches!(chars.peek(), Some(&next) if next.is_whitespace() || "'\"\\".contains(next)) => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Substitute `${VAR}` and `${VAR:-default}` references in `command` from `env`.
+///
+/// `${VAR}` is left untouched when `VAR` is unknown, so the shell still gets a
+/// chance to expand it. `${VAR:-default}` uses the default when `VAR` is unset
+/// or empty, like POSIX shells. Bare `$VAR` is never rewritten.
+pub fn interpolate_env(command: &str, env: &BTreeMap<String, String>) -> String {
+    substitute(command, |name| env.get(name).map(String::as_str), &mut Vec::new())
+}
+
+/// Interpolate `command` against the environment the spawned process will see:
+/// the parent environment filtered by `env_mode`, overlaid with `env_vars`.
+pub fn interpolate_command(
+    command: &str,
+    env_mode: &EnvMode,
+    env_vars: Option<&HashMap<String, String>>,
+) -> String {
+    if !command.contains("${") {
+        return command.to_string();
+    }
+    interpolate_env(command, &crate::spawn_env::effective_env(env_mode, env_vars))
+}
+
+/// A `${VAR}` placeholder no source could resolve.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExpandError {
+    #[error("Unresolved variable{} in command: {} (add to env vars or use ${{VAR:-default}})", plural_s(.0), .0.join(", "))]
+    Unresolved(Vec<String>),
+}
+
+/// Strict version of [`interpolate_env`] for commands about to be spawned.
+///
+/// `sources` are searched in order and the first one that has the variable
+/// wins. A `${VAR}` none of them has is an error instead of being passed on
+/// literally. Substituted values and defaults are not expanded again, and
+/// anything that isn't `${NAME}` (bare `$VAR`, Windows `%VAR%`) is left for
+/// the shell.
+pub fn expand_env(command: &str, sources: &[&BTreeMap<String, String>]) -> Result<String, ExpandError> {
+    let mut unresolved = Vec::new();
+    let expanded = substitute(
+        command,
+        |name| sources.iter().find_map(|env| env.get(name)).map(String::as_str),
+        &mut unresolved,
+    );
+    if unresolved.is_empty() {
+        Ok(expanded)
+    } else {
+        Err(ExpandError::Unresolved(unresolved))
+    }
+}
+
+/// [`expand_env`] against what a spawned process will see: `env_vars` first,
+/// then the parent environment filtered by `env_mode`.
+pub fn expand_command(
+    command: &str,
+    env_mode: &EnvMode,
+    env_vars: Option<&HashMap<String, String>>,
+) -> Result<String, ExpandError> {
+    if !command.contains("${") {
+        return Ok(command.to_string());
+    }
+    let vars: BTreeMap<String, String> = env_vars
+        .map(|vars| vars.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+    let parent = crate::spawn_env::effective_env(env_mode, None);
+    expand_env(command, &[&vars, &parent])
+}
+
+/// Replace every well-formed `${NAME}` / `${NAME:-default}` via `lookup`.
+/// Names `lookup` doesn't know (and that have no default) stay as written
+/// and are collected, once each, into `unresolved`.
+fn substitute<'a>(
+    command: &str,
+    lookup: impl Fn(&str) -> Option<&'a str>,
+    unresolved: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(command.len());
+    let mut rest = command;
+
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let inner = &after[..end];
+        let (name, default) = match inner.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (inner, None),
+        };
+        let value = if is_var_name(name) {
+            match lookup(name) {
+                Some(v) if !(v.is_empty() && default.is_some()) => Some(v),
+                _ => default,
+            }
+        } else {
+            None
+        };
+
+        match value {
+            Some(v) => out.push_str(v),
+            None => {
+                if is_var_name(name) && !unresolved.iter().any(|n| n == name) {
+                    unresolved.push(name.to_string());
+                }
+                out.push_str(&rest[start..start + 2 + end + 1]);
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{GlobalScript, ScriptParameter, ScriptParamType};
+    use crate::models::{EnvMode, GlobalScript, OutputFormat, ScriptParameter, ScriptParamType};
     use chrono::Utc;
 
     fn make_script(command: &str, script_path: Option<&str>, params: Vec<ScriptParameter>) -> GlobalScript {
@@ -88,14 +714,26 @@ mod tests {
             color: None,
             status: None,
             tags: vec![],
+            folder: None,
             parameters: params,
             parameter_presets: vec![],
             default_preset_id: None,
             env_vars: None,
+            env_mode: EnvMode::Inherit,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             order: 0,
             auto_discovered: false,
+            content_hash: None,
+            raw_help: None,
+            requires: vec![],
+            artifacts: vec![],
+            origin: None,
+            output_format: OutputFormat::Raw,
+            allow_concurrent: false,
+            mutex_group: None,
+            queue_on_mutex: false,
+            schedule: None,
         }
     }
 
@@ -110,6 +748,7 @@ mod tests {
             required: false,
             enum_values: vec![],
             nargs: nargs.map(|s| s.to_string()),
+            mutex_group: None,
         }
     }
 
@@ -169,6 +808,32 @@ mod tests {
         ])));
     }
 
+    #[test]
+    fn nargs_keeps_quoted_values_together() {
+        let params = vec![make_param("players", ScriptParamType::String, Some("--players"), None, Some("+"))];
+        let script = make_script("game", None, params);
+        let mut values = HashMap::new();
+        values.insert("players".to_string(), "alice \"bob smith\" 'carol'".to_string());
+        let (_, args) = build_command(&script, &values, &[]).unwrap();
+        assert_eq!(args, vec!["--players", "alice", "bob smith", "carol"]);
+    }
+
+    #[test]
+    fn fixed_nargs_count_is_checked() {
+        let params = vec![make_param("range", ScriptParamType::Number, Some("--speed-range"), None, Some("2"))];
+        let script = make_script("game", None, params);
+
+        let values = resolve_param_values(&script, None, &strings(&["range=1 5"]), &[]).unwrap();
+        let (_, args) = build_command(&script, &values, &[]).unwrap();
+        assert_eq!(args, vec!["--speed-range", "1", "5"]);
+
+        let err = resolve_param_values(&script, None, &strings(&["range=1 5 9"]), &[]).unwrap_err();
+        assert_eq!(err, ParamError::WrongValueCount { name: "range".into(), expected: 2, got: 3 });
+
+        let values = HashMap::from([("range".to_string(), "1".to_string())]);
+        assert!(check_value_counts(&script, &values).is_err());
+    }
+
     #[test]
     fn quote_stripping() {
         let params = vec![make_param("msg", ScriptParamType::String, Some("--msg"), None, None)];
@@ -229,4 +894,353 @@ mod tests {
             "--alpha".to_string(), "1".to_string(),
         ])));
     }
+
+    #[test]
+    fn quoting_keeps_spaces_and_quotes_together() {
+        assert_eq!(shell_quote("plain-value_1.txt", QuoteStyle::Posix), "plain-value_1.txt");
+        assert_eq!(shell_quote("my project", QuoteStyle::Posix), "'my project'");
+        assert_eq!(shell_quote("it's", QuoteStyle::Posix), r"'it'\''s'");
+        assert_eq!(shell_quote("", QuoteStyle::Posix), "''");
+
+        assert_eq!(shell_quote(r"C:\My Files\a.txt", QuoteStyle::Cmd), r#""C:\My Files\a.txt""#);
+        assert_eq!(shell_quote(r"C:\tools\a.txt", QuoteStyle::Cmd), r"C:\tools\a.txt");
+        assert_eq!(shell_quote(r#"say "hi""#, QuoteStyle::Cmd), r#""say ""hi""""#);
+        assert_eq!(shell_quote("a&b", QuoteStyle::Cmd), r#""a&b""#);
+
+        assert_eq!(shell_quote("it's here", QuoteStyle::PowerShell), "'it''s here'");
+    }
+
+    #[test]
+    fn quoting_stops_variable_expansion() {
+        assert_eq!(shell_quote("$HOME", QuoteStyle::Posix), "'$HOME'");
+        assert_eq!(shell_quote("cost: $5 `id`", QuoteStyle::Posix), "'cost: $5 `id`'");
+        assert_eq!(shell_quote("$env:PATH", QuoteStyle::PowerShell), "'$env:PATH'");
+        assert_eq!(shell_quote("%PATH%", QuoteStyle::Cmd), r#"""^%"PATH"^%"""#);
+
+        // A POSIX shell gives the value back unchanged
+        #[cfg(unix)]
+        {
+            let value = "my $HOME 'quoted' \"too\" \\n";
+            let line = format!("printf %s {}", shell_quote(value, QuoteStyle::Posix));
+            let output = std::process::Command::new("sh").args(["-c", &line]).output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), value);
+        }
+    }
+
+    #[test]
+    fn joined_command_lines_round_trip_through_split_args() {
+        let args: Vec<String> = ["--name", "my project", "--price", "$5", "it's"].iter().map(|s| s.to_string()).collect();
+        let line = shell_join("deploy", &args, QuoteStyle::Posix);
+        assert_eq!(line, r"deploy --name 'my project' --price '$5' 'it'\''s'");
+
+        let mut words = split_args(&line);
+        assert_eq!(words.remove(0), "deploy");
+        assert_eq!(words, args);
+
+        assert_eq!(
+            split_args(r#"--msg "say \"hi\"" --dir C:\temp  ''"#),
+            vec!["--msg", r#"say "hi""#, "--dir", r"C:\temp", ""]
+        );
+    }
+
+    #[test]
+    fn quote_style_follows_the_shell_setting() {
+        let shell = |kind, path: Option<&str>| ShellConfig { kind, custom_path: path.map(String::from) };
+        assert_eq!(QuoteStyle::for_shell(&shell(ShellKind::Bash, None)), QuoteStyle::Posix);
+        assert_eq!(QuoteStyle::for_shell(&shell(ShellKind::Cmd, None)), QuoteStyle::Cmd);
+        assert_eq!(QuoteStyle::for_shell(&shell(ShellKind::Custom, Some("C:/x/pwsh.exe"))), QuoteStyle::PowerShell);
+        assert_eq!(QuoteStyle::for_shell(&shell(ShellKind::Custom, Some("/usr/bin/fish"))), QuoteStyle::Posix);
+        assert_eq!(QuoteStyle::for_shell(&shell(ShellKind::Default, None)), QuoteStyle::native());
+    }
+
+    fn env(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn interpolates_known_vars() {
+        let vars = env(&[("API_URL", "http://localhost:3000")]);
+        assert_eq!(
+            interpolate_env("curl ${API_URL}/health", &vars),
+            "curl http://localhost:3000/health"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_and_bare_vars_untouched() {
+        let vars = env(&[("A", "1")]);
+        assert_eq!(interpolate_env("echo ${MISSING} $A ${A}", &vars), "echo ${MISSING} $A 1");
+        assert_eq!(interpolate_env("echo ${not valid}", &vars), "echo ${not valid}");
+        assert_eq!(interpolate_env("echo ${A", &vars), "echo ${A");
+    }
+
+    #[test]
+    fn default_fallback() {
+        let vars = env(&[("SET", "yes"), ("EMPTY", "")]);
+        assert_eq!(interpolate_env("${SET:-no}", &vars), "yes");
+        assert_eq!(interpolate_env("${EMPTY:-fallback}", &vars), "fallback");
+        assert_eq!(interpolate_env("${UNSET:-8080}", &vars), "8080");
+        assert_eq!(interpolate_env("${UNSET:-}x", &vars), "x");
+    }
+
+    #[test]
+    fn build_command_uses_script_env_vars() {
+        let mut script = make_script("deploy --target ${TARGET:-dev}", None, vec![]);
+        let result = build_command(&script, &HashMap::new(), &[]);
+        assert_eq!(result, Some(("deploy".to_string(), vec!["--target".to_string(), "dev".to_string()])));
+
+        script.env_vars = Some(HashMap::from([("TARGET".to_string(), "prod".to_string())]));
+        let result = build_command(&script, &HashMap::new(), &[]);
+        assert_eq!(result, Some(("deploy".to_string(), vec!["--target".to_string(), "prod".to_string()])));
+    }
+
+    #[test]
+    fn expand_checks_sources_in_order() {
+        let service = env(&[("PORT", "4000")]);
+        let parent = env(&[("PORT", "3000"), ("HOME", "/home/me")]);
+        assert_eq!(
+            expand_env("serve --port ${PORT} --root ${HOME}", &[&service, &parent]),
+            Ok("serve --port 4000 --root /home/me".to_string())
+        );
+        assert_eq!(expand_env("serve --port ${PORT}", &[&parent]), Ok("serve --port 3000".to_string()));
+    }
+
+    #[test]
+    fn expand_reports_unresolved_vars() {
+        let vars = env(&[("A", "1")]);
+        let err = expand_env("run ${MISSING} ${A} ${OTHER} ${MISSING}", &[&vars]).unwrap_err();
+        assert_eq!(err, ExpandError::Unresolved(vec!["MISSING".into(), "OTHER".into()]));
+        assert!(err.to_string().contains("MISSING, OTHER"));
+        // No sources at all is the same as none having the var
+        assert!(expand_env("run ${A}", &[]).is_err());
+    }
+
+    #[test]
+    fn expand_defaults_and_no_nesting() {
+        let vars = env(&[("EMPTY", ""), ("REF", "${A}"), ("A", "1")]);
+        assert_eq!(expand_env("${UNSET:-8080} ${EMPTY:-x}", &[&vars]), Ok("8080 x".to_string()));
+        // Values and defaults are used as-is, never expanded again
+        assert_eq!(expand_env("${REF}", &[&vars]), Ok("${A}".to_string()));
+        assert_eq!(expand_env("${UNSET:-$A}", &[&vars]), Ok("$A".to_string()));
+    }
+
+    #[test]
+    fn expand_leaves_other_syntaxes_alone() {
+        let vars = env(&[("A", "1")]);
+        assert_eq!(
+            expand_env("echo %A% %MISSING% $A $MISSING ${A} ${not valid} ${A", &[&vars]),
+            Ok("echo %A% %MISSING% $A $MISSING 1 ${not valid} ${A".to_string())
+        );
+    }
+
+    #[test]
+    fn expand_command_prefers_env_vars() {
+        let vars = HashMap::from([("CORTX_TEST_EXPAND_PORT".to_string(), "5000".to_string())]);
+        assert_eq!(
+            expand_command("serve ${CORTX_TEST_EXPAND_PORT}", &EnvMode::Inherit, Some(&vars)),
+            Ok("serve 5000".to_string())
+        );
+        assert!(expand_command("serve ${CORTX_TEST_EXPAND_PORT}", &EnvMode::Inherit, None).is_err());
+        assert_eq!(expand_command("serve %PORT%", &EnvMode::Clean, None), Ok("serve %PORT%".to_string()));
+    }
+
+    fn deploy_script() -> GlobalScript {
+        let mut env = make_param("env", ScriptParamType::Enum, Some("--env"), None, None);
+        env.required = true;
+        env.enum_values = vec!["staging".into(), "prod".into()];
+        let region = make_param("region", ScriptParamType::String, None, Some("-r"), None);
+        let replicas = make_param("replicas", ScriptParamType::Number, Some("--replicas"), None, None);
+        let dry_run = make_param("dry_run", ScriptParamType::Bool, Some("--dry-run"), None, None);
+        make_script("deploy", None, vec![env, region, replicas, dry_run])
+    }
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn cli_params_map_to_flags() {
+        let script = deploy_script();
+        let values = resolve_param_values(
+            &script,
+            None,
+            &strings(&["env=staging", "REGION=eu-west-1"]),
+            &strings(&["dry_run"]),
+        )
+        .unwrap();
+        let (_, args) = build_command(&script, &values, &[]).unwrap();
+        assert_eq!(args, strings(&["--env", "staging", "-r", "eu-west-1", "--dry-run"]));
+    }
+
+    #[test]
+    fn cli_params_override_preset() {
+        let script = deploy_script();
+        let preset = ParameterPreset {
+            id: "p".into(),
+            name: "eu".into(),
+            description: None,
+            values: HashMap::from([
+                ("env".to_string(), "prod".to_string()),
+                ("region".to_string(), "eu-west-1".to_string()),
+            ]),
+            enabled: HashMap::new(),
+        };
+        let values =
+            resolve_param_values(&script, Some(&preset), &strings(&["env=staging"]), &[]).unwrap();
+        assert_eq!(values["env"], "staging");
+        assert_eq!(values["region"], "eu-west-1");
+    }
+
+    #[test]
+    fn cli_param_validation_errors() {
+        let script = deploy_script();
+        let err = resolve_param_values(&script, None, &[], &[]).unwrap_err();
+        assert_eq!(err, ParamError::MissingRequired(vec!["env".into()]));
+
+        let err = resolve_param_values(&script, None, &strings(&["env=staging", "replicas=two"]), &[])
+            .unwrap_err();
+        assert!(matches!(err, ParamError::NotANumber { .. }));
+
+        let err = resolve_param_values(&script, None, &strings(&["env=dev"]), &[]).unwrap_err();
+        assert!(err.to_string().contains("staging, prod"));
+
+        let err = resolve_param_values(&script, None, &strings(&["env=staging", "zone=a"]), &[])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown parameter 'zone' (valid: env, region, replicas, dry_run)"
+        );
+
+        let err = resolve_param_values(&script, None, &strings(&["env=staging"]), &strings(&["region"]))
+            .unwrap_err();
+        assert_eq!(err, ParamError::NotAFlag("region".into()));
+
+        let err = resolve_param_values(&script, None, &strings(&["env"]), &[]).unwrap_err();
+        assert_eq!(err, ParamError::Malformed("env".into()));
+    }
+
+    fn format_script() -> GlobalScript {
+        let mut json = make_param("json", ScriptParamType::Bool, Some("--json"), None, None);
+        let mut yaml = make_param("yaml", ScriptParamType::Bool, Some("--yaml"), None, None);
+        json.mutex_group = Some("format".into());
+        yaml.mutex_group = Some("format".into());
+        let out = make_param("out", ScriptParamType::String, Some("--out"), None, None);
+        make_script("export", None, vec![json, yaml, out])
+    }
+
+    #[test]
+    fn enabling_a_mutex_member_disables_its_siblings() {
+        let script = format_script();
+        let mut enabled = HashMap::new();
+        set_param_enabled(&script, &mut enabled, "out", true);
+        set_param_enabled(&script, &mut enabled, "json", true);
+        set_param_enabled(&script, &mut enabled, "yaml", true);
+        assert!(!enabled["json"]);
+        assert!(enabled["yaml"] && enabled["out"]);
+
+        // The form passes enabled params only; the builder would also drop a
+        // second set member on its own
+        let all: HashMap<String, String> = [("json", "true"), ("yaml", "true"), ("out", "x")]
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let from_form: HashMap<String, String> =
+            all.iter().filter(|(k, _)| enabled[*k]).map(|(k, v)| (k.clone(), v.clone())).collect();
+        let (_, args) = build_command(&script, &from_form, &[]).unwrap();
+        assert_eq!(args, strings(&["--yaml", "--out", "x"]));
+        let (_, args) = build_command(&script, &all, &[]).unwrap();
+        assert_eq!(args, strings(&["--json", "--out", "x"]));
+
+        // An unset member doesn't claim the group
+        let values = HashMap::from([("json".to_string(), "false".to_string()), ("yaml".to_string(), "true".to_string())]);
+        let (_, args) = build_command(&script, &values, &[]).unwrap();
+        assert_eq!(args, strings(&["--yaml"]));
+    }
+
+    #[test]
+    fn cli_mutex_members_replace_preset_and_clash() {
+        let script = format_script();
+        let preset = ParameterPreset {
+            id: "p".into(),
+            name: "json".into(),
+            description: None,
+            values: HashMap::from([("json".to_string(), "true".to_string())]),
+            enabled: HashMap::new(),
+        };
+        let values = resolve_param_values(&script, Some(&preset), &[], &strings(&["yaml"])).unwrap();
+        assert!(!values.contains_key("json"));
+        assert_eq!(values["yaml"], "true");
+
+        let err = resolve_param_values(&script, None, &[], &strings(&["yaml", "json"])).unwrap_err();
+        assert_eq!(err, ParamError::MutuallyExclusive(strings(&["json", "yaml"])));
+    }
+
+    #[test]
+    fn required_param_falls_back_to_default() {
+        let mut script = deploy_script();
+        script.parameters[0].default_value = Some("prod".into());
+        let values = resolve_param_values(&script, None, &[], &[]).unwrap();
+        assert_eq!(values["env"], "prod");
+    }
+
+    #[test]
+    fn extracts_param_args_from_shortcut() {
+        let args = strings(&["--param", "env=staging", "--flag=dry_run", "-v", "--", "--param", "x"]);
+        let (params, flags, rest) = extract_param_args(&args);
+        assert_eq!(params, strings(&["env=staging"]));
+        assert_eq!(flags, strings(&["dry_run"]));
+        assert_eq!(rest, strings(&["-v", "--param", "x"]));
+    }
+
+    fn recorded_run(script: &GlobalScript, values: &[(&str, &str)], extra: &[&str]) -> ExecutionRecord {
+        let mut record = ExecutionRecord::new(script.id.clone());
+        record.parameters_used = values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        record.extra_args = strings(extra);
+        record.working_dir = Some("/srv/deploy".into());
+        record.parameters_signature = Some(parameters_signature(script));
+        record
+    }
+
+    #[test]
+    fn replay_rebuilds_the_same_command() {
+        let script = deploy_script();
+        let record = recorded_run(&script, &[("env", "prod"), ("replicas", "3"), ("dry_run", "true")], &["-v"]);
+        let original = build_command(&script, &record.parameters_used, &record.extra_args).unwrap();
+
+        let plan = plan_replay(&record, Some(&script)).unwrap();
+        assert_eq!((plan.program, plan.args), original);
+        assert_eq!(plan.working_dir.as_deref(), Some("/srv/deploy"));
+        assert!(plan.warnings.is_empty());
+    }
+
+    #[test]
+    fn replay_validates_against_the_current_script() {
+        let script = deploy_script();
+        let record = recorded_run(&script, &[("env", "staging"), ("region", "eu")], &[]);
+        assert_eq!(
+            plan_replay(&record, None).unwrap_err(),
+            ReplayError::ScriptMissing(script.id.clone())
+        );
+
+        // A definition changed but the recorded values still fit: warn only
+        let mut changed = script.clone();
+        changed.parameters[1].long_flag = Some("--region".into());
+        let plan = plan_replay(&record, Some(&changed)).unwrap();
+        assert_eq!(plan.warnings, vec!["The parameters of 'test' changed since this run"]);
+        assert!(plan.args.contains(&"--region".to_string()));
+
+        // A recorded parameter that's gone, or a value that no longer fits, fails
+        let mut removed = script.clone();
+        removed.parameters.remove(1);
+        assert!(matches!(
+            plan_replay(&record, Some(&removed)).unwrap_err(),
+            ReplayError::Params(ParamError::Unknown { .. })
+        ));
+        let mut narrowed = script.clone();
+        narrowed.parameters[0].enum_values = vec!["prod".into()];
+        assert!(matches!(
+            plan_replay(&record, Some(&narrowed)).unwrap_err(),
+            ReplayError::Params(ParamError::NotInEnum { .. })
+        ));
+    }
 }
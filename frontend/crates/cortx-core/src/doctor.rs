@@ -0,0 +1,306 @@
+//! Environment checks behind `cortx doctor`.
+//!
+//! Each check takes what it inspects (a directory, settings, a PATH value)
+//! as arguments, so tests can run it against a temp dir instead of the
+//! machine's real setup. A failed check is critical; a warning only
+//! affects an optional feature.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::models::{AppSettings, ShellConfig, TerminalConfig, TerminalPreset};
+use crate::project_files;
+use crate::script_validation::program_resolves;
+use crate::storage::{validate_data_file, Storage};
+
+/// Terminals the Linux external launch tries, in order
+const LINUX_TERMINALS: &[&str] = &["gnome-terminal", "konsole", "xfce4-terminal", "alacritty", "kitty", "xterm"];
+
+const PROBE_FILE: &str = ".cortx-doctor-probe";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Something optional won't work
+    Warn,
+    /// cortx itself won't work properly
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix it, for anything but a pass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, message: String) -> Self {
+        Self { name, status: CheckStatus::Pass, message, hint: None }
+    }
+
+    fn problem(name: &'static str, status: CheckStatus, message: String, hint: String) -> Self {
+        Self { name, status, message, hint: Some(hint) }
+    }
+}
+
+/// Whether any check failed critically
+pub fn has_failures(results: &[CheckResult]) -> bool {
+    results.iter().any(|r| r.status == CheckStatus::Fail)
+}
+
+/// Every check, in the order `cortx doctor` prints them.
+pub fn run_checks(app_dir: &Path, path_var: Option<&OsStr>) -> Vec<CheckResult> {
+    let data_dir = check_data_dir(app_dir);
+    let data_files = check_data_files(app_dir);
+    let settings = load_settings(app_dir);
+    vec![
+        data_dir,
+        data_files,
+        check_shell(&settings.shell, path_var),
+        check_terminal(&settings.terminal, path_var),
+        check_vscode(path_var),
+    ]
+}
+
+/// Settings from `app_dir`, or the defaults when they can't be read (the
+/// data files check reports why).
+pub fn load_settings(app_dir: &Path) -> AppSettings {
+    fs::read_to_string(app_dir.join("settings.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The data dir exists (or can be created) and accepts writes.
+pub fn check_data_dir(app_dir: &Path) -> CheckResult {
+    const NAME: &str = "Data directory";
+    let probe = app_dir.join(PROBE_FILE);
+    let result = fs::create_dir_all(app_dir).and_then(|_| fs::write(&probe, b"ok"));
+    let _ = fs::remove_file(&probe);
+    match result {
+        Ok(()) => CheckResult::pass(NAME, format!("{} is writable", app_dir.display())),
+        Err(e) => CheckResult::problem(
+            NAME,
+            CheckStatus::Fail,
+            format!("Cannot write to {}: {}", app_dir.display(), e),
+            "Make sure the directory exists and belongs to your user".to_string(),
+        ),
+    }
+}
+
+/// Every data file that exists parses as what it should hold.
+pub fn check_data_files(app_dir: &Path) -> CheckResult {
+    const NAME: &str = "Data files";
+    let mut checked = 0;
+    let mut broken = Vec::new();
+    for name in Storage::ARCHIVE_FILES {
+        let path = app_dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        checked += 1;
+        let outcome = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| validate_data_file(name, &content).map_err(|e| e.to_string()));
+        if let Err(reason) = outcome {
+            broken.push(format!("{} ({})", name, reason));
+        }
+    }
+    let projects = project_files::read_dir(app_dir);
+    checked += projects.projects.len() + projects.unreadable.len();
+    for (path, reason) in projects.unreadable {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        broken.push(format!("{}/{} ({})", project_files::PROJECTS_DIR, name, reason));
+    }
+
+    if broken.is_empty() {
+        CheckResult::pass(NAME, format!("{} file{} OK", checked, if checked == 1 { "" } else { "s" }))
+    } else {
+        CheckResult::problem(
+            NAME,
+            CheckStatus::Fail,
+            format!("Unreadable: {}", broken.join(", ")),
+            format!(
+                "Fix the file by hand or restore one from {}",
+                app_dir.join("backups").display()
+            ),
+        )
+    }
+}
+
+/// The shell service and script commands run through is installed.
+pub fn check_shell(shell: &ShellConfig, path_var: Option<&OsStr>) -> CheckResult {
+    const NAME: &str = "Shell";
+    let (program, _) = crate::runtime_state::shell_wrap_with(shell, "");
+    if program_resolves(&program, None, path_var) {
+        CheckResult::pass(NAME, format!("Commands run through {}", program))
+    } else {
+        CheckResult::problem(
+            NAME,
+            CheckStatus::Fail,
+            format!("'{}' was not found", program),
+            "Install it or pick another shell with `cortx settings set shell <kind>`".to_string(),
+        )
+    }
+}
+
+/// The terminal preset external launches use can be started.
+pub fn check_terminal(terminal: &TerminalConfig, path_var: Option<&OsStr>) -> CheckResult {
+    const NAME: &str = "Terminal";
+    let candidates = terminal_programs(terminal);
+    if candidates.is_empty() {
+        return CheckResult::problem(
+            NAME,
+            CheckStatus::Warn,
+            "Custom terminal path is not configured".to_string(),
+            "Set terminal.customPath in the settings".to_string(),
+        );
+    }
+
+    match candidates.iter().find(|p| program_resolves(p, None, path_var)) {
+        Some(found) => CheckResult::pass(NAME, format!("External launches use {}", found)),
+        None => CheckResult::problem(
+            NAME,
+            CheckStatus::Warn,
+            format!("None of {} was found", candidates.join(", ")),
+            "Install one or pick another preset with `cortx settings set terminal.preset <preset>`".to_string(),
+        ),
+    }
+}
+
+/// VS Code's `code` launcher is on PATH.
+pub fn check_vscode(path_var: Option<&OsStr>) -> CheckResult {
+    const NAME: &str = "VS Code";
+    if program_resolves("code", None, path_var) {
+        CheckResult::pass(NAME, "`code` is on PATH".to_string())
+    } else {
+        CheckResult::problem(
+            NAME,
+            CheckStatus::Warn,
+            "`code` is not on PATH; opening projects in VS Code won't work".to_string(),
+            "In VS Code, run \"Shell Command: Install 'code' command in PATH\"".to_string(),
+        )
+    }
+}
+
+/// Programs an external launch with this config may start, any one of which
+/// is enough. Empty when a custom terminal is selected but not configured.
+fn terminal_programs(terminal: &TerminalConfig) -> Vec<String> {
+    let custom = terminal.custom_path.trim();
+    if terminal.preset == TerminalPreset::Custom && !custom.is_empty() {
+        // macOS opens custom terminals by app name through `open -a`
+        let program = if cfg!(target_os = "macos") { "open" } else { custom };
+        return vec![program.to_string()];
+    }
+
+    let programs: &[&str] = if cfg!(target_os = "windows") {
+        match terminal.preset {
+            TerminalPreset::PowerShell => &["powershell"],
+            TerminalPreset::Cmd | TerminalPreset::Warp => &["cmd"],
+            TerminalPreset::Custom => &[],
+            _ => &["wt"],
+        }
+    } else if cfg!(target_os = "macos") {
+        match terminal.preset {
+            TerminalPreset::Custom => &[],
+            _ => &["osascript"],
+        }
+    } else {
+        // Every preset falls back to auto-detection on Linux
+        LINUX_TERMINALS
+    };
+    programs.iter().map(|p| p.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ShellKind;
+
+    /// A PATH holding empty files named after `programs`
+    fn fake_path(programs: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for program in programs {
+            fs::write(dir.path().join(program), "").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn data_dir_must_be_writable() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("data");
+        assert_eq!(check_data_dir(&dir).status, CheckStatus::Pass);
+        assert!(dir.is_dir());
+        assert!(!dir.join(PROBE_FILE).exists());
+
+        // A file where the directory should be
+        let blocked = tmp.path().join("blocked");
+        fs::write(&blocked, "").unwrap();
+        let result = check_data_dir(&blocked.join("data"));
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn corrupt_data_files_fail() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(check_data_files(tmp.path()).status, CheckStatus::Pass);
+
+        fs::write(tmp.path().join("projects.json"), "[]").unwrap();
+        assert_eq!(check_data_files(tmp.path()).message, "1 file OK");
+
+        fs::write(tmp.path().join("tools.json"), "{ not json").unwrap();
+        let result = check_data_files(tmp.path());
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("tools.json"));
+        assert!(!result.message.contains("projects.json"));
+
+        // Each project file is checked on its own
+        fs::remove_file(tmp.path().join("tools.json")).unwrap();
+        fs::create_dir(tmp.path().join("projects")).unwrap();
+        fs::write(tmp.path().join("projects").join("broken.json"), "{").unwrap();
+        let result = check_data_files(tmp.path());
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("projects/broken.json"));
+    }
+
+    #[test]
+    fn programs_are_looked_up_on_the_given_path() {
+        let empty = fake_path(&[]);
+        let with_code = fake_path(&["code", "fish"]);
+
+        assert_eq!(check_vscode(Some(empty.path().as_os_str())).status, CheckStatus::Warn);
+        assert_eq!(check_vscode(Some(with_code.path().as_os_str())).status, CheckStatus::Pass);
+        assert_eq!(check_vscode(None).status, CheckStatus::Warn);
+
+        let fish = ShellConfig { kind: ShellKind::Custom, custom_path: Some("fish".into()) };
+        assert_eq!(check_shell(&fish, Some(with_code.path().as_os_str())).status, CheckStatus::Pass);
+        assert_eq!(check_shell(&fish, Some(empty.path().as_os_str())).status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn custom_terminal_needs_a_path() {
+        let dir = fake_path(&["my-term", "open"]);
+        let path = Some(dir.path().as_os_str());
+
+        // Linux auto-detects instead, and finds none of its terminals here
+        let unset = TerminalConfig { preset: TerminalPreset::Custom, ..Default::default() };
+        assert_eq!(check_terminal(&unset, path).status, CheckStatus::Warn);
+
+        let set = TerminalConfig { custom_path: "my-term".into(), ..unset.clone() };
+        assert_eq!(check_terminal(&set, path).status, CheckStatus::Pass);
+        let missing = TerminalConfig { custom_path: "gone-term".into(), ..unset };
+        let expected = if cfg!(target_os = "macos") { CheckStatus::Pass } else { CheckStatus::Warn };
+        assert_eq!(check_terminal(&missing, path).status, expected);
+    }
+}
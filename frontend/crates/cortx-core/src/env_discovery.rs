@@ -0,0 +1,428 @@
+//! Finding a project's env files.
+//!
+//! The walk runs on the `ignore` crate's parallel walker, skipping
+//! `IGNORED_DIRECTORIES` and stopping at the scan budget. .gitignore files
+//! are not applied: env files are usually git-ignored themselves. Progress
+//! is reported at most every `PROGRESS_INTERVAL`, and every visited entry
+//! checks the cancel flag, so a cancel stops a walk of a huge monorepo
+//! right away. The project is only updated once a walk completes.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use ignore::{WalkBuilder, WalkState};
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::env_file::{carry_over_variants, detect_variant, parse_env_file_lenient};
+use crate::models::{EnvDiscoveryProgress, EnvFile, Project};
+use crate::script_discovery::ScanBudget;
+use crate::storage::Storage;
+
+/// Directories to skip during env file discovery
+pub const IGNORED_DIRECTORIES: &[&str] = &[
+    "node_modules",
+    ".git",
+    "target",
+    "dist",
+    "build",
+    ".next",
+    ".nuxt",
+    "__pycache__",
+    "venv",
+    ".venv",
+    "vendor",
+    ".cargo",
+    ".cache",
+];
+
+/// Shortest time between two progress reports of a walk
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Check if a filename is an env file
+pub fn is_env_file(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    lower == ".env"
+        || lower.starts_with(".env.")
+        || lower == ".env.local"
+        || lower == ".env.development"
+        || lower == ".env.production"
+        || lower == ".env.test"
+        || lower == ".env.staging"
+        || lower == ".env.example"
+        || lower == ".env.sample"
+}
+
+/// Auto-link env file to service if in same directory
+pub fn find_matching_service(env_file_dir: &Path, project: &Project) -> Option<String> {
+    for service in &project.services {
+        let service_dir = PathBuf::from(project.resolve_dir(&service.working_dir));
+
+        // Check if the env file is in the service directory
+        if let Ok(env_canonical) = env_file_dir.canonicalize() {
+            if let Ok(service_canonical) = service_dir.canonicalize() {
+                if env_canonical == service_canonical {
+                    return Some(service.id.clone());
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Lets through one report per `interval`, from any walker thread.
+pub struct ProgressThrottle {
+    interval: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl ProgressThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last: Mutex::new(None) }
+    }
+
+    /// Whether to report at `now`; the first call always does.
+    pub fn ready(&self, now: Instant) -> bool {
+        let mut last = self.last.lock();
+        if last.is_some_and(|last| now.saturating_duration_since(last) < self.interval) {
+            return false;
+        }
+        *last = Some(now);
+        true
+    }
+}
+
+/// How a discovery ended
+#[derive(Debug, Clone)]
+pub enum DiscoveryOutcome {
+    /// The project's env files, as saved
+    Completed { env_files: Vec<EnvFile>, truncated: bool },
+    /// Stopped by `EnvDiscovery::cancel`; nothing was saved
+    Cancelled,
+}
+
+/// One env file discovery of a project, cancellable from another thread.
+pub struct EnvDiscovery {
+    pub id: String,
+    cancelled: AtomicBool,
+}
+
+impl Default for EnvDiscovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnvDiscovery {
+    pub fn new() -> Self {
+        Self { id: Uuid::new_v4().to_string(), cancelled: AtomicBool::new(false) }
+    }
+
+    /// Stop the walk; `run` then returns `Cancelled` without saving.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Discover the env files of `project_id` and save them on the project.
+    /// Files added by hand are kept when `force` re-discovers, as are
+    /// variants picked by hand. Without `force`, an already discovered
+    /// project is returned as is.
+    pub fn run(
+        &self,
+        storage: &Storage,
+        project_id: &str,
+        force: bool,
+        budget: &ScanBudget,
+        progress_interval: Duration,
+        on_progress: impl Fn(EnvDiscoveryProgress) + Sync,
+    ) -> Result<DiscoveryOutcome, String> {
+        let project = storage
+            .get_project(project_id)
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+        // Skip if already discovered and not forcing
+        if project.env_files_discovered && !force {
+            return Ok(DiscoveryOutcome::Completed { env_files: project.env_files, truncated: false });
+        }
+
+        if !Path::new(project.effective_root()).exists() {
+            return Err(format!("Project root path does not exist: {}", project.effective_root()));
+        }
+
+        let Some((mut discovered_files, truncated)) = self.walk(&project, budget, progress_interval, &on_progress)
+        else {
+            return Ok(DiscoveryOutcome::Cancelled);
+        };
+        if truncated {
+            log::warn!("Env file discovery in {} stopped early: scan truncated", project.effective_root());
+        }
+
+        // Keep variants the user picked by hand for files that are still there
+        carry_over_variants(&project.env_files, &mut discovered_files);
+
+        // If forcing, preserve manually added files
+        let mut final_files = discovered_files;
+        if force {
+            for manual_file in project.env_files.iter().filter(|f| f.is_manually_added) {
+                if !final_files.iter().any(|f| f.path == manual_file.path) {
+                    final_files.push(manual_file.clone());
+                }
+            }
+        }
+
+        // A cancel that came in while parsing still wins
+        if self.is_cancelled() {
+            return Ok(DiscoveryOutcome::Cancelled);
+        }
+        storage
+            .update_project(project_id, |p| {
+                p.env_files = final_files.clone();
+                p.env_files_discovered = true;
+            })
+            .map_err(|e| e.to_string())?;
+
+        Ok(DiscoveryOutcome::Completed { env_files: final_files, truncated })
+    }
+
+    /// Walk the project's root for env files, within `budget`. Returns the
+    /// files found and whether the walk stopped early, or `None` once
+    /// cancelled.
+    fn walk(
+        &self,
+        project: &Project,
+        budget: &ScanBudget,
+        progress_interval: Duration,
+        on_progress: &(dyn Fn(EnvDiscoveryProgress) + Sync),
+    ) -> Option<(Vec<EnvFile>, bool)> {
+        let root_path = Path::new(project.effective_root());
+        let started = Instant::now();
+        let visited = AtomicUsize::new(0);
+        let dirs_scanned = AtomicUsize::new(0);
+        let files_found = AtomicUsize::new(0);
+        let truncated = AtomicBool::new(false);
+        let throttle = ProgressThrottle::new(progress_interval);
+        let (found_tx, found_rx) = mpsc::channel::<PathBuf>();
+
+        WalkBuilder::new(root_path)
+            .standard_filters(false)
+            .follow_links(false)
+            // Skip ignored directories
+            .filter_entry(|e| {
+                !(e.file_type().is_some_and(|t| t.is_dir())
+                    && e.file_name().to_str().is_some_and(|name| IGNORED_DIRECTORIES.contains(&name)))
+            })
+            .build_parallel()
+            .run(|| {
+                let found_tx = found_tx.clone();
+                let (visited, dirs_scanned, files_found, truncated, throttle) =
+                    (&visited, &dirs_scanned, &files_found, &truncated, &throttle);
+                Box::new(move |entry| {
+                    if self.is_cancelled() || truncated.load(Ordering::Relaxed) {
+                        return WalkState::Quit;
+                    }
+                    if visited.fetch_add(1, Ordering::Relaxed) >= budget.max_entries
+                        || started.elapsed() >= budget.max_duration
+                    {
+                        truncated.store(true, Ordering::Relaxed);
+                        return WalkState::Quit;
+                    }
+                    let Ok(entry) = entry else { return WalkState::Continue };
+
+                    let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                    if is_dir {
+                        dirs_scanned.fetch_add(1, Ordering::Relaxed);
+                    } else if entry.file_type().is_some_and(|t| t.is_file())
+                        && entry.file_name().to_str().is_some_and(is_env_file)
+                    {
+                        files_found.fetch_add(1, Ordering::Relaxed);
+                        let _ = found_tx.send(entry.path().to_path_buf());
+                    }
+
+                    if throttle.ready(Instant::now()) {
+                        on_progress(EnvDiscoveryProgress {
+                            discovery_id: self.id.clone(),
+                            dirs_scanned: dirs_scanned.load(Ordering::Relaxed),
+                            files_found: files_found.load(Ordering::Relaxed),
+                            current_path: entry.path().to_string_lossy().to_string(),
+                        });
+                    }
+                    WalkState::Continue
+                })
+            });
+        drop(found_tx);
+
+        if self.is_cancelled() {
+            return None;
+        }
+
+        // Threads finish in any order; report files sorted by path
+        let mut paths: Vec<PathBuf> = found_rx.into_iter().collect();
+        paths.sort();
+        let discovered_files = paths.iter().map(|full_path| env_file_at(project, root_path, full_path)).collect();
+        Some((discovered_files, truncated.load(Ordering::Relaxed)))
+    }
+}
+
+/// Read a discovered env file
+fn env_file_at(project: &Project, root_path: &Path, full_path: &Path) -> EnvFile {
+    let filename = full_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let relative_path = full_path
+        .strip_prefix(root_path)
+        .unwrap_or(full_path)
+        .to_string_lossy()
+        .to_string();
+
+    // Parse the env file (problems are recorded, never fatal)
+    let parsed = parse_env_file_lenient(full_path);
+    let variant = detect_variant(&filename);
+
+    let mut env_file = EnvFile::new(
+        full_path.to_string_lossy().to_string(),
+        relative_path,
+        filename,
+        variant,
+        parsed.variables,
+        false, // not manually added
+    );
+    env_file.parse_errors = parsed.errors;
+
+    // Try to link to a service
+    if let Some(parent_dir) = full_path.parent() {
+        env_file.linked_service_id = find_matching_service(parent_dir, project);
+    }
+    env_file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A tree `depth` directories deep and `width` wide at each level, with a
+    /// `.env` in every directory. Returns how many directories it has.
+    fn deep_tree(root: &Path, depth: usize, width: usize) -> usize {
+        fs::write(root.join(".env"), "KEY=value\n").unwrap();
+        fs::write(root.join("main.rs"), "").unwrap();
+        if depth == 0 {
+            return 1;
+        }
+        1 + (0..width)
+            .map(|i| {
+                let dir = root.join(format!("pkg{}", i));
+                fs::create_dir(&dir).unwrap();
+                deep_tree(&dir, depth - 1, width)
+            })
+            .sum::<usize>()
+    }
+
+    /// Storage holding one project rooted at a fresh deep tree, with a file
+    /// added by hand. Returns the tree's directory count.
+    fn project_with_tree() -> (tempfile::TempDir, Storage, Project, usize) {
+        let dir = tempfile::tempdir().unwrap();
+        let tree = dir.path().join("repo");
+        fs::create_dir(&tree).unwrap();
+        let dirs = deep_tree(&tree, 4, 4);
+        fs::create_dir_all(tree.join("node_modules/lib")).unwrap();
+        fs::write(tree.join("node_modules/lib/.env"), "SKIPPED=1\n").unwrap();
+
+        let storage = Storage::with_app_dir(dir.path().join("data")).unwrap();
+        let mut project = Project::new("repo".into(), tree.to_string_lossy().to_string());
+        let manual = EnvFile::new(
+            "/elsewhere/.env.shared".into(),
+            ".env.shared".into(),
+            ".env.shared".into(),
+            detect_variant(".env.shared"),
+            Vec::new(),
+            true,
+        );
+        project.env_files.push(manual);
+        let project = storage.create_project(project).unwrap();
+        (dir, storage, project, dirs)
+    }
+
+    #[test]
+    fn progress_is_throttled_across_threads() {
+        let throttle = ProgressThrottle::new(Duration::from_millis(200));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(throttle.ready(start));
+        assert!(!throttle.ready(at(50)));
+        assert!(!throttle.ready(at(199)));
+        assert!(throttle.ready(at(200)));
+        assert!(!throttle.ready(at(300)));
+        assert!(throttle.ready(at(450)));
+    }
+
+    #[test]
+    fn completed_walks_are_saved_with_the_files_added_by_hand() {
+        let (_dir, storage, project, dirs) = project_with_tree();
+        let reports = Mutex::new(Vec::new());
+        let discovery = EnvDiscovery::new();
+
+        let outcome = discovery
+            .run(&storage, &project.id, true, &ScanBudget::default(), Duration::ZERO, |p| reports.lock().push(p))
+            .unwrap();
+        let DiscoveryOutcome::Completed { env_files, truncated } = outcome else { panic!("cancelled") };
+        assert!(!truncated);
+        // One per directory, none from node_modules, plus the one added by hand
+        assert_eq!(env_files.len(), dirs + 1);
+        assert!(!env_files.iter().any(|f| f.path.contains("node_modules")));
+        assert!(env_files.iter().any(|f| f.is_manually_added));
+
+        let saved = storage.get_project(&project.id).unwrap();
+        assert!(saved.env_files_discovered);
+        assert_eq!(saved.env_files.len(), dirs + 1);
+
+        let reports = reports.into_inner();
+        assert!(!reports.is_empty() && reports.iter().all(|p| p.discovery_id == discovery.id));
+    }
+
+    #[test]
+    fn cancelled_walks_stop_midway_and_save_nothing() {
+        let (_dir, storage, project, dirs) = project_with_tree();
+        let discovery = EnvDiscovery::new();
+        let last_report = Mutex::new(None);
+
+        let outcome = discovery
+            .run(&storage, &project.id, true, &ScanBudget::default(), Duration::ZERO, |p| {
+                // Cancel once the walk is under way
+                if p.dirs_scanned >= 3 {
+                    discovery.cancel();
+                }
+                *last_report.lock() = Some(p);
+            })
+            .unwrap();
+        assert!(matches!(outcome, DiscoveryOutcome::Cancelled));
+
+        let last = last_report.into_inner().unwrap();
+        assert!(last.dirs_scanned < dirs, "walked the whole tree ({} dirs)", last.dirs_scanned);
+        let saved = storage.get_project(&project.id).unwrap();
+        assert!(!saved.env_files_discovered);
+        let ids = |files: &[EnvFile]| files.iter().map(|f| f.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&saved.env_files), ids(&project.env_files));
+    }
+
+    #[test]
+    fn walks_stop_at_the_scan_budget() {
+        let (_dir, storage, project, dirs) = project_with_tree();
+        let budget = ScanBudget { max_entries: 20, max_duration: Duration::from_secs(60) };
+
+        let outcome = EnvDiscovery::new()
+            .run(&storage, &project.id, false, &budget, PROGRESS_INTERVAL, |_| {})
+            .unwrap();
+        let DiscoveryOutcome::Completed { env_files, truncated } = outcome else { panic!("cancelled") };
+        assert!(truncated);
+        assert!(env_files.len() < dirs);
+    }
+}
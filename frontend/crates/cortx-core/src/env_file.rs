@@ -0,0 +1,1259 @@
+//! `.env` file parsing.
+//!
+//! Parsing is lenient: every line that can be understood becomes an
+//! `EnvVariable`, and every line that can't is reported as an `EnvParseError`
+//! (line number + reason) instead of failing the whole file. Edits go
+//! through [`apply_edit`], which rewrites only the lines of the variable.
+//! Files are read with [`text_file`], so a BOM, UTF-16 or CRLF line endings
+//! don't get in the way, and edited files are written back in that form.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::models::{EnvFile, EnvFileVariant, EnvMatrix, EnvMatrixRow, EnvParseError, EnvVariable, Project, Service};
+use crate::text_file::{self, DecodedText, TextEncoding};
+
+/// Result of parsing one env file: the valid variables plus the problem lines.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedEnvFile {
+    pub variables: Vec<EnvVariable>,
+    pub errors: Vec<EnvParseError>,
+}
+
+/// Read and parse an env file. Only I/O failures return `Err`.
+pub fn parse_env_file(path: &Path) -> Result<ParsedEnvFile, String> {
+    let content = text_file::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(parse_env_content(&content))
+}
+
+/// Like [`parse_env_file`], but never fails: a read error is reported as a
+/// single problem on line 0. Used by discovery so one bad file can't abort a scan.
+pub fn parse_env_file_lenient(path: &Path) -> ParsedEnvFile {
+    parse_env_file(path).unwrap_or_else(|reason| ParsedEnvFile {
+        variables: Vec::new(),
+        errors: vec![EnvParseError { line_number: 0, reason }],
+    })
+}
+
+/// Parse `.env` content into key-value pairs, collecting malformed lines.
+///
+/// Besides `KEY=value` this understands an `export ` prefix, inline comments
+/// after a value (`KEY=value # note`) and double-quoted values spanning
+/// several lines, which are joined with `\n`. `line_number` is always the
+/// line the key is on.
+pub fn parse_env_content(content: &str) -> ParsedEnvFile {
+    parse_with_spans(content).0
+}
+
+/// [`parse_env_content`], plus the first and last line (0-based) of every
+/// value spanning several lines
+fn parse_with_spans(content: &str) -> (ParsedEnvFile, BTreeMap<usize, usize>) {
+    let mut parsed = ParsedEnvFile::default();
+    let mut spans = BTreeMap::new();
+    let lines: Vec<&str> = text_file::strip_bom(content).lines().collect();
+
+    let mut next = 0;
+    while next < lines.len() {
+        let index = next;
+        next += 1;
+        let line_number = (index + 1) as u32;
+        let trimmed = lines[index].trim();
+
+        // Skip empty lines and comments
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let trimmed = strip_export(trimmed);
+
+        // Parse KEY=VALUE format
+        let Some(eq_pos) = trimmed.find('=') else {
+            parsed.errors.push(EnvParseError {
+                line_number,
+                reason: "Missing '=' (expected KEY=VALUE)".to_string(),
+            });
+            continue;
+        };
+
+        let key = trimmed[..eq_pos].trim();
+        let (raw_value, rest) = split_comment(trimmed[eq_pos + 1..].trim());
+
+        if key.is_empty() {
+            parsed.errors.push(EnvParseError {
+                line_number,
+                reason: "Empty variable name".to_string(),
+            });
+            continue;
+        }
+        if !is_valid_key(key) {
+            parsed.errors.push(EnvParseError {
+                line_number,
+                reason: format!("Invalid variable name '{}'", key),
+            });
+            continue;
+        }
+
+        let unterminated = EnvParseError {
+            line_number,
+            reason: "Unterminated quoted value".to_string(),
+        };
+        let (value, rest) = match quote_of(raw_value) {
+            Some(q) if closing_quote(&raw_value[1..], q).is_some() => {
+                let inner = &raw_value[1..raw_value.len() - 1];
+                (if q == '"' { unescape(inner) } else { inner.to_string() }, rest)
+            }
+            // Continued on the following lines. Without a proper closing
+            // line it's a typo, and the lines after are read on their own.
+            Some('"') => match continued_value(&lines, index, &raw_value[1..]) {
+                Some((value, last, rest)) if is_comment_or_blank(rest) => {
+                    spans.insert(index, last);
+                    next = last + 1;
+                    (value, rest)
+                }
+                _ => {
+                    parsed.errors.push(unterminated);
+                    continue;
+                }
+            },
+            Some(_) => {
+                parsed.errors.push(unterminated);
+                continue;
+            }
+            None => (raw_value.to_string(), rest),
+        };
+        if !is_comment_or_blank(rest) {
+            parsed.errors.push(EnvParseError {
+                line_number,
+                reason: "Unexpected text after the closing quote".to_string(),
+            });
+            continue;
+        }
+
+        parsed.variables.push(EnvVariable {
+            key: key.to_string(),
+            value,
+            line_number,
+        });
+    }
+
+    (parsed, spans)
+}
+
+/// A double-quoted value opened on `lines[first]`, where `opened` follows
+/// the quote, and closed on a later line: the value with its lines joined
+/// by `\n`, the index of the closing line, and what follows the quote there
+fn continued_value<'a>(lines: &[&'a str], first: usize, opened: &str) -> Option<(String, usize, &'a str)> {
+    let mut value = opened.to_string();
+    for (index, &line) in lines.iter().enumerate().skip(first + 1) {
+        value.push('\n');
+        match closing_quote(line, '"') {
+            Some(end) => {
+                value.push_str(&line[..end]);
+                return Some((unescape(&value), index, &line[end + 1..]));
+            }
+            None => value.push_str(line),
+        }
+    }
+    None
+}
+
+/// The lines after the first of each multiline value in `spans`
+fn inner_lines(spans: &BTreeMap<usize, usize>) -> HashSet<usize> {
+    spans.iter().flat_map(|(&first, &last)| first + 1..=last).collect()
+}
+
+/// `line` without a leading `export ` (as in files meant to be `source`d)
+fn strip_export(line: &str) -> &str {
+    match line.strip_prefix("export") {
+        Some(rest) if rest.starts_with([' ', '\t']) => rest.trim_start(),
+        _ => line,
+    }
+}
+
+/// Byte offset of the `quote` closing a value in `text`, the value after its
+/// opening quote. In double quotes `\"` doesn't close it.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// A double-quoted value as written, with `\"` and `\\` unescaped. Other
+/// backslashes are kept, so `"C:\dir"` reads as written.
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next @ ('"' | '\\'))) => {
+                out.push(next);
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Byte offset of an inline comment in an unquoted value: a `#` after
+/// whitespace, so `COLOR=#fff` keeps its value
+fn comment_start(raw: &str) -> Option<usize> {
+    raw.char_indices()
+        .find(|&(i, c)| c == '#' && raw[..i].ends_with(char::is_whitespace))
+        .map(|(i, _)| i)
+}
+
+/// `raw`, a value as written after `=`, split into the value (quotes
+/// included) and what follows it on the line: an inline comment and the
+/// space before it. An unclosed quote takes the whole line.
+fn split_comment(raw: &str) -> (&str, &str) {
+    let end = match quote_of(raw) {
+        Some(q) => closing_quote(&raw[1..], q).map_or(raw.len(), |i| i + 2),
+        None => comment_start(raw).unwrap_or(raw.len()),
+    };
+    let value = raw[..end].trim_end();
+    (value, &raw[value.len()..])
+}
+
+fn is_comment_or_blank(text: &str) -> bool {
+    let text = text.trim_start();
+    text.is_empty() || text.starts_with('#')
+}
+
+/// Determine the variant type from filename
+pub fn detect_variant(filename: &str) -> EnvFileVariant {
+    let lower = filename.to_lowercase();
+    match lower.as_str() {
+        ".env" => EnvFileVariant::Base,
+        ".env.local" => EnvFileVariant::Local,
+        s if s.contains("development") || s.contains(".dev") => EnvFileVariant::Development,
+        s if s.contains("production") || s.contains(".prod") => EnvFileVariant::Production,
+        s if s.contains("test") => EnvFileVariant::Test,
+        s if s.contains("staging") => EnvFileVariant::Staging,
+        s if s.contains("example") || s.contains("sample") => EnvFileVariant::Example,
+        _ => EnvFileVariant::Other,
+    }
+}
+
+/// Set a manual variant, or with `None` go back to detecting it from the filename.
+pub fn set_variant(file: &mut EnvFile, variant: Option<EnvFileVariant>) {
+    match variant {
+        Some(variant) => {
+            file.variant = variant;
+            file.variant_overridden = true;
+        }
+        None => {
+            file.variant = detect_variant(&file.filename);
+            file.variant_overridden = false;
+        }
+    }
+}
+
+/// Store freshly parsed contents. Only variables and parse errors change;
+/// the variant (manual or detected) is left alone.
+pub fn apply_refresh(file: &mut EnvFile, parsed: ParsedEnvFile) {
+    file.variables = parsed.variables;
+    file.parse_errors = parsed.errors;
+    file.last_read_at = Utc::now();
+}
+
+/// Copy manual variants from `previous` onto rediscovered files with the same path.
+pub fn carry_over_variants(previous: &[EnvFile], discovered: &mut [EnvFile]) {
+    for file in discovered {
+        if let Some(old) = previous.iter().find(|f| f.path == file.path && f.variant_overridden) {
+            file.variant = old.variant.clone();
+            file.variant_overridden = true;
+        }
+    }
+}
+
+/// Keys that several env files in `dir` define with different values, each
+/// with its `filename=value` definitions. Which one wins depends on load
+/// order, so these are worth a warning. Example files aren't loaded and are
+/// skipped.
+pub fn duplicate_keys(files: &[EnvFile], dir: &Path) -> Vec<(String, Vec<String>)> {
+    let mut by_key: BTreeMap<&str, Vec<(&str, &str)>> = BTreeMap::new();
+    for file in files {
+        if file.variant == EnvFileVariant::Example || Path::new(&file.path).parent() != Some(dir) {
+            continue;
+        }
+        for var in &file.variables {
+            by_key
+                .entry(var.key.as_str())
+                .or_default()
+                .push((file.filename.as_str(), var.value.as_str()));
+        }
+    }
+
+    by_key
+        .into_iter()
+        .filter(|(_, defs)| defs.iter().any(|(_, value)| *value != defs[0].1))
+        .map(|(key, defs)| {
+            let defs = defs
+                .into_iter()
+                .map(|(filename, value)| format!("{}={}", filename, value))
+                .collect();
+            (key.to_string(), defs)
+        })
+        .collect()
+}
+
+/// Every key of `files` against every file, as stored. A key a file
+/// defines twice takes its last value. Mask the values with [`mask_matrix`]
+/// only afterwards, so `values_differ` compares the real ones.
+pub fn env_matrix(files: &[EnvFile]) -> EnvMatrix {
+    let mut by_key: BTreeMap<&str, Vec<Option<&str>>> = BTreeMap::new();
+    for (column, file) in files.iter().enumerate() {
+        for var in &file.variables {
+            by_key.entry(var.key.as_str()).or_insert_with(|| vec![None; files.len()])[column] = Some(var.value.as_str());
+        }
+    }
+
+    // Each base file with the example files next to it
+    let same_dir = |a: &EnvFile, b: &EnvFile| Path::new(&a.path).parent() == Path::new(&b.path).parent();
+    let bases: Vec<(usize, Vec<usize>)> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.variant == EnvFileVariant::Base)
+        .map(|(column, base)| {
+            let examples = files
+                .iter()
+                .enumerate()
+                .filter(|(_, file)| file.variant == EnvFileVariant::Example && same_dir(file, base))
+                .map(|(column, _)| column)
+                .collect();
+            (column, examples)
+        })
+        .collect();
+
+    let rows = by_key
+        .into_iter()
+        .map(|(key, values)| {
+            let mut defined = files
+                .iter()
+                .zip(&values)
+                .filter(|(file, _)| file.variant != EnvFileVariant::Example)
+                .filter_map(|(_, value)| *value);
+            let values_differ = defined.next().is_some_and(|first| defined.any(|value| value != first));
+            let missing_in_base = bases
+                .iter()
+                .filter(|(base, examples)| values[*base].is_none() && examples.iter().any(|&e| values[e].is_some()))
+                .map(|&(base, _)| files[base].id.clone())
+                .collect();
+            EnvMatrixRow {
+                key: key.to_string(),
+                values: values.into_iter().map(|value| value.map(str::to_string)).collect(),
+                values_differ,
+                missing_in_base,
+            }
+        })
+        .collect();
+
+    EnvMatrix { file_ids: files.iter().map(|file| file.id.clone()).collect(), rows }
+}
+
+/// The `env_vars` a service is started with: the variables of every env file
+/// linked to it, read fresh from disk in the project's order (later files
+/// win), overridden by the active project variant's `env_var_overrides`, then
+/// by the service's own `env_vars`. Linked files missing on disk are skipped
+/// with a warning; example files are never loaded. Under a variant, files
+/// are read from the variant's checkout.
+pub fn service_env_vars(project: &Project, service: &Service) -> Option<HashMap<String, String>> {
+    let mut vars = HashMap::new();
+    for file in project.env_files.iter().filter(|f| f.linked_service_id.as_deref() == Some(service.id.as_str())) {
+        if file.variant == EnvFileVariant::Example {
+            continue;
+        }
+        match parse_env_file(Path::new(&project.resolve_dir(&file.path))) {
+            Ok(parsed) => vars.extend(parsed.variables.into_iter().map(|v| (v.key, v.value))),
+            Err(e) => log::warn!(
+                "Skipping env file {} linked to service {}: {}",
+                file.path,
+                service.name,
+                e
+            ),
+        }
+    }
+    if let Some(variant) = project.active_variant() {
+        vars.extend(variant.env_var_overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    if let Some(own) = &service.env_vars {
+        vars.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    if vars.is_empty() && service.env_vars.is_none() {
+        None
+    } else {
+        Some(vars)
+    }
+}
+
+/// What a masked value is shown as
+pub const MASKED_VALUE: &str = "\u{2022}\u{2022}\u{2022}\u{2022}"; // ••••
+
+/// Whether `key` likely holds a secret: it has a word (split on `_`, `.`,
+/// `-`) like `SECRET`, `TOKEN` or `PASSWORD`, or ends in `KEY` (`API_KEY`,
+/// `AWS_SECRET_ACCESS_KEY`, `GITHUB_TOKEN`, `DB_PASSWORD`). A heuristic: it
+/// errs towards masking.
+pub fn is_sensitive(key: &str) -> bool {
+    const ANYWHERE: [&str; 7] = ["SECRET", "SECRETS", "TOKEN", "PASSWORD", "PASSWD", "CREDENTIALS", "APIKEY"];
+    const LAST: [&str; 4] = ["KEY", "PWD", "PASS", "CREDENTIAL"];
+    let upper = key.to_ascii_uppercase();
+    let words: Vec<&str> = upper.split(['_', '.', '-']).filter(|w| !w.is_empty()).collect();
+    words.iter().any(|w| ANYWHERE.contains(w)) || words.last().is_some_and(|w| LAST.contains(w))
+}
+
+/// Replace the non-empty values of sensitive variables with [`MASKED_VALUE`].
+/// Keys and line numbers stay as they are.
+pub fn mask_variables(variables: &mut [EnvVariable]) {
+    for var in variables.iter_mut().filter(|v| !v.value.is_empty() && is_sensitive(&v.key)) {
+        var.value = MASKED_VALUE.to_string();
+    }
+}
+
+/// [`mask_variables`] for an [`env_matrix`]
+pub fn mask_matrix(matrix: &mut EnvMatrix) {
+    for row in matrix.rows.iter_mut().filter(|row| is_sensitive(&row.key)) {
+        for value in row.values.iter_mut().flatten().filter(|value| !value.is_empty()) {
+            *value = MASKED_VALUE.to_string();
+        }
+    }
+}
+
+/// `.env` content with the values of sensitive variables replaced by
+/// [`MASKED_VALUE`], quotes included. Every other byte, line endings and
+/// comments too, is kept, so line numbers still match the file; the further
+/// lines of a masked multiline value are left empty.
+pub fn mask_content(content: &str) -> String {
+    let spans = parse_with_spans(content).1;
+    let mut out = String::with_capacity(content.len());
+    // Inside a multiline value, up to this line, and whether it's masked
+    let mut inside: Option<(usize, bool)> = None;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let (body, ending) = split_line_ending(line);
+        if let Some((last, masked)) = inside.filter(|&(last, _)| index <= last) {
+            out.push_str(if masked { ending } else { line });
+            if index == last {
+                inside = None;
+            }
+            continue;
+        }
+        let masked = (!body.trim_start().starts_with('#'))
+            .then(|| body.find('='))
+            .flatten()
+            .filter(|&eq| is_sensitive(strip_export(body[..eq].trim())) && !body[eq + 1..].trim().is_empty());
+        if let Some(&last) = spans.get(&index) {
+            inside = Some((last, masked.is_some()));
+        }
+        match masked {
+            Some(eq) => {
+                // Keep the space between `=` and the value, and a comment after it
+                let raw = &body[eq + 1..];
+                let lead = raw.len() - raw.trim_start().len();
+                out.push_str(&body[..eq + 1 + lead]);
+                out.push_str(MASKED_VALUE);
+                if !spans.contains_key(&index) {
+                    out.push_str(split_comment(raw.trim()).1);
+                }
+                out.push_str(ending);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+/// A change to one variable of an env file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvEdit {
+    /// Change the value of a variable, appending it if it isn't defined yet
+    Set { key: String, value: String },
+    /// Append a variable that isn't defined yet
+    Add { key: String, value: String },
+    Remove { key: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EnvEditError {
+    #[error("Invalid variable name '{0}'")]
+    InvalidKey(String),
+    #[error("Value of '{0}' can't span several lines")]
+    MultilineValue(String),
+    #[error("Variable not found: {0}")]
+    NotFound(String),
+    #[error("Variable already defined: {0}")]
+    AlreadyDefined(String),
+    /// The file changed on disk after cortx last read it
+    #[error("{0} was modified outside cortx since it was last read; refresh it and try again")]
+    Conflict(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+/// Apply `edit` to `.env` content, touching only the lines of that variable.
+///
+/// Comments, blank lines, ordering and line endings are kept as they are. A
+/// changed value keeps the quotes it had; an unquoted or new value is only
+/// quoted when it wouldn't read back the same otherwise. Every line defining
+/// the key is updated (or removed), so duplicates can't disagree afterwards.
+pub fn apply_edit(content: &str, edit: &EnvEdit) -> Result<String, EnvEditError> {
+    let key = match edit {
+        EnvEdit::Set { key, .. } | EnvEdit::Add { key, .. } | EnvEdit::Remove { key } => key,
+    };
+    if !is_valid_key(key) {
+        return Err(EnvEditError::InvalidKey(key.clone()));
+    }
+    if let EnvEdit::Set { value, .. } | EnvEdit::Add { value, .. } = edit {
+        if value.contains(['\n', '\r']) {
+            return Err(EnvEditError::MultilineValue(key.clone()));
+        }
+    }
+
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    // Multiline values: first line -> last line. Lines inside one never
+    // define a key themselves.
+    let spans = parse_with_spans(content).1;
+    let inside = inner_lines(&spans);
+    let defines_key = |index: usize| !inside.contains(&index) && value_start(lines[index], key).is_some();
+    let found = (0..lines.len()).any(defines_key);
+    // The lines of each definition of the key, as (first, last)
+    let definitions = || {
+        (0..lines.len())
+            .filter(|&index| defines_key(index))
+            .map(|index| (index, spans.get(&index).copied().unwrap_or(index)))
+    };
+
+    match edit {
+        EnvEdit::Set { value, .. } if !found => Ok(append_variable(content, key, value)),
+        EnvEdit::Set { value, .. } => {
+            let mut out = String::with_capacity(content.len() + value.len());
+            let mut next = 0;
+            for (first, last) in definitions() {
+                out.extend(lines[next..first].iter().copied());
+                let start = value_start(lines[first], key).unwrap_or_default();
+                let body = split_line_ending(lines[first]).0;
+                // Keep `KEY =`, any space before the value and a comment after it
+                let raw = &body[start..];
+                let lead = raw.len() - raw.trim_start().len();
+                let (old, comment) = split_comment(raw.trim());
+                out.push_str(&body[..start + lead]);
+                out.push_str(&render_value(value, quote_of(old)));
+                if first == last {
+                    out.push_str(comment);
+                }
+                out.push_str(split_line_ending(lines[last]).1);
+                next = last + 1;
+            }
+            out.extend(lines[next..].iter().copied());
+            Ok(out)
+        }
+        EnvEdit::Add { value, .. } => {
+            if found {
+                return Err(EnvEditError::AlreadyDefined(key.clone()));
+            }
+            Ok(append_variable(content, key, value))
+        }
+        EnvEdit::Remove { .. } => {
+            if !found {
+                return Err(EnvEditError::NotFound(key.clone()));
+            }
+            let removed: HashSet<usize> = definitions().flat_map(|(first, last)| first..=last).collect();
+            Ok(lines.iter().enumerate().filter(|(index, _)| !removed.contains(index)).map(|(_, line)| *line).collect())
+        }
+    }
+}
+
+/// Apply `edit` to the file on disk, then store the re-parsed variables on
+/// `file`. Refuses with [`EnvEditError::Conflict`] when the file was modified
+/// after `file.last_read_at`, so edits made elsewhere aren't overwritten.
+pub fn edit_env_file(file: &mut EnvFile, edit: &EnvEdit) -> Result<(), EnvEditError> {
+    let path = Path::new(&file.path);
+    let io_error = |e: std::io::Error| EnvEditError::Io(format!("{}: {}", file.path, e));
+
+    let modified: DateTime<Utc> = fs::metadata(path).and_then(|m| m.modified()).map_err(io_error)?.into();
+    if modified > file.last_read_at {
+        return Err(EnvEditError::Conflict(file.path.clone()));
+    }
+
+    let decoded = text_file::read(path).map_err(|e| EnvEditError::Io(e.to_string()))?;
+    let updated = apply_edit(&decoded.text, edit)?;
+    write_env_file(path, &decoded.encode(&updated)).map_err(io_error)?;
+    apply_refresh(file, parse_env_content(&updated));
+    Ok(())
+}
+
+/// A new env file made from an example: every variable of `example` with an
+/// empty value, its comments, blank lines, order and line endings kept.
+/// Lines that don't parse, and the rest of multiline values, are left out.
+pub fn scaffold_content(example: &str) -> String {
+    let (parsed, spans) = parse_with_spans(example);
+    let variables = parsed.variables;
+    let inside = inner_lines(&spans);
+    let mut out = String::with_capacity(example.len());
+    for (i, line) in example.split_inclusive('\n').enumerate() {
+        let (body, ending) = split_line_ending(line);
+        let trimmed = body.trim();
+        if inside.contains(&i) {
+            continue;
+        } else if trimmed.is_empty() || trimmed.starts_with('#') {
+            out.push_str(line);
+        } else if let Some(var) = variables.iter().find(|v| v.line_number == i as u32 + 1) {
+            out.push_str(&format!("{}={}", var.key, ending));
+        }
+    }
+    out
+}
+
+/// Fill `target` from the example file `example`: a missing target is
+/// created with [`scaffold_content`], an existing one only gets the keys it
+/// lacks appended, empty, and is never overwritten. Returns the keys added.
+pub fn scaffold_env_file(example: &Path, target: &Path) -> Result<Vec<String>, String> {
+    let read = |path: &Path| text_file::read(path).map_err(|e| e.to_string());
+    let example = read(example)?;
+    let mut keys: Vec<String> = Vec::new();
+    for var in parse_env_content(&example.text).variables {
+        if !keys.contains(&var.key) {
+            keys.push(var.key);
+        }
+    }
+
+    if !target.exists() {
+        let write_error = |e: std::io::Error| format!("Failed to create {}: {}", target.display(), e);
+        let mut file = fs::OpenOptions::new().write(true).create_new(true).open(target).map_err(write_error)?;
+        // In the example's line endings, as UTF-8
+        let new_file = DecodedText { bom: false, encoding: TextEncoding::Utf8, ..example };
+        file.write_all(&new_file.encode(&scaffold_content(&new_file.text))).map_err(write_error)?;
+        return Ok(keys);
+    }
+
+    let existing = read(target)?;
+    let mut content = existing.text.clone();
+    let defined: Vec<String> = parse_env_content(&content).variables.into_iter().map(|v| v.key).collect();
+    keys.retain(|key| !defined.contains(key));
+    if keys.is_empty() {
+        return Ok(keys);
+    }
+    for key in &keys {
+        content = append_variable(&content, key, "");
+    }
+    write_env_file(target, &existing.encode(&content)).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    Ok(keys)
+}
+
+/// `content` with `key=value` added as its last line, in the file's line endings
+fn append_variable(content: &str, key: &str, value: &str) -> String {
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut out = content.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push_str(newline);
+    }
+    out.push_str(&format!("{}={}{}", key, render_value(value, None), newline));
+    out
+}
+
+/// Byte offset just past the `=` when `line` defines `key`
+fn value_start(line: &str, key: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+    let eq = line.find('=')?;
+    (strip_export(line[..eq].trim()) == key).then_some(eq + 1)
+}
+
+fn split_line_ending(line: &str) -> (&str, &str) {
+    let body = line.trim_end_matches(['\n', '\r']);
+    (body, &line[body.len()..])
+}
+
+/// The quote a raw value opens with, if any
+fn quote_of(raw: &str) -> Option<char> {
+    raw.chars().next().filter(|&c| c == '"' || c == '\'')
+}
+
+/// `value` as written after `=`, so that parsing gives `value` back
+fn render_value(value: &str, quote: Option<char>) -> String {
+    // Unquoted values are trimmed, a leading quote would be taken as one,
+    // ` #` as the start of a comment, and a line break as the next line
+    let needs_quotes = value != value.trim()
+        || value.starts_with(['"', '\''])
+        || value.contains('\n')
+        || comment_start(value).is_some();
+    match quote {
+        // Single quotes can't hold one of their own, nor span lines
+        Some('\'') if !value.contains(['\'', '\n']) => format!("'{}'", value),
+        Some(_) => double_quoted(value),
+        None if needs_quotes => double_quoted(value),
+        None => value.to_string(),
+    }
+}
+
+fn double_quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Replace the file through a temp file next to it, keeping its permissions.
+/// Symlinks are followed so the link itself stays in place.
+fn write_env_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let target = fs::canonicalize(path)?;
+    let permissions = fs::metadata(&target)?.permissions();
+    let mut tmp_name = target.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".cortx-tmp");
+    let tmp = target.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut tmp_file = fs::File::create(&tmp)?;
+        tmp_file.write_all(content)?;
+        tmp_file.sync_all()?;
+        fs::set_permissions(&tmp, permissions)?;
+        fs::rename(&tmp, &target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Variable names: letters, digits, `_`, `.` and `-`, not starting with a digit.
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+}
+
+/// Strip surrounding quotes from a value
+pub fn strip_quotes(s: &str) -> String {
+    let trimmed = s.trim();
+    if trimmed.len() >= 2
+        && ((trimmed.starts_with('"') && trimmed.ends_with('"'))
+            || (trimmed.starts_with('\'') && trimmed.ends_with('\'')))
+    {
+        trimmed[1..trimmed.len() - 1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProjectVariant;
+
+    #[test]
+    fn parses_valid_and_reports_malformed_lines() {
+        let content = "\
+# comment
+API_URL=http://localhost:3000
+just some text
+=novalue
+9LIVES=cat
+QUOTED=\"hello world\"
+BROKEN=\"never closed
+EMPTY=
+";
+        let parsed = parse_env_content(content);
+
+        let keys: Vec<&str> = parsed.variables.iter().map(|v| v.key.as_str()).collect();
+        assert_eq!(keys, vec!["API_URL", "QUOTED", "EMPTY"]);
+        assert_eq!(parsed.variables[1].value, "hello world");
+        assert_eq!(parsed.variables[1].line_number, 6);
+        assert_eq!(parsed.variables[2].value, "");
+
+        let bad_lines: Vec<u32> = parsed.errors.iter().map(|e| e.line_number).collect();
+        assert_eq!(bad_lines, vec![3, 4, 5, 7]);
+        assert!(parsed.errors[0].reason.contains("Missing '='"));
+        assert!(parsed.errors[3].reason.contains("Unterminated"));
+    }
+
+    #[test]
+    fn export_prefixes_inline_comments_and_multiline_values() {
+        let content = "\
+export API_URL=http://localhost:3000
+export\tDEBUG=1 # verbose
+COLOR=#fff
+NAME=\"Zoe # not a comment\" # a comment
+CERT=\"-----BEGIN-----
+abc # kept
+-----END-----\"
+AFTER='x'
+JUNK=\"a\" b
+export=1
+";
+        let parsed = parse_env_content(content);
+        let vars: Vec<(&str, &str, u32)> =
+            parsed.variables.iter().map(|v| (v.key.as_str(), v.value.as_str(), v.line_number)).collect();
+        assert_eq!(
+            vars,
+            vec![
+                ("API_URL", "http://localhost:3000", 1),
+                ("DEBUG", "1", 2),
+                ("COLOR", "#fff", 3),
+                ("NAME", "Zoe # not a comment", 4),
+                ("CERT", "-----BEGIN-----\nabc # kept\n-----END-----", 5),
+                ("AFTER", "x", 8),
+                ("export", "1", 10),
+            ]
+        );
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 9);
+        assert!(parsed.errors[0].reason.contains("after the closing quote"));
+
+        // CRLF files join multiline values with \n too
+        let parsed = parse_env_content("KEY=\"a\r\nb\"\r\nNEXT=1\r\n");
+        assert_eq!(parsed.variables[0].value, "a\nb");
+        assert_eq!(parsed.variables[1].line_number, 3);
+
+        // A quote left open doesn't swallow the rest of the file
+        let parsed = parse_env_content("OPEN=\"abc\nA=\"x\"\nB=2\n");
+        let keys: Vec<&str> = parsed.variables.iter().map(|v| v.key.as_str()).collect();
+        assert_eq!(keys, vec!["A", "B"]);
+        assert_eq!(parsed.errors[0].line_number, 1);
+    }
+
+    #[test]
+    fn edits_and_masking_cover_whole_multiline_values() {
+        let content = "export DB_HOST=localhost # dev only\nPRIVATE_KEY=\"line1\nDB_HOST=inside\nline3\"\nPORT=1\n";
+        let out = edit(content, set("DB_HOST", "db")).unwrap();
+        assert_eq!(out, content.replace("=localhost", "=db"));
+
+        let out = edit(content, set("PRIVATE_KEY", "short")).unwrap();
+        assert_eq!(out, "export DB_HOST=localhost # dev only\nPRIVATE_KEY=\"short\"\nPORT=1\n");
+        let out = edit(content, EnvEdit::Remove { key: "PRIVATE_KEY".into() }).unwrap();
+        assert_eq!(out, "export DB_HOST=localhost # dev only\nPORT=1\n");
+
+        // A value that would otherwise read as a comment gets quoted
+        let out = edit("A=1\n", set("A", "x # y")).unwrap();
+        assert_eq!(parse_env_content(&out).variables[0].value, "x # y");
+
+        assert_eq!(
+            mask_content(content),
+            format!("export DB_HOST=localhost # dev only\nPRIVATE_KEY={}\n\n\nPORT=1\n", MASKED_VALUE)
+        );
+        assert_eq!(mask_content("export TOKEN=abc # ci\n"), format!("export TOKEN={} # ci\n", MASKED_VALUE));
+        assert_eq!(scaffold_content(content), "DB_HOST=\nPRIVATE_KEY=\nPORT=\n");
+    }
+
+    #[test]
+    fn unreadable_file_is_reported_not_fatal() {
+        let parsed = parse_env_file_lenient(Path::new("/definitely/not/here/.env"));
+        assert!(parsed.variables.is_empty());
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line_number, 0);
+    }
+
+    #[test]
+    fn single_quote_value_is_unterminated() {
+        let parsed = parse_env_content("A=\"");
+        assert!(parsed.variables.is_empty());
+        assert_eq!(parsed.errors.len(), 1);
+    }
+
+    fn env_file_at(path: &Path) -> EnvFile {
+        let filename = path.file_name().unwrap().to_string_lossy().to_string();
+        let parsed = parse_env_file(path).unwrap();
+        EnvFile::new(
+            path.to_string_lossy().to_string(),
+            filename.clone(),
+            filename.clone(),
+            detect_variant(&filename),
+            parsed.variables,
+            false,
+        )
+    }
+
+    #[test]
+    fn overridden_variant_survives_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env.ci");
+        fs::write(&path, "A=1\n").unwrap();
+
+        let mut file = env_file_at(&path);
+        assert_eq!(file.variant, EnvFileVariant::Other);
+
+        set_variant(&mut file, Some(EnvFileVariant::Test));
+        fs::write(&path, "A=1\nB=2\n").unwrap();
+        apply_refresh(&mut file, parse_env_file(&path).unwrap());
+
+        assert_eq!(file.variables.len(), 2);
+        assert_eq!(file.variant, EnvFileVariant::Test);
+        assert!(file.variant_overridden);
+
+        // Forced rediscovery builds fresh entries; the manual variant carries over
+        let mut rediscovered = vec![env_file_at(&path)];
+        carry_over_variants(&[file.clone()], &mut rediscovered);
+        assert_eq!(rediscovered[0].variant, EnvFileVariant::Test);
+        assert!(rediscovered[0].variant_overridden);
+
+        // Clearing the override goes back to auto-detection
+        set_variant(&mut file, None);
+        assert_eq!(file.variant, EnvFileVariant::Other);
+        assert!(!file.variant_overridden);
+    }
+
+    #[test]
+    fn linked_env_files_feed_the_service_env() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".env"), "PORT=3000\nHOST=localhost\nDEBUG=1\n").unwrap();
+        fs::write(dir.path().join(".env.local"), "PORT=4000\n").unwrap();
+        fs::write(dir.path().join(".env.test"), "UNLINKED=1\n").unwrap();
+
+        let mut project = Project::new("app".into(), dir.path().to_string_lossy().to_string());
+        let mut service = Service::new("api".into(), ".".into(), "npm start".into());
+        service.env_vars = Some(HashMap::from([("DEBUG".to_string(), "0".to_string())]));
+        project.env_files = [".env", ".env.local", ".env.test", ".env.gone"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                let mut file = if path.exists() {
+                    env_file_at(&path)
+                } else {
+                    let path = path.to_string_lossy().to_string();
+                    EnvFile::new(path, name.to_string(), name.to_string(), EnvFileVariant::Other, Vec::new(), true)
+                };
+                if *name != ".env.test" {
+                    file.linked_service_id = Some(service.id.clone());
+                }
+                file
+            })
+            .collect();
+
+        // .env.local beats .env, the service's own vars beat both; the
+        // missing file is skipped
+        let vars = service_env_vars(&project, &service).unwrap();
+        assert_eq!(vars.get("PORT").map(String::as_str), Some("4000"));
+        assert_eq!(vars.get("HOST").map(String::as_str), Some("localhost"));
+        assert_eq!(vars.get("DEBUG").map(String::as_str), Some("0"));
+        assert!(!vars.contains_key("UNLINKED"));
+
+        // Unlinking stops the injection on the next start
+        for file in &mut project.env_files {
+            file.linked_service_id = None;
+        }
+        let vars = service_env_vars(&project, &service).unwrap();
+        assert_eq!(vars, HashMap::from([("DEBUG".to_string(), "0".to_string())]));
+        service.env_vars = None;
+        assert_eq!(service_env_vars(&project, &service), None);
+    }
+
+    #[test]
+    fn variants_read_their_own_env_files_and_overrides() {
+        let main = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        fs::write(main.path().join(".env"), "PORT=3000\nDB=main\n").unwrap();
+        fs::write(worktree.path().join(".env"), "PORT=3100\nDB=hotfix\n").unwrap();
+
+        let mut project = Project::new("app".into(), main.path().to_string_lossy().to_string());
+        let mut service = Service::new("api".into(), ".".into(), "npm start".into());
+        service.env_vars = Some(HashMap::from([("DEBUG".to_string(), "1".to_string())]));
+        let mut file = env_file_at(&main.path().join(".env"));
+        file.linked_service_id = Some(service.id.clone());
+        project.env_files.push(file);
+
+        let mut variant = ProjectVariant::new("hotfix".into(), worktree.path().to_string_lossy().to_string());
+        variant.env_var_overrides = HashMap::from([
+            ("PORT".to_string(), "3200".to_string()),
+            ("DEBUG".to_string(), "0".to_string()),
+        ]);
+        project.active_variant_id = Some(variant.id.clone());
+        project.variants.push(variant);
+
+        // The worktree's .env, under the variant overrides, under the service's own vars
+        let vars = service_env_vars(&project, &service).unwrap();
+        assert_eq!(vars.get("DB").map(String::as_str), Some("hotfix"));
+        assert_eq!(vars.get("PORT").map(String::as_str), Some("3200"));
+        assert_eq!(vars.get("DEBUG").map(String::as_str), Some("1"));
+
+        // Back on the main checkout
+        project.active_variant_id = None;
+        let vars = service_env_vars(&project, &service).unwrap();
+        assert_eq!((vars["DB"].as_str(), vars["PORT"].as_str()), ("main", "3000"));
+    }
+
+    const FIXTURE: &str = "\
+# Database
+DB_HOST=localhost
+DB_PASSWORD=\"s3cret\"
+
+  # Feature flags
+FLAG='on'
+GREETING = hello
+";
+
+    fn edit(content: &str, edit: EnvEdit) -> Result<String, EnvEditError> {
+        apply_edit(content, &edit)
+    }
+
+    fn set(key: &str, value: &str) -> EnvEdit {
+        EnvEdit::Set { key: key.into(), value: value.into() }
+    }
+
+    #[test]
+    fn edits_keep_comments_order_and_quoting() {
+        let out = edit(FIXTURE, set("DB_PASSWORD", "new pass")).unwrap();
+        assert_eq!(out, FIXTURE.replace("\"s3cret\"", "\"new pass\""));
+
+        let out = edit(&out, set("FLAG", "off")).unwrap();
+        assert!(out.contains("FLAG='off'\n"));
+        let out = edit(&out, set("GREETING", " padded ")).unwrap();
+        assert!(out.contains("GREETING = \" padded \"\n"));
+        let out = edit(&out, set("DB_HOST", "db.internal")).unwrap();
+        assert!(out.contains("DB_HOST=db.internal\n"));
+
+        let out = edit(&out, EnvEdit::Remove { key: "FLAG".into() }).unwrap();
+        let out = edit(&out, EnvEdit::Add { key: "PORT".into(), value: "5432".into() }).unwrap();
+        assert_eq!(
+            out,
+            "# Database\nDB_HOST=db.internal\nDB_PASSWORD=\"new pass\"\n\n  # Feature flags\nGREETING = \" padded \"\nPORT=5432\n"
+        );
+
+        // Values read back exactly as set
+        let parsed = parse_env_content(&out);
+        let vars: Vec<(&str, &str)> = parsed.variables.iter().map(|v| (v.key.as_str(), v.value.as_str())).collect();
+        assert_eq!(
+            vars,
+            vec![("DB_HOST", "db.internal"), ("DB_PASSWORD", "new pass"), ("GREETING", " padded "), ("PORT", "5432")]
+        );
+    }
+
+    #[test]
+    fn quotes_and_line_breaks_in_values_read_back() {
+        let values = ["say \"hi\"", r"C:\dir\", "it's", "\"quoted\"", "two\nlines", r#"\"mixed\" \\ it's"#];
+        for value in values {
+            for quote in [None, Some('"'), Some('\'')] {
+                let content = format!("KEY={}\nNEXT=1\n", render_value(value, quote));
+                let parsed = parse_env_content(&content);
+                assert!(parsed.errors.is_empty(), "{:?}", content);
+                assert_eq!(parsed.variables[0].value, value, "{:?}", content);
+                assert_eq!(parsed.variables[1].key, "NEXT", "{:?}", content);
+            }
+        }
+
+        // Single quotes give way only when they must
+        let out = edit("FLAG='off'\n", set("FLAG", "don't")).unwrap();
+        assert_eq!(out, "FLAG=\"don't\"\n");
+        let out = edit(&out, set("FLAG", "a \"b\"")).unwrap();
+        assert_eq!(out, "FLAG=\"a \\\"b\\\"\"\n");
+        assert_eq!(parse_env_content(&out).variables[0].value, "a \"b\"");
+    }
+
+    #[test]
+    fn crlf_files_stay_crlf() {
+        let crlf = "# comment\r\nA=1\r\nB=\"two\"";
+        let out = edit(crlf, set("A", "10")).unwrap();
+        assert_eq!(out, "# comment\r\nA=10\r\nB=\"two\"");
+        let out = edit(&out, EnvEdit::Add { key: "C".into(), value: "3".into() }).unwrap();
+        assert_eq!(out, "# comment\r\nA=10\r\nB=\"two\"\r\nC=3\r\n");
+
+        // Only the edited line changes, even in a file with mixed endings
+        let mixed = "A=1\r\nB=2\n";
+        assert_eq!(edit(mixed, set("B", "3")).unwrap(), "A=1\r\nB=3\n");
+    }
+
+    #[test]
+    fn scaffolding_from_an_example_creates_or_completes_the_file() {
+        let example = "# Database\r\nDB_HOST=localhost\r\nDB_PASSWORD=\"changeme\"\r\n\r\nnot a variable\r\n# API\r\nAPI_KEY=xxx\r\n";
+        assert_eq!(scaffold_content(example), "# Database\r\nDB_HOST=\r\nDB_PASSWORD=\r\n\r\n# API\r\nAPI_KEY=\r\n");
+
+        let dir = tempfile::tempdir().unwrap();
+        let example_path = dir.path().join(".env.example");
+        fs::write(&example_path, "# Database\nDB_HOST=localhost\nDB_PORT=5432\nAPI_KEY=xxx\n").unwrap();
+
+        let target = dir.path().join(".env");
+        let added = scaffold_env_file(&example_path, &target).unwrap();
+        assert_eq!(added, vec!["DB_HOST", "DB_PORT", "API_KEY"]);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "# Database\nDB_HOST=\nDB_PORT=\nAPI_KEY=\n");
+
+        // An existing file keeps its values and only gets the missing keys
+        fs::write(&target, "DB_HOST=db.internal\n# mine\nEXTRA=1").unwrap();
+        let added = scaffold_env_file(&example_path, &target).unwrap();
+        assert_eq!(added, vec!["DB_PORT", "API_KEY"]);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "DB_HOST=db.internal\n# mine\nEXTRA=1\nDB_PORT=\nAPI_KEY=\n");
+        assert!(scaffold_env_file(&example_path, &target).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sensitive_values_are_masked_keeping_keys_and_lines() {
+        for key in ["API_KEY", "aws_secret_access_key", "GITHUB_TOKEN", "DB_PASSWORD", "PASSWORD", "client.secret", "SMTP_PASS"] {
+            assert!(is_sensitive(key), "{}", key);
+        }
+        for key in ["KEYBOARD_LAYOUT", "TOKENIZER_PATH", "PRIMARY_KEY_COLUMN", "DB_HOST", "PORT", "PASSENGER_COUNT"] {
+            assert!(!is_sensitive(key), "{}", key);
+        }
+
+        let content = "# API_KEY=in a comment\r\nAPI_KEY = \"sk-123\"\r\nHOST=localhost\r\nEMPTY_TOKEN=\r\nDB_PASSWORD=hunter2";
+        let masked = mask_content(content);
+        assert_eq!(
+            masked,
+            "# API_KEY=in a comment\r\nAPI_KEY = \u{2022}\u{2022}\u{2022}\u{2022}\r\nHOST=localhost\r\nEMPTY_TOKEN=\r\nDB_PASSWORD=\u{2022}\u{2022}\u{2022}\u{2022}"
+        );
+
+        let mut vars = parse_env_content(content).variables;
+        mask_variables(&mut vars);
+        let vars: Vec<(&str, &str, u32)> = vars.iter().map(|v| (v.key.as_str(), v.value.as_str(), v.line_number)).collect();
+        assert_eq!(
+            vars,
+            vec![("API_KEY", MASKED_VALUE, 2), ("HOST", "localhost", 3), ("EMPTY_TOKEN", "", 4), ("DB_PASSWORD", MASKED_VALUE, 5)]
+        );
+    }
+
+    #[test]
+    fn setting_an_undefined_variable_appends_it() {
+        let out = edit("A=1\n# B=old", set("B", "two words ")).unwrap();
+        assert_eq!(out, "A=1\n# B=old\nB=\"two words \"\n");
+        assert_eq!(parse_env_content(&out).variables[1].value, "two words ");
+    }
+
+    #[test]
+    fn bad_edits_are_refused() {
+        assert_eq!(
+            edit(FIXTURE, EnvEdit::Add { key: "DB_HOST".into(), value: "x".into() }),
+            Err(EnvEditError::AlreadyDefined("DB_HOST".into()))
+        );
+        assert_eq!(edit(FIXTURE, set("not valid", "x")), Err(EnvEditError::InvalidKey("not valid".into())));
+        assert_eq!(edit(FIXTURE, set("DB_HOST", "a\nb")), Err(EnvEditError::MultilineValue("DB_HOST".into())));
+        // A commented-out definition doesn't count
+        assert!(edit("# OLD=1\n", EnvEdit::Remove { key: "OLD".into() }).is_err());
+    }
+
+    #[test]
+    fn external_changes_block_edits_until_refreshed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, FIXTURE).unwrap();
+
+        let mut file = env_file_at(&path);
+        edit_env_file(&mut file, &set("DB_HOST", "db")).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), FIXTURE.replace("=localhost", "=db"));
+        assert_eq!(file.variables[0].value, "db");
+        assert!(!dir.path().join(".env.cortx-tmp").exists());
+
+        // Read before the file last changed
+        file.last_read_at = Utc::now() - chrono::Duration::hours(1);
+        let err = edit_env_file(&mut file, &set("DB_HOST", "other")).unwrap_err();
+        assert!(matches!(err, EnvEditError::Conflict(_)));
+        assert!(fs::read_to_string(&path).unwrap().contains("DB_HOST=db\n"));
+
+        apply_refresh(&mut file, parse_env_file(&path).unwrap());
+        edit_env_file(&mut file, &set("DB_HOST", "other")).unwrap();
+        assert_eq!(file.variables[0].value, "other");
+    }
+
+    #[test]
+    fn windows_saved_files_parse_and_are_edited_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        // Notepad: UTF-8 with a BOM, CRLF
+        fs::write(&path, b"\xEF\xBB\xBFDATABASE_URL=postgres://db\r\nPORT=5432\r\n").unwrap();
+
+        let mut file = env_file_at(&path);
+        assert_eq!(file.variables[0].key, "DATABASE_URL");
+        edit_env_file(&mut file, &set("PORT", "6543")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"\xEF\xBB\xBFDATABASE_URL=postgres://db\r\nPORT=6543\r\n");
+
+        // PowerShell 5: UTF-16LE with a BOM
+        let utf16 = |text: &str| {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        };
+        fs::write(&path, utf16("A=1\r\n")).unwrap();
+        let mut file = env_file_at(&path);
+        assert_eq!(file.variables[0].value, "1");
+        edit_env_file(&mut file, &EnvEdit::Add { key: "B".into(), value: "2".into() }).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), utf16("A=1\r\nB=2\r\n"));
+
+        fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0").unwrap();
+        let err = parse_env_file(&path).unwrap_err();
+        assert_eq!(err, format!("{} is not valid UTF-8 text", path.display()));
+    }
+
+    #[test]
+    fn detects_variant_from_filename() {
+        assert_eq!(detect_variant(".env"), EnvFileVariant::Base);
+        assert_eq!(detect_variant(".env.local"), EnvFileVariant::Local);
+        assert_eq!(detect_variant(".env.production"), EnvFileVariant::Production);
+        assert_eq!(detect_variant(".env.example"), EnvFileVariant::Example);
+        assert_eq!(detect_variant(".env.qa"), EnvFileVariant::Other);
+    }
+
+    #[test]
+    fn finds_keys_defined_differently_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".env"), "PORT=3000\nHOST=localhost\nDEBUG=1\n").unwrap();
+        fs::write(dir.path().join(".env.local"), "PORT=4000\nHOST=localhost\n").unwrap();
+        fs::write(dir.path().join(".env.example"), "PORT=\nDEBUG=0\n").unwrap();
+        fs::create_dir(dir.path().join("api")).unwrap();
+        fs::write(dir.path().join("api").join(".env"), "PORT=5000\n").unwrap();
+
+        let files: Vec<EnvFile> = [".env", ".env.local", ".env.example", "api/.env"]
+            .iter()
+            .map(|name| env_file_at(&dir.path().join(name)))
+            .collect();
+
+        let duplicates = duplicate_keys(&files, dir.path());
+        assert_eq!(
+            duplicates,
+            vec![(
+                "PORT".to_string(),
+                vec![".env=3000".to_string(), ".env.local=4000".to_string()]
+            )]
+        );
+        assert!(duplicate_keys(&files, &dir.path().join("api")).is_empty());
+    }
+
+    #[test]
+    fn matrix_lines_up_every_key_against_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".env"), "PORT=3000\nHOST=localhost\nAPI_TOKEN=abc\n").unwrap();
+        fs::write(dir.path().join(".env.local"), "PORT=4000\nHOST=localhost\nAPI_TOKEN=def\n").unwrap();
+        fs::write(dir.path().join(".env.example"), "PORT=\nHOST=changeme\nSENTRY_DSN=\n").unwrap();
+        fs::create_dir(dir.path().join("api")).unwrap();
+        fs::write(dir.path().join("api").join(".env"), "PORT=3000\n").unwrap();
+
+        let files: Vec<EnvFile> = [".env", ".env.local", ".env.example", "api/.env"]
+            .iter()
+            .map(|name| env_file_at(&dir.path().join(name)))
+            .collect();
+        let mut matrix = env_matrix(&files);
+        assert_eq!(matrix.file_ids, files.iter().map(|f| f.id.clone()).collect::<Vec<_>>());
+
+        let row = |matrix: &EnvMatrix, key: &str| matrix.rows.iter().find(|r| r.key == key).unwrap().clone();
+        let keys: Vec<&str> = matrix.rows.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, ["API_TOKEN", "HOST", "PORT", "SENTRY_DSN"]);
+
+        let port = row(&matrix, "PORT");
+        assert_eq!(port.values, [Some("3000".into()), Some("4000".into()), Some(String::new()), Some("3000".into())]);
+        assert!(port.values_differ);
+        // The example's placeholder doesn't count as a difference
+        assert!(!row(&matrix, "HOST").values_differ);
+
+        // Only the .env next to the example is missing its key
+        let sentry = row(&matrix, "SENTRY_DSN");
+        assert_eq!(sentry.values, [None, None, Some(String::new()), None]);
+        assert_eq!(sentry.missing_in_base, [files[0].id.clone()]);
+        assert!(!sentry.values_differ);
+        assert!(row(&matrix, "PORT").missing_in_base.is_empty());
+
+        // Masking comes after the comparison
+        mask_matrix(&mut matrix);
+        let token = row(&matrix, "API_TOKEN");
+        assert_eq!(token.values, [Some(MASKED_VALUE.into()), Some(MASKED_VALUE.into()), None, None]);
+        assert!(token.values_differ);
+        assert_eq!(row(&matrix, "PORT").values[0].as_deref(), Some("3000"));
+    }
+}
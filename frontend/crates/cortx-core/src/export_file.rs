@@ -0,0 +1,355 @@
+//! Choosing files for script export / import.
+//!
+//! Shared by the desktop file dialogs and the TUI path prompt: the default
+//! export filename, the remembered directory (`AppSettings::last_export_dir`),
+//! a writability check run before exporting, and path completion for the prompt.
+//! Exports are JSON only: a single file, or a directory with one file per
+//! script for keeping them in git.
+
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::models::{ExportSummary, GlobalScript, ImportOptions};
+
+/// Script files of a directory export live here
+pub const SCRIPTS_SUBDIR: &str = "scripts";
+/// Order, folders and tag / status definitions of a directory export
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Current `ScriptDirManifest::version`
+pub const SCRIPT_DIR_VERSION: &str = "1";
+
+/// `cortx-scripts-YYYYMMDD.json`
+pub fn default_export_filename(date: NaiveDate) -> String {
+    format!("cortx-scripts-{}.json", date.format("%Y%m%d"))
+}
+
+/// Directory to open a dialog / prompt in: the remembered one, if it still exists.
+pub fn start_dir(last_dir: Option<&str>) -> Option<PathBuf> {
+    last_dir.map(PathBuf::from).filter(|dir| dir.is_dir())
+}
+
+/// The directory to remember after a file was picked.
+pub fn dir_to_remember(path: &Path) -> Option<String> {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(|dir| dir.to_string_lossy().to_string())
+}
+
+/// Import every category the file actually contains.
+pub fn import_options_for(summary: &ExportSummary) -> ImportOptions {
+    ImportOptions {
+        projects: summary.projects_count > 0,
+        scripts: summary.scripts_count > 0,
+        tools: summary.tools_count > 0,
+        apps: summary.apps_count > 0,
+        shell_config: summary.aliases_count > 0,
+        tags_and_statuses: summary.tag_definitions_count > 0 || summary.status_definitions_count > 0,
+        settings: summary.has_settings,
+    }
+}
+
+/// File name for each script of a directory export, in the same order:
+/// `<slug of the name>-<first 8 chars of the id>.json`. Names that still
+/// collide (ignoring case) get `-2`, `-3`, ... appended.
+pub fn script_file_names(scripts: &[GlobalScript]) -> Vec<String> {
+    let mut taken: Vec<String> = Vec::with_capacity(scripts.len());
+    let mut names = Vec::with_capacity(scripts.len());
+    for script in scripts {
+        let id: String = script.id.chars().filter(|c| c.is_ascii_alphanumeric()).take(8).collect();
+        let stem = format!("{}-{}", slug(&script.name), id.to_lowercase());
+        let mut name = format!("{}.json", stem);
+        let mut n = 2;
+        while taken.contains(&name.to_lowercase()) {
+            name = format!("{}-{}.json", stem, n);
+            n += 1;
+        }
+        taken.push(name.to_lowercase());
+        names.push(name);
+    }
+    names
+}
+
+/// Lowercase ASCII letters and digits, other runs turned into a single `-`
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_end_matches('-').chars().take(48).collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "script".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Check that an export can be written to `path` before serializing anything,
+/// so a read-only target is reported clearly instead of as a bare I/O error.
+pub fn check_writable(path: &Path) -> Result<(), String> {
+    if path.is_dir() {
+        return Err(format!("{} is a directory", path.display()));
+    }
+    if path.exists() {
+        let read_only = fs::metadata(path).map(|m| m.permissions().readonly()).unwrap_or(false);
+        if read_only {
+            return Err(format!("{} is read-only", path.display()));
+        }
+        // Opening for append doesn't touch the existing content
+        return OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map(|_| ())
+            .map_err(|e| format!("Cannot write to {}: {}", path.display(), e));
+    }
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if !dir.is_dir() {
+        return Err(format!("Directory does not exist: {}", dir.display()));
+    }
+    // Directory permission bits don't tell the whole story (ACLs, read-only
+    // mounts), so probe with a real file
+    let probe = dir.join(format!(".cortx-write-check-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(e) => Err(format!("Cannot write to {}: {}", dir.display(), e)),
+    }
+}
+
+/// Result of completing a partially typed path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathCompletion {
+    /// The input extended as far as all candidates agree
+    pub completed: String,
+    /// Matching entry names; directories end with `/`
+    pub candidates: Vec<String>,
+}
+
+/// Complete the last component of `input` against the entries of its
+/// directory. A single match is completed fully (directories get a trailing
+/// `/`); several matches are completed to their common prefix. Hidden entries
+/// are only offered when the typed prefix starts with `.`.
+pub fn complete_path(input: &str) -> PathCompletion {
+    let split = input.rfind(['/', std::path::MAIN_SEPARATOR]).map(|i| i + 1).unwrap_or(0);
+    let (dir_part, prefix) = input.split_at(split);
+    let dir = if dir_part.is_empty() { Path::new(".") } else { Path::new(dir_part) };
+
+    let mut candidates: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    let name = entry.file_name().into_string().ok()?;
+                    if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                        return None;
+                    }
+                    let is_dir = entry.path().is_dir();
+                    Some(if is_dir { format!("{}/", name) } else { name })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    candidates.sort();
+
+    let completed = match candidates.as_slice() {
+        [] => input.to_string(),
+        [only] => format!("{}{}", dir_part, only),
+        [first, rest @ ..] => {
+            let mut common = first.as_str();
+            for candidate in rest {
+                let len = common
+                    .char_indices()
+                    .zip(candidate.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map(|((i, a), _)| i + a.len_utf8())
+                    .unwrap_or(0);
+                common = &common[..len];
+            }
+            format!("{}{}", dir_part, common)
+        }
+    };
+    PathCompletion { completed, candidates }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_filename_uses_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 7).unwrap();
+        assert_eq!(default_export_filename(date), "cortx-scripts-20240307.json");
+    }
+
+    #[test]
+    fn remembers_parent_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.json");
+        let remembered = dir_to_remember(&file).unwrap();
+        assert_eq!(start_dir(Some(&remembered)), Some(dir.path().to_path_buf()));
+
+        assert_eq!(dir_to_remember(Path::new("out.json")), None);
+        assert_eq!(start_dir(Some("/definitely/not/here")), None);
+        assert_eq!(start_dir(None), None);
+    }
+
+    #[test]
+    fn export_and_import_remember_the_directory() {
+        let data = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let storage = crate::storage::Storage::with_app_dir(data.path().to_path_buf()).unwrap();
+        let file = out.path().join("cortx-scripts-20240307.json");
+
+        storage.export_scripts_config_to(&file).unwrap();
+        let remembered = storage.get_settings().last_export_dir;
+        assert_eq!(remembered.as_deref(), Some(out.path().to_string_lossy().as_ref()));
+
+        // Persisted across restarts
+        let reopened = crate::storage::Storage::with_app_dir(data.path().to_path_buf()).unwrap();
+        assert_eq!(reopened.get_settings().last_export_dir, remembered);
+
+        let (json, summary) = reopened.read_import_file(&file).unwrap();
+        assert!(json.contains("\"version\""));
+        assert_eq!(summary.scripts_count, 0);
+        let options = import_options_for(&summary);
+        assert!(!options.scripts && !options.projects);
+        assert!(options.settings);
+    }
+
+    #[test]
+    fn exports_saved_by_powershell_import_and_binary_files_are_named() {
+        let data = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let storage = crate::storage::Storage::with_app_dir(data.path().to_path_buf()).unwrap();
+        let json = storage.export_scripts_config().unwrap();
+
+        // `Get-Content export.json | Out-File copy.json`: UTF-16LE, BOM, CRLF
+        let file = out.path().join("copy.json");
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(json.replace('\n', "\r\n").encode_utf16().flat_map(u16::to_le_bytes));
+        fs::write(&file, bytes).unwrap();
+        let (read, summary) = storage.read_import_file(&file).unwrap();
+        assert_eq!(read, json);
+        assert_eq!(summary.scripts_count, 0);
+        // Pasted with its BOM
+        assert!(crate::storage::Storage::preview_import(&format!("\u{feff}{}", json)).is_ok());
+
+        let file = out.path().join("export.zip");
+        fs::write(&file, b"PK\x03\x04\x14\0\0\0\x08\0\xB7\x8C").unwrap();
+        let err = storage.read_import_file(&file).unwrap_err().to_string();
+        assert_eq!(err, format!("{} is not valid UTF-8 text", file.display()));
+    }
+
+    #[test]
+    fn script_file_names_are_slugged_and_unique() {
+        let mut scripts: Vec<GlobalScript> = ["Deploy (prod)!", "deploy prod", "日本"]
+            .iter()
+            .map(|name| GlobalScript::new(name.to_string(), "true".into(), None))
+            .collect();
+        for script in &mut scripts {
+            script.id = "ABCDEF12-3456".into();
+        }
+
+        assert_eq!(
+            script_file_names(&scripts),
+            vec!["deploy-prod-abcdef12.json", "deploy-prod-abcdef12-2.json", "script-abcdef12.json"]
+        );
+    }
+
+    #[test]
+    fn script_dir_round_trip() {
+        let data = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let storage = crate::storage::Storage::with_app_dir(data.path().to_path_buf()).unwrap();
+        let mut ids = Vec::new();
+        for (name, folder) in [("Deploy", Some("ops/aws")), ("Deploy", Some("ops/aws")), ("lint", None)] {
+            let mut script = GlobalScript::new(name.into(), format!("./{}.sh", name), None);
+            script.folder = folder.map(String::from);
+            ids.push(script.id.clone());
+            storage.create_global_script(script).unwrap();
+        }
+
+        assert_eq!(storage.export_scripts_to_dir(out.path()).unwrap(), 3);
+        let files = fs::read_dir(out.path().join(SCRIPTS_SUBDIR)).unwrap().count();
+        assert_eq!(files, 3);
+        let manifest = fs::read_to_string(out.path().join(MANIFEST_FILE)).unwrap();
+        assert!(manifest.contains("ops/aws"));
+
+        let other_data = tempfile::tempdir().unwrap();
+        let other = crate::storage::Storage::with_app_dir(other_data.path().to_path_buf()).unwrap();
+        let result = other.import_scripts_from_dir(out.path()).unwrap();
+        assert_eq!(result.scripts_added, 3);
+        let as_json = |s: &crate::storage::Storage| serde_json::to_value(s.get_all_global_scripts()).unwrap();
+        assert_eq!(as_json(&other), as_json(&storage));
+
+        // Deleted scripts lose their file on the next export
+        storage.delete_global_script(&ids[2]).unwrap();
+        storage.export_scripts_to_dir(out.path()).unwrap();
+        assert_eq!(fs::read_dir(out.path().join(SCRIPTS_SUBDIR)).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn writability_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check_writable(&dir.path().join("new.json")).is_ok());
+        assert!(check_writable(dir.path()).unwrap_err().contains("is a directory"));
+        assert!(check_writable(&dir.path().join("missing").join("x.json"))
+            .unwrap_err()
+            .contains("does not exist"));
+
+        let existing = dir.path().join("existing.json");
+        fs::write(&existing, "keep").unwrap();
+        assert!(check_writable(&existing).is_ok());
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "keep");
+
+        let mut perms = fs::metadata(&existing).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&existing, perms).unwrap();
+        assert!(check_writable(&existing).unwrap_err().contains("read-only"));
+
+        // No probe file left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn completes_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("exports")).unwrap();
+        fs::write(dir.path().join("export-a.json"), "").unwrap();
+        fs::write(dir.path().join("export-b.json"), "").unwrap();
+        fs::write(dir.path().join(".hidden"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        let c = complete_path(&format!("{}exp", base));
+        assert_eq!(c.completed, format!("{}export", base));
+        assert_eq!(c.candidates, vec!["export-a.json", "export-b.json", "exports/"]);
+
+        let c = complete_path(&format!("{}exports", base));
+        assert_eq!(c.completed, format!("{}exports/", base));
+
+        let c = complete_path(&format!("{}export-b", base));
+        assert_eq!(c.completed, format!("{}export-b.json", base));
+
+        let c = complete_path(&base);
+        assert_eq!(c.candidates.len(), 3);
+        let c = complete_path(&format!("{}.h", base));
+        assert_eq!(c.candidates, vec![".hidden"]);
+
+        let c = complete_path(&format!("{}nothing", base));
+        assert_eq!(c.completed, format!("{}nothing", base));
+        assert!(c.candidates.is_empty());
+    }
+}
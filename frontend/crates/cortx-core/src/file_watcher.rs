@@ -41,6 +41,20 @@ pub struct FileWatcherHandle {
     _thread: std::thread::JoinHandle<()>,
 }
 
+/// Which data file a changed `.json` under the data dir belongs to: the
+/// files in `projects/` are all [`DataFile::Projects`]
+fn data_file_of(path: &std::path::Path) -> Option<DataFile> {
+    let filename = path.file_name()?.to_str()?;
+    if !filename.ends_with(".json") {
+        return None;
+    }
+    let in_projects_dir = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|dir| dir == crate::project_files::PROJECTS_DIR);
+    Some(if in_projects_dir { DataFile::Projects } else { DataFile::from_filename(filename) })
+}
+
 /// Start watching `watch_dir` for .json changes. Debounces by 300ms.
 /// Calls `on_change` with list of changed DataFiles.
 pub fn start_watching<F>(
@@ -54,7 +68,7 @@ where
     let mut debouncer = new_debouncer(Duration::from_millis(300), tx)?;
     debouncer
         .watcher()
-        .watch(&watch_dir, notify::RecursiveMode::NonRecursive)?;
+        .watch(&watch_dir, notify::RecursiveMode::Recursive)?;
 
     let thread = std::thread::Builder::new()
         .name("cortx-file-watcher".into())
@@ -70,12 +84,9 @@ where
 
                 let mut changed: Vec<DataFile> = Vec::new();
                 for event in events {
-                    if let Some(filename) = event.path.file_name().and_then(|f| f.to_str()) {
-                        if filename.ends_with(".json") {
-                            let df = DataFile::from_filename(filename);
-                            if !changed.contains(&df) {
-                                changed.push(df);
-                            }
+                    if let Some(df) = data_file_of(&event.path) {
+                        if !changed.contains(&df) {
+                            changed.push(df);
                         }
                     }
                 }
@@ -0,0 +1,430 @@
+//! OS-wide shortcuts of the desktop app.
+//!
+//! They fire while CortX isn't focused: `AppSettings::global_shortcuts`
+//! maps a [`ShortcutAction`] to an accelerator ("CmdOrCtrl+Alt+S"), and
+//! `AppSettings::global_hotkey` opens the palette. This module checks the
+//! accelerators, keeps track of what is registered with the OS so a
+//! settings change only (un)registers the difference, and resolves an
+//! action against the projects. Talking to the OS and running the action
+//! is the app's part.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::models::{AppSettings, Project, ShortcutAction, ShortcutWarning, ShortcutWarningKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Modifier {
+    /// Cmd on macOS, Ctrl elsewhere
+    CmdOrCtrl,
+    Ctrl,
+    Alt,
+    Shift,
+    /// Cmd on macOS, the Windows key elsewhere
+    Super,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "cmdorctrl" | "commandorcontrol" | "cmdorcontrol" | "commandorctrl" => Modifier::CmdOrCtrl,
+            "ctrl" | "control" => Modifier::Ctrl,
+            "alt" | "option" => Modifier::Alt,
+            "shift" => Modifier::Shift,
+            "super" | "cmd" | "command" | "meta" => Modifier::Super,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Modifier::CmdOrCtrl => "CmdOrCtrl",
+            Modifier::Ctrl => "Ctrl",
+            Modifier::Alt => "Alt",
+            Modifier::Shift => "Shift",
+            Modifier::Super => "Super",
+        })
+    }
+}
+
+/// Named keys, by their canonical spelling and its aliases (lowercase)
+const NAMED_KEYS: &[(&str, &[&str])] = &[
+    ("Space", &["space"]),
+    ("Enter", &["enter", "return"]),
+    ("Tab", &["tab"]),
+    ("Escape", &["escape", "esc"]),
+    ("Backspace", &["backspace"]),
+    ("Delete", &["delete", "del"]),
+    ("Insert", &["insert", "ins"]),
+    ("Home", &["home"]),
+    ("End", &["end"]),
+    ("PageUp", &["pageup"]),
+    ("PageDown", &["pagedown"]),
+    ("Up", &["up", "arrowup"]),
+    ("Down", &["down", "arrowdown"]),
+    ("Left", &["left", "arrowleft"]),
+    ("Right", &["right", "arrowright"]),
+];
+
+/// Punctuation keys, as typed without Shift on a US layout
+const PUNCTUATION_KEYS: &str = ",./;'[]\\-=`";
+
+/// The canonical name of `key`: "A", "7", "F5", "Space", ",". `None` if
+/// it isn't a key.
+fn canonical_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+        return PUNCTUATION_KEYS.contains(c).then(|| c.to_string());
+    }
+    let lower = key.to_ascii_lowercase();
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{}", n));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&lower.as_str()))
+        .map(|(name, _)| name.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AcceleratorError {
+    #[error("The shortcut is empty")]
+    Empty,
+    #[error("\"{0}\" has an empty part")]
+    EmptyPart(String),
+    #[error("\"{0}\" is not a modifier (Ctrl, Alt, Shift, Super or CmdOrCtrl)")]
+    NotAModifier(String),
+    #[error("\"{0}\" is not a key")]
+    UnknownKey(String),
+    #[error("The shortcut has no key, only modifiers")]
+    MissingKey,
+    #[error("{0} appears twice")]
+    DuplicateModifier(Modifier),
+    /// Without Ctrl, Alt or the like, a global shortcut would swallow the
+    /// key in every other app
+    #[error("{0} needs a modifier other than Shift")]
+    NeedsModifier(String),
+}
+
+/// A checked shortcut: modifiers plus one key. Its `Display` is the
+/// canonical spelling ("CmdOrCtrl+Shift+S"), which the global-shortcut
+/// plugin parses too.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Accelerator {
+    modifiers: BTreeSet<Modifier>,
+    key: String,
+}
+
+impl Accelerator {
+    pub fn parse(text: &str) -> Result<Self, AcceleratorError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(AcceleratorError::Empty);
+        }
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(AcceleratorError::EmptyPart(text.to_string()));
+        }
+        let (key, modifier_names) = parts.split_last().expect("split yields at least one part");
+
+        let mut modifiers = BTreeSet::new();
+        for name in modifier_names {
+            let modifier = Modifier::parse(name).ok_or_else(|| AcceleratorError::NotAModifier(name.to_string()))?;
+            if !modifiers.insert(modifier) {
+                return Err(AcceleratorError::DuplicateModifier(modifier));
+            }
+        }
+        if let Some(modifier) = Modifier::parse(key) {
+            return Err(if modifiers.contains(&modifier) {
+                AcceleratorError::DuplicateModifier(modifier)
+            } else {
+                AcceleratorError::MissingKey
+            });
+        }
+        let key = canonical_key(key).ok_or_else(|| AcceleratorError::UnknownKey(key.to_string()))?;
+
+        let function_key = key.len() > 1 && key.starts_with('F') && key[1..].parse::<u8>().is_ok();
+        if !function_key && modifiers.iter().all(|m| *m == Modifier::Shift) {
+            return Err(AcceleratorError::NeedsModifier(text.to_string()));
+        }
+        Ok(Self { modifiers, key })
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier)?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+/// The shortcuts `settings` asks for: `global_shortcuts`, plus the palette's
+/// `global_hotkey` (`default_palette` when unset, none when blank)
+pub fn wanted_shortcuts(settings: &AppSettings, default_palette: &str) -> BTreeMap<ShortcutAction, String> {
+    let mut wanted = settings.global_shortcuts.clone();
+    let palette = settings.global_hotkey.as_deref().unwrap_or(default_palette);
+    wanted.remove(&ShortcutAction::OpenPalette);
+    if !palette.trim().is_empty() {
+        wanted.insert(ShortcutAction::OpenPalette, palette.to_string());
+    }
+    wanted.retain(|_, accelerator| !accelerator.trim().is_empty());
+    wanted
+}
+
+/// The shortcuts registered with the OS, and the action of each
+#[derive(Debug, Default)]
+pub struct ShortcutRegistry {
+    registered: BTreeMap<Accelerator, ShortcutAction>,
+}
+
+impl ShortcutRegistry {
+    /// Make the registered shortcuts those of `wanted`: `unregister` the
+    /// ones no longer wanted, `register` the new ones, and re-map those that
+    /// only changed action without touching the OS. Returns why some wanted
+    /// shortcuts aren't registered: an invalid accelerator, one an earlier
+    /// action (in key order) already has, or one `register` failed for
+    /// (taken by the OS or another app). A failed one is tried again on the
+    /// next update.
+    pub fn update(
+        &mut self,
+        wanted: &BTreeMap<ShortcutAction, String>,
+        mut register: impl FnMut(&Accelerator) -> Result<(), String>,
+        mut unregister: impl FnMut(&Accelerator),
+    ) -> Vec<ShortcutWarning> {
+        let mut warnings = Vec::new();
+        let warn = |action: &ShortcutAction, accelerator: &str, kind, message: String| ShortcutWarning {
+            action: action.clone(),
+            accelerator: accelerator.to_string(),
+            kind,
+            message,
+        };
+
+        let mut desired: BTreeMap<Accelerator, ShortcutAction> = BTreeMap::new();
+        for (action, text) in wanted {
+            let accelerator = match Accelerator::parse(text) {
+                Ok(accelerator) => accelerator,
+                Err(e) => {
+                    warnings.push(warn(action, text, ShortcutWarningKind::Invalid, e.to_string()));
+                    continue;
+                }
+            };
+            if let Some(owner) = desired.get(&accelerator) {
+                let message = format!("{} is already the shortcut of {}", accelerator, owner);
+                warnings.push(warn(action, text, ShortcutWarningKind::Duplicate, message));
+                continue;
+            }
+            desired.insert(accelerator, action.clone());
+        }
+
+        let stale: Vec<Accelerator> =
+            self.registered.keys().filter(|a| !desired.contains_key(*a)).cloned().collect();
+        for accelerator in stale {
+            unregister(&accelerator);
+            self.registered.remove(&accelerator);
+        }
+
+        for (accelerator, action) in desired {
+            if let Some(current) = self.registered.get_mut(&accelerator) {
+                *current = action;
+                continue;
+            }
+            match register(&accelerator) {
+                Ok(()) => {
+                    self.registered.insert(accelerator, action);
+                }
+                Err(e) => {
+                    let text = &wanted[&action];
+                    let message = format!("{} is not available: {}", accelerator, e);
+                    warnings.push(warn(&action, text, ShortcutWarningKind::Unavailable, message));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// The action of the registered shortcut `matches` picks
+    pub fn find(&self, matches: impl Fn(&Accelerator) -> bool) -> Option<ShortcutAction> {
+        self.registered.iter().find(|(a, _)| matches(a)).map(|(_, action)| action.clone())
+    }
+
+    pub fn registered(&self) -> impl Iterator<Item = (&Accelerator, &ShortcutAction)> {
+        self.registered.iter()
+    }
+}
+
+/// What a fired shortcut comes down to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutDispatch {
+    OpenPalette,
+    ToggleWindow,
+    StopAll,
+    /// Start every service of this project
+    StartProject { project_id: String, name: String },
+}
+
+/// Resolve `action`; a workspace is the project of that name (ignoring case)
+pub fn dispatch(action: &ShortcutAction, projects: &[Project]) -> Result<ShortcutDispatch, String> {
+    Ok(match action {
+        ShortcutAction::OpenPalette => ShortcutDispatch::OpenPalette,
+        ShortcutAction::ToggleWindow => ShortcutDispatch::ToggleWindow,
+        ShortcutAction::StopAll => ShortcutDispatch::StopAll,
+        ShortcutAction::StartWorkspace(name) => {
+            let project = projects
+                .iter()
+                .find(|p| p.name.trim().eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| format!("No project named \"{}\"", name))?;
+            ShortcutDispatch::StartProject { project_id: project.id.clone(), name: project.name.clone() }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StopAllSummary;
+    use std::cell::RefCell;
+
+    #[test]
+    fn accelerators_are_checked_and_spelled_canonically() {
+        for (text, canonical) in [
+            ("CmdOrCtrl+Shift+S", "CmdOrCtrl+Shift+S"),
+            ("shift + control + alt + k", "Ctrl+Alt+Shift+K"),
+            ("Option+Cmd+esc", "Alt+Super+Escape"),
+            ("Ctrl+ArrowUp", "Ctrl+Up"),
+            ("Alt+/", "Alt+/"),
+            ("F9", "F9"),
+            ("Shift+F12", "Shift+F12"),
+        ] {
+            assert_eq!(Accelerator::parse(text).map(|a| a.to_string()), Ok(canonical.to_string()), "{}", text);
+        }
+
+        use AcceleratorError::*;
+        for (text, error) in [
+            ("  ", Empty),
+            ("Ctrl++A", EmptyPart("Ctrl++A".into())),
+            ("Ctrl+", EmptyPart("Ctrl+".into())),
+            ("Ctrl+Ctrl+A", DuplicateModifier(Modifier::Ctrl)),
+            ("Control+Ctrl+A", DuplicateModifier(Modifier::Ctrl)),
+            ("Ctrl+Shift+Shift", DuplicateModifier(Modifier::Shift)),
+            ("Ctrl+Alt", MissingKey),
+            ("A+B", NotAModifier("A".into())),
+            ("Hyper+A", NotAModifier("Hyper".into())),
+            ("Ctrl+Banana", UnknownKey("Banana".into())),
+            ("Ctrl+F25", UnknownKey("F25".into())),
+            ("Ctrl+é", UnknownKey("é".into())),
+            ("A", NeedsModifier("A".into())),
+            ("Shift+Space", NeedsModifier("Shift+Space".into())),
+        ] {
+            assert_eq!(Accelerator::parse(text), Err(error), "{}", text);
+        }
+    }
+
+    #[test]
+    fn updates_register_the_difference_and_report_what_failed() {
+        let stop = ShortcutAction::StopAll;
+        let toggle = ShortcutAction::ToggleWindow;
+        let work = ShortcutAction::StartWorkspace("Shop".into());
+        let mut registry = ShortcutRegistry::default();
+        let os: RefCell<BTreeSet<String>> = RefCell::default();
+        // Calls made to the OS, in order. "Ctrl+Alt+Delete" is the system's.
+        let update = |registry: &mut ShortcutRegistry, wanted: &[(&ShortcutAction, &str)]| {
+            let wanted: BTreeMap<ShortcutAction, String> =
+                wanted.iter().map(|(a, s)| ((*a).clone(), s.to_string())).collect();
+            let calls = RefCell::new(Vec::new());
+            let warnings = registry.update(
+                &wanted,
+                |a| {
+                    calls.borrow_mut().push(format!("+{}", a));
+                    if a.to_string() == "Ctrl+Alt+Delete" {
+                        return Err("HotKey already registered".into());
+                    }
+                    os.borrow_mut().insert(a.to_string());
+                    Ok(())
+                },
+                |a| {
+                    calls.borrow_mut().push(format!("-{}", a));
+                    os.borrow_mut().remove(&a.to_string());
+                },
+            );
+            (calls.into_inner(), warnings)
+        };
+
+        let (calls, warnings) = update(&mut registry, &[(&stop, "Ctrl+Alt+S"), (&toggle, "Ctrl+Alt+Delete")]);
+        assert_eq!(calls, vec!["+Ctrl+Alt+Delete", "+Ctrl+Alt+S"]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((&warnings[0].action, warnings[0].kind), (&toggle, ShortcutWarningKind::Unavailable));
+        assert!(warnings[0].message.contains("HotKey already registered"));
+
+        // Unchanged ones stay, removed ones go, a re-mapped one doesn't touch the OS
+        let (calls, warnings) = update(
+            &mut registry,
+            &[(&work, "ctrl+alt+s"), (&toggle, "Ctrl+Alt+T"), (&stop, "Ctrl+Ctrl+A")],
+        );
+        assert_eq!(calls, vec!["+Ctrl+Alt+T"]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((&warnings[0].action, warnings[0].kind), (&stop, ShortcutWarningKind::Invalid));
+        assert_eq!(warnings[0].accelerator, "Ctrl+Ctrl+A");
+        let accelerator = Accelerator::parse("Ctrl+Alt+S").unwrap();
+        assert_eq!(registry.find(|a| *a == accelerator), Some(work.clone()));
+
+        // Two actions on one accelerator: the first in key order keeps it
+        let (calls, warnings) = update(&mut registry, &[(&stop, "Ctrl+Alt+T"), (&toggle, "Ctrl+Alt+T")]);
+        assert_eq!(calls, vec!["-Ctrl+Alt+S"]);
+        assert_eq!((&warnings[0].action, warnings[0].kind), (&toggle, ShortcutWarningKind::Duplicate));
+        let registered: Vec<String> = registry.registered().map(|(a, action)| format!("{} {}", a, action)).collect();
+        assert_eq!(registered, vec!["Ctrl+Alt+T stopAll"]);
+
+        let (calls, warnings) = update(&mut registry, &[]);
+        assert_eq!((calls, warnings), (vec!["-Ctrl+Alt+T".to_string()], vec![]));
+        assert!(os.borrow().is_empty());
+    }
+
+    #[test]
+    fn actions_resolve_against_settings_and_projects() {
+        let mut settings = AppSettings::default();
+        settings.global_shortcuts.insert(ShortcutAction::StopAll, "Ctrl+Alt+S".into());
+        settings.global_shortcuts.insert(ShortcutAction::ToggleWindow, " ".into());
+        settings.global_shortcuts.insert(ShortcutAction::OpenPalette, "Ctrl+Alt+P".into());
+        let wanted = wanted_shortcuts(&settings, "CmdOrCtrl+Shift+Space");
+        assert_eq!(wanted.len(), 2);
+        assert_eq!(wanted[&ShortcutAction::OpenPalette], "CmdOrCtrl+Shift+Space");
+        settings.global_hotkey = Some(String::new());
+        assert!(!wanted_shortcuts(&settings, "CmdOrCtrl+Shift+Space").contains_key(&ShortcutAction::OpenPalette));
+
+        let json = serde_json::to_string(&settings.global_shortcuts).unwrap();
+        assert_eq!(json, r#"{"openPalette":"Ctrl+Alt+P","stopAll":"Ctrl+Alt+S","toggleWindow":" "}"#);
+        let parsed: BTreeMap<ShortcutAction, String> =
+            serde_json::from_str(r#"{"startWorkspace:Shop API":"Ctrl+Alt+1"}"#).unwrap();
+        let work = parsed.keys().next().unwrap().clone();
+        assert_eq!(work, ShortcutAction::StartWorkspace("Shop API".into()));
+        assert!(serde_json::from_str::<BTreeMap<ShortcutAction, String>>(r#"{"startWorkspace:":"F1"}"#).is_err());
+
+        let project = Project::new("shop api".into(), "/tmp/shop".into());
+        let projects = vec![project.clone()];
+        assert_eq!(
+            dispatch(&work, &projects),
+            Ok(ShortcutDispatch::StartProject { project_id: project.id.clone(), name: "shop api".into() })
+        );
+        assert_eq!(dispatch(&ShortcutAction::StopAll, &projects), Ok(ShortcutDispatch::StopAll));
+        assert!(dispatch(&ShortcutAction::StartWorkspace("Blog".into()), &projects).is_err());
+
+        // The StopAll notification
+        let mut summary = StopAllSummary::default();
+        assert_eq!(summary.message(), "Nothing was running");
+        summary.services = ["api", "web", "db", "queue"].map(String::from).to_vec();
+        summary.scripts = vec!["backup".into()];
+        assert_eq!(summary.message(), "Stopped 4 services (api, web, db, …) and 1 script (backup)");
+        summary.services.clear();
+        summary.failed = vec!["worker: Access is denied".into()];
+        assert_eq!(summary.message(), "Stopped 1 script (backup); failed to stop worker: Access is denied");
+    }
+}
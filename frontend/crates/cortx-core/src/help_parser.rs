@@ -1,6 +1,112 @@
+use std::io::Read;
+use std::ops::Range;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
 use regex::Regex;
 
-use crate::models::{ScriptParamType, ScriptParameter};
+use crate::models::{DetectedHelp, GlobalScript, ScriptParamType, ScriptParameter};
+use crate::storage::Storage;
+
+/// Raw help text longer than this is cut (at a line boundary) before it's
+/// stored on a script.
+pub const MAX_RAW_HELP_LEN: usize = 16 * 1024;
+
+/// How often a running `--help` is checked for having exited or been cancelled
+const HELP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// A `--help` still running after this is killed: it's most likely waiting
+/// for input rather than printing help
+pub const HELP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Line patterns shared by [`parse_help_output`] and [`locate_param_help`].
+struct HelpPatterns {
+    option_with_short: Regex,
+    long_only: Regex,
+    continuation: Regex,
+    section_header: Regex,
+    positional: Regex,
+    go_flag: Regex,
+    go_continuation: Regex,
+}
+
+impl HelpPatterns {
+    fn new() -> Self {
+        // Option lines match "  -s, --long [VALUE]   description" or "  --long VALUE" etc.
+        // The description part is optional (may be on the next line in argparse format)
+        // Value hints support multi-value patterns: PLAYER [PLAYER ...], MIN MAX, etc.
+        // clap shows repeatable values as `<VALUE>...` and counted flags as `--verbose...`
+        let value_hint = r"[A-Z][A-Z0-9_.*-]*(?:[ \t]+(?:\.\.\.|[A-Z][A-Z0-9_.*-]*(?:[ \t]+\.\.\.)?|\[[^\]\n]+\]))*|<[^>\n]+>(?:\.\.\.)?|\[[^\]\n]+\]";
+        Self {
+            option_with_short: Regex::new(&format!(
+                r"^[ \t]{{1,8}}(-[a-zA-Z0-9])(?:[ \t]*,?[ \t]*(--[\w][\w-]*)(?:\.\.\.)?)?(?:[ \t]+(?:=[ \t]*)?({value_hint}))?(?:[ \t]{{2,}}(.+))?$"
+            ))
+            .unwrap(),
+            long_only: Regex::new(&format!(
+                r"^[ \t]{{2,}}(--[\w][\w-]*)(?:\.\.\.)?(?:[ \t]+(?:=[ \t]*)?({value_hint}))?(?:[ \t]{{2,}}(.+))?$"
+            ))
+            .unwrap(),
+            // Continuation line: starts with lots of whitespace, no dashes
+            continuation: Regex::new(r"^[ \t]{10,}(\S.*)$").unwrap(),
+            // Section header: "positional arguments:", "options:", "optional arguments:", etc.
+            section_header: Regex::new(r"^[a-zA-Z][\w\s]*:\s*$").unwrap(),
+            // Positional argument line: "  argname             Description text"
+            // Must be a simple word (no dashes) followed by enough spacing and a description.
+            // clap brackets it: `<INPUT>` (required), `[OUTPUT]` (optional), `<FILES>...`
+            positional: Regex::new(
+                r"^[ \t]{2,8}([a-zA-Z][a-zA-Z0-9_-]*|<[a-zA-Z][\w-]*>|\[[a-zA-Z][\w-]*\])(\.\.\.)?(?:[ \t]{2,}(.+))?$",
+            )
+            .unwrap(),
+            // Go's `flag` package: "  -count int" with the description on the
+            // next line, and one-letter bools as "  -v\tdescription"
+            go_flag: Regex::new(r"^ {1,4}(-[a-zA-Z0-9][\w.-]*)(?: ([a-z][a-z0-9]*))?(?:\t(.+))?$").unwrap(),
+            // Go's description lines: "    \tdescription"
+            go_continuation: Regex::new(r"^ {2,4}\t[ \t]*(\S.*)$").unwrap(),
+        }
+    }
+}
+
+/// Type of a Go flag from the type word after its name (`-count int`). Go
+/// prints `string`, `int`, `float`, `duration`, ... for the built-in flag
+/// types, nothing for bools, and otherwise a name taken from the usage text.
+fn go_flag_type(type_word: Option<&str>) -> ScriptParamType {
+    match type_word {
+        None | Some("bool") => ScriptParamType::Bool,
+        Some("int" | "int64" | "uint" | "uint64" | "float" | "float64" | "duration") => ScriptParamType::Number,
+        Some("string") => ScriptParamType::String,
+        Some(other) => deduce_type(Some(other), None, false).0,
+    }
+}
+
+/// Default of a Go flag: its description ends with `(default 3)` or
+/// `(default ":8080")`
+fn go_default(description: Option<&str>) -> Option<String> {
+    let default_re = Regex::new(r#"\(default (?:"((?:[^"\\]|\\.)*)"|([^)]*))\)\s*$"#).unwrap();
+    let caps = default_re.captures(description?)?;
+    caps.get(1).or(caps.get(2)).map(|m| m.as_str().to_string())
+}
+
+/// Whether a section header starts the positionals: argparse's
+/// `positional arguments:` or clap's `Arguments:`
+fn is_positional_header(line: &str) -> bool {
+    let header = line.trim().to_lowercase();
+    header.starts_with("positional argument") || header == "arguments:"
+}
+
+/// Name of a positional and whether it's required. clap's `<INPUT>` /
+/// `[OUTPUT]` placeholders are the field name upper-cased; `[...]` is optional.
+fn positional_name(token: &str) -> (String, bool) {
+    if let Some(name) = token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+        (name.to_lowercase(), true)
+    } else if let Some(name) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        (name.to_lowercase(), false)
+    } else {
+        (token.to_string(), true)
+    }
+}
 
 /// Parse the output of `<command> --help` and extract script parameters.
 ///
@@ -8,8 +114,10 @@ use crate::models::{ScriptParamType, ScriptParameter};
 /// - GNU/POSIX style: `-f, --flag  Description`
 /// - Python argparse style (description on next line)
 /// - Long-only options: `--option VALUE`
-/// - Value placeholders: `VALUE`, `<value>`, `[VALUE]`, `[VALUE ...]`
-/// - Positional arguments (argparse `positional arguments:` section)
+/// - Go `flag` style: `-name type` with the description on the next line
+/// - Value placeholders: `VALUE`, `<value>`, `[VALUE]`, `[VALUE ...]`, `<VALUE>...`
+/// - Positional arguments (argparse `positional arguments:` section, clap
+///   `Arguments:` section with `<NAME>` / `[NAME]` placeholders)
 pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
     let mut params = Vec::new();
     let mut seen_names = std::collections::HashSet::new();
@@ -17,30 +125,15 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
     let lines: Vec<&str> = help_text.lines().collect();
     let line_count = lines.len();
 
-    // Regex for option lines:
-    // Matches: "  -s, --long [VALUE]   description" or "  --long VALUE" etc.
-    // The description part is optional (may be on the next line in argparse format)
-    // Value hints support multi-value patterns: PLAYER [PLAYER ...], MIN MAX, etc.
-    let value_hint = r"[A-Z][A-Z0-9_.*-]*(?:[ \t]+(?:\.\.\.|[A-Z][A-Z0-9_.*-]*(?:[ \t]+\.\.\.)?|\[[^\]\n]+\]))*|<[^>\n]+>|\[[^\]\n]+\]";
-    let option_with_short_re = Regex::new(
-        &format!(r"^[ \t]{{1,8}}(-[a-zA-Z0-9])(?:[ \t]*,?[ \t]*(--[\w][\w-]*))?(?:[ \t]+(?:=[ \t]*)?({value_hint}))?(?:[ \t]{{2,}}(.+))?$")
-    ).unwrap();
-
-    let long_only_re = Regex::new(
-        &format!(r"^[ \t]{{2,}}(--[\w][\w-]*)(?:[ \t]+(?:=[ \t]*)?({value_hint}))?(?:[ \t]{{2,}}(.+))?$")
-    ).unwrap();
-
-    // Continuation line: starts with lots of whitespace, no dashes
-    let continuation_re = Regex::new(r"^[ \t]{10,}(\S.*)$").unwrap();
-
-    // Section header: "positional arguments:", "options:", "optional arguments:", etc.
-    let section_header_re = Regex::new(r"^[a-zA-Z][\w\s]*:\s*$").unwrap();
-
-    // Positional argument line: "  argname             Description text"
-    // Must be a simple word (no dashes) followed by enough spacing and a description
-    let positional_re = Regex::new(
-        r"^[ \t]{2,8}([a-zA-Z][a-zA-Z0-9_-]*)(?:[ \t]{2,}(.+))?$"
-    ).unwrap();
+    let HelpPatterns {
+        option_with_short: option_with_short_re,
+        long_only: long_only_re,
+        continuation: continuation_re,
+        section_header: section_header_re,
+        positional: positional_re,
+        go_flag: go_flag_re,
+        go_continuation: go_continuation_re,
+    } = HelpPatterns::new();
 
     let mut in_positional_section = false;
     let mut i = 0;
@@ -49,8 +142,7 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
 
         // Detect section headers
         if section_header_re.is_match(line) {
-            let header_lower = line.trim().to_lowercase();
-            in_positional_section = header_lower.starts_with("positional argument");
+            in_positional_section = is_positional_header(line);
             i += 1;
             continue;
         }
@@ -107,6 +199,7 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
                     required: false,
                     enum_values: Vec::new(),
                     nargs,
+                    mutex_group: None,
                 });
             }
 
@@ -156,6 +249,52 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
                     required: false,
                     enum_values: Vec::new(),
                     nargs,
+                    mutex_group: None,
+                });
+            }
+
+            i += 1;
+            continue;
+        }
+
+        // Try Go flag pattern (single dash, lowercase type word)
+        if let Some(caps) = go_flag_re.captures(line) {
+            in_positional_section = false;
+            let flag = caps.get(1).unwrap().as_str().to_string();
+            let type_word = caps.get(2).map(|m| m.as_str());
+            let mut description = caps.get(3).map(|m| m.as_str().trim().to_string());
+
+            // Go indents every description line with a tab
+            while i + 1 < line_count {
+                let Some(cont_caps) = go_continuation_re.captures(lines[i + 1]) else {
+                    break;
+                };
+                let more = cont_caps.get(1).unwrap().as_str().trim();
+                description = Some(match description {
+                    Some(desc) => format!("{} {}", desc, more),
+                    None => more.to_string(),
+                });
+                i += 1;
+            }
+
+            // One letter is a short flag; Go accepts `-name` and `--name` alike
+            let (short, long) = if flag.len() == 2 { (Some(flag), None) } else { (None, Some(flag)) };
+            let name = derive_name(long.as_deref(), short.as_deref());
+            if !seen_names.contains(&name) {
+                seen_names.insert(name.clone());
+                let default_value = go_default(description.as_deref()).or_else(|| extract_default(description.as_deref()));
+
+                params.push(ScriptParameter {
+                    name,
+                    param_type: go_flag_type(type_word),
+                    short_flag: short,
+                    long_flag: long,
+                    description,
+                    default_value,
+                    required: false,
+                    enum_values: Vec::new(),
+                    nargs: None,
+                    mutex_group: None,
                 });
             }
 
@@ -166,8 +305,10 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
         // Try positional argument pattern (only in positional section)
         if in_positional_section {
             if let Some(caps) = positional_re.captures(line) {
-                let name = caps.get(1).unwrap().as_str().to_string();
-                let mut description = caps.get(2).map(|m| m.as_str().trim().to_string());
+                let token = caps.get(1).unwrap().as_str();
+                let (name, required) = positional_name(token);
+                let repeated = caps.get(2).is_some();
+                let mut description = caps.get(3).map(|m| m.as_str().trim().to_string());
 
                 // Check next line for continuation description
                 if description.is_none() && i + 1 < line_count {
@@ -189,8 +330,12 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
 
                 if !seen_names.contains(&name) {
                     seen_names.insert(name.clone());
-                    // Deduce type from description (no value hint for positional)
-                    let (param_type, _) = deduce_type(None, description.as_deref(), true);
+                    // Deduce type from a clap placeholder (`<FILE>`), else from the description
+                    let placeholder = token.starts_with(['<', '[']);
+                    let (param_type, _) = match deduce_type(Some(token), None, true) {
+                        typed @ (ScriptParamType::Number | ScriptParamType::Path, _) if placeholder => typed,
+                        _ => deduce_type(None, description.as_deref(), true),
+                    };
                     // Positional args that are typed as Bool from description don't make sense;
                     // default to String for positional args
                     let param_type = if param_type == ScriptParamType::Bool {
@@ -207,9 +352,10 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
                         long_flag: None,
                         description,
                         default_value,
-                        required: true, // positional args are required
+                        required, // positional args are required unless clap brackets them
                         enum_values: Vec::new(),
-                        nargs: None,
+                        nargs: repeated.then(|| if required { "+" } else { "*" }.to_string()),
+                        mutex_group: None,
                     });
                 }
 
@@ -221,66 +367,405 @@ pub fn parse_help_output(help_text: &str) -> Vec<ScriptParameter> {
         i += 1;
     }
 
+    assign_mutex_groups(help_text, &mut params);
+    mark_required_options(help_text, &mut params);
     params
 }
 
+/// The `usage:` synopsis, its wrapped lines joined
+fn usage_synopsis(help_text: &str) -> String {
+    help_text
+        .lines()
+        .skip_while(|line| !line.trim_start().to_lowercase().starts_with("usage:"))
+        .take_while(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Mark options as required when the usage synopsis lists them outside any
+/// `[...]` / `(...)` (argparse's `--input INPUT` vs `[--verbose]`), or when
+/// their description says `(required)`.
+fn mark_required_options(help_text: &str, params: &mut [ScriptParameter]) {
+    let usage = usage_synopsis(help_text);
+    let mut unbracketed = String::new();
+    let mut depth = 0usize;
+    for c in usage.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => unbracketed.push(c),
+            _ => {}
+        }
+    }
+    let required_flags: Vec<&str> = unbracketed
+        .split(|c: char| c.is_whitespace() || c == '|')
+        .filter(|token| token.starts_with('-') && token.len() > 1)
+        .map(|token| token.split('=').next().unwrap_or(token))
+        .collect();
+
+    for param in params.iter_mut().filter(|p| p.long_flag.is_some() || p.short_flag.is_some()) {
+        let in_usage = required_flags
+            .iter()
+            .any(|&flag| param.long_flag.as_deref() == Some(flag) || param.short_flag.as_deref() == Some(flag));
+        let described = param
+            .description
+            .as_deref()
+            .is_some_and(|d| d.to_lowercase().contains("(required)"));
+        if in_usage || described {
+            param.required = true;
+        }
+    }
+}
+
+/// Put options that the usage line lists as alternatives (`[--json | --yaml]`,
+/// or `(-a | -b)` for a required group) in a shared mutex group, named after
+/// its members.
+fn assign_mutex_groups(help_text: &str, params: &mut [ScriptParameter]) {
+    let usage = usage_synopsis(help_text);
+    let group_re = Regex::new(r"[\[(]\s*(-[^\[\]()|]*(?:\|[^\[\]()|]*)+)[\])]").unwrap();
+
+    for caps in group_re.captures_iter(&usage) {
+        let members: Vec<usize> = caps[1]
+            .split('|')
+            .filter_map(|alt| alt.split_whitespace().next())
+            .filter(|flag| flag.starts_with('-'))
+            .filter_map(|flag| {
+                params.iter().position(|p| {
+                    p.long_flag.as_deref() == Some(flag) || p.short_flag.as_deref() == Some(flag)
+                })
+            })
+            .collect();
+        if members.len() < 2 {
+            continue;
+        }
+        let group = members.iter().map(|&i| params[i].name.as_str()).collect::<Vec<_>>().join("/");
+        for &i in &members {
+            params[i].mutex_group = Some(group.clone());
+        }
+    }
+}
+
+/// Find the lines (as a `start..end` line range) documenting `param` in
+/// `help_text`: its option / positional line plus any continuation lines.
+/// Options are matched by flag, positionals by name.
+pub fn locate_param_help(help_text: &str, param: &ScriptParameter) -> Option<Range<usize>> {
+    let patterns = HelpPatterns::new();
+    let lines: Vec<&str> = help_text.lines().collect();
+
+    let mut in_positional_section = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if patterns.section_header.is_match(line) {
+            in_positional_section = is_positional_header(line);
+            i += 1;
+            continue;
+        }
+
+        // Some(documents `param`?) for lines that start an entry
+        let mut go_entry = false;
+        let entry = if let Some(caps) = patterns.option_with_short.captures(line) {
+            in_positional_section = false;
+            Some(flags_match(param, caps.get(1).map(|m| m.as_str()), caps.get(2).map(|m| m.as_str())))
+        } else if let Some(caps) = patterns.long_only.captures(line) {
+            in_positional_section = false;
+            Some(flags_match(param, None, caps.get(1).map(|m| m.as_str())))
+        } else if let Some(caps) = patterns.go_flag.captures(line) {
+            in_positional_section = false;
+            go_entry = true;
+            let flag = caps.get(1).map(|m| m.as_str());
+            Some(flags_match(param, flag, flag))
+        } else if in_positional_section {
+            patterns.positional.captures(line).map(|caps| {
+                param.short_flag.is_none() && param.long_flag.is_none() && positional_name(&caps[1]).0 == param.name
+            })
+        } else {
+            None
+        };
+        let Some(documents_param) = entry else {
+            i += 1;
+            continue;
+        };
+
+        // The entry extends over its continuation lines
+        let mut end = i + 1;
+        while end < lines.len()
+            && (patterns.continuation.is_match(lines[end]) || (go_entry && patterns.go_continuation.is_match(lines[end])))
+        {
+            end += 1;
+        }
+        if documents_param {
+            return Some(i..end);
+        }
+        i = end;
+    }
+    None
+}
+
+/// Whether an option line with these flags documents `param`. Parameters
+/// without flags are positionals and never match an option line.
+fn flags_match(param: &ScriptParameter, short: Option<&str>, long: Option<&str>) -> bool {
+    (long.is_some() && param.long_flag.as_deref() == long)
+        || (short.is_some() && param.short_flag.as_deref() == short)
+}
+
 /// Run `<command> --help` and parse the output
 pub fn detect_parameters(command: &str) -> Result<Vec<ScriptParameter>, String> {
-    // Try --help first, then -h
-    let output = try_help_flag(command, "--help")
-        .or_else(|_| try_help_flag(command, "-h"))
-        .map_err(|e| format!("Failed to run help command: {}", e))?;
+    detect_help(command).map(|detected| detected.parameters)
+}
 
-    Ok(parse_help_output(&output))
+/// Like [`detect_parameters`], but keeps the raw help text as well
+/// (capped to [`MAX_RAW_HELP_LEN`]).
+pub fn detect_help(command: &str) -> Result<DetectedHelp, String> {
+    match HelpDetection::new().run(command)? {
+        HelpDetectionOutcome::Completed(detected) => Ok(detected),
+        HelpDetectionOutcome::Cancelled => Err("Parameter detection was cancelled".to_string()),
+    }
 }
 
-fn try_help_flag(command: &str, flag: &str) -> Result<String, String> {
-    let parts: Vec<&str> = command.split_whitespace().collect();
-    if parts.is_empty() {
-        return Err("Empty command".to_string());
+/// How a parameter detection ended
+#[derive(Debug, Clone)]
+pub enum HelpDetectionOutcome {
+    Completed(DetectedHelp),
+    /// Stopped by [`HelpDetection::cancel`]; the `--help` process was killed
+    Cancelled,
+}
+
+/// One parameter detection, cancellable from another thread. Cancelling
+/// kills the `--help` process being waited on rather than leaving it behind.
+#[derive(Debug)]
+pub struct HelpDetection {
+    cancelled: AtomicBool,
+    timeout: Duration,
+}
+
+impl Default for HelpDetection {
+    fn default() -> Self {
+        Self { cancelled: AtomicBool::new(false), timeout: HELP_TIMEOUT }
+    }
+}
+
+/// What one `<command> <flag>` run printed
+enum HelpOutput {
+    Exited(String),
+    /// Killed after the timeout; what it printed until then
+    TimedOut(String),
+    Cancelled,
+}
+
+impl HelpDetection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Kill the `--help` process after `timeout` instead of [`HELP_TIMEOUT`]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
     }
 
-    let program = parts[0];
-    let mut args: Vec<&str> = parts[1..].to_vec();
-    args.push(flag);
+    /// Stop the detection; `run` kills its process and returns `Cancelled`.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
 
-    let mut cmd = std::process::Command::new(program);
-    cmd.args(&args);
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
 
-    // Force UTF-8 and avoid console allocation on Windows
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-        cmd.env("PYTHONUTF8", "1");
-        cmd.env("PYTHONIOENCODING", "utf-8");
+    /// Run `<command> --help` (then `-h` if that fails or prints nothing)
+    /// and parse the output. A command that prints nothing, or hangs and is
+    /// killed after the timeout, has whatever it printed parsed, which is
+    /// usually no parameters at all; only a command that can't be started
+    /// is an error.
+    pub fn run(&self, command: &str) -> Result<HelpDetectionOutcome, String> {
+        let output = match self.try_help_flag(command, "--help") {
+            Ok(HelpOutput::Exited(output)) if output.trim().is_empty() => self.try_help_flag(command, "-h"),
+            Err(_) => self.try_help_flag(command, "-h"),
+            done => done,
+        }
+        .map_err(|e| format!("Failed to run help command: {}", e))?;
+
+        let output = match output {
+            HelpOutput::Exited(output) => output,
+            HelpOutput::TimedOut(output) => {
+                log::warn!("`{} --help` was still running after {:?}, killed it", command, self.timeout);
+                output
+            }
+            HelpOutput::Cancelled => return Ok(HelpDetectionOutcome::Cancelled),
+        };
+        Ok(HelpDetectionOutcome::Completed(DetectedHelp {
+            parameters: parse_help_output(&output),
+            raw_help: cap_raw_help(&output),
+        }))
     }
 
-    let result = cmd.output().map_err(|e| e.to_string())?;
+    /// The help output of `<command> <flag>`
+    fn try_help_flag(&self, command: &str, flag: &str) -> Result<HelpOutput, String> {
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        if parts.is_empty() {
+            return Err("Empty command".to_string());
+        }
+
+        let program = parts[0];
+        let mut args: Vec<&str> = parts[1..].to_vec();
+        args.push(flag);
+
+        let mut cmd = std::process::Command::new(program);
+        cmd.args(&args);
+        cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        // Force UTF-8 and avoid console allocation on Windows
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+            cmd.env("PYTHONUTF8", "1");
+            cmd.env("PYTHONIOENCODING", "utf-8");
+        }
 
-    // Many programs output help to stderr or stdout regardless of exit code
-    let stdout = String::from_utf8_lossy(&result.stdout);
-    let stderr = String::from_utf8_lossy(&result.stderr);
+        let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+        // Drained while waiting: a long help text would otherwise fill the
+        // pipe and block the process before it exits
+        let stdout = Drain::start(child.stdout.take());
+        let stderr = Drain::start(child.stderr.take());
+        let started = Instant::now();
+        loop {
+            if self.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(HelpOutput::Cancelled);
+            }
+            if started.elapsed() >= self.timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                // Not joined: a process it started may still hold the pipes
+                return Ok(HelpOutput::TimedOut(pick_help_output(&stdout.so_far(), &stderr.so_far())));
+            }
+            match child.try_wait().map_err(|e| e.to_string())? {
+                Some(_) => break,
+                None => std::thread::sleep(HELP_POLL_INTERVAL),
+            }
+        }
+        Ok(HelpOutput::Exited(pick_help_output(&stdout.finish(), &stderr.finish())))
+    }
+}
 
-    let combined = if stdout.len() > stderr.len() {
-        stdout.to_string()
-    } else if !stderr.is_empty() {
-        stderr.to_string()
+/// Many programs output help to stderr or stdout regardless of exit code;
+/// the longer of the two is taken
+fn pick_help_output(stdout: &[u8], stderr: &[u8]) -> String {
+    let stdout = String::from_utf8_lossy(stdout);
+    let stderr = String::from_utf8_lossy(stderr);
+    if stdout.len() > stderr.len() {
+        stdout.into_owned()
     } else {
-        stdout.to_string()
-    };
+        stderr.into_owned()
+    }
+}
 
-    if combined.trim().is_empty() {
-        return Err("No help output received".to_string());
+/// A pipe read to its end on its own thread
+struct Drain {
+    buf: Arc<Mutex<Vec<u8>>>,
+    reader: JoinHandle<()>,
+}
+
+impl Drain {
+    fn start(pipe: Option<impl Read + Send + 'static>) -> Self {
+        let buf = Arc::new(Mutex::new(Vec::new()));
+        let reader = {
+            let buf = buf.clone();
+            std::thread::spawn(move || {
+                let Some(mut pipe) = pipe else { return };
+                let mut chunk = [0u8; 4096];
+                loop {
+                    match pipe.read(&mut chunk) {
+                        Ok(0) => break,
+                        Ok(n) => buf.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&chunk[..n]),
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(_) => break,
+                    }
+                }
+            })
+        };
+        Self { buf, reader }
     }
 
-    Ok(combined)
+    /// Everything the pipe yielded, once it's closed
+    fn finish(self) -> Vec<u8> {
+        let _ = self.reader.join();
+        std::mem::take(&mut *self.buf.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// What the pipe yielded until now
+    fn so_far(&self) -> Vec<u8> {
+        self.buf.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Cut `text` to at most [`MAX_RAW_HELP_LEN`] bytes, at the last full line.
+pub fn cap_raw_help(text: &str) -> String {
+    if text.len() <= MAX_RAW_HELP_LEN {
+        return text.to_string();
+    }
+    let mut cut = MAX_RAW_HELP_LEN;
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let cut = text[..cut].rfind('\n').map(|i| i + 1).unwrap_or(cut);
+    format!("{}[… truncated]\n", &text[..cut])
+}
+
+/// Add newly detected parameters to `existing`, keeping every existing one
+/// (and the user's edits to it) as is — the same merge as "Import from
+/// --help" → "Add new" in the desktop app. A detected parameter with the
+/// name of an existing one is dropped, whatever its description, default
+/// or flags; new ones are appended in detection order.
+pub fn merge_detected(existing: &[ScriptParameter], detected: Vec<ScriptParameter>) -> Vec<ScriptParameter> {
+    let mut merged = existing.to_vec();
+    for param in detected {
+        if !merged.iter().any(|p| p.name == param.name) {
+            merged.push(param);
+        }
+    }
+    merged
+}
+
+/// Re-run the script's `--help`, store the new raw text and add any new
+/// parameters. Returns the updated script.
+pub fn refresh_help(storage: &Storage, script_id: &str) -> Result<GlobalScript, String> {
+    let script = storage
+        .get_global_script(script_id)
+        .ok_or_else(|| format!("Script not found: {}", script_id))?;
+    let command = match script.script_path {
+        Some(ref path) => script.command.replace("{{SCRIPT_FILE}}", path),
+        None => script.command.clone(),
+    };
+    let detected = detect_help(&command)?;
+    merge_into_script(storage, script_id, detected)
+}
+
+/// Add `detected`'s new parameters to the stored script ([`merge_detected`])
+/// and keep its raw help text. A detection that found no help text leaves
+/// the stored one as it was.
+pub fn merge_into_script(storage: &Storage, script_id: &str, detected: DetectedHelp) -> Result<GlobalScript, String> {
+    storage
+        .update_global_script(script_id, |script| {
+            script.parameters = merge_detected(&script.parameters, detected.parameters);
+            if !detected.raw_help.trim().is_empty() {
+                script.raw_help = Some(detected.raw_help);
+            }
+        })
+        .map_err(|e| e.to_string())
 }
 
 /// Detect nargs from a value hint string.
 /// Returns Some("+") for "PLAYER [PLAYER ...]", Some("2") for "MIN MAX", etc.
 fn detect_nargs(value_hint: Option<&str>) -> Option<String> {
     let hint = value_hint?;
+    // clap's repeatable value  e.g. "<FILE>..."
+    if hint.starts_with('<') && hint.ends_with("...") {
+        return Some("+".to_string());
+    }
     // Contains [...] with "..." inside → nargs='+'  e.g. "PLAYER [PLAYER ...]"
     if hint.contains("[") && hint.contains("...") {
         return Some("+".to_string());
@@ -332,8 +817,9 @@ fn deduce_type(value_hint: Option<&str>, description: Option<&str>, is_positiona
     }
 
     let hint = value_hint.unwrap().to_uppercase();
-    // Strip brackets and ... suffix
+    // Strip brackets and ... suffix (inside or outside them)
     let hint_clean = hint
+        .trim_end_matches("...")
         .trim_matches(|c| c == '<' || c == '>' || c == '[' || c == ']')
         .trim_end_matches("...")
         .trim();
@@ -367,9 +853,7 @@ fn extract_default(description: Option<&str>) -> Option<String> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_gnu_style() {
-        let help = r#"
+    const GNU_HELP: &str = r#"
 Usage: myapp [OPTIONS]
 
 Options:
@@ -380,6 +864,81 @@ Options:
   -h, --help              Show help
   -V, --version           Show version
 "#;
+
+    const ARGPARSE_HELP: &str = r#"usage: dir_tree.py [-h] [--exclude [EXCLUDE ...]] [--files] directory
+
+Print a tree structure of a directory.
+
+positional arguments:
+  directory             The root directory to print the tree from.
+
+options:
+  -h, --help            show this help message and exit
+  --exclude [EXCLUDE ...]
+                        List of folders to exclude.
+  --files               Include files in the tree structure.
+"#;
+
+    const MULTI_VALUE_HELP: &str = r#"usage: imposter_game [-h] [--player-list] [--players PLAYER [PLAYER ...]]
+                     [--speed-range MIN MAX] [--nb-impostor N]
+                     [--special-impostor-odds JSON] [--dry-run]
+
+Imposter Game
+
+options:
+  -h, --help            show this help message and exit
+  --player-list         Show all configured players from user_map.json and exit.
+  --players PLAYER [PLAYER ...]
+                        Players for this session (by name or discord ID). At
+                        least 1 required.
+  --speed-range MIN MAX
+                        Speed range in km/h (default: 0 150).
+  --nb-impostor N       Default number of impostors (default: 1).
+  --special-impostor-odds JSON
+                        Odds for special impostor counts as JSON. Example:
+                        '{"2": 0.1, "all": 0.01}'
+  --special-rank-game PROB
+                        Probability (0-1) of a special rank game.
+  --dry-run             Run the game logic without sending Discord DMs.
+"#;
+
+    // `convert -h` for a clap 4 derive parser
+    const CLAP_HELP: &str = r#"Convert documents between formats
+
+Usage: convert [OPTIONS] <INPUT> [ATTACHMENTS]...
+
+Arguments:
+  <INPUT>           Document to convert
+  [ATTACHMENTS]...  Extra files to bundle
+
+Options:
+  -o, --output <FILE>    Where to write the result
+  -j, --jobs <JOBS>      Worker threads [default: 4]
+      --tag <TAG>...     Tags to add
+  -v, --verbose...       More output (repeatable)
+  -h, --help             Print help
+  -V, --version          Print version
+"#;
+
+    // `go run . -h` for a program using the standard `flag` package
+    const GO_HELP: &str = "Usage of /tmp/go-build2817/b001/exe/server:
+  -addr string
+    \taddress to listen on (default \":8080\")
+  -count int
+    \tnumber of workers (default 4)
+  -debug
+    \tenable debug logging
+  -ratio float
+    \tsampling ratio; applies to
+    \tevery request (default 0.5)
+  -timeout duration
+    \trequest timeout (default 5s)
+  -v\tverbose output
+";
+
+    #[test]
+    fn test_parse_gnu_style() {
+        let help = GNU_HELP;
         let params = parse_help_output(help);
         assert_eq!(params.len(), 6); // verbose, output, count, no-color, help, version
 
@@ -397,19 +956,7 @@ Options:
 
     #[test]
     fn test_parse_python_argparse_style() {
-        let help = r#"usage: dir_tree.py [-h] [--exclude [EXCLUDE ...]] [--files] directory
-
-Print a tree structure of a directory.
-
-positional arguments:
-  directory             The root directory to print the tree from.
-
-options:
-  -h, --help            show this help message and exit
-  --exclude [EXCLUDE ...]
-                        List of folders to exclude.
-  --files               Include files in the tree structure.
-"#;
+        let help = ARGPARSE_HELP;
         let params = parse_help_output(help);
 
         // Should find: directory (positional), help, exclude, files
@@ -467,28 +1014,7 @@ options:
 
     #[test]
     fn test_parse_multi_value_args() {
-        let help = r#"usage: imposter_game [-h] [--player-list] [--players PLAYER [PLAYER ...]]
-                     [--speed-range MIN MAX] [--nb-impostor N]
-                     [--special-impostor-odds JSON] [--dry-run]
-
-Imposter Game
-
-options:
-  -h, --help            show this help message and exit
-  --player-list         Show all configured players from user_map.json and exit.
-  --players PLAYER [PLAYER ...]
-                        Players for this session (by name or discord ID). At
-                        least 1 required.
-  --speed-range MIN MAX
-                        Speed range in km/h (default: 0 150).
-  --nb-impostor N       Default number of impostors (default: 1).
-  --special-impostor-odds JSON
-                        Odds for special impostor counts as JSON. Example:
-                        '{"2": 0.1, "all": 0.01}'
-  --special-rank-game PROB
-                        Probability (0-1) of a special rank game.
-  --dry-run             Run the game logic without sending Discord DMs.
-"#;
+        let help = MULTI_VALUE_HELP;
         let params = parse_help_output(help);
 
         // --players with nargs='+' (PLAYER [PLAYER ...])
@@ -524,6 +1050,66 @@ options:
         assert_eq!(dry_run.param_type, ScriptParamType::Bool);
     }
 
+    #[test]
+    fn test_parse_clap_style() {
+        let params = parse_help_output(CLAP_HELP);
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["input", "attachments", "output", "jobs", "tag", "verbose", "help", "version"]);
+
+        let input = &params[0];
+        assert!(input.required);
+        assert!(input.short_flag.is_none() && input.long_flag.is_none());
+        assert_eq!(input.param_type, ScriptParamType::String);
+        assert_eq!(input.description.as_deref(), Some("Document to convert"));
+
+        let attachments = &params[1];
+        assert!(!attachments.required);
+        assert_eq!(attachments.nargs.as_deref(), Some("*"));
+        assert_eq!(attachments.param_type, ScriptParamType::Path); // "files" in description
+
+        let output = &params[2];
+        assert_eq!(output.param_type, ScriptParamType::Path);
+        assert_eq!(output.short_flag.as_deref(), Some("-o"));
+        assert_eq!(output.long_flag.as_deref(), Some("--output"));
+        assert_eq!(output.description.as_deref(), Some("Where to write the result"));
+
+        assert_eq!(params[3].default_value.as_deref(), Some("4"));
+        assert_eq!(params[4].nargs.as_deref(), Some("+"));
+        assert_eq!(params[5].param_type, ScriptParamType::Bool);
+    }
+
+    #[test]
+    fn test_parse_go_flag_style() {
+        let params = parse_help_output(GO_HELP);
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["addr", "count", "debug", "ratio", "timeout", "v"]);
+
+        let addr = &params[0];
+        assert_eq!(addr.param_type, ScriptParamType::String);
+        assert_eq!(addr.long_flag.as_deref(), Some("-addr"));
+        assert_eq!(addr.default_value.as_deref(), Some(":8080"));
+        assert_eq!(addr.description.as_deref(), Some("address to listen on (default \":8080\")"));
+
+        assert_eq!(params[1].param_type, ScriptParamType::Number);
+        assert_eq!(params[1].default_value.as_deref(), Some("4"));
+        assert_eq!(params[2].param_type, ScriptParamType::Bool);
+        assert_eq!(params[2].description.as_deref(), Some("enable debug logging"));
+        assert_eq!(params[3].param_type, ScriptParamType::Number);
+        assert_eq!(params[3].description.as_deref(), Some("sampling ratio; applies to every request (default 0.5)"));
+        assert_eq!(params[4].param_type, ScriptParamType::Number);
+        assert_eq!(params[4].default_value.as_deref(), Some("5s"));
+
+        // One-letter bools keep their description on the same line
+        let verbose = &params[5];
+        assert_eq!(verbose.param_type, ScriptParamType::Bool);
+        assert_eq!(verbose.short_flag.as_deref(), Some("-v"));
+        assert!(verbose.long_flag.is_none());
+        assert_eq!(verbose.description.as_deref(), Some("verbose output"));
+
+        assert!(params.iter().all(|p| !p.required));
+        assert_eq!(excerpt(GO_HELP, "ratio"), vec!["-ratio float", "sampling ratio; applies to", "every request (default 0.5)"]);
+    }
+
     #[test]
     fn test_extract_default_value() {
         assert_eq!(
@@ -546,4 +1132,254 @@ options:
             Some("1".to_string())
         );
     }
+
+    fn excerpt(help: &str, param_name: &str) -> Vec<String> {
+        let param = parse_help_output(help).into_iter().find(|p| p.name == param_name).unwrap();
+        let range = locate_param_help(help, &param).unwrap();
+        help.lines().skip(range.start).take(range.len()).map(|l| l.trim().to_string()).collect()
+    }
+
+    #[test]
+    fn locates_every_detected_param() {
+        for help in [GNU_HELP, ARGPARSE_HELP, MULTI_VALUE_HELP, CLAP_HELP, GO_HELP] {
+            for param in parse_help_output(help) {
+                assert!(locate_param_help(help, &param).is_some(), "{} not located", param.name);
+            }
+        }
+    }
+
+    #[test]
+    fn locates_param_with_continuation_lines() {
+        assert_eq!(excerpt(GNU_HELP, "output"), vec!["-o, --output FILE       Output file path"]);
+        assert_eq!(
+            excerpt(ARGPARSE_HELP, "exclude"),
+            vec!["--exclude [EXCLUDE ...]", "List of folders to exclude."]
+        );
+        assert_eq!(
+            excerpt(MULTI_VALUE_HELP, "special_impostor_odds"),
+            vec![
+                "--special-impostor-odds JSON",
+                "Odds for special impostor counts as JSON. Example:",
+                "'{\"2\": 0.1, \"all\": 0.01}'",
+            ]
+        );
+        // Positional: found in its section, not in the usage line
+        assert_eq!(
+            excerpt(ARGPARSE_HELP, "directory"),
+            vec!["directory             The root directory to print the tree from."]
+        );
+    }
+
+    #[test]
+    fn locates_by_flag_after_rename() {
+        // The user renamed the parameter; its flag still identifies it
+        let mut param = parse_help_output(GNU_HELP).into_iter().find(|p| p.name == "count").unwrap();
+        param.name = "items".into();
+        let range = locate_param_help(GNU_HELP, &param).unwrap();
+        assert!(GNU_HELP.lines().nth(range.start).unwrap().contains("--count NUM"));
+
+        param.long_flag = Some("--missing".into());
+        param.short_flag = None;
+        assert_eq!(locate_param_help(GNU_HELP, &param), None);
+    }
+
+    #[test]
+    fn caps_raw_help_at_a_line() {
+        assert_eq!(cap_raw_help(GNU_HELP), GNU_HELP);
+        let long = "option line\n".repeat(MAX_RAW_HELP_LEN / 10);
+        let capped = cap_raw_help(&long);
+        assert!(capped.len() <= MAX_RAW_HELP_LEN + 20);
+        assert!(capped.ends_with("option line\n[… truncated]\n"));
+    }
+
+    #[test]
+    fn detects_mutually_exclusive_groups() {
+        let help = r#"usage: export.py [-h] [--json | --yaml | -c] [--out OUT]
+                 (--all | --since SINCE)
+
+options:
+  -h, --help     show this help message and exit
+  --json         JSON output
+  --yaml         YAML output
+  -c, --csv      CSV output
+  --out OUT      Output file
+  --all          Export everything
+  --since SINCE  Only changes since this date
+"#;
+        let params = parse_help_output(help);
+        let group = |name: &str| params.iter().find(|p| p.name == name).unwrap().mutex_group.clone();
+        assert_eq!(group("json").as_deref(), Some("json/yaml/csv"));
+        assert_eq!(group("csv"), group("json"));
+        assert_eq!(group("all").as_deref(), Some("all/since"));
+        assert_eq!(group("since"), group("all"));
+        assert_eq!(group("out"), None);
+        assert_eq!(group("help"), None);
+    }
+
+    #[test]
+    fn detects_required_options_from_usage_and_description() {
+        let help = r#"usage: ingest.py [-h] --input INPUT [--output OUTPUT] [--verbose]
+                 -t TABLE [--mode MODE] --config=CONFIG
+                 [--json | --yaml] source
+
+positional arguments:
+  source                Where the rows come from
+
+options:
+  -h, --help            show this help message and exit
+  --input INPUT         File to ingest
+  --output OUTPUT       Where to write the report
+  --verbose             More output
+  -t, --table TABLE     Target table
+  --mode MODE           How to write the rows
+  --config CONFIG       Settings file
+  --token TOKEN         API token (required)
+  --json                JSON report
+  --yaml                YAML report
+"#;
+        let params = parse_help_output(help);
+        let required = |name: &str| params.iter().find(|p| p.name == name).unwrap().required;
+        assert!(required("input"));
+        assert!(required("config"));
+        assert!(required("table"));
+        assert!(required("token"));
+        assert!(required("source"));
+        assert!(!required("output"));
+        assert!(!required("verbose"));
+        assert!(!required("mode"));
+        assert!(!required("json"));
+        assert!(!required("help"));
+    }
+
+    #[test]
+    fn bracketed_options_stay_optional() {
+        for help in [ARGPARSE_HELP, MULTI_VALUE_HELP, CLAP_HELP, GNU_HELP] {
+            let params = parse_help_output(help);
+            assert!(params.iter().filter(|p| p.long_flag.is_some()).all(|p| !p.required), "{}", help);
+        }
+    }
+
+    #[test]
+    fn merge_keeps_existing_params() {
+        let mut existing = parse_help_output(GNU_HELP);
+        existing.retain(|p| p.name == "verbose" || p.name == "output");
+        existing[0].description = Some("Edited".into());
+
+        let merged = merge_detected(&existing, parse_help_output(GNU_HELP));
+        assert_eq!(merged.len(), 6);
+        assert_eq!(merged[0].description.as_deref(), Some("Edited"));
+        assert_eq!(merged[2].name, "count");
+    }
+
+    #[test]
+    fn merge_never_clobbers_same_named_params() {
+        let mut existing = parse_help_output(GNU_HELP);
+        existing.retain(|p| p.name == "output" || p.name == "count");
+        existing[0].description = Some("Where the report goes".into());
+        existing[0].default_value = None;
+        existing[1].default_value = Some("3".into());
+        existing[1].long_flag = Some("--times".into());
+
+        let detected = parse_help_output(GNU_HELP);
+        let merged = merge_detected(&existing, detected.clone());
+        // Edited params come first, untouched; the rest follow in help order
+        assert_eq!(&merged[..2], &existing[..]);
+        let added: Vec<&str> = merged[2..].iter().map(|p| p.name.as_str()).collect();
+        let expected: Vec<&str> = detected
+            .iter()
+            .map(|p| p.name.as_str())
+            .filter(|name| *name != "output" && *name != "count")
+            .collect();
+        assert_eq!(added, expected);
+
+        // Detecting again adds nothing
+        assert_eq!(merge_detected(&merged, detected), merged);
+    }
+
+    #[test]
+    fn merging_into_a_script_keeps_its_edits_and_help_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let mut script = GlobalScript::new("report".into(), "./report.sh".into(), None);
+        let mut verbose = parse_help_output(GNU_HELP).remove(0);
+        verbose.description = Some("Chatty".into());
+        script.parameters = vec![verbose.clone()];
+        script.raw_help = Some(GNU_HELP.into());
+        let script = storage.create_global_script(script).unwrap();
+
+        let detected = DetectedHelp { parameters: parse_help_output(GNU_HELP), raw_help: String::new() };
+        let merged = merge_into_script(&storage, &script.id, detected).unwrap();
+        assert_eq!(merged.parameters[0], verbose);
+        assert_eq!(merged.parameters.len(), parse_help_output(GNU_HELP).len());
+        // A detection without help text doesn't wipe the stored one
+        assert_eq!(merged.raw_help.as_deref(), Some(GNU_HELP));
+        assert_eq!(storage.get_global_script(&script.id).unwrap().parameters, merged.parameters);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cancelling_a_detection_kills_the_help_process() {
+        use std::sync::Arc;
+        use std::time::Instant;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("slow.sh");
+        // Records its pid, then hangs instead of printing help
+        std::fs::write(&script, "echo $$ > \"$(dirname \"$0\")/pid\"\nexec sleep 30\n").unwrap();
+        let pid_file = dir.path().join("pid");
+
+        let detection = Arc::new(HelpDetection::new());
+        let worker = {
+            let detection = detection.clone();
+            let command = format!("sh {}", script.display());
+            std::thread::spawn(move || detection.run(&command))
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let pid = loop {
+            let pid = std::fs::read_to_string(&pid_file).unwrap_or_default().trim().to_string();
+            if !pid.is_empty() {
+                break pid;
+            }
+            assert!(Instant::now() < deadline, "the help process never started");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        let cancelled_at = Instant::now();
+        detection.cancel();
+        let outcome = worker.join().unwrap().unwrap();
+        assert!(matches!(outcome, HelpDetectionOutcome::Cancelled));
+        assert!(cancelled_at.elapsed() < Duration::from_secs(2));
+
+        // Killed and reaped: nothing runs under its pid any more
+        let alive = std::process::Command::new("kill").args(["-0", &pid]).stderr(Stdio::null()).status().unwrap().success();
+        assert!(!alive);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hanging_or_silent_commands_detect_no_parameters() {
+        let dir = tempfile::tempdir().unwrap();
+        let waits = dir.path().join("waits.sh");
+        // Prints a prompt, then waits for an answer that never comes
+        std::fs::write(&waits, "printf 'Name: '\nexec sleep 30\n").unwrap();
+        let silent = dir.path().join("silent.sh");
+        std::fs::write(&silent, "exit 0\n").unwrap();
+
+        let started = std::time::Instant::now();
+        let detection = HelpDetection::new().with_timeout(Duration::from_millis(300));
+        let HelpDetectionOutcome::Completed(detected) = detection.run(&format!("sh {}", waits.display())).unwrap() else {
+            panic!("not cancelled")
+        };
+        assert!(detected.parameters.is_empty());
+        assert_eq!(detected.raw_help, "Name: ");
+        // Killed once, not retried with -h
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let detected = detect_help(&format!("sh {}", silent.display())).unwrap();
+        assert!(detected.parameters.is_empty());
+        assert_eq!(detected.raw_help, "");
+
+        assert!(detect_parameters("cortx-no-such-program").is_err());
+    }
 }
@@ -1,13 +1,47 @@
+pub mod change_event;
 pub mod command_builder;
+pub mod doctor;
+pub mod env_discovery;
+pub mod env_file;
 pub mod error;
+pub mod export_file;
 pub mod file_watcher;
+pub mod global_shortcuts;
 pub mod help_parser;
+pub mod migration;
 pub mod models;
+pub mod mutex_group;
+pub mod organize_rules;
+pub mod output_format;
+pub mod param_fill;
 pub mod port_detector;
 pub mod process_manager;
+pub mod project_files;
+pub mod project_roots;
+pub mod prompt_detect;
+pub mod run_compare;
+pub mod run_guard;
 pub mod runtime_state;
+pub mod scheduler;
+pub mod script_artifacts;
+pub mod script_dag;
 pub mod script_discovery;
+pub mod script_index;
+pub mod script_location;
+pub mod script_query;
+pub mod script_relocation;
+pub mod script_requirements;
+pub mod script_sync;
+pub mod script_validation;
+pub mod service_health;
+pub mod service_idle;
+pub mod service_log;
+pub mod service_order;
+pub mod service_restart;
+pub mod service_watch;
 pub mod shell_init;
 pub mod shim;
+pub mod spawn_env;
 pub mod storage;
+pub mod text_file;
 pub mod tool_discovery;
@@ -0,0 +1,354 @@
+//! Importing another machine's CortX data directory ("machine migration").
+//!
+//! The foreign dir is read entry by entry: fields that older versions didn't
+//! write are filled in before deserializing, and an entry that still can't be
+//! read is reported as a problem instead of aborting the whole migration.
+//! Nothing here writes to disk — see `Storage::import_from_data_dir` (preview)
+//! and `Storage::apply_path_mappings` (apply).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::models::{AppSettings, MigrationItem, MigrationItemStatus, Project, ScriptExport};
+use crate::project_files;
+use crate::storage::StorageError;
+
+/// Data files this version imports.
+const DATA_FILES: &[&str] = &[
+    "projects.json",
+    "global_scripts.json",
+    "tools.json",
+    "aliases.json",
+    "apps.json",
+    "tag_definitions.json",
+    "status_definitions.json",
+    "settings.json",
+];
+
+/// Data files written by other CortX versions that aren't imported here.
+const UNSUPPORTED_FILES: &[&str] = &["folders.json", "script_groups.json"];
+
+/// Everything readable from a foreign data dir, as an in-memory export.
+pub struct ForeignData {
+    pub export: ScriptExport,
+    pub problems: Vec<String>,
+    pub ignored_files: Vec<String>,
+}
+
+/// Read a foreign CortX data dir. Fails only if `dir` isn't a directory or
+/// contains none of the known data files.
+pub fn read_data_dir(dir: &Path) -> Result<ForeignData, StorageError> {
+    if !dir.is_dir() {
+        return Err(StorageError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Not a directory: {}", dir.display()),
+        )));
+    }
+    if !DATA_FILES.iter().any(|f| dir.join(f).exists()) && !project_files::projects_dir(dir).is_dir() {
+        return Err(StorageError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No CortX data files found in {}", dir.display()),
+        )));
+    }
+
+    let mut problems = Vec::new();
+    let export = ScriptExport {
+        version: "data-dir".to_string(),
+        scripts: read_entries(dir, "global_scripts.json", &mut problems),
+        groups: Vec::new(),
+        tools: read_entries(dir, "tools.json", &mut problems),
+        tag_definitions: read_entries(dir, "tag_definitions.json", &mut problems),
+        aliases: read_entries(dir, "aliases.json", &mut problems),
+        apps: read_entries(dir, "apps.json", &mut problems),
+        status_definitions: read_entries(dir, "status_definitions.json", &mut problems),
+        projects: read_projects(dir, &mut problems),
+        settings: read_settings(dir, &mut problems),
+        exported_at: Utc::now(),
+    };
+    let ignored_files = UNSUPPORTED_FILES
+        .iter()
+        .filter(|f| dir.join(f).exists())
+        .map(|f| f.to_string())
+        .collect();
+
+    Ok(ForeignData { export, problems, ignored_files })
+}
+
+fn read_value(dir: &Path, file: &str, problems: &mut Vec<String>) -> Option<Value> {
+    let path = dir.join(file);
+    if !path.exists() {
+        return None;
+    }
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(value) => Some(value),
+        Err(e) => {
+            problems.push(format!("{}: {}", file, e));
+            None
+        }
+    }
+}
+
+fn read_entries<T: DeserializeOwned>(dir: &Path, file: &str, problems: &mut Vec<String>) -> Vec<T> {
+    let Some(value) = read_value(dir, file, problems) else {
+        return Vec::new();
+    };
+    let Value::Array(entries) = value else {
+        problems.push(format!("{}: expected a JSON array", file));
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, mut entry)| {
+            upgrade_entry(file, &mut entry);
+            serde_json::from_value(entry)
+                .map_err(|e| problems.push(format!("{} entry {}: {}", file, i + 1, e)))
+                .ok()
+        })
+        .collect()
+}
+
+/// The projects of a data dir: from `projects.json` if it's a version
+/// before the split (or one that hasn't started since), else `projects/`
+fn read_projects(dir: &Path, problems: &mut Vec<String>) -> Vec<Project> {
+    if dir.join(project_files::LEGACY_FILE).exists() {
+        return read_entries(dir, project_files::LEGACY_FILE, problems);
+    }
+    let contents = project_files::read_dir(dir);
+    for (path, e) in contents.unreadable {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        problems.push(format!("{}/{}: {}", project_files::PROJECTS_DIR, name, e));
+    }
+    contents.projects
+}
+
+fn read_settings(dir: &Path, problems: &mut Vec<String>) -> Option<AppSettings> {
+    let value = read_value(dir, "settings.json", problems)?;
+    serde_json::from_value(value)
+        .map_err(|e| problems.push(format!("settings.json: {}", e)))
+        .ok()
+}
+
+/// Fill in required fields that older versions didn't write (timestamps and
+/// `order`), so entries saved before those fields existed still deserialize.
+fn upgrade_entry(file: &str, entry: &mut Value) {
+    let Some(obj) = entry.as_object_mut() else {
+        return;
+    };
+    let now = Value::String(Utc::now().to_rfc3339());
+
+    match file {
+        "projects.json" => {
+            obj.entry("createdAt").or_insert_with(|| now.clone());
+            obj.entry("updatedAt").or_insert(now);
+            obj.entry("services").or_insert_with(|| Value::Array(Vec::new()));
+            for key in ["services", "scripts"] {
+                if let Some(Value::Array(children)) = obj.get_mut(key) {
+                    for (i, child) in children.iter_mut().enumerate() {
+                        if let Some(child) = child.as_object_mut() {
+                            child.entry("order").or_insert_with(|| Value::from(i));
+                        }
+                    }
+                }
+            }
+        }
+        "global_scripts.json" => {
+            obj.entry("createdAt").or_insert_with(|| now.clone());
+            obj.entry("updatedAt").or_insert(now);
+            obj.entry("order").or_insert_with(|| Value::from(0));
+        }
+        "tools.json" | "aliases.json" | "apps.json" => {
+            obj.entry("createdAt").or_insert_with(|| now.clone());
+            obj.entry("updatedAt").or_insert(now);
+        }
+        _ => {}
+    }
+}
+
+/// Classify incoming items against local ones. `same` decides whether two
+/// items refer to the same thing (mirroring the duplicate checks applied on
+/// import); a match with equal content is `Identical`, otherwise `Conflict`.
+pub fn classify<T: Serialize>(
+    kind: &str,
+    incoming: &[T],
+    local: &[T],
+    id_and_name: impl Fn(&T) -> (&str, &str),
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<MigrationItem> {
+    incoming
+        .iter()
+        .map(|item| {
+            let status = match local.iter().find(|l| same(l, item)) {
+                None => MigrationItemStatus::New,
+                Some(existing) if serde_json::to_value(existing).ok() == serde_json::to_value(item).ok() => {
+                    MigrationItemStatus::Identical
+                }
+                Some(_) => MigrationItemStatus::Conflict,
+            };
+            let (id, name) = id_and_name(item);
+            MigrationItem {
+                kind: kind.to_string(),
+                id: id.to_string(),
+                name: name.to_string(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The project's image inside the foreign `images/` dir, if it exists there.
+pub fn foreign_image(dir: &Path, project: &Project) -> Option<PathBuf> {
+    let image = project.image_path.as_deref()?;
+    let file_name = Path::new(image).file_name()?;
+    let candidate = dir.join("images").join(file_name);
+    candidate.is_file().then_some(candidate)
+}
+
+/// Move a project to `new_root`, rebasing service and script working dirs
+/// that were absolute paths under the old root.
+pub fn remap_project_root(project: &mut Project, new_root: &str) {
+    let old_root = PathBuf::from(&project.root_path);
+    let rebase = |dir: &mut String| {
+        if let Ok(rest) = Path::new(dir.as_str()).strip_prefix(&old_root) {
+            *dir = if rest.as_os_str().is_empty() {
+                new_root.to_string()
+            } else {
+                Path::new(new_root).join(rest).to_string_lossy().to_string()
+            };
+        }
+    };
+    for service in &mut project.services {
+        rebase(&mut service.working_dir);
+    }
+    for script in &mut project.scripts {
+        rebase(&mut script.working_dir);
+    }
+    project.root_path = new_root.to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ImportOptions, PathMapping};
+    use crate::storage::Storage;
+
+    /// A data dir as written by an older CortX: no timestamps or `order`
+    /// fields, one corrupt script entry and a file this version doesn't import.
+    fn write_old_data_dir(dir: &Path) {
+        fs::write(
+            dir.join("projects.json"),
+            r#"[{
+                "id": "p1",
+                "name": "Old API",
+                "rootPath": "/nonexistent/old/api",
+                "imagePath": "/home/old/.local/share/cortx/images/api.png",
+                "services": [
+                    { "id": "s1", "name": "web", "workingDir": "/nonexistent/old/api/web", "command": "npm start" },
+                    { "id": "s2", "name": "worker", "workingDir": ".", "command": "npm run worker" }
+                ]
+            }]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("global_scripts.json"),
+            r#"[{ "id": "g1", "name": "deploy", "command": "./deploy.sh" }, { "id": "g2" }]"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("status_definitions.json"),
+            r##"[{ "name": "Active", "color": "#22c55e", "order": 0 }]"##,
+        )
+        .unwrap();
+        fs::write(dir.join("folders.json"), "[]").unwrap();
+        fs::create_dir_all(dir.join("images")).unwrap();
+        fs::write(dir.join("images").join("api.png"), b"png").unwrap();
+    }
+
+    #[test]
+    fn preview_classifies_and_has_no_side_effects() {
+        let old = tempfile::tempdir().unwrap();
+        let local = tempfile::tempdir().unwrap();
+        write_old_data_dir(old.path());
+        let storage = Storage::with_app_dir(local.path().to_path_buf()).unwrap();
+
+        let report = storage
+            .import_from_data_dir(old.path(), &ImportOptions::default())
+            .unwrap();
+
+        let status_of = |kind: &str, id: &str| {
+            report.items.iter().find(|i| i.kind == kind && i.id == id).map(|i| i.status)
+        };
+        assert_eq!(status_of("project", "p1"), Some(MigrationItemStatus::New));
+        assert_eq!(status_of("script", "g1"), Some(MigrationItemStatus::New));
+        assert_eq!(status_of("status", "Active"), Some(MigrationItemStatus::Identical));
+        assert_eq!(status_of("script", "g2"), None);
+
+        assert_eq!(report.problems.len(), 1);
+        assert!(report.problems[0].starts_with("global_scripts.json entry 2"));
+        assert_eq!(report.ignored_files, vec!["folders.json".to_string()]);
+        assert_eq!(report.needs_path.len(), 1);
+        assert_eq!(report.needs_path[0].old_root_path, "/nonexistent/old/api");
+        assert_eq!(report.images_to_copy.len(), 1);
+
+        // Nothing written
+        assert!(storage.get_all_projects().is_empty());
+        assert!(!local.path().join("projects.json").exists());
+        assert!(!project_files::projects_dir(local.path()).exists());
+        assert_eq!(fs::read_dir(storage.images_dir()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn apply_remaps_paths_and_copies_images() {
+        let old = tempfile::tempdir().unwrap();
+        let local = tempfile::tempdir().unwrap();
+        let new_root = tempfile::tempdir().unwrap();
+        write_old_data_dir(old.path());
+        let storage = Storage::with_app_dir(local.path().to_path_buf()).unwrap();
+        let new_root_str = new_root.path().to_string_lossy().to_string();
+
+        let result = storage
+            .apply_path_mappings(
+                old.path(),
+                &ImportOptions::default(),
+                &[PathMapping { project_id: "p1".into(), new_root_path: new_root_str.clone() }],
+            )
+            .unwrap();
+        assert_eq!(result.projects_added, 1);
+        assert_eq!(result.scripts_added, 1);
+
+        let project = storage.get_project("p1").unwrap();
+        assert_eq!(project.root_path, new_root_str);
+        assert_eq!(
+            project.services[0].working_dir,
+            new_root.path().join("web").to_string_lossy()
+        );
+        assert_eq!(project.services[1].working_dir, ".");
+        assert_eq!(project.services[1].order, 1);
+
+        let image = PathBuf::from(project.image_path.unwrap());
+        assert!(image.starts_with(storage.images_dir()));
+        assert!(image.is_file());
+
+        // Re-running the preview now finds everything already present
+        let report = storage
+            .import_from_data_dir(old.path(), &ImportOptions::default())
+            .unwrap();
+        assert!(report.items.iter().all(|i| i.status != MigrationItemStatus::New));
+        assert!(report.needs_path.is_empty());
+    }
+
+    #[test]
+    fn rejects_dir_without_data_files() {
+        let empty = tempfile::tempdir().unwrap();
+        assert!(read_data_dir(empty.path()).is_err());
+    }
+}
@@ -1,12 +1,161 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 // ============================================================================
 // Existing models (extracted from frontend/src-tauri/src/models.rs)
 // ============================================================================
 
+/// How much of the parent (CortX) environment a spawned process inherits.
+///
+/// - `Inherit`: the full parent environment (default, legacy behavior).
+/// - `Clean`: only a minimal OS-specific set (PATH, HOME/USERPROFILE, TEMP, LANG, ...).
+/// - `Allowlist`: the minimal set plus parent variables matching the patterns
+///   (`*` / `?` globs, e.g. `LC_*`). A `!NAME` entry drops a variable from the
+///   minimal set (e.g. `!PATH`).
+///
+/// Explicitly configured `env_vars` are always applied on top.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", content = "patterns", rename_all = "lowercase")]
+pub enum EnvMode {
+    #[default]
+    Inherit,
+    Clean,
+    Allowlist(Vec<String>),
+}
+
+impl EnvMode {
+    pub fn is_inherit(&self) -> bool {
+        matches!(self, EnvMode::Inherit)
+    }
+
+    /// Short human-readable label, e.g. for the TUI run form
+    pub fn label(&self) -> String {
+        match self {
+            EnvMode::Inherit => "inherit".to_string(),
+            EnvMode::Clean => "clean".to_string(),
+            EnvMode::Allowlist(patterns) => format!("allowlist ({})", patterns.join(", ")),
+        }
+    }
+}
+
+/// How the output panels display a script's stdout (see `output_format`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputFormat {
+    #[default]
+    Raw,
+    PrettyJson,
+    Table,
+}
+
+impl OutputFormat {
+    pub fn is_raw(&self) -> bool {
+        matches!(self, OutputFormat::Raw)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputFormat::Raw => "raw",
+            OutputFormat::PrettyJson => "pretty JSON",
+            OutputFormat::Table => "table",
+        }
+    }
+}
+
+/// What happens when a service with an idle timeout goes quiet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleAction {
+    /// Write a warning to the service's output
+    #[default]
+    Warn,
+    /// Warn, then stop the service
+    Stop,
+}
+
+impl IdleAction {
+    pub fn is_warn(&self) -> bool {
+        matches!(self, IdleAction::Warn)
+    }
+}
+
+/// How a service's health is probed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum HealthProbe {
+    /// Connect to `port` on localhost (the service's `port` when unset)
+    Tcp {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+    },
+    /// GET `url` (plain http); healthy on `expected_status`, or any 2xx
+    #[serde(rename_all = "camelCase")]
+    Http {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expected_status: Option<u16>,
+    },
+}
+
+/// Periodic health check of a running service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckConfig {
+    pub probe: HealthProbe,
+    /// Seconds between two checks (default 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// How long one check may take (default 2000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Failed checks in a row before the service is reported unhealthy (default 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<u32>,
+}
+
+/// What happens when a running service exits on its own (not stopped from
+/// CortX)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    /// Restart after a non-zero exit or a crash, up to `max_retries` times
+    /// in a row
+    #[serde(rename_all = "camelCase")]
+    OnFailure { max_retries: u32 },
+    /// Restart after any exit, up to `max_retries` times in a row
+    #[serde(rename_all = "camelCase")]
+    Always { max_retries: u32 },
+}
+
+impl RestartPolicy {
+    pub fn is_never(&self) -> bool {
+        *self == RestartPolicy::Never
+    }
+}
+
+/// When automatic restarts give up on a service that keeps crashing: the
+/// circuit opens after `max_failures` failed runs within `window_secs`, and
+/// the service stays down until started by hand, or retried once after
+/// `cool_down_secs`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartCircuitConfig {
+    /// Failed runs that open the circuit (default 5)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_failures: Option<u32>,
+    /// Rolling window failed runs are counted in (default 60)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<u64>,
+    /// Wait before retrying an open circuit once (default 600); 0 waits for
+    /// a manual start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cool_down_secs: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Service {
@@ -30,6 +179,41 @@ pub struct Service {
     pub port: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env_vars: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "EnvMode::is_inherit")]
+    pub env_mode: EnvMode,
+    /// Files / directories (relative to `working_dir`) whose changes restart
+    /// the running service
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watch_paths: Vec<String>,
+    /// Quiet period before a change triggers a restart (default 500ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watch_debounce_ms: Option<u64>,
+    /// Seconds without any stdout / stderr line after which `idle_action`
+    /// kicks in. Unset (or 0) for services that are quiet on purpose.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "IdleAction::is_warn")]
+    pub idle_action: IdleAction,
+    /// Checked while the service runs; failing checks make it `Unhealthy`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check: Option<HealthCheckConfig>,
+    #[serde(default, skip_serializing_if = "RestartPolicy::is_never")]
+    pub restart_policy: RestartPolicy,
+    /// Wait before the first automatic restart, doubled for each further
+    /// one in a row (default 1000ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_backoff_ms: Option<u64>,
+    /// Stops restarting a service that keeps crashing (defaults when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restart_circuit: Option<RestartCircuitConfig>,
+    /// How long a stop waits for the service to exit after asking it to,
+    /// before killing it (default 5s)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_grace_seconds: Option<u64>,
+    /// Ids of services of the same project that start-all brings up before
+    /// this one (and stop-all stops after it)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
     pub order: u32,
 }
 
@@ -48,11 +232,81 @@ impl Service {
             color: None,
             port: None,
             env_vars: None,
+            env_mode: EnvMode::Inherit,
+            watch_paths: Vec::new(),
+            watch_debounce_ms: None,
+            idle_timeout_secs: None,
+            idle_action: IdleAction::Warn,
+            health_check: None,
+            restart_policy: RestartPolicy::Never,
+            restart_backoff_ms: None,
+            restart_circuit: None,
+            stop_grace_seconds: None,
+            depends_on: Vec::new(),
             order: 0,
         }
     }
 }
 
+/// Outcome of starting (or stopping) one service as part of a group
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceGroupResult {
+    pub service_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Left alone: already running on start-all, not running on stop-all
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+impl ServiceGroupResult {
+    pub fn started(service_id: String, pid: u32) -> Self {
+        Self { service_id, pid: Some(pid), error: None, skipped: false }
+    }
+
+    pub fn stopped(service_id: String) -> Self {
+        Self { service_id, pid: None, error: None, skipped: false }
+    }
+
+    pub fn skipped(service_id: String) -> Self {
+        Self { service_id, pid: None, error: None, skipped: true }
+    }
+
+    pub fn failed(service_id: String, error: impl Into<String>) -> Self {
+        Self { service_id, pid: None, error: Some(error.into()), skipped: false }
+    }
+}
+
+/// Outcome of saving a service's definition and restarting it with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartResult {
+    /// The service as saved
+    pub service: Service,
+    /// It was running, and now runs the saved definition. False: only saved.
+    pub restarted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+}
+
+/// What start-all waits for after starting each service, before the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum StartWait {
+    /// Until it has stayed up for a moment, like sequential group starts
+    #[default]
+    Settle,
+    /// A fixed delay
+    Delay { ms: u64 },
+    /// Until its `port` accepts connections, for at most `timeout_ms`.
+    /// Services without a port settle instead.
+    #[serde(rename_all = "camelCase")]
+    Port { timeout_ms: u64 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Script {
@@ -69,6 +323,13 @@ pub struct Script {
     #[serde(default)]
     pub linked_service_ids: Vec<String>,
     pub order: u32,
+    /// Scripts sharing a mutex group never run at the same time (see
+    /// `mutex_group`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutex_group: Option<String>,
+    /// Wait for the mutex group to be free instead of refusing to start
+    #[serde(default)]
+    pub queue_on_mutex: bool,
 }
 
 impl Script {
@@ -83,6 +344,33 @@ impl Script {
             color: None,
             linked_service_ids: Vec::new(),
             order: 0,
+            mutex_group: None,
+            queue_on_mutex: false,
+        }
+    }
+}
+
+/// Another checkout of a project (e.g. a git worktree) with the same
+/// services and scripts, rooted elsewhere
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectVariant {
+    pub id: String,
+    pub name: String,
+    pub root_path: String,
+    /// Env vars set for every service started under this variant, over the
+    /// linked env files and under the service's own `env_vars`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env_var_overrides: HashMap<String, String>,
+}
+
+impl ProjectVariant {
+    pub fn new(name: String, root_path: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            root_path,
+            env_var_overrides: HashMap::new(),
         }
     }
 }
@@ -114,6 +402,12 @@ pub struct Project {
     pub status: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub toolbox_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<ProjectVariant>,
+    /// The variant services and scripts run under; the project's own
+    /// `root_path` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_variant_id: Option<String>,
 }
 
 impl Project {
@@ -135,9 +429,40 @@ impl Project {
             tags: Vec::new(),
             status: None,
             toolbox_url: None,
+            variants: Vec::new(),
+            active_variant_id: None,
         }
     }
 
+    /// The active variant, if it still exists
+    pub fn active_variant(&self) -> Option<&ProjectVariant> {
+        let id = self.active_variant_id.as_deref()?;
+        self.variants.iter().find(|v| v.id == id)
+    }
+
+    /// Root services and scripts run from: the active variant's, or the
+    /// project's own.
+    pub fn effective_root(&self) -> &str {
+        self.active_variant().map_or(&self.root_path, |v| &v.root_path)
+    }
+
+    /// A service / script working dir resolved against [`Self::effective_root`].
+    /// Empty or `.` is the root itself; absolute paths under the project's
+    /// own root are moved to the active variant's.
+    pub fn resolve_dir(&self, dir: &str) -> String {
+        let dir = dir.trim();
+        let root = std::path::Path::new(self.effective_root());
+        if dir.is_empty() || dir == "." {
+            return self.effective_root().to_string();
+        }
+        let path = std::path::Path::new(dir);
+        let resolved = match path.strip_prefix(&self.root_path) {
+            Ok(relative) if path.is_absolute() => root.join(relative),
+            _ => root.join(path),
+        };
+        resolved.to_string_lossy().to_string()
+    }
+
     /// Clone with every `env_files[].variables[].value` cleared.
     ///
     /// Must be called before serializing a Project to any external surface
@@ -166,6 +491,14 @@ pub struct EnvVariable {
     pub line_number: u32,
 }
 
+/// A line of an env file that couldn't be parsed (line 0 = the whole file)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvParseError {
+    pub line_number: u32,
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum EnvFileVariant {
@@ -186,8 +519,15 @@ pub struct EnvFile {
     pub path: String,
     pub relative_path: String,
     pub filename: String,
+    /// Detected from the filename, unless `variant_overridden` is set
     pub variant: EnvFileVariant,
+    /// The user picked `variant` manually; rediscovery must keep it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub variant_overridden: bool,
     pub variables: Vec<EnvVariable>,
+    /// Lines that couldn't be parsed during the last read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<EnvParseError>,
     pub is_manually_added: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub linked_service_id: Option<String>,
@@ -211,7 +551,9 @@ impl EnvFile {
             relative_path,
             filename,
             variant,
+            variant_overridden: false,
             variables,
+            parse_errors: Vec::new(),
             is_manually_added,
             linked_service_id: None,
             discovered_at: now,
@@ -230,6 +572,33 @@ pub struct EnvComparison {
     pub common_keys: Vec<String>,
 }
 
+/// Every key of a project's env files against every file: a row per key,
+/// a column per file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvMatrix {
+    /// The columns: env file ids, in the project's order
+    pub file_ids: Vec<String>,
+    /// Sorted by key
+    pub rows: Vec<EnvMatrixRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvMatrixRow {
+    pub key: String,
+    /// The key's value in each file of `EnvMatrix::file_ids`; `None` where
+    /// the file doesn't define it
+    pub values: Vec<Option<String>>,
+    /// The files defining the key don't all agree. Example files are left
+    /// out: their values are placeholders.
+    pub values_differ: bool,
+    /// Base files (`.env`) without the key although an example file in
+    /// their directory has it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_in_base: Vec<String>,
+}
+
 // Terminal configuration
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -350,6 +719,333 @@ pub struct AppSettings {
     /// Empty/None means use the platform default (see `shim::resolve_shim_dir`).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub shim_dir: Option<String>,
+    /// Shell used to run service and project-script commands.
+    /// Unset means the platform default (`cmd /C` on Windows, `sh -c` elsewhere).
+    #[serde(default)]
+    pub shell: ShellConfig,
+    /// TUI: pin running scripts to an "Active" section at the top of the
+    /// Scripts list (initial state of the `!` toggle).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pin_running_scripts: bool,
+    /// Directory of the last export / import file, where the next file
+    /// dialog or TUI path prompt starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_export_dir: Option<String>,
+    /// Persisting integrated service output to disk
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    /// TUI: when finished script runs drop their in-memory logs
+    #[serde(default)]
+    pub script_retention: ScriptRetentionConfig,
+    /// Zip backups of the data files under `<app_dir>/backups`
+    #[serde(default)]
+    pub backups: BackupConfig,
+    /// TUI: how much queued work one frame handles before redrawing
+    #[serde(default)]
+    pub tui_event_budget: TuiEventBudgetConfig,
+    /// TUI: options of its settings screen (`,`)
+    #[serde(default)]
+    pub tui: TuiConfig,
+    /// Show the values of sensitive-looking env variables (`API_KEY`,
+    /// `DB_PASSWORD`, ...) as `••••` in env file views
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mask_env_values: bool,
+    /// Desktop app: OS-wide shortcuts, which work while the app isn't
+    /// focused, accelerator by action (see `global_shortcuts`). The
+    /// palette's shortcut is `global_hotkey`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub global_shortcuts: BTreeMap<ShortcutAction, String>,
+}
+
+/// What a global shortcut does. Stored as a key of
+/// `AppSettings::global_shortcuts`: "stopAll", "toggleWindow", "openPalette"
+/// or "startWorkspace:<project name>".
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ShortcutAction {
+    /// Show the window and open the command palette
+    OpenPalette,
+    /// Gracefully stop every service and script the app started
+    StopAll,
+    /// Start every service of the project with this name, dependencies first
+    StartWorkspace(String),
+    /// Show the main window, or hide it when it's showing
+    ToggleWindow,
+}
+
+const START_WORKSPACE_PREFIX: &str = "startWorkspace:";
+
+impl std::fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutAction::OpenPalette => f.write_str("openPalette"),
+            ShortcutAction::StopAll => f.write_str("stopAll"),
+            ShortcutAction::StartWorkspace(name) => write!(f, "{}{}", START_WORKSPACE_PREFIX, name),
+            ShortcutAction::ToggleWindow => f.write_str("toggleWindow"),
+        }
+    }
+}
+
+impl TryFrom<String> for ShortcutAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "openPalette" => Ok(ShortcutAction::OpenPalette),
+            "stopAll" => Ok(ShortcutAction::StopAll),
+            "toggleWindow" => Ok(ShortcutAction::ToggleWindow),
+            other => match other.strip_prefix(START_WORKSPACE_PREFIX).map(str::trim) {
+                Some(name) if !name.is_empty() => Ok(ShortcutAction::StartWorkspace(name.to_string())),
+                _ => Err(format!("Unknown shortcut action: {}", other)),
+            },
+        }
+    }
+}
+
+impl From<ShortcutAction> for String {
+    fn from(action: ShortcutAction) -> Self {
+        action.to_string()
+    }
+}
+
+/// A global shortcut that isn't registered, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutWarning {
+    pub action: ShortcutAction,
+    /// As configured
+    pub accelerator: String,
+    pub kind: ShortcutWarningKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutWarningKind {
+    /// Not an accelerator, e.g. "Ctrl+Ctrl+A"
+    Invalid,
+    /// Another action already has it
+    Duplicate,
+    /// Refused by the OS: the system or another app has it
+    Unavailable,
+}
+
+/// What the StopAll shortcut stopped, by display name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopAllSummary {
+    pub services: Vec<String>,
+    pub scripts: Vec<String>,
+    /// "name: error" of those that didn't stop
+    pub failed: Vec<String>,
+}
+
+impl StopAllSummary {
+    /// One line for the system notification, e.g. "Stopped 2 services
+    /// (api, web) and 1 script (backup)"
+    pub fn message(&self) -> String {
+        fn describe(names: &[String], noun: &str) -> Option<String> {
+            if names.is_empty() {
+                return None;
+            }
+            let shown: Vec<&str> = names.iter().take(3).map(String::as_str).collect();
+            let more = if names.len() > shown.len() { ", …" } else { "" };
+            let plural = if names.len() == 1 { "" } else { "s" };
+            Some(format!("{} {}{} ({}{})", names.len(), noun, plural, shown.join(", "), more))
+        }
+
+        let stopped: Vec<String> = [describe(&self.services, "service"), describe(&self.scripts, "script")]
+            .into_iter()
+            .flatten()
+            .collect();
+        let mut message = if stopped.is_empty() {
+            "Nothing was running".to_string()
+        } else {
+            format!("Stopped {}", stopped.join(" and "))
+        };
+        if !self.failed.is_empty() {
+            message.push_str(&format!("; failed to stop {}", self.failed.join(", ")));
+        }
+        message
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TuiConfig {
+    /// Output lines kept in memory per script / service
+    #[serde(default = "default_tui_max_log_lines")]
+    pub max_log_lines: usize,
+    /// Ask before quitting while scripts or services are running
+    #[serde(default)]
+    pub confirm_on_quit: bool,
+    /// Focus the output panel when a script is started
+    #[serde(default)]
+    pub focus_output_on_run: bool,
+    /// Keep a script's output bookmarks when it's run again
+    #[serde(default)]
+    pub keep_bookmarks: bool,
+    /// Open the input box when a running script stops at a prompt
+    #[serde(default = "default_true")]
+    pub detect_prompts: bool,
+    /// Regexes recognizing prompts, on top of
+    /// `prompt_detect::DEFAULT_PROMPT_PATTERNS`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompt_patterns: Vec<String>,
+    /// Quick run bar: slot (1-9) → id of the script it quick-runs. A slot
+    /// whose script was deleted shows as empty.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub quick_slots: BTreeMap<u8, String>,
+}
+
+fn default_tui_max_log_lines() -> usize {
+    5000
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            max_log_lines: default_tui_max_log_lines(),
+            confirm_on_quit: false,
+            focus_output_on_run: false,
+            keep_bookmarks: false,
+            detect_prompts: true,
+            prompt_patterns: Vec::new(),
+            quick_slots: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupConfig {
+    /// Older backups are pruned beyond this many
+    #[serde(default = "default_backups_keep")]
+    pub keep: usize,
+}
+
+fn default_backups_keep() -> usize {
+    10
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self { keep: default_backups_keep() }
+    }
+}
+
+/// Finished script runs keep their logs in the TUI until they are older
+/// than `max_age_minutes` or beyond the `max_retained` most recent ones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRetentionConfig {
+    #[serde(default = "default_retention_max_age_minutes")]
+    pub max_age_minutes: u64,
+    #[serde(default = "default_retention_max_retained")]
+    pub max_retained: usize,
+}
+
+fn default_retention_max_age_minutes() -> u64 {
+    120
+}
+
+fn default_retention_max_retained() -> usize {
+    20
+}
+
+impl Default for ScriptRetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age_minutes: default_retention_max_age_minutes(),
+            max_retained: default_retention_max_retained(),
+        }
+    }
+}
+
+/// Per-frame limits of the TUI main loop, so a flood of output can't delay
+/// redraws and key handling. Whatever is left over waits for the next frame.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TuiEventBudgetConfig {
+    /// Queued events taken per frame
+    #[serde(default = "default_budget_max_events")]
+    pub max_events: usize,
+    /// Time spent handling them before the frame is drawn anyway
+    #[serde(default = "default_budget_max_millis")]
+    pub max_millis: u64,
+}
+
+fn default_budget_max_events() -> usize {
+    2000
+}
+
+fn default_budget_max_millis() -> u64 {
+    25
+}
+
+impl Default for TuiEventBudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_events: default_budget_max_events(),
+            max_millis: default_budget_max_millis(),
+        }
+    }
+}
+
+/// Service output written to `<app_dir>/logs/<service_id>/`, one file per
+/// session, rotated by size (see `service_log`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub persist_service_logs: bool,
+    /// A session's file is rotated once the next line would exceed this
+    #[serde(default = "default_log_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Oldest files of a service are deleted beyond this many
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+fn default_log_max_file_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_log_max_files() -> usize {
+    10
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            persist_service_logs: false,
+            max_file_bytes: default_log_max_file_bytes(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellKind {
+    #[default]
+    Default,
+    Sh,
+    Bash,
+    Zsh,
+    Cmd,
+    Powershell,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellConfig {
+    #[serde(default)]
+    pub kind: ShellKind,
+    /// Interpreter for `ShellKind::Custom`, e.g. "/opt/homebrew/bin/fish"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_path: Option<String>,
 }
 
 // Input types for commands
@@ -365,6 +1061,9 @@ pub struct CreateProjectInput {
     pub tags: Vec<String>,
     pub status: Option<String>,
     pub toolbox_url: Option<String>,
+    /// Accept a root another project already has
+    #[serde(default)]
+    pub allow_duplicate: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -377,6 +1076,59 @@ pub struct UpdateProjectInput {
     pub tags: Option<Vec<String>>,
     pub status: Option<String>,
     pub toolbox_url: Option<String>,
+    /// Accept a root another project already has
+    #[serde(default)]
+    pub allow_duplicate: bool,
+}
+
+/// How a project's root relates to another project's (see `project_roots`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RootRelation {
+    /// Both have the same root
+    Same,
+    /// The root is inside the other project's
+    Inside,
+    /// The other project's root is inside this one
+    Contains,
+}
+
+/// Another project whose root is the same as, inside or around a project's
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootOverlap {
+    pub project_id: String,
+    pub project_name: String,
+    pub root_path: String,
+    pub relation: RootRelation,
+}
+
+/// A created or updated project, with the projects its root overlaps
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedProject {
+    #[serde(flatten)]
+    pub project: Project,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overlaps: Vec<RootOverlap>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProjectVariantInput {
+    pub name: String,
+    pub root_path: String,
+    pub env_var_overrides: Option<HashMap<String, String>>,
+}
+
+/// A project after switching variants, with the services still running
+/// under another one
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantSwitch {
+    pub project: Project,
+    /// Ids of the project's running services started under another variant
+    pub still_running: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -393,6 +1145,17 @@ pub struct CreateServiceInput {
     pub color: Option<String>,
     pub port: Option<u16>,
     pub env_vars: Option<HashMap<String, String>>,
+    pub env_mode: Option<EnvMode>,
+    pub watch_paths: Option<Vec<String>>,
+    pub watch_debounce_ms: Option<u64>,
+    pub idle_timeout_secs: Option<u64>,
+    pub idle_action: Option<IdleAction>,
+    pub health_check: Option<HealthCheckConfig>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub restart_backoff_ms: Option<u64>,
+    pub restart_circuit: Option<RestartCircuitConfig>,
+    pub stop_grace_seconds: Option<u64>,
+    pub depends_on: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -409,6 +1172,80 @@ pub struct UpdateServiceInput {
     pub color: Option<String>,
     pub port: Option<u16>,
     pub env_vars: Option<HashMap<String, String>>,
+    pub env_mode: Option<EnvMode>,
+    pub watch_paths: Option<Vec<String>>,
+    pub watch_debounce_ms: Option<u64>,
+    pub idle_timeout_secs: Option<u64>,
+    pub idle_action: Option<IdleAction>,
+    pub health_check: Option<HealthCheckConfig>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub restart_backoff_ms: Option<u64>,
+    pub restart_circuit: Option<RestartCircuitConfig>,
+    pub stop_grace_seconds: Option<u64>,
+    pub depends_on: Option<Vec<String>>,
+}
+
+impl UpdateServiceInput {
+    /// Write the input onto `service`. Fields the form always sends (modes,
+    /// presets, color, port, env vars...) are assigned even when empty, so
+    /// they can be cleared.
+    pub fn apply_to(self, service: &mut Service) {
+        if let Some(name) = self.name {
+            service.name = name;
+        }
+        if let Some(working_dir) = self.working_dir {
+            service.working_dir = working_dir;
+        }
+        if let Some(command) = self.command {
+            service.command = command;
+        }
+        // Always update modes and default_mode to allow clearing them
+        // The frontend sends these fields on every update
+        service.modes = self.modes;
+        service.default_mode = self.default_mode;
+        // Same for arg presets - always update to allow clearing
+        service.extra_args = self.extra_args;
+        service.arg_presets = self.arg_presets;
+        service.default_arg_preset = self.default_arg_preset;
+        // Always assign so the user can clear these fields by submitting them empty.
+        service.color = self.color;
+        service.port = self.port;
+        service.env_vars = self.env_vars;
+        if let Some(env_mode) = self.env_mode {
+            service.env_mode = env_mode;
+        }
+        if let Some(watch_paths) = self.watch_paths {
+            service.watch_paths = watch_paths;
+        }
+        if self.watch_debounce_ms.is_some() {
+            service.watch_debounce_ms = self.watch_debounce_ms;
+        }
+        // 0 turns the idle timeout off
+        if self.idle_timeout_secs.is_some() {
+            service.idle_timeout_secs = self.idle_timeout_secs.filter(|&secs| secs > 0);
+        }
+        if let Some(idle_action) = self.idle_action {
+            service.idle_action = idle_action;
+        }
+        if let Some(health_check) = self.health_check {
+            service.health_check = Some(health_check);
+        }
+        if let Some(restart_policy) = self.restart_policy {
+            service.restart_policy = restart_policy;
+        }
+        if self.restart_backoff_ms.is_some() {
+            service.restart_backoff_ms = self.restart_backoff_ms;
+        }
+        if self.restart_circuit.is_some() {
+            service.restart_circuit = self.restart_circuit;
+        }
+        if self.stop_grace_seconds.is_some() {
+            service.stop_grace_seconds = self.stop_grace_seconds;
+        }
+        if let Some(depends_on) = self.depends_on {
+            service.depends_on = depends_on;
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -421,6 +1258,8 @@ pub struct CreateScriptInput {
     pub working_dir: String,
     pub color: Option<String>,
     pub linked_service_ids: Option<Vec<String>>,
+    pub mutex_group: Option<String>,
+    pub queue_on_mutex: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -433,6 +1272,8 @@ pub struct UpdateScriptInput {
     pub working_dir: Option<String>,
     pub color: Option<String>,
     pub linked_service_ids: Option<Vec<String>>,
+    pub mutex_group: Option<String>,
+    pub queue_on_mutex: Option<bool>,
 }
 
 // Environment file input types
@@ -443,6 +1284,33 @@ pub struct DiscoverEnvFilesInput {
     pub force: bool,
 }
 
+/// Payload of `env-discovery-progress`, sent while an env file discovery
+/// walks the project
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDiscoveryProgress {
+    pub discovery_id: String,
+    pub dirs_scanned: usize,
+    pub files_found: usize,
+    pub current_path: String,
+}
+
+/// Payload of `env-discovery-complete`, sent once a discovery ended
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvDiscoveryComplete {
+    pub discovery_id: String,
+    pub project_id: String,
+    /// The project's env files as saved; empty when cancelled or failed
+    pub env_files: Vec<EnvFile>,
+    /// The walk stopped at the scan budget
+    pub truncated: bool,
+    /// Stopped by `cancel_env_discovery`; nothing was saved
+    pub cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AddEnvFileInput {
@@ -463,7 +1331,71 @@ pub enum ServiceStatus {
     Stopped,
     Starting,
     Running,
+    /// Running, but its health check failed several times in a row
+    Unhealthy,
+    /// Exited on its own; started again after a backoff (`RestartPolicy`)
+    Restarting,
+    /// Asked to exit, within its stop grace period
+    Stopping,
     Error,
+    /// Crashed too often in a short time; not restarted until started by
+    /// hand or its circuit is reset (see [`ServiceCircuit`])
+    #[serde(rename = "circuitOpen")]
+    CircuitOpen,
+}
+
+impl ServiceStatus {
+    /// Whether the process is up, healthy or not
+    pub fn is_up(&self) -> bool {
+        matches!(self, ServiceStatus::Running | ServiceStatus::Unhealthy)
+    }
+}
+
+/// An open restart circuit of a service (`RestartCircuitConfig`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceCircuit {
+    pub service_id: String,
+    /// Failed runs within the window when it opened
+    pub failures: u32,
+    /// How the last run ended, e.g. "code 1" or "killed"
+    pub last_exit: String,
+    pub opened_at: DateTime<Utc>,
+    /// When it's retried once on its own; None waits for a manual start
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Latest health check of a running service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceHealth {
+    pub service_id: String,
+    /// The run being checked
+    pub pid: u32,
+    /// False once `failure_threshold` checks in a row failed
+    pub healthy: bool,
+    /// None until the first check
+    pub last_check_at: Option<DateTime<Utc>>,
+    /// How long the last successful check took
+    pub latency_ms: Option<u64>,
+    pub consecutive_failures: u32,
+    /// Why the last check failed, while it keeps failing
+    pub last_error: Option<String>,
+}
+
+impl ServiceHealth {
+    /// Healthy until proven otherwise
+    pub fn new(service_id: String, pid: u32) -> Self {
+        Self {
+            service_id,
+            pid,
+            healthy: true,
+            last_check_at: None,
+            latency_ms: None,
+            consecutive_failures: 0,
+            last_error: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -474,6 +1406,9 @@ pub struct ServiceState {
     pub pid: Option<u32>,
     pub active_mode: Option<String>,
     pub active_arg_preset: Option<String>,
+    /// When the running process was spawned (None unless running)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -481,6 +1416,8 @@ pub struct ServiceState {
 pub enum LogStream {
     Stdout,
     Stderr,
+    /// The command a run starts with (`ScriptsConfig::echo_command`)
+    Command,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -499,6 +1436,8 @@ pub struct ServiceLogPayload {
     pub service_id: String,
     pub stream: LogStream,
     pub content: String,
+    /// When the line was read from the process
+    pub timestamp: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -509,6 +1448,9 @@ pub struct ServiceStatusPayload {
     pub pid: Option<u32>,
     pub active_mode: Option<String>,
     pub active_arg_preset: Option<String>,
+    /// When the running process was spawned (None unless running)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -516,6 +1458,10 @@ pub struct ServiceStatusPayload {
 pub struct ServiceExitPayload {
     pub service_id: String,
     pub exit_code: Option<i32>,
+    /// Last stderr lines, set only when the service failed right after
+    /// starting (it is then reported with `ServiceStatus::Error`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_excerpt: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -540,6 +1486,11 @@ pub struct ScriptLogPayload {
     pub script_id: String,
     pub stream: LogStream,
     pub content: String,
+    /// When the line was read from the process
+    pub timestamp: DateTime<Utc>,
+    /// Set on the concurrent runs of a global script, next to its first run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -548,6 +1499,9 @@ pub struct ScriptStatusPayload {
     pub script_id: String,
     pub status: ScriptStatus,
     pub pid: Option<u32>,
+    /// Set on the concurrent runs of a global script, next to its first run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -556,6 +1510,17 @@ pub struct ScriptExitPayload {
     pub script_id: String,
     pub exit_code: Option<i32>,
     pub success: bool,
+    /// Set on the concurrent runs of a global script, next to its first run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+/// Payload of `file-dropped-for-param`, sent when files are dropped on the
+/// window so an open run form can fill a parameter with them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDroppedPayload {
+    pub paths: Vec<String>,
 }
 
 // ============================================================================
@@ -574,7 +1539,7 @@ pub enum ScriptParamType {
     Path,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptParameter {
     pub name: String,
@@ -592,6 +1557,19 @@ pub struct ScriptParameter {
     pub enum_values: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nargs: Option<String>,
+    /// Parameters sharing a group are mutually exclusive (argparse
+    /// `[--json | --yaml]`): at most one of them reaches the command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutex_group: Option<String>,
+}
+
+/// Output of running a command's `--help`: the detected parameters plus the
+/// raw text, which is kept on the script for the per-parameter help popup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedHelp {
+    pub parameters: Vec<ScriptParameter>,
+    pub raw_help: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -625,6 +1603,9 @@ pub struct GlobalScript {
     pub color: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Optional "/"-separated folder path used to group scripts (e.g. "deploy/aws")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
     #[serde(default)]
     pub parameters: Vec<ScriptParameter>,
     #[serde(default)]
@@ -633,13 +1614,77 @@ pub struct GlobalScript {
     pub default_preset_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env_vars: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "EnvMode::is_inherit")]
+    pub env_mode: EnvMode,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub order: u32,
     #[serde(default)]
     pub auto_discovered: bool,
+    /// Hash of `script_path`'s content when it was imported, used to follow
+    /// the file when it moves (see `script_relocation`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
+    /// Full `--help` output from the last parameter detection (size-capped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_help: Option<String>,
+    /// Services that must be running before the script is run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<ServiceRequirement>,
+    /// Glob patterns, relative to the working dir, for the files a run
+    /// creates (see `script_artifacts`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Set on scripts imported from an export, to sync them with later
+    /// versions of it (see `script_sync`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<ScriptOrigin>,
+    /// How its output is displayed; the logs keep the raw lines
+    #[serde(default, skip_serializing_if = "OutputFormat::is_raw")]
+    pub output_format: OutputFormat,
+    /// Runs may overlap: starting it while it runs starts another run
+    /// instead of refusing
+    #[serde(default)]
+    pub allow_concurrent: bool,
+    /// Scripts sharing a mutex group never run at the same time (see
+    /// `mutex_group`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutex_group: Option<String>,
+    /// Wait for the mutex group to be free instead of refusing to start
+    #[serde(default)]
+    pub queue_on_mutex: bool,
+    /// Cron expression it runs on by itself, with its default preset (see
+    /// `scheduler`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+}
+
+/// Where an imported script came from, and the upstream version it was
+/// last in sync with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptOrigin {
+    /// Id of the script in the export (stays the same across its versions)
+    pub source_id: String,
+    /// Its name there, as of the last sync
+    pub source_name: String,
+    pub imported_at: DateTime<Utc>,
+    /// `script_sync::content_hash` of the upstream version last applied
+    pub last_synced_hash: String,
+}
+
+/// A project service a global script needs running. Project and service are
+/// named by id or by name (see `script_requirements`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceRequirement {
+    pub project_name_or_id: String,
+    pub service_name_or_id: String,
+    /// May be started on the user's behalf when it isn't running
+    #[serde(default)]
+    pub auto_start: bool,
 }
 
 impl GlobalScript {
@@ -654,19 +1699,61 @@ impl GlobalScript {
             working_dir,
             color: None,
             tags: Vec::new(),
+            folder: None,
             parameters: Vec::new(),
             parameter_presets: Vec::new(),
             default_preset_id: None,
             env_vars: None,
+            env_mode: EnvMode::Inherit,
             created_at: now,
             updated_at: now,
             order: 0,
             auto_discovered: false,
+            content_hash: None,
             status: None,
+            raw_help: None,
+            requires: Vec::new(),
+            artifacts: Vec::new(),
+            origin: None,
+            output_format: OutputFormat::Raw,
+            allow_concurrent: false,
+            mutex_group: None,
+            queue_on_mutex: false,
+            schedule: None,
         }
     }
 }
 
+// Script DAG runs
+
+/// One global script of a DAG run, and the scripts of the same run that
+/// must succeed before it starts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDependency {
+    pub script_id: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ScriptNodeOutcome {
+    Succeeded,
+    Failed { error: String },
+    /// Not run: a script it depends on (directly or not) failed
+    #[serde(rename_all = "camelCase")]
+    Skipped { failed_dependency: String },
+}
+
+/// How one script of a DAG run went
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptNodeResult {
+    pub script_id: String,
+    pub outcome: ScriptNodeOutcome,
+}
+
 // Tag Definitions (enriched tags with color/order)
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -685,6 +1772,7 @@ pub struct TagDefinition {
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionRecord {
     pub id: String,
+    /// The global script that ran, or the service for service runs
     pub script_id: String,
     pub started_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -698,6 +1786,30 @@ pub struct ExecutionRecord {
     pub parameters_used: HashMap<String, String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preset_name: Option<String>,
+    /// Working directory and extra args of the run, kept so it can be replayed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_args: Vec<String>,
+    /// `command_builder::parameters_signature` of the script at run time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters_signature: Option<String>,
+    /// Byte range of this run's output in the script's runtime log (which
+    /// every run appends to), so runs can be compared later
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_start: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_end: Option<u64>,
+    /// Env vars the run injected (`spawn_env::env_snapshot`: secrets masked
+    /// or omitted, size-bounded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_snapshot: Option<HashMap<String, String>>,
+    /// Files matching the script's `artifacts` patterns after a successful run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Started by the script's schedule rather than by hand
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub scheduled: bool,
 }
 
 impl ExecutionRecord {
@@ -712,6 +1824,14 @@ impl ExecutionRecord {
             exit_code: None,
             parameters_used: HashMap::new(),
             preset_name: None,
+            working_dir: None,
+            extra_args: Vec::new(),
+            parameters_signature: None,
+            log_start: None,
+            log_end: None,
+            env_snapshot: None,
+            artifacts: Vec::new(),
+            scheduled: false,
         }
     }
 }
@@ -731,6 +1851,111 @@ pub struct ScriptsConfig {
     pub auto_scan_on_startup: bool,
     #[serde(default = "default_command_templates")]
     pub command_templates: HashMap<String, String>,
+    /// Stop a folder scan after visiting this many entries
+    #[serde(default = "default_scan_max_entries")]
+    pub scan_max_entries: usize,
+    /// Stop a folder scan after this many seconds
+    #[serde(default = "default_scan_max_seconds")]
+    pub scan_max_seconds: u64,
+    /// Folder / tag / color assignment applied to newly scanned scripts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub organize_rules: Vec<OrganizeRule>,
+    /// Extra regexes masked out of logs before two runs are diffed, on top
+    /// of `run_compare::DEFAULT_NOISE_PATTERNS`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_noise_patterns: Vec<String>,
+    /// What execution history records of a run's env vars
+    #[serde(default)]
+    pub env_snapshot: EnvSnapshotConfig,
+    /// How many global script runs may nest through cortx invoking itself
+    /// (0 = no limit; cycles are refused regardless)
+    #[serde(default = "default_max_run_depth")]
+    pub max_run_depth: u32,
+    /// Start each run's output with `$ <resolved command>` and its working dir
+    #[serde(default)]
+    pub echo_command: bool,
+}
+
+/// How secret-looking env vars appear in a run's recorded snapshot.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretEnvHandling {
+    /// Key kept, value replaced
+    #[default]
+    Mask,
+    /// Left out entirely
+    Omit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvSnapshotConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub secrets: SecretEnvHandling,
+    /// Globs (`*`, `?`, case-insensitive) naming secret variables
+    #[serde(default = "default_secret_env_patterns")]
+    pub secret_patterns: Vec<String>,
+}
+
+fn default_secret_env_patterns() -> Vec<String> {
+    ["*TOKEN*", "*SECRET*", "*PASSWORD*", "*PASSWD*", "*KEY*", "*CREDENTIAL*", "*AUTH*"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+impl Default for EnvSnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            secrets: SecretEnvHandling::default(),
+            secret_patterns: default_secret_env_patterns(),
+        }
+    }
+}
+
+/// What an [`OrganizeRule`]'s `pattern` is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrganizeMatch {
+    /// `*` / `?` glob over the script name
+    NameGlob,
+    /// `*` / `?` glob over the script file path ("/"-separated)
+    PathGlob,
+    /// Script file extension, with or without the leading dot
+    ExtensionIs,
+    /// Substring of the command
+    CommandContains,
+}
+
+/// Organizes scripts that match `pattern` (case-insensitive). Rules run in
+/// order: tags accumulate, the last matching folder / color wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeRule {
+    #[serde(rename = "match")]
+    pub match_on: OrganizeMatch,
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assign_folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_color: Option<String>,
+}
+
+fn default_max_run_depth() -> u32 {
+    crate::run_guard::DEFAULT_MAX_RUN_DEPTH
+}
+
+fn default_scan_max_entries() -> usize {
+    100_000
+}
+
+fn default_scan_max_seconds() -> u64 {
+    10
 }
 
 fn default_scan_extensions() -> Vec<String> {
@@ -799,6 +2024,13 @@ impl Default for ScriptsConfig {
             ignored_patterns: default_ignored_patterns(),
             auto_scan_on_startup: false,
             command_templates: default_command_templates(),
+            scan_max_entries: default_scan_max_entries(),
+            scan_max_seconds: default_scan_max_seconds(),
+            organize_rules: Vec::new(),
+            log_noise_patterns: Vec::new(),
+            env_snapshot: EnvSnapshotConfig::default(),
+            max_run_depth: default_max_run_depth(),
+            echo_command: false,
         }
     }
 }
@@ -813,6 +2045,80 @@ pub struct DiscoveredScript {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub extension: String,
+    /// Content hash, unless the file is too large to hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// Result of scanning a folder for scripts. `truncated` means the scan hit
+/// its entry / time budget and `scripts` may be incomplete.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptScanResult {
+    pub scripts: Vec<DiscoveredScript>,
+    pub entries_visited: usize,
+    pub truncated: bool,
+    /// Auto-discovered scripts whose file moved, now pointing at it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relocated: Vec<ScriptRelocation>,
+    /// Files that may be a moved script but weren't relinked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved_moves: Vec<UnresolvedMove>,
+    /// Auto-discovered scripts whose file is gone and wasn't found elsewhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vanished: Vec<VanishedScript>,
+}
+
+/// An auto-discovered script whose `script_path` no longer exists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VanishedScript {
+    pub script_id: String,
+    pub script_name: String,
+    pub path: String,
+}
+
+/// Result of importing discovered script files as global scripts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredImport {
+    pub imported: Vec<GlobalScript>,
+    /// Paths left out: already registered, or no longer a readable file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+    /// How many of the imported scripts the organize rules changed
+    #[serde(default)]
+    pub organized: usize,
+}
+
+/// An auto-discovered script whose file was found at a new path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRelocation {
+    pub script_id: String,
+    pub script_name: String,
+    pub old_path: String,
+    pub new_path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnresolvedMoveReason {
+    /// More than one script could have moved here, or the script could have
+    /// moved to more than one file
+    Ambiguous,
+    /// Same file name as a missing script, but different content
+    ContentChanged,
+}
+
+/// A discovered file left for the user to match up by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedMove {
+    pub path: String,
+    /// Missing scripts it may belong to
+    pub candidate_ids: Vec<String>,
+    pub reason: UnresolvedMoveReason,
 }
 
 // Discovered tool (from package manager scanning)
@@ -840,10 +2146,21 @@ pub struct CreateGlobalScriptInput {
     pub working_dir: Option<String>,
     pub color: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub folder: Option<String>,
     pub parameters: Option<Vec<ScriptParameter>>,
     pub parameter_presets: Option<Vec<ParameterPreset>>,
     pub env_vars: Option<HashMap<String, String>>,
+    pub env_mode: Option<EnvMode>,
     pub status: Option<String>,
+    pub requires: Option<Vec<ServiceRequirement>>,
+    pub artifacts: Option<Vec<String>>,
+    pub output_format: Option<OutputFormat>,
+    /// Imported from a folder scan
+    pub auto_discovered: Option<bool>,
+    pub allow_concurrent: Option<bool>,
+    pub mutex_group: Option<String>,
+    pub queue_on_mutex: Option<bool>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -856,11 +2173,21 @@ pub struct UpdateGlobalScriptInput {
     pub working_dir: Option<String>,
     pub color: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub folder: Option<String>,
     pub parameters: Option<Vec<ScriptParameter>>,
     pub parameter_presets: Option<Vec<ParameterPreset>>,
     pub default_preset_id: Option<String>,
     pub env_vars: Option<HashMap<String, String>>,
+    pub env_mode: Option<EnvMode>,
     pub status: Option<String>,
+    pub raw_help: Option<String>,
+    pub requires: Option<Vec<ServiceRequirement>>,
+    pub artifacts: Option<Vec<String>>,
+    pub output_format: Option<OutputFormat>,
+    pub allow_concurrent: Option<bool>,
+    pub mutex_group: Option<String>,
+    pub queue_on_mutex: Option<bool>,
+    pub schedule: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -907,6 +2234,54 @@ pub struct ScriptExport {
     pub exported_at: DateTime<Utc>,
 }
 
+/// A projects-only export (see `Storage::export_projects_config`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectExport {
+    pub version: String,
+    pub projects: Vec<Project>,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// `manifest.json` of a script directory export (see
+/// `Storage::export_scripts_to_dir`). The scripts themselves are one file
+/// each under `scripts/`; the manifest keeps their order and what they
+/// reference. No timestamp, so re-exporting unchanged scripts is a no-op diff.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDirManifest {
+    pub version: String,
+    /// Script file names, in the app's order
+    #[serde(default)]
+    pub scripts: Vec<String>,
+    /// Folder path → the script files in it
+    #[serde(default)]
+    pub folders: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub tag_definitions: Vec<TagDefinition>,
+    #[serde(default)]
+    pub status_definitions: Vec<StatusDefinition>,
+}
+
+/// A zip backup of the data files (see `Storage::create_backup`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    /// Data files replaced from the backup
+    pub restored_files: Vec<String>,
+    /// Backup of the files as they were before the restore
+    pub previous: BackupInfo,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportResult {
@@ -921,6 +2296,58 @@ pub struct ImportResult {
     pub settings_imported: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectImportResult {
+    pub projects_added: u32,
+    /// Projects whose id is already known
+    pub skipped: u32,
+    /// Added projects whose root path doesn't exist on this machine
+    pub needs_path: Vec<PathRemapRequest>,
+}
+
+/// One of the JSON files `Storage` keeps its data in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageFile {
+    Projects,
+    Settings,
+    GlobalScripts,
+    TagDefinitions,
+    ExecutionHistory,
+    Tools,
+    Aliases,
+    StatusDefinitions,
+    Apps,
+}
+
+impl StorageFile {
+    /// What the file holds, for messages ("failed to save settings")
+    pub fn label(&self) -> &'static str {
+        match self {
+            StorageFile::Projects => "projects",
+            StorageFile::Settings => "settings",
+            StorageFile::GlobalScripts => "scripts",
+            StorageFile::TagDefinitions => "tags",
+            StorageFile::ExecutionHistory => "run history",
+            StorageFile::Tools => "tools",
+            StorageFile::Aliases => "aliases",
+            StorageFile::StatusDefinitions => "statuses",
+            StorageFile::Apps => "apps",
+        }
+    }
+}
+
+/// A data file whose latest changes are in memory but couldn't be written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSave {
+    pub file: StorageFile,
+    /// Why the last attempt failed
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
 fn default_true() -> bool {
     true
 }
@@ -973,6 +2400,75 @@ pub struct ExportSummary {
     pub has_settings: bool,
 }
 
+/// An import file picked through a dialog: its content plus the preview.
+/// Nothing is imported until the content is passed to `import_scripts_config`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFilePreview {
+    pub path: String,
+    pub json: String,
+    pub summary: ExportSummary,
+}
+
+// ============================================================================
+// Data-dir migration
+// ============================================================================
+
+/// How an item found in a foreign data dir relates to the local data
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationItemStatus {
+    /// Not present locally; will be added
+    New,
+    /// Same id and same content; nothing to do
+    Identical,
+    /// Same id with different content, or same name under another id; skipped
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationItem {
+    /// "project", "script", "tool", "app", "alias", "tag" or "status"
+    pub kind: String,
+    pub id: String,
+    pub name: String,
+    pub status: MigrationItemStatus,
+}
+
+/// A project whose `root_path` doesn't exist on this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathRemapRequest {
+    pub project_id: String,
+    pub project_name: String,
+    pub old_root_path: String,
+}
+
+/// New location chosen for a project listed in `MigrationReport::needs_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMapping {
+    pub project_id: String,
+    pub new_root_path: String,
+}
+
+/// Side-effect free preview of importing another machine's data dir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub source_dir: String,
+    pub items: Vec<MigrationItem>,
+    pub needs_path: Vec<PathRemapRequest>,
+    /// Image files that will be copied into the local images dir
+    pub images_to_copy: Vec<String>,
+    pub has_settings: bool,
+    /// Files or entries that couldn't be read (file name + reason)
+    pub problems: Vec<String>,
+    /// Data files present in the source that this version doesn't import
+    pub ignored_files: Vec<String>,
+}
+
 // ============================================================================
 // Shell Aliases
 // ============================================================================
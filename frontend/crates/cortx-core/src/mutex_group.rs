@@ -0,0 +1,226 @@
+//! Mutex groups: scripts that name the same group (`GlobalScript::mutex_group`,
+//! `Script::mutex_group`) never run at the same time, even when they are
+//! different scripts.
+//!
+//! A run holds its group through a [`GroupLock`] kept with its process, so
+//! the group is released wherever the process leaves its map — the reaper
+//! noticing the exit (also when it was killed from outside), a stop,
+//! `stop_all` — and when the spawn fails. Groups are held per CortX
+//! instance.
+
+use chrono::{DateTime, Utc};
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often a queued run checks whether it should give up waiting
+const WAIT_POLL: Duration = Duration::from_millis(250);
+
+/// The run holding a group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    /// Id of the script (or `run_key` of the run) holding it
+    pub id: String,
+    pub name: String,
+    pub since: DateTime<Utc>,
+}
+
+impl Holder {
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            since: Utc::now(),
+        }
+    }
+}
+
+/// Which groups are held, and by whom
+#[derive(Default)]
+pub struct MutexGroups {
+    held: Mutex<HashMap<String, Holder>>,
+    released: Condvar,
+}
+
+impl MutexGroups {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Take `group` for `holder`, or get the run that holds it
+    pub fn try_acquire(self: &Arc<Self>, group: &str, holder: Holder) -> Result<GroupLock, Holder> {
+        let mut held = self.held.lock();
+        if let Some(current) = held.get(group) {
+            return Err(current.clone());
+        }
+        held.insert(group.to_string(), holder);
+        Ok(GroupLock {
+            groups: self.clone(),
+            group: group.to_string(),
+        })
+    }
+
+    /// Wait until `group` is free and take it. `None` once `give_up` returns
+    /// true (checked every [`WAIT_POLL`]). Waiting runs take the group in no
+    /// particular order.
+    pub fn acquire_waiting(
+        self: &Arc<Self>,
+        group: &str,
+        holder: Holder,
+        give_up: impl Fn() -> bool,
+    ) -> Option<GroupLock> {
+        let mut held = self.held.lock();
+        while held.contains_key(group) {
+            if give_up() {
+                return None;
+            }
+            self.released.wait_for(&mut held, WAIT_POLL);
+        }
+        held.insert(group.to_string(), Holder { since: Utc::now(), ..holder });
+        Some(GroupLock {
+            groups: self.clone(),
+            group: group.to_string(),
+        })
+    }
+
+    /// The run holding `group`, if any
+    pub fn holder(&self, group: &str) -> Option<Holder> {
+        self.held.lock().get(group).cloned()
+    }
+
+    fn release(&self, group: &str) {
+        self.held.lock().remove(group);
+        self.released.notify_all();
+    }
+}
+
+/// A held group, released when dropped
+pub struct GroupLock {
+    groups: Arc<MutexGroups>,
+    group: String,
+}
+
+impl GroupLock {
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+}
+
+impl Drop for GroupLock {
+    fn drop(&mut self) {
+        self.groups.release(&self.group);
+    }
+}
+
+impl std::fmt::Debug for GroupLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GroupLock").field("group", &self.group).finish()
+    }
+}
+
+/// Why a run of `group` can't start: "blocked by 'db-migrate-prod', running
+/// for 2m05s (mutex group 'db')"
+pub fn blocked_message(group: &str, holder: &Holder) -> String {
+    let running_for = (Utc::now() - holder.since).to_std().unwrap_or_default();
+    format!(
+        "Blocked by '{}', running for {} (mutex group '{}')",
+        holder.name,
+        crate::runtime_state::format_uptime(running_for),
+        group
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+
+    #[test]
+    fn simultaneous_starts_race_and_exactly_one_wins() {
+        for _ in 0..50 {
+            let groups = MutexGroups::new();
+            let barrier = Arc::new(Barrier::new(8));
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let groups = groups.clone();
+                    let barrier = barrier.clone();
+                    thread::spawn(move || {
+                        barrier.wait();
+                        groups.try_acquire("db", Holder::new(format!("s{}", i), format!("script {}", i)))
+                    })
+                })
+                .collect();
+            let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+            let winners: Vec<&GroupLock> = results.iter().filter_map(|r| r.as_ref().ok()).collect();
+            assert_eq!(winners.len(), 1);
+
+            // The losers were told who won
+            let holder = groups.holder("db").unwrap();
+            for result in &results {
+                if let Err(blocker) = result {
+                    assert_eq!(blocker, &holder);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dropping_the_lock_releases_the_group() {
+        let groups = MutexGroups::new();
+        let lock = groups.try_acquire("db", Holder::new("a", "migrate")).unwrap();
+        assert_eq!(lock.group(), "db");
+        let blocker = groups.try_acquire("db", Holder::new("b", "seed")).unwrap_err();
+        assert_eq!(blocker.name, "migrate");
+        assert!(blocked_message("db", &blocker).starts_with("Blocked by 'migrate', running for 0s"));
+
+        // Other groups are independent
+        let _other = groups.try_acquire("cache", Holder::new("b", "seed")).unwrap();
+
+        drop(lock);
+        assert_eq!(groups.holder("db"), None);
+        assert!(groups.try_acquire("db", Holder::new("b", "seed")).is_ok());
+    }
+
+    #[test]
+    fn queued_runs_take_the_group_one_after_another() {
+        let groups = MutexGroups::new();
+        let first = groups.try_acquire("db", Holder::new("a", "a")).unwrap();
+        let inside = Arc::new(AtomicUsize::new(0));
+        let most_inside = Arc::new(AtomicUsize::new(0));
+
+        let waiters: Vec<_> = (0..4)
+            .map(|i| {
+                let groups = groups.clone();
+                let inside = inside.clone();
+                let most_inside = most_inside.clone();
+                thread::spawn(move || {
+                    let lock = groups.acquire_waiting("db", Holder::new(format!("w{}", i), "w"), || false);
+                    assert!(lock.is_some());
+                    let now = inside.fetch_add(1, Ordering::SeqCst) + 1;
+                    most_inside.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    inside.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(inside.load(Ordering::SeqCst), 0);
+        drop(first);
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+        assert_eq!(most_inside.load(Ordering::SeqCst), 1);
+        assert_eq!(groups.holder("db"), None);
+
+        // A waiter that gives up doesn't take the group
+        let _held = groups.try_acquire("db", Holder::new("a", "a")).unwrap();
+        let stop = AtomicBool::new(true);
+        assert!(groups
+            .acquire_waiting("db", Holder::new("b", "b"), || stop.load(Ordering::SeqCst))
+            .is_none());
+    }
+}
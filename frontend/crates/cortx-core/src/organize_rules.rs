@@ -0,0 +1,279 @@
+//! Batch folder / tag / color assignment for global scripts.
+//!
+//! `ScriptsConfig::organize_rules` are evaluated in order against each
+//! script. Every matching rule contributes: tags are unioned, and the last
+//! matching rule that sets a folder (or color) wins. [`plan_changes`] only
+//! computes what would change; `Storage::apply_organize_rules` applies a plan
+//! in one save. Folders aren't stored separately, so assigning a folder no
+//! script uses yet creates it — such changes are flagged.
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::models::{GlobalScript, OrganizeMatch, OrganizeRule};
+use crate::spawn_env::glob_match;
+
+/// What the rules would do (or did) to one script. Only fields that actually
+/// change are set; scripts the rules leave untouched get no entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    pub script_id: String,
+    pub script_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub add_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// `folder` isn't used by any script yet
+    pub creates_folder: bool,
+}
+
+impl PlannedChange {
+    pub fn apply(&self, script: &mut GlobalScript) {
+        if let Some(folder) = &self.folder {
+            script.folder = Some(folder.clone());
+        }
+        script.tags.extend(self.add_tags.iter().cloned());
+        if let Some(color) = &self.color {
+            script.color = Some(color.clone());
+        }
+    }
+
+    /// Short description, e.g. `folder → infra, +deploy, color #22c55e`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(folder) = &self.folder {
+            let new = if self.creates_folder { " (new)" } else { "" };
+            parts.push(format!("folder → {}{}", folder, new));
+        }
+        parts.extend(self.add_tags.iter().map(|t| format!("+{}", t)));
+        if let Some(color) = &self.color {
+            parts.push(format!("color {}", color));
+        }
+        parts.join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeResult {
+    pub changes: Vec<PlannedChange>,
+    /// Folders the changes create, in first-use order
+    pub created_folders: Vec<String>,
+    /// False for a dry run
+    pub applied: bool,
+}
+
+impl OrganizeResult {
+    pub fn new(changes: Vec<PlannedChange>, applied: bool) -> Self {
+        let mut created_folders: Vec<String> = Vec::new();
+        for folder in changes.iter().filter(|c| c.creates_folder).filter_map(|c| c.folder.as_ref()) {
+            if !created_folders.contains(folder) {
+                created_folders.push(folder.clone());
+            }
+        }
+        Self { changes, created_folders, applied }
+    }
+}
+
+/// Whether `rule` applies to `script`. Matching is case-insensitive.
+pub fn rule_matches(rule: &OrganizeRule, script: &GlobalScript) -> bool {
+    let pattern = rule.pattern.trim().to_lowercase();
+    if pattern.is_empty() {
+        return false;
+    }
+    match rule.match_on {
+        OrganizeMatch::NameGlob => glob_match(&pattern, &script.name.to_lowercase()),
+        OrganizeMatch::PathGlob => script.script_path.as_deref().is_some_and(|path| {
+            glob_match(&pattern.replace('\\', "/"), &path.replace('\\', "/").to_lowercase())
+        }),
+        OrganizeMatch::ExtensionIs => script
+            .script_path
+            .as_deref()
+            .and_then(|path| std::path::Path::new(path).extension())
+            .is_some_and(|ext| ext.to_string_lossy().to_lowercase() == pattern.trim_start_matches('.')),
+        OrganizeMatch::CommandContains => script.command.to_lowercase().contains(&pattern),
+    }
+}
+
+fn normalize_folder(folder: &str) -> Option<String> {
+    let folder = folder.trim().trim_matches('/');
+    (!folder.is_empty()).then(|| folder.to_string())
+}
+
+/// The changes `rules` make to `scripts` (all of them, or only those in
+/// `script_ids`), in script order.
+pub fn plan_changes(
+    rules: &[OrganizeRule],
+    scripts: &[GlobalScript],
+    script_ids: Option<&[String]>,
+) -> Vec<PlannedChange> {
+    let existing_folders: HashSet<String> = scripts
+        .iter()
+        .filter_map(|s| s.folder.as_deref().and_then(normalize_folder))
+        .collect();
+
+    scripts
+        .iter()
+        .filter(|s| match script_ids {
+            Some(ids) => ids.contains(&s.id),
+            None => true,
+        })
+        .filter_map(|script| {
+            let mut folder: Option<String> = None;
+            let mut color: Option<String> = None;
+            let mut add_tags: Vec<String> = Vec::new();
+            for rule in rules.iter().filter(|r| rule_matches(r, script)) {
+                if let Some(f) = rule.assign_folder.as_deref().and_then(normalize_folder) {
+                    folder = Some(f);
+                }
+                if let Some(c) = rule.set_color.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+                    color = Some(c.to_string());
+                }
+                for tag in rule.add_tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+                    let known = script.tags.iter().chain(add_tags.iter()).any(|t| t.eq_ignore_ascii_case(tag));
+                    if !known {
+                        add_tags.push(tag.to_string());
+                    }
+                }
+            }
+
+            let current_folder = script.folder.as_deref().and_then(normalize_folder);
+            let folder = folder.filter(|f| current_folder.as_ref() != Some(f));
+            let color = color.filter(|c| script.color.as_deref() != Some(c.as_str()));
+            if folder.is_none() && color.is_none() && add_tags.is_empty() {
+                return None;
+            }
+            Some(PlannedChange {
+                script_id: script.id.clone(),
+                script_name: script.name.clone(),
+                creates_folder: folder.as_ref().is_some_and(|f| !existing_folders.contains(f)),
+                folder,
+                add_tags,
+                color,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(name: &str, path: Option<&str>, command: &str) -> GlobalScript {
+        let mut s = GlobalScript::new(name.to_string(), command.to_string(), None);
+        s.script_path = path.map(str::to_string);
+        s
+    }
+
+    fn rule(match_on: OrganizeMatch, pattern: &str) -> OrganizeRule {
+        OrganizeRule {
+            match_on,
+            pattern: pattern.to_string(),
+            assign_folder: None,
+            add_tags: Vec::new(),
+            set_color: None,
+        }
+    }
+
+    fn synthetic_scripts() -> Vec<GlobalScript> {
+        let mut scripts = vec![
+            script("deploy-api", Some("/home/me/scripts/deploy/api.sh"), "bash {{SCRIPT_FILE}}"),
+            script("Deploy-Web", Some("C:\\scripts\\deploy\\web.ps1"), "pwsh {{SCRIPT_FILE}}"),
+            script("seed_db", Some("/home/me/scripts/db/seed.py"), "uv run {{SCRIPT_FILE}}"),
+            script("backup", None, "pg_dump shop > shop.sql"),
+        ];
+        scripts[3].folder = Some("db".into());
+        scripts
+    }
+
+    #[test]
+    fn matchers() {
+        let scripts = synthetic_scripts();
+        let matching = |r: &OrganizeRule| -> Vec<&str> {
+            scripts.iter().filter(|s| rule_matches(r, s)).map(|s| s.name.as_str()).collect()
+        };
+        assert_eq!(matching(&rule(OrganizeMatch::NameGlob, "deploy-*")), vec!["deploy-api", "Deploy-Web"]);
+        assert_eq!(matching(&rule(OrganizeMatch::PathGlob, "*/deploy/*")), vec!["deploy-api", "Deploy-Web"]);
+        assert_eq!(matching(&rule(OrganizeMatch::ExtensionIs, ".PY")), vec!["seed_db"]);
+        assert_eq!(matching(&rule(OrganizeMatch::ExtensionIs, "ps1")), vec!["Deploy-Web"]);
+        assert_eq!(matching(&rule(OrganizeMatch::CommandContains, "PG_DUMP")), vec!["backup"]);
+        assert!(matching(&rule(OrganizeMatch::CommandContains, " ")).is_empty());
+    }
+
+    #[test]
+    fn later_rules_add_to_earlier_ones() {
+        let scripts = synthetic_scripts();
+        let mut deploy = rule(OrganizeMatch::NameGlob, "deploy-*");
+        deploy.assign_folder = Some("deploy".into());
+        deploy.add_tags = vec!["deploy".into()];
+        deploy.set_color = Some("#ef4444".into());
+        let mut windows = rule(OrganizeMatch::ExtensionIs, "ps1");
+        windows.assign_folder = Some("/deploy/windows/".into());
+        windows.add_tags = vec!["windows".into(), "DEPLOY".into()];
+
+        let changes = plan_changes(&[deploy, windows], &scripts, None);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].script_name, "deploy-api");
+        assert_eq!(changes[0].folder.as_deref(), Some("deploy"));
+        assert_eq!(changes[0].add_tags, vec!["deploy"]);
+
+        // Folder: last writer wins; tags: union without case duplicates
+        assert_eq!(changes[1].folder.as_deref(), Some("deploy/windows"));
+        assert_eq!(changes[1].add_tags, vec!["deploy", "windows"]);
+        assert_eq!(changes[1].color.as_deref(), Some("#ef4444"));
+        assert_eq!(changes[1].summary(), "folder → deploy/windows (new), +deploy, +windows, color #ef4444");
+    }
+
+    #[test]
+    fn unchanged_scripts_are_left_out() {
+        let mut scripts = synthetic_scripts();
+        scripts[2].tags = vec!["DB".into()];
+        let mut db = rule(OrganizeMatch::NameGlob, "*");
+        db.assign_folder = Some("db".into());
+        db.add_tags = vec!["db".into()];
+        let rules = [db];
+
+        let changes = plan_changes(&rules, &scripts, None);
+        assert_eq!(changes.len(), 4);
+        // seed_db already has the tag, backup is already in the folder
+        assert!(changes[2].add_tags.is_empty());
+        assert_eq!(changes[2].folder.as_deref(), Some("db"));
+        assert_eq!(changes[3].folder, None);
+        assert_eq!(changes[3].add_tags, vec!["db"]);
+
+        // Once applied, running the rules again changes nothing
+        for change in &changes {
+            let script = scripts.iter_mut().find(|s| s.id == change.script_id).unwrap();
+            change.apply(script);
+        }
+        assert!(plan_changes(&rules, &scripts, None).is_empty());
+    }
+
+    #[test]
+    fn new_folders_are_flagged_and_ids_filter() {
+        let scripts = synthetic_scripts();
+        let mut python = rule(OrganizeMatch::ExtensionIs, "py");
+        python.assign_folder = Some("python".into());
+        let mut seed = rule(OrganizeMatch::NameGlob, "seed*");
+        seed.assign_folder = Some("db".into());
+        let rules = [python, seed];
+
+        let changes = plan_changes(&rules[..1], &scripts, None);
+        assert!(changes[0].creates_folder);
+        let result = OrganizeResult::new(changes, false);
+        assert_eq!(result.created_folders, vec!["python"]);
+
+        // "db" is already used by `backup`
+        let changes = plan_changes(&rules, &scripts, None);
+        assert_eq!(changes[0].folder.as_deref(), Some("db"));
+        assert!(!changes[0].creates_folder);
+
+        let ids = vec![scripts[0].id.clone()];
+        assert!(plan_changes(&rules, &scripts, Some(&ids)).is_empty());
+        let ids = vec![scripts[2].id.clone()];
+        assert_eq!(plan_changes(&rules, &scripts, Some(&ids)).len(), 1);
+    }
+}
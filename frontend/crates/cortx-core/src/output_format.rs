@@ -0,0 +1,246 @@
+//! Display formatting of script output.
+//!
+//! A global script's `output_format` picks how the output panels show its
+//! stdout: as is, JSON pretty-printed, or JSON records as a table. Only the
+//! display changes; logs, history and exports keep the raw lines. JSON may
+//! be a single line or a block spread over several lines; anything that
+//! doesn't parse is shown as is.
+
+use serde_json::Value;
+
+use crate::models::OutputFormat;
+
+/// Give up on a JSON block that hasn't closed after this many lines
+const MAX_BLOCK_LINES: usize = 500;
+
+/// Widest a table column gets before its cells are cut
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// One line to display, and the input line it comes from (the first line of
+/// its block for reformatted JSON)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedLine {
+    pub source: usize,
+    pub text: String,
+}
+
+/// Format `lines` (one stream's output, in order) for display.
+pub fn format_output<S: AsRef<str>>(format: OutputFormat, lines: &[S]) -> Vec<FormattedLine> {
+    let mut formatted = Vec::with_capacity(lines.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let rendered = match format {
+            OutputFormat::Raw => None,
+            OutputFormat::PrettyJson => {
+                parse_block(lines, i).map(|(value, end)| (pretty(&value), end))
+            }
+            OutputFormat::Table => table_at(lines, i),
+        };
+        match rendered {
+            Some((text, end)) => {
+                formatted.extend(text.lines().map(|line| FormattedLine { source: i, text: line.to_string() }));
+                i = end;
+            }
+            None => {
+                formatted.push(FormattedLine { source: i, text: lines[i].as_ref().to_string() });
+                i += 1;
+            }
+        }
+    }
+    formatted
+}
+
+/// The JSON object or array starting at line `start`, and the index past
+/// its last line
+fn parse_block<S: AsRef<str>>(lines: &[S], start: usize) -> Option<(Value, usize)> {
+    let first = lines[start].as_ref().trim();
+    if !first.starts_with(['{', '[']) {
+        return None;
+    }
+    if let Ok(value) = serde_json::from_str::<Value>(first) {
+        return Some((value, start + 1));
+    }
+    // Spread over lines: try each line that could close it
+    let mut block = first.to_string();
+    for (offset, line) in lines[start + 1..].iter().take(MAX_BLOCK_LINES).enumerate() {
+        let line = line.as_ref();
+        block.push('\n');
+        block.push_str(line);
+        if line.trim_start().starts_with(['}', ']']) {
+            if let Ok(value) = serde_json::from_str::<Value>(&block) {
+                return Some((value, start + offset + 2));
+            }
+        }
+    }
+    None
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// A table of the records at line `start`: an array of objects, or objects
+/// on consecutive lines (JSON Lines)
+fn table_at<S: AsRef<str>>(lines: &[S], start: usize) -> Option<(String, usize)> {
+    let (value, mut end) = parse_block(lines, start)?;
+    let rows: Vec<Value> = match value {
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => items,
+        Value::Object(_) => {
+            let mut rows = vec![value];
+            while end < lines.len() {
+                match serde_json::from_str::<Value>(lines[end].as_ref().trim()) {
+                    Ok(row @ Value::Object(_)) => rows.push(row),
+                    _ => break,
+                }
+                end += 1;
+            }
+            rows
+        }
+        _ => return None,
+    };
+    Some((render_table(&rows), end))
+}
+
+fn render_table(rows: &[Value]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for row in rows {
+        for key in row.as_object().into_iter().flat_map(|fields| fields.keys()) {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|column| cell(row.get(*column))).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(c, column)| {
+            cells
+                .iter()
+                .map(|row| row[c].chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or(0)
+                .min(MAX_COLUMN_WIDTH)
+        })
+        .collect();
+
+    let render_row = |values: Vec<String>| {
+        values
+            .into_iter()
+            .zip(&widths)
+            .map(|(value, &width)| format!("{:<width$}", truncate(&value, width), width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut table = vec![
+        render_row(columns.iter().map(|c| c.to_string()).collect()),
+        render_row(widths.iter().map(|&w| "-".repeat(w)).collect()),
+    ];
+    table.extend(cells.into_iter().map(render_row));
+    table.join("\n")
+}
+
+fn cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_string();
+    }
+    let mut cut: String = value.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(lines: &[FormattedLine]) -> Vec<&str> {
+        lines.iter().map(|l| l.text.as_str()).collect()
+    }
+
+    #[test]
+    fn compact_json_lines_render_multi_line_and_the_rest_passes_through() {
+        let lines = [
+            "Fetching status...",
+            r#"{"ok":true,"items":[1,2]}"#,
+            "{not json}",
+            "done",
+        ];
+        let formatted = format_output(OutputFormat::PrettyJson, &lines);
+        assert_eq!(
+            texts(&formatted),
+            vec![
+                "Fetching status...",
+                "{",
+                "  \"items\": [",
+                "    1,",
+                "    2",
+                "  ],",
+                "  \"ok\": true",
+                "}",
+                "{not json}",
+                "done",
+            ]
+        );
+        // Every line still points at the line it came from
+        let sources: Vec<usize> = formatted.iter().map(|l| l.source).collect();
+        assert_eq!(sources, vec![0, 1, 1, 1, 1, 1, 1, 1, 2, 3]);
+
+        // Raw leaves everything alone
+        assert_eq!(texts(&format_output(OutputFormat::Raw, &lines)), lines.to_vec());
+    }
+
+    #[test]
+    fn json_blocks_over_several_lines_are_reformatted_once_closed() {
+        let lines = ["{", r#"  "a":   1,"#, r#""b": {"c": null}"#, "}", "after"];
+        let formatted = format_output(OutputFormat::PrettyJson, &lines);
+        assert_eq!(
+            texts(&formatted),
+            vec!["{", "  \"a\": 1,", "  \"b\": {", "    \"c\": null", "  }", "}", "after"]
+        );
+
+        // Never closed: left as is
+        let open = ["[", "1,", "2"];
+        assert_eq!(texts(&format_output(OutputFormat::PrettyJson, &open)), open.to_vec());
+    }
+
+    #[test]
+    fn records_render_as_a_table() {
+        let lines = [
+            r#"{"name":"web","port":3000}"#,
+            r#"{"name":"api","port":8080,"healthy":true}"#,
+            "2 services",
+        ];
+        let formatted = format_output(OutputFormat::Table, &lines);
+        assert_eq!(
+            texts(&formatted),
+            vec![
+                "name  port  healthy",
+                "----  ----  -------",
+                "web   3000",
+                "api   8080  true",
+                "2 services",
+            ]
+        );
+
+        let array = [r#"[{"id":1},{"id":22}]"#];
+        assert_eq!(texts(&format_output(OutputFormat::Table, &array)), vec!["id", "--", "1", "22"]);
+
+        // Not records: shown as is
+        let scalars = ["[1,2,3]", "42"];
+        assert_eq!(texts(&format_output(OutputFormat::Table, &scalars)), scalars.to_vec());
+    }
+}
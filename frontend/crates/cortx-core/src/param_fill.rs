@@ -0,0 +1,133 @@
+//! Filling run-form parameters from the clipboard and from dropped files.
+//!
+//! The GUI run dialog offers the clipboard as a one-click value for path
+//! parameters when it holds a path or URL, and fills a parameter with the
+//! files dropped on the window (`file-dropped-for-param`). Multi-value
+//! parameters take every dropped file, the way `build_command` splits them.
+
+use std::path::{Path, PathBuf};
+
+use crate::models::{FileDroppedPayload, ScriptParameter};
+
+/// `text` as a parameter value when it's a single existing path or a URL,
+/// with surrounding quotes dropped and `~` expanded.
+pub fn pathlike(text: &str) -> Option<String> {
+    let text = text.trim();
+    let text = strip_quotes(text);
+    if text.is_empty() || text.contains(['\n', '\r']) {
+        return None;
+    }
+    if is_url(text) {
+        return Some(text.to_string());
+    }
+    let path = expand_home(text);
+    path.exists().then(|| path.to_string_lossy().to_string())
+}
+
+fn strip_quotes(text: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| text.strip_prefix(q).and_then(|t| t.strip_suffix(q)))
+        .unwrap_or(text)
+}
+
+/// `scheme://rest`, the scheme being letters, digits, `+`, `-` or `.`
+fn is_url(text: &str) -> bool {
+    let Some((scheme, rest)) = text.split_once("://") else {
+        return false;
+    };
+    scheme.len() > 1 // not a Windows drive letter
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+        && !rest.contains(char::is_whitespace)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        if let Some(dirs) = directories::BaseDirs::new() {
+            return dirs.home_dir().join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+/// The value `param` gets from dropped `paths`: the first one for a
+/// single-value parameter, all of them space-separated for `+` / `*`, and
+/// the first N for a fixed count. None when nothing was dropped.
+///
+/// Multi-value parameters are split on whitespace when the command is built,
+/// so a path containing spaces can only fill a single-value parameter.
+pub fn join_for_param(param: &ScriptParameter, paths: &[String]) -> Option<String> {
+    let take = match param.nargs.as_deref() {
+        None | Some("?") => 1,
+        Some("+" | "*") => paths.len(),
+        Some(count) => count.parse().unwrap_or(1),
+    };
+    let picked: Vec<&str> = paths.iter().take(take).map(String::as_str).collect();
+    (!picked.is_empty()).then(|| picked.join(" "))
+}
+
+/// Payload for the dropped `paths`
+pub fn dropped_payload(paths: &[impl AsRef<Path>]) -> FileDroppedPayload {
+    FileDroppedPayload {
+        paths: paths.iter().map(|p| p.as_ref().to_string_lossy().to_string()).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ScriptParamType;
+
+    fn param(nargs: Option<&str>) -> ScriptParameter {
+        ScriptParameter {
+            name: "files".into(),
+            param_type: ScriptParamType::Path,
+            short_flag: None,
+            long_flag: Some("--files".into()),
+            description: None,
+            default_value: None,
+            required: false,
+            enum_values: Vec::new(),
+            nargs: nargs.map(String::from),
+            mutex_group: None,
+        }
+    }
+
+    #[test]
+    fn only_existing_paths_and_urls_are_pathlike() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("report.csv");
+        std::fs::write(&file, "").unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        assert_eq!(pathlike(&file), Some(file.clone()));
+        assert_eq!(pathlike(&format!("  \"{}\"\n", file)), Some(file.clone()));
+        assert_eq!(pathlike("https://example.com/a?b=1"), Some("https://example.com/a?b=1".into()));
+        assert_eq!(pathlike("file:///tmp/x"), Some("file:///tmp/x".into()));
+
+        assert_eq!(pathlike(&dir.path().join("missing.csv").to_string_lossy()), None);
+        assert_eq!(pathlike("just some copied text"), None);
+        assert_eq!(pathlike(&format!("{}\n{}", file, file)), None);
+        assert_eq!(pathlike("https://"), None);
+        assert_eq!(pathlike(""), None);
+    }
+
+    #[test]
+    fn dropped_paths_fill_per_nargs() {
+        let paths: Vec<String> = ["/a.txt", "/b.txt", "/c.txt"].map(String::from).to_vec();
+        assert_eq!(join_for_param(&param(None), &paths).as_deref(), Some("/a.txt"));
+        assert_eq!(join_for_param(&param(Some("?")), &paths).as_deref(), Some("/a.txt"));
+        assert_eq!(join_for_param(&param(Some("+")), &paths).as_deref(), Some("/a.txt /b.txt /c.txt"));
+        assert_eq!(join_for_param(&param(Some("*")), &paths).as_deref(), Some("/a.txt /b.txt /c.txt"));
+        assert_eq!(join_for_param(&param(Some("2")), &paths).as_deref(), Some("/a.txt /b.txt"));
+        assert_eq!(join_for_param(&param(Some("+")), &[]), None);
+    }
+
+    #[test]
+    fn dropped_payload_shape() {
+        let payload = dropped_payload(&[PathBuf::from("/tmp/one"), PathBuf::from("/tmp/two")]);
+        assert_eq!(serde_json::to_value(&payload).unwrap(), serde_json::json!({ "paths": ["/tmp/one", "/tmp/two"] }));
+    }
+}
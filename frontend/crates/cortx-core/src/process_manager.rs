@@ -1,27 +1,49 @@
-use crate::models::{LogStream, ScriptStatus, ServiceStatus};
+use crate::command_builder::{expand_command, shell_join, QuoteStyle};
+use crate::models::{
+    EnvMode, IdleAction, LogStream, LoggingConfig, RestartResult, ScriptStatus, Service, ServiceCircuit,
+    ServiceGroupResult, ServiceHealth, ServiceStatus, ShellConfig, StartWait, StopAllSummary,
+};
+use crate::mutex_group::{self, GroupLock, Holder, MutexGroups};
+use crate::prompt_detect::{LineSplitter, PromptDetector};
+use crate::scheduler::Scheduler;
 use crate::runtime_state::{
-    self, EntityKind, RuntimeEntry, RuntimeStore,
+    self, EntityKind, RunningCounts, RunningProcess, RunningStatus, RuntimeEntry, RuntimeStore,
 };
-use parking_lot::Mutex;
-use std::collections::HashMap;
+use crate::service_health::HealthCheck;
+use crate::service_idle::{self, IdleTimeout};
+use crate::service_log::{ServiceLogStore, ServiceLogWriter};
+use crate::service_restart::{AutoRestart, CircuitBreaker, CircuitState, RestartDecision};
+use crate::service_watch::{self, ServiceWatch, ServiceWatcherHandle};
+use chrono::{DateTime, Utc};
+use parking_lot::{Condvar, Mutex};
+use std::collections::{HashMap, VecDeque};
 use std::fs::OpenOptions;
-use std::io::{BufRead, BufReader, Write as IoWrite};
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::io::{Read, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Once, Weak};
 use std::thread;
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-/// Display-only metadata that the caller knows but ProcessManager doesn't,
-/// passed through to the RuntimeStore entry written on spawn.
+/// Metadata that the caller knows but ProcessManager doesn't, passed
+/// through to the RuntimeStore entry written on spawn.
 #[derive(Debug, Clone, Default)]
 pub struct RuntimeMeta {
     pub display_name: String,
     pub project_id: Option<String>,
     pub project_name: Option<String>,
+    /// Project variant it runs under (`Project::active_variant_id`)
+    pub variant_id: Option<String>,
+    /// A global script that is already running starts another run next to
+    /// it instead of refusing (`GlobalScript::allow_concurrent`)
+    pub allow_concurrent: bool,
+    /// Mutex group the script holds while it runs (see `mutex_group`)
+    pub mutex_group: Option<String>,
+    /// Wait for a held mutex group instead of refusing to start
+    pub queue_on_mutex: bool,
 }
 
 impl RuntimeMeta {
@@ -30,6 +52,10 @@ impl RuntimeMeta {
             display_name: display_name.into(),
             project_id: None,
             project_name: None,
+            variant_id: None,
+            allow_concurrent: false,
+            mutex_group: None,
+            queue_on_mutex: false,
         }
     }
 
@@ -38,8 +64,114 @@ impl RuntimeMeta {
         self.project_name = Some(name.into());
         self
     }
+
+    pub fn with_variant(mut self, variant_id: Option<String>) -> Self {
+        self.variant_id = variant_id;
+        self
+    }
+
+    pub fn with_allow_concurrent(mut self, allow: bool) -> Self {
+        self.allow_concurrent = allow;
+        self
+    }
+
+    pub fn with_mutex_group(mut self, group: Option<String>, queue: bool) -> Self {
+        // A blank group is no group
+        self.mutex_group = group.filter(|g| !g.trim().is_empty());
+        self.queue_on_mutex = queue;
+        self
+    }
+}
+
+/// Id a run of a global script goes by in the process map, the runtime
+/// store and its events: the script id for its first run, and
+/// `<script id>#<run id>` for the runs started next to it
+/// (`GlobalScript::allow_concurrent`).
+pub fn run_key(script_id: &str, run_id: Option<&str>) -> String {
+    match run_id {
+        Some(run_id) => format!("{}#{}", script_id, run_id),
+        None => script_id.to_string(),
+    }
+}
+
+/// The script id and (for a concurrent run) the run id in a [`run_key`]
+pub fn split_run_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('#') {
+        Some((script_id, run_id)) => (script_id, Some(run_id)),
+        None => (key, None),
+    }
 }
 
+/// Everything needed to start one service. Resolved by the caller, since
+/// ProcessManager has no access to Storage (working dir, mode, presets...).
+#[derive(Debug, Clone)]
+pub struct ServiceLaunch {
+    pub service_id: String,
+    pub working_dir: String,
+    pub command: String,
+    pub env_vars: Option<HashMap<String, String>>,
+    pub env_mode: EnvMode,
+    pub mode: Option<String>,
+    pub arg_preset: Option<String>,
+    pub meta: RuntimeMeta,
+    /// Restart the service when these paths change (`Service::watch_paths`)
+    pub watch: Option<ServiceWatch>,
+    /// Warn or stop when it prints nothing for a while (`Service::idle_timeout_secs`)
+    pub idle: Option<IdleTimeout>,
+    /// Probe it while it runs (`Service::health_check`)
+    pub health: Option<HealthCheck>,
+    /// Where it listens (`Service::port`), for `StartWait::Port`
+    pub port: Option<u16>,
+    /// Start it again when it exits on its own (`Service::restart_policy`)
+    pub restart: Option<AutoRestart>,
+    /// How long a stop waits for it to exit before killing it
+    /// (`Service::stop_grace_seconds`; [`DEFAULT_STOP_GRACE`] when unset)
+    pub stop_grace: Option<std::time::Duration>,
+}
+
+/// How long a service must stay alive in sequential group starts before the
+/// next one is launched. Catches services that crash right after spawning.
+const GROUP_SETTLE_MS: u64 = 1000;
+
+/// A service that exits non-zero within this long after spawning, before
+/// printing anything to stdout, is reported as failed to start
+/// (`ServiceStatus::Error`) rather than simply stopped.
+const EARLY_EXIT_MS: u64 = 2000;
+
+/// Stderr lines kept for `ServiceExitPayload::error_excerpt`
+const ERROR_EXCERPT_LINES: usize = 10;
+
+/// How long the exit watcher waits for the output readers to drain before
+/// judging an early exit
+const OUTPUT_DRAIN_MS: u64 = 500;
+
+/// Interval between the reaper's sweeps over the running processes
+const REAP_INTERVAL_MS: u64 = 25;
+
+/// How often a health checker waiting for its next check looks whether the
+/// service stopped
+const HEALTH_POLL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// How often a service waiting out its restart backoff looks whether it was
+/// stopped in the meantime
+const RESTART_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// How long a stopped process gets to exit after being asked to (SIGTERM,
+/// or a close request on Windows) before its tree is killed
+pub const DEFAULT_STOP_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Grace `stop_all` gives everything it stops, together, so quitting stays
+/// quick
+pub const SHUTDOWN_STOP_GRACE: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// How often a stop waiting out the grace period looks whether the process
+/// exited
+const STOP_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// How long output has to pause on an open line that looks like a prompt
+/// before it's reported as one, so a line written in pieces isn't split
+const PROMPT_QUIET: std::time::Duration = std::time::Duration::from_millis(150);
+
 /// Apply platform-specific spawn config that must be set on every spawned process:
 /// - Windows: hide the console window (CREATE_NO_WINDOW).
 /// - Unix:   put the child in its own process group so `kill -PGID` reaches the
@@ -67,8 +199,11 @@ pub trait ProcessEventEmitter: Send + Sync {
         pid: Option<u32>,
         active_mode: Option<String>,
         active_arg_preset: Option<String>,
+        started_at: Option<DateTime<Utc>>,
     );
-    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>);
+    /// `error_excerpt` holds the last stderr lines when the service failed
+    /// right after starting (see [`ServiceStatus::Error`]).
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>);
     fn emit_script_log(&self, script_id: &str, stream: LogStream, content: String);
     fn emit_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>);
     fn emit_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool);
@@ -83,6 +218,13 @@ pub trait ProcessEventEmitter: Send + Sync {
     ///
     /// Default no-op for emitters that don't surface port info (TUI / MCP).
     fn emit_service_ports(&self, _service_id: &str, _ports: Vec<u16>) {}
+
+    /// A global script stopped at an interactive prompt (`prompt`, already
+    /// emitted as a log line) and waits for input. Only reported with
+    /// interactive stdin and prompt detection on (`set_prompt_detection`).
+    ///
+    /// Default no-op for emitters that can't feed a script's stdin.
+    fn emit_global_script_prompt(&self, _script_id: &str, _prompt: String) {}
 }
 
 pub struct ProcessInfo {
@@ -91,28 +233,172 @@ pub struct ProcessInfo {
     pub pid: u32,
     pub active_mode: Option<String>,
     pub active_arg_preset: Option<String>,
+    /// Project variant it was started under
+    pub variant_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// Wait for it to exit on its own when stopped, before killing it
+    pub stop_grace: std::time::Duration,
+    /// Write end of its stdin, for global scripts started with interactive
+    /// stdin (see `ProcessManager::write_stdin`); None once closed
+    pub stdin: Option<ChildStdin>,
+    /// Mutex group it holds, released when it leaves its map
+    pub group_lock: Option<GroupLock>,
+}
+
+type ProcessMap = Arc<Mutex<HashMap<String, ProcessInfo>>>;
+
+/// Restart circuit of a service that has crashed since it was started
+struct CircuitEntry {
+    breaker: CircuitBreaker,
+    /// How its last run ended, e.g. "code 1"
+    last_exit: String,
+    /// When it last opened
+    opened_at: DateTime<Utc>,
 }
 
 pub struct ProcessManager {
     /// Local Child handles for processes WE spawned, used for `.try_wait()` and
     /// `.kill()`. Authoritative process metadata (PID, started_at, mode, ...)
     /// lives in `runtime_store` so other CortX instances can see them.
-    processes: Arc<Mutex<HashMap<String, ProcessInfo>>>,
-    scripts: Arc<Mutex<HashMap<String, ProcessInfo>>>,
-    global_scripts: Arc<Mutex<HashMap<String, ProcessInfo>>>,
+    processes: ProcessMap,
+    scripts: ProcessMap,
+    global_scripts: ProcessMap,
     shutdown_flag: Arc<AtomicBool>,
     runtime_store: Arc<RuntimeStore>,
+    /// Shell that service / project-script command strings run through
+    shell: Mutex<ShellConfig>,
+    /// File watchers of services started with watch paths. Dropping a handle
+    /// stops its watcher.
+    watchers: Mutex<HashMap<String, ServiceWatcherHandle>>,
+    /// Notices exits of everything in the three maps above
+    reaper: Arc<Reaper>,
+    /// Service output persisted to `<app_dir>/logs` (when enabled)
+    service_logs: Arc<ServiceLogStore>,
+    /// Open session log of each service started, flushed by `stop_all`
+    log_writers: Mutex<HashMap<String, Arc<ServiceLogWriter>>>,
+    /// Latest health-check results of the running services that have one
+    health: Mutex<HashMap<String, ServiceHealth>>,
+    /// "Stopped intentionally" flag of each service started with a restart
+    /// policy, shared by its automatic restarts. Set by `stop_service` (or a
+    /// fresh start), so a pending restart is dropped instead of spawned.
+    stopped_intentionally: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    /// Failed runs of services with a restart policy (`service_restart`).
+    /// Dropped by a start or stop from the user.
+    circuits: Mutex<HashMap<String, CircuitEntry>>,
+    /// Generation of each service's launch, bumped whenever a new one is
+    /// installed (a start, a save-and-restart). A watcher holding an older
+    /// launch doesn't restart the service with it.
+    generations: Mutex<HashMap<String, u64>>,
+    /// Held while a service is started or restarted, so a save-and-restart
+    /// and a watcher's restart never interleave their stops and starts
+    restart_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Nesting limit for global scripts that run cortx themselves
+    max_run_depth: AtomicU32,
+    /// Pipe global scripts' stdin instead of inheriting it
+    interactive_stdin: AtomicBool,
+    /// Start each script run's output with the command it runs
+    echo_command: AtomicBool,
+    /// Recognizes interactive prompts in global scripts' output; None = off
+    prompts: Mutex<Option<Arc<PromptDetector>>>,
+    /// Mutex groups held by running scripts
+    mutex_groups: Arc<MutexGroups>,
+    /// Cron schedules of global scripts, run by `scheduler::spawn`
+    scheduler: Arc<Scheduler>,
 }
 
 impl ProcessManager {
     pub fn new(runtime_store: Arc<RuntimeStore>) -> Self {
+        let processes: ProcessMap = Arc::new(Mutex::new(HashMap::new()));
+        let scripts: ProcessMap = Arc::new(Mutex::new(HashMap::new()));
+        let global_scripts: ProcessMap = Arc::new(Mutex::new(HashMap::new()));
+        let shutdown_flag = Arc::new(AtomicBool::new(false));
+        let reaper = Arc::new(Reaper::new(
+            [processes.clone(), scripts.clone(), global_scripts.clone()],
+            runtime_store.clone(),
+            shutdown_flag.clone(),
+        ));
+        // `<app_dir>/logs`, next to the runtime dir
+        let service_logs = Arc::new(ServiceLogStore::new(runtime_store.dir().with_file_name("logs")));
         Self {
-            processes: Arc::new(Mutex::new(HashMap::new())),
-            scripts: Arc::new(Mutex::new(HashMap::new())),
-            global_scripts: Arc::new(Mutex::new(HashMap::new())),
-            shutdown_flag: Arc::new(AtomicBool::new(false)),
+            processes,
+            scripts,
+            global_scripts,
+            shutdown_flag,
             runtime_store,
+            shell: Mutex::new(ShellConfig::default()),
+            watchers: Mutex::new(HashMap::new()),
+            reaper,
+            service_logs,
+            log_writers: Mutex::new(HashMap::new()),
+            health: Mutex::new(HashMap::new()),
+            stopped_intentionally: Mutex::new(HashMap::new()),
+            circuits: Mutex::new(HashMap::new()),
+            generations: Mutex::new(HashMap::new()),
+            restart_locks: Mutex::new(HashMap::new()),
+            max_run_depth: AtomicU32::new(crate::run_guard::DEFAULT_MAX_RUN_DEPTH),
+            interactive_stdin: AtomicBool::new(false),
+            echo_command: AtomicBool::new(false),
+            prompts: Mutex::new(None),
+            mutex_groups: MutexGroups::new(),
+            scheduler: Scheduler::new(),
+        }
+    }
+
+    /// Select the shell used for subsequent service / project-script starts
+    /// (from `AppSettings::shell`).
+    pub fn set_shell(&self, shell: ShellConfig) {
+        *self.shell.lock() = shell;
+    }
+
+    /// Whether and how service output is persisted, for services started
+    /// from now on (from `AppSettings::logging`).
+    pub fn set_logging(&self, config: LoggingConfig) {
+        self.service_logs.set_config(config);
+    }
+
+    /// How deep global script runs may nest through cortx invoking itself
+    /// (from `ScriptsConfig::max_run_depth`).
+    pub fn set_max_run_depth(&self, max_depth: u32) {
+        self.max_run_depth.store(max_depth, Ordering::Relaxed);
+    }
+
+    /// Give global scripts started from now on a stdin pipe that
+    /// `write_stdin` feeds, instead of this process's stdin. For the TUI,
+    /// whose stdin is the terminal it draws on.
+    pub fn set_interactive_stdin(&self, enabled: bool) {
+        self.interactive_stdin.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether script runs started from now on begin their output with an
+    /// `echo_line` (from `ScriptsConfig::echo_command`).
+    pub fn set_echo_command(&self, enabled: bool) {
+        self.echo_command.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Report global scripts started from now on that wait at a prompt
+    /// (`ProcessEventEmitter::emit_global_script_prompt`), or stop with
+    /// `None`. Needs interactive stdin (`set_interactive_stdin`).
+    pub fn set_prompt_detection(&self, detector: Option<PromptDetector>) {
+        *self.prompts.lock() = detector.map(Arc::new);
+    }
+
+    /// With `set_echo_command` on, append a run's `echo_line` to its log
+    /// file and return it for the caller to emit. Called before the output
+    /// readers start, so it comes first in both.
+    fn write_echo_line(&self, log_path: &Path, command: &str, working_dir: &str) -> Option<String> {
+        if !self.echo_command.load(Ordering::Relaxed) {
+            return None;
         }
+        let line = echo_line(command, working_dir);
+        if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(log_path) {
+            let _ = writeln!(file, "{}", line);
+        }
+        Some(line)
+    }
+
+    /// Persisted service logs, for listing and reading past sessions
+    pub fn service_logs(&self) -> &Arc<ServiceLogStore> {
+        &self.service_logs
     }
 
     /// Borrow the underlying RuntimeStore (e.g. for `cortx ps` queries from
@@ -130,17 +416,232 @@ impl ProcessManager {
     // Services
     // ========================================================================
 
+    /// Start a service. With `watch`, a watcher restarts it whenever the
+    /// watched paths change, until `stop_service` / `stop_all`. With `idle`,
+    /// each run of it is monitored for silence; with `health`, probed until
+    /// it stops. With `restart`, it's started again when it exits on its own.
+    /// Stopping it waits up to `stop_grace` for it to exit before killing it.
     pub fn start_service(
-        &self,
+        self: &Arc<Self>,
         emitter: Arc<dyn ProcessEventEmitter>,
         service_id: String,
         working_dir: String,
         command: String,
         env_vars: Option<HashMap<String, String>>,
+        env_mode: &EnvMode,
         mode: Option<String>,
         arg_preset: Option<String>,
         meta: RuntimeMeta,
+        watch: Option<ServiceWatch>,
+        idle: Option<IdleTimeout>,
+        health: Option<HealthCheck>,
+        restart: Option<AutoRestart>,
+        stop_grace: Option<std::time::Duration>,
+    ) -> Result<u32, String> {
+        let launch = ServiceLaunch {
+            service_id,
+            working_dir,
+            command,
+            env_vars,
+            env_mode: env_mode.clone(),
+            mode,
+            arg_preset,
+            meta,
+            watch,
+            idle,
+            health,
+            port: None,
+            restart,
+            stop_grace,
+        };
+        let lock = self.restart_lock(&launch.service_id);
+        let _starting = lock.lock();
+        let pid = self.spawn_service(emitter.clone(), launch.clone())?;
+        self.watch_service(emitter, launch);
+        Ok(pid)
+    }
+
+    /// Stop a service (if it's running) and start it again with the same
+    /// launch settings. Its watcher, if any, is kept.
+    pub fn restart_service(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+    ) -> Result<u32, String> {
+        let lock = self.restart_lock(&launch.service_id);
+        let _restarting = lock.lock();
+        self.respawn_service(emitter, launch)
+    }
+
+    /// Save a new definition of a service and, if it's running (or waiting
+    /// for an automatic restart), restart it with that definition: `save`
+    /// stores the change and returns the saved service, `resolve` turns it
+    /// into the launch to restart with. The service's restart lock is held
+    /// from the save to the new start, and the new launch replaces the one
+    /// its watcher restarts with, so neither a watcher firing meanwhile nor a
+    /// pending automatic restart brings the old command back. Late events of
+    /// the old run are dropped as for any stop (the reaper only reports the
+    /// pid it was given). Not running: only saves, and `resolve` isn't called.
+    pub fn update_and_restart_service(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        service_id: &str,
+        save: impl FnOnce() -> Result<Service, String>,
+        resolve: impl FnOnce(&Service) -> Result<ServiceLaunch, String>,
+    ) -> Result<RestartResult, String> {
+        let lock = self.restart_lock(service_id);
+        let _restarting = lock.lock();
+        let running = self.is_service_running(service_id) || self.restart_pending(service_id);
+        let service = save()?;
+        if !running {
+            return Ok(RestartResult { service, restarted: false, pid: None });
+        }
+
+        let launch = resolve(&service).map_err(|e| format!("Saved, but not restarted: {}", e))?;
+        let pid = self.respawn_service(emitter.clone(), launch.clone())?;
+        self.watch_service(emitter, launch);
+        Ok(RestartResult { service, restarted: true, pid: Some(pid) })
+    }
+
+    /// A watcher's restart, unless its launch (`generation`) has been
+    /// replaced since: the service was started again, or saved and restarted
+    /// with a new definition
+    fn restart_watched(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+        generation: u64,
+    ) -> Result<(), String> {
+        let lock = self.restart_lock(&launch.service_id);
+        let _restarting = lock.lock();
+        if self.generations.lock().get(&launch.service_id) != Some(&generation) {
+            log::info!("Not restarting service {}: its definition changed", launch.service_id);
+            return Ok(());
+        }
+        self.respawn_service(emitter, launch).map(|_| ())
+    }
+
+    /// Stop then spawn, with the service's restart lock held
+    fn respawn_service(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+    ) -> Result<u32, String> {
+        // Not running (e.g. it crashed on the previous change) → just start it
+        let _ = self.stop_process(emitter.as_ref(), &launch.service_id);
+        self.spawn_service(emitter, launch)
+    }
+
+    /// Between two runs: it exited, and an automatic restart is scheduled
+    fn restart_pending(&self, service_id: &str) -> bool {
+        if self.processes.lock().contains_key(service_id) {
+            return false;
+        }
+        let flags = self.stopped_intentionally.lock();
+        flags.get(service_id).is_some_and(|stopped| !stopped.load(Ordering::SeqCst))
+    }
+
+    fn restart_lock(&self, service_id: &str) -> Arc<Mutex<()>> {
+        self.restart_locks.lock().entry(service_id.to_string()).or_default().clone()
+    }
+
+    /// Replace the service's watcher with one for `launch.watch`, or drop it
+    /// if the launch has no watch paths. Either way `launch` becomes the
+    /// service's current generation.
+    fn watch_service(self: &Arc<Self>, emitter: Arc<dyn ProcessEventEmitter>, launch: ServiceLaunch) {
+        let service_id = launch.service_id.clone();
+        let generation = {
+            let mut generations = self.generations.lock();
+            let generation = generations.entry(service_id.clone()).or_insert(0);
+            *generation += 1;
+            *generation
+        };
+        let Some(watch) = launch.watch.clone() else {
+            self.watchers.lock().remove(&service_id);
+            return;
+        };
+
+        // Weak, so a watcher never keeps the manager (and its processes) alive
+        let manager: Weak<Self> = Arc::downgrade(self);
+        let on_change = move || {
+            let Some(manager) = manager.upgrade() else { return };
+            if manager.shutdown_flag.load(Ordering::SeqCst) {
+                return;
+            }
+            log::info!("Change detected, restarting service {}", launch.service_id);
+            if let Err(e) = manager.restart_watched(emitter.clone(), launch.clone(), generation) {
+                log::warn!("Failed to restart service {}: {}", launch.service_id, e);
+            }
+        };
+
+        match service_watch::start_watching(&watch, on_change) {
+            Ok(handle) => {
+                self.watchers.lock().insert(service_id, handle);
+            }
+            Err(e) => {
+                log::warn!("Not watching service {}: {}", service_id, e);
+                self.watchers.lock().remove(&service_id);
+            }
+        }
+    }
+
+    fn spawn_service(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+    ) -> Result<u32, String> {
+        let service_id = launch.service_id.clone();
+        let with_restart = launch.restart.is_some();
+
+        // A fresh start drops any pending restart of an earlier run, and
+        // closes its circuit
+        {
+            let mut flags = self.stopped_intentionally.lock();
+            if !self.processes.lock().contains_key(&service_id) {
+                if let Some(earlier) = flags.remove(&service_id) {
+                    earlier.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+        self.circuits.lock().remove(&service_id);
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let pid = self.spawn_run(emitter, launch, 0, stopped.clone())?;
+        if with_restart {
+            if let Some(earlier) = self.stopped_intentionally.lock().insert(service_id, stopped) {
+                earlier.store(true, Ordering::SeqCst);
+            }
+        }
+        Ok(pid)
+    }
+
+    /// Spawn one run of a service, the `restarts`-th automatic restart in a
+    /// row (0 for a start). `stopped` is its "stopped intentionally" flag.
+    fn spawn_run(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+        restarts: u32,
+        stopped: Arc<AtomicBool>,
     ) -> Result<u32, String> {
+        let relaunch = launch.clone();
+        let ServiceLaunch {
+            service_id,
+            working_dir,
+            command,
+            env_vars,
+            env_mode,
+            mode,
+            arg_preset,
+            meta,
+            watch: _,
+            idle,
+            health,
+            port: _,
+            restart,
+            stop_grace,
+        } = launch;
+
         // Check if already running anywhere on the host (this process or
         // another instance — store is the canonical view).
         if let Some(existing) = self.runtime_store.get(&service_id) {
@@ -153,6 +654,10 @@ impl ProcessManager {
             // Stale entry from a crashed instance — let register() overwrite it.
         }
 
+        // Resolve ${VAR} references (env_vars, then the parent env) before
+        // reporting anything, so an unresolved one fails the start cleanly
+        let command = expand_command(&command, &env_mode, env_vars.as_ref()).map_err(|e| e.to_string())?;
+
         // Emit starting status
         emitter.emit_service_status(
             &service_id,
@@ -160,10 +665,11 @@ impl ProcessManager {
             None,
             mode.clone(),
             arg_preset.clone(),
+            None,
         );
 
         // Parse command
-        let (program, args) = parse_command(&command);
+        let (program, args) = parse_command(&self.shell.lock(), &command);
 
         // Build command
         let mut cmd = Command::new(&program);
@@ -172,6 +678,9 @@ impl ProcessManager {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
+        // Start from a sanitized environment unless the service inherits everything
+        crate::spawn_env::prepare_env(&mut cmd, &env_mode);
+
         // Force UTF-8 output on Windows to avoid cp1252 encoding errors
         #[cfg(target_os = "windows")]
         {
@@ -200,6 +709,22 @@ impl ProcessManager {
         let stderr = child.stderr.take();
 
         let log_path = self.runtime_store.log_path(&service_id);
+        let spawned = std::time::Instant::now();
+        let trace = Arc::new(OutputTrace::default());
+
+        // New session file when persisting is on; a restart's replaces the old one
+        let persist = match self.service_logs.open_session(&service_id) {
+            Ok(writer) => writer,
+            Err(e) => {
+                log::warn!("Failed to open log file for service {}: {}", service_id, e);
+                None
+            }
+        };
+        if let Some(writer) = &persist {
+            if let Some(old) = self.log_writers.lock().insert(service_id.clone(), writer.clone()) {
+                old.close();
+            }
+        }
 
         // Spawn thread to read stdout (tee → log file + emitter)
         if let Some(stdout) = stdout {
@@ -209,7 +734,8 @@ impl ProcessManager {
                 emitter.clone(),
                 service_id.clone(),
                 LogStream::Stdout,
-                LogTarget::Service,
+                LogTarget::Service(persist.clone()),
+                Some(trace.clone()),
             );
         }
 
@@ -221,13 +747,15 @@ impl ProcessManager {
                 emitter.clone(),
                 service_id.clone(),
                 LogStream::Stderr,
-                LogTarget::Service,
+                LogTarget::Service(persist.clone()),
+                Some(trace.clone()),
             );
         }
 
         // Register the canonical runtime entry BEFORE storing the Child
-        // handle, so even if the wait-thread races us we never observe a
+        // handle, so even if the reaper races us we never observe a
         // running PID without an entry.
+        let started_at = Utc::now();
         let entry = RuntimeEntry {
             id: service_id.clone(),
             kind: EntityKind::Service,
@@ -235,11 +763,13 @@ impl ProcessManager {
             display_name: meta.display_name.clone(),
             command: command.clone(),
             working_dir: working_dir.clone(),
-            started_at: chrono::Utc::now(),
+            started_at,
             project_id: meta.project_id.clone(),
             project_name: meta.project_name.clone(),
             mode: mode.clone(),
             arg_preset: arg_preset.clone(),
+            run_id: runtime_state::new_run_id(),
+            variant_id: meta.variant_id.clone(),
         };
         if let Err(e) = self.runtime_store.register(&entry) {
             log::warn!("Failed to register service {} in runtime store: {}", service_id, e);
@@ -256,6 +786,11 @@ impl ProcessManager {
                     pid,
                     active_mode: mode.clone(),
                     active_arg_preset: arg_preset.clone(),
+                    variant_id: meta.variant_id.clone(),
+                    started_at,
+                    stop_grace: stop_grace.unwrap_or(DEFAULT_STOP_GRACE),
+                    stdin: None,
+                    group_lock: None,
                 },
             );
         }
@@ -267,6 +802,7 @@ impl ProcessManager {
             Some(pid),
             mode.clone(),
             arg_preset.clone(),
+            Some(started_at),
         );
 
         // Spawn port poller — queries the OS for TCP ports the service (and its
@@ -287,7 +823,7 @@ impl ProcessManager {
                         break;
                     }
 
-                    // If the service is gone from the processes map, the wait-thread
+                    // If the service is gone from the processes map, the reaper
                     // already cleaned up — exit silently and emit empty ports below.
                     let pid_opt = {
                         let processes = processes_pp.lock();
@@ -317,77 +853,388 @@ impl ProcessManager {
             });
         }
 
-        // Spawn thread to wait for process exit
-        let processes = self.processes.clone();
-        let shutdown_flag = self.shutdown_flag.clone();
+        if let Some(idle) = idle {
+            self.monitor_idle(emitter.clone(), service_id.clone(), pid, idle, trace.clone(), spawned);
+        }
+
+        if let Some(health) = health {
+            self.monitor_health(
+                emitter.clone(),
+                service_id.clone(),
+                pid,
+                health,
+                mode.clone(),
+                arg_preset.clone(),
+                started_at,
+            );
+        }
+
+        // Report the exit once the reaper sees it, and restart the service
+        // if its policy says so
         let service_id_exit = service_id.clone();
-        let exit_mode = mode.clone();
-        let exit_arg_preset = arg_preset.clone();
-        let runtime_store = self.runtime_store.clone();
+        let manager: Weak<Self> = Arc::downgrade(self);
+        self.reaper.watch(ExitWatch {
+            kind: ProcessKind::Service,
+            id: service_id.clone(),
+            pid,
+            on_exit: Box::new(move |exit_code| {
+                let ran_for = spawned.elapsed();
+                let error_excerpt = early_failure_excerpt(&trace, exit_code, ran_for);
+                let exit_detail = exit_code.map_or("killed".to_string(), |code| format!("code {}", code));
+                let manager = manager.upgrade();
+                let mut decision = match restart {
+                    Some(restart) if !stopped.load(Ordering::SeqCst) => restart.decide(exit_code, ran_for, restarts),
+                    _ => RestartDecision::Stay,
+                };
+                // Too many failed runs lately: stop restarting
+                if let (Some(manager), Some(restart)) = (&manager, restart) {
+                    if decision != RestartDecision::Stay {
+                        if let Some(opened) = manager.record_failed_run(&service_id_exit, restart, &exit_detail) {
+                            decision = opened;
+                        }
+                    }
+                }
+                let status = match decision {
+                    RestartDecision::Restart { attempt, delay } => {
+                        let message = format!(
+                            "[cortx] Exited ({}); restarting in {} (restart {} in a row)",
+                            exit_detail,
+                            runtime_state::format_uptime(delay),
+                            attempt,
+                        );
+                        log::info!("Service {}: {}", service_id_exit, message);
+                        emitter.emit_service_log(&service_id_exit, LogStream::Stderr, message);
+                        ServiceStatus::Restarting
+                    }
+                    RestartDecision::GiveUp => {
+                        let message = format!("[cortx] Exited again after {} restarts in a row; giving up", restarts);
+                        log::warn!("Service {}: {}", service_id_exit, message);
+                        emitter.emit_service_log(&service_id_exit, LogStream::Stderr, message);
+                        ServiceStatus::Error
+                    }
+                    RestartDecision::CircuitOpen { failures, retry_in } => {
+                        let message = format!(
+                            "[cortx] Exited ({}), {} failed runs in {}; not restarting until started again{}",
+                            exit_detail,
+                            failures,
+                            runtime_state::format_uptime(restart.map_or_else(Default::default, |r| r.circuit.window)),
+                            retry_in.map_or(String::new(), |wait| format!(" (one retry in {})", runtime_state::format_uptime(wait))),
+                        );
+                        log::warn!("Service {}: {}", service_id_exit, message);
+                        emitter.emit_service_log(&service_id_exit, LogStream::Stderr, message);
+                        ServiceStatus::CircuitOpen
+                    }
+                    RestartDecision::Stay if error_excerpt.is_some() => ServiceStatus::Error,
+                    RestartDecision::Stay => ServiceStatus::Stopped,
+                };
+                emitter.emit_service_status(&service_id_exit, status, None, mode, arg_preset, None);
+                emitter.emit_service_exit(&service_id_exit, exit_code, error_excerpt);
+
+                let Some(manager) = manager else { return };
+                match decision {
+                    RestartDecision::Restart { attempt, delay } => {
+                        manager.schedule_restart(emitter, relaunch, attempt, delay, stopped);
+                    }
+                    // The retry starts a fresh count of restarts in a row
+                    RestartDecision::CircuitOpen { retry_in: Some(wait), .. } => {
+                        manager.schedule_restart(emitter, relaunch, 0, wait, stopped);
+                    }
+                    _ => manager.end_restarts(&service_id_exit, &stopped),
+                }
+            }),
+        });
+
+        Ok(pid)
+    }
+
+    /// Spawn the `restarts`-th automatic restart of a service once `delay`
+    /// passed, unless it was stopped (or started afresh) in the meantime.
+    fn schedule_restart(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launch: ServiceLaunch,
+        restarts: u32,
+        delay: std::time::Duration,
+        stopped: Arc<AtomicBool>,
+    ) {
+        let manager: Weak<Self> = Arc::downgrade(self);
         thread::spawn(move || {
+            let due = std::time::Instant::now() + delay;
             loop {
-                if shutdown_flag.load(Ordering::SeqCst) {
+                let left = due.saturating_duration_since(std::time::Instant::now());
+                if left.is_zero() {
                     break;
                 }
+                thread::sleep(RESTART_POLL.min(left));
+                let Some(manager) = manager.upgrade() else { return };
+                if stopped.load(Ordering::SeqCst) || manager.shutdown_flag.load(Ordering::SeqCst) {
+                    return;
+                }
+            }
+
+            let Some(manager) = manager.upgrade() else { return };
+            let service_id = launch.service_id.clone();
+            let (mode, arg_preset) = (launch.mode.clone(), launch.arg_preset.clone());
+            // Under the flags' lock, so a stop can't slip in between the
+            // check and the spawn
+            let spawned = {
+                let _flags = manager.stopped_intentionally.lock();
+                if stopped.load(Ordering::SeqCst) || manager.shutdown_flag.load(Ordering::SeqCst) {
+                    return;
+                }
+                if !manager.begin_circuit_retry(&service_id) {
+                    return;
+                }
+                manager.spawn_run(emitter.clone(), launch, restarts, stopped.clone())
+            };
+            if let Err(e) = spawned {
+                log::warn!("Failed to restart service {}: {}", service_id, e);
+                emitter.emit_service_log(&service_id, LogStream::Stderr, format!("[cortx] Failed to restart: {}", e));
+                emitter.emit_service_status(&service_id, ServiceStatus::Error, None, mode, arg_preset, None);
+                manager.end_restarts(&service_id, &stopped);
+            }
+        });
+    }
+
+    /// Forget the "stopped intentionally" flag of a service whose restarts
+    /// are over, unless a fresh start already replaced it.
+    fn end_restarts(&self, service_id: &str, stopped: &Arc<AtomicBool>) {
+        let mut flags = self.stopped_intentionally.lock();
+        if flags.get(service_id).is_some_and(|flag| Arc::ptr_eq(flag, stopped)) {
+            flags.remove(service_id);
+        }
+    }
+
+    /// Count a failed run of a service its policy would restart after.
+    /// Returns the decision to take instead when its circuit is open now.
+    fn record_failed_run(&self, service_id: &str, restart: AutoRestart, exit_detail: &str) -> Option<RestartDecision> {
+        let now = std::time::Instant::now();
+        let mut circuits = self.circuits.lock();
+        let entry = circuits.entry(service_id.to_string()).or_insert_with(|| CircuitEntry {
+            breaker: CircuitBreaker::new(restart.circuit),
+            last_exit: String::new(),
+            opened_at: Utc::now(),
+        });
+        entry.last_exit = exit_detail.to_string();
+        if !entry.breaker.record_failure(now) {
+            return None;
+        }
+        entry.opened_at = Utc::now();
+        Some(RestartDecision::CircuitOpen {
+            failures: entry.breaker.failures(),
+            retry_in: entry.breaker.retry_at().map(|due| due.saturating_duration_since(now)),
+        })
+    }
+
+    /// Whether a scheduled restart may start: always, unless its circuit is
+    /// open, in which case this is its retry (turning the circuit half-open)
+    fn begin_circuit_retry(&self, service_id: &str) -> bool {
+        match self.circuits.lock().get_mut(service_id) {
+            Some(entry) if matches!(entry.breaker.state(), CircuitState::Open { .. }) => {
+                entry.breaker.begin_retry(std::time::Instant::now())
+            }
+            _ => true,
+        }
+    }
+
+    /// The open restart circuit of a service, if it has one
+    pub fn service_circuit(&self, service_id: &str) -> Option<ServiceCircuit> {
+        let now = std::time::Instant::now();
+        let mut circuits = self.circuits.lock();
+        let entry = circuits.get_mut(service_id)?;
+        entry.breaker.refresh(now);
+        if !matches!(entry.breaker.state(), CircuitState::Open { .. }) {
+            return None;
+        }
+        Some(ServiceCircuit {
+            service_id: service_id.to_string(),
+            failures: entry.breaker.failures(),
+            last_exit: entry.last_exit.clone(),
+            opened_at: entry.opened_at,
+            retry_at: entry.breaker.retry_at().map(|due| {
+                Utc::now() + chrono::Duration::from_std(due.saturating_duration_since(now)).unwrap_or_default()
+            }),
+        })
+    }
 
-                thread::sleep(std::time::Duration::from_millis(100));
+    /// Close the open restart circuit of a service without starting it; its
+    /// pending retry is dropped and it's reported stopped. Returns whether
+    /// it was open.
+    pub fn reset_circuit(&self, emitter: &dyn ProcessEventEmitter, service_id: &str) -> bool {
+        if self.service_circuit(service_id).is_none() {
+            return false;
+        }
+        self.circuits.lock().remove(service_id);
+        self.cancel_restarts(service_id);
+        emitter.emit_service_status(service_id, ServiceStatus::Stopped, None, None, None, None);
+        true
+    }
 
-                let mut should_remove = false;
-                let mut exit_code = None;
+    /// Mark a service as stopped intentionally. Returns whether it had
+    /// automatic restarts going, e.g. one waiting out its backoff.
+    fn cancel_restarts(&self, service_id: &str) -> bool {
+        match self.stopped_intentionally.lock().remove(service_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
 
+    /// Watch one run of a service for silence. Ends when that run is stopped
+    /// or replaced by a restart (which gets its own monitor).
+    fn monitor_idle(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        service_id: String,
+        pid: u32,
+        idle: IdleTimeout,
+        trace: Arc<OutputTrace>,
+        spawned: std::time::Instant,
+    ) {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            let mut fired_for = None;
+            loop {
+                thread::sleep(idle.poll_interval());
+                let Some(manager) = manager.upgrade() else { return };
+                if manager.shutdown_flag.load(Ordering::SeqCst)
+                    || manager.processes.lock().get(&service_id).map(|p| p.pid) != Some(pid)
                 {
-                    let mut processes_guard = processes.lock();
-                    if let Some(info) = processes_guard.get_mut(&service_id_exit) {
-                        match info.child.try_wait() {
-                            Ok(Some(status)) => {
-                                exit_code = status.code();
-                                should_remove = true;
-                            }
-                            Ok(None) => {
-                                // Still running
-                            }
-                            Err(_) => {
-                                should_remove = true;
-                            }
-                        }
-                    } else {
-                        // Process was removed (stopped manually or during shutdown)
-                        break;
-                    }
+                    return;
                 }
 
-                if should_remove {
-                    {
-                        let mut processes_guard = processes.lock();
-                        processes_guard.remove(&service_id_exit);
+                let last_output = trace.last_output.lock().unwrap_or(spawned);
+                if !service_idle::is_idle(last_output, fired_for, idle.timeout, std::time::Instant::now()) {
+                    continue;
+                }
+                fired_for = Some(last_output);
+
+                let silent = runtime_state::format_uptime(idle.timeout);
+                let message = match idle.action {
+                    IdleAction::Warn => format!("[cortx] No output for {}; the service may be stuck", silent),
+                    IdleAction::Stop => format!("[cortx] No output for {}; stopping the service", silent),
+                };
+                log::warn!("Service {}: {}", service_id, message);
+                emitter.emit_service_log(&service_id, LogStream::Stderr, message);
+
+                if idle.action == IdleAction::Stop {
+                    if let Err(e) = manager.stop_service(emitter.as_ref(), &service_id) {
+                        log::warn!("Failed to stop idle service {}: {}", service_id, e);
                     }
-                    let _ = runtime_store.unregister(&service_id_exit);
-
-                    // Don't emit events during shutdown
-                    if !shutdown_flag.load(Ordering::SeqCst) {
-                        emitter.emit_service_status(
-                            &service_id_exit,
-                            ServiceStatus::Stopped,
-                            None,
-                            exit_mode.clone(),
-                            exit_arg_preset.clone(),
-                        );
+                    return;
+                }
+            }
+        });
+    }
 
-                        emitter.emit_service_exit(&service_id_exit, exit_code);
+    /// Probe one run of a service every `health.interval`, reporting
+    /// `Unhealthy` after `failure_threshold` failed checks in a row and
+    /// `Running` once it recovers. Ends as soon as that run is stopped or
+    /// replaced by a restart (which gets its own checker).
+    fn monitor_health(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        service_id: String,
+        pid: u32,
+        health: HealthCheck,
+        mode: Option<String>,
+        arg_preset: Option<String>,
+        started_at: DateTime<Utc>,
+    ) {
+        self.health.lock().insert(service_id.clone(), ServiceHealth::new(service_id.clone(), pid));
+        let manager: Weak<Self> = Arc::downgrade(self);
+        thread::spawn(move || {
+            // Whether this run is still the live one
+            let current = |manager: &Self| {
+                !manager.shutdown_flag.load(Ordering::SeqCst)
+                    && manager.processes.lock().get(&service_id).map(|p| p.pid) == Some(pid)
+            };
+            loop {
+                // Short naps, so a stop ends the checker promptly
+                let next = std::time::Instant::now() + health.interval;
+                while std::time::Instant::now() < next {
+                    thread::sleep(HEALTH_POLL.min(next.saturating_duration_since(std::time::Instant::now())));
+                    let Some(manager) = manager.upgrade() else { return };
+                    if !current(&manager) {
+                        manager.forget_health(&service_id, pid);
+                        return;
                     }
+                }
 
-                    break;
+                let outcome = health.check();
+                let Some(manager) = manager.upgrade() else { return };
+                if !current(&manager) {
+                    manager.forget_health(&service_id, pid);
+                    return;
+                }
+                let (change, error) = {
+                    let mut states = manager.health.lock();
+                    let state = states
+                        .entry(service_id.clone())
+                        .or_insert_with(|| ServiceHealth::new(service_id.clone(), pid));
+                    (health.record(state, outcome, Utc::now()), state.last_error.clone())
+                };
+                if let Some(status) = change {
+                    match error {
+                        Some(error) => log::warn!("Service {} is unhealthy: {}", service_id, error),
+                        None => log::info!("Service {} is healthy again", service_id),
+                    }
+                    emitter.emit_service_status(
+                        &service_id,
+                        status,
+                        Some(pid),
+                        mode.clone(),
+                        arg_preset.clone(),
+                        Some(started_at),
+                    );
                 }
             }
         });
+    }
 
-        Ok(pid)
+    /// Drop the health state of a service's run `pid`, unless a restart
+    /// already replaced it.
+    fn forget_health(&self, service_id: &str, pid: u32) {
+        let mut states = self.health.lock();
+        if states.get(service_id).map(|h| h.pid) == Some(pid) {
+            states.remove(service_id);
+        }
+    }
+
+    /// Latest health-check results of a running service, or `None` if it has
+    /// no health check (or isn't running).
+    pub fn service_health(&self, service_id: &str) -> Option<ServiceHealth> {
+        let pid = self.processes.lock().get(service_id).map(|p| p.pid)?;
+        self.health.lock().get(service_id).filter(|h| h.pid == pid).cloned()
     }
 
+    /// Stop a service and tear down its watcher and health checker. A
+    /// pending automatic restart is cancelled, and counts as stopping it.
     pub fn stop_service(
         &self,
         emitter: &dyn ProcessEventEmitter,
         service_id: &str,
+    ) -> Result<(), String> {
+        self.watchers.lock().remove(service_id);
+        self.health.lock().remove(service_id);
+        self.circuits.lock().remove(service_id);
+        let restarting = self.cancel_restarts(service_id);
+        match self.stop_process(emitter, service_id) {
+            // Between two runs
+            Err(_) if restarting => {
+                emitter.emit_service_status(service_id, ServiceStatus::Stopped, None, None, None, None);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn stop_process(
+        &self,
+        emitter: &dyn ProcessEventEmitter,
+        service_id: &str,
     ) -> Result<(), String> {
         // First, try the in-memory path (we spawned this process).
         let owned = {
@@ -398,9 +1245,22 @@ impl ProcessManager {
         let (stopped_mode, stopped_arg_preset) = if let Some(mut info) = owned {
             let mode = info.active_mode.clone();
             let preset = info.active_arg_preset.clone();
-            let _ = kill_process_tree(info.pid);
-            let _ = info.child.kill();
-            let _ = info.child.wait();
+            emitter.emit_service_status(
+                service_id,
+                ServiceStatus::Stopping,
+                Some(info.pid),
+                mode.clone(),
+                preset.clone(),
+                Some(info.started_at),
+            );
+            let grace = info.stop_grace;
+            if !stop_gracefully(&mut info, grace) {
+                emitter.emit_service_log(
+                    service_id,
+                    LogStream::Stderr,
+                    format!("[cortx] Still running {:.1}s after the stop request, killed", grace.as_secs_f64()),
+                );
+            }
             (mode, preset)
         } else {
             // Not in our in-memory map — maybe started by another CortX
@@ -408,11 +1268,10 @@ impl ProcessManager {
             let entry = self
                 .runtime_store
                 .get(service_id)
+                .filter(|e| matches!(e.kind, EntityKind::Service))
                 .ok_or_else(|| "Service is not running".to_string())?;
-            if matches!(entry.kind, EntityKind::Service)
-                && runtime_state::is_pid_alive(entry.pid)
-            {
-                runtime_state::kill_pid_tree(entry.pid)?;
+            if runtime_state::is_pid_alive(entry.pid) {
+                stop_pid_gracefully(entry.pid, DEFAULT_STOP_GRACE)?;
             }
             (entry.mode, entry.arg_preset)
         };
@@ -425,54 +1284,356 @@ impl ProcessManager {
             None,
             stopped_mode,
             stopped_arg_preset,
+            None,
         );
 
         Ok(())
     }
 
-    pub fn is_running(&self, service_id: &str) -> bool {
-        self.runtime_store
-            .get(service_id)
-            .filter(|e| matches!(e.kind, EntityKind::Service))
-            .map(|e| runtime_state::is_pid_alive(e.pid))
-            .unwrap_or(false)
+    pub fn is_service_running(&self, service_id: &str) -> bool {
+        self.is_running(ProcessKind::Service, service_id)
     }
 
-    pub fn get_running_services(&self) -> Vec<String> {
-        self.runtime_store
-            .list()
-            .into_iter()
-            .filter(|(e, alive)| *alive && matches!(e.kind, EntityKind::Service))
-            .map(|(e, _)| e.id)
-            .collect()
+    /// How long a running service has been up, or `None` if it's stopped.
+    /// Falls back to the runtime store for services started by another instance.
+    pub fn service_uptime(&self, service_id: &str) -> Option<std::time::Duration> {
+        let started_at = match self.processes.lock().get(service_id) {
+            Some(info) => info.started_at,
+            None => {
+                let entry = self.runtime_store.get(service_id)?;
+                if !matches!(entry.kind, EntityKind::Service) || !runtime_state::is_pid_alive(entry.pid) {
+                    return None;
+                }
+                entry.started_at
+            }
+        };
+        // Clock went backwards → report zero rather than nothing
+        Some((Utc::now() - started_at).to_std().unwrap_or_default())
     }
 
-    // ========================================================================
-    // Project Scripts
-    // ========================================================================
-
-    pub fn run_script(
-        &self,
+    /// Start several services, in the given order.
+    ///
+    /// - `sequential`: each service must stay up for a short settle window
+    ///   before the next one starts. If one fails to spawn or exits during
+    ///   that window, the remaining services are not started.
+    /// - parallel: all services are spawned back to back; failures don't
+    ///   affect the others.
+    ///
+    /// Returns one result per launch, in order.
+    pub fn run_service_group(
+        self: &Arc<Self>,
         emitter: Arc<dyn ProcessEventEmitter>,
-        script_id: String,
-        working_dir: String,
-        command: String,
-        meta: RuntimeMeta,
-    ) -> Result<u32, String> {
-        if let Some(existing) = self.runtime_store.get(&script_id) {
-            if runtime_state::is_pid_alive(existing.pid) {
-                return Err(format!(
-                    "Script is already running (PID {})",
-                    existing.pid
+        launches: Vec<ServiceLaunch>,
+        sequential: bool,
+    ) -> Vec<ServiceGroupResult> {
+        let mut results = Vec::with_capacity(launches.len());
+        let mut aborted_by: Option<String> = None;
+
+        for launch in launches {
+            if let Some(ref failed) = aborted_by {
+                results.push(ServiceGroupResult::failed(
+                    launch.service_id,
+                    format!("Skipped: '{}' failed to start", failed),
                 ));
+                continue;
             }
-        }
-
-        // Emit running status
-        emitter.emit_script_status(&script_id, ScriptStatus::Running, None);
 
-        // Parse command
-        let (program, args) = parse_command(&command);
+            let service_id = launch.service_id.clone();
+            let display_name = launch.meta.display_name.clone();
+            let started = self.start_service(
+                emitter.clone(),
+                launch.service_id,
+                launch.working_dir,
+                launch.command,
+                launch.env_vars,
+                &launch.env_mode,
+                launch.mode,
+                launch.arg_preset,
+                launch.meta,
+                launch.watch,
+                launch.idle,
+                launch.health,
+                launch.restart,
+                launch.stop_grace,
+            );
+
+            match started {
+                Ok(pid) => {
+                    if sequential && !self.wait_for_settle(&service_id) {
+                        results.push(ServiceGroupResult::failed(
+                            service_id,
+                            "Service exited during startup",
+                        ));
+                        aborted_by = Some(display_name);
+                    } else {
+                        results.push(ServiceGroupResult::started(service_id, pid));
+                    }
+                }
+                Err(e) => {
+                    results.push(ServiceGroupResult::failed(service_id, e));
+                    if sequential {
+                        aborted_by = Some(display_name);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Start a project's services one after the other, in the given order,
+    /// waiting as `wait` says after each. Services that are already running
+    /// are skipped. If one fails to start, the remaining ones are not
+    /// started.
+    ///
+    /// Returns one result per launch, in order.
+    pub fn start_services_in_order(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        launches: Vec<ServiceLaunch>,
+        wait: StartWait,
+    ) -> Vec<ServiceGroupResult> {
+        let mut results = Vec::with_capacity(launches.len());
+        let mut aborted_by: Option<String> = None;
+
+        for launch in launches {
+            if self.is_service_running(&launch.service_id) {
+                results.push(ServiceGroupResult::skipped(launch.service_id));
+                continue;
+            }
+            if let Some(ref failed) = aborted_by {
+                results.push(ServiceGroupResult::failed(
+                    launch.service_id,
+                    format!("Skipped: '{}' failed to start", failed),
+                ));
+                continue;
+            }
+
+            let service_id = launch.service_id.clone();
+            let display_name = launch.meta.display_name.clone();
+            let port = launch.port;
+            let started = self.start_service(
+                emitter.clone(),
+                launch.service_id,
+                launch.working_dir,
+                launch.command,
+                launch.env_vars,
+                &launch.env_mode,
+                launch.mode,
+                launch.arg_preset,
+                launch.meta,
+                launch.watch,
+                launch.idle,
+                launch.health,
+                launch.restart,
+                launch.stop_grace,
+            );
+
+            match started.and_then(|pid| self.wait_for_start(&service_id, port, wait).map(|()| pid)) {
+                Ok(pid) => results.push(ServiceGroupResult::started(service_id, pid)),
+                Err(e) => {
+                    results.push(ServiceGroupResult::failed(service_id, e));
+                    aborted_by = Some(display_name);
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Stop services in the given order. Services that aren't running are
+    /// skipped; a failure doesn't keep the others from stopping.
+    pub fn stop_services_in_order(
+        &self,
+        emitter: &dyn ProcessEventEmitter,
+        service_ids: Vec<String>,
+    ) -> Vec<ServiceGroupResult> {
+        service_ids
+            .into_iter()
+            .map(|service_id| {
+                if !self.is_service_running(&service_id) {
+                    return ServiceGroupResult::skipped(service_id);
+                }
+                match self.stop_service(emitter, &service_id) {
+                    Ok(()) => ServiceGroupResult::stopped(service_id),
+                    Err(e) => ServiceGroupResult::failed(service_id, e),
+                }
+            })
+            .collect()
+    }
+
+    /// Wait after starting a service, as `wait` says. Errors if the service
+    /// exited meanwhile, or its port didn't open in time.
+    fn wait_for_start(&self, service_id: &str, port: Option<u16>, wait: StartWait) -> Result<(), String> {
+        const EXITED: &str = "Service exited during startup";
+        match (wait, port) {
+            (StartWait::Delay { ms }, _) => {
+                let deadline = std::time::Instant::now() + std::time::Duration::from_millis(ms);
+                while std::time::Instant::now() < deadline {
+                    if self.shutdown_flag.load(Ordering::SeqCst) || !self.is_service_running(service_id) {
+                        return Err(EXITED.to_string());
+                    }
+                    thread::sleep(std::time::Duration::from_millis(100));
+                }
+                if self.is_service_running(service_id) {
+                    Ok(())
+                } else {
+                    Err(EXITED.to_string())
+                }
+            }
+            (StartWait::Port { timeout_ms }, Some(port)) => {
+                let deadline = std::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+                loop {
+                    if self.shutdown_flag.load(Ordering::SeqCst) || !self.is_service_running(service_id) {
+                        return Err(EXITED.to_string());
+                    }
+                    if accepts_connections(port) {
+                        return Ok(());
+                    }
+                    if std::time::Instant::now() >= deadline {
+                        return Err(format!("Port {} did not open within {}ms", port, timeout_ms));
+                    }
+                    thread::sleep(std::time::Duration::from_millis(100));
+                }
+            }
+            (StartWait::Settle, _) | (StartWait::Port { .. }, None) => {
+                if self.wait_for_settle(service_id) {
+                    Ok(())
+                } else {
+                    Err(EXITED.to_string())
+                }
+            }
+        }
+    }
+
+    /// Poll until the service has been alive for `GROUP_SETTLE_MS`.
+    /// Returns false if it exited before that.
+    fn wait_for_settle(&self, service_id: &str) -> bool {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(GROUP_SETTLE_MS);
+        while std::time::Instant::now() < deadline {
+            if self.shutdown_flag.load(Ordering::SeqCst) || !self.is_service_running(service_id) {
+                return false;
+            }
+            thread::sleep(std::time::Duration::from_millis(100));
+        }
+        self.is_service_running(service_id)
+    }
+
+    /// Ids of the running services, in [`Self::list_running`] order.
+    pub fn get_running_services(&self) -> Vec<String> {
+        self.list_running()
+            .into_iter()
+            .filter(|p| p.target_kind == EntityKind::Service)
+            .map(|p| p.target_id)
+            .collect()
+    }
+
+    /// Every live service and script, including those started by other
+    /// cortx instances, sorted by [`runtime_state::sort_running`].
+    pub fn list_running(&self) -> Vec<RunningProcess> {
+        let list = self
+            .runtime_store
+            .list()
+            .into_iter()
+            .filter(|(_, alive)| *alive)
+            .map(|(entry, _)| {
+                let status = if self.owns(entry.kind, &entry.id) {
+                    RunningStatus::Running
+                } else {
+                    RunningStatus::External
+                };
+                RunningProcess::from_entry(entry, status)
+            })
+            .collect();
+        runtime_state::sort_running(list)
+    }
+
+    /// How many services and scripts are running, without building the list.
+    pub fn count_running(&self) -> RunningCounts {
+        let mut counts = RunningCounts::default();
+        for (entry, alive) in self.runtime_store.list() {
+            if alive {
+                counts.add(entry.kind);
+            }
+        }
+        counts
+    }
+
+    /// Whether this manager spawned the `kind` process `id`
+    fn owns(&self, kind: EntityKind, id: &str) -> bool {
+        let map = match kind {
+            EntityKind::Service => &self.processes,
+            EntityKind::ProjectScript => &self.scripts,
+            EntityKind::GlobalScript => &self.global_scripts,
+        };
+        map.lock().contains_key(id)
+    }
+
+    // ========================================================================
+    // Project Scripts
+    // ========================================================================
+
+    /// Run a project script. While its mutex group is held by another run
+    /// it's refused, or with `meta.queue_on_mutex` started once the group is
+    /// free: `Ok(0)` then comes back right away, and its status when it
+    /// starts.
+    pub fn run_script(
+        self: &Arc<Self>,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        script_id: String,
+        working_dir: String,
+        command: String,
+        meta: RuntimeMeta,
+    ) -> Result<u32, String> {
+        if let Some(existing) = self.runtime_store.get(&script_id) {
+            if runtime_state::is_pid_alive(existing.pid) {
+                return Err(format!(
+                    "Script is already running (PID {})",
+                    existing.pid
+                ));
+            }
+        }
+
+        let Some(group) = meta.mutex_group.clone() else {
+            return self.start_script(emitter, script_id, working_dir, command, meta, None);
+        };
+        let holder = Holder::new(script_id.clone(), meta.display_name.clone());
+        match self.mutex_groups.try_acquire(&group, holder.clone()) {
+            Ok(lock) => self.start_script(emitter, script_id, working_dir, command, meta, Some(lock)),
+            Err(blocker) if meta.queue_on_mutex => {
+                let message = mutex_group::blocked_message(&group, &blocker);
+                emitter.emit_script_log(&script_id, LogStream::Stderr, format!("[cortx] queued, {}", message));
+                self.queue_for_group(group, holder, move |manager, lock| {
+                    let started =
+                        manager.start_script(emitter.clone(), script_id.clone(), working_dir, command, meta, Some(lock));
+                    if let Err(e) = started {
+                        emitter.emit_script_log(&script_id, LogStream::Stderr, format!("Failed to start: {}", e));
+                        emitter.emit_script_status(&script_id, ScriptStatus::Failed, None);
+                    }
+                });
+                Ok(0)
+            }
+            Err(blocker) => Err(mutex_group::blocked_message(&group, &blocker)),
+        }
+    }
+
+    fn start_script(
+        &self,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        script_id: String,
+        working_dir: String,
+        command: String,
+        meta: RuntimeMeta,
+        group_lock: Option<GroupLock>,
+    ) -> Result<u32, String> {
+        // Resolve ${VAR} references from the environment before reporting it running
+        let command = expand_command(&command, &EnvMode::Inherit, None).map_err(|e| e.to_string())?;
+
+        // Emit running status
+        emitter.emit_script_status(&script_id, ScriptStatus::Running, None);
+
+        // Parse command
+        let (program, args) = parse_command(&self.shell.lock(), &command);
 
         // Build command
         let mut cmd = Command::new(&program);
@@ -500,6 +1661,9 @@ impl ProcessManager {
 
         let log_path = self.runtime_store.log_path(&script_id);
 
+        if let Some(line) = self.write_echo_line(&log_path, &command, &working_dir) {
+            emitter.emit_script_log(&script_id, LogStream::Command, line);
+        }
         if let Some(stdout) = stdout {
             spawn_tee_reader(
                 stdout,
@@ -508,6 +1672,7 @@ impl ProcessManager {
                 script_id.clone(),
                 LogStream::Stdout,
                 LogTarget::ProjectScript,
+                None,
             );
         }
         if let Some(stderr) = stderr {
@@ -518,6 +1683,7 @@ impl ProcessManager {
                 script_id.clone(),
                 LogStream::Stderr,
                 LogTarget::ProjectScript,
+                None,
             );
         }
 
@@ -533,6 +1699,8 @@ impl ProcessManager {
             project_name: meta.project_name.clone(),
             mode: None,
             arg_preset: None,
+            run_id: runtime_state::new_run_id(),
+            variant_id: meta.variant_id.clone(),
         };
         if let Err(e) = self.runtime_store.register(&entry) {
             log::warn!("Failed to register project script {} in runtime store: {}", script_id, e);
@@ -549,6 +1717,11 @@ impl ProcessManager {
                     pid,
                     active_mode: None,
                     active_arg_preset: None,
+                    variant_id: meta.variant_id.clone(),
+                    started_at: Utc::now(),
+                    stop_grace: DEFAULT_STOP_GRACE,
+                    stdin: None,
+                    group_lock,
                 },
             );
         }
@@ -556,66 +1729,17 @@ impl ProcessManager {
         // Update status with PID
         emitter.emit_script_status(&script_id, ScriptStatus::Running, Some(pid));
 
-        // Spawn exit watcher
-        let scripts = self.scripts.clone();
-        let shutdown_flag = self.shutdown_flag.clone();
         let script_id_exit = script_id.clone();
-        let runtime_store = self.runtime_store.clone();
-        thread::spawn(move || {
-            loop {
-                if shutdown_flag.load(Ordering::SeqCst) {
-                    break;
-                }
-
-                thread::sleep(std::time::Duration::from_millis(100));
-
-                let mut should_remove = false;
-                let mut exit_code = None;
-
-                {
-                    let mut scripts_guard = scripts.lock();
-                    if let Some(info) = scripts_guard.get_mut(&script_id_exit) {
-                        match info.child.try_wait() {
-                            Ok(Some(status)) => {
-                                exit_code = status.code();
-                                should_remove = true;
-                            }
-                            Ok(None) => {}
-                            Err(_) => {
-                                should_remove = true;
-                            }
-                        }
-                    } else {
-                        break;
-                    }
-                }
-
-                if should_remove {
-                    {
-                        let mut scripts_guard = scripts.lock();
-                        scripts_guard.remove(&script_id_exit);
-                    }
-                    let _ = runtime_store.unregister(&script_id_exit);
-
-                    if !shutdown_flag.load(Ordering::SeqCst) {
-                        let success = exit_code.map(|c| c == 0).unwrap_or(false);
-
-                        emitter.emit_script_status(
-                            &script_id_exit,
-                            if success {
-                                ScriptStatus::Completed
-                            } else {
-                                ScriptStatus::Failed
-                            },
-                            None,
-                        );
-
-                        emitter.emit_script_exit(&script_id_exit, exit_code, success);
-                    }
-
-                    break;
-                }
-            }
+        self.reaper.watch(ExitWatch {
+            kind: ProcessKind::ProjectScript,
+            id: script_id.clone(),
+            pid,
+            on_exit: Box::new(move |exit_code| {
+                let success = exit_code.map(|c| c == 0).unwrap_or(false);
+                let status = if success { ScriptStatus::Completed } else { ScriptStatus::Failed };
+                emitter.emit_script_status(&script_id_exit, status, None);
+                emitter.emit_script_exit(&script_id_exit, exit_code, success);
+            }),
         });
 
         Ok(pid)
@@ -632,19 +1756,17 @@ impl ProcessManager {
         };
 
         if let Some(mut info) = owned {
-            let _ = kill_process_tree(info.pid);
-            let _ = info.child.kill();
-            let _ = info.child.wait();
+            let grace = info.stop_grace;
+            stop_gracefully(&mut info, grace);
         } else {
             // Cross-instance fallback: kill by PID from the store.
             let entry = self
                 .runtime_store
                 .get(script_id)
+                .filter(|e| matches!(e.kind, EntityKind::ProjectScript))
                 .ok_or_else(|| "Script is not running".to_string())?;
-            if matches!(entry.kind, EntityKind::ProjectScript)
-                && runtime_state::is_pid_alive(entry.pid)
-            {
-                runtime_state::kill_pid_tree(entry.pid)?;
+            if runtime_state::is_pid_alive(entry.pid) {
+                stop_pid_gracefully(entry.pid, DEFAULT_STOP_GRACE)?;
             }
         }
 
@@ -654,43 +1776,117 @@ impl ProcessManager {
     }
 
     pub fn is_script_running(&self, script_id: &str) -> bool {
-        self.runtime_store
-            .get(script_id)
-            .filter(|e| matches!(e.kind, EntityKind::ProjectScript))
-            .map(|e| runtime_state::is_pid_alive(e.pid))
-            .unwrap_or(false)
+        self.is_running(ProcessKind::ProjectScript, script_id)
     }
 
     // ========================================================================
     // Global Scripts
     // ========================================================================
 
+    /// Run a global script. Its mutex group, if any, is handled as by
+    /// `run_script`.
     pub fn run_global_script(
-        &self,
+        self: &Arc<Self>,
         emitter: Arc<dyn ProcessEventEmitter>,
         script_id: String,
         working_dir: String,
         program: String,
         args: Vec<String>,
         env_vars: Option<HashMap<String, String>>,
+        env_mode: &EnvMode,
         meta: RuntimeMeta,
     ) -> Result<u32, String> {
-        if let Some(existing) = self.runtime_store.get(&script_id) {
-            if runtime_state::is_pid_alive(existing.pid) {
-                return Err(format!(
-                    "Global script is already running (PID {})",
-                    existing.pid
-                ));
+        let Some(group) = meta.mutex_group.clone() else {
+            return self.start_global_script(emitter, script_id, working_dir, program, args, env_vars, env_mode, meta, None);
+        };
+        // Already running, rather than blocked by itself
+        if !meta.allow_concurrent {
+            if let Some(existing) = self.runtime_store.get(&script_id) {
+                if runtime_state::is_pid_alive(existing.pid) {
+                    return Err(format!(
+                        "Global script is already running (PID {})",
+                        existing.pid
+                    ));
+                }
+            }
+        }
+
+        let holder = Holder::new(script_id.clone(), meta.display_name.clone());
+        match self.mutex_groups.try_acquire(&group, holder.clone()) {
+            Ok(lock) => self.start_global_script(
+                emitter, script_id, working_dir, program, args, env_vars, env_mode, meta, Some(lock),
+            ),
+            Err(blocker) if meta.queue_on_mutex => {
+                let message = mutex_group::blocked_message(&group, &blocker);
+                emitter.emit_global_script_log(&script_id, LogStream::Stderr, format!("[cortx] queued, {}", message));
+                let env_mode = env_mode.clone();
+                self.queue_for_group(group, holder, move |manager, lock| {
+                    let started = manager.start_global_script(
+                        emitter.clone(),
+                        script_id.clone(),
+                        working_dir,
+                        program,
+                        args,
+                        env_vars,
+                        &env_mode,
+                        meta,
+                        Some(lock),
+                    );
+                    if let Err(e) = started {
+                        emitter.emit_global_script_log(&script_id, LogStream::Stderr, format!("Failed to start: {}", e));
+                        emitter.emit_global_script_status(&script_id, ScriptStatus::Failed, None);
+                    }
+                });
+                Ok(0)
             }
+            Err(blocker) => Err(mutex_group::blocked_message(&group, &blocker)),
         }
+    }
+
+    fn start_global_script(
+        &self,
+        emitter: Arc<dyn ProcessEventEmitter>,
+        script_id: String,
+        working_dir: String,
+        program: String,
+        args: Vec<String>,
+        env_vars: Option<HashMap<String, String>>,
+        env_mode: &EnvMode,
+        meta: RuntimeMeta,
+        group_lock: Option<GroupLock>,
+    ) -> Result<u32, String> {
+        // A second run of a script that allows it gets a key of its own
+        let run_id = runtime_state::new_run_id();
+        let key = match self.runtime_store.get(&script_id) {
+            Some(existing) if runtime_state::is_pid_alive(existing.pid) => {
+                if !meta.allow_concurrent {
+                    return Err(format!(
+                        "Global script is already running (PID {})",
+                        existing.pid
+                    ));
+                }
+                run_key(&script_id, Some(&run_id))
+            }
+            _ => script_id.clone(),
+        };
 
-        emitter.emit_global_script_status(&script_id, ScriptStatus::Running, None);
+        // Refuse self-invocation loops before anything is reported as running
+        let lineage_env = crate::run_guard::guard_run(&script_id, self.max_run_depth.load(Ordering::Relaxed))
+            .map_err(|e| e.to_string())?;
+
+        emitter.emit_global_script_status(&key, ScriptStatus::Running, None);
 
         let mut cmd = Command::new(&program);
         cmd.args(&args)
             .current_dir(&working_dir)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
+        if self.interactive_stdin.load(Ordering::Relaxed) {
+            cmd.stdin(Stdio::piped());
+        }
+
+        // Start from a sanitized environment unless the script inherits everything
+        crate::spawn_env::prepare_env(&mut cmd, env_mode);
 
         // Force UTF-8 output on Windows to avoid cp1252 encoding errors
         #[cfg(target_os = "windows")]
@@ -704,6 +1900,7 @@ impl ProcessManager {
                 cmd.env(key, value);
             }
         }
+        cmd.envs(lineage_env);
 
         apply_spawn_flags(&mut cmd);
 
@@ -714,17 +1911,25 @@ impl ProcessManager {
         let pid = child.id();
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
+        let stdin = child.stdin.take();
 
-        let log_path = self.runtime_store.log_path(&script_id);
+        let log_path = self.runtime_store.log_path(&key);
+        let command_display = shell_join(&program, &args, QuoteStyle::native());
 
+        if let Some(line) = self.write_echo_line(&log_path, &command_display, &working_dir) {
+            emitter.emit_global_script_log(&key, LogStream::Command, line);
+        }
+        // Prompts are worth reporting only when the script can be answered
+        let prompts = if stdin.is_some() { self.prompts.lock().clone() } else { None };
         if let Some(stdout) = stdout {
             spawn_tee_reader(
                 stdout,
                 log_path.clone(),
                 emitter.clone(),
-                script_id.clone(),
+                key.clone(),
                 LogStream::Stdout,
-                LogTarget::GlobalScript,
+                LogTarget::GlobalScript(prompts.clone()),
+                None,
             );
         }
         if let Some(stderr) = stderr {
@@ -732,15 +1937,15 @@ impl ProcessManager {
                 stderr,
                 log_path.clone(),
                 emitter.clone(),
-                script_id.clone(),
+                key.clone(),
                 LogStream::Stderr,
-                LogTarget::GlobalScript,
+                LogTarget::GlobalScript(prompts),
+                None,
             );
         }
 
-        let command_display = format!("{} {}", program, args.join(" "));
         let entry = RuntimeEntry {
-            id: script_id.clone(),
+            id: key.clone(),
             kind: EntityKind::GlobalScript,
             pid,
             display_name: meta.display_name.clone(),
@@ -751,91 +1956,52 @@ impl ProcessManager {
             project_name: meta.project_name.clone(),
             mode: None,
             arg_preset: None,
+            run_id,
+            variant_id: meta.variant_id.clone(),
         };
         if let Err(e) = self.runtime_store.register(&entry) {
-            log::warn!("Failed to register global script {} in runtime store: {}", script_id, e);
+            log::warn!("Failed to register global script {} in runtime store: {}", key, e);
         }
 
         {
             let mut global = self.global_scripts.lock();
             global.insert(
-                script_id.clone(),
+                key.clone(),
                 ProcessInfo {
                     child,
-                    service_id: script_id.clone(),
+                    service_id: key.clone(),
                     pid,
                     active_mode: None,
                     active_arg_preset: None,
+                    variant_id: meta.variant_id.clone(),
+                    started_at: Utc::now(),
+                    stop_grace: DEFAULT_STOP_GRACE,
+                    stdin,
+                    group_lock,
                 },
             );
         }
 
-        emitter.emit_global_script_status(&script_id, ScriptStatus::Running, Some(pid));
-
-        let global = self.global_scripts.clone();
-        let shutdown_flag = self.shutdown_flag.clone();
-        let script_id_exit = script_id.clone();
-        let runtime_store = self.runtime_store.clone();
-        thread::spawn(move || {
-            loop {
-                if shutdown_flag.load(Ordering::SeqCst) {
-                    break;
-                }
-
-                thread::sleep(std::time::Duration::from_millis(100));
-
-                let mut should_remove = false;
-                let mut exit_code = None;
-
-                {
-                    let mut global_guard = global.lock();
-                    if let Some(info) = global_guard.get_mut(&script_id_exit) {
-                        match info.child.try_wait() {
-                            Ok(Some(status)) => {
-                                exit_code = status.code();
-                                should_remove = true;
-                            }
-                            Ok(None) => {}
-                            Err(_) => {
-                                should_remove = true;
-                            }
-                        }
-                    } else {
-                        break;
-                    }
-                }
-
-                if should_remove {
-                    {
-                        let mut global_guard = global.lock();
-                        global_guard.remove(&script_id_exit);
-                    }
-                    let _ = runtime_store.unregister(&script_id_exit);
-
-                    if !shutdown_flag.load(Ordering::SeqCst) {
-                        let success = exit_code.map(|c| c == 0).unwrap_or(false);
-
-                        emitter.emit_global_script_status(
-                            &script_id_exit,
-                            if success {
-                                ScriptStatus::Completed
-                            } else {
-                                ScriptStatus::Failed
-                            },
-                            None,
-                        );
-
-                        emitter.emit_global_script_exit(&script_id_exit, exit_code, success);
-                    }
+        emitter.emit_global_script_status(&key, ScriptStatus::Running, Some(pid));
 
-                    break;
-                }
-            }
+        let key_exit = key.clone();
+        self.reaper.watch(ExitWatch {
+            kind: ProcessKind::GlobalScript,
+            id: key,
+            pid,
+            on_exit: Box::new(move |exit_code| {
+                let success = exit_code.map(|c| c == 0).unwrap_or(false);
+                let status = if success { ScriptStatus::Completed } else { ScriptStatus::Failed };
+                emitter.emit_global_script_status(&key_exit, status, None);
+                emitter.emit_global_script_exit(&key_exit, exit_code, success);
+            }),
         });
 
         Ok(pid)
     }
 
+    /// Stop the global script run `script_id` (a [`run_key`]: the script id
+    /// stops its first run, see `global_script_runs` for the others)
     pub fn stop_global_script(
         &self,
         emitter: &dyn ProcessEventEmitter,
@@ -847,18 +2013,16 @@ impl ProcessManager {
         };
 
         if let Some(mut info) = owned {
-            let _ = kill_process_tree(info.pid);
-            let _ = info.child.kill();
-            let _ = info.child.wait();
+            let grace = info.stop_grace;
+            stop_gracefully(&mut info, grace);
         } else {
             let entry = self
                 .runtime_store
                 .get(script_id)
+                .filter(|e| matches!(e.kind, EntityKind::GlobalScript))
                 .ok_or_else(|| "Global script is not running".to_string())?;
-            if matches!(entry.kind, EntityKind::GlobalScript)
-                && runtime_state::is_pid_alive(entry.pid)
-            {
-                runtime_state::kill_pid_tree(entry.pid)?;
+            if runtime_state::is_pid_alive(entry.pid) {
+                stop_pid_gracefully(entry.pid, DEFAULT_STOP_GRACE)?;
             }
         }
 
@@ -867,21 +2031,174 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Whether any run of the global script `script_id` is going, its first
+    /// or a concurrent one
     pub fn is_global_script_running(&self, script_id: &str) -> bool {
-        self.runtime_store
+        !self.global_script_runs(script_id).is_empty()
+    }
+
+    /// Runs of the global script `script_id` that are going, oldest first.
+    /// Their `id` is the [`run_key`] to stop them or feed their input by.
+    pub fn global_script_runs(&self, script_id: &str) -> Vec<RuntimeEntry> {
+        let mut runs: Vec<RuntimeEntry> = self
+            .runtime_store
+            .list()
+            .into_iter()
+            .filter(|(entry, alive)| {
+                *alive
+                    && entry.kind == EntityKind::GlobalScript
+                    && split_run_key(&entry.id).0 == script_id
+            })
+            .map(|(entry, _)| entry)
+            .collect();
+        runs.sort_by_key(|entry| entry.started_at);
+        runs
+    }
+
+    /// Write `data` to the stdin of a global script this instance started
+    /// with interactive stdin (see `set_interactive_stdin`).
+    pub fn write_stdin(&self, script_id: &str, data: &[u8]) -> Result<(), String> {
+        let mut global = self.global_scripts.lock();
+        let info = global
+            .get_mut(script_id)
+            .ok_or_else(|| "Global script is not running".to_string())?;
+        let stdin = info
+            .stdin
+            .as_mut()
+            .ok_or_else(|| "The script's input is closed".to_string())?;
+        stdin
+            .write_all(data)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write to the script's input: {}", e))
+    }
+
+    /// Close a global script's stdin, so it reads end of file (Ctrl+D in a
+    /// terminal). Nothing to do once closed.
+    pub fn close_stdin(&self, script_id: &str) -> Result<(), String> {
+        let mut global = self.global_scripts.lock();
+        let info = global
+            .get_mut(script_id)
+            .ok_or_else(|| "Global script is not running".to_string())?;
+        info.stdin = None;
+        Ok(())
+    }
+
+    /// Whether `write_stdin` can feed the global script `script_id`
+    pub fn accepts_stdin(&self, script_id: &str) -> bool {
+        self.global_scripts
+            .lock()
             .get(script_id)
-            .filter(|e| matches!(e.kind, EntityKind::GlobalScript))
+            .is_some_and(|info| info.stdin.is_some())
+    }
+
+    /// Next fire times of scheduled global scripts, and the pause switch
+    pub fn scheduler(&self) -> &Arc<Scheduler> {
+        &self.scheduler
+    }
+
+    /// The run holding mutex group `group`, if any (see `mutex_group`)
+    pub fn mutex_group_holder(&self, group: &str) -> Option<Holder> {
+        self.mutex_groups.holder(group)
+    }
+
+    /// Wait in a thread for `group` to be free, then `start` a run holding
+    /// it. Given up on shutdown.
+    fn queue_for_group<F>(self: &Arc<Self>, group: String, holder: Holder, start: F)
+    where
+        F: FnOnce(&Arc<Self>, GroupLock) + Send + 'static,
+    {
+        // Weak, so a queued run never keeps the manager alive
+        let manager: Weak<Self> = Arc::downgrade(self);
+        let groups = self.mutex_groups.clone();
+        let shutdown = self.shutdown_flag.clone();
+        let name = group.clone();
+        let spawned = thread::Builder::new().name("cortx-mutex-queue".into()).spawn(move || {
+            let give_up = || shutdown.load(Ordering::SeqCst) || manager.strong_count() == 0;
+            let Some(lock) = groups.acquire_waiting(&group, holder, give_up) else { return };
+            if let Some(manager) = manager.upgrade() {
+                start(&manager, lock);
+            }
+        });
+        if let Err(e) = spawned {
+            log::error!("Failed to queue a run for mutex group {}: {}", name, e);
+        }
+    }
+
+    // ========================================================================
+    // Any kind
+    // ========================================================================
+
+    /// Stop whatever runs under `id`, for callers that only have an id and
+    /// its kind. Same as the `stop_*` method of that kind.
+    pub fn stop(&self, kind: ProcessKind, id: &str, emitter: &dyn ProcessEventEmitter) -> Result<(), String> {
+        match kind {
+            ProcessKind::Service => self.stop_service(emitter, id),
+            ProcessKind::ProjectScript => self.stop_script(emitter, id),
+            ProcessKind::GlobalScript => self.stop_global_script(emitter, id),
+        }
+    }
+
+    /// Whether a process of `kind` runs under `id`, started by this instance
+    /// or another one.
+    pub fn is_running(&self, kind: ProcessKind, id: &str) -> bool {
+        self.runtime_store
+            .get(id)
+            .filter(|e| e.kind == kind.entity_kind())
             .map(|e| runtime_state::is_pid_alive(e.pid))
             .unwrap_or(false)
     }
 
+    /// The map processes of `kind` live in. Code that locks more than one
+    /// map at once takes them in `ProcessKind::ALL` order.
+    fn map(&self, kind: ProcessKind) -> &ProcessMap {
+        match kind {
+            ProcessKind::Service => &self.processes,
+            ProcessKind::ProjectScript => &self.scripts,
+            ProcessKind::GlobalScript => &self.global_scripts,
+        }
+    }
+
     // ========================================================================
     // Shutdown
     // ========================================================================
 
+    /// Gracefully stop every service and script this instance started, for
+    /// the StopAll shortcut: services first, those in `service_order` in
+    /// that order, then scripts. Processes of other cortx instances are left
+    /// alone, and unlike [`Self::stop_all`] the manager stays usable.
+    pub fn stop_owned(&self, emitter: &dyn ProcessEventEmitter, service_order: &[String]) -> StopAllSummary {
+        let owned: Vec<RunningProcess> =
+            self.list_running().into_iter().filter(|p| p.status == RunningStatus::Running).collect();
+        let (mut services, scripts): (Vec<RunningProcess>, Vec<RunningProcess>) =
+            owned.into_iter().partition(|p| p.target_kind == EntityKind::Service);
+        services.sort_by_key(|p| service_order.iter().position(|id| *id == p.target_id).unwrap_or(usize::MAX));
+
+        let mut summary = StopAllSummary::default();
+        for process in services.into_iter().chain(scripts) {
+            let kind = ProcessKind::from(process.target_kind);
+            let stopped = match kind {
+                ProcessKind::Service => &mut summary.services,
+                ProcessKind::ProjectScript | ProcessKind::GlobalScript => &mut summary.scripts,
+            };
+            match self.stop(kind, &process.target_id, emitter) {
+                Ok(()) => stopped.push(process.name),
+                Err(e) => summary.failed.push(format!("{}: {}", process.name, e)),
+            }
+        }
+        summary
+    }
+
     pub fn stop_all(&self) {
         // Set shutdown flag to stop monitoring threads
         self.shutdown_flag.store(true, Ordering::SeqCst);
+        self.reaper.wake();
+
+        // No restarts from here on; health checkers see the flag
+        self.watchers.lock().clear();
+        self.health.lock().clear();
+        for (_, flag) in self.stopped_intentionally.lock().drain() {
+            flag.store(true, Ordering::SeqCst);
+        }
 
         // Give monitoring threads a moment to see the flag
         thread::sleep(std::time::Duration::from_millis(50));
@@ -923,6 +2240,17 @@ impl ProcessManager {
             let _ = self.runtime_store.unregister(id);
         }
 
+        // Ask everything to exit first, and give it a short moment to
+        // (together rather than each its own `stop_grace`)
+        for (_, pid) in processes_to_kill
+            .iter()
+            .chain(scripts_to_kill.iter())
+            .chain(global_scripts_to_kill.iter())
+        {
+            request_stop(*pid);
+        }
+        self.wait_for_exits(SHUTDOWN_STOP_GRACE);
+
         // Kill all service processes
         for (service_id, pid) in &processes_to_kill {
             log::info!("Stopping service {} (PID: {})", service_id, pid);
@@ -976,6 +2304,11 @@ impl ProcessManager {
             }
         }
 
+        // Close persisted service logs once their writers are done
+        for (_, writer) in self.log_writers.lock().drain() {
+            writer.close();
+        }
+
         // Final verification - try to kill any remaining processes
         for (_, pid) in &processes_to_kill {
             let _ = kill_process_tree_robust(*pid);
@@ -990,14 +2323,29 @@ impl ProcessManager {
         log::info!("All services and scripts stopped");
     }
 
-    /// Check if any processes are still running
-    pub fn has_running_processes(&self) -> bool {
-        let processes = self.processes.lock();
-        let scripts = self.scripts.lock();
-        let global = self.global_scripts.lock();
-        !processes.is_empty() || !scripts.is_empty() || !global.is_empty()
-    }
-}
+    /// Wait until every process in the three maps has exited, or `grace`
+    /// has passed
+    fn wait_for_exits(&self, grace: std::time::Duration) {
+        let deadline = std::time::Instant::now() + grace;
+        loop {
+            let all_exited = ProcessKind::ALL.iter().all(|&kind| {
+                self.map(kind)
+                    .lock()
+                    .values_mut()
+                    .all(|info| !matches!(info.child.try_wait(), Ok(None)))
+            });
+            if all_exited || std::time::Instant::now() >= deadline {
+                return;
+            }
+            thread::sleep(STOP_POLL);
+        }
+    }
+
+    /// Check if any processes are still running
+    pub fn has_running_processes(&self) -> bool {
+        ProcessKind::ALL.iter().any(|&kind| !self.map(kind).lock().is_empty())
+    }
+}
 
 impl Drop for ProcessManager {
     fn drop(&mut self) {
@@ -1007,10 +2355,230 @@ impl Drop for ProcessManager {
     }
 }
 
+// ============================================================================
+// Exit reaping
+// ============================================================================
+
+/// The three kinds of processes the manager runs, one map each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    Service,
+    ProjectScript,
+    GlobalScript,
+}
+
+impl ProcessKind {
+    /// Also the order the maps are locked in when more than one is held
+    pub const ALL: [ProcessKind; 3] = [ProcessKind::Service, ProcessKind::ProjectScript, ProcessKind::GlobalScript];
+
+    /// How processes of this kind are recorded in the runtime store
+    pub fn entity_kind(self) -> EntityKind {
+        match self {
+            ProcessKind::Service => EntityKind::Service,
+            ProcessKind::ProjectScript => EntityKind::ProjectScript,
+            ProcessKind::GlobalScript => EntityKind::GlobalScript,
+        }
+    }
+
+    /// Position in `Reaper::maps`
+    fn index(self) -> usize {
+        match self {
+            ProcessKind::Service => 0,
+            ProcessKind::ProjectScript => 1,
+            ProcessKind::GlobalScript => 2,
+        }
+    }
+}
+
+impl From<EntityKind> for ProcessKind {
+    fn from(kind: EntityKind) -> Self {
+        match kind {
+            EntityKind::Service => ProcessKind::Service,
+            EntityKind::ProjectScript => ProcessKind::ProjectScript,
+            EntityKind::GlobalScript => ProcessKind::GlobalScript,
+        }
+    }
+}
+
+/// A process the reaper reports on. `on_exit` gets the exit code once the
+/// process exits on its own; stopped processes are dropped silently, since
+/// the stop path reports them.
+struct ExitWatch {
+    kind: ProcessKind,
+    id: String,
+    /// Guards against a newer run registered under the same id
+    pid: u32,
+    on_exit: Box<dyn FnOnce(Option<i32>) + Send>,
+}
+
+/// One thread that sweeps every running process for exits, instead of a
+/// polling thread per process. Each sweep takes each map's lock once. An
+/// exited process is removed from its map and the runtime store, then its
+/// `on_exit` runs — except during shutdown, when nothing is emitted. The
+/// thread starts with the first watch, sleeps while nothing runs, and ends
+/// on shutdown.
+struct Reaper {
+    /// Services, project scripts, global scripts (see `ProcessKind::index`)
+    maps: [ProcessMap; 3],
+    runtime_store: Arc<RuntimeStore>,
+    shutdown_flag: Arc<AtomicBool>,
+    watches: Mutex<Vec<ExitWatch>>,
+    wakeup: Condvar,
+    started: Once,
+}
+
+impl Reaper {
+    fn new(maps: [ProcessMap; 3], runtime_store: Arc<RuntimeStore>, shutdown_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            maps,
+            runtime_store,
+            shutdown_flag,
+            watches: Mutex::new(Vec::new()),
+            wakeup: Condvar::new(),
+            started: Once::new(),
+        }
+    }
+
+    /// Start reporting on a process already stored in its map.
+    fn watch(self: &Arc<Self>, watch: ExitWatch) {
+        self.started.call_once(|| {
+            let reaper = self.clone();
+            let spawned = thread::Builder::new()
+                .name("cortx-reaper".into())
+                .spawn(move || reaper.run());
+            if let Err(e) = spawned {
+                log::error!("Failed to start the process reaper: {}", e);
+            }
+        });
+        self.watches.lock().push(watch);
+        self.wakeup.notify_all();
+    }
+
+    /// Wake the thread so it notices the shutdown flag.
+    fn wake(&self) {
+        // Under the lock, so the wakeup can't slip in between the thread's
+        // flag check and its wait
+        let _watches = self.watches.lock();
+        self.wakeup.notify_all();
+    }
+
+    fn run(&self) {
+        loop {
+            {
+                let mut watches = self.watches.lock();
+                while watches.is_empty() && !self.shutdown_flag.load(Ordering::SeqCst) {
+                    self.wakeup.wait(&mut watches);
+                }
+            }
+            if self.shutdown_flag.load(Ordering::SeqCst) {
+                return;
+            }
+            thread::sleep(std::time::Duration::from_millis(REAP_INTERVAL_MS));
+            self.sweep();
+        }
+    }
+
+    fn sweep(&self) {
+        let mut pending = std::mem::take(&mut *self.watches.lock());
+        let mut kept = Vec::with_capacity(pending.len());
+        let mut exited = Vec::new();
+
+        for kind in ProcessKind::ALL {
+            let (of_kind, rest): (Vec<ExitWatch>, Vec<ExitWatch>) =
+                pending.into_iter().partition(|w| w.kind == kind);
+            pending = rest;
+            if of_kind.is_empty() {
+                continue;
+            }
+            let mut map = self.maps[kind.index()].lock();
+            for watch in of_kind {
+                let status = match map.get_mut(&watch.id) {
+                    // Stopped, or replaced by a newer run with its own watch
+                    None => continue,
+                    Some(info) if info.pid != watch.pid => continue,
+                    Some(info) => info.child.try_wait(),
+                };
+                match status {
+                    Ok(None) => kept.push(watch),
+                    Ok(Some(status)) => {
+                        map.remove(&watch.id);
+                        exited.push((watch, status.code()));
+                    }
+                    Err(_) => {
+                        map.remove(&watch.id);
+                        exited.push((watch, None));
+                    }
+                }
+            }
+        }
+
+        // Watches added during the sweep are already in the list
+        self.watches.lock().extend(kept);
+
+        for (watch, exit_code) in exited {
+            let _ = self.runtime_store.unregister(&watch.id);
+            // Don't emit events during shutdown
+            if !self.shutdown_flag.load(Ordering::SeqCst) {
+                (watch.on_exit)(exit_code);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Platform-specific process kill helpers
 // ============================================================================
 
+/// Stop a process we spawned: ask its tree to exit, give it `grace` to do
+/// so, then kill whatever is left of the tree. Returns whether it exited
+/// within the grace period.
+fn stop_gracefully(info: &mut ProcessInfo, grace: std::time::Duration) -> bool {
+    request_stop(info.pid);
+    let deadline = std::time::Instant::now() + grace;
+    let exited = loop {
+        match info.child.try_wait() {
+            Ok(None) if std::time::Instant::now() < deadline => thread::sleep(STOP_POLL),
+            Ok(None) => break false,
+            Ok(Some(_)) | Err(_) => break true,
+        }
+    };
+    // Past the grace, or descendants that outlived it
+    let _ = kill_process_tree(info.pid);
+    let _ = info.child.kill();
+    let _ = info.child.wait();
+    exited
+}
+
+/// [`stop_gracefully`] for a process another instance spawned, known only
+/// by PID
+fn stop_pid_gracefully(pid: u32, grace: std::time::Duration) -> Result<(), String> {
+    request_stop(pid);
+    let deadline = std::time::Instant::now() + grace;
+    while runtime_state::is_pid_alive(pid) && std::time::Instant::now() < deadline {
+        thread::sleep(STOP_POLL);
+    }
+    runtime_state::kill_pid_tree(pid)
+}
+
+/// Ask a process tree to exit: `taskkill` without `/F` sends the windows
+/// of the tree a close request
+#[cfg(target_os = "windows")]
+fn request_stop(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/PID", &pid.to_string()])
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+}
+
+/// Ask a process tree to exit: SIGTERM to its process group
+#[cfg(not(target_os = "windows"))]
+fn request_stop(pid: u32) {
+    let _ = Command::new("kill")
+        .args(["-TERM", &format!("-{}", pid)])
+        .output();
+}
+
 /// Kill a process and all its child processes on Windows (basic version)
 #[cfg(target_os = "windows")]
 fn kill_process_tree(pid: u32) -> Result<(), std::io::Error> {
@@ -1081,17 +2649,10 @@ fn kill_process_tree_robust(pid: u32) -> Result<(), String> {
     Ok(())
 }
 
-/// Kill a process and all its child processes on Unix (basic version)
+/// Kill a process and all its child processes on Unix (basic version).
+/// Callers ask it to exit first (`stop_gracefully`).
 #[cfg(not(target_os = "windows"))]
 fn kill_process_tree(pid: u32) -> Result<(), std::io::Error> {
-    // Try to kill the process group
-    let _ = Command::new("kill")
-        .args(["-TERM", &format!("-{}", pid)])
-        .output();
-
-    // Give it a moment, then force kill
-    thread::sleep(std::time::Duration::from_millis(100));
-
     let _ = Command::new("kill")
         .args(["-KILL", &format!("-{}", pid)])
         .output();
@@ -1144,71 +2705,1283 @@ fn kill_process_tree_robust(pid: u32) -> Result<(), String> {
 // ============================================================================
 // Tee reader: write each child output line to the log file AND emit it via
 // the emitter for live UI consumers. Two writers (stdout + stderr threads)
-// each hold their own File handle so they don't share a Mutex.
+// each hold their own File handle so they don't share a Mutex. A service's
+// persisted session log (`service_log`) is the exception: both share it so
+// rotation sees every line.
 // ============================================================================
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 enum LogTarget {
-    Service,
+    /// With the session's persisted log, if enabled
+    Service(Option<Arc<ServiceLogWriter>>),
     ProjectScript,
-    GlobalScript,
+    /// With the prompt detector, if prompts are reported
+    GlobalScript(Option<Arc<PromptDetector>>),
+}
+
+/// What a service printed, as far as the exit watcher needs to know to tell
+/// a failed start from a normal exit.
+#[derive(Default)]
+struct OutputTrace {
+    saw_stdout: AtomicBool,
+    /// When the last line on either stream arrived, for idle monitoring
+    last_output: Mutex<Option<std::time::Instant>>,
+    stderr_tail: Mutex<VecDeque<String>>,
+    /// Readers still running; output is complete once this is back to zero
+    open_readers: AtomicUsize,
+}
+
+impl OutputTrace {
+    fn record(&self, stream: &LogStream, line: &str) {
+        *self.last_output.lock() = Some(std::time::Instant::now());
+        match stream {
+            LogStream::Stdout => self.saw_stdout.store(true, Ordering::SeqCst),
+            LogStream::Command => {}
+            LogStream::Stderr => {
+                let mut tail = self.stderr_tail.lock();
+                if tail.len() == ERROR_EXCERPT_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line.to_string());
+            }
+        }
+    }
+
+    /// Wait (bounded) for the readers to hit EOF, so lines still sitting in
+    /// the pipes are accounted for.
+    fn wait_drained(&self, timeout: std::time::Duration) {
+        let deadline = std::time::Instant::now() + timeout;
+        while self.open_readers.load(Ordering::SeqCst) > 0 && std::time::Instant::now() < deadline {
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+}
+
+/// Whether something listens on `port` on the loopback interface
+fn accepts_connections(port: u16) -> bool {
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+    std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_millis(200)).is_ok()
 }
 
-fn spawn_tee_reader<R: std::io::Read + Send + 'static>(
+/// The stderr excerpt to report if a service that exited with `exit_code`
+/// after `uptime` failed to start: non-zero exit, within [`EARLY_EXIT_MS`],
+/// and nothing on stdout. `None` for a normal exit. A failed start with a
+/// silent stderr still gets an (empty) excerpt, since that's the flag.
+fn early_failure_excerpt(
+    trace: &OutputTrace,
+    exit_code: Option<i32>,
+    uptime: std::time::Duration,
+) -> Option<String> {
+    let failed = matches!(exit_code, Some(code) if code != 0);
+    if !failed || uptime > std::time::Duration::from_millis(EARLY_EXIT_MS) {
+        return None;
+    }
+    trace.wait_drained(std::time::Duration::from_millis(OUTPUT_DRAIN_MS));
+    if trace.saw_stdout.load(Ordering::SeqCst) {
+        return None;
+    }
+    let tail = trace.stderr_tail.lock();
+    Some(tail.iter().map(String::as_str).collect::<Vec<_>>().join("\n"))
+}
+
+fn spawn_tee_reader<R: Read + Send + 'static>(
     source: R,
     log_path: PathBuf,
     emitter: Arc<dyn ProcessEventEmitter>,
     id: String,
     stream: LogStream,
     target: LogTarget,
+    trace: Option<Arc<OutputTrace>>,
 ) {
+    if let Some(trace) = &trace {
+        trace.open_readers.fetch_add(1, Ordering::SeqCst);
+    }
     thread::spawn(move || {
         let mut log = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&log_path)
             .ok();
-        let reader = BufReader::new(source);
-        for line in reader.lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(_) => continue,
-            };
+        let mut emit_line = |line: String| {
             if let Some(file) = log.as_mut() {
                 let _ = writeln!(file, "{}", line);
             }
-            match target {
-                LogTarget::Service => emitter.emit_service_log(&id, stream.clone(), line),
+            if let Some(trace) = &trace {
+                trace.record(&stream, &line);
+            }
+            match &target {
+                LogTarget::Service(persist) => {
+                    if let Some(writer) = persist {
+                        let _ = writer.write_line(&line);
+                    }
+                    emitter.emit_service_log(&id, stream.clone(), line)
+                }
                 LogTarget::ProjectScript => emitter.emit_script_log(&id, stream.clone(), line),
-                LogTarget::GlobalScript => {
+                LogTarget::GlobalScript(_) => {
                     emitter.emit_global_script_log(&id, stream.clone(), line)
                 }
             }
+        };
+        let prompts = match &target {
+            LogTarget::GlobalScript(prompts) => prompts.clone(),
+            _ => None,
+        };
+
+        let mut splitter = LineSplitter::default();
+        match prompts {
+            None => read_chunks(source, |chunk| {
+                for line in splitter.push(chunk) {
+                    emit_line(line);
+                }
+            }),
+            Some(prompts) => {
+                // Reads move to a thread of their own, so a lull in the
+                // output can be told from a line that's still arriving
+                let (tx, rx) = mpsc::channel::<Vec<u8>>();
+                thread::spawn(move || {
+                    read_chunks(source, |chunk| {
+                        let _ = tx.send(chunk.to_vec());
+                    })
+                });
+                loop {
+                    // A prompt leaves its line open while the script waits
+                    let chunk = if prompts.is_prompt(&splitter.partial()) {
+                        match rx.recv_timeout(PROMPT_QUIET) {
+                            Ok(chunk) => chunk,
+                            Err(mpsc::RecvTimeoutError::Timeout) => {
+                                let prompt = splitter.take_partial().unwrap_or_default();
+                                emit_line(prompt.clone());
+                                emitter.emit_global_script_prompt(&id, prompt);
+                                continue;
+                            }
+                            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                        }
+                    } else {
+                        match rx.recv() {
+                            Ok(chunk) => chunk,
+                            Err(_) => break,
+                        }
+                    };
+                    for line in splitter.push(&chunk) {
+                        emit_line(line);
+                    }
+                }
+            }
+        }
+        if let Some(line) = splitter.take_partial() {
+            emit_line(line);
+        }
+        if let Some(trace) = &trace {
+            trace.open_readers.fetch_sub(1, Ordering::SeqCst);
         }
     });
 }
 
+/// Hand each chunk read from `source` to `f`, until it ends or fails
+fn read_chunks(mut source: impl Read, mut f: impl FnMut(&[u8])) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match source.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => f(&buf[..read]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+    }
+}
+
 // ============================================================================
 // Command parsing
 // ============================================================================
 
-fn parse_command(command: &str) -> (String, Vec<String>) {
-    #[cfg(target_os = "windows")]
-    {
-        // On Windows, run through cmd
-        (
-            "cmd".to_string(),
-            vec!["/C".to_string(), command.to_string()],
-        )
+/// The line a run's output starts with under `ScriptsConfig::echo_command`:
+/// the resolved command, then where it runs as a shell comment.
+pub fn echo_line(command: &str, working_dir: &str) -> String {
+    format!("$ {}  # in {}", command, working_dir)
+}
+
+fn parse_command(shell: &ShellConfig, command: &str) -> (String, Vec<String>) {
+    runtime_state::shell_wrap_with(shell, command)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service_restart::CircuitConfig;
+    use std::time::{Duration, Instant};
+
+    #[derive(Default)]
+    struct RecordingEmitter {
+        logs: Mutex<Vec<String>>,
+        statuses: Mutex<Vec<ServiceStatus>>,
+        /// When each of `statuses` was emitted
+        status_times: Mutex<Vec<Instant>>,
+        exits: Mutex<Vec<(Option<i32>, Option<String>)>>,
+        global_exits: Mutex<Vec<(String, Option<i32>)>>,
+        global_logs: Mutex<Vec<String>>,
+        prompts: Mutex<Vec<String>>,
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // On Unix, run through sh
-        (
-            "sh".to_string(),
-            vec!["-c".to_string(), command.to_string()],
-        )
+    impl ProcessEventEmitter for RecordingEmitter {
+        fn emit_service_log(&self, _service_id: &str, _stream: LogStream, content: String) {
+            self.logs.lock().push(content);
+        }
+        fn emit_service_status(
+            &self,
+            _service_id: &str,
+            status: ServiceStatus,
+            _pid: Option<u32>,
+            _active_mode: Option<String>,
+            _active_arg_preset: Option<String>,
+            _started_at: Option<DateTime<Utc>>,
+        ) {
+            self.statuses.lock().push(status);
+            self.status_times.lock().push(Instant::now());
+        }
+        fn emit_service_exit(&self, _service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>) {
+            self.exits.lock().push((exit_code, error_excerpt));
+        }
+        fn emit_script_log(&self, _script_id: &str, _stream: LogStream, _content: String) {}
+        fn emit_script_status(&self, _script_id: &str, _status: ScriptStatus, _pid: Option<u32>) {}
+        fn emit_script_exit(&self, _script_id: &str, _exit_code: Option<i32>, _success: bool) {}
+        fn emit_global_script_log(&self, _script_id: &str, _stream: LogStream, content: String) {
+            self.global_logs.lock().push(content);
+        }
+        fn emit_global_script_status(&self, _script_id: &str, _status: ScriptStatus, _pid: Option<u32>) {}
+        fn emit_global_script_exit(&self, script_id: &str, exit_code: Option<i32>, _success: bool) {
+            self.global_exits.lock().push((script_id.to_string(), exit_code));
+        }
+        fn emit_global_script_prompt(&self, _script_id: &str, prompt: String) {
+            self.prompts.lock().push(prompt);
+        }
     }
-}
 
+    /// Start `command` as a service and wait for it to exit. Returns the
+    /// statuses it went through and its exit event.
+    fn run_service(command: &str) -> (Vec<ServiceStatus>, (Option<i32>, Option<String>)) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+
+        manager
+            .start_service(
+                emitter.clone(),
+                "svc".into(),
+                dir.path().to_string_lossy().to_string(),
+                command.into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("svc"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.exits.lock().is_empty() {
+            assert!(Instant::now() < deadline, "service never exited");
+            thread::sleep(Duration::from_millis(20));
+        }
+        let statuses = emitter.statuses.lock().clone();
+        let exit = emitter.exits.lock()[0].clone();
+        (statuses, exit)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn instant_failure_is_reported_as_error() {
+        let (statuses, (code, excerpt)) =
+            run_service("echo 'loading config' >&2; echo 'missing-binary: not found' >&2; exit 127");
+        assert_eq!(
+            statuses,
+            vec![ServiceStatus::Starting, ServiceStatus::Running, ServiceStatus::Error]
+        );
+        assert_eq!(code, Some(127));
+        assert_eq!(excerpt.as_deref(), Some("loading config\nmissing-binary: not found"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn excerpt_keeps_only_the_last_stderr_lines() {
+        let (_, (_, excerpt)) = run_service("for i in $(seq 1 25); do echo \"line $i\" >&2; done; exit 1");
+        let excerpt = excerpt.unwrap();
+        assert_eq!(excerpt.lines().count(), ERROR_EXCERPT_LINES);
+        assert_eq!(excerpt.lines().last(), Some("line 25"));
+        assert_eq!(excerpt.lines().next(), Some("line 16"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runtime_exits_are_plain_stops() {
+        // Printed to stdout first: it got going, then failed
+        let (statuses, (code, excerpt)) = run_service("echo listening; exit 2");
+        assert_eq!(statuses.last(), Some(&ServiceStatus::Stopped));
+        assert_eq!((code, excerpt), (Some(2), None));
+
+        // Clean exit
+        let (statuses, (code, excerpt)) = run_service("exit 0");
+        assert_eq!(statuses.last(), Some(&ServiceStatus::Stopped));
+        assert_eq!((code, excerpt), (Some(0), None));
+    }
+
+    /// Start `echo ready; sleep 30` as a service that goes idle after 300ms
+    #[cfg(unix)]
+    fn start_silent_service(action: IdleAction) -> (tempfile::TempDir, Arc<ProcessManager>, Arc<RecordingEmitter>) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let idle = IdleTimeout { timeout: Duration::from_millis(300), action };
+
+        manager
+            .start_service(
+                emitter.clone(),
+                "svc".into(),
+                dir.path().to_string_lossy().to_string(),
+                "echo ready; sleep 30".into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("svc"),
+                None,
+                Some(idle),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        (dir, manager, emitter)
+    }
+
+    #[cfg(unix)]
+    fn idle_warnings(emitter: &RecordingEmitter) -> usize {
+        emitter.logs.lock().iter().filter(|l| l.contains("No output for")).count()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn silent_services_are_stopped_past_the_idle_timeout() {
+        let (_dir, manager, emitter) = start_silent_service(IdleAction::Stop);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while manager.is_service_running("svc") {
+            assert!(Instant::now() < deadline, "idle service was never stopped");
+            thread::sleep(Duration::from_millis(20));
+        }
+        let logs = emitter.logs.lock().clone();
+        assert_eq!(logs.first().map(String::as_str), Some("ready"));
+        assert!(logs.last().unwrap().contains("stopping the service"));
+        assert_eq!(emitter.statuses.lock().last(), Some(&ServiceStatus::Stopped));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn idle_warnings_fire_once_and_keep_the_service() {
+        let (_dir, manager, emitter) = start_silent_service(IdleAction::Warn);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while idle_warnings(&emitter) == 0 {
+            assert!(Instant::now() < deadline, "no idle warning");
+            thread::sleep(Duration::from_millis(20));
+        }
+        // Several timeouts later: still running, still a single warning
+        thread::sleep(Duration::from_millis(1000));
+        assert!(manager.is_service_running("svc"));
+        assert_eq!(idle_warnings(&emitter), 1);
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn start_all_skips_running_services_and_stops_at_a_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let launch = |id: &str, command: &str, port: Option<u16>| ServiceLaunch {
+            service_id: id.into(),
+            working_dir: dir.path().to_string_lossy().to_string(),
+            command: command.into(),
+            env_vars: None,
+            env_mode: EnvMode::Inherit,
+            mode: None,
+            arg_preset: None,
+            meta: RuntimeMeta::new(id),
+            watch: None,
+            idle: None,
+            health: None,
+            port,
+            restart: None,
+            stop_grace: None,
+        };
+
+        let running = launch("running", "sleep 30", None);
+        manager
+            .start_service(
+                emitter.clone(),
+                running.service_id,
+                running.working_dir,
+                running.command,
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                running.meta,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let results = manager.start_services_in_order(
+            emitter.clone(),
+            vec![
+                launch("web", "sleep 30", Some(listener.local_addr().unwrap().port())),
+                launch("running", "sleep 30", None),
+                launch("broken", "exit 1", None),
+                launch("after", "sleep 30", None),
+            ],
+            StartWait::Port { timeout_ms: 2000 },
+        );
+        assert!(results[0].pid.is_some());
+        assert!(results[1].skipped);
+        assert_eq!(results[2].error.as_deref(), Some("Service exited during startup"));
+        assert_eq!(results[3].error.as_deref(), Some("Skipped: 'broken' failed to start"));
+        assert!(!manager.is_service_running("after"));
+
+        let ids = ["after", "broken", "running", "web"].map(String::from).to_vec();
+        let stopped = manager.stop_services_in_order(emitter.as_ref(), ids);
+        let skipped: Vec<bool> = stopped.iter().map(|r| r.skipped).collect();
+        assert_eq!(skipped, vec![true, true, false, false]);
+        assert!(stopped.iter().all(|r| r.error.is_none()));
+        assert!(!manager.is_service_running("web") && !manager.is_service_running("running"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn health_checks_follow_the_port_and_stop_with_the_service() {
+        use crate::service_health::Probe;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let health = HealthCheck {
+            probe: Probe::Tcp(listener.local_addr().unwrap().port()),
+            interval: Duration::from_millis(50),
+            timeout: Duration::from_millis(200),
+            failure_threshold: 2,
+        };
+        manager
+            .start_service(
+                emitter.clone(),
+                "svc".into(),
+                dir.path().to_string_lossy().to_string(),
+                "sleep 30".into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("svc"),
+                None,
+                None,
+                Some(health),
+                None,
+                None,
+            )
+            .unwrap();
+        let wait_for = |what: &str, done: &dyn Fn() -> bool| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !done() {
+                assert!(Instant::now() < deadline, "{}", what);
+                thread::sleep(Duration::from_millis(20));
+            }
+        };
+
+        wait_for("no health check", &|| manager.service_health("svc").is_some_and(|h| h.last_check_at.is_some()));
+        let healthy = manager.service_health("svc").unwrap();
+        assert!(healthy.healthy && healthy.latency_ms.is_some());
+
+        drop(listener);
+        wait_for("never unhealthy", &|| emitter.statuses.lock().contains(&ServiceStatus::Unhealthy));
+        assert!(manager.is_service_running("svc"));
+        assert!(!manager.service_health("svc").unwrap().healthy);
+
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+        assert!(manager.service_health("svc").is_none());
+        // The checker is gone: nothing is reported after the stop
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(emitter.statuses.lock().last(), Some(&ServiceStatus::Stopped));
+    }
+
+    /// Start `command` as the service `svc` with the given restart config
+    #[cfg(unix)]
+    fn start_restarting_service(
+        command: &str,
+        restart: AutoRestart,
+    ) -> (tempfile::TempDir, Arc<ProcessManager>, Arc<RecordingEmitter>) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        manager
+            .start_service(
+                emitter.clone(),
+                "svc".into(),
+                dir.path().to_string_lossy().to_string(),
+                command.into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("svc"),
+                None,
+                None,
+                None,
+                Some(restart),
+                None,
+            )
+            .unwrap();
+        (dir, manager, emitter)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn crashed_services_restart_with_backoff_until_max_retries() {
+        use crate::models::RestartPolicy;
+
+        let restart = AutoRestart {
+            policy: RestartPolicy::OnFailure { max_retries: 2 },
+            backoff: Duration::from_millis(100),
+            circuit: CircuitConfig::from_config(None),
+        };
+        let (_dir, manager, emitter) = start_restarting_service("exit 3", restart);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.statuses.lock().last() != Some(&ServiceStatus::Error) {
+            assert!(Instant::now() < deadline, "never gave up");
+            thread::sleep(Duration::from_millis(20));
+        }
+        // Nothing after giving up
+        thread::sleep(Duration::from_millis(400));
+
+        let statuses = emitter.statuses.lock().clone();
+        use ServiceStatus::*;
+        assert_eq!(
+            statuses,
+            vec![Starting, Running, Restarting, Starting, Running, Restarting, Starting, Running, Error]
+        );
+        let exits = emitter.exits.lock().clone();
+        assert_eq!(exits.len(), 3);
+        assert!(exits.iter().all(|(code, _)| *code == Some(3)));
+
+        // Backoff of 100ms, then doubled
+        let times = emitter.status_times.lock().clone();
+        assert!(times[3] - times[2] >= Duration::from_millis(100));
+        assert!(times[6] - times[5] >= Duration::from_millis(200));
+        assert!(emitter.logs.lock().iter().any(|line| line.contains("giving up")));
+        assert!(!manager.is_service_running("svc"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stopping_a_service_cancels_its_pending_restart() {
+        use crate::models::RestartPolicy;
+
+        let restart = AutoRestart {
+            policy: RestartPolicy::Always { max_retries: 5 },
+            backoff: Duration::from_millis(400),
+            circuit: CircuitConfig::from_config(None),
+        };
+        let (_dir, manager, emitter) = start_restarting_service("exit 0", restart);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !emitter.statuses.lock().contains(&ServiceStatus::Restarting) {
+            assert!(Instant::now() < deadline, "never restarting");
+            thread::sleep(Duration::from_millis(20));
+        }
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+
+        // Well past the backoff: it was not started again
+        thread::sleep(Duration::from_millis(800));
+        let statuses = emitter.statuses.lock().clone();
+        assert_eq!(statuses.iter().filter(|s| **s == ServiceStatus::Starting).count(), 1);
+        assert_eq!(statuses.last(), Some(&ServiceStatus::Stopped));
+        assert!(!manager.is_service_running("svc"));
+        // Nothing left pending: stopping again reports it's not running
+        assert!(manager.stop_service(emitter.as_ref(), "svc").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn services_crashing_too_often_open_their_circuit_until_reset() {
+        use crate::models::{RestartCircuitConfig, RestartPolicy};
+
+        let circuit = |cool_down_secs| {
+            CircuitConfig::from_config(Some(&RestartCircuitConfig {
+                max_failures: Some(3),
+                window_secs: Some(60),
+                cool_down_secs: Some(cool_down_secs),
+            }))
+        };
+        let restart = AutoRestart {
+            policy: RestartPolicy::OnFailure { max_retries: 10 },
+            backoff: Duration::from_millis(20),
+            circuit: circuit(0),
+        };
+        let (_dir, manager, emitter) = start_restarting_service("exit 3", restart);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.statuses.lock().last() != Some(&ServiceStatus::CircuitOpen) {
+            assert!(Instant::now() < deadline, "circuit never opened");
+            thread::sleep(Duration::from_millis(20));
+        }
+        // Nothing after it opened, well under max_retries
+        thread::sleep(Duration::from_millis(300));
+        let statuses = emitter.statuses.lock().clone();
+        use ServiceStatus::*;
+        assert_eq!(
+            statuses,
+            vec![Starting, Running, Restarting, Starting, Running, Restarting, Starting, Running, CircuitOpen]
+        );
+        assert!(emitter.logs.lock().iter().any(|line| line.contains("3 failed runs")));
+
+        let open = manager.service_circuit("svc").unwrap();
+        assert_eq!((open.failures, open.last_exit.as_str(), open.retry_at), (3, "code 3", None));
+
+        assert!(manager.reset_circuit(emitter.as_ref(), "svc"));
+        assert!(manager.service_circuit("svc").is_none());
+        assert_eq!(emitter.statuses.lock().last(), Some(&Stopped));
+        assert!(!manager.reset_circuit(emitter.as_ref(), "svc"));
+
+        // With a cool-down, it's retried once and opens again when that fails
+        let restart = AutoRestart { circuit: circuit(1), ..restart };
+        let (_dir, manager, emitter) = start_restarting_service("exit 3", restart);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.statuses.lock().iter().filter(|s| **s == CircuitOpen).count() < 2 {
+            assert!(Instant::now() < deadline, "never retried");
+            thread::sleep(Duration::from_millis(20));
+        }
+        let statuses = emitter.statuses.lock().clone();
+        assert_eq!(statuses[statuses.len() - 4..], [CircuitOpen, Starting, Running, CircuitOpen]);
+        assert!(manager.service_circuit("svc").unwrap().retry_at.is_some());
+
+        // Stopping it drops the circuit and its pending retry
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+        assert!(manager.service_circuit("svc").is_none());
+    }
+
+    /// Start `command` as the service `svc`, stopped with `stop_grace`, and
+    /// wait until it's ready (it prints `ready`)
+    #[cfg(unix)]
+    fn start_stoppable_service(
+        command: &str,
+        stop_grace: Duration,
+    ) -> (tempfile::TempDir, Arc<ProcessManager>, Arc<RecordingEmitter>) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        manager
+            .start_service(
+                emitter.clone(),
+                "svc".into(),
+                dir.path().to_string_lossy().to_string(),
+                command.into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("svc"),
+                None,
+                None,
+                None,
+                None,
+                Some(stop_grace),
+            )
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !emitter.logs.lock().iter().any(|line| line == "ready") {
+            assert!(Instant::now() < deadline, "never ready");
+            thread::sleep(Duration::from_millis(20));
+        }
+        (dir, manager, emitter)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn services_that_handle_sigterm_exit_within_the_grace() {
+        let marker = tempfile::tempdir().unwrap();
+        let cleaned = marker.path().join("cleaned");
+        let command = format!(
+            "trap 'touch \"{}\"; exit 0' TERM; echo ready; while true; do sleep 0.1; done",
+            cleaned.display()
+        );
+        let (_dir, manager, emitter) = start_stoppable_service(&command, Duration::from_secs(5));
+
+        let started = Instant::now();
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+        assert!(started.elapsed() < Duration::from_secs(3), "waited out the grace: {:?}", started.elapsed());
+
+        // It got to clean up, and wasn't reported killed
+        assert!(cleaned.exists());
+        assert!(!emitter.logs.lock().iter().any(|line| line.contains("killed")));
+        let statuses = emitter.statuses.lock().clone();
+        assert_eq!(statuses[statuses.len() - 2..], [ServiceStatus::Stopping, ServiceStatus::Stopped]);
+        assert!(!manager.is_service_running("svc"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn services_ignoring_sigterm_are_killed_after_the_grace() {
+        let (_dir, manager, emitter) =
+            start_stoppable_service("trap '' TERM; echo ready; while true; do sleep 0.1; done", Duration::from_millis(600));
+        let pid = manager.processes.lock().get("svc").map(|p| p.pid).unwrap();
+
+        let started = Instant::now();
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(600), "killed before the grace: {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(5));
+
+        assert!(!runtime_state::is_pid_alive(pid));
+        assert!(emitter.logs.lock().iter().any(|line| line.contains("killed")));
+        assert_eq!(emitter.statuses.lock().last(), Some(&ServiceStatus::Stopped));
+        assert!(!manager.is_service_running("svc"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saving_and_restarting_relaunches_the_updated_command() {
+        // Takes half a second to exit once asked to, so the restart's stop is slow
+        let version = |v: &str| {
+            format!(
+                "trap 'stopping=1' TERM; echo ready; echo {}; n=0; \
+                 while [ $n -lt 5 ]; do sleep 0.1; [ -n \"$stopping\" ] && n=$((n+1)); done",
+                v
+            )
+        };
+        let (dir, manager, emitter) = start_stoppable_service(&version("v1"), Duration::from_secs(2));
+        let launch = |command: String| ServiceLaunch {
+            service_id: "svc".into(),
+            working_dir: dir.path().to_string_lossy().to_string(),
+            command,
+            env_vars: None,
+            env_mode: EnvMode::Inherit,
+            mode: None,
+            arg_preset: None,
+            meta: RuntimeMeta::new("svc"),
+            watch: None,
+            idle: None,
+            health: None,
+            port: None,
+            restart: None,
+            stop_grace: Some(Duration::from_secs(2)),
+        };
+        let mut service = Service::new("svc".into(), ".".into(), version("v2"));
+        service.id = "svc".into();
+
+        // A watcher fires with the launch it got at the start while the save
+        // is under way, and has to wait for the restart
+        let stale_generation = manager.generations.lock()["svc"];
+        let saving = Arc::new(std::sync::Barrier::new(2));
+        let watcher = {
+            let (manager, emitter, saving, stale) = (manager.clone(), emitter.clone(), saving.clone(), launch(version("v1")));
+            thread::spawn(move || {
+                saving.wait();
+                manager.restart_watched(emitter, stale, stale_generation)
+            })
+        };
+        let result = manager
+            .update_and_restart_service(
+                emitter.clone(),
+                "svc",
+                || {
+                    saving.wait();
+                    thread::sleep(Duration::from_millis(200));
+                    Ok(service.clone())
+                },
+                |saved| Ok(launch(saved.command.clone())),
+            )
+            .unwrap();
+        watcher.join().unwrap().unwrap();
+
+        assert!(result.restarted);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !emitter.logs.lock().iter().any(|line| line == "v2") {
+            assert!(Instant::now() < deadline, "the updated command never ran");
+            thread::sleep(Duration::from_millis(20));
+        }
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(manager.processes.lock().get("svc").map(|p| p.pid), result.pid);
+        assert_eq!(emitter.logs.lock().iter().filter(|line| *line == "v1").count(), 1, "the old command ran again");
+
+        // Not running: only saved
+        manager.stop_service(emitter.as_ref(), "svc").unwrap();
+        let result = manager
+            .update_and_restart_service(emitter.clone(), "svc", || Ok(service.clone()), |_| unreachable!("not running"))
+            .unwrap();
+        assert!(!result.restarted && result.pid.is_none());
+        assert!(!manager.is_service_running("svc"));
+    }
+
+    #[test]
+    fn late_failures_are_not_start_failures() {
+        let trace = OutputTrace::default();
+        trace.record(&LogStream::Stderr, "panic");
+        let late = Duration::from_millis(EARLY_EXIT_MS + 1);
+        assert_eq!(early_failure_excerpt(&trace, Some(1), late), None);
+        assert_eq!(early_failure_excerpt(&trace, None, Duration::ZERO), None);
+        assert_eq!(early_failure_excerpt(&trace, Some(1), Duration::ZERO).as_deref(), Some("panic"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn the_unified_api_dispatches_on_the_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+
+        manager
+            .start_service(
+                emitter.clone(),
+                "web".into(),
+                working_dir.clone(),
+                "sleep 30".into(),
+                None,
+                &EnvMode::Inherit,
+                None,
+                None,
+                RuntimeMeta::new("web"),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        manager
+            .run_script(emitter.clone(), "build".into(), working_dir.clone(), "sleep 30".into(), RuntimeMeta::new("build"))
+            .unwrap();
+        manager
+            .run_global_script(
+                emitter.clone(),
+                "deploy".into(),
+                working_dir,
+                "sleep".into(),
+                vec!["30".into()],
+                None,
+                &EnvMode::Inherit,
+                RuntimeMeta::new("deploy"),
+            )
+            .unwrap();
+
+        let running = [
+            (ProcessKind::Service, "web"),
+            (ProcessKind::ProjectScript, "build"),
+            (ProcessKind::GlobalScript, "deploy"),
+        ];
+        for (kind, id) in running {
+            for other in ProcessKind::ALL {
+                assert_eq!(manager.is_running(other, id), other == kind, "{:?} {}", other, id);
+                assert_eq!(manager.map(other).lock().contains_key(id), other == kind);
+            }
+        }
+        assert!(manager.is_service_running("web"));
+        assert!(manager.is_script_running("build"));
+        assert!(manager.is_global_script_running("deploy"));
+
+        // Under the wrong kind there is nothing to stop
+        assert!(manager.stop(ProcessKind::GlobalScript, "build", emitter.as_ref()).is_err());
+        assert!(manager.stop(ProcessKind::Service, "deploy", emitter.as_ref()).is_err());
+        assert!(manager.is_running(ProcessKind::ProjectScript, "build"));
+        assert!(manager.is_running(ProcessKind::GlobalScript, "deploy"));
+
+        for (kind, id) in running {
+            manager.stop(kind, id, emitter.as_ref()).unwrap();
+            assert!(!manager.is_running(kind, id));
+            assert!(!manager.map(kind).lock().contains_key(id));
+        }
+        assert!(!manager.has_running_processes());
+        assert_eq!(ProcessKind::from(EntityKind::ProjectScript), ProcessKind::ProjectScript);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_owned_stops_services_in_order_then_scripts_and_stays_usable() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+        let start = |id: &str| {
+            manager
+                .start_service(
+                    emitter.clone(),
+                    id.into(),
+                    working_dir.clone(),
+                    "sleep 30".into(),
+                    None,
+                    &EnvMode::Inherit,
+                    None,
+                    None,
+                    RuntimeMeta::new(id),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+        };
+
+        start("api");
+        start("db");
+        start("web");
+        manager
+            .run_script(emitter.clone(), "build".into(), working_dir.clone(), "sleep 30".into(), RuntimeMeta::new("build"))
+            .unwrap();
+
+        let order = ["web", "api"].map(String::from);
+        let summary = manager.stop_owned(emitter.as_ref(), &order);
+        assert_eq!(summary.services, vec!["web", "api", "db"]);
+        assert_eq!(summary.scripts, vec!["build"]);
+        assert!(summary.failed.is_empty());
+        assert!(!manager.has_running_processes());
+
+        // Not a shutdown: starting again works, and there's nothing left to stop
+        start("api");
+        assert!(manager.is_service_running("api"));
+        manager.stop_service(emitter.as_ref(), "api").unwrap();
+        assert_eq!(manager.stop_owned(emitter.as_ref(), &order), StopAllSummary::default());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reaper_reports_every_exit_without_leaks() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store.clone()));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+
+        // Every 5th script is long-running and gets stopped; the rest exit
+        // on their own with code i % 3
+        let mut expected: Vec<(String, Option<i32>)> = Vec::new();
+        for i in 0..50 {
+            let id = format!("script-{}", i);
+            let command = if i % 5 == 0 {
+                "sleep 30".to_string()
+            } else {
+                format!("sleep 0.0{}; exit {}", i % 10, i % 3)
+            };
+            manager
+                .run_global_script(
+                    emitter.clone(),
+                    id.clone(),
+                    working_dir.clone(),
+                    "sh".into(),
+                    vec!["-c".into(), command],
+                    None,
+                    &EnvMode::Inherit,
+                    RuntimeMeta::new(id.clone()),
+                )
+                .unwrap();
+            if i % 5 == 0 {
+                manager.stop_global_script(emitter.as_ref(), &id).unwrap();
+            } else {
+                expected.push((id, Some(i % 3)));
+            }
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.global_exits.lock().len() < expected.len() {
+            assert!(Instant::now() < deadline, "missing exit events");
+            thread::sleep(Duration::from_millis(20));
+        }
+        // Stopped scripts must not report an exit on top of their stop
+        thread::sleep(Duration::from_millis(REAP_INTERVAL_MS * 4));
+        let mut exits = emitter.global_exits.lock().clone();
+        exits.sort();
+        expected.sort();
+        assert_eq!(exits, expected);
+
+        assert!(!manager.has_running_processes());
+        assert!(store.list().is_empty());
+        assert!(manager.reaper.watches.lock().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn interactive_stdin_feeds_a_prompting_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+        let run = |id: &str, command: &str| {
+            manager
+                .run_global_script(
+                    emitter.clone(),
+                    id.into(),
+                    working_dir.clone(),
+                    "sh".into(),
+                    vec!["-c".into(), command.into()],
+                    None,
+                    &EnvMode::Inherit,
+                    RuntimeMeta::new(id),
+                )
+                .unwrap();
+        };
+
+        // Off by default: nothing to write to
+        run("plain", "sleep 30");
+        assert!(!manager.accepts_stdin("plain"));
+        assert!(manager.write_stdin("plain", b"y\n").is_err());
+        manager.stop_global_script(emitter.as_ref(), "plain").unwrap();
+
+        manager.set_interactive_stdin(true);
+        run("ask", "read answer; echo got:$answer; cat; echo eof");
+        assert!(manager.accepts_stdin("ask"));
+        manager.write_stdin("ask", b"yes\n").unwrap();
+        manager.write_stdin("ask", b"more\n").unwrap();
+        // `cat` only ends at end of file
+        manager.close_stdin("ask").unwrap();
+        assert!(manager.write_stdin("ask", b"late\n").is_err());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.global_exits.lock().is_empty() || emitter.global_logs.lock().len() < 3 {
+            assert!(Instant::now() < deadline, "the script never finished");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(emitter.global_exits.lock().clone(), vec![("ask".to_string(), Some(0))]);
+        assert_eq!(emitter.global_logs.lock().clone(), vec!["got:yes", "more", "eof"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_script_stopped_at_a_prompt_awaits_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        manager.set_interactive_stdin(true);
+        manager.set_prompt_detection(Some(PromptDetector::new(&[]).unwrap()));
+        manager
+            .run_global_script(
+                emitter.clone(),
+                "ask".into(),
+                dir.path().to_string_lossy().to_string(),
+                "sh".into(),
+                vec!["-c".into(), "echo ready; printf 'Continue? [y/N] '; read answer; echo got:$answer".into()],
+                None,
+                &EnvMode::Inherit,
+                RuntimeMeta::new("ask"),
+            )
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.prompts.lock().is_empty() {
+            assert!(Instant::now() < deadline, "the prompt was never reported");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(emitter.prompts.lock().clone(), vec!["Continue? [y/N] "]);
+        // The prompt shows although its line never ended
+        assert_eq!(emitter.global_logs.lock().clone(), vec!["ready", "Continue? [y/N] "]);
+
+        manager.write_stdin("ask", b"y\n").unwrap();
+        while emitter.global_exits.lock().is_empty() || emitter.global_logs.lock().len() < 3 {
+            assert!(Instant::now() < deadline, "the script never finished");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(emitter.global_logs.lock().clone(), vec!["ready", "Continue? [y/N] ", "got:y"]);
+        assert_eq!(emitter.prompts.lock().len(), 1);
+    }
+
+    /// Output that arrives in the chunks given, `pause` apart
+    struct Trickle {
+        chunks: VecDeque<&'static [u8]>,
+        pause: Duration,
+    }
+
+    impl Read for Trickle {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some(chunk) = self.chunks.pop_front() else {
+                return Ok(0);
+            };
+            thread::sleep(self.pause);
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn a_line_split_across_reads_is_no_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let emitter = Arc::new(RecordingEmitter::default());
+        let source = Trickle {
+            chunks: VecDeque::from([&b"key: "[..], &b"value\n"[..], &b"Password: "[..]]),
+            pause: Duration::from_millis(20),
+        };
+        spawn_tee_reader(
+            source,
+            dir.path().join("out.log"),
+            emitter.clone(),
+            "ask".into(),
+            LogStream::Stdout,
+            LogTarget::GlobalScript(Some(Arc::new(PromptDetector::new(&[]).unwrap()))),
+            None,
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.global_logs.lock().len() < 2 {
+            assert!(Instant::now() < deadline, "the output never arrived");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(emitter.global_logs.lock().clone(), vec!["key: value", "Password: "]);
+        assert!(emitter.prompts.lock().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn concurrent_runs_get_keys_of_their_own() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+        let run = |id: &str, allow_concurrent: bool| {
+            manager.run_global_script(
+                emitter.clone(),
+                id.into(),
+                working_dir.clone(),
+                "sleep".into(),
+                vec!["30".into()],
+                None,
+                &EnvMode::Inherit,
+                RuntimeMeta::new(id).with_allow_concurrent(allow_concurrent),
+            )
+        };
+
+        // Singletons refuse a second run
+        run("single", false).unwrap();
+        assert!(run("single", false).is_err());
+        assert_eq!(manager.global_script_runs("single").len(), 1);
+
+        run("multi", true).unwrap();
+        run("multi", true).unwrap();
+        run("multi", true).unwrap();
+        let runs = manager.global_script_runs("multi");
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].id, "multi");
+        for run in &runs[1..] {
+            assert_eq!(run_key("multi", Some(&run.run_id)), run.id);
+            assert_eq!(split_run_key(&run.id), ("multi", Some(run.run_id.as_str())));
+        }
+
+        // Stopping one run leaves the others going
+        manager.stop_global_script(emitter.as_ref(), &runs[1].id).unwrap();
+        let left: Vec<String> = manager.global_script_runs("multi").into_iter().map(|r| r.id).collect();
+        assert_eq!(left, vec![runs[0].id.clone(), runs[2].id.clone()]);
+        manager.stop_global_script(emitter.as_ref(), "multi").unwrap();
+        assert!(manager.is_global_script_running("multi"));
+        manager.stop_global_script(emitter.as_ref(), &runs[2].id).unwrap();
+        assert!(!manager.is_global_script_running("multi"));
+        manager.stop_global_script(emitter.as_ref(), "single").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scripts_in_a_mutex_group_never_run_together() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+        let run = |id: &str, group: &str, queue: bool| {
+            manager.run_global_script(
+                emitter.clone(),
+                id.into(),
+                working_dir.clone(),
+                "sleep".into(),
+                vec!["30".into()],
+                None,
+                &EnvMode::Inherit,
+                RuntimeMeta::new(id).with_mutex_group(Some(group.into()), queue),
+            )
+        };
+
+        assert!(run("migrate", "db", false).unwrap() > 0);
+        let refused = run("seed", "db", false).unwrap_err();
+        assert!(refused.starts_with("Blocked by 'migrate', running for"), "{}", refused);
+        assert!(!manager.is_global_script_running("seed"));
+
+        // Other groups are independent
+        run("warm", "cache", false).unwrap();
+
+        // A queued run starts once the group is released
+        assert_eq!(run("seed", "db", true).unwrap(), 0);
+        assert!(!manager.is_global_script_running("seed"));
+        manager.stop_global_script(emitter.as_ref(), "migrate").unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !manager.is_global_script_running("seed") {
+            assert!(Instant::now() < deadline, "queued run never started");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(manager.mutex_group_holder("db").unwrap().id, "seed");
+
+        manager.stop_global_script(emitter.as_ref(), "seed").unwrap();
+        manager.stop_global_script(emitter.as_ref(), "warm").unwrap();
+        assert_eq!(manager.mutex_group_holder("db"), None);
+        assert_eq!(manager.mutex_group_holder("cache"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn echo_command_starts_the_run_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(RuntimeStore::new(dir.path()).unwrap());
+        let manager = Arc::new(ProcessManager::new(store.clone()));
+        let emitter = Arc::new(RecordingEmitter::default());
+        let working_dir = dir.path().to_string_lossy().to_string();
+        manager.set_echo_command(true);
+
+        manager
+            .run_global_script(
+                emitter.clone(),
+                "greet".into(),
+                working_dir.clone(),
+                "sh".into(),
+                vec!["-c".into(), "echo hi".into()],
+                None,
+                &EnvMode::Inherit,
+                RuntimeMeta::new("greet"),
+            )
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while emitter.global_exits.lock().is_empty() || emitter.global_logs.lock().len() < 2 {
+            assert!(Instant::now() < deadline, "the script never finished");
+            thread::sleep(Duration::from_millis(20));
+        }
+        let expected = format!("$ sh -c 'echo hi'  # in {}", working_dir);
+        assert_eq!(emitter.global_logs.lock().clone(), vec![expected.clone(), "hi".to_string()]);
+        // The saved log starts the same way
+        let log = std::fs::read_to_string(store.log_path("greet")).unwrap();
+        assert_eq!(log.lines().next(), Some(expected.as_str()));
+    }
+}
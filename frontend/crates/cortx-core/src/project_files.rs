@@ -0,0 +1,327 @@
+//! How projects are laid out on disk: one `projects/<id>.json` per project,
+//! plus `projects/index.json` listing their ids and names in display order.
+//!
+//! A change to one project rewrites only its file (and the index when the
+//! list or a name changed), and a corrupt file loses that project alone: it
+//! is skipped with a warning and left on disk for repair.
+//!
+//! Data dirs of older versions keep every project in `projects.json`. While
+//! that file exists it's the source of truth: loading splits it into the
+//! directory, then renames it to `projects.json.migrated`. The rename comes
+//! last, so a migration cut short is simply done again on the next start.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::Project;
+use crate::storage::{read_json_locked, write_json_locked, StorageError};
+
+pub const PROJECTS_DIR: &str = "projects";
+pub const INDEX_FILE: &str = "index.json";
+/// Where versions before the split kept all projects
+pub const LEGACY_FILE: &str = "projects.json";
+
+/// A project in `index.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexEntry {
+    pub id: String,
+    pub name: String,
+}
+
+pub fn projects_dir(app_dir: &Path) -> PathBuf {
+    app_dir.join(PROJECTS_DIR)
+}
+
+/// File of the project `id`. Ids are UUIDs; anything else that couldn't be
+/// a file name is replaced.
+pub fn project_path(app_dir: &Path, id: &str) -> PathBuf {
+    let name: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    projects_dir(app_dir).join(format!("{}.json", name))
+}
+
+fn index_path(app_dir: &Path) -> PathBuf {
+    projects_dir(app_dir).join(INDEX_FILE)
+}
+
+pub fn index_of(projects: &[Project]) -> Vec<IndexEntry> {
+    projects
+        .iter()
+        .map(|p| IndexEntry { id: p.id.clone(), name: p.name.clone() })
+        .collect()
+}
+
+/// The project files in `dir` (not the index, nor stray `.tmp` files)
+fn project_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path.extension().is_some_and(|ext| ext == "json")
+                && path.file_name().is_some_and(|name| name != INDEX_FILE)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The project files in `dir` that parse, each with its path, and those
+/// that don't, with why
+fn read_project_files(dir: &Path) -> (Vec<(PathBuf, Project)>, Vec<(PathBuf, String)>) {
+    let mut read = Vec::new();
+    let mut unreadable = Vec::new();
+    for path in project_files(dir) {
+        match read_json_locked::<Project>(&path) {
+            Ok(project) => read.push((path, project)),
+            Err(e) => unreadable.push((path, e.to_string())),
+        }
+    }
+    (read, unreadable)
+}
+
+/// What a `projects/` directory holds
+#[derive(Debug, Default)]
+pub struct ProjectDir {
+    /// In index order, then those the index misses by name
+    pub projects: Vec<Project>,
+    /// Files that couldn't be read, with why
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// The index doesn't list exactly `projects`, or couldn't be read
+    pub index_stale: bool,
+}
+
+/// Read the `projects/` directory of `app_dir` without changing anything.
+/// Index entries without a file are dropped.
+pub fn read_dir(app_dir: &Path) -> ProjectDir {
+    let dir = projects_dir(app_dir);
+    if !dir.is_dir() {
+        return ProjectDir::default();
+    }
+    let index: Vec<IndexEntry> = match read_json_locked(&index_path(app_dir)) {
+        Ok(index) => index,
+        Err(StorageError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            log::warn!("Unreadable project index, rebuilding it: {}", e);
+            Vec::new()
+        }
+    };
+
+    let (read, unreadable) = read_project_files(&dir);
+    let mut found: Vec<Project> = read.into_iter().map(|(_, project)| project).collect();
+    let mut projects = Vec::with_capacity(found.len());
+    for entry in &index {
+        if let Some(i) = found.iter().position(|p| p.id == entry.id) {
+            projects.push(found.remove(i));
+        }
+    }
+    found.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    projects.extend(found);
+
+    let index_stale = index_of(&projects) != index;
+    ProjectDir { projects, unreadable, index_stale }
+}
+
+/// Load the projects of `app_dir`, migrating a legacy `projects.json`
+/// first and rewriting a stale index. Unreadable project files are logged
+/// and skipped.
+pub fn load(app_dir: &Path) -> Result<Vec<Project>, StorageError> {
+    let legacy = app_dir.join(LEGACY_FILE);
+    if legacy.exists() {
+        let projects: Vec<Project> = read_json_locked(&legacy)?;
+        write_all(app_dir, &projects)?;
+        let mut migrated = legacy.clone().into_os_string();
+        migrated.push(".migrated");
+        fs::rename(&legacy, migrated)?;
+        log::info!("Split {} into {}/", LEGACY_FILE, PROJECTS_DIR);
+        return Ok(projects);
+    }
+
+    let contents = read_dir(app_dir);
+    for (path, e) in &contents.unreadable {
+        log::warn!("Skipping unreadable project file {}: {}", path.display(), e);
+    }
+    if contents.index_stale {
+        write_index(app_dir, &contents.projects)?;
+    }
+    Ok(contents.projects)
+}
+
+/// Write the file of `project`
+pub fn write_project(app_dir: &Path, project: &Project) -> Result<(), StorageError> {
+    fs::create_dir_all(projects_dir(app_dir))?;
+    write_json_locked(&project_path(app_dir, &project.id), project)
+}
+
+pub fn write_index(app_dir: &Path, projects: &[Project]) -> Result<(), StorageError> {
+    fs::create_dir_all(projects_dir(app_dir))?;
+    write_json_locked(&index_path(app_dir), &index_of(projects))
+}
+
+/// Delete the file of the project `id`; nothing to do if it's gone already
+pub fn remove_project(app_dir: &Path, id: &str) -> Result<(), StorageError> {
+    match fs::remove_file(project_path(app_dir, id)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Write every project and the index, and delete the files of projects
+/// that aren't in `projects` anymore. Unreadable files are kept.
+pub fn write_all(app_dir: &Path, projects: &[Project]) -> Result<(), StorageError> {
+    for project in projects {
+        write_project(app_dir, project)?;
+    }
+    write_index(app_dir, projects)?;
+
+    let kept: HashSet<&str> = projects.iter().map(|p| p.id.as_str()).collect();
+    for (path, project) in read_project_files(&projects_dir(app_dir)).0 {
+        if !kept.contains(project.id.as_str()) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+
+    fn project(id: &str, name: &str) -> Project {
+        let mut project = Project::new(name.to_string(), format!("/work/{}", id));
+        project.id = id.to_string();
+        project
+    }
+
+    fn index(app_dir: &Path) -> Vec<String> {
+        let entries: Vec<IndexEntry> = read_json_locked(&index_path(app_dir)).unwrap();
+        entries.into_iter().map(|e| e.id).collect()
+    }
+
+    fn ids(storage: &Storage) -> Vec<String> {
+        storage.get_all_projects().into_iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn a_legacy_projects_file_is_split_on_first_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = vec![project("b", "Beta"), project("a", "Alpha")];
+        fs::write(dir.path().join(LEGACY_FILE), serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        // Order is kept, not re-sorted
+        assert_eq!(ids(&storage), ["b", "a"]);
+        assert_eq!(index(dir.path()), ["b", "a"]);
+        assert!(project_path(dir.path(), "a").exists());
+        assert!(!dir.path().join(LEGACY_FILE).exists());
+        assert!(dir.path().join("projects.json.migrated").exists());
+
+        // The next start reads the directory
+        drop(storage);
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        assert_eq!(ids(&storage), ["b", "a"]);
+    }
+
+    #[test]
+    fn a_migration_cut_short_is_done_again_from_the_legacy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut first = project("a", "Alpha");
+        first.description = Some("from projects.json".to_string());
+        let legacy = vec![first, project("b", "Beta")];
+        fs::write(dir.path().join(LEGACY_FILE), serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        // The previous run wrote one file (older content), half of another,
+        // and neither the index nor the rename
+        let projects = projects_dir(dir.path());
+        fs::create_dir_all(&projects).unwrap();
+        fs::write(project_path(dir.path(), "a"), serde_json::to_string(&project("a", "Alpha")).unwrap()).unwrap();
+        fs::write(projects.join("b.json.tmp"), "{\"id\": \"b\", \"na").unwrap();
+
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        assert_eq!(ids(&storage), ["a", "b"]);
+        assert_eq!(storage.get_project("a").unwrap().description.as_deref(), Some("from projects.json"));
+        assert_eq!(index(dir.path()), ["a", "b"]);
+        assert!(!dir.path().join(LEGACY_FILE).exists());
+    }
+
+    #[test]
+    fn restoring_a_backup_splits_its_projects_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        storage.create_project(project("a", "Alpha")).unwrap();
+        let backup = storage.create_backup().unwrap();
+
+        storage.delete_project("a").unwrap();
+        storage.create_project(project("b", "Beta")).unwrap();
+        storage.restore_backup(Path::new(&backup.path)).unwrap();
+        assert_eq!(ids(&storage), ["a"]);
+        assert!(project_path(dir.path(), "a").exists());
+        assert!(!project_path(dir.path(), "b").exists());
+        assert!(!dir.path().join(LEGACY_FILE).exists());
+    }
+
+    #[test]
+    fn the_index_is_repaired_to_match_the_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(dir.path(), &project("a", "Alpha")).unwrap();
+        write_project(dir.path(), &project("z", "Zulu")).unwrap();
+        write_project(dir.path(), &project("m", "Mike")).unwrap();
+        // "gone" has no file, "m" and "a" aren't listed
+        let listed = vec![project("z", "Zulu"), project("gone", "Gone")];
+        write_index(dir.path(), &listed).unwrap();
+
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        // Listed first, then the others by name
+        assert_eq!(ids(&storage), ["z", "a", "m"]);
+        assert_eq!(index(dir.path()), ["z", "a", "m"]);
+
+        // A missing index is rebuilt the same way
+        fs::remove_file(index_path(dir.path())).unwrap();
+        drop(storage);
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        assert_eq!(ids(&storage), ["a", "m", "z"]);
+        assert_eq!(index(dir.path()), ["a", "m", "z"]);
+    }
+
+    #[test]
+    fn saves_touch_only_the_changed_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        storage.create_project(project("a", "Alpha")).unwrap();
+        storage.create_project(project("b", "Beta")).unwrap();
+        storage.create_project(project("c", "Gamma")).unwrap();
+
+        // Hand-edited and compact: a rewrite would reformat it
+        let b_path = project_path(dir.path(), "b");
+        let b_content = serde_json::to_string(&storage.get_project("b").unwrap()).unwrap();
+        fs::write(&b_path, &b_content).unwrap();
+        // A crash while writing "c" left its previous file plus a stray .tmp
+        fs::write(project_path(dir.path(), "c").with_extension("json.tmp"), "{ trunc").unwrap();
+
+        storage.update_project("a", |p| p.name = "Alpha 2".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&b_path).unwrap(), b_content);
+
+        storage.delete_project("a").unwrap();
+        assert!(!project_path(dir.path(), "a").exists());
+        assert_eq!(fs::read_to_string(&b_path).unwrap(), b_content);
+        assert_eq!(index(dir.path()), ["b", "c"]);
+
+        // A corrupt file costs that project only, and stays for repair
+        fs::write(&b_path, "{ not json").unwrap();
+        drop(storage);
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        assert_eq!(ids(&storage), ["c"]);
+        storage.update_project("c", |p| p.name = "Gamma 2".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&b_path).unwrap(), "{ not json");
+    }
+}
@@ -0,0 +1,200 @@
+//! Keeping project roots apart.
+//!
+//! Two projects on the same folder fight over its env files (each links them
+//! to its own services), and a project inside another one has its files
+//! tracked twice. `Storage::create_project` / `update_project` reject a root
+//! another project already has (unless explicitly allowed), and report roots
+//! inside or around other projects' roots as [`RootOverlap`]s without
+//! blocking. Roots are compared canonicalized, and case-folded on Windows and
+//! macOS, whose file systems usually ignore case.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::models::{Project, RootOverlap, RootRelation};
+
+/// Whether paths differing only in case are the same folder here
+pub const CASE_INSENSITIVE_FS: bool = cfg!(any(windows, target_os = "macos"));
+
+/// `path` made absolute, with symlinks resolved as far as the path exists.
+/// Parts below the deepest existing folder (or below a dangling symlink) are
+/// kept as written, with `.` and `..` resolved.
+pub fn canonical_root(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+    let normalized = normalize(&absolute);
+
+    let mut existing = normalized.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = fs::canonicalize(existing) {
+            let mut root = strip_verbatim(canonical);
+            root.extend(rest.iter().rev());
+            return root;
+        }
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return normalized,
+        }
+    }
+}
+
+/// Resolve `.` and `..` without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// On Windows, `canonicalize` returns `\\?\C:\...`; project roots are
+/// stored without the prefix
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    #[cfg(windows)]
+    {
+        if let Some(plain) = path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+            return PathBuf::from(plain);
+        }
+    }
+    path
+}
+
+/// How `candidate` relates to each of `existing`, by index; all canonical.
+pub fn compare_roots(candidate: &Path, existing: &[PathBuf], case_insensitive: bool) -> Vec<(usize, RootRelation)> {
+    let fold = |path: &Path| {
+        if case_insensitive {
+            PathBuf::from(path.to_string_lossy().to_lowercase())
+        } else {
+            path.to_path_buf()
+        }
+    };
+    let candidate = fold(candidate);
+    existing
+        .iter()
+        .enumerate()
+        .filter_map(|(index, root)| {
+            let root = fold(root);
+            // Component-wise: "/code/app2" is not inside "/code/app"
+            let relation = if candidate == root {
+                RootRelation::Same
+            } else if candidate.starts_with(&root) {
+                RootRelation::Inside
+            } else if root.starts_with(&candidate) {
+                RootRelation::Contains
+            } else {
+                return None;
+            };
+            Some((index, relation))
+        })
+        .collect()
+}
+
+/// Projects other than `except_id` whose root overlaps `root_path`
+pub fn find_overlaps(projects: &[Project], root_path: &str, except_id: Option<&str>) -> Vec<RootOverlap> {
+    let others: Vec<&Project> = projects.iter().filter(|p| Some(p.id.as_str()) != except_id).collect();
+    let roots: Vec<PathBuf> = others.iter().map(|p| canonical_root(Path::new(&p.root_path))).collect();
+    compare_roots(&canonical_root(Path::new(root_path)), &roots, CASE_INSENSITIVE_FS)
+        .into_iter()
+        .map(|(index, relation)| RootOverlap {
+            project_id: others[index].id.clone(),
+            project_name: others[index].name.clone(),
+            root_path: others[index].root_path.clone(),
+            relation,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roots(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn same_nested_and_unrelated_roots() {
+        let existing = roots(&["/code/app", "/code/app/web", "/code", "/code/app2", "/other"]);
+        assert_eq!(
+            compare_roots(Path::new("/code/app"), &existing, false),
+            vec![(0, RootRelation::Same), (1, RootRelation::Contains), (2, RootRelation::Inside)]
+        );
+        assert!(compare_roots(Path::new("/code/ap"), &existing[..1], false).is_empty());
+        assert!(compare_roots(Path::new("/elsewhere"), &existing, false).is_empty());
+    }
+
+    #[test]
+    fn case_only_matters_on_case_sensitive_file_systems() {
+        let existing = roots(&["/Users/me/Code/App", "/Users/me/code"]);
+        assert_eq!(
+            compare_roots(Path::new("/users/me/code/app"), &existing, true),
+            vec![(0, RootRelation::Same), (1, RootRelation::Inside)]
+        );
+        assert!(compare_roots(Path::new("/users/me/code/app"), &existing, false).is_empty());
+    }
+
+    #[test]
+    fn canonical_roots_resolve_dots_and_missing_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(base.join("app")).unwrap();
+
+        assert_eq!(canonical_root(&base.join("app/./src/../")), base.join("app"));
+        // Not created yet: kept as written below what exists
+        assert_eq!(canonical_root(&base.join("app/new/deeper")), base.join("app/new/deeper"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_roots_are_the_same_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(base.join("real/app")).unwrap();
+        std::os::unix::fs::symlink(base.join("real"), base.join("link")).unwrap();
+        // Pointing at something that doesn't exist (yet)
+        std::os::unix::fs::symlink(base.join("missing"), base.join("dangling")).unwrap();
+
+        assert_eq!(canonical_root(&base.join("link/app")), base.join("real/app"));
+        assert_eq!(canonical_root(&base.join("dangling/sub")), base.join("dangling/sub"));
+
+        let existing = vec![canonical_root(&base.join("real"))];
+        assert_eq!(
+            compare_roots(&canonical_root(&base.join("link/app")), &existing, false),
+            vec![(0, RootRelation::Inside)]
+        );
+    }
+
+    #[test]
+    fn overlaps_name_the_other_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |rel: &str| dir.path().join(rel).to_string_lossy().to_string();
+        let mut outer = Project::new("monorepo".into(), path("mono"));
+        outer.id = "outer".into();
+        let mut inner = Project::new("web".into(), path("mono/web"));
+        inner.id = "inner".into();
+        let projects = vec![outer, inner];
+
+        let overlaps = find_overlaps(&projects, &path("mono/web"), None);
+        let found: Vec<(&str, RootRelation)> =
+            overlaps.iter().map(|o| (o.project_id.as_str(), o.relation)).collect();
+        assert_eq!(found, vec![("outer", RootRelation::Inside), ("inner", RootRelation::Same)]);
+        assert_eq!(overlaps[0].project_name, "monorepo");
+
+        // A project doesn't overlap itself
+        let overlaps = find_overlaps(&projects, &path("mono/web"), Some("inner"));
+        assert_eq!(overlaps.len(), 1);
+    }
+}
@@ -0,0 +1,156 @@
+//! Recognizing a script that waits at an interactive prompt.
+//!
+//! Output is read in lines, but a prompt ("Continue? [y/N] ") is printed
+//! without a newline and then the script blocks on its stdin, so the line
+//! never completes. [`LineSplitter`] keeps such a trailing partial line
+//! apart; when it matches a [`PromptDetector`] pattern the reader emits it
+//! right away and reports the script as awaiting input
+//! (`ProcessEventEmitter::emit_global_script_prompt`).
+
+use regex::Regex;
+
+use crate::models::TuiConfig;
+
+/// A partial line matching any of these is a prompt.
+/// `TuiConfig::prompt_patterns` adds to these.
+pub const DEFAULT_PROMPT_PATTERNS: &[&str] = &[
+    // Continue? [y/N]   Overwrite (y/n)
+    r"(?i)[\[(]y(?:es)?/no?[\])]\s*:?\s*$",
+    // Are you sure?
+    r"\?\s*$",
+    // Password:   Name: (the trailing space is what a prompt leaves)
+    r":\s+$",
+];
+
+/// ANSI escapes (colors), ignored when matching
+const ANSI_ESCAPE: &str = r"\x1b\[[0-9;?]*[ -/]*[@-~]";
+
+#[derive(Debug, Clone)]
+pub struct PromptDetector {
+    ansi: Regex,
+    patterns: Vec<Regex>,
+}
+
+impl PromptDetector {
+    /// The default patterns plus `extra` (from `TuiConfig::prompt_patterns`).
+    pub fn new(extra: &[String]) -> Result<Self, regex::Error> {
+        let patterns = DEFAULT_PROMPT_PATTERNS
+            .iter()
+            .copied()
+            .chain(extra.iter().map(String::as_str).filter(|p| !p.trim().is_empty()))
+            .map(Regex::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { ansi: Regex::new(ANSI_ESCAPE)?, patterns })
+    }
+
+    /// Whether `partial`, output not (yet) ended by a newline, is a prompt
+    pub fn is_prompt(&self, partial: &str) -> bool {
+        let text = self.ansi.replace_all(partial, "");
+        // A progress bar redraws its line with \r; only the last state counts
+        let text = text.rsplit('\r').next().unwrap_or_default();
+        !text.trim().is_empty() && self.patterns.iter().any(|p| p.is_match(text))
+    }
+}
+
+/// The detector `config` asks for, if any. Configured patterns that aren't
+/// valid regexes are logged and left out.
+pub fn from_config(config: &TuiConfig) -> Option<PromptDetector> {
+    if !config.detect_prompts {
+        return None;
+    }
+    let valid: Vec<String> = config
+        .prompt_patterns
+        .iter()
+        .filter(|p| match Regex::new(p) {
+            Ok(_) => true,
+            Err(e) => {
+                log::warn!("Ignoring prompt pattern {:?}: {}", p, e);
+                false
+            }
+        })
+        .cloned()
+        .collect();
+    PromptDetector::new(&valid).ok()
+}
+
+/// Cuts a stream of output chunks into lines (without their `\n` or
+/// `\r\n`), holding back the last one until it's complete.
+#[derive(Debug, Default)]
+pub struct LineSplitter {
+    partial: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// The lines `chunk` completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for &byte in chunk {
+            if byte == b'\n' {
+                if self.partial.last() == Some(&b'\r') {
+                    self.partial.pop();
+                }
+                lines.push(String::from_utf8_lossy(&self.partial).into_owned());
+                self.partial.clear();
+            } else {
+                self.partial.push(byte);
+            }
+        }
+        lines
+    }
+
+    /// The incomplete last line so far
+    pub fn partial(&self) -> String {
+        String::from_utf8_lossy(&self.partial).into_owned()
+    }
+
+    /// Hand out the incomplete last line as if it were complete; `None` if
+    /// there's none
+    pub fn take_partial(&mut self) -> Option<String> {
+        if self.partial.is_empty() {
+            return None;
+        }
+        let line = self.partial();
+        self.partial.clear();
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_prompts_are_recognized_at_the_tail() {
+        let detector = PromptDetector::new(&[]).unwrap();
+        for prompt in [
+            "Continue? [y/N] ",
+            "Overwrite existing file (y/n)",
+            "Proceed [Yes/no]: ",
+            "Are you sure?",
+            "Password: ",
+            "\x1b[1mDatabase name:\x1b[0m ",
+        ] {
+            assert!(detector.is_prompt(prompt), "{:?}", prompt);
+        }
+        for output in ["", "   ", "Downloading 45%", "Step 1: done", "Downloading 40%\rDownloading 45%"] {
+            assert!(!detector.is_prompt(output), "{:?}", output);
+        }
+
+        // Configured patterns add to the defaults
+        let detector = PromptDetector::new(&[r">\s*$".to_string(), " ".to_string()]).unwrap();
+        assert!(detector.is_prompt("mysql> "));
+        assert!(detector.is_prompt("Continue? [y/N] "));
+        assert!(PromptDetector::new(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn the_splitter_holds_back_the_partial_line() {
+        let mut splitter = LineSplitter::default();
+        assert_eq!(splitter.push(b"one\r\ntw"), vec!["one"]);
+        assert_eq!(splitter.partial(), "tw");
+        assert_eq!(splitter.push(b"o\n\nContinue? [y/N] "), vec!["two", ""]);
+        assert_eq!(splitter.take_partial().as_deref(), Some("Continue? [y/N] "));
+        assert_eq!(splitter.take_partial(), None);
+        assert_eq!(splitter.push(b"y\n"), vec!["y"]);
+    }
+}
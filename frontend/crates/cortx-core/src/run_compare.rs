@@ -0,0 +1,395 @@
+//! Comparing two recorded runs of a global script.
+//!
+//! Every run appends to the script's runtime log and records where its output
+//! starts and ends (`ExecutionRecord::log_start` / `log_end`). [`compare_runs`]
+//! lines two records up: parameter changes, duration and exit differences, and
+//! a line diff of their logs. Both logs first go through a [`NoiseFilter`]
+//! that masks timestamps, ids and other values that differ on every run, so
+//! the diff shows what actually changed. Env differences aren't part of it:
+//! runs don't record their environment.
+
+use regex::Regex;
+use serde::Serialize;
+use similar::{Algorithm, DiffTag};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::ExecutionRecord;
+use crate::runtime_state::RuntimeStore;
+use crate::storage::Storage;
+
+/// Masked before diffing, in order. `ScriptsConfig::log_noise_patterns` adds to these.
+pub const DEFAULT_NOISE_PATTERNS: &[&str] = &[
+    // ISO-8601 date-times, then bare dates and clock times
+    r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?",
+    r"\b\d{4}[-/]\d{2}[-/]\d{2}\b",
+    r"\b\d{1,2}:\d{2}:\d{2}(?:[.,]\d+)?\b",
+    // UUIDs and long hex ids / hashes
+    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+    r"\b[0-9a-f]{12,}\b",
+    // Durations: 12ms, 1.5s, 3 m
+    r"\b\d+(?:\.\d+)?\s?(?:ns|us|µs|ms|s|m|min)\b",
+    // Process ids
+    r"(?i)\bpid[ =:#]*\d+",
+];
+
+/// ANSI escapes (colors), dropped before masking
+const ANSI_ESCAPE: &str = r"\x1b\[[0-9;?]*[ -/]*[@-~]";
+
+/// What a masked value is replaced with
+const MASK: &str = "…";
+
+/// Lines of unchanged context around each diff hunk
+const DIFF_CONTEXT: usize = 3;
+
+/// Regexes whose matches are masked out of log lines before diffing.
+#[derive(Debug, Clone)]
+pub struct NoiseFilter {
+    ansi: Regex,
+    patterns: Vec<Regex>,
+}
+
+impl NoiseFilter {
+    /// The default patterns plus `extra` (from `ScriptsConfig::log_noise_patterns`).
+    pub fn new(extra: &[String]) -> Result<Self, regex::Error> {
+        let patterns = DEFAULT_NOISE_PATTERNS
+            .iter()
+            .copied()
+            .chain(extra.iter().map(String::as_str).filter(|p| !p.trim().is_empty()))
+            .map(Regex::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { ansi: Regex::new(ANSI_ESCAPE)?, patterns })
+    }
+
+    pub fn normalize_line(&self, line: &str) -> String {
+        let mut line = self.ansi.replace_all(line.trim_end(), "").into_owned();
+        for pattern in &self.patterns {
+            if pattern.is_match(&line) {
+                line = pattern.replace_all(&line, MASK).into_owned();
+            }
+        }
+        line
+    }
+}
+
+/// A value that differs between run A and run B (`None` = unset in that run).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueChange {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub a: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b: Option<String>,
+}
+
+/// Changed parameters, by key.
+pub fn param_changes(a: &HashMap<String, String>, b: &HashMap<String, String>) -> Vec<ValueChange> {
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    keys.into_iter()
+        .filter(|key| a.get(*key) != b.get(*key))
+        .map(|key| ValueChange {
+            key: key.clone(),
+            a: a.get(key).cloned(),
+            b: b.get(key).cloned(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineChange {
+    Equal,
+    /// Only in run A
+    Removed,
+    /// Only in run B
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub change: LineChange,
+    /// The line as logged (not normalized)
+    pub text: String,
+}
+
+/// A run of changes with surrounding context. Starts are 0-based line indices.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub a_start: usize,
+    pub a_len: usize,
+    pub b_start: usize,
+    pub b_len: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    /// Unified-diff style header, e.g. `@@ -12,4 +12,5 @@`
+    pub fn header(&self) -> String {
+        format!("@@ -{},{} +{},{} @@", self.a_start + 1, self.a_len, self.b_start + 1, self.b_len)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl LogDiff {
+    pub fn is_identical(&self) -> bool {
+        self.hunks.is_empty()
+    }
+}
+
+/// Line diff of two logs after noise filtering. Lines are compared in their
+/// normalized form but reported as logged (run B's version for unchanged
+/// lines).
+pub fn diff_logs(a: &str, b: &str, noise: &NoiseFilter) -> LogDiff {
+    let a_lines: Vec<&str> = a.lines().collect();
+    let b_lines: Vec<&str> = b.lines().collect();
+    let a_norm: Vec<String> = a_lines.iter().map(|l| noise.normalize_line(l)).collect();
+    let b_norm: Vec<String> = b_lines.iter().map(|l| noise.normalize_line(l)).collect();
+
+    let ops = similar::capture_diff_slices(Algorithm::Myers, &a_norm, &b_norm);
+    let mut diff = LogDiff::default();
+    for group in similar::group_diff_ops(ops, DIFF_CONTEXT) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let mut hunk = DiffHunk {
+            a_start: first.old_range().start,
+            a_len: last.old_range().end - first.old_range().start,
+            b_start: first.new_range().start,
+            b_len: last.new_range().end - first.new_range().start,
+            lines: Vec::new(),
+        };
+        for op in &group {
+            let (tag, a_range, b_range) = op.as_tag_tuple();
+            let line = |change: LineChange, text: &str| DiffLine { change, text: text.to_string() };
+            match tag {
+                DiffTag::Equal => hunk.lines.extend(b_lines[b_range].iter().map(|&l| line(LineChange::Equal, l))),
+                DiffTag::Delete | DiffTag::Replace | DiffTag::Insert => {
+                    diff.removed += a_range.len();
+                    diff.added += b_range.len();
+                    hunk.lines.extend(a_lines[a_range].iter().map(|&l| line(LineChange::Removed, l)));
+                    hunk.lines.extend(b_lines[b_range].iter().map(|&l| line(LineChange::Added, l)));
+                }
+            }
+        }
+        diff.hunks.push(hunk);
+    }
+    diff
+}
+
+/// One side of a comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSide {
+    pub record_id: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset_name: Option<String>,
+    /// Whether the run's output could be read back from the log
+    pub has_log: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunComparison {
+    pub script_id: String,
+    pub a: RunSide,
+    pub b: RunSide,
+    pub param_changes: Vec<ValueChange>,
+    /// B's duration minus A's, when both finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_delta_ms: Option<i64>,
+    pub exit_changed: bool,
+    /// `None` unless both logs are available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_diff: Option<LogDiff>,
+}
+
+/// Compare run `a` (the reference, usually the older one) with run `b`.
+pub fn compare_runs(
+    a: &ExecutionRecord,
+    b: &ExecutionRecord,
+    log_a: Option<&str>,
+    log_b: Option<&str>,
+    noise: &NoiseFilter,
+) -> RunComparison {
+    let side = |record: &ExecutionRecord, log: Option<&str>| RunSide {
+        record_id: record.id.clone(),
+        started_at: record.started_at,
+        duration_ms: record.duration_ms,
+        exit_code: record.exit_code,
+        success: record.success,
+        preset_name: record.preset_name.clone(),
+        has_log: log.is_some(),
+    };
+    RunComparison {
+        script_id: b.script_id.clone(),
+        a: side(a, log_a),
+        b: side(b, log_b),
+        param_changes: param_changes(&a.parameters_used, &b.parameters_used),
+        duration_delta_ms: match (a.duration_ms, b.duration_ms) {
+            (Some(da), Some(db)) => Some(db as i64 - da as i64),
+            _ => None,
+        },
+        exit_changed: a.exit_code != b.exit_code || a.success != b.success,
+        log_diff: match (log_a, log_b) {
+            (Some(la), Some(lb)) => Some(diff_logs(la, lb, noise)),
+            _ => None,
+        },
+    }
+}
+
+/// A run's output, read back from the script's runtime log.
+pub fn run_log(runtime_store: &RuntimeStore, record: &ExecutionRecord) -> Option<String> {
+    runtime_store.read_log_range(&record.script_id, record.log_start?, record.log_end)
+}
+
+/// Look up two runs of the same script and compare them, with the noise
+/// patterns from the scripts config.
+pub fn compare_recorded_runs(
+    storage: &Storage,
+    runtime_store: &RuntimeStore,
+    record_id_a: &str,
+    record_id_b: &str,
+) -> Result<RunComparison, String> {
+    let record = |id: &str| {
+        storage
+            .get_execution_record(id)
+            .ok_or_else(|| format!("Execution record not found: {}", id))
+    };
+    let (a, b) = (record(record_id_a)?, record(record_id_b)?);
+    if a.script_id != b.script_id {
+        return Err("Only runs of the same script can be compared".to_string());
+    }
+    let extra = storage.get_settings().scripts_config.log_noise_patterns;
+    let noise = NoiseFilter::new(&extra).map_err(|e| format!("Invalid log noise pattern: {}", e))?;
+    let (log_a, log_b) = (run_log(runtime_store, &a), run_log(runtime_store, &b));
+    Ok(compare_runs(&a, &b, log_a.as_deref(), log_b.as_deref(), &noise))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn noise() -> NoiseFilter {
+        NoiseFilter::new(&[]).unwrap()
+    }
+
+    fn changes(diff: &LogDiff) -> Vec<(LineChange, &str)> {
+        diff.hunks
+            .iter()
+            .flat_map(|h| h.lines.iter())
+            .filter(|l| l.change != LineChange::Equal)
+            .map(|l| (l.change, l.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn masks_volatile_values() {
+        let n = noise();
+        assert_eq!(
+            n.normalize_line("2024-05-01T10:22:03.123Z INFO started in 153ms (pid 4242)  "),
+            "… INFO started in … (…)"
+        );
+        assert_eq!(n.normalize_line("[10:22:03] job 3f2a9c1e-8b7d-4e6f-a1b2-c3d4e5f60718 done"), "[…] job … done");
+        assert_eq!(n.normalize_line("\x1b[32mok\x1b[0m 12 tests"), "ok 12 tests");
+
+        let custom = NoiseFilter::new(&["req-\\d+".to_string()]).unwrap();
+        assert_eq!(custom.normalize_line("handled req-991"), "handled …");
+        assert!(NoiseFilter::new(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn timestamp_churn_is_not_a_difference() {
+        let a = "2024-05-01 10:00:00 connecting\n2024-05-01 10:00:01 connected in 80ms\n10:00:02 done\n";
+        let b = "2024-05-02 09:13:44 connecting\n2024-05-02 09:13:45 connected in 95ms\n09:13:46 done\n";
+        let diff = diff_logs(a, b, &noise());
+        assert!(diff.is_identical());
+        assert_eq!((diff.added, diff.removed), (0, 0));
+    }
+
+    #[test]
+    fn real_changes_become_hunks() {
+        let a = "10:00:00 start\nstep 1\nstep 2\nstep 3\nstep 4\nstep 5\nstep 6\nstep 7\nok\n";
+        let b = "11:30:00 start\nstep 1\nstep 2\nstep 3\nstep 4\nstep 5\nstep 6\nstep 7\nERROR: disk full\nwarn\n";
+        let diff = diff_logs(a, b, &noise());
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!((diff.added, diff.removed), (2, 1));
+        assert_eq!(
+            changes(&diff),
+            vec![
+                (LineChange::Removed, "ok"),
+                (LineChange::Added, "ERROR: disk full"),
+                (LineChange::Added, "warn"),
+            ]
+        );
+        let hunk = &diff.hunks[0];
+        // 3 lines of context before the change, reported as logged in run B
+        assert_eq!(hunk.lines[0].text, "step 5");
+        assert_eq!(hunk.header(), "@@ -6,4 +6,5 @@");
+    }
+
+    #[test]
+    fn compares_params_duration_and_exit() {
+        let mut a = ExecutionRecord::new("s".into());
+        a.parameters_used = HashMap::from([("env".into(), "dev".into()), ("verbose".into(), "true".into())]);
+        a.duration_ms = Some(1200);
+        a.exit_code = Some(0);
+        a.success = true;
+        let mut b = ExecutionRecord::new("s".into());
+        b.parameters_used = HashMap::from([("env".into(), "prod".into()), ("limit".into(), "5".into())]);
+        b.duration_ms = Some(900);
+        b.exit_code = Some(1);
+
+        let cmp = compare_runs(&a, &b, Some("x\n"), None, &noise());
+        let keys: Vec<(&str, Option<&str>, Option<&str>)> = cmp
+            .param_changes
+            .iter()
+            .map(|c| (c.key.as_str(), c.a.as_deref(), c.b.as_deref()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![("env", Some("dev"), Some("prod")), ("limit", None, Some("5")), ("verbose", Some("true"), None)]
+        );
+        assert_eq!(cmp.duration_delta_ms, Some(-300));
+        assert!(cmp.exit_changed);
+        assert!(cmp.a.has_log && !cmp.b.has_log);
+        assert_eq!(cmp.log_diff, None);
+    }
+
+    #[test]
+    fn reads_each_runs_slice_of_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RuntimeStore::new(dir.path()).unwrap();
+        let mut first = ExecutionRecord::new("s".into());
+        first.log_start = Some(store.log_len("s"));
+        std::fs::write(store.log_path("s"), "run one\n").unwrap();
+        first.log_end = Some(store.log_len("s"));
+
+        let mut second = ExecutionRecord::new("s".into());
+        second.log_start = Some(store.log_len("s"));
+        let mut log = std::fs::read_to_string(store.log_path("s")).unwrap();
+        log.push_str("run two\n");
+        std::fs::write(store.log_path("s"), log).unwrap();
+
+        assert_eq!(run_log(&store, &first).as_deref(), Some("run one\n"));
+        // Unfinished: up to the end of the log
+        assert_eq!(run_log(&store, &second).as_deref(), Some("run two\n"));
+        assert_eq!(run_log(&store, &ExecutionRecord::new("s".into())), None);
+        second.log_start = Some(999);
+        assert_eq!(run_log(&store, &second), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Guard rails for scripts that invoke cortx themselves.
+//!
+//! Every global script run gets two env vars: [`RUN_DEPTH_VAR`], how many
+//! cortx runs are above it, and [`RUN_CHAIN_VAR`], the ids of those runs
+//! (colon-separated, oldest first). A cortx started from inside a script
+//! reads them back through [`RunLineage::from_env`] before running anything,
+//! so a script that (indirectly) runs itself, or a chain nested deeper than
+//! `ScriptsConfig::max_run_depth`, fails with a clear error instead of
+//! spawning forever.
+
+use thiserror::Error;
+
+pub const RUN_DEPTH_VAR: &str = "CORTX_RUN_DEPTH";
+pub const RUN_CHAIN_VAR: &str = "CORTX_RUN_CHAIN";
+
+/// Default for `ScriptsConfig::max_run_depth`
+pub const DEFAULT_MAX_RUN_DEPTH: u32 = 3;
+
+/// Most ids [`RUN_CHAIN_VAR`] carries; older ones are dropped first (the
+/// depth limit still applies to the whole chain).
+pub const MAX_CHAIN_IDS: usize = 32;
+
+const CHAIN_SEPARATOR: char = ':';
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RunGuardError {
+    #[error("Refusing to run '{script}': nested {depth} cortx runs deep (max {max}, see scriptsConfig.maxRunDepth)")]
+    TooDeep { script: String, depth: u32, max: u32 },
+    #[error("Refusing to run '{script}': it is already running further up this chain ({})", .chain.join(" -> "))]
+    Cycle { script: String, chain: Vec<String> },
+}
+
+/// Missing, empty or garbage values count as a top-level run.
+pub fn parse_depth(value: Option<&str>) -> u32 {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(0)
+}
+
+pub fn parse_chain(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or("")
+        .split(CHAIN_SEPARATOR)
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The cortx runs the current process was started under.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunLineage {
+    pub depth: u32,
+    pub chain: Vec<String>,
+}
+
+impl RunLineage {
+    pub fn from_env() -> Self {
+        Self {
+            depth: parse_depth(std::env::var(RUN_DEPTH_VAR).ok().as_deref()),
+            chain: parse_chain(std::env::var(RUN_CHAIN_VAR).ok().as_deref()),
+        }
+    }
+
+    /// Whether `script_id` may run from here. A `max_depth` of 0 disables
+    /// the depth limit; cycles are always refused.
+    pub fn check(&self, script_id: &str, max_depth: u32) -> Result<(), RunGuardError> {
+        if self.chain.iter().any(|id| id == script_id) {
+            let mut chain = self.chain.clone();
+            chain.push(script_id.to_string());
+            return Err(RunGuardError::Cycle { script: script_id.to_string(), chain });
+        }
+        if max_depth > 0 && self.depth >= max_depth {
+            return Err(RunGuardError::TooDeep {
+                script: script_id.to_string(),
+                depth: self.depth,
+                max: max_depth,
+            });
+        }
+        Ok(())
+    }
+
+    /// Env vars for the process that runs `script_id` from here.
+    pub fn child_env(&self, script_id: &str) -> [(&'static str, String); 2] {
+        let mut chain: Vec<&str> = self.chain.iter().map(String::as_str).collect();
+        chain.push(script_id);
+        let skip = chain.len().saturating_sub(MAX_CHAIN_IDS);
+        [
+            (RUN_DEPTH_VAR, self.depth.saturating_add(1).to_string()),
+            (RUN_CHAIN_VAR, chain[skip..].join(&CHAIN_SEPARATOR.to_string())),
+        ]
+    }
+}
+
+/// Check `script_id` against this process's lineage and return the env vars
+/// to run it with. Every global script start goes through here.
+pub fn guard_run(script_id: &str, max_depth: u32) -> Result<[(&'static str, String); 2], RunGuardError> {
+    let lineage = RunLineage::from_env();
+    lineage.check(script_id, max_depth)?;
+    Ok(lineage.child_env(script_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lineage(depth: u32, chain: &[&str]) -> RunLineage {
+        RunLineage { depth, chain: chain.iter().map(|id| id.to_string()).collect() }
+    }
+
+    #[test]
+    fn depth_parsing_tolerates_missing_and_garbage() {
+        assert_eq!(parse_depth(None), 0);
+        assert_eq!(parse_depth(Some("")), 0);
+        assert_eq!(parse_depth(Some("abc")), 0);
+        assert_eq!(parse_depth(Some("-2")), 0);
+        assert_eq!(parse_depth(Some(" 2 ")), 2);
+        assert_eq!(parse_chain(None), Vec::<String>::new());
+        assert_eq!(parse_chain(Some("a::b: ")), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn top_level_runs_are_unaffected() {
+        let top = RunLineage::default();
+        assert_eq!(top.check("build", DEFAULT_MAX_RUN_DEPTH), Ok(()));
+        let [depth, chain] = top.child_env("build");
+        assert_eq!(depth, (RUN_DEPTH_VAR, "1".to_string()));
+        assert_eq!(chain, (RUN_CHAIN_VAR, "build".to_string()));
+
+        // Nested runs of other scripts are fine below the limit
+        let nested = lineage(2, &["deploy", "build"]);
+        assert_eq!(nested.check("lint", 3), Ok(()));
+    }
+
+    #[test]
+    fn cycles_and_depth_are_refused() {
+        let err = lineage(2, &["deploy", "build"]).check("deploy", 3).unwrap_err();
+        assert_eq!(
+            err,
+            RunGuardError::Cycle {
+                script: "deploy".into(),
+                chain: vec!["deploy".into(), "build".into(), "deploy".into()],
+            }
+        );
+        assert!(err.to_string().contains("deploy -> build -> deploy"));
+
+        assert!(matches!(
+            lineage(3, &["a", "b", "c"]).check("d", 3),
+            Err(RunGuardError::TooDeep { depth: 3, max: 3, .. })
+        ));
+        // 0 lifts the depth limit, not the cycle check
+        assert_eq!(lineage(10, &["a"]).check("b", 0), Ok(()));
+        assert!(lineage(10, &["a"]).check("a", 0).is_err());
+    }
+
+    #[test]
+    fn chain_is_capped() {
+        let ids: Vec<String> = (0..MAX_CHAIN_IDS).map(|i| format!("s{}", i)).collect();
+        let long = RunLineage { depth: MAX_CHAIN_IDS as u32, chain: ids };
+        let [_, (_, chain)] = long.child_env("next");
+        let chain = parse_chain(Some(&chain));
+        assert_eq!(chain.len(), MAX_CHAIN_IDS);
+        assert_eq!(chain.first().map(String::as_str), Some("s1"));
+        assert_eq!(chain.last().map(String::as_str), Some("next"));
+    }
+}
@@ -21,7 +21,10 @@ use std::fs::{self, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+use crate::models::{EnvMode, Project, ShellConfig, ShellKind};
+
+/// Ordered as running processes are listed: services first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EntityKind {
     Service,
@@ -59,6 +62,118 @@ pub struct RuntimeEntry {
     pub mode: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arg_preset: Option<String>,
+    /// Fresh for every start, see [`new_run_id`]. Empty in entries written
+    /// by older versions.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub run_id: String,
+    /// Project variant it was started under (`Project::active_variant_id`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
+}
+
+pub fn new_run_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Whether a running process belongs to the cortx process listing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunningStatus {
+    /// Spawned here; its output and exit are being watched
+    Running,
+    /// Spawned by another cortx (CLI, TUI, MCP or GUI); only its PID is known
+    External,
+}
+
+/// One live service or script, as listed by `ProcessManager::list_running`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningProcess {
+    pub target_kind: EntityKind,
+    pub target_id: String,
+    /// Differs between two starts of the same target
+    pub run_id: String,
+    pub project_id: Option<String>,
+    pub name: String,
+    pub pid: u32,
+    pub started_at: DateTime<Utc>,
+    pub status: RunningStatus,
+    /// Project variant it was started under
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
+}
+
+impl RunningProcess {
+    pub fn from_entry(entry: RuntimeEntry, status: RunningStatus) -> Self {
+        // Older entries have no run id; pid + start time still tell runs apart
+        let run_id = if entry.run_id.is_empty() {
+            format!("{}-{}", entry.pid, entry.started_at.timestamp_millis())
+        } else {
+            entry.run_id
+        };
+        Self {
+            target_kind: entry.kind,
+            target_id: entry.id,
+            run_id,
+            project_id: entry.project_id,
+            name: entry.display_name,
+            pid: entry.pid,
+            started_at: entry.started_at,
+            status,
+            variant_id: entry.variant_id,
+        }
+    }
+}
+
+/// Running processes per kind, for badges
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningCounts {
+    pub services: usize,
+    pub scripts: usize,
+    pub global_scripts: usize,
+}
+
+impl RunningCounts {
+    pub fn add(&mut self, kind: EntityKind) {
+        match kind {
+            EntityKind::Service => self.services += 1,
+            EntityKind::ProjectScript => self.scripts += 1,
+            EntityKind::GlobalScript => self.global_scripts += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.services + self.scripts + self.global_scripts
+    }
+}
+
+/// Put running processes in the order every surface lists them: by kind
+/// (services, project scripts, global scripts), then project (unscoped
+/// first), then start time, with the target id breaking ties. A target
+/// listed more than once keeps only its latest start.
+pub fn sort_running(mut list: Vec<RunningProcess>) -> Vec<RunningProcess> {
+    list.sort_by(|a, b| {
+        (a.target_kind, &a.target_id)
+            .cmp(&(b.target_kind, &b.target_id))
+            .then_with(|| b.started_at.cmp(&a.started_at))
+    });
+    list.dedup_by(|later, kept| later.target_kind == kept.target_kind && later.target_id == kept.target_id);
+    list.sort_by(|a, b| {
+        (a.target_kind, &a.project_id, a.started_at, &a.target_id)
+            .cmp(&(b.target_kind, &b.project_id, b.started_at, &b.target_id))
+    });
+    list
+}
+
+/// Services of `project` in `running` that were started under another
+/// variant than the project's active one (or the project's own root).
+pub fn running_under_other_variant<'a>(project: &Project, running: &'a [RunningProcess]) -> Vec<&'a RunningProcess> {
+    running
+        .iter()
+        .filter(|p| p.target_kind == EntityKind::Service && p.project_id.as_deref() == Some(project.id.as_str()))
+        .filter(|p| p.variant_id != project.active_variant_id)
+        .collect()
 }
 
 pub struct RuntimeStore {
@@ -84,6 +199,27 @@ impl RuntimeStore {
         self.dir.join(format!("{}.log", id))
     }
 
+    /// Current size of `id`'s log: where the next run's output starts.
+    pub fn log_len(&self, id: &str) -> u64 {
+        fs::metadata(self.log_path(id)).map(|m| m.len()).unwrap_or(0)
+    }
+
+    /// The part of `id`'s log from byte `start` to `end` (or the end of the
+    /// file). `None` if the log doesn't reach `start`, e.g. it was deleted.
+    pub fn read_log_range(&self, id: &str, start: u64, end: Option<u64>) -> Option<String> {
+        use std::io::{Read, Seek, SeekFrom};
+        let mut file = fs::File::open(self.log_path(id)).ok()?;
+        let len = file.metadata().ok()?.len();
+        if start > len {
+            return None;
+        }
+        let end = end.unwrap_or(len).clamp(start, len);
+        file.seek(SeekFrom::Start(start)).ok()?;
+        let mut bytes = Vec::with_capacity((end - start) as usize);
+        file.take(end - start).read_to_end(&mut bytes).ok()?;
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
     /// Atomic write: serialize to a `.tmp` file then rename into place.
     pub fn register(&self, entry: &RuntimeEntry) -> std::io::Result<()> {
         let path = self.state_path(&entry.id);
@@ -167,6 +303,7 @@ pub fn spawn_detached(
     args: &[String],
     working_dir: &str,
     env_vars: Option<&HashMap<String, String>>,
+    env_mode: &EnvMode,
     log_path: &Path,
 ) -> std::io::Result<u32> {
     if let Some(parent) = log_path.parent() {
@@ -185,6 +322,8 @@ pub fn spawn_detached(
         .stdout(Stdio::from(log))
         .stderr(Stdio::from(log_err));
 
+    crate::spawn_env::prepare_env(&mut cmd, env_mode);
+
     #[cfg(target_os = "windows")]
     {
         cmd.env("PYTHONUTF8", "1");
@@ -233,19 +372,42 @@ fn apply_detached_flags(cmd: &mut Command) {
 /// `ProcessManager::parse_command` convention used elsewhere. Returns
 /// `(program, args)` suitable for [`spawn_detached`].
 pub fn shell_wrap(command: &str) -> (String, Vec<String>) {
-    #[cfg(target_os = "windows")]
-    {
-        (
-            "cmd".to_string(),
-            vec!["/C".to_string(), command.to_string()],
-        )
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        (
-            "sh".to_string(),
-            vec!["-c".to_string(), command.to_string()],
-        )
+    shell_wrap_with(&ShellConfig::default(), command)
+}
+
+/// [`shell_wrap`] for the shell selected in settings. `Default`, or `Custom`
+/// without a path, falls back to `cmd /C` on Windows and `sh -c` elsewhere.
+pub fn shell_wrap_with(shell: &ShellConfig, command: &str) -> (String, Vec<String>) {
+    let custom = shell.custom_path.as_deref().map(str::trim).filter(|p| !p.is_empty());
+    let (program, flag) = match (shell.kind, custom) {
+        (ShellKind::Sh, _) => ("sh".to_string(), "-c"),
+        (ShellKind::Bash, _) => ("bash".to_string(), "-c"),
+        (ShellKind::Zsh, _) => ("zsh".to_string(), "-c"),
+        (ShellKind::Cmd, _) => ("cmd".to_string(), "/C"),
+        (ShellKind::Powershell, _) => ("powershell".to_string(), "-Command"),
+        (ShellKind::Custom, Some(path)) => (path.to_string(), custom_shell_flag(path)),
+        (ShellKind::Default, _) | (ShellKind::Custom, None) => {
+            if cfg!(target_os = "windows") {
+                ("cmd".to_string(), "/C")
+            } else {
+                ("sh".to_string(), "-c")
+            }
+        }
+    };
+    (program, vec![flag.to_string(), command.to_string()])
+}
+
+/// Command flag for a custom interpreter, guessed from its file name.
+fn custom_shell_flag(path: &str) -> &'static str {
+    let stem = Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match stem.as_str() {
+        "cmd" => "/C",
+        "powershell" | "pwsh" => "-Command",
+        _ => "-c",
     }
 }
 
@@ -294,6 +456,17 @@ pub fn kill_pid_tree(pid: u32) -> Result<(), String> {
     }
 }
 
+/// Compact uptime for status lines: `45s`, `3m12s`, `2h05m`, `1d03h`.
+pub fn format_uptime(uptime: std::time::Duration) -> String {
+    let secs = uptime.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        3600..=86399 => format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60),
+        _ => format!("{}d{:02}h", secs / 86400, (secs % 86400) / 3600),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,9 +485,132 @@ mod tests {
             project_name: None,
             mode: None,
             arg_preset: None,
+            run_id: new_run_id(),
+            variant_id: None,
         }
     }
 
+    fn running(kind: EntityKind, id: &str, project: Option<&str>, started_secs: i64) -> RunningProcess {
+        RunningProcess {
+            target_kind: kind,
+            target_id: id.into(),
+            run_id: format!("run-{}-{}", id, started_secs),
+            project_id: project.map(str::to_string),
+            name: id.into(),
+            pid: 1,
+            started_at: DateTime::from_timestamp(1_700_000_000 + started_secs, 0).unwrap(),
+            status: RunningStatus::Running,
+            variant_id: None,
+        }
+    }
+
+    #[test]
+    fn running_list_order_is_deterministic_and_deduplicated() {
+        let list = vec![
+            running(EntityKind::GlobalScript, "backup", None, 5),
+            running(EntityKind::Service, "web", Some("p2"), 1),
+            running(EntityKind::ProjectScript, "test", Some("p1"), 3),
+            running(EntityKind::Service, "api", Some("p1"), 9),
+            running(EntityKind::Service, "worker", Some("p1"), 2),
+            running(EntityKind::Service, "db", None, 7),
+            // A stale duplicate of api, started earlier
+            running(EntityKind::Service, "api", Some("p1"), 4),
+        ];
+        let sorted = sort_running(list.clone());
+        let ids: Vec<&str> = sorted.iter().map(|p| p.target_id.as_str()).collect();
+        assert_eq!(ids, vec!["db", "worker", "api", "web", "test", "backup"]);
+        assert_eq!(sorted[2].run_id, "run-api-9");
+
+        // Input order doesn't matter
+        let mut reversed = list;
+        reversed.reverse();
+        assert_eq!(sort_running(reversed), sorted);
+    }
+
+    #[test]
+    fn services_running_under_another_variant_are_found() {
+        let mut project = Project::new("app".into(), "/code/app".into());
+        project.id = "p1".into();
+        let under = |id: &str, variant: Option<&str>| RunningProcess {
+            variant_id: variant.map(str::to_string),
+            ..running(EntityKind::Service, id, Some("p1"), 0)
+        };
+        let list = vec![
+            under("api", None),
+            under("web", Some("hotfix")),
+            RunningProcess { variant_id: Some("hotfix".into()), ..running(EntityKind::ProjectScript, "test", Some("p1"), 0) },
+            RunningProcess { variant_id: Some("hotfix".into()), ..running(EntityKind::Service, "db", Some("p2"), 0) },
+        ];
+        let ids = |project: &Project| -> Vec<String> {
+            running_under_other_variant(project, &list).iter().map(|p| p.target_id.clone()).collect()
+        };
+
+        assert_eq!(ids(&project), vec!["web"]);
+        project.active_variant_id = Some("hotfix".into());
+        assert_eq!(ids(&project), vec!["api"]);
+    }
+
+    #[test]
+    fn running_process_serialization() {
+        let process = running(EntityKind::ProjectScript, "test", Some("p1"), 0);
+        assert_eq!(
+            serde_json::to_value(&process).unwrap(),
+            serde_json::json!({
+                "targetKind": "project_script",
+                "targetId": "test",
+                "runId": "run-test-0",
+                "projectId": "p1",
+                "name": "test",
+                "pid": 1,
+                "startedAt": "2023-11-14T22:13:20Z",
+                "status": "running",
+            })
+        );
+        let counts = RunningCounts { services: 2, scripts: 0, global_scripts: 1 };
+        assert_eq!(
+            serde_json::to_value(counts).unwrap(),
+            serde_json::json!({ "services": 2, "scripts": 0, "globalScripts": 1 })
+        );
+
+        // Entries from before run ids existed still get a stable one
+        let mut entry = sample_entry("old", 42);
+        entry.run_id.clear();
+        let expected = format!("42-{}", entry.started_at.timestamp_millis());
+        let legacy = RunningProcess::from_entry(entry, RunningStatus::External);
+        assert_eq!(legacy.run_id, expected);
+        assert_eq!(legacy.status, RunningStatus::External);
+    }
+
+    #[test]
+    fn uptime_formatting() {
+        use std::time::Duration;
+        assert_eq!(format_uptime(Duration::from_secs(45)), "45s");
+        assert_eq!(format_uptime(Duration::from_secs(192)), "3m12s");
+        assert_eq!(format_uptime(Duration::from_secs(2 * 3600 + 5 * 60 + 9)), "2h05m");
+        assert_eq!(format_uptime(Duration::from_secs(86400 + 3 * 3600)), "1d03h");
+    }
+
+    #[test]
+    fn shell_wrap_uses_selected_shell() {
+        let wrap = |kind, custom: Option<&str>| {
+            let shell = ShellConfig { kind, custom_path: custom.map(str::to_string) };
+            shell_wrap_with(&shell, "echo hi")
+        };
+        let args = |flag: &str| vec![flag.to_string(), "echo hi".to_string()];
+
+        assert_eq!(wrap(ShellKind::Bash, None), ("bash".to_string(), args("-c")));
+        assert_eq!(wrap(ShellKind::Powershell, None), ("powershell".to_string(), args("-Command")));
+        assert_eq!(wrap(ShellKind::Cmd, None), ("cmd".to_string(), args("/C")));
+        assert_eq!(
+            wrap(ShellKind::Custom, Some("/usr/local/bin/fish")),
+            ("/usr/local/bin/fish".to_string(), args("-c"))
+        );
+        assert_eq!(wrap(ShellKind::Custom, Some("pwsh")), ("pwsh".to_string(), args("-Command")));
+        // Unset / incomplete config keeps the historical platform default
+        assert_eq!(wrap(ShellKind::Custom, Some("  ")), shell_wrap("echo hi"));
+        assert_eq!(wrap(ShellKind::Default, None), shell_wrap("echo hi"));
+    }
+
     #[test]
     fn register_then_get_roundtrip() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,300 @@
+//! Cron schedules of global scripts (`GlobalScript::schedule`).
+//!
+//! [`spawn`] starts a thread that checks every second which scheduled
+//! scripts are due and runs them with their default preset, skipping any
+//! still running from an earlier run. Schedules are in local time. The
+//! thread ends with the process manager (`ProcessManager::stop_all`).
+//!
+//! Expressions take the usual five fields (`30 3 * * *`) or six / seven
+//! with seconds first and a year last. Day-of-week numbers follow the `cron`
+//! crate (1 = Sunday); names (`Mon-Fri`) read the same everywhere.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use cron::Schedule;
+use parking_lot::Mutex;
+
+use crate::command_builder;
+use crate::models::{ExecutionRecord, GlobalScript};
+use crate::process_manager::{ProcessEventEmitter, ProcessManager, RuntimeMeta};
+use crate::storage::Storage;
+use crate::{script_location, script_requirements, spawn_env};
+
+/// How often the scheduler thread looks for due scripts
+const TICK: Duration = Duration::from_secs(1);
+
+/// Parse a cron expression, five fields or six / seven with seconds
+pub fn parse_schedule(expr: &str) -> Result<Schedule, String> {
+    let expr = expr.trim();
+    let full = match expr.split_whitespace().count() {
+        5 => format!("0 {}", expr),
+        _ => expr.to_string(),
+    };
+    Schedule::from_str(&full).map_err(|e| format!("Invalid schedule '{}': {}", expr, e))
+}
+
+/// When `expr` fires next, strictly after `after`
+pub fn next_fire<Tz: TimeZone>(expr: &str, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    parse_schedule(expr).ok()?.after(after).next()
+}
+
+/// The schedule of `script`, unless blank
+fn schedule_of(script: &GlobalScript) -> Option<&str> {
+    script.schedule.as_deref().map(str::trim).filter(|e| !e.is_empty())
+}
+
+struct Entry {
+    expr: String,
+    next: Option<DateTime<Utc>>,
+}
+
+/// Next fire times of the scheduled scripts, and whether firing is paused
+#[derive(Default)]
+pub struct Scheduler {
+    entries: Mutex<HashMap<String, Entry>>,
+    paused: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// While paused, due scripts are passed over rather than run later
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// When the scheduler runs `script_id` next, once it has seen it
+    pub fn next_run(&self, script_id: &str) -> Option<DateTime<Utc>> {
+        self.entries.lock().get(script_id).and_then(|e| e.next)
+    }
+
+    /// Ids of the `scripts` due at `now`, moving their next fire time on. A
+    /// script seen for the first time, or whose schedule changed, counts
+    /// from `now`: times already past never fire.
+    pub fn due<Tz: TimeZone>(&self, scripts: &[GlobalScript], now: &DateTime<Tz>) -> Vec<String> {
+        let now_utc = now.with_timezone(&Utc);
+        let mut entries = self.entries.lock();
+        entries.retain(|id, _| scripts.iter().any(|s| &s.id == id && schedule_of(s).is_some()));
+
+        let mut due = Vec::new();
+        for script in scripts {
+            let Some(expr) = schedule_of(script) else { continue };
+            let next = || next_fire(expr, now).map(|t| t.with_timezone(&Utc));
+            match entries.get_mut(&script.id) {
+                Some(entry) if entry.expr == expr => {
+                    if entry.next.is_some_and(|at| at <= now_utc) {
+                        due.push(script.id.clone());
+                        entry.next = next();
+                    }
+                }
+                _ => {
+                    entries.insert(script.id.clone(), Entry { expr: expr.to_string(), next: next() });
+                }
+            }
+        }
+        due
+    }
+
+    /// One pass at `now`: `run` the due `scripts`, except those
+    /// `is_running` finds still running. Returns the ids run and the ids
+    /// skipped; nothing runs while paused.
+    pub fn tick<Tz: TimeZone>(
+        &self,
+        scripts: &[GlobalScript],
+        now: &DateTime<Tz>,
+        is_running: impl Fn(&str) -> bool,
+        mut run: impl FnMut(&GlobalScript),
+    ) -> (Vec<String>, Vec<String>) {
+        let due = self.due(scripts, now);
+        if self.is_paused() {
+            return (Vec::new(), Vec::new());
+        }
+        let (skipped, ran): (Vec<String>, Vec<String>) = due.into_iter().partition(|id| is_running(id));
+        for id in &ran {
+            if let Some(script) = scripts.iter().find(|s| &s.id == id) {
+                run(script);
+            }
+        }
+        (ran, skipped)
+    }
+}
+
+/// Start the scheduler thread of `manager` (`ProcessManager::scheduler`),
+/// reading the scripts from `storage`
+pub fn spawn(manager: &Arc<ProcessManager>, storage: Arc<Storage>, emitter: Arc<dyn ProcessEventEmitter>) {
+    let scheduler = manager.scheduler().clone();
+    let shutdown = manager.get_shutdown_flag();
+    // Weak, so the scheduler never keeps the manager alive
+    let manager = Arc::downgrade(manager);
+    let spawned = thread::Builder::new().name("cortx-scheduler".into()).spawn(move || loop {
+        thread::sleep(TICK);
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Some(manager) = manager.upgrade() else { break };
+        let scripts = storage.get_all_global_scripts();
+        let (_, skipped) = scheduler.tick(
+            &scripts,
+            &Local::now(),
+            |id| manager.is_global_script_running(id),
+            |script| {
+                if let Err(e) = run_scheduled(&storage, &manager, emitter.clone(), script) {
+                    log::warn!("Scheduled run of {} failed to start: {}", script.name, e);
+                }
+            },
+        );
+        for id in skipped {
+            log::info!("Scheduled run of {} skipped: it's still running", id);
+        }
+    });
+    if let Err(e) = spawned {
+        log::error!("Failed to start the script scheduler: {}", e);
+    }
+}
+
+/// Start `script` the way its schedule does: with its default preset, from
+/// the current directory, recorded in the execution history as scheduled
+pub fn run_scheduled(
+    storage: &Storage,
+    manager: &Arc<ProcessManager>,
+    emitter: Arc<dyn ProcessEventEmitter>,
+    script: &GlobalScript,
+) -> Result<u32, String> {
+    let projects = storage.get_all_projects();
+    script_requirements::ensure_requirements(script, &projects, |id| manager.is_service_running(id))
+        .map_err(|e| e.to_string())?;
+
+    let preset = script
+        .default_preset_id
+        .as_ref()
+        .and_then(|id| script.parameter_presets.iter().find(|p| &p.id == id));
+    let values = preset.map(|p| p.values.clone()).unwrap_or_default();
+    command_builder::check_value_counts(script, &values).map_err(|e| e.to_string())?;
+    let (program, args) =
+        command_builder::build_command(script, &values, &[]).ok_or_else(|| "Empty command".to_string())?;
+
+    let base = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let working_dir = script_location::working_dir(script, &base).to_string_lossy().to_string();
+
+    let mut record = ExecutionRecord::new(script.id.clone());
+    record.scheduled = true;
+    record.parameters_used = values;
+    record.preset_name = preset.map(|p| p.name.clone());
+    record.working_dir = Some(working_dir.clone());
+    record.parameters_signature = Some(command_builder::parameters_signature(script));
+    record.log_start = Some(manager.runtime_store().log_len(&script.id));
+    record.env_snapshot =
+        spawn_env::env_snapshot(script.env_vars.as_ref(), &storage.get_settings().scripts_config.env_snapshot);
+
+    let pid = manager.run_global_script(
+        emitter,
+        script.id.clone(),
+        working_dir,
+        program,
+        args,
+        script.env_vars.clone(),
+        &script.env_mode,
+        RuntimeMeta::new(script.name.clone())
+            .with_allow_concurrent(script.allow_concurrent)
+            .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
+    )?;
+    let _ = storage.add_execution_record(record);
+    Ok(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, h, m, s).unwrap()
+    }
+
+    fn scheduled(id: &str, expr: &str) -> GlobalScript {
+        let mut script = GlobalScript::new(id.to_string(), "true".to_string(), None);
+        script.id = id.to_string();
+        script.schedule = Some(expr.to_string());
+        script
+    }
+
+    #[test]
+    fn next_fire_follows_five_and_six_field_expressions() {
+        assert_eq!(next_fire("*/15 * * * *", &at(10, 7, 0)), Some(at(10, 15, 0)));
+        assert_eq!(next_fire("*/15 * * * *", &at(10, 15, 0)), Some(at(10, 30, 0)));
+        assert_eq!(next_fire("30 3 * * *", &at(10, 0, 0)), Some(Utc.with_ymd_and_hms(2026, 3, 3, 3, 30, 0).unwrap()));
+        // 2026-03-02 is a Monday
+        assert_eq!(next_fire("0 9 * * Mon-Fri", &at(10, 0, 0)), Some(Utc.with_ymd_and_hms(2026, 3, 3, 9, 0, 0).unwrap()));
+        assert_eq!(next_fire("*/20 * * * * *", &at(10, 0, 5)), Some(at(10, 0, 20)));
+        assert!(parse_schedule("every day").is_err());
+        assert_eq!(next_fire("61 * * * *", &at(10, 0, 0)), None);
+    }
+
+    #[test]
+    fn due_scripts_fire_once_per_slot_and_never_for_the_past() {
+        let scheduler = Scheduler::new();
+        let mut scripts = vec![scheduled("backup", "0 * * * *"), scheduled("cleanup", "*/30 * * * *")];
+        scripts.push(GlobalScript::new("manual".into(), "true".into(), None));
+
+        // First sight only sets the next fire time
+        assert!(scheduler.due(&scripts, &at(9, 45, 0)).is_empty());
+        assert_eq!(scheduler.next_run("backup"), Some(at(10, 0, 0)));
+        assert_eq!(scheduler.next_run("manual"), None);
+
+        assert!(scheduler.due(&scripts, &at(9, 59, 59)).is_empty());
+        assert_eq!(scheduler.due(&scripts, &at(10, 0, 0)), vec!["backup", "cleanup"]);
+        assert!(scheduler.due(&scripts, &at(10, 0, 1)).is_empty());
+        assert_eq!(scheduler.next_run("cleanup"), Some(at(10, 30, 0)));
+
+        // A clock that jumped ahead fires once, not once per missed slot
+        assert_eq!(scheduler.due(&scripts, &at(12, 10, 0)), vec!["backup", "cleanup"]);
+        assert_eq!(scheduler.next_run("backup"), Some(at(13, 0, 0)));
+
+        // A changed schedule starts over from now
+        scripts[0].schedule = Some("15 * * * *".into());
+        assert_eq!(scheduler.due(&scripts, &at(12, 20, 0)), Vec::<String>::new());
+        assert_eq!(scheduler.next_run("backup"), Some(at(13, 15, 0)));
+
+        // An unscheduled script is forgotten
+        scripts[1].schedule = None;
+        scheduler.due(&scripts, &at(12, 21, 0));
+        assert_eq!(scheduler.next_run("cleanup"), None);
+    }
+
+    #[test]
+    fn running_scripts_are_skipped_and_pausing_passes_over() {
+        let scheduler = Scheduler::new();
+        let scripts = vec![scheduled("backup", "*/5 * * * *"), scheduled("report", "*/5 * * * *")];
+        let started = RefCell::new(Vec::new());
+        let run = |s: &GlobalScript| started.borrow_mut().push(s.id.clone());
+
+        scheduler.tick(&scripts, &at(10, 1, 0), |_| false, run);
+        let (ran, skipped) = scheduler.tick(&scripts, &at(10, 5, 0), |id| id == "backup", run);
+        assert_eq!(ran, vec!["report"]);
+        assert_eq!(skipped, vec!["backup"]);
+        assert_eq!(*started.borrow(), vec!["report"]);
+
+        // Slots passed while paused don't run on resume
+        scheduler.set_paused(true);
+        assert_eq!(scheduler.tick(&scripts, &at(10, 10, 0), |_| false, run), (vec![], vec![]));
+        scheduler.set_paused(false);
+        let (ran, _) = scheduler.tick(&scripts, &at(10, 11, 0), |_| false, run);
+        assert!(ran.is_empty());
+        let (ran, _) = scheduler.tick(&scripts, &at(10, 15, 0), |_| false, run);
+        assert_eq!(ran, vec!["backup", "report"]);
+        assert_eq!(started.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,111 @@
+//! Files a global script run leaves behind.
+//!
+//! `GlobalScript::artifacts` holds glob patterns relative to the run's working
+//! dir (e.g. `dist/*.zip`, `report-??.html`). After a successful run they are
+//! resolved to the files that exist and recorded on the execution record, so
+//! the UIs can offer to open or reveal them. `*` and `?` match across `/`
+//! like elsewhere (`spawn_env::glob_match`); patterns matching nothing are
+//! simply left out.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path};
+
+use walkdir::WalkDir;
+
+use crate::spawn_env::glob_match;
+
+/// Stop walking a pattern's directory after this many entries
+const MAX_SCAN_ENTRIES: usize = 50_000;
+
+/// Resolve `patterns` against `working_dir` to the absolute paths of the
+/// matching files, sorted and without duplicates.
+pub fn resolve_artifacts(working_dir: &Path, patterns: &[String]) -> Vec<String> {
+    let mut found = BTreeSet::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().replace('\\', "/");
+        let pattern = pattern.trim_start_matches("./");
+        if pattern.is_empty() || Path::new(pattern).is_absolute() {
+            continue;
+        }
+        if Path::new(pattern).components().any(|c| matches!(c, Component::ParentDir)) {
+            continue;
+        }
+
+        // Only walk below the part of the pattern without wildcards
+        let base = literal_prefix(pattern);
+        let root = working_dir.join(&base);
+        if !root.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(&root)
+            .follow_links(false)
+            .into_iter()
+            .filter_map(Result::ok)
+            .take(MAX_SCAN_ENTRIES)
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(relative) = entry.path().strip_prefix(working_dir) else { continue };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if glob_match(pattern, &relative) {
+                found.insert(entry.path().to_string_lossy().to_string());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// Leading directories of `pattern` that hold no wildcard
+fn literal_prefix(pattern: &str) -> String {
+    let mut parts: Vec<&str> = pattern.split('/').collect();
+    parts.pop(); // The file name part is matched, not walked into
+    parts
+        .into_iter()
+        .take_while(|part| !part.contains(['*', '?']))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn files_created_by_a_run_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("dist/nested")).unwrap();
+        fs::write(dir.path().join("dist/notes.txt"), "").unwrap();
+
+        let patterns = vec!["dist/*.zip".to_string(), "./report.html".to_string()];
+        assert!(resolve_artifacts(dir.path(), &patterns).is_empty());
+
+        // What a run would leave behind
+        fs::write(dir.path().join("dist/app.zip"), "zip").unwrap();
+        fs::write(dir.path().join("dist/nested/extra.zip"), "zip").unwrap();
+        fs::write(dir.path().join("report.html"), "<html>").unwrap();
+
+        let path = |rel: &str| dir.path().join(rel).to_string_lossy().to_string();
+        let mut expected = vec![path("dist/app.zip"), path("dist/nested/extra.zip"), path("report.html")];
+        expected.sort();
+        assert_eq!(resolve_artifacts(dir.path(), &patterns), expected);
+    }
+
+    #[test]
+    fn patterns_matching_nothing_or_outside_the_working_dir_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = dir.path().join("work");
+        fs::create_dir_all(&inner).unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        let patterns = vec![
+            "missing/*.bin".to_string(),
+            "../*.txt".to_string(),
+            String::new(),
+        ];
+        assert!(resolve_artifacts(&inner, &patterns).is_empty());
+        assert_eq!(literal_prefix("build/out/*/app.js"), "build/out");
+        assert_eq!(literal_prefix("*.log"), "");
+    }
+}
@@ -0,0 +1,322 @@
+//! Running global scripts that depend on each other.
+//!
+//! A script starts once every script it `depends_on` succeeded. Scripts
+//! whose dependencies are all done run concurrently, up to a cap. When a
+//! script fails, everything depending on it (directly or not) is skipped;
+//! the rest of the run carries on.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::{Condvar, Mutex};
+use thiserror::Error;
+
+use crate::models::{LogStream, ScriptDependency, ScriptNodeOutcome, ScriptNodeResult, ScriptStatus, ServiceStatus};
+use crate::process_manager::ProcessEventEmitter;
+
+/// Concurrent scripts when the caller doesn't say
+pub const DEFAULT_MAX_PARALLEL: usize = 4;
+
+/// Some scripts (by id) depend on each other in a loop
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Scripts depend on each other: {}", .0.join(", "))]
+pub struct ScriptCycle(pub Vec<String>);
+
+/// `nodes` in an order where every script comes after its dependencies,
+/// otherwise in the given order. Dependencies on scripts that aren't among
+/// `nodes` are ignored.
+pub fn run_order(nodes: &[ScriptDependency]) -> Result<Vec<&ScriptDependency>, ScriptCycle> {
+    let ids: HashSet<&str> = nodes.iter().map(|n| n.script_id.as_str()).collect();
+    let mut pending: Vec<&ScriptDependency> = nodes.iter().collect();
+    let mut done: HashSet<&str> = HashSet::new();
+    let mut ordered = Vec::with_capacity(nodes.len());
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|n| {
+            n.depends_on
+                .iter()
+                .all(|dep| !ids.contains(dep.as_str()) || done.contains(dep.as_str()))
+        });
+        let Some(index) = ready else {
+            return Err(ScriptCycle(pending.iter().map(|n| n.script_id.clone()).collect()));
+        };
+        let node = pending.remove(index);
+        done.insert(node.script_id.as_str());
+        ordered.push(node);
+    }
+    Ok(ordered)
+}
+
+/// Run every script of `nodes` with `run`, which blocks until the script
+/// finished and says whether it succeeded. At most `max_parallel` (at least
+/// one) run at a time.
+///
+/// Returns one result per script, in [`run_order`].
+pub fn run_dag<F>(nodes: &[ScriptDependency], max_parallel: usize, run: F) -> Result<Vec<ScriptNodeResult>, ScriptCycle>
+where
+    F: Fn(&str) -> Result<(), String> + Sync,
+{
+    let order = run_order(nodes)?;
+    let max_parallel = max_parallel.max(1);
+    let index: HashMap<&str, usize> = order.iter().enumerate().map(|(i, n)| (n.script_id.as_str(), i)).collect();
+    let deps: Vec<Vec<usize>> = order
+        .iter()
+        .map(|n| n.depends_on.iter().filter_map(|d| index.get(d.as_str()).copied()).collect())
+        .collect();
+
+    let mut outcomes: Vec<Option<ScriptNodeOutcome>> = vec![None; order.len()];
+    let mut started = vec![false; order.len()];
+    let (done_tx, done_rx) = mpsc::channel::<(usize, Result<(), String>)>();
+    let run = &run;
+
+    thread::scope(|scope| {
+        let mut running = 0;
+        loop {
+            // Dependencies come first in `order`, so one pass settles skips
+            // that cascade through several levels
+            for i in 0..order.len() {
+                if started[i] || outcomes[i].is_some() {
+                    continue;
+                }
+                let failed = deps[i].iter().find_map(|&d| match &outcomes[d] {
+                    Some(ScriptNodeOutcome::Failed { .. }) => Some(order[d].script_id.clone()),
+                    Some(ScriptNodeOutcome::Skipped { failed_dependency }) => Some(failed_dependency.clone()),
+                    _ => None,
+                });
+                if let Some(failed_dependency) = failed {
+                    outcomes[i] = Some(ScriptNodeOutcome::Skipped { failed_dependency });
+                    continue;
+                }
+                let ready = deps[i].iter().all(|&d| outcomes[d] == Some(ScriptNodeOutcome::Succeeded));
+                if ready && running < max_parallel {
+                    started[i] = true;
+                    running += 1;
+                    let done_tx = done_tx.clone();
+                    let script_id = order[i].script_id.as_str();
+                    scope.spawn(move || {
+                        let _ = done_tx.send((i, run(script_id)));
+                    });
+                }
+            }
+
+            if running == 0 {
+                break;
+            }
+            let Ok((i, result)) = done_rx.recv() else { break };
+            running -= 1;
+            outcomes[i] = Some(match result {
+                Ok(()) => ScriptNodeOutcome::Succeeded,
+                Err(error) => ScriptNodeOutcome::Failed { error },
+            });
+        }
+    });
+
+    Ok(order
+        .iter()
+        .zip(outcomes)
+        .map(|(node, outcome)| ScriptNodeResult {
+            script_id: node.script_id.clone(),
+            // Unreachable: every script either ran or was skipped
+            outcome: outcome.unwrap_or(ScriptNodeOutcome::Failed { error: "Not run".to_string() }),
+        })
+        .collect())
+}
+
+/// Forwards every event to `inner` and remembers how global scripts
+/// exited, so a DAG run can wait for each script it starts.
+pub struct ScriptExitWaiter {
+    inner: Arc<dyn ProcessEventEmitter>,
+    exits: Mutex<HashMap<String, bool>>,
+    exited: Condvar,
+}
+
+impl ScriptExitWaiter {
+    pub fn new(inner: Arc<dyn ProcessEventEmitter>) -> Self {
+        Self { inner, exits: Mutex::new(HashMap::new()), exited: Condvar::new() }
+    }
+
+    /// Drop the remembered exit of `script_id`, before starting it again
+    pub fn forget(&self, script_id: &str) {
+        self.exits.lock().remove(script_id);
+    }
+
+    /// Block until `script_id` exits; returns whether it succeeded. Gives up
+    /// (false) once `shutdown` is set.
+    pub fn wait(&self, script_id: &str, shutdown: &AtomicBool) -> bool {
+        let mut exits = self.exits.lock();
+        loop {
+            if let Some(success) = exits.get(script_id) {
+                return *success;
+            }
+            if shutdown.load(Ordering::SeqCst) {
+                return false;
+            }
+            self.exited.wait_for(&mut exits, Duration::from_millis(200));
+        }
+    }
+}
+
+impl ProcessEventEmitter for ScriptExitWaiter {
+    fn emit_service_log(&self, service_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_service_log(service_id, stream, content);
+    }
+
+    fn emit_service_status(
+        &self,
+        service_id: &str,
+        status: ServiceStatus,
+        pid: Option<u32>,
+        active_mode: Option<String>,
+        active_arg_preset: Option<String>,
+        started_at: Option<DateTime<Utc>>,
+    ) {
+        self.inner
+            .emit_service_status(service_id, status, pid, active_mode, active_arg_preset, started_at);
+    }
+
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>) {
+        self.inner.emit_service_exit(service_id, exit_code, error_excerpt);
+    }
+
+    fn emit_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_script_log(script_id, stream, content);
+    }
+
+    fn emit_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        self.inner.emit_script_status(script_id, status, pid);
+    }
+
+    fn emit_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        self.inner.emit_script_exit(script_id, exit_code, success);
+    }
+
+    fn emit_global_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_global_script_log(script_id, stream, content);
+    }
+
+    fn emit_global_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        self.inner.emit_global_script_status(script_id, status, pid);
+    }
+
+    fn emit_global_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        self.inner.emit_global_script_exit(script_id, exit_code, success);
+        self.exits.lock().insert(script_id.to_string(), success);
+        self.exited.notify_all();
+    }
+
+    fn emit_service_ports(&self, service_id: &str, ports: Vec<u16>) {
+        self.inner.emit_service_ports(service_id, ports);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn node(id: &str, depends_on: &[&str]) -> ScriptDependency {
+        ScriptDependency {
+            script_id: id.to_string(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    /// build → (test, lint) → deploy
+    fn diamond() -> Vec<ScriptDependency> {
+        vec![
+            node("deploy", &["test", "lint"]),
+            node("test", &["build"]),
+            node("lint", &["build"]),
+            node("build", &[]),
+        ]
+    }
+
+    /// Run `nodes`, each script taking 100ms and failing if listed in `failing`.
+    /// Returns the results and the (start, end) of each script that ran.
+    fn run_recorded(
+        nodes: &[ScriptDependency],
+        failing: &[&str],
+    ) -> (Vec<ScriptNodeResult>, HashMap<String, (Instant, Instant)>) {
+        let spans = Mutex::new(HashMap::new());
+        let results = run_dag(nodes, 4, |id| {
+            let start = Instant::now();
+            thread::sleep(Duration::from_millis(100));
+            spans.lock().insert(id.to_string(), (start, Instant::now()));
+            if failing.contains(&id) {
+                Err(format!("{} failed", id))
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+        (results, spans.into_inner())
+    }
+
+    #[test]
+    fn diamond_runs_dependencies_first_and_branches_together() {
+        let (results, spans) = run_recorded(&diamond(), &[]);
+
+        let ids: Vec<&str> = results.iter().map(|r| r.script_id.as_str()).collect();
+        assert_eq!(ids, vec!["build", "test", "lint", "deploy"]);
+        assert!(results.iter().all(|r| r.outcome == ScriptNodeOutcome::Succeeded));
+
+        let (build, test, lint, deploy) = (spans["build"], spans["test"], spans["lint"], spans["deploy"]);
+        assert!(test.0 >= build.1 && lint.0 >= build.1);
+        assert!(deploy.0 >= test.1 && deploy.0 >= lint.1);
+        // The two branches overlap
+        assert!(test.0 < lint.1 && lint.0 < test.1);
+    }
+
+    #[test]
+    fn failures_skip_their_dependents_only() {
+        let mut nodes = diamond();
+        nodes.push(node("docs", &[]));
+        nodes.push(node("publish", &["deploy"]));
+        let (results, spans) = run_recorded(&nodes, &["test"]);
+
+        let outcome = |id: &str| results.iter().find(|r| r.script_id == id).unwrap().outcome.clone();
+        assert_eq!(outcome("build"), ScriptNodeOutcome::Succeeded);
+        assert_eq!(outcome("test"), ScriptNodeOutcome::Failed { error: "test failed".into() });
+        // lint doesn't depend on test and still runs
+        assert_eq!(outcome("lint"), ScriptNodeOutcome::Succeeded);
+        assert_eq!(outcome("docs"), ScriptNodeOutcome::Succeeded);
+        let skipped = ScriptNodeOutcome::Skipped { failed_dependency: "test".into() };
+        assert_eq!(outcome("deploy"), skipped);
+        assert_eq!(outcome("publish"), skipped);
+        assert!(!spans.contains_key("deploy") && !spans.contains_key("publish"));
+    }
+
+    #[test]
+    fn cycles_are_refused_before_running_anything() {
+        let nodes = vec![node("a", &["c"]), node("b", &["a"]), node("c", &["b"]), node("d", &["gone"])];
+        let ran = AtomicBool::new(false);
+        let result = run_dag(&nodes, 2, |_| {
+            ran.store(true, Ordering::SeqCst);
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err(), ScriptCycle(vec!["a".into(), "b".into(), "c".into()]));
+        assert!(!ran.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn parallelism_is_capped() {
+        let nodes: Vec<ScriptDependency> = (0..6).map(|i| node(&format!("s{}", i), &[])).collect();
+        let (running, peak) = (Mutex::new(0), Mutex::new(0));
+        run_dag(&nodes, 2, |_| {
+            {
+                let mut running = running.lock();
+                *running += 1;
+                let mut peak = peak.lock();
+                *peak = (*peak).max(*running);
+            }
+            thread::sleep(Duration::from_millis(30));
+            *running.lock() -= 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(peak.into_inner(), 2);
+    }
+}
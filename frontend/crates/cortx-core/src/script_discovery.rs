@@ -1,89 +1,191 @@
+use std::collections::HashMap;
 use std::path::Path;
-use walkdir::WalkDir;
+use std::time::{Duration, Instant};
+use walkdir::{DirEntry, WalkDir};
 
-use crate::models::DiscoveredScript;
+use crate::models::{DiscoveredScript, GlobalScript, ScriptScanResult, ScriptsConfig};
+use crate::script_relocation::content_hash;
+
+/// Limits for a directory walk, so pointing discovery at a huge tree stops
+/// early instead of stalling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanBudget {
+    pub max_entries: usize,
+    pub max_duration: Duration,
+}
+
+impl ScanBudget {
+    pub fn from_config(config: &ScriptsConfig) -> Self {
+        Self {
+            max_entries: config.scan_max_entries,
+            max_duration: Duration::from_secs(config.scan_max_seconds),
+        }
+    }
+}
+
+impl Default for ScanBudget {
+    fn default() -> Self {
+        Self::from_config(&ScriptsConfig::default())
+    }
+}
+
+/// How a budgeted walk went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalkStats {
+    pub visited: usize,
+    pub truncated: bool,
+}
+
+/// Walk `root`, skipping entries for which `skip` returns true (and, for
+/// directories, everything below them), and call `visit` for the rest. Stops
+/// once `budget` is used up; skipped entries don't count against it.
+pub fn walk_with_budget(
+    root: &Path,
+    follow_links: bool,
+    budget: &ScanBudget,
+    skip: impl Fn(&DirEntry) -> bool,
+    mut visit: impl FnMut(&DirEntry),
+) -> WalkStats {
+    let started = Instant::now();
+    let mut stats = WalkStats::default();
+
+    for entry in WalkDir::new(root)
+        .follow_links(follow_links)
+        .into_iter()
+        .filter_entry(|e| !skip(e))
+    {
+        if stats.visited >= budget.max_entries || started.elapsed() >= budget.max_duration {
+            stats.truncated = true;
+            break;
+        }
+        stats.visited += 1;
+        if let Ok(entry) = entry {
+            visit(&entry);
+        }
+    }
+    stats
+}
 
 /// Scan a folder for script files matching the given extensions,
-/// ignoring paths that match any of the ignored patterns.
+/// ignoring paths that match any of the ignored patterns. The walk stops
+/// early (and the result is marked truncated) once `budget` is used up.
 pub fn scan_folder(
     folder: &str,
     extensions: &[String],
     ignored_patterns: &[String],
-) -> Vec<DiscoveredScript> {
+    budget: &ScanBudget,
+) -> ScriptScanResult {
     let root = Path::new(folder);
     if !root.is_dir() {
-        return Vec::new();
+        return ScriptScanResult::default();
     }
 
-    let mut results = Vec::new();
-
-    for entry in WalkDir::new(root)
-        .follow_links(true)
-        .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            // Skip ignored directories/files
-            !ignored_patterns.iter().any(|p| name.contains(p.as_str()))
-        })
-    {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+    let mut scripts = Vec::new();
 
-        if !entry.file_type().is_file() {
-            continue;
+    let skip = |e: &DirEntry| {
+        let name = e.file_name().to_string_lossy();
+        // Skip ignored directories/files
+        ignored_patterns.iter().any(|p| name.contains(p.as_str()))
+    };
+    let stats = walk_with_budget(root, true, budget, skip, |entry| {
+        if let Some(script) = discovered_script(entry, extensions) {
+            scripts.push(script);
         }
+    });
 
-        let path = entry.path();
-        let ext = match path.extension().and_then(|e| e.to_str()) {
-            Some(e) => format!(".{}", e),
-            None => continue,
-        };
-
-        // Check if extension matches (handle both ".py" and "py" formats)
-        let ext_no_dot = &ext[1..]; // "py" from ".py"
-        if !extensions.iter().any(|scan_ext| {
-            let s = scan_ext.trim();
-            s.eq_ignore_ascii_case(&ext) || s.eq_ignore_ascii_case(ext_no_dot)
-        }) {
-            continue;
-        }
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    ScriptScanResult {
+        scripts,
+        entries_visited: stats.visited,
+        truncated: stats.truncated,
+        ..Default::default()
+    }
+}
 
-        let name = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
-        let abs_path = path
-            .canonicalize()
-            .unwrap_or_else(|_| path.to_path_buf())
-            .to_string_lossy()
-            .to_string();
-        // On Windows, canonicalize() returns UNC paths like \\?\C:\...
-        // Strip the prefix for cleaner, comparable paths
-        #[cfg(target_os = "windows")]
-        let abs_path = abs_path.strip_prefix(r"\\?\").unwrap_or(&abs_path).to_string();
-
-        // Try to extract a description from the first comment line
-        let description = extract_description(path);
-
-        results.push(DiscoveredScript {
-            path: abs_path,
-            name,
-            description,
-            extension: ext,
-        });
+/// The script at `entry`, if it's a file with one of the scanned extensions.
+fn discovered_script(entry: &DirEntry, extensions: &[String]) -> Option<DiscoveredScript> {
+    if !entry.file_type().is_file() {
+        return None;
     }
 
-    results.sort_by(|a, b| a.name.cmp(&b.name));
-    results
+    let path = entry.path();
+    let ext = format!(".{}", path.extension().and_then(|e| e.to_str())?);
+
+    // Check if extension matches (handle both ".py" and "py" formats)
+    let ext_no_dot = &ext[1..]; // "py" from ".py"
+    if !extensions.iter().any(|scan_ext| {
+        let s = scan_ext.trim();
+        s.eq_ignore_ascii_case(&ext) || s.eq_ignore_ascii_case(ext_no_dot)
+    }) {
+        return None;
+    }
+
+    discover_path(path)
+}
+
+/// The script file at `path`, whatever its extension; `None` if it isn't a
+/// file. Used to import a file picked from an earlier scan.
+pub fn discover_path(path: &Path) -> Option<DiscoveredScript> {
+    if !path.is_file() {
+        return None;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let abs_path = path
+        .canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string();
+    // On Windows, canonicalize() returns UNC paths like \\?\C:\...
+    // Strip the prefix for cleaner, comparable paths
+    #[cfg(target_os = "windows")]
+    let abs_path = abs_path.strip_prefix(r"\\?\").unwrap_or(&abs_path).to_string();
+
+    // Try to extract a description from the first comment line
+    let description = extract_description(path);
+
+    Some(DiscoveredScript {
+        path: abs_path,
+        name,
+        description,
+        extension: ext,
+        content_hash: content_hash(path),
+    })
+}
+
+/// The command a script with `extension` (".py") is run with: the
+/// configured template for it (`python {{SCRIPT_FILE}}`), else the file itself.
+pub fn default_command(extension: &str, templates: &HashMap<String, String>) -> String {
+    let ext = extension.trim_start_matches('.').to_ascii_lowercase();
+    templates.get(&ext).cloned().unwrap_or_else(|| "{{SCRIPT_FILE}}".to_string())
+}
+
+/// A new auto-discovered global script for `discovered`, run with
+/// [`default_command`] from the directory the file is in.
+pub fn to_global_script(discovered: &DiscoveredScript, templates: &HashMap<String, String>) -> GlobalScript {
+    let working_dir = Path::new(&discovered.path).parent().map(|dir| dir.to_string_lossy().to_string());
+    let mut script = GlobalScript::new(
+        discovered.name.clone(),
+        default_command(&discovered.extension, templates),
+        working_dir,
+    );
+    script.description = discovered.description.clone();
+    script.script_path = Some(discovered.path.clone());
+    script.auto_discovered = true;
+    // Lets a later scan follow the file if it moves
+    script.content_hash = discovered.content_hash.clone();
+    script
 }
 
 /// Try to read the first comment line from a script file as its description.
 fn extract_description(path: &Path) -> Option<String> {
-    let content = std::fs::read_to_string(path).ok()?;
+    let content = crate::text_file::read_to_string(path).ok()?;
     let mut in_script_block = false;
     for line in content.lines() {
         let trimmed = line.trim();
@@ -114,6 +216,14 @@ fn extract_description(path: &Path) -> Option<String> {
             }
             continue;
         }
+        // JavaScript/TypeScript/Go comments
+        if let Some(comment) = trimmed.strip_prefix("//") {
+            let desc = comment.trim();
+            if !desc.is_empty() {
+                return Some(desc.to_string());
+            }
+            continue;
+        }
         // Batch REM comments
         if trimmed.to_uppercase().starts_with("REM ") {
             let desc = trimmed[4..].trim();
@@ -133,3 +243,109 @@ fn extract_description(path: &Path) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// `levels` nested directories, each holding `files` scripts plus an
+    /// ignored `node_modules` full of scripts.
+    fn deep_tree(root: &Path, levels: usize, files: usize) {
+        let mut dir = root.to_path_buf();
+        for level in 0..levels {
+            dir = dir.join(format!("level{}", level));
+            fs::create_dir_all(dir.join("node_modules")).unwrap();
+            for i in 0..files {
+                fs::write(dir.join(format!("script{}.sh", i)), "# does things\necho hi\n").unwrap();
+                fs::write(dir.join("node_modules").join(format!("dep{}.js", i)), "").unwrap();
+            }
+        }
+    }
+
+    fn exts() -> Vec<String> {
+        vec!["sh".into(), "js".into()]
+    }
+
+    #[test]
+    fn unlimited_budget_scans_everything() {
+        let root = tempfile::tempdir().unwrap();
+        deep_tree(root.path(), 20, 5);
+        let budget = ScanBudget { max_entries: usize::MAX, max_duration: Duration::from_secs(60) };
+
+        let result = scan_folder(&root.path().to_string_lossy(), &exts(), &["node_modules".into()], &budget);
+        assert!(!result.truncated);
+        assert_eq!(result.scripts.len(), 100);
+        assert!(result.scripts.iter().all(|s| s.extension == ".sh"));
+        assert_eq!(result.scripts[0].description.as_deref(), Some("does things"));
+        // root + 20 directories + 100 scripts; node_modules never entered
+        assert_eq!(result.entries_visited, 121);
+    }
+
+    #[test]
+    fn entry_budget_truncates_the_walk() {
+        let root = tempfile::tempdir().unwrap();
+        deep_tree(root.path(), 20, 5);
+        let budget = ScanBudget { max_entries: 30, max_duration: Duration::from_secs(60) };
+
+        let result = scan_folder(&root.path().to_string_lossy(), &exts(), &["node_modules".into()], &budget);
+        assert!(result.truncated);
+        assert_eq!(result.entries_visited, 30);
+        assert!(result.scripts.len() < 30);
+    }
+
+    #[test]
+    fn time_budget_truncates_the_walk() {
+        let root = tempfile::tempdir().unwrap();
+        deep_tree(root.path(), 3, 2);
+        let budget = ScanBudget { max_entries: usize::MAX, max_duration: Duration::ZERO };
+
+        let stats = walk_with_budget(root.path(), false, &budget, |_| false, |_| {});
+        assert!(stats.truncated);
+        assert_eq!(stats.visited, 0);
+    }
+
+    #[test]
+    fn scanned_scripts_import_once_and_vanish_when_deleted() {
+        let data = tempfile::tempdir().unwrap();
+        let folder = tempfile::tempdir().unwrap();
+        let folder_path = folder.path().canonicalize().unwrap();
+        fs::create_dir(folder_path.join("tools")).unwrap();
+        fs::write(folder_path.join("tools").join("backup.py"), "#!/usr/bin/env python\n# Back up the db\n").unwrap();
+        fs::write(folder_path.join("greet.js"), "// Say hello\nconsole.log('hi')\n").unwrap();
+        fs::write(folder_path.join("notes.txt"), "not a script").unwrap();
+
+        let storage = crate::storage::Storage::with_app_dir(data.path().to_path_buf()).unwrap();
+        assert!(matches!(storage.scan_scripts_folder(None), Err(crate::storage::StorageError::NoScriptsFolder)));
+        let mut settings = storage.get_settings();
+        settings.scripts_config.main_folder = Some(folder_path.to_string_lossy().to_string());
+        storage.update_settings(settings).unwrap();
+
+        let result = storage.scan_scripts_folder(None).unwrap();
+        let names: Vec<&str> = result.scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["backup", "greet"]);
+        let paths: Vec<String> = result.scripts.iter().map(|s| s.path.clone()).collect();
+
+        let imported = storage.import_discovered_scripts(&paths).unwrap().imported;
+        assert_eq!(imported.len(), 2);
+        let backup = &imported[0];
+        assert!(backup.auto_discovered);
+        assert_eq!(backup.command, "python {{SCRIPT_FILE}}");
+        assert_eq!(backup.description.as_deref(), Some("Back up the db"));
+        assert_eq!(backup.working_dir.as_deref(), Some(folder_path.join("tools").to_string_lossy().as_ref()));
+        assert!(backup.content_hash.is_some());
+        assert_eq!(imported[1].description.as_deref(), Some("Say hello"));
+
+        // Importing a re-scan adds nothing
+        let again = storage.import_discovered_scripts(&paths).unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.skipped, paths);
+        assert_eq!(storage.get_all_global_scripts().len(), 2);
+
+        fs::remove_file(folder_path.join("greet.js")).unwrap();
+        let result = storage.scan_scripts_folder(None).unwrap();
+        assert_eq!(result.scripts.len(), 1);
+        assert_eq!(result.vanished.len(), 1);
+        assert_eq!(result.vanished[0].script_name, "greet");
+    }
+}
@@ -0,0 +1,78 @@
+//! Where a global script runs, and opening folders in the file manager.
+//!
+//! A script runs in its configured `working_dir` (`~` expanded, relative
+//! paths taken from the caller's directory), or in the caller's directory
+//! when it has none. The TUI opens that folder, or the folder of the
+//! script's `script_path`, with [`open_in_explorer`], the helper behind the
+//! GUI's `open_in_explorer` command.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::models::GlobalScript;
+use crate::script_validation::expand_home;
+
+/// The directory `script` runs in when started from `base`
+pub fn working_dir(script: &GlobalScript, base: &Path) -> PathBuf {
+    match script.working_dir.as_deref().map(str::trim) {
+        None | Some("") | Some(".") => base.to_path_buf(),
+        Some(dir) => base.join(expand_home(dir)),
+    }
+}
+
+/// The folder holding `script`'s source file, resolved like its working dir
+pub fn source_dir(script: &GlobalScript, base: &Path) -> Option<PathBuf> {
+    let path = script.script_path.as_deref().map(str::trim).filter(|p| !p.is_empty())?;
+    let file = working_dir(script, base).join(expand_home(path));
+    file.parent().map(Path::to_path_buf)
+}
+
+/// Open `path` in the platform's file manager
+pub fn open_in_explorer(path: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let program = "explorer";
+    #[cfg(target_os = "macos")]
+    let program = "open";
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let program = "xdg-open";
+
+    Command::new(program).arg(path).spawn().map(|_| ()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(working_dir: Option<&str>, script_path: Option<&str>) -> GlobalScript {
+        let mut script = GlobalScript::new("deploy".into(), "sh {{SCRIPT_FILE}}".into(), working_dir.map(String::from));
+        script.script_path = script_path.map(String::from);
+        script
+    }
+
+    #[test]
+    fn working_dir_falls_back_to_the_base_and_resolves_relative_dirs() {
+        let base = Path::new("/home/me/code");
+        assert_eq!(working_dir(&script(None, None), base), base);
+        assert_eq!(working_dir(&script(Some("  "), None), base), base);
+        assert_eq!(working_dir(&script(Some("."), None), base), base);
+        assert_eq!(working_dir(&script(Some("tools"), None), base), base.join("tools"));
+        assert_eq!(working_dir(&script(Some("/srv/app"), None), base), Path::new("/srv/app"));
+
+        let home = directories::BaseDirs::new().unwrap().home_dir().to_path_buf();
+        assert_eq!(working_dir(&script(Some("~/scripts"), None), base), home.join("scripts"));
+    }
+
+    #[test]
+    fn source_dir_is_the_script_files_folder() {
+        let base = Path::new("/home/me/code");
+        assert_eq!(source_dir(&script(Some("/srv/app"), None), base), None);
+        assert_eq!(
+            source_dir(&script(Some("/srv/app"), Some("bin/deploy.sh")), base),
+            Some(PathBuf::from("/srv/app/bin"))
+        );
+        assert_eq!(
+            source_dir(&script(None, Some("/opt/scripts/deploy.sh")), base),
+            Some(PathBuf::from("/opt/scripts"))
+        );
+    }
+}
@@ -0,0 +1,274 @@
+//! Structured search queries for global scripts.
+//!
+//! A query is a list of space-separated terms that must all match:
+//! `tag:deploy` (script has the tag), `folder:infra` (script lives in a folder
+//! named "infra", or under `infra/...` when the value contains a `/`), and
+//! plain text (substring of name, description or command). Prefixes and
+//! values are case-insensitive; unknown prefixes are treated as plain text.
+//!
+//! [`hoist_running`] is the optional second step of building a script list:
+//! after filtering, running scripts are pinned to the top. [`folder_targets`]
+//! lists the folders of a built list for jumping between them.
+
+use crate::models::GlobalScript;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryTerm {
+    Tag(String),
+    Folder(String),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptQuery {
+    pub terms: Vec<QueryTerm>,
+}
+
+impl ScriptQuery {
+    pub fn parse(input: &str) -> Self {
+        let terms = input
+            .split_whitespace()
+            .filter_map(|word| {
+                let lower = word.to_lowercase();
+                if let Some((prefix, value)) = lower.split_once(':') {
+                    match prefix {
+                        // A bare `tag:` while typing shouldn't hide everything
+                        "tag" | "folder" if value.is_empty() => return None,
+                        "tag" => return Some(QueryTerm::Tag(value.to_string())),
+                        "folder" => {
+                            let value = value.trim_matches('/');
+                            return (!value.is_empty()).then(|| QueryTerm::Folder(value.to_string()));
+                        }
+                        _ => {}
+                    }
+                }
+                Some(QueryTerm::Text(lower))
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, script: &GlobalScript) -> bool {
+        self.terms.iter().all(|term| match term {
+            QueryTerm::Tag(tag) => script.tags.iter().any(|t| t.to_lowercase() == *tag),
+            QueryTerm::Folder(folder) => script
+                .folder
+                .as_deref()
+                .is_some_and(|f| folder_matches(&f.to_lowercase(), folder)),
+            QueryTerm::Text(text) => {
+                script.name.to_lowercase().contains(text)
+                    || script.description.as_deref().unwrap_or("").to_lowercase().contains(text)
+                    || script.command.to_lowercase().contains(text)
+            }
+        })
+    }
+
+    /// Human-readable form of the parsed query, e.g. `tag=deploy AND "db"`.
+    pub fn describe(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| match term {
+                QueryTerm::Tag(tag) => format!("tag={}", tag),
+                QueryTerm::Folder(folder) => format!("folder={}", folder),
+                QueryTerm::Text(text) => format!("\"{}\"", text),
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+}
+
+/// `infra` matches any folder segment named infra; `deploy/aws` matches that
+/// path and anything below it.
+pub(crate) fn folder_matches(script_folder: &str, wanted: &str) -> bool {
+    let script_folder = script_folder.trim_matches('/');
+    if wanted.contains('/') {
+        script_folder == wanted || script_folder.starts_with(&format!("{}/", wanted))
+    } else {
+        script_folder.split('/').any(|segment| segment == wanted)
+    }
+}
+
+/// Rows of a script list after [`hoist_running`]: `rows[..pinned]` is the
+/// pinned "Active" section, the rest keep their normal order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HoistedRows {
+    pub rows: Vec<usize>,
+    pub pinned: usize,
+}
+
+/// Move rows that are currently running to the front, ordered by when they
+/// started (`started_at` returns `None` for rows that aren't running). Only
+/// rows already in `rows` are considered, so filtered-out scripts stay hidden.
+pub fn hoist_running<K: Ord>(rows: &[usize], started_at: impl Fn(usize) -> Option<K>) -> HoistedRows {
+    let mut running: Vec<(K, usize)> = Vec::new();
+    let mut rest: Vec<usize> = Vec::new();
+    for &row in rows {
+        match started_at(row) {
+            Some(key) => running.push((key, row)),
+            None => rest.push(row),
+        }
+    }
+    // Stable, so scripts started at the same instant keep their list order
+    running.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let pinned = running.len();
+    let mut rows: Vec<usize> = running.into_iter().map(|(_, row)| row).collect();
+    rows.extend(rest);
+    HoistedRows { rows, pinned }
+}
+
+/// A folder in a script list, as a jump target. `folder` is `None` for the
+/// root group (scripts outside any folder).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderTarget {
+    pub folder: Option<String>,
+    /// Position of the folder's first script in the list
+    pub first_row: usize,
+    pub count: usize,
+}
+
+/// Folders present in `rows` (indices into `scripts`, in display order): the
+/// root group first, then folders alphabetically. Only the list order is
+/// read, so jumping never changes what's filtered.
+pub fn folder_targets(scripts: &[GlobalScript], rows: &[usize]) -> Vec<FolderTarget> {
+    let mut targets: Vec<FolderTarget> = Vec::new();
+    for (pos, &row) in rows.iter().enumerate() {
+        let folder = scripts[row]
+            .folder
+            .as_deref()
+            .map(|f| f.trim_matches('/'))
+            .filter(|f| !f.is_empty())
+            .map(str::to_string);
+        match targets.iter_mut().find(|t| t.folder == folder) {
+            Some(target) => target.count += 1,
+            None => targets.push(FolderTarget { folder, first_row: pos, count: 1 }),
+        }
+    }
+    targets.sort_by_key(|t| t.folder.as_ref().map(|f| f.to_lowercase()));
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(name: &str, tags: &[&str], folder: Option<&str>) -> GlobalScript {
+        let mut s = GlobalScript::new(name.to_string(), format!("run-{}", name), None);
+        s.tags = tags.iter().map(|t| t.to_string()).collect();
+        s.folder = folder.map(|f| f.to_string());
+        s
+    }
+
+    #[test]
+    fn parses_prefixes_and_text() {
+        let q = ScriptQuery::parse("tag:deploy  db folder:infra");
+        assert_eq!(
+            q.terms,
+            vec![
+                QueryTerm::Tag("deploy".into()),
+                QueryTerm::Text("db".into()),
+                QueryTerm::Folder("infra".into()),
+            ]
+        );
+        assert_eq!(q.describe(), "tag=deploy AND \"db\" AND folder=infra");
+    }
+
+    #[test]
+    fn combined_filters_are_anded() {
+        let q = ScriptQuery::parse("tag:deploy db");
+        assert!(q.matches(&script("db-migrate", &["deploy"], None)));
+        assert!(!q.matches(&script("web", &["deploy"], None)));
+        assert!(!q.matches(&script("db-backup", &["backup"], None)));
+    }
+
+    #[test]
+    fn folder_filter() {
+        let q = ScriptQuery::parse("folder:infra");
+        assert!(q.matches(&script("a", &[], Some("infra"))));
+        assert!(q.matches(&script("b", &[], Some("ops/infra"))));
+        assert!(!q.matches(&script("c", &[], Some("infrastructure"))));
+        assert!(!q.matches(&script("d", &[], None)));
+
+        let q = ScriptQuery::parse("folder:deploy/aws");
+        assert!(q.matches(&script("e", &[], Some("deploy/aws/lambda"))));
+        assert!(!q.matches(&script("f", &[], Some("deploy/gcp"))));
+    }
+
+    #[test]
+    fn unknown_prefix_is_plain_text() {
+        let q = ScriptQuery::parse("http://localhost");
+        assert_eq!(q.terms, vec![QueryTerm::Text("http://localhost".into())]);
+
+        let mut s = script("ping", &[], None);
+        s.command = "curl http://localhost:3000".into();
+        assert!(q.matches(&s));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let q = ScriptQuery::parse("TAG:Deploy Folder:INFRA DB");
+        assert!(q.matches(&script("DB-Migrate", &["deploy"], Some("Infra"))));
+    }
+
+    #[test]
+    fn empty_prefix_values_are_ignored() {
+        assert!(ScriptQuery::parse("tag: folder:/").is_empty());
+        assert!(ScriptQuery::parse("").matches(&script("x", &[], None)));
+    }
+
+    #[test]
+    fn running_rows_are_pinned_in_start_order() {
+        // Rows 1 and 4 running, 4 started first
+        let started = |row: usize| match row {
+            1 => Some(20),
+            4 => Some(10),
+            _ => None,
+        };
+        let hoisted = hoist_running(&[0, 1, 2, 3, 4], started);
+        assert_eq!(hoisted.rows, vec![4, 1, 0, 2, 3]);
+        assert_eq!(hoisted.pinned, 2);
+
+        // Once finished they fall back to their normal position
+        let hoisted = hoist_running(&[0, 1, 2, 3, 4], |row| (row == 1).then_some(20));
+        assert_eq!(hoisted.rows, vec![1, 0, 2, 3, 4]);
+        let hoisted = hoist_running(&[0, 1, 2, 3, 4], |_| None::<u32>);
+        assert_eq!(hoisted.rows, vec![0, 1, 2, 3, 4]);
+        assert_eq!(hoisted.pinned, 0);
+    }
+
+    #[test]
+    fn hoisting_respects_filters() {
+        // Row 4 is running but filtered out: it must not reappear
+        let hoisted = hoist_running(&[0, 2, 3], |row| (row == 4 || row == 3).then_some(row));
+        assert_eq!(hoisted.rows, vec![3, 0, 2]);
+        assert_eq!(hoisted.pinned, 1);
+    }
+
+    #[test]
+    fn folder_jump_lands_on_first_member() {
+        // List order as sorted by primary tag: folders are interleaved
+        let scripts = vec![
+            script("build", &["ci"], Some("infra")),
+            script("lint", &["ci"], None),
+            script("deploy", &["ops"], Some("deploy/aws")),
+            script("migrate", &["ops"], Some("infra/")),
+            script("notes", &[], None),
+        ];
+        let targets = folder_targets(&scripts, &[0, 1, 2, 3, 4]);
+        let folders: Vec<Option<&str>> = targets.iter().map(|t| t.folder.as_deref()).collect();
+        assert_eq!(folders, vec![None, Some("deploy/aws"), Some("infra")]);
+        assert_eq!((targets[0].first_row, targets[0].count), (1, 2));
+        assert_eq!((targets[1].first_row, targets[1].count), (2, 1));
+        assert_eq!((targets[2].first_row, targets[2].count), (0, 2));
+
+        // Positions follow the displayed rows, not the script indices
+        let targets = folder_targets(&scripts, &[3, 4, 0]);
+        let infra = targets.iter().find(|t| t.folder.as_deref() == Some("infra")).unwrap();
+        assert_eq!(infra.first_row, 0);
+        assert_eq!(targets.len(), 2);
+    }
+}
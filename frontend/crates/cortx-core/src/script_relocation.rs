@@ -0,0 +1,248 @@
+//! Following auto-discovered scripts whose file moved.
+//!
+//! Reorganizing a scripts folder leaves imported scripts pointing at paths
+//! that no longer exist, and a re-scan would offer the moved files as new
+//! scripts. [`plan_relocations`] pairs the files of a scan with missing
+//! auto-discovered scripts, by file name and by the content hash stored at
+//! import time. Only unambiguous pairs are relinked; the rest is reported.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::models::{DiscoveredScript, GlobalScript, ScriptRelocation, UnresolvedMove, UnresolvedMoveReason};
+
+/// Files larger than this aren't hashed
+pub const MAX_HASH_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Hash of the file at `path`, or `None` when it can't be read or is over
+/// [`MAX_HASH_BYTES`]. FNV-1a, so hashes stay comparable across builds.
+pub fn content_hash(path: &Path) -> Option<String> {
+    if fs::metadata(path).ok()?.len() > MAX_HASH_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    Some(format!("{:016x}", hash))
+}
+
+/// What a scan should do about moved scripts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelocationPlan {
+    pub relocated: Vec<ScriptRelocation>,
+    pub unresolved: Vec<UnresolvedMove>,
+}
+
+/// How a discovered file relates to a missing script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Match {
+    /// Same content, or same file name where a hash is unknown
+    Moved,
+    /// Same file name, different content
+    Changed,
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn match_of(script: &GlobalScript, script_path: &str, file: &DiscoveredScript) -> Option<Match> {
+    let same_name = file_name(script_path) == file_name(&file.path);
+    match (&script.content_hash, &file.content_hash) {
+        (Some(a), Some(b)) if a == b => Some(Match::Moved),
+        (Some(_), Some(_)) => same_name.then_some(Match::Changed),
+        _ => same_name.then_some(Match::Moved),
+    }
+}
+
+/// Pair `discovered` files with auto-discovered `scripts` whose
+/// `script_path` is gone (`path_exists` says so).
+///
+/// Only files no script points at yet are considered. A file relocates a
+/// script when it has the same content (a rename or a move), or the same
+/// file name where either hash is unknown, and that pairing is the only one
+/// for both. A file that could be several scripts, or a script that could
+/// be several files, is reported as ambiguous. A same-named file whose
+/// content differs is reported rather than relinked.
+pub fn plan_relocations(
+    scripts: &[GlobalScript],
+    discovered: &[DiscoveredScript],
+    path_exists: impl Fn(&str) -> bool,
+) -> RelocationPlan {
+    let known: HashSet<&str> = scripts.iter().filter_map(|s| s.script_path.as_deref()).collect();
+    let missing: Vec<(&GlobalScript, &str)> = scripts
+        .iter()
+        .filter(|s| s.auto_discovered)
+        .filter_map(|s| s.script_path.as_deref().map(|p| (s, p)))
+        .filter(|&(_, path)| !path_exists(path))
+        .collect();
+
+    // For each new file: the scripts it may be, by kind of match
+    let candidates = discovered
+        .iter()
+        .filter(|file| !known.contains(file.path.as_str()))
+        .map(|file| {
+            let mut moved = Vec::new();
+            let mut changed = Vec::new();
+            for &(script, path) in &missing {
+                match match_of(script, path, file) {
+                    Some(Match::Moved) => moved.push((script, path)),
+                    Some(Match::Changed) => changed.push(script),
+                    None => {}
+                }
+            }
+            (file, moved, changed)
+        })
+        .collect::<Vec<_>>();
+
+    let mut claims: HashMap<&str, usize> = HashMap::new();
+    for (_, moved, _) in &candidates {
+        for (script, _) in moved {
+            *claims.entry(script.id.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut plan = RelocationPlan::default();
+    for (file, moved, changed) in candidates {
+        let unresolved = |scripts: Vec<&GlobalScript>, reason| UnresolvedMove {
+            path: file.path.clone(),
+            candidate_ids: scripts.iter().map(|s| s.id.clone()).collect(),
+            reason,
+        };
+        match moved.as_slice() {
+            [] if changed.is_empty() => {}
+            [] => plan.unresolved.push(unresolved(changed, UnresolvedMoveReason::ContentChanged)),
+            &[(script, old_path)] if claims[script.id.as_str()] == 1 => plan.relocated.push(ScriptRelocation {
+                script_id: script.id.clone(),
+                script_name: script.name.clone(),
+                old_path: old_path.to_string(),
+                new_path: file.path.clone(),
+            }),
+            _ => {
+                let scripts = moved.iter().map(|&(s, _)| s).collect();
+                plan.unresolved.push(unresolved(scripts, UnresolvedMoveReason::Ambiguous));
+            }
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imported(id: &str, path: &str, hash: Option<&str>) -> GlobalScript {
+        let mut s = GlobalScript::new(id.to_string(), "bash {{SCRIPT_FILE}}".to_string(), None);
+        s.id = id.to_string();
+        s.script_path = Some(path.to_string());
+        s.auto_discovered = true;
+        s.content_hash = hash.map(str::to_string);
+        s
+    }
+
+    fn found(path: &str, hash: Option<&str>) -> DiscoveredScript {
+        DiscoveredScript {
+            path: path.to_string(),
+            name: file_name(path).to_string(),
+            description: None,
+            extension: ".sh".to_string(),
+            content_hash: hash.map(str::to_string),
+        }
+    }
+
+    /// Only `present` paths exist
+    fn plan_for(scripts: &[GlobalScript], discovered: &[DiscoveredScript], present: &[&str]) -> RelocationPlan {
+        plan_relocations(scripts, discovered, |p| present.contains(&p))
+    }
+
+    fn moved(plan: &RelocationPlan) -> Vec<(&str, &str)> {
+        plan.relocated.iter().map(|r| (r.script_id.as_str(), r.new_path.as_str())).collect()
+    }
+
+    #[test]
+    fn renamed_and_moved_files_are_followed() {
+        let scripts = vec![
+            imported("rename", "/s/deploy.sh", Some("aaa")),
+            imported("move", "/s/backup.sh", Some("bbb")),
+            imported("no-hash", "C:\\s\\seed.sh", None),
+        ];
+        let discovered = vec![
+            found("/s/deploy-prod.sh", Some("aaa")),
+            found("/s/db/backup.sh", Some("bbb")),
+            found("C:\\s\\db\\seed.sh", Some("ccc")),
+        ];
+
+        let plan = plan_for(&scripts, &discovered, &[]);
+        assert_eq!(
+            moved(&plan),
+            vec![("rename", "/s/deploy-prod.sh"), ("move", "/s/db/backup.sh"), ("no-hash", "C:\\s\\db\\seed.sh")]
+        );
+        assert_eq!(plan.relocated[1].old_path, "/s/backup.sh");
+        assert!(plan.unresolved.is_empty());
+    }
+
+    #[test]
+    fn edited_files_are_reported_not_relinked() {
+        let scripts = vec![imported("edited", "/s/deploy.sh", Some("aaa"))];
+        let discovered = vec![found("/s/aws/deploy.sh", Some("zzz")), found("/s/other.sh", Some("yyy"))];
+
+        let plan = plan_for(&scripts, &discovered, &[]);
+        assert!(plan.relocated.is_empty());
+        assert_eq!(
+            plan.unresolved,
+            vec![UnresolvedMove {
+                path: "/s/aws/deploy.sh".into(),
+                candidate_ids: vec!["edited".into()],
+                reason: UnresolvedMoveReason::ContentChanged,
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_file_names_are_ambiguous() {
+        // One script, two same-named files
+        let scripts = vec![imported("a", "/s/run.sh", None)];
+        let discovered = vec![found("/s/x/run.sh", Some("1")), found("/s/y/run.sh", Some("2"))];
+        let plan = plan_for(&scripts, &discovered, &[]);
+        assert!(plan.relocated.is_empty());
+        assert_eq!(plan.unresolved.len(), 2);
+        assert!(plan.unresolved.iter().all(|u| u.reason == UnresolvedMoveReason::Ambiguous));
+
+        // Two scripts, one file
+        let scripts = vec![imported("a", "/s/x/run.sh", None), imported("b", "/s/y/run.sh", None)];
+        let plan = plan_for(&scripts, &[found("/s/run.sh", None)], &[]);
+        assert!(plan.relocated.is_empty());
+        assert_eq!(plan.unresolved[0].candidate_ids, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn only_missing_auto_discovered_scripts_move() {
+        let mut manual = imported("manual", "/s/a.sh", None);
+        manual.auto_discovered = false;
+        let mut other = imported("other", "/s/c.sh", None);
+        other.auto_discovered = false;
+        let scripts = vec![manual, imported("present", "/s/b.sh", None), imported("known", "/old/c.sh", None), other];
+        // b.sh still exists, and /s/c.sh already belongs to another script
+        let discovered = vec![found("/new/a.sh", None), found("/new/b.sh", None), found("/s/c.sh", None)];
+
+        assert_eq!(plan_for(&scripts, &discovered, &["/s/b.sh"]), RelocationPlan::default());
+    }
+
+    #[test]
+    fn large_files_are_not_hashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.sh");
+        fs::write(&small, "echo hi\n").unwrap();
+        let large = dir.path().join("large.sh");
+        fs::File::create(&large).unwrap().set_len(MAX_HASH_BYTES + 1).unwrap();
+
+        assert_eq!(content_hash(&small), content_hash(&small));
+        assert_eq!(content_hash(&small).unwrap().len(), 16);
+        assert_eq!(content_hash(&large), None);
+        assert_eq!(content_hash(&dir.path().join("gone.sh")), None);
+    }
+}
@@ -0,0 +1,315 @@
+//! Services a global script needs running (`GlobalScript::requires`).
+//!
+//! A requirement names a project and one of its services, each by id or by
+//! name. Names are matched tolerantly — ignoring case, then punctuation, then
+//! as a unique prefix — and a name that fits several candidates is reported
+//! as ambiguous rather than guessed. Checking is pure over a projects snapshot
+//! and an "is this service running" predicate, so every front end (GUI, TUI,
+//! CLI, MCP) runs the same check before starting a script.
+
+use serde::Serialize;
+
+use crate::models::{GlobalScript, Project, Service, ServiceRequirement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RequirementState {
+    Running,
+    Stopped,
+    /// The project or service couldn't be resolved (see `problem`)
+    Unresolved,
+}
+
+/// One requirement, resolved and checked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementStatus {
+    pub requirement: ServiceRequirement,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<String>,
+    /// "project / service", with the resolved names where available
+    pub label: String,
+    pub state: RequirementState,
+    /// Why the requirement couldn't be resolved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<String>,
+}
+
+impl RequirementStatus {
+    pub fn is_met(&self) -> bool {
+        self.state == RequirementState::Running
+    }
+
+    /// Resolved but stopped, and the script allows starting it on demand.
+    pub fn can_auto_start(&self) -> bool {
+        self.state == RequirementState::Stopped && self.requirement.auto_start
+    }
+}
+
+/// A script can't run because some of its required services aren't up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementNotMet {
+    pub script_name: String,
+    pub missing: Vec<RequirementStatus>,
+    /// Every missing service may be auto-started: a front end that can start
+    /// services can offer to start them and retry the run.
+    pub start_and_retry: bool,
+}
+
+impl std::fmt::Display for RequirementNotMet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let missing: Vec<String> = self
+            .missing
+            .iter()
+            .map(|status| match &status.problem {
+                Some(problem) => format!("{} ({})", status.label, problem),
+                None => format!("{} (not running)", status.label),
+            })
+            .collect();
+        write!(f, "'{}' requires {}", self.script_name, missing.join(", "))
+    }
+}
+
+impl std::error::Error for RequirementNotMet {}
+
+/// Check each requirement against `projects`; `is_running` is asked about
+/// resolved service ids only.
+pub fn check_requirements(
+    requires: &[ServiceRequirement],
+    projects: &[Project],
+    is_running: impl Fn(&str) -> bool,
+) -> Vec<RequirementStatus> {
+    requires
+        .iter()
+        .map(|requirement| match resolve_requirement(projects, requirement) {
+            Ok((project, service)) => RequirementStatus {
+                requirement: requirement.clone(),
+                project_id: Some(project.id.clone()),
+                service_id: Some(service.id.clone()),
+                label: format!("{} / {}", project.name, service.name),
+                state: if is_running(&service.id) {
+                    RequirementState::Running
+                } else {
+                    RequirementState::Stopped
+                },
+                problem: None,
+            },
+            Err(problem) => RequirementStatus {
+                requirement: requirement.clone(),
+                project_id: None,
+                service_id: None,
+                label: format!(
+                    "{} / {}",
+                    requirement.project_name_or_id, requirement.service_name_or_id
+                ),
+                state: RequirementState::Unresolved,
+                problem: Some(problem),
+            },
+        })
+        .collect()
+}
+
+/// `Ok` when every service `script` requires is running.
+pub fn ensure_requirements(
+    script: &GlobalScript,
+    projects: &[Project],
+    is_running: impl Fn(&str) -> bool,
+) -> Result<(), RequirementNotMet> {
+    let missing: Vec<RequirementStatus> = check_requirements(&script.requires, projects, is_running)
+        .into_iter()
+        .filter(|status| !status.is_met())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let start_and_retry = missing.iter().all(RequirementStatus::can_auto_start);
+    Err(RequirementNotMet {
+        script_name: script.name.clone(),
+        missing,
+        start_and_retry,
+    })
+}
+
+/// The project and service a requirement names.
+pub fn resolve_requirement<'a>(
+    projects: &'a [Project],
+    requirement: &ServiceRequirement,
+) -> Result<(&'a Project, &'a Service), String> {
+    let project = find_by_name_or_id(
+        projects,
+        &requirement.project_name_or_id,
+        "project",
+        |p| &p.id,
+        |p| &p.name,
+    )?;
+    let service = find_by_name_or_id(
+        &project.services,
+        &requirement.service_name_or_id,
+        "service",
+        |s| &s.id,
+        |s| &s.name,
+    )?;
+    Ok((project, service))
+}
+
+/// Lowercase alphanumerics only: "API-Server" and "api server" compare equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find the item `query` refers to: by exact id, then by name — exactly,
+/// ignoring case, ignoring case and punctuation, then as a prefix of the
+/// normalized name. A step only runs when the previous ones found nothing,
+/// and more than one match within a step is an ambiguity error.
+fn find_by_name_or_id<'a, T>(
+    items: &'a [T],
+    query: &str,
+    kind: &str,
+    id: impl Fn(&T) -> &str,
+    name: impl Fn(&T) -> &str,
+) -> Result<&'a T, String> {
+    let query = query.trim();
+    if let Some(item) = items.iter().find(|item| id(item) == query) {
+        return Ok(item);
+    }
+
+    let lower = query.to_lowercase();
+    let normalized = normalize(query);
+    let steps: [&dyn Fn(&str) -> bool; 4] = [
+        &|n| n == query,
+        &|n| n.to_lowercase() == lower,
+        &|n| !normalized.is_empty() && normalize(n) == normalized,
+        &|n| !normalized.is_empty() && normalize(n).starts_with(&normalized),
+    ];
+    for matches in steps {
+        let found: Vec<&T> = items.iter().filter(|item| matches(name(item))).collect();
+        match found.as_slice() {
+            [] => continue,
+            [only] => return Ok(*only),
+            several => {
+                let names: Vec<&str> = several.iter().map(|item| name(item)).collect();
+                return Err(format!(
+                    "'{}' matches several {}s: {}",
+                    query,
+                    kind,
+                    names.join(", ")
+                ));
+            }
+        }
+    }
+    Err(format!("no {} matches '{}'", kind, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, services: &[&str]) -> Project {
+        let mut p = Project::new(name.to_string(), format!("/src/{}", name));
+        p.services = services
+            .iter()
+            .map(|s| Service::new(s.to_string(), p.root_path.clone(), "run".into()))
+            .collect();
+        p
+    }
+
+    fn requirement(project: &str, service: &str, auto_start: bool) -> ServiceRequirement {
+        ServiceRequirement {
+            project_name_or_id: project.to_string(),
+            service_name_or_id: service.to_string(),
+            auto_start,
+        }
+    }
+
+    fn resolved<'a>(projects: &'a [Project], p: &str, s: &str) -> Result<(&'a str, &'a str), String> {
+        resolve_requirement(projects, &requirement(p, s, false))
+            .map(|(p, s)| (p.name.as_str(), s.name.as_str()))
+    }
+
+    #[test]
+    fn resolves_names_tolerantly() {
+        let projects = vec![
+            project("Shop Backend", &["API Server", "worker"]),
+            project("shop-frontend", &["web"]),
+        ];
+        let by_id = resolve_requirement(
+            &projects,
+            &requirement(&projects[1].id, &projects[1].services[0].id, false),
+        )
+        .unwrap();
+        assert_eq!(by_id.1.name, "web");
+
+        assert_eq!(resolved(&projects, "SHOP BACKEND", "worker"), Ok(("Shop Backend", "worker")));
+        assert_eq!(resolved(&projects, "shop_backend", "api-server"), Ok(("Shop Backend", "API Server")));
+        assert_eq!(resolved(&projects, "shop-f", "WEB"), Ok(("shop-frontend", "web")));
+    }
+
+    #[test]
+    fn ambiguous_and_unknown_names_are_errors() {
+        let projects = vec![
+            project("shop-backend", &["api", "api-admin"]),
+            project("shop-frontend", &["web"]),
+        ];
+        let err = resolved(&projects, "shop", "api").unwrap_err();
+        assert!(err.contains("several projects"), "{}", err);
+        assert!(err.contains("shop-backend") && err.contains("shop-frontend"));
+
+        // An exact name wins over a longer name it's a prefix of
+        assert_eq!(resolved(&projects, "shop-backend", "api"), Ok(("shop-backend", "api")));
+
+        let err = resolved(&projects, "billing", "api").unwrap_err();
+        assert_eq!(err, "no project matches 'billing'");
+        let err = resolved(&projects, "shop-frontend", "").unwrap_err();
+        assert!(err.contains("no service"), "{}", err);
+    }
+
+    #[test]
+    fn checks_against_the_running_set() {
+        let projects = vec![project("shop", &["api", "db"])];
+        let api_id = projects[0].services[0].id.clone();
+        let requires = vec![
+            requirement("shop", "api", false),
+            requirement("shop", "db", true),
+            requirement("shop", "cache", true),
+        ];
+        let statuses = check_requirements(&requires, &projects, |id| id == api_id);
+        let states: Vec<RequirementState> = statuses.iter().map(|s| s.state).collect();
+        assert_eq!(
+            states,
+            vec![RequirementState::Running, RequirementState::Stopped, RequirementState::Unresolved]
+        );
+        assert_eq!(statuses[1].label, "shop / db");
+        assert!(statuses[1].can_auto_start());
+        assert!(!statuses[2].can_auto_start());
+        assert_eq!(statuses[2].problem.as_deref(), Some("no service matches 'cache'"));
+    }
+
+    #[test]
+    fn ensure_lists_what_is_missing() {
+        let projects = vec![project("shop", &["api", "db"])];
+        let mut script = GlobalScript::new("seed".into(), "seed.sh".into(), None);
+        assert!(ensure_requirements(&script, &projects, |_| false).is_ok());
+
+        script.requires = vec![requirement("shop", "api", true), requirement("shop", "db", true)];
+        assert!(ensure_requirements(&script, &projects, |_| true).is_ok());
+
+        let err = ensure_requirements(&script, &projects, |_| false).unwrap_err();
+        assert_eq!(err.missing.len(), 2);
+        assert!(err.start_and_retry);
+        assert_eq!(
+            err.to_string(),
+            "'seed' requires shop / api (not running), shop / db (not running)"
+        );
+
+        // One requirement can't be started for the user → no retry offer
+        script.requires[1].auto_start = false;
+        let err = ensure_requirements(&script, &projects, |_| false).unwrap_err();
+        assert!(!err.start_and_retry);
+    }
+}
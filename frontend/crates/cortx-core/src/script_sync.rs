@@ -0,0 +1,659 @@
+//! Keeping scripts imported from a shared export in sync with later
+//! versions of it.
+//!
+//! A script imported from an export keeps the id it has there and records
+//! a `ScriptOrigin` with the hash of the upstream version it was last in
+//! sync with. When a newer export comes in, [`plan_sync`] compares each
+//! tracked script with its incoming version:
+//!
+//! - neither side changed since the last sync (or both made the same
+//!   change): up to date
+//! - only upstream changed: the new version is applied
+//! - only the local copy changed: it is kept
+//! - both changed: a conflict, left as is until resolved per script
+//!
+//! Incoming scripts nobody imported yet are offered as additions. Local
+//! scripts without an origin are never touched. [`apply_plan`] carries out
+//! a plan with the user's resolutions; `Storage::apply_sync_plan` saves the
+//! result in one go.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::models::{GlobalScript, ScriptOrigin};
+
+/// Fields that are local bookkeeping rather than part of what a script does
+const LOCAL_FIELDS: &[&str] = &[
+    "id",
+    "createdAt",
+    "updatedAt",
+    "order",
+    "autoDiscovered",
+    "contentHash",
+    "rawHelp",
+    "status",
+    "origin",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncStatus {
+    UpToDate,
+    /// Only upstream changed; applied unless resolved otherwise
+    UpstreamUpdated,
+    /// Only the local copy changed; kept unless resolved otherwise
+    LocallyModified,
+    /// Both changed; left as is until resolved
+    Conflict,
+    /// Not imported yet; added unless resolved otherwise
+    Added,
+}
+
+/// A field that differs between the local copy and the upstream version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    pub field: String,
+    pub local: Value,
+    pub upstream: Value,
+}
+
+/// What a sync would do with one incoming script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// Id of the script in the export
+    pub source_id: String,
+    /// Local name, or the upstream one for additions
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_id: Option<String>,
+    pub status: SyncStatus,
+    /// Hash of the local copy when planned, to detect edits made since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_hash: Option<String>,
+    pub upstream_hash: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diff: Vec<FieldDiff>,
+    pub upstream: GlobalScript,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPlan {
+    pub entries: Vec<SyncEntry>,
+    /// Names of incoming scripts whose id a local script without an origin
+    /// already has; those are left alone
+    #[serde(default)]
+    pub skipped: Vec<String>,
+}
+
+impl SyncPlan {
+    pub fn count(&self, status: SyncStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+}
+
+/// How to settle an entry, keyed by its `source_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncResolution {
+    /// Keep the local copy (for additions: don't add)
+    KeepLocal,
+    /// Replace the local copy with upstream (for additions: add)
+    TakeUpstream,
+}
+
+/// Script names by what a sync did with them
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub updated: Vec<String>,
+    pub added: Vec<String>,
+    pub kept: Vec<String>,
+    /// Conflicts left without a resolution
+    pub unresolved: Vec<String>,
+    /// Entries whose local copy changed (or went away) since planning
+    pub stale: Vec<String>,
+    /// Ids of the scripts that were created or modified
+    #[serde(skip)]
+    pub changed_ids: Vec<String>,
+}
+
+/// The part of a script that is compared across versions, by camelCase field
+fn content(script: &GlobalScript) -> Map<String, Value> {
+    let mut fields = match serde_json::to_value(script) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    for field in LOCAL_FIELDS {
+        fields.remove(*field);
+    }
+    // Unset and empty optional fields are the same thing
+    fields.retain(|_, value| !is_empty(value));
+    fields
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+/// Hash of what a script does, ignoring ids, order, timestamps and other
+/// local bookkeeping (FNV-1a, as hex).
+pub fn content_hash(script: &GlobalScript) -> String {
+    // Map keys are sorted, so equal content serializes the same
+    let serialized = Value::Object(content(script)).to_string();
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in serialized.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Fields that differ between `local` and `upstream`, in field order
+pub fn field_diff(local: &GlobalScript, upstream: &GlobalScript) -> Vec<FieldDiff> {
+    let local = content(local);
+    let upstream = content(upstream);
+    let mut fields: Vec<&String> = local.keys().chain(upstream.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let l = local.get(field).cloned().unwrap_or(Value::Null);
+            let u = upstream.get(field).cloned().unwrap_or(Value::Null);
+            (l != u).then(|| FieldDiff { field: field.clone(), local: l, upstream: u })
+        })
+        .collect()
+}
+
+/// Origin for a script imported from `upstream` as it is now
+pub fn origin_of(upstream: &GlobalScript, now: DateTime<Utc>) -> ScriptOrigin {
+    ScriptOrigin {
+        source_id: upstream.id.clone(),
+        source_name: upstream.name.clone(),
+        imported_at: now,
+        last_synced_hash: content_hash(upstream),
+    }
+}
+
+/// Compare the `local` scripts with the `incoming` ones of an export.
+pub fn plan_sync(local: &[GlobalScript], incoming: &[GlobalScript]) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+    for upstream in incoming {
+        let upstream_hash = content_hash(upstream);
+        let tracked = local.iter().find_map(|script| {
+            script.origin.as_ref().filter(|o| o.source_id == upstream.id).map(|o| (script, o))
+        });
+
+        let Some((script, origin)) = tracked else {
+            if local.iter().any(|s| s.id == upstream.id) {
+                plan.skipped.push(upstream.name.clone());
+            } else {
+                plan.entries.push(SyncEntry {
+                    source_id: upstream.id.clone(),
+                    name: upstream.name.clone(),
+                    local_id: None,
+                    status: SyncStatus::Added,
+                    local_hash: None,
+                    upstream_hash,
+                    diff: Vec::new(),
+                    upstream: upstream.clone(),
+                });
+            }
+            continue;
+        };
+
+        let local_hash = content_hash(script);
+        let local_changed = local_hash != origin.last_synced_hash;
+        let upstream_changed = upstream_hash != origin.last_synced_hash;
+        let status = match (local_changed, upstream_changed) {
+            _ if local_hash == upstream_hash => SyncStatus::UpToDate,
+            (false, false) => SyncStatus::UpToDate,
+            (false, true) => SyncStatus::UpstreamUpdated,
+            (true, false) => SyncStatus::LocallyModified,
+            (true, true) => SyncStatus::Conflict,
+        };
+        let diff = if status == SyncStatus::UpToDate { Vec::new() } else { field_diff(script, upstream) };
+        plan.entries.push(SyncEntry {
+            source_id: upstream.id.clone(),
+            name: script.name.clone(),
+            local_id: Some(script.id.clone()),
+            status,
+            local_hash: Some(local_hash),
+            upstream_hash,
+            diff,
+            upstream: upstream.clone(),
+        });
+    }
+    plan
+}
+
+/// Carry out `plan` on `scripts`. Entries without a resolution get the
+/// default for their status; conflicts stay unresolved.
+pub fn apply_plan(
+    scripts: &mut Vec<GlobalScript>,
+    plan: &SyncPlan,
+    resolutions: &HashMap<String, SyncResolution>,
+    now: DateTime<Utc>,
+) -> SyncResult {
+    let mut result = SyncResult::default();
+    for entry in &plan.entries {
+        let resolution = resolutions.get(&entry.source_id).copied();
+
+        if entry.status == SyncStatus::Added {
+            if resolution == Some(SyncResolution::KeepLocal) {
+                result.kept.push(entry.name.clone());
+                continue;
+            }
+            let taken = scripts.iter().any(|s| {
+                s.id == entry.upstream.id
+                    || s.origin.as_ref().is_some_and(|o| o.source_id == entry.source_id)
+            });
+            if taken {
+                result.stale.push(entry.name.clone());
+                continue;
+            }
+            let mut script = entry.upstream.clone();
+            script.order = scripts.iter().map(|s| s.order + 1).max().unwrap_or(0);
+            script.created_at = now;
+            script.updated_at = now;
+            script.auto_discovered = false;
+            script.content_hash = None;
+            script.origin = Some(origin_of(&entry.upstream, now));
+            result.added.push(script.name.clone());
+            result.changed_ids.push(script.id.clone());
+            scripts.push(script);
+            continue;
+        }
+
+        let Some(script) = scripts.iter_mut().find(|s| Some(&s.id) == entry.local_id.as_ref()) else {
+            result.stale.push(entry.name.clone());
+            continue;
+        };
+        if entry.local_hash.as_deref() != Some(content_hash(script).as_str()) {
+            result.stale.push(entry.name.clone());
+            continue;
+        }
+
+        let take_upstream = match resolution {
+            Some(resolution) => resolution == SyncResolution::TakeUpstream,
+            None => entry.status == SyncStatus::UpstreamUpdated,
+        };
+        if take_upstream && entry.status != SyncStatus::UpToDate {
+            take_upstream_version(script, entry, now);
+            result.updated.push(script.name.clone());
+            result.changed_ids.push(script.id.clone());
+            continue;
+        }
+
+        match (entry.status, resolution) {
+            (SyncStatus::Conflict, None) => {
+                result.unresolved.push(script.name.clone());
+                continue;
+            }
+            (SyncStatus::UpToDate, _) => {}
+            _ => result.kept.push(script.name.clone()),
+        }
+        // The local copy now builds on this upstream version
+        if let Some(origin) = script.origin.as_mut() {
+            if origin.last_synced_hash != entry.upstream_hash || origin.source_name != entry.upstream.name {
+                origin.last_synced_hash = entry.upstream_hash.clone();
+                origin.source_name = entry.upstream.name.clone();
+                result.changed_ids.push(script.id.clone());
+            }
+        }
+    }
+    result
+}
+
+/// Replace what `script` does with the upstream version, keeping its local
+/// bookkeeping
+fn take_upstream_version(script: &mut GlobalScript, entry: &SyncEntry, now: DateTime<Utc>) {
+    let local = std::mem::replace(script, entry.upstream.clone());
+    script.id = local.id;
+    script.created_at = local.created_at;
+    script.updated_at = now;
+    script.order = local.order;
+    script.auto_discovered = local.auto_discovered;
+    script.content_hash = local.content_hash;
+    script.status = local.status;
+    script.raw_help = script.raw_help.take().or(local.raw_help);
+    script.origin = Some(ScriptOrigin {
+        source_id: entry.source_id.clone(),
+        source_name: entry.upstream.name.clone(),
+        imported_at: local.origin.map_or(now, |o| o.imported_at),
+        last_synced_hash: entry.upstream_hash.clone(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap()
+    }
+
+    /// The library a team shares, as first exported
+    fn library() -> Vec<GlobalScript> {
+        let mut deploy = GlobalScript::new("deploy".into(), "./deploy.sh".into(), None);
+        deploy.id = "lib-deploy".into();
+        deploy.tags = vec!["ops".into()];
+        let mut lint = GlobalScript::new("lint".into(), "cargo clippy".into(), None);
+        lint.id = "lib-lint".into();
+        let mut backup = GlobalScript::new("backup".into(), "./backup.sh".into(), Some("/srv".into()));
+        backup.id = "lib-backup".into();
+        vec![deploy, lint, backup]
+    }
+
+    /// The library as imported locally, plus a script of our own
+    fn imported() -> Vec<GlobalScript> {
+        let mut scripts: Vec<GlobalScript> = library()
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut script)| {
+                script.origin = Some(origin_of(&script, at(1)));
+                script.order = i as u32 + 1;
+                script
+            })
+            .collect();
+        let mut own = GlobalScript::new("mine".into(), "echo mine".into(), None);
+        own.id = "local-mine".into();
+        scripts.insert(0, own);
+        scripts
+    }
+
+    fn json<T: Serialize + ?Sized>(value: &T) -> Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    fn find<'a>(scripts: &'a [GlobalScript], id: &str) -> &'a GlobalScript {
+        scripts.iter().find(|s| s.id == id).unwrap()
+    }
+
+    fn find_mut<'a>(scripts: &'a mut [GlobalScript], id: &str) -> &'a mut GlobalScript {
+        scripts.iter_mut().find(|s| s.id == id).unwrap()
+    }
+
+    fn status_of(plan: &SyncPlan, source_id: &str) -> SyncStatus {
+        plan.entries.iter().find(|e| e.source_id == source_id).unwrap().status
+    }
+
+    #[test]
+    fn the_hash_ignores_local_bookkeeping() {
+        let script = &library()[0];
+        let mut copy = script.clone();
+        copy.id = "other".into();
+        copy.order = 42;
+        copy.created_at = at(20);
+        copy.updated_at = at(21);
+        copy.auto_discovered = true;
+        copy.content_hash = Some("abc".into());
+        copy.raw_help = Some("usage: deploy".into());
+        copy.origin = Some(origin_of(script, at(2)));
+        assert_eq!(content_hash(&copy), content_hash(script));
+
+        // Unset and empty optional fields hash the same
+        copy.env_vars = Some(HashMap::new());
+        assert_eq!(content_hash(&copy), content_hash(script));
+
+        copy.command = "./deploy.sh --prod".into();
+        assert_ne!(content_hash(&copy), content_hash(script));
+    }
+
+    #[test]
+    fn the_hash_does_not_depend_on_env_var_order() {
+        let mut a = library()[0].clone();
+        let mut b = a.clone();
+        let vars = [("A", "1"), ("B", "2"), ("C", "3")];
+        a.env_vars = Some(vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+        b.env_vars = Some(vars.iter().rev().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+        assert_eq!(content_hash(&a), content_hash(&b));
+    }
+
+    #[test]
+    fn an_unchanged_export_is_up_to_date() {
+        let local = imported();
+        let plan = plan_sync(&local, &library());
+        assert_eq!(plan.entries.len(), 3);
+        assert_eq!(plan.count(SyncStatus::UpToDate), 3);
+        assert!(plan.entries.iter().all(|e| e.diff.is_empty()));
+
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert!(result.changed_ids.is_empty());
+        assert_eq!(json(&scripts), json(&local));
+    }
+
+    #[test]
+    fn upstream_changes_are_applied_keeping_local_bookkeeping() {
+        let local = imported();
+        let mut export = library();
+        export[0].command = "./deploy.sh --all".into();
+        export[0].name = "deploy-all".into();
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(status_of(&plan, "lib-deploy"), SyncStatus::UpstreamUpdated);
+        let entry = &plan.entries[0];
+        let fields: Vec<&str> = entry.diff.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["command", "name"]);
+        assert_eq!(entry.diff[0].local, Value::from("./deploy.sh"));
+        assert_eq!(entry.diff[0].upstream, Value::from("./deploy.sh --all"));
+
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.updated, vec!["deploy-all"]);
+        assert_eq!(result.changed_ids, vec!["lib-deploy"]);
+
+        let before = find(&local, "lib-deploy");
+        let after = find(&scripts, "lib-deploy");
+        assert_eq!(after.command, "./deploy.sh --all");
+        assert_eq!(after.order, before.order);
+        assert_eq!(after.created_at, before.created_at);
+        assert_eq!(after.updated_at, at(5));
+        let origin = after.origin.as_ref().unwrap();
+        assert_eq!(origin.imported_at, at(1));
+        assert_eq!(origin.source_name, "deploy-all");
+        assert_eq!(origin.last_synced_hash, content_hash(&export[0]));
+
+        // Synced now
+        assert_eq!(status_of(&plan_sync(&scripts, &export), "lib-deploy"), SyncStatus::UpToDate);
+    }
+
+    #[test]
+    fn local_edits_are_kept_when_upstream_did_not_change() {
+        let mut local = imported();
+        find_mut(&mut local, "lib-lint").command = "cargo clippy -- -D warnings".into();
+
+        let plan = plan_sync(&local, &library());
+        assert_eq!(status_of(&plan, "lib-lint"), SyncStatus::LocallyModified);
+
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.kept, vec!["lint"]);
+        assert!(result.changed_ids.is_empty());
+        assert_eq!(find(&scripts, "lib-lint").command, "cargo clippy -- -D warnings");
+
+        // Unless explicitly reset to upstream
+        let resolutions = HashMap::from([("lib-lint".to_string(), SyncResolution::TakeUpstream)]);
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &resolutions, at(5));
+        assert_eq!(result.updated, vec!["lint"]);
+        assert_eq!(find(&scripts, "lib-lint").command, "cargo clippy");
+    }
+
+    #[test]
+    fn changes_on_both_sides_are_conflicts_until_resolved() {
+        let mut local = imported();
+        find_mut(&mut local, "lib-backup").tags = vec!["nightly".into()];
+        let mut export = library();
+        export[2].command = "./backup.sh --incremental".into();
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(status_of(&plan, "lib-backup"), SyncStatus::Conflict);
+        let entry = plan.entries.iter().find(|e| e.source_id == "lib-backup").unwrap();
+        let fields: Vec<&str> = entry.diff.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["command", "tags"]);
+        assert_eq!(entry.diff[1].local, serde_json::json!(["nightly"]));
+        assert_eq!(entry.diff[1].upstream, Value::Null);
+
+        // No resolution: nothing changes
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.unresolved, vec!["backup"]);
+        assert_eq!(json(&scripts), json(&local));
+
+        // Taking upstream drops the local edit
+        let take = HashMap::from([("lib-backup".to_string(), SyncResolution::TakeUpstream)]);
+        let mut scripts = local.clone();
+        apply_plan(&mut scripts, &plan, &take, at(5));
+        let backup = find(&scripts, "lib-backup");
+        assert_eq!(backup.command, "./backup.sh --incremental");
+        assert!(backup.tags.is_empty());
+
+        // Keeping local rebases it on the new upstream version
+        let keep = HashMap::from([("lib-backup".to_string(), SyncResolution::KeepLocal)]);
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &keep, at(5));
+        assert_eq!(result.kept, vec!["backup"]);
+        assert_eq!(result.changed_ids, vec!["lib-backup"]);
+        let backup = find(&scripts, "lib-backup");
+        assert_eq!(backup.command, "./backup.sh");
+        assert_eq!(backup.origin.as_ref().unwrap().last_synced_hash, content_hash(&export[2]));
+        assert_eq!(status_of(&plan_sync(&scripts, &export), "lib-backup"), SyncStatus::LocallyModified);
+    }
+
+    #[test]
+    fn the_same_change_on_both_sides_is_up_to_date() {
+        let mut local = imported();
+        find_mut(&mut local, "lib-lint").description = Some("Lints everything".into());
+        let mut export = library();
+        export[1].description = Some("Lints everything".into());
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(status_of(&plan, "lib-lint"), SyncStatus::UpToDate);
+
+        // The recorded upstream version moves forward
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.changed_ids, vec!["lib-lint"]);
+        assert!(result.kept.is_empty() && result.updated.is_empty());
+        assert_eq!(
+            find(&scripts, "lib-lint").origin.as_ref().unwrap().last_synced_hash,
+            content_hash(&export[1])
+        );
+    }
+
+    #[test]
+    fn new_upstream_scripts_are_offered_as_additions() {
+        let local = imported();
+        let mut export = library();
+        let mut release = GlobalScript::new("release".into(), "./release.sh".into(), None);
+        release.id = "lib-release".into();
+        release.order = 0;
+        export.push(release);
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(status_of(&plan, "lib-release"), SyncStatus::Added);
+
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.added, vec!["release"]);
+        let added = find(&scripts, "lib-release");
+        assert_eq!(added.order, local.iter().map(|s| s.order).max().unwrap() + 1);
+        assert_eq!(added.created_at, at(5));
+        let origin = added.origin.as_ref().unwrap();
+        assert_eq!(origin.source_id, "lib-release");
+        assert_eq!(origin.imported_at, at(5));
+
+        // Declined additions are skipped
+        let decline = HashMap::from([("lib-release".to_string(), SyncResolution::KeepLocal)]);
+        let mut scripts = local.clone();
+        let result = apply_plan(&mut scripts, &plan, &decline, at(5));
+        assert_eq!(result.kept, vec!["release"]);
+        assert_eq!(scripts.len(), local.len());
+    }
+
+    #[test]
+    fn local_only_scripts_are_never_touched() {
+        let local = imported();
+        let mut export = library();
+        // An upstream script that happens to share the id of one of ours
+        let mut clash = GlobalScript::new("theirs".into(), "rm -rf build".into(), None);
+        clash.id = "local-mine".into();
+        export.push(clash);
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(plan.skipped, vec!["theirs"]);
+        assert!(plan.entries.iter().all(|e| e.source_id != "local-mine"));
+
+        let mut scripts = local.clone();
+        apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(json(find(&scripts, "local-mine")), json(find(&local, "local-mine")));
+
+        // Tracked scripts missing from the export are left alone too
+        let plan = plan_sync(&local, &library()[..1]);
+        assert_eq!(plan.entries.len(), 1);
+        let mut scripts = local.clone();
+        apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(json(&scripts), json(&local));
+    }
+
+    #[test]
+    fn edits_made_after_planning_are_not_overwritten() {
+        let local = imported();
+        let mut export = library();
+        export[0].command = "./deploy.sh --all".into();
+        let plan = plan_sync(&local, &export);
+
+        let mut scripts = local.clone();
+        find_mut(&mut scripts, "lib-deploy").command = "./deploy.sh --dry-run".into();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.stale, vec!["deploy"]);
+        assert_eq!(find(&scripts, "lib-deploy").command, "./deploy.sh --dry-run");
+
+        // Same for deleted scripts
+        let mut scripts: Vec<GlobalScript> = local.into_iter().filter(|s| s.id != "lib-deploy").collect();
+        let result = apply_plan(&mut scripts, &plan, &HashMap::new(), at(5));
+        assert_eq!(result.stale, vec!["deploy"]);
+        assert!(result.changed_ids.is_empty());
+    }
+
+    #[test]
+    fn renamed_local_copies_are_still_matched_by_origin() {
+        let mut local = imported();
+        find_mut(&mut local, "lib-deploy").name = "ship it".into();
+        let mut export = library();
+        export[0].tags.push("prod".into());
+
+        let plan = plan_sync(&local, &export);
+        assert_eq!(status_of(&plan, "lib-deploy"), SyncStatus::Conflict);
+        assert_eq!(plan.entries[0].name, "ship it");
+        assert_eq!(plan.entries[0].local_id.as_deref(), Some("lib-deploy"));
+    }
+
+    #[test]
+    fn plans_survive_a_round_trip_through_json() {
+        let mut export = library();
+        export[0].command = "./deploy.sh --all".into();
+        let plan = plan_sync(&imported(), &export);
+        let json = serde_json::to_string(&plan).unwrap();
+        assert!(json.contains("\"upstreamUpdated\""));
+        let back: SyncPlan = serde_json::from_str(&json).unwrap();
+
+        let mut scripts = imported();
+        let result = apply_plan(&mut scripts, &back, &HashMap::new(), at(5));
+        assert_eq!(result.updated, vec!["deploy"]);
+    }
+}
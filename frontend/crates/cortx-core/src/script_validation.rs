@@ -0,0 +1,269 @@
+//! Pre-save checks for global scripts.
+//!
+//! Everything here is a warning: a script may legitimately be created before
+//! its tool is installed or its file is written, so front ends show the issues
+//! and save anyway.
+
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::models::CreateGlobalScriptInput;
+
+/// Placeholder substituted with `script_path` when the script runs
+pub const SCRIPT_FILE_PLACEHOLDER: &str = "{{SCRIPT_FILE}}";
+
+/// Shell builtins that are valid leading tokens without being on PATH
+/// (scripts run through `sh -c` / `cmd /C`).
+const SHELL_BUILTINS: &[&str] = &[
+    ".", "cd", "echo", "eval", "exec", "exit", "export", "set", "source", "test", "true", "false",
+    "call", "copy", "del", "dir", "start", "type",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationIssueKind {
+    /// The command's program is neither on PATH nor an existing file
+    CommandNotFound,
+    ScriptPathMissing,
+    WorkingDirMissing,
+    /// `script_path` is set but the command never references it
+    ScriptFileNotReferenced,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub kind: ValidationIssueKind,
+    /// Input field the issue is about ("command", "scriptPath", "workingDir")
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn new(kind: ValidationIssueKind, field: &'static str, message: String) -> Self {
+        Self { kind, field, message }
+    }
+}
+
+/// Check a script about to be created or saved.
+pub fn validate_global_script(input: &CreateGlobalScriptInput) -> Vec<ValidationIssue> {
+    validate_script_fields(&input.command, input.script_path.as_deref(), input.working_dir.as_deref())
+}
+
+/// [`validate_global_script`] over the individual fields, for callers that
+/// hold a `GlobalScript` or a form rather than an input struct.
+pub fn validate_script_fields(
+    command: &str,
+    script_path: Option<&str>,
+    working_dir: Option<&str>,
+) -> Vec<ValidationIssue> {
+    let path_var = std::env::var_os("PATH");
+    validate_with_path(command, script_path, working_dir, path_var.as_deref())
+}
+
+fn validate_with_path(
+    command: &str,
+    script_path: Option<&str>,
+    working_dir: Option<&str>,
+    path_var: Option<&OsStr>,
+) -> Vec<ValidationIssue> {
+    let script_path = script_path.map(str::trim).filter(|p| !p.is_empty());
+    let working_dir = working_dir.map(str::trim).filter(|p| !p.is_empty());
+    let mut issues = Vec::new();
+
+    if let Some(program) = leading_program(command) {
+        // `{{SCRIPT_FILE}} --flag` runs the script file itself: checked below
+        if program != SCRIPT_FILE_PLACEHOLDER && !program_resolves(&program, working_dir, path_var) {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::CommandNotFound,
+                "command",
+                format!("'{}' was not found on PATH or as a file", program),
+            ));
+        }
+    }
+
+    if let Some(path) = script_path {
+        if !expand_home(path).is_file() {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::ScriptPathMissing,
+                "scriptPath",
+                format!("Script file '{}' does not exist", path),
+            ));
+        }
+        if !command.contains(SCRIPT_FILE_PLACEHOLDER) {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::ScriptFileNotReferenced,
+                "command",
+                format!("A script file is set but the command doesn't use {}", SCRIPT_FILE_PLACEHOLDER),
+            ));
+        }
+    }
+
+    if let Some(dir) = working_dir {
+        if !expand_home(dir).is_dir() {
+            issues.push(ValidationIssue::new(
+                ValidationIssueKind::WorkingDirMissing,
+                "workingDir",
+                format!("Working directory '{}' does not exist", dir),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// The program a shell command line starts with: leading `VAR=value`
+/// assignments are skipped and surrounding quotes removed.
+fn leading_program(command: &str) -> Option<String> {
+    let mut rest = command.trim_start();
+    loop {
+        let (token, after) = match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let inner = &rest[1..];
+                match inner.find(quote) {
+                    Some(end) => (&inner[..end], &inner[end + 1..]),
+                    None => (inner, ""),
+                }
+            }
+            _ => match rest.find(char::is_whitespace) {
+                Some(end) => (&rest[..end], &rest[end..]),
+                None => (rest, ""),
+            },
+        };
+        let is_assignment = token
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'));
+        if !is_assignment {
+            return (!token.is_empty()).then(|| token.to_string());
+        }
+        rest = after.trim_start();
+    }
+}
+
+/// Whether `program` is a shell builtin, an existing file, or on `path_var`.
+pub(crate) fn program_resolves(program: &str, working_dir: Option<&str>, path_var: Option<&OsStr>) -> bool {
+    if SHELL_BUILTINS.iter().any(|b| b.eq_ignore_ascii_case(program)) {
+        return true;
+    }
+
+    // A path: relative ones are resolved against the working dir, and can't
+    // be checked without one (it's picked at run time)
+    if program.contains('/') || program.contains('\\') || program.starts_with('~') {
+        let path = expand_home(program);
+        if path.is_absolute() {
+            return with_extensions(&path).iter().any(|p| p.is_file());
+        }
+        return match working_dir {
+            Some(dir) => with_extensions(&expand_home(dir).join(&path)).iter().any(|p| p.is_file()),
+            None => true,
+        };
+    }
+
+    path_var.is_some_and(|paths| {
+        std::env::split_paths(paths)
+            .any(|dir| with_extensions(&dir.join(program)).iter().any(|p| p.is_file()))
+    })
+}
+
+/// `path` plus, on Windows, `path` with each PATHEXT extension when it has none.
+fn with_extensions(path: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![path.to_path_buf()];
+    if cfg!(target_os = "windows") && path.extension().is_none() {
+        let exts = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+        candidates.extend(
+            exts.split(';')
+                .filter(|e| !e.is_empty())
+                .map(|ext| path.with_extension(ext.trim_start_matches('.'))),
+        );
+    }
+    candidates
+}
+
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        if let Some(dirs) = directories::BaseDirs::new() {
+            return dirs.home_dir().join(rest);
+        }
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn kinds(issues: &[ValidationIssue]) -> Vec<ValidationIssueKind> {
+        issues.iter().map(|i| i.kind).collect()
+    }
+
+    #[test]
+    fn parses_the_leading_program() {
+        assert_eq!(leading_program("  npm run dev").as_deref(), Some("npm"));
+        assert_eq!(leading_program("RUST_LOG=debug PORT=1 cargo run").as_deref(), Some("cargo"));
+        assert_eq!(leading_program("\"/opt/my tools/run\" --x").as_deref(), Some("/opt/my tools/run"));
+        assert_eq!(leading_program("{{SCRIPT_FILE}} a").as_deref(), Some("{{SCRIPT_FILE}}"));
+        assert_eq!(leading_program("   "), None);
+    }
+
+    #[test]
+    fn command_resolves_on_path_or_as_file() {
+        let bin = tempfile::tempdir().unwrap();
+        fs::write(bin.path().join("mytool"), "").unwrap();
+        let path_var = bin.path().as_os_str();
+
+        let issues = validate_with_path("mytool --flag", None, None, Some(path_var));
+        assert!(issues.is_empty(), "{:?}", issues);
+        assert!(validate_with_path("cd /tmp && ls", None, None, Some(path_var)).is_empty());
+
+        let issues = validate_with_path("nope-not-a-tool arg", None, None, Some(path_var));
+        assert_eq!(kinds(&issues), vec![ValidationIssueKind::CommandNotFound]);
+        assert_eq!(issues[0].field, "command");
+        assert!(issues[0].message.contains("nope-not-a-tool"));
+
+        // Paths are checked as files, relative ones against the working dir
+        let dir = bin.path().to_str().unwrap();
+        let abs = bin.path().join("mytool");
+        let abs_command = format!("{} --flag", abs.display());
+        assert!(validate_with_path(&abs_command, None, None, None).is_empty());
+        assert!(validate_with_path("./mytool", None, Some(dir), None).is_empty());
+        assert_eq!(
+            kinds(&validate_with_path("./missing", None, Some(dir), None)),
+            vec![ValidationIssueKind::CommandNotFound]
+        );
+        // No working dir yet: relative paths can't be judged
+        assert!(validate_with_path("./missing", None, None, None).is_empty());
+    }
+
+    #[test]
+    fn script_path_must_exist_and_be_referenced() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("seed.py");
+        fs::write(&file, "print()").unwrap();
+        let file = file.to_str().unwrap();
+
+        assert!(validate_with_path("{{SCRIPT_FILE}}", Some(file), None, None).is_empty());
+
+        let missing = dir.path().join("gone.py");
+        let issues = validate_with_path("{{SCRIPT_FILE}}", Some(missing.to_str().unwrap()), None, None);
+        assert_eq!(kinds(&issues), vec![ValidationIssueKind::ScriptPathMissing]);
+        assert_eq!(issues[0].field, "scriptPath");
+
+        let issues = validate_with_path("cd .", Some(file), None, None);
+        assert_eq!(kinds(&issues), vec![ValidationIssueKind::ScriptFileNotReferenced]);
+    }
+
+    #[test]
+    fn working_dir_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().to_str().unwrap();
+        assert!(validate_with_path("echo hi", None, Some(existing), None).is_empty());
+        assert!(validate_with_path("echo hi", None, Some("  "), None).is_empty());
+
+        let missing = dir.path().join("nope");
+        let issues = validate_with_path("echo hi", None, Some(missing.to_str().unwrap()), None);
+        assert_eq!(kinds(&issues), vec![ValidationIssueKind::WorkingDirMissing]);
+        assert_eq!(issues[0].field, "workingDir");
+    }
+}
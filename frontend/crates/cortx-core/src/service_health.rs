@@ -0,0 +1,253 @@
+//! Health checks for services.
+//!
+//! A service with a `health_check` gets a checker while it runs (see
+//! `ProcessManager::start_service`). Every interval it probes the service,
+//! by a TCP connect to its port or an HTTP GET. After `failure_threshold`
+//! failed probes in a row the service is reported `ServiceStatus::Unhealthy`,
+//! and `Running` again on the next successful one.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use crate::models::{HealthProbe, Service, ServiceHealth, ServiceStatus};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(2000);
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// What one check does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    Tcp(u16),
+    Http { url: String, expected_status: Option<u16> },
+}
+
+/// Health-check config for one running service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub probe: Probe,
+    pub interval: Duration,
+    pub timeout: Duration,
+    pub failure_threshold: u32,
+}
+
+impl HealthCheck {
+    /// `None` when the service has no health check, or a TCP check without
+    /// a port to connect to.
+    pub fn for_service(service: &Service) -> Option<Self> {
+        let config = service.health_check.as_ref()?;
+        let probe = match &config.probe {
+            HealthProbe::Tcp { port } => Probe::Tcp(port.or(service.port)?),
+            HealthProbe::Http { url, expected_status } => Probe::Http {
+                url: url.clone(),
+                expected_status: *expected_status,
+            },
+        };
+        Some(Self {
+            probe,
+            interval: config.interval_secs.filter(|&secs| secs > 0).map_or(DEFAULT_INTERVAL, Duration::from_secs),
+            timeout: config.timeout_ms.filter(|&ms| ms > 0).map_or(DEFAULT_TIMEOUT, Duration::from_millis),
+            failure_threshold: config.failure_threshold.unwrap_or(DEFAULT_FAILURE_THRESHOLD).max(1),
+        })
+    }
+
+    /// Probe once. Returns how long a successful check took.
+    pub fn check(&self) -> Result<Duration, String> {
+        let started = Instant::now();
+        match &self.probe {
+            Probe::Tcp(port) => {
+                let addr = SocketAddr::from(([127, 0, 0, 1], *port));
+                TcpStream::connect_timeout(&addr, self.timeout).map_err(|e| format!("Port {}: {}", port, e))?;
+            }
+            Probe::Http { url, expected_status } => {
+                let status = http_status(url, self.timeout)?;
+                let healthy = match expected_status {
+                    Some(expected) => status == *expected,
+                    None => (200..300).contains(&status),
+                };
+                if !healthy {
+                    return Err(format!("{} answered {}", url, status));
+                }
+            }
+        }
+        Ok(started.elapsed())
+    }
+
+    /// Fold one check into `health`. Returns the status to report when the
+    /// service just turned unhealthy, or recovered.
+    pub fn record(
+        &self,
+        health: &mut ServiceHealth,
+        outcome: Result<Duration, String>,
+        at: DateTime<Utc>,
+    ) -> Option<ServiceStatus> {
+        health.last_check_at = Some(at);
+        match outcome {
+            Ok(latency) => {
+                health.latency_ms = Some(latency.as_millis() as u64);
+                health.consecutive_failures = 0;
+                health.last_error = None;
+                let recovered = !health.healthy;
+                health.healthy = true;
+                recovered.then_some(ServiceStatus::Running)
+            }
+            Err(error) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(error);
+                let failed = health.healthy && health.consecutive_failures >= self.failure_threshold;
+                if failed {
+                    health.healthy = false;
+                }
+                failed.then_some(ServiceStatus::Unhealthy)
+            }
+        }
+    }
+}
+
+/// Status code of a `GET url`. Only plain `http://` URLs are supported.
+fn http_status(url: &str, timeout: Duration) -> Result<u16, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("Only http:// URLs can be checked: {}", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let host = authority.rsplit_once(':').map_or(authority, |(host, _)| host);
+    let addr_text = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+    let addr = addr_text
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("Can't resolve {}", authority))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).map_err(|e| format!("{}: {}", url, e))?;
+    let _ = stream.set_read_timeout(Some(timeout));
+    let _ = stream.set_write_timeout(Some(timeout));
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: cortx-health\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    stream.write_all(request.as_bytes()).map_err(|e| format!("{}: {}", url, e))?;
+
+    // The status line is all we need
+    let mut head = Vec::new();
+    let mut buf = [0u8; 256];
+    while !head.contains(&b'\n') && head.len() < 1024 {
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+            Err(e) => return Err(format!("{}: {}", url, e)),
+        }
+    }
+    parse_status_line(&String::from_utf8_lossy(&head)).ok_or_else(|| format!("{}: not an HTTP response", url))
+}
+
+/// `200` from "HTTP/1.1 200 OK"
+fn parse_status_line(response: &str) -> Option<u16> {
+    let line = response.lines().next()?;
+    let mut parts = line.split_whitespace();
+    if !parts.next()?.starts_with("HTTP/") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HealthCheckConfig;
+    use std::net::TcpListener;
+
+    fn check(probe: Probe) -> HealthCheck {
+        HealthCheck {
+            probe,
+            interval: Duration::from_millis(100),
+            timeout: Duration::from_millis(500),
+            failure_threshold: 2,
+        }
+    }
+
+    /// Serve `response` to every connection of a local listener
+    fn http_server(response: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn tcp_checks_follow_the_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(check(Probe::Tcp(port)).check().is_ok());
+
+        drop(listener);
+        assert!(check(Probe::Tcp(port)).check().is_err());
+    }
+
+    #[test]
+    fn http_checks_compare_the_status() {
+        let ok = http_server("HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n");
+        let down = http_server("HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+        let http = |port: u16, expected_status| {
+            check(Probe::Http { url: format!("http://127.0.0.1:{}/health", port), expected_status })
+        };
+
+        assert!(http(ok, None).check().is_ok());
+        assert!(http(ok, Some(200)).check().is_err());
+        assert!(http(down, Some(503)).check().is_ok());
+        assert_eq!(http(down, None).check().unwrap_err(), format!("http://127.0.0.1:{}/health answered 503", down));
+        assert!(check(Probe::Http { url: "https://example.com".into(), expected_status: None }).check().is_err());
+    }
+
+    #[test]
+    fn turns_unhealthy_after_the_threshold_and_recovers() {
+        let check = check(Probe::Tcp(1));
+        let mut health = ServiceHealth::new("svc".into(), 42);
+        let now = Utc::now();
+        let fail = || Err("refused".to_string());
+
+        assert_eq!(check.record(&mut health, fail(), now), None);
+        assert!(health.healthy);
+        assert_eq!(check.record(&mut health, fail(), now), Some(ServiceStatus::Unhealthy));
+        assert_eq!(check.record(&mut health, fail(), now), None);
+        assert_eq!((health.healthy, health.consecutive_failures), (false, 3));
+        assert_eq!(health.last_error.as_deref(), Some("refused"));
+
+        let ok = Ok(Duration::from_millis(12));
+        assert_eq!(check.record(&mut health, ok.clone(), now), Some(ServiceStatus::Running));
+        assert_eq!(check.record(&mut health, ok, now), None);
+        assert_eq!((health.healthy, health.latency_ms, health.last_error), (true, Some(12), None));
+        assert_eq!(health.last_check_at, Some(now));
+    }
+
+    #[test]
+    fn tcp_checks_default_to_the_service_port() {
+        let mut service = Service::new("api".into(), ".".into(), "cargo run".into());
+        assert_eq!(HealthCheck::for_service(&service), None);
+
+        service.health_check = Some(HealthCheckConfig {
+            probe: HealthProbe::Tcp { port: None },
+            interval_secs: None,
+            timeout_ms: Some(0),
+            failure_threshold: Some(0),
+        });
+        // No port anywhere: nothing to check
+        assert_eq!(HealthCheck::for_service(&service), None);
+
+        service.port = Some(8080);
+        let check = HealthCheck::for_service(&service).unwrap();
+        assert_eq!(check.probe, Probe::Tcp(8080));
+        assert_eq!((check.interval, check.timeout, check.failure_threshold), (DEFAULT_INTERVAL, DEFAULT_TIMEOUT, 1));
+    }
+}
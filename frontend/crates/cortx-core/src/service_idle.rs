@@ -0,0 +1,80 @@
+//! Idle detection for services.
+//!
+//! A service with `idle_timeout_secs` gets a monitor while it runs (see
+//! `ProcessManager::start_service`). Once neither stdout nor stderr has
+//! produced a line for the timeout, it writes a warning to the service's
+//! output and, with `IdleAction::Stop`, stops the service. It fires once per
+//! silent stretch; the next line of output re-arms it.
+
+use std::time::{Duration, Instant};
+
+use crate::models::{IdleAction, Service};
+
+/// Shortest / longest wait between two idle checks
+const MIN_POLL: Duration = Duration::from_millis(50);
+const MAX_POLL: Duration = Duration::from_secs(1);
+
+/// Idle config for one running service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleTimeout {
+    pub timeout: Duration,
+    pub action: IdleAction,
+}
+
+impl IdleTimeout {
+    /// `None` when the service has no idle timeout (unset or 0).
+    pub fn for_service(service: &Service) -> Option<Self> {
+        let secs = service.idle_timeout_secs.filter(|&secs| secs > 0)?;
+        Some(Self {
+            timeout: Duration::from_secs(secs),
+            action: service.idle_action,
+        })
+    }
+
+    /// How long the monitor sleeps between checks
+    pub fn poll_interval(&self) -> Duration {
+        (self.timeout / 4).clamp(MIN_POLL, MAX_POLL)
+    }
+}
+
+/// Whether the monitor should fire at `now`: nothing was printed for at
+/// least `timeout` since `last_output`, and it hasn't already fired for that
+/// same silence (`fired_for` is the `last_output` it last fired at).
+pub fn is_idle(last_output: Instant, fired_for: Option<Instant>, timeout: Duration, now: Instant) -> bool {
+    fired_for != Some(last_output) && now.saturating_duration_since(last_output) >= timeout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fires_once_per_silent_stretch() {
+        let start = Instant::now();
+        let timeout = Duration::from_secs(60);
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(!is_idle(start, None, timeout, at(59)));
+        assert!(is_idle(start, None, timeout, at(60)));
+        // Already warned about this silence
+        assert!(!is_idle(start, Some(start), timeout, at(600)));
+        // New output re-arms it
+        assert!(!is_idle(at(100), Some(start), timeout, at(120)));
+        assert!(is_idle(at(100), Some(start), timeout, at(160)));
+    }
+
+    #[test]
+    fn only_configured_services_are_monitored() {
+        let mut service = Service::new("web".into(), ".".into(), "npm run dev".into());
+        assert_eq!(IdleTimeout::for_service(&service), None);
+        service.idle_timeout_secs = Some(0);
+        assert_eq!(IdleTimeout::for_service(&service), None);
+
+        service.idle_timeout_secs = Some(3600);
+        service.idle_action = IdleAction::Stop;
+        let idle = IdleTimeout::for_service(&service).unwrap();
+        assert_eq!(idle.timeout, Duration::from_secs(3600));
+        assert_eq!(idle.action, IdleAction::Stop);
+        assert_eq!(idle.poll_interval(), MAX_POLL);
+    }
+}
@@ -0,0 +1,393 @@
+//! Service output persisted to disk.
+//!
+//! With `LoggingConfig::persist_service_logs` on, every line a service prints
+//! is also written to `<app_dir>/logs/<service_id>/`, one file per session
+//! named after its start time (`20240501-102203-123.log`). A session's file
+//! is rotated once the next line would take it past `max_file_bytes`
+//! (`20240501-102203-123.2.log`, `.3.log`, ...), and a service keeps at most
+//! `max_files` files, oldest deleted first. Unlike the runtime log, which
+//! every start appends to, these survive as separate sessions the GUI can
+//! page through.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::{CoreError, CoreResult};
+use crate::models::LoggingConfig;
+
+/// A persisted log file of a service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceLogFile {
+    pub name: String,
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// Lines `offset..next_offset` of a log file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceLogPage {
+    pub lines: Vec<String>,
+    pub offset: usize,
+    pub next_offset: usize,
+    pub has_more: bool,
+}
+
+/// Ids and file names become path components: no separators or `..`.
+fn check_component(kind: &str, value: &str) -> CoreResult<()> {
+    let valid = !value.is_empty()
+        && value != "."
+        && value != ".."
+        && !value.contains(['/', '\\', ':'])
+        && Path::new(value).file_name().is_some_and(|n| n == value);
+    if valid {
+        Ok(())
+    } else {
+        Err(CoreError::InvalidInput(format!("Invalid {}: {}", kind, value)))
+    }
+}
+
+/// `(session, part)` of a log file name, for chronological order. The
+/// session is a fixed-width timestamp, so it sorts as text.
+fn file_order(name: &str) -> (String, u32) {
+    let stem = name.strip_suffix(".log").unwrap_or(name);
+    match stem.rsplit_once('.') {
+        Some((session, part)) => match part.parse() {
+            Ok(part) => (session.to_string(), part),
+            Err(_) => (stem.to_string(), 1),
+        },
+        None => (stem.to_string(), 1),
+    }
+}
+
+fn part_name(session: &str, part: u32) -> String {
+    if part <= 1 {
+        format!("{}.log", session)
+    } else {
+        format!("{}.{}.log", session, part)
+    }
+}
+
+/// Log files in `dir`, oldest first.
+fn sorted_log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    files.sort_by_cached_key(|p| file_order(&p.file_name().unwrap_or_default().to_string_lossy()));
+    files
+}
+
+/// Where persisted service logs live, and the current rotation settings.
+pub struct ServiceLogStore {
+    dir: PathBuf,
+    config: Mutex<LoggingConfig>,
+}
+
+impl ServiceLogStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, config: Mutex::new(LoggingConfig::default()) }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Applies to sessions started from now on (from `AppSettings::logging`).
+    pub fn set_config(&self, config: LoggingConfig) {
+        *self.config.lock() = config;
+    }
+
+    pub fn config(&self) -> LoggingConfig {
+        self.config.lock().clone()
+    }
+
+    fn service_dir(&self, service_id: &str) -> CoreResult<PathBuf> {
+        check_component("service id", service_id)?;
+        Ok(self.dir.join(service_id))
+    }
+
+    /// Start a new session file for `service_id`; `None` while persisting
+    /// is turned off.
+    pub fn open_session(&self, service_id: &str) -> CoreResult<Option<Arc<ServiceLogWriter>>> {
+        let config = self.config();
+        if !config.persist_service_logs {
+            return Ok(None);
+        }
+        let dir = self.service_dir(service_id)?;
+        fs::create_dir_all(&dir)?;
+        let session = Utc::now().format("%Y%m%d-%H%M%S-%3f").to_string();
+        Ok(Some(Arc::new(ServiceLogWriter::open(dir, session, config)?)))
+    }
+
+    /// A service's log files, newest first.
+    pub fn list_files(&self, service_id: &str) -> CoreResult<Vec<ServiceLogFile>> {
+        let dir = self.service_dir(service_id)?;
+        let files = sorted_log_files(&dir)
+            .into_iter()
+            .rev()
+            .filter_map(|path| {
+                let meta = fs::metadata(&path).ok()?;
+                Some(ServiceLogFile {
+                    name: path.file_name()?.to_string_lossy().into_owned(),
+                    size: meta.len(),
+                    modified: meta.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+                })
+            })
+            .collect();
+        Ok(files)
+    }
+
+    /// Up to `limit` lines of `file`, starting at line `offset`.
+    pub fn read(&self, service_id: &str, file: &str, offset: usize, limit: usize) -> CoreResult<ServiceLogPage> {
+        check_component("log file", file)?;
+        let path = self.service_dir(service_id)?.join(file);
+        if !path.is_file() {
+            return Err(CoreError::NotFound(format!("Log file {} of service {}", file, service_id)));
+        }
+        let reader = BufReader::new(File::open(&path)?);
+        let mut lines = Vec::with_capacity(limit.min(1024));
+        let mut has_more = false;
+        for line in reader.lines().skip(offset) {
+            let line = line?;
+            if lines.len() == limit {
+                has_more = true;
+                break;
+            }
+            lines.push(line);
+        }
+        Ok(ServiceLogPage { next_offset: offset + lines.len(), offset, lines, has_more })
+    }
+}
+
+struct WriterState {
+    part: u32,
+    /// `None` once closed
+    file: Option<LineWriter<File>>,
+    written: u64,
+}
+
+/// One session's log, shared by the stdout and stderr readers of a service.
+pub struct ServiceLogWriter {
+    dir: PathBuf,
+    session: String,
+    config: LoggingConfig,
+    state: Mutex<WriterState>,
+}
+
+impl ServiceLogWriter {
+    fn open(dir: PathBuf, session: String, config: LoggingConfig) -> std::io::Result<Self> {
+        let file = Self::create_part(&dir, &session, 1)?;
+        let writer = Self {
+            dir,
+            session,
+            config,
+            state: Mutex::new(WriterState { part: 1, file: Some(file), written: 0 }),
+        };
+        writer.prune();
+        Ok(writer)
+    }
+
+    fn create_part(dir: &Path, session: &str, part: u32) -> std::io::Result<LineWriter<File>> {
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(part_name(session, part)))?;
+        Ok(LineWriter::new(file))
+    }
+
+    /// File currently written to
+    pub fn current_path(&self) -> PathBuf {
+        self.dir.join(part_name(&self.session, self.state.lock().part))
+    }
+
+    /// Append one line, rotating first if it would overflow the current
+    /// file. A line longer than the limit gets a file of its own. No-op once
+    /// closed.
+    pub fn write_line(&self, line: &str) -> std::io::Result<()> {
+        let mut state = self.state.lock();
+        if state.file.is_none() {
+            return Ok(());
+        }
+        let len = line.len() as u64 + 1;
+        if state.written > 0 && state.written + len > self.config.max_file_bytes {
+            if let Some(mut old) = state.file.take() {
+                old.flush()?;
+            }
+            state.part += 1;
+            state.file = Some(Self::create_part(&self.dir, &self.session, state.part)?);
+            state.written = 0;
+            self.prune();
+        }
+        if let Some(file) = state.file.as_mut() {
+            writeln!(file, "{}", line)?;
+            state.written += len;
+        }
+        Ok(())
+    }
+
+    /// Flush and close the file; later lines are dropped.
+    pub fn close(&self) {
+        if let Some(mut file) = self.state.lock().file.take() {
+            let _ = file.flush();
+        }
+    }
+
+    /// Delete the service's oldest files beyond `max_files`. The newest file
+    /// is the one being written, so it's never removed.
+    fn prune(&self) {
+        let files = sorted_log_files(&self.dir);
+        let excess = files.len().saturating_sub(self.config.max_files.max(1));
+        for path in &files[..excess] {
+            if let Err(e) = fs::remove_file(path) {
+                log::warn!("Failed to remove old service log {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn store(dir: &Path, max_file_bytes: u64, max_files: usize) -> ServiceLogStore {
+        let store = ServiceLogStore::new(dir.join("logs"));
+        store.set_config(LoggingConfig { persist_service_logs: true, max_file_bytes, max_files });
+        store
+    }
+
+    fn sizes(store: &ServiceLogStore, id: &str) -> Vec<u64> {
+        store.list_files(id).unwrap().iter().rev().map(|f| f.size).collect()
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ServiceLogStore::new(dir.path().join("logs"));
+        assert!(store.open_session("svc").unwrap().is_none());
+        assert!(store.list_files("svc").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rotates_at_the_size_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 20, 10);
+        let writer = store.open_session("svc").unwrap().unwrap();
+
+        // 9 chars + newline: two lines fill a file exactly, the third rotates
+        writer.write_line("aaaaaaaaa").unwrap();
+        writer.write_line("bbbbbbbbb").unwrap();
+        assert_eq!(sizes(&store, "svc"), vec![20]);
+        writer.write_line("ccccccccc").unwrap();
+        assert_eq!(sizes(&store, "svc"), vec![20, 10]);
+        assert!(writer.current_path().to_string_lossy().ends_with(".2.log"));
+
+        // Oversized lines get a file of their own
+        writer.write_line(&"x".repeat(30)).unwrap();
+        writer.write_line("d").unwrap();
+        assert_eq!(sizes(&store, "svc"), vec![20, 10, 31, 2]);
+    }
+
+    #[test]
+    fn keeps_at_most_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 10, 3);
+        let writer = store.open_session("svc").unwrap().unwrap();
+        for i in 0..6 {
+            writer.write_line(&format!("line {:03}", i)).unwrap();
+        }
+        let files = store.list_files("svc").unwrap();
+        assert_eq!(files.len(), 3);
+        // Newest first; the oldest three were deleted
+        let first_lines: Vec<String> = files
+            .iter()
+            .map(|f| store.read("svc", &f.name, 0, 1).unwrap().lines[0].clone())
+            .collect();
+        assert_eq!(first_lines, vec!["line 005", "line 004", "line 003"]);
+    }
+
+    #[test]
+    fn concurrent_stdout_and_stderr_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 4096, 1000);
+        let writer = store.open_session("svc").unwrap().unwrap();
+
+        let threads: Vec<_> = ["out", "err"]
+            .into_iter()
+            .map(|stream| {
+                let writer = writer.clone();
+                thread::spawn(move || {
+                    for i in 0..2000 {
+                        writer.write_line(&format!("{} {:05} {}", stream, i, "-".repeat(40))).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for t in threads {
+            t.join().unwrap();
+        }
+        writer.close();
+
+        let mut lines = Vec::new();
+        for file in store.list_files("svc").unwrap().iter().rev() {
+            assert!(file.size <= 4096);
+            lines.extend(store.read("svc", &file.name, 0, usize::MAX).unwrap().lines);
+        }
+        assert_eq!(lines.len(), 4000);
+        for stream in ["out", "err"] {
+            // Whole lines, each stream in order
+            let seq: Vec<usize> = lines
+                .iter()
+                .filter(|l| l.starts_with(stream))
+                .map(|l| {
+                    let parts: Vec<&str> = l.split(' ').collect();
+                    assert_eq!(parts.len(), 3, "torn line {:?}", l);
+                    assert_eq!(parts[2].len(), 40);
+                    parts[1].parse().unwrap()
+                })
+                .collect();
+            assert_eq!(seq, (0..2000).collect::<Vec<_>>());
+        }
+        // Closed: nothing more is written
+        writer.write_line("late").unwrap();
+        let total: u64 = sizes(&store, "svc").iter().sum();
+        assert_eq!(total, 4000 * 51);
+    }
+
+    #[test]
+    fn reads_pages_and_rejects_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path(), 1 << 20, 10);
+        let writer = store.open_session("svc").unwrap().unwrap();
+        for i in 0..5 {
+            writer.write_line(&format!("line {}", i)).unwrap();
+        }
+        let name = store.list_files("svc").unwrap()[0].name.clone();
+
+        let page = store.read("svc", &name, 1, 2).unwrap();
+        assert_eq!(page.lines, vec!["line 1", "line 2"]);
+        assert_eq!((page.next_offset, page.has_more), (3, true));
+        let page = store.read("svc", &name, 3, 10).unwrap();
+        assert_eq!(page.lines, vec!["line 3", "line 4"]);
+        assert!(!page.has_more);
+
+        assert!(matches!(store.read("svc", "../../secret", 0, 10), Err(CoreError::InvalidInput(_))));
+        assert!(matches!(store.read("..", &name, 0, 10), Err(CoreError::InvalidInput(_))));
+        assert!(matches!(store.read("svc", "missing.log", 0, 10), Err(CoreError::NotFound(_))));
+    }
+
+    #[test]
+    fn orders_rotated_parts_after_their_session() {
+        let mut names = vec!["20240501-102203-123.2.log", "20240501-102203-123.log", "20240430-090000-000.log"];
+        names.sort_by_key(|n| file_order(n));
+        assert_eq!(names, vec!["20240430-090000-000.log", "20240501-102203-123.log", "20240501-102203-123.2.log"]);
+    }
+}
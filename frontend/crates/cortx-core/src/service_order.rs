@@ -0,0 +1,92 @@
+//! The order in which start-all brings a project's services up.
+//!
+//! Services start after the services they `depends_on`, otherwise by their
+//! `order` (then name). Stop-all walks the same order backwards.
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::models::Service;
+
+/// Some services (by name) depend on each other in a loop
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Services depend on each other: {}", .0.join(", "))]
+pub struct DependencyCycle(pub Vec<String>);
+
+/// `services` in start order. Dependencies on ids that aren't among
+/// `services` are ignored.
+pub fn start_order(services: &[Service]) -> Result<Vec<&Service>, DependencyCycle> {
+    let ids: HashSet<&str> = services.iter().map(|s| s.id.as_str()).collect();
+    let mut pending: Vec<&Service> = services.iter().collect();
+    pending.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.name.cmp(&b.name)));
+
+    let mut started: HashSet<&str> = HashSet::new();
+    let mut ordered = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        // First pending service whose known dependencies are all started
+        let ready = pending.iter().position(|s| {
+            s.depends_on
+                .iter()
+                .all(|dep| !ids.contains(dep.as_str()) || started.contains(dep.as_str()))
+        });
+        let Some(index) = ready else {
+            return Err(DependencyCycle(pending.iter().map(|s| s.name.clone()).collect()));
+        };
+        let service = pending.remove(index);
+        started.insert(service.id.as_str());
+        ordered.push(service);
+    }
+    Ok(ordered)
+}
+
+/// `services` in stop order: dependents before their dependencies. With a
+/// dependency cycle, falls back to reverse `order`.
+pub fn stop_order(services: &[Service]) -> Vec<&Service> {
+    let mut ordered = start_order(services).unwrap_or_else(|_| {
+        let mut by_order: Vec<&Service> = services.iter().collect();
+        by_order.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.name.cmp(&b.name)));
+        by_order
+    });
+    ordered.reverse();
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(id: &str, order: u32, depends_on: &[&str]) -> Service {
+        let mut s = Service::new(id.to_string(), ".".to_string(), "run".to_string());
+        s.id = id.to_string();
+        s.order = order;
+        s.depends_on = depends_on.iter().map(|d| d.to_string()).collect();
+        s
+    }
+
+    fn ids(services: Vec<&Service>) -> Vec<&str> {
+        services.into_iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn dependencies_start_first() {
+        let services = vec![
+            service("api", 0, &["db", "cache"]),
+            service("web", 1, &["api"]),
+            service("db", 2, &[]),
+            service("cache", 3, &["gone"]),
+            service("docs", 4, &[]),
+        ];
+
+        assert_eq!(ids(start_order(&services).unwrap()), vec!["db", "cache", "api", "web", "docs"]);
+        assert_eq!(ids(stop_order(&services)), vec!["docs", "web", "api", "cache", "db"]);
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let services = vec![service("a", 0, &["b"]), service("b", 1, &["a"]), service("c", 2, &[])];
+
+        assert_eq!(start_order(&services).unwrap_err(), DependencyCycle(vec!["a".into(), "b".into()]));
+        assert_eq!(ids(stop_order(&services)), vec!["c", "b", "a"]);
+    }
+}
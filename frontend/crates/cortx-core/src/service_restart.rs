@@ -0,0 +1,370 @@
+//! Automatic restarts of crashed services.
+//!
+//! A service with a `restart_policy` other than `Never` is started again
+//! when it exits on its own (see `ProcessManager::start_service`): it is
+//! reported `ServiceStatus::Restarting`, and re-spawned with the same
+//! command, mode and arg preset after a backoff that doubles with each
+//! restart in a row. Past `max_retries` restarts in a row it is left
+//! stopped with `ServiceStatus::Error`. A run that stayed up for a while
+//! starts a fresh count. Stopping the service cancels any pending restart.
+//!
+//! A service that crashes right away would still be restarted every few
+//! seconds, so a [`CircuitBreaker`] counts failed runs in a rolling window:
+//! too many and the circuit opens (`ServiceStatus::CircuitOpen`), and the
+//! service stays down until started by hand or its circuit is reset. After
+//! a cool-down it's retried once (half-open): a retry that stays up closes
+//! the circuit, one that fails opens it again.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::models::{RestartCircuitConfig, RestartPolicy, Service};
+
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(1000);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A run that stayed up this long resets the count of restarts in a row
+const STABLE_RUN: Duration = Duration::from_secs(30);
+
+const DEFAULT_CIRCUIT_FAILURES: u32 = 5;
+const DEFAULT_CIRCUIT_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_CIRCUIT_COOL_DOWN: Duration = Duration::from_secs(600);
+
+/// A half-open retry that stays up this long closes the circuit
+const PROBE_RUN: Duration = Duration::from_secs(60);
+
+/// Restart config for one running service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoRestart {
+    pub policy: RestartPolicy,
+    /// Wait before the first restart in a row
+    pub backoff: Duration,
+    pub circuit: CircuitConfig,
+}
+
+/// `RestartCircuitConfig` with its defaults filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitConfig {
+    pub max_failures: u32,
+    pub window: Duration,
+    /// None: stay open until started by hand
+    pub cool_down: Option<Duration>,
+}
+
+impl CircuitConfig {
+    pub fn from_config(config: Option<&RestartCircuitConfig>) -> Self {
+        let config = config.copied().unwrap_or_default();
+        Self {
+            max_failures: config.max_failures.unwrap_or(DEFAULT_CIRCUIT_FAILURES).max(1),
+            window: config.window_secs.map_or(DEFAULT_CIRCUIT_WINDOW, Duration::from_secs),
+            cool_down: match config.cool_down_secs {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => Some(DEFAULT_CIRCUIT_COOL_DOWN),
+            },
+        }
+    }
+}
+
+/// What to do once a run of a service exited on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartDecision {
+    /// Not something the policy restarts after
+    Stay,
+    /// Start it again after `delay`; this is the `attempt`-th restart in a row
+    Restart { attempt: u32, delay: Duration },
+    /// Restarted `max_retries` times in a row already
+    GiveUp,
+    /// Its circuit opened after `failures` failed runs; retried once after
+    /// `retry_in`, or only when started again
+    CircuitOpen { failures: u32, retry_in: Option<Duration> },
+}
+
+impl AutoRestart {
+    /// `None` when the service is never restarted.
+    pub fn for_service(service: &Service) -> Option<Self> {
+        if service.restart_policy.is_never() {
+            return None;
+        }
+        Some(Self {
+            policy: service.restart_policy,
+            backoff: service.restart_backoff_ms.map_or(DEFAULT_BACKOFF, Duration::from_millis),
+            circuit: CircuitConfig::from_config(service.restart_circuit.as_ref()),
+        })
+    }
+
+    /// Decide after a run that exited with `exit_code` (`None` when killed
+    /// by a signal) after `ran_for`, `restarts` restarts in a row into it.
+    pub fn decide(&self, exit_code: Option<i32>, ran_for: Duration, restarts: u32) -> RestartDecision {
+        let max_retries = match self.policy {
+            RestartPolicy::Never => return RestartDecision::Stay,
+            RestartPolicy::OnFailure { .. } if exit_code == Some(0) => return RestartDecision::Stay,
+            RestartPolicy::OnFailure { max_retries } | RestartPolicy::Always { max_retries } => max_retries,
+        };
+        let restarts = if ran_for >= STABLE_RUN { 0 } else { restarts };
+        if restarts >= max_retries {
+            return RestartDecision::GiveUp;
+        }
+        RestartDecision::Restart { attempt: restarts + 1, delay: self.delay(restarts) }
+    }
+
+    /// Backoff before the restart following `restarts` earlier ones in a row
+    pub fn delay(&self, restarts: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(restarts.min(16)))
+            .min(MAX_BACKOFF.max(self.backoff))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Restarting as the policy says
+    Closed,
+    /// Too many failures; not restarted
+    Open { since: Instant },
+    /// Retried once after the cool-down, at `since`
+    HalfOpen { since: Instant },
+}
+
+/// Failed runs of one service, and whether they stopped its restarts.
+/// Times are passed in, so it's driven by whatever clock the caller has.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitConfig,
+    state: CircuitState,
+    /// When the failed runs in the window ended
+    failures: VecDeque<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitConfig) -> Self {
+        Self { config, state: CircuitState::Closed, failures: VecDeque::new() }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+
+    /// Failed runs counted in the window
+    pub fn failures(&self) -> u32 {
+        self.failures.len() as u32
+    }
+
+    /// Close a half-open circuit whose retry has stayed up long enough
+    pub fn refresh(&mut self, now: Instant) {
+        if let CircuitState::HalfOpen { since } = self.state {
+            if now.saturating_duration_since(since) >= PROBE_RUN {
+                self.state = CircuitState::Closed;
+                self.failures.clear();
+            }
+        }
+    }
+
+    /// A run ended on its own at `now`, in a way the policy restarts after.
+    /// Returns whether the circuit is open now (no restart).
+    pub fn record_failure(&mut self, now: Instant) -> bool {
+        self.refresh(now);
+        self.failures.retain(|&at| now.saturating_duration_since(at) < self.config.window);
+        self.failures.push_back(now);
+        match self.state {
+            // The retry failed too
+            CircuitState::HalfOpen { .. } | CircuitState::Open { .. } => {
+                self.state = CircuitState::Open { since: now };
+            }
+            CircuitState::Closed if self.failures() >= self.config.max_failures => {
+                self.state = CircuitState::Open { since: now };
+            }
+            CircuitState::Closed => {}
+        }
+        matches!(self.state, CircuitState::Open { .. })
+    }
+
+    /// When an open circuit gets its retry; None when closed, half-open, or
+    /// waiting for a manual start
+    pub fn retry_at(&self) -> Option<Instant> {
+        match self.state {
+            CircuitState::Open { since } => self.config.cool_down.map(|cool_down| since + cool_down),
+            _ => None,
+        }
+    }
+
+    /// Start the retry of an open circuit whose cool-down is over (it turns
+    /// half-open). Returns whether the retry may start.
+    pub fn begin_retry(&mut self, now: Instant) -> bool {
+        match self.retry_at() {
+            Some(due) if now >= due => {
+                self.state = CircuitState::HalfOpen { since: now };
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_failure(max_retries: u32) -> AutoRestart {
+        AutoRestart {
+            policy: RestartPolicy::OnFailure { max_retries },
+            backoff: Duration::from_millis(500),
+            circuit: CircuitConfig::from_config(None),
+        }
+    }
+
+    #[test]
+    fn failures_restart_with_a_doubling_backoff_until_max_retries() {
+        let restart = on_failure(3);
+        let quick = Duration::from_secs(1);
+        assert_eq!(
+            restart.decide(Some(1), quick, 0),
+            RestartDecision::Restart { attempt: 1, delay: Duration::from_millis(500) }
+        );
+        assert_eq!(
+            restart.decide(None, quick, 1),
+            RestartDecision::Restart { attempt: 2, delay: Duration::from_millis(1000) }
+        );
+        assert_eq!(
+            restart.decide(Some(1), quick, 2),
+            RestartDecision::Restart { attempt: 3, delay: Duration::from_millis(2000) }
+        );
+        assert_eq!(restart.decide(Some(1), quick, 3), RestartDecision::GiveUp);
+        // A long run starts over
+        assert!(matches!(restart.decide(Some(1), STABLE_RUN, 3), RestartDecision::Restart { attempt: 1, .. }));
+        assert_eq!(restart.delay(40), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn clean_exits_restart_only_with_always() {
+        let quick = Duration::from_secs(1);
+        assert_eq!(on_failure(3).decide(Some(0), quick, 0), RestartDecision::Stay);
+        let always =
+            AutoRestart { policy: RestartPolicy::Always { max_retries: 1 }, backoff: Duration::ZERO, ..on_failure(0) };
+        assert_eq!(always.decide(Some(0), quick, 0), RestartDecision::Restart { attempt: 1, delay: Duration::ZERO });
+        assert_eq!(always.decide(Some(0), quick, 1), RestartDecision::GiveUp);
+    }
+
+    #[test]
+    fn only_configured_services_are_restarted() {
+        let mut service = Service::new("web".into(), ".".into(), "npm run dev".into());
+        assert_eq!(AutoRestart::for_service(&service), None);
+
+        service.restart_policy = RestartPolicy::OnFailure { max_retries: 5 };
+        let restart = AutoRestart::for_service(&service).unwrap();
+        assert_eq!(restart.backoff, DEFAULT_BACKOFF);
+        service.restart_backoff_ms = Some(250);
+        assert_eq!(AutoRestart::for_service(&service).unwrap().backoff, Duration::from_millis(250));
+    }
+
+    fn breaker(cool_down_secs: Option<u64>) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitConfig::from_config(Some(&RestartCircuitConfig {
+            max_failures: Some(3),
+            window_secs: Some(60),
+            cool_down_secs,
+        })))
+    }
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn circuit_opens_after_max_failures_within_the_window() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(600));
+        assert!(!circuit.record_failure(start));
+        assert!(!circuit.record_failure(start + secs(10)));
+        assert_eq!(circuit.state(), CircuitState::Closed);
+        assert!(circuit.record_failure(start + secs(20)));
+        assert_eq!(circuit.state(), CircuitState::Open { since: start + secs(20) });
+        assert_eq!(circuit.failures(), 3);
+        assert_eq!(circuit.retry_at(), Some(start + secs(620)));
+    }
+
+    #[test]
+    fn failures_older_than_the_window_are_forgotten() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(600));
+        assert!(!circuit.record_failure(start));
+        assert!(!circuit.record_failure(start + secs(30)));
+        // The first one is out of the window by now
+        assert!(!circuit.record_failure(start + secs(60)));
+        assert_eq!(circuit.failures(), 2);
+        assert!(!circuit.record_failure(start + secs(95)));
+        assert!(circuit.record_failure(start + secs(100)));
+    }
+
+    #[test]
+    fn open_circuit_retries_once_after_the_cool_down() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(600));
+        for at in [0, 1, 2] {
+            circuit.record_failure(start + secs(at));
+        }
+        assert!(!circuit.begin_retry(start + secs(300)));
+        assert!(circuit.begin_retry(start + secs(602)));
+        assert_eq!(circuit.state(), CircuitState::HalfOpen { since: start + secs(602) });
+        assert_eq!(circuit.retry_at(), None);
+        // Only one retry at a time
+        assert!(!circuit.begin_retry(start + secs(700)));
+    }
+
+    #[test]
+    fn a_retry_that_stays_up_closes_the_circuit() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(600));
+        for at in [0, 1, 2] {
+            circuit.record_failure(start + secs(at));
+        }
+        circuit.begin_retry(start + secs(602));
+        circuit.refresh(start + secs(630));
+        assert!(matches!(circuit.state(), CircuitState::HalfOpen { .. }));
+        circuit.refresh(start + secs(662));
+        assert_eq!(circuit.state(), CircuitState::Closed);
+        assert_eq!(circuit.failures(), 0);
+
+        // Counting starts over
+        assert!(!circuit.record_failure(start + secs(700)));
+        assert_eq!(circuit.failures(), 1);
+    }
+
+    #[test]
+    fn a_failed_retry_opens_the_circuit_again() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(600));
+        for at in [0, 1, 2] {
+            circuit.record_failure(start + secs(at));
+        }
+        circuit.begin_retry(start + secs(602));
+        assert!(circuit.record_failure(start + secs(605)));
+        assert_eq!(circuit.state(), CircuitState::Open { since: start + secs(605) });
+        assert_eq!(circuit.retry_at(), Some(start + secs(1205)));
+
+        // A retry that ends after its probe run counts as closed first
+        circuit.begin_retry(start + secs(1205));
+        assert!(!circuit.record_failure(start + secs(1300)));
+        assert_eq!(circuit.state(), CircuitState::Closed);
+        assert_eq!(circuit.failures(), 1);
+    }
+
+    #[test]
+    fn without_a_cool_down_the_circuit_waits_for_a_manual_start() {
+        let start = Instant::now();
+        let mut circuit = breaker(Some(0));
+        for at in [0, 1, 2] {
+            circuit.record_failure(start + secs(at));
+        }
+        assert_eq!(circuit.retry_at(), None);
+        assert!(!circuit.begin_retry(start + secs(100_000)));
+        assert!(matches!(circuit.state(), CircuitState::Open { .. }));
+    }
+
+    #[test]
+    fn circuit_defaults() {
+        let config = CircuitConfig::from_config(None);
+        assert_eq!(config.max_failures, 5);
+        assert_eq!(config.window, secs(60));
+        assert_eq!(config.cool_down, Some(secs(600)));
+    }
+}
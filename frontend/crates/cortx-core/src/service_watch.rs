@@ -0,0 +1,149 @@
+//! Restart-on-change watching for services.
+//!
+//! A service with `watch_paths` gets a watcher while it runs (see
+//! `ProcessManager::start_service`). Changes are debounced so a bulk save or a
+//! `git checkout` triggers one restart, and changes inside build output /
+//! dependency directories are ignored so a service that writes there doesn't
+//! restart itself in a loop.
+
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::models::Service;
+
+/// Used when a service doesn't set `watch_debounce_ms`
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 500;
+
+/// Directory names whose contents never trigger a restart
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", "dist", "build", "__pycache__", ".venv"];
+
+/// What to watch for one running service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceWatch {
+    pub paths: Vec<PathBuf>,
+    pub debounce: Duration,
+}
+
+impl ServiceWatch {
+    /// Watch config for `service` started in `working_dir`, or `None` if it
+    /// has no watch paths. Relative paths are resolved against `working_dir`.
+    pub fn for_service(service: &Service, working_dir: &str) -> Option<Self> {
+        let paths: Vec<PathBuf> = service
+            .watch_paths
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(|p| Path::new(working_dir).join(p))
+            .collect();
+        if paths.is_empty() {
+            return None;
+        }
+        let debounce_ms = service.watch_debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS);
+        Some(Self {
+            paths,
+            debounce: Duration::from_millis(debounce_ms),
+        })
+    }
+}
+
+/// Whether a change to `path` should be ignored (it lies in an ignored directory).
+pub fn is_ignored(path: &Path) -> bool {
+    path.components()
+        .any(|c| c.as_os_str().to_str().is_some_and(|name| IGNORED_DIRS.contains(&name)))
+}
+
+/// Handle returned by [`start_watching`]. Drop to stop watching.
+pub struct ServiceWatcherHandle {
+    _debouncer: notify_debouncer_mini::Debouncer<notify::RecommendedWatcher>,
+    _thread: std::thread::JoinHandle<()>,
+}
+
+/// Watch every path of `watch` (directories recursively) and call `on_change`
+/// once per debounced batch that contains at least one relevant change.
+/// Paths that don't exist are skipped with a warning; fails only if none can
+/// be watched.
+pub fn start_watching<F>(watch: &ServiceWatch, on_change: F) -> Result<ServiceWatcherHandle, notify::Error>
+where
+    F: Fn() + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+    let mut debouncer = new_debouncer(watch.debounce, tx)?;
+
+    let mut watched = 0;
+    for path in &watch.paths {
+        let mode = if path.is_dir() {
+            notify::RecursiveMode::Recursive
+        } else {
+            notify::RecursiveMode::NonRecursive
+        };
+        match debouncer.watcher().watch(path, mode) {
+            Ok(()) => watched += 1,
+            Err(e) => log::warn!("Cannot watch {}: {}", path.display(), e),
+        }
+    }
+    if watched == 0 {
+        return Err(notify::Error::generic("None of the watch paths could be watched"));
+    }
+
+    // Exits once the debouncer (and with it the sender) is dropped
+    let thread = std::thread::Builder::new()
+        .name("cortx-service-watcher".into())
+        .spawn(move || {
+            while let Ok(result) = rx.recv() {
+                match result {
+                    Ok(events) => {
+                        if events.iter().any(|event| !is_ignored(&event.path)) {
+                            on_change();
+                        }
+                    }
+                    Err(err) => log::warn!("Service watcher error: {:?}", err),
+                }
+            }
+        })
+        .map_err(|e| notify::Error::generic(&e.to_string()))?;
+
+    Ok(ServiceWatcherHandle {
+        _debouncer: debouncer,
+        _thread: thread,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_paths_against_working_dir() {
+        let mut service = Service::new("api".into(), "/srv/api".into(), "cargo run".into());
+        assert_eq!(ServiceWatch::for_service(&service, "/srv/api"), None);
+
+        service.watch_paths = vec!["src".into(), " ".into(), "/etc/api.toml".into()];
+        let watch = ServiceWatch::for_service(&service, "/srv/api").unwrap();
+        assert_eq!(watch.paths, vec![PathBuf::from("/srv/api/src"), PathBuf::from("/etc/api.toml")]);
+        assert_eq!(watch.debounce, Duration::from_millis(DEFAULT_WATCH_DEBOUNCE_MS));
+
+        service.watch_debounce_ms = Some(2000);
+        let watch = ServiceWatch::for_service(&service, "/srv/api").unwrap();
+        assert_eq!(watch.debounce, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn ignores_build_and_dependency_dirs() {
+        assert!(is_ignored(Path::new("/srv/web/node_modules/react/index.js")));
+        assert!(is_ignored(Path::new("/srv/api/target/debug/api")));
+        assert!(is_ignored(Path::new("/srv/api/.git/index")));
+        assert!(!is_ignored(Path::new("/srv/api/src/main.rs")));
+        assert!(!is_ignored(Path::new("/srv/api/src/targets.rs")));
+    }
+
+    #[test]
+    fn missing_paths_fail_to_watch() {
+        let watch = ServiceWatch {
+            paths: vec![PathBuf::from("/definitely/not/here")],
+            debounce: Duration::from_millis(50),
+        };
+        assert!(start_watching(&watch, || {}).is_err());
+    }
+}
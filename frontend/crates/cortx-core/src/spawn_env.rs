@@ -0,0 +1,316 @@
+//! Environment construction for spawned processes.
+//!
+//! By default a child inherits the whole CortX environment (`EnvMode::Inherit`).
+//! The `Clean` and `Allowlist` modes start from an empty environment and only
+//! re-add a minimal OS-specific set (so PATH lookups and temp dirs keep working),
+//! plus allowlisted parent variables, plus the explicitly configured `env_vars`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::process::Command;
+
+use crate::models::{EnvMode, EnvSnapshotConfig, SecretEnvHandling};
+
+/// Variables re-added in `Clean` / `Allowlist` mode so common tools still work.
+#[cfg(target_os = "windows")]
+const MINIMAL_VARS: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "USERPROFILE",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "TEMP",
+    "TMP",
+    "SystemRoot",
+    "SystemDrive",
+    "windir",
+    "ComSpec",
+    "LANG",
+];
+
+#[cfg(not(target_os = "windows"))]
+const MINIMAL_VARS: &[&str] = &["PATH", "HOME", "USER", "SHELL", "TMPDIR", "LANG", "TERM"];
+
+/// The minimal variable set for the current OS.
+pub fn minimal_var_names() -> &'static [&'static str] {
+    MINIMAL_VARS
+}
+
+/// Compare variable names the way the OS does (case-insensitive on Windows).
+fn names_equal(a: &str, b: &str) -> bool {
+    if cfg!(target_os = "windows") {
+        a.eq_ignore_ascii_case(b)
+    } else {
+        a == b
+    }
+}
+
+/// Match a variable name against a glob pattern supporting `*` and `?`.
+/// Case-insensitive on Windows, like environment variable names themselves.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let fold = |c: char| if cfg!(target_os = "windows") { c.to_ascii_uppercase() } else { c };
+    let p: Vec<char> = pattern.chars().map(fold).collect();
+    let n: Vec<char> = name.chars().map(fold).collect();
+
+    // Iterative wildcard matching with single-star backtracking
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
+
+/// Build the base environment (before `env_vars` are applied) for a mode, from
+/// the given parent variables. Returns `None` for `Inherit`, meaning "leave the
+/// parent environment untouched".
+pub fn base_env(
+    mode: &EnvMode,
+    parent: impl IntoIterator<Item = (String, String)>,
+) -> Option<BTreeMap<String, String>> {
+    let patterns: &[String] = match mode {
+        EnvMode::Inherit => return None,
+        EnvMode::Clean => &[],
+        EnvMode::Allowlist(patterns) => patterns,
+    };
+
+    let (excludes, includes): (Vec<&str>, Vec<&str>) = patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .partition(|p| p.starts_with('!'));
+    let excludes: Vec<&str> = excludes.iter().map(|p| &p[1..]).collect();
+
+    let mut env = BTreeMap::new();
+    for (key, value) in parent {
+        if excludes.iter().any(|p| glob_match(p, &key)) {
+            continue;
+        }
+        let minimal = MINIMAL_VARS.iter().any(|m| names_equal(m, &key));
+        if minimal || includes.iter().any(|p| glob_match(p, &key)) {
+            env.insert(key, value);
+        }
+    }
+    Some(env)
+}
+
+/// The full environment a process would see for this mode and `env_vars`,
+/// computed against the current process environment.
+pub fn effective_env(
+    mode: &EnvMode,
+    env_vars: Option<&HashMap<String, String>>,
+) -> BTreeMap<String, String> {
+    let mut env = base_env(mode, parent_vars()).unwrap_or_else(|| parent_vars().collect());
+    if let Some(vars) = env_vars {
+        for (key, value) in vars {
+            env.insert(key.clone(), value.clone());
+        }
+    }
+    env
+}
+
+/// Reset `cmd`'s environment according to `mode`. Must be called before any
+/// `cmd.env(...)` the caller wants to keep, since non-inherit modes clear it.
+pub fn prepare_env(cmd: &mut Command, mode: &EnvMode) {
+    if let Some(env) = base_env(mode, parent_vars()) {
+        cmd.env_clear();
+        cmd.envs(env);
+    }
+}
+
+/// At most this many variables are recorded per run
+pub const ENV_SNAPSHOT_MAX_VARS: usize = 64;
+
+/// Recorded values are cut to this many characters
+pub const ENV_SNAPSHOT_MAX_VALUE: usize = 256;
+
+/// Replaces the value of a masked secret
+pub const MASKED_VALUE: &str = "********";
+
+/// The injected `env_vars` of a run as execution history records them:
+/// secret-looking names masked or omitted, at most
+/// [`ENV_SNAPSHOT_MAX_VARS`] variables (by name) with values cut to
+/// [`ENV_SNAPSHOT_MAX_VALUE`] characters. `None` when disabled or when
+/// nothing is injected.
+pub fn env_snapshot(
+    env_vars: Option<&HashMap<String, String>>,
+    config: &EnvSnapshotConfig,
+) -> Option<HashMap<String, String>> {
+    if !config.enabled {
+        return None;
+    }
+    let sorted: BTreeMap<&String, &String> = env_vars?.iter().collect();
+    let is_secret = |key: &str| {
+        let key = key.to_ascii_uppercase();
+        config.secret_patterns.iter().any(|p| glob_match(&p.trim().to_ascii_uppercase(), &key))
+    };
+    let snapshot: HashMap<String, String> = sorted
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = match (is_secret(key), config.secrets) {
+                (true, SecretEnvHandling::Omit) => return None,
+                (true, SecretEnvHandling::Mask) => MASKED_VALUE.to_string(),
+                (false, _) if value.chars().count() > ENV_SNAPSHOT_MAX_VALUE => {
+                    let mut cut: String = value.chars().take(ENV_SNAPSHOT_MAX_VALUE).collect();
+                    cut.push('…');
+                    cut
+                }
+                (false, _) => value.clone(),
+            };
+            Some((key.clone(), value))
+        })
+        .take(ENV_SNAPSHOT_MAX_VARS)
+        .collect();
+    (!snapshot.is_empty()).then_some(snapshot)
+}
+
+/// Current process environment, skipping entries that aren't valid UTF-8.
+fn parent_vars() -> impl Iterator<Item = (String, String)> {
+    std::env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent() -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = MINIMAL_VARS
+            .iter()
+            .map(|k| (k.to_string(), format!("value-of-{}", k)))
+            .collect();
+        vars.push(("SSH_AUTH_SOCK".into(), "/tmp/agent.sock".into()));
+        vars.push(("GITHUB_TOKEN".into(), "secret".into()));
+        vars.push(("LC_ALL".into(), "C.UTF-8".into()));
+        vars.push(("LC_TIME".into(), "C".into()));
+        vars
+    }
+
+    #[test]
+    fn inherit_leaves_environment_alone() {
+        assert!(base_env(&EnvMode::Inherit, parent()).is_none());
+    }
+
+    #[test]
+    fn clean_keeps_only_minimal_set() {
+        let env = base_env(&EnvMode::Clean, parent()).unwrap();
+        assert!(env.contains_key("PATH"));
+        for name in minimal_var_names() {
+            assert!(env.contains_key(*name), "missing {}", name);
+        }
+        assert!(!env.contains_key("SSH_AUTH_SOCK"));
+        assert!(!env.contains_key("GITHUB_TOKEN"));
+        assert!(!env.contains_key("LC_ALL"));
+        assert_eq!(env.len(), minimal_var_names().len());
+    }
+
+    #[test]
+    fn allowlist_passes_glob_matches() {
+        let mode = EnvMode::Allowlist(vec!["LC_*".into()]);
+        let env = base_env(&mode, parent()).unwrap();
+        assert_eq!(env.get("LC_ALL").map(String::as_str), Some("C.UTF-8"));
+        assert!(env.contains_key("LC_TIME"));
+        assert!(env.contains_key("PATH"));
+        assert!(!env.contains_key("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn allowlist_can_remove_minimal_vars() {
+        let mode = EnvMode::Allowlist(vec!["!PATH".into()]);
+        let env = base_env(&mode, parent()).unwrap();
+        assert!(!env.contains_key("PATH"));
+        assert!(env.contains_key("LANG"));
+    }
+
+    fn injected(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn snapshot_masks_or_omits_secrets() {
+        let vars = injected(&[("APP_ENV", "staging"), ("github_token", "ghp_x"), ("DB_PASSWORD", "hunter2")]);
+        let mut config = EnvSnapshotConfig::default();
+
+        let snap = env_snapshot(Some(&vars), &config).unwrap();
+        assert_eq!(snap.get("APP_ENV").map(String::as_str), Some("staging"));
+        assert_eq!(snap.get("github_token").map(String::as_str), Some(MASKED_VALUE));
+        assert_eq!(snap.get("DB_PASSWORD").map(String::as_str), Some(MASKED_VALUE));
+
+        config.secrets = SecretEnvHandling::Omit;
+        let snap = env_snapshot(Some(&vars), &config).unwrap();
+        assert_eq!(snap.keys().collect::<Vec<_>>(), vec!["APP_ENV"]);
+
+        config.enabled = false;
+        assert!(env_snapshot(Some(&vars), &config).is_none());
+        assert!(env_snapshot(None, &EnvSnapshotConfig::default()).is_none());
+    }
+
+    #[test]
+    fn run_records_its_injected_env() {
+        use crate::models::{ExecutionRecord, GlobalScript};
+        use crate::storage::Storage;
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let mut script = GlobalScript::new("deploy".into(), "./deploy.sh".into(), None);
+        script.env_vars = Some(injected(&[("API_URL", "https://staging"), ("API_TOKEN", "t0k")]));
+
+        // As the run commands record it
+        let mut record = ExecutionRecord::new(script.id.clone());
+        record.env_snapshot = env_snapshot(script.env_vars.as_ref(), &storage.get_settings().scripts_config.env_snapshot);
+        storage.add_execution_record(record).unwrap();
+
+        let reloaded = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let snap = reloaded.get_execution_history(&script.id, 1)[0].env_snapshot.clone().unwrap();
+        assert_eq!(snap.get("API_URL").map(String::as_str), Some("https://staging"));
+        assert_eq!(snap.get("API_TOKEN").map(String::as_str), Some(MASKED_VALUE));
+    }
+
+    #[test]
+    fn snapshot_is_size_bounded() {
+        let mut vars: HashMap<String, String> =
+            (0..200).map(|i| (format!("VAR_{:03}", i), "v".to_string())).collect();
+        vars.insert("AAA_LONG".into(), "x".repeat(1000));
+        let snap = env_snapshot(Some(&vars), &EnvSnapshotConfig::default()).unwrap();
+        assert_eq!(snap.len(), ENV_SNAPSHOT_MAX_VARS);
+        // The first names are kept
+        assert!(snap.contains_key("VAR_000") && !snap.contains_key("VAR_199"));
+        assert_eq!(snap["AAA_LONG"].chars().count(), ENV_SNAPSHOT_MAX_VALUE + 1);
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("LC_*", "LC_ALL"));
+        assert!(glob_match("LC_*", "LC_"));
+        assert!(!glob_match("LC_*", "LANG"));
+        assert!(glob_match("AWS_?EGION", "AWS_REGION"));
+        assert!(glob_match("*_TOKEN", "GITHUB_TOKEN"));
+        assert!(glob_match("*", "ANYTHING"));
+        assert!(glob_match("A*B*C", "AxxBxxC"));
+        assert!(!glob_match("A*B*C", "AxxBxx"));
+        assert!(!glob_match("PATH", "PATHEXT"));
+    }
+
+    #[test]
+    fn env_vars_override_base() {
+        let mut vars = HashMap::new();
+        vars.insert("MY_VAR".to_string(), "1".to_string());
+        let env = effective_env(&EnvMode::Clean, Some(&vars));
+        assert_eq!(env.get("MY_VAR").map(String::as_str), Some("1"));
+    }
+}
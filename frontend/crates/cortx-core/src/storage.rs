@@ -1,7 +1,20 @@
+use crate::change_event::{ChangeEvent, ChangeKind, ChangeObservers};
+use crate::env_file;
+use crate::export_file;
+use crate::migration;
+use crate::organize_rules::{self, OrganizeResult};
+use crate::project_files;
+use crate::project_roots;
 use crate::models::*;
+use crate::script_discovery::{self, ScanBudget};
+use crate::script_index::ScriptSearchIndex;
+use crate::script_relocation;
+use crate::script_sync::{self, SyncPlan, SyncResolution, SyncResult, SyncStatus};
+use crate::text_file::{self, TextFileError};
 use directories::ProjectDirs;
 use fs2::FileExt;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write as IoWrite;
 use std::path::{Path, PathBuf};
@@ -18,6 +31,8 @@ pub enum StorageError {
     Json(#[from] serde_json::Error),
     #[error("Project not found: {0}")]
     ProjectNotFound(String),
+    #[error("Project variant not found: {0}")]
+    VariantNotFound(String),
     #[error("Service not found: {0}")]
     ServiceNotFound(String),
     #[error("Script not found: {0}")]
@@ -34,6 +49,37 @@ pub enum StorageError {
     StatusDefinitionNotFound(String),
     #[error("App not found: {0}")]
     AppNotFound(String),
+    #[error("Backup error: {0}")]
+    Backup(String),
+    #[error("Unsupported export version: {0}")]
+    UnsupportedExportVersion(String),
+    #[error("Project '{name}' already has the root {path}")]
+    DuplicateProjectRoot { path: String, name: String, id: String },
+    #[error(transparent)]
+    Text(#[from] TextFileError),
+    #[error("No scripts folder configured (scriptsConfig.mainFolder)")]
+    NoScriptsFolder,
+}
+
+/// Overlaps of `root_path` with the roots of `projects` other than
+/// `except_id`; an identical root is an error unless `allow_duplicate`.
+fn check_project_root(
+    projects: &[Project],
+    root_path: &str,
+    except_id: Option<&str>,
+    allow_duplicate: bool,
+) -> Result<Vec<RootOverlap>, StorageError> {
+    let overlaps = project_roots::find_overlaps(projects, root_path, except_id);
+    if !allow_duplicate {
+        if let Some(same) = overlaps.iter().find(|o| o.relation == RootRelation::Same) {
+            return Err(StorageError::DuplicateProjectRoot {
+                path: root_path.to_string(),
+                name: same.project_name.clone(),
+                id: same.project_id.clone(),
+            });
+        }
+    }
+    Ok(overlaps)
 }
 
 pub struct Storage {
@@ -41,6 +87,8 @@ pub struct Storage {
     projects: RwLock<Vec<Project>>,
     settings: RwLock<AppSettings>,
     global_scripts: RwLock<Vec<GlobalScript>>,
+    /// Built on the first search, then kept in step with `global_scripts`
+    script_index: RwLock<Option<ScriptSearchIndex>>,
     tag_definitions: RwLock<Vec<TagDefinition>>,
     execution_history: RwLock<Vec<ExecutionRecord>>,
     tools: RwLock<Vec<Tool>>,
@@ -48,11 +96,14 @@ pub struct Storage {
     status_definitions: RwLock<Vec<StatusDefinition>>,
     apps: RwLock<Vec<App>>,
     suppress_watcher: AtomicBool,
+    observers: ChangeObservers,
+    /// Files whose last write failed, so memory is ahead of disk
+    pending_saves: Mutex<Vec<PendingSave>>,
 }
 
 // File locking helpers
 
-fn read_json_locked<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Result<T, StorageError> {
+pub(crate) fn read_json_locked<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Result<T, StorageError> {
     let file = File::open(path)?;
     file.lock_shared().map_err(|e| StorageError::Io(e))?;
     let content = std::io::read_to_string(&file)?;
@@ -60,26 +111,60 @@ fn read_json_locked<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Result<T,
     Ok(serde_json::from_str(&content)?)
 }
 
-fn write_json_locked<T: serde::Serialize>(path: &PathBuf, data: &T) -> Result<(), StorageError> {
-    let file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)?;
-    file.lock_exclusive().map_err(|e| StorageError::Io(e))?;
+/// Replace `path` with `data` atomically: the JSON goes to `<name>.tmp` next
+/// to it, is fsynced, then renamed over the target. A crash mid-write leaves
+/// the previous complete file (plus a stray `.tmp`), never a truncated one.
+/// The exclusive lock on the target is held throughout.
+pub(crate) fn write_json_locked<T: serde::Serialize>(path: &PathBuf, data: &T) -> Result<(), StorageError> {
     let content = serde_json::to_string_pretty(data)?;
-    (&file).write_all(content.as_bytes())?;
+
+    let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+    file.lock_exclusive().map_err(|e| StorageError::Io(e))?;
+    let written = write_replacing(path, content.as_bytes());
     file.unlock().map_err(|e| StorageError::Io(e))?;
+    written
+}
+
+fn write_replacing(path: &Path, content: &[u8]) -> Result<(), StorageError> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+
+    let result = (|| {
+        let mut tmp_file = File::create(&tmp)?;
+        tmp_file.write_all(content)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    // Persist the rename itself; directories can't be opened for this on Windows
+    #[cfg(unix)]
+    {
+        if let Some(dir) = path.parent() {
+            let _ = File::open(dir).and_then(|d| d.sync_all());
+        }
+    }
     Ok(())
 }
 
 impl Storage {
     pub fn new() -> Result<Self, StorageError> {
+        Self::with_app_dir(Self::default_app_dir()?)
+    }
+
+    /// The platform data dir `new()` opens, without creating anything.
+    pub fn default_app_dir() -> Result<PathBuf, StorageError> {
         let project_dirs =
             ProjectDirs::from("com", "cortx", "Cortx").ok_or(StorageError::NoAppDir)?;
+        Ok(project_dirs.data_dir().to_path_buf())
+    }
 
-        let app_dir = project_dirs.data_dir().to_path_buf();
-
+    /// Open storage rooted at `app_dir` instead of the platform data dir.
+    pub fn with_app_dir(app_dir: PathBuf) -> Result<Self, StorageError> {
         // Create directories if they don't exist
         fs::create_dir_all(&app_dir)?;
         fs::create_dir_all(app_dir.join("images"))?;
@@ -89,6 +174,7 @@ impl Storage {
             projects: RwLock::new(Vec::new()),
             settings: RwLock::new(AppSettings::default()),
             global_scripts: RwLock::new(Vec::new()),
+            script_index: RwLock::new(None),
             tag_definitions: RwLock::new(Vec::new()),
             execution_history: RwLock::new(Vec::new()),
             tools: RwLock::new(Vec::new()),
@@ -96,6 +182,8 @@ impl Storage {
             status_definitions: RwLock::new(Vec::new()),
             apps: RwLock::new(Vec::new()),
             suppress_watcher: AtomicBool::new(false),
+            observers: ChangeObservers::default(),
+            pending_saves: Mutex::new(Vec::new()),
         };
 
         // Load existing data
@@ -130,12 +218,74 @@ impl Storage {
         self.suppress_watcher.load(Ordering::Relaxed)
     }
 
-    // Path helpers
+    /// Call `observer` after every saved change to a project, service,
+    /// project script, global script or the settings. Reloads from disk
+    /// aren't reported; the file watcher covers those.
+    pub fn subscribe(&self, observer: impl Fn(ChangeEvent) + Send + Sync + 'static) {
+        self.observers.subscribe(observer);
+    }
+
+    /// Report a saved change. Must be called with no data lock held.
+    fn notify(&self, event: ChangeEvent) {
+        self.observers.notify([event]);
+    }
 
-    fn projects_path(&self) -> PathBuf {
-        self.app_dir.join("projects.json")
+    /// Data files whose latest changes failed to save, in the order their
+    /// last attempt failed. Empty when everything is on disk.
+    pub fn pending_saves(&self) -> Vec<PendingSave> {
+        self.pending_saves.lock().clone()
     }
 
+    /// Write every file in [`Self::pending_saves`] again. Returns what's
+    /// still unsaved.
+    pub fn retry_pending_saves(&self) -> Vec<PendingSave> {
+        let files: Vec<StorageFile> = self.pending_saves.lock().iter().map(|p| p.file).collect();
+        for file in files {
+            // A failure is recorded again
+            let _ = self.save_file(file);
+        }
+        self.pending_saves()
+    }
+
+    fn save_file(&self, file: StorageFile) -> Result<(), StorageError> {
+        match file {
+            StorageFile::Projects => self.save_projects(),
+            StorageFile::Settings => self.save_settings(),
+            StorageFile::GlobalScripts => self.save_global_scripts(),
+            StorageFile::TagDefinitions => self.save_tag_definitions(),
+            StorageFile::ExecutionHistory => self.save_execution_history(),
+            StorageFile::Tools => self.save_tools(),
+            StorageFile::Aliases => self.save_aliases(),
+            StorageFile::StatusDefinitions => self.save_status_definitions(),
+            StorageFile::Apps => self.save_apps(),
+        }
+    }
+
+    /// Track the outcome of writing `file`: a failure makes it pending, a
+    /// success clears it. Reports `ChangeEvent::SaveStatus` whenever that
+    /// changes anything, then passes `result` on.
+    fn record_save(&self, file: StorageFile, result: Result<(), StorageError>) -> Result<(), StorageError> {
+        let changed = {
+            let mut pending = self.pending_saves.lock();
+            let before = pending.len();
+            pending.retain(|p| p.file != file);
+            match &result {
+                Ok(()) => pending.len() != before,
+                Err(e) => {
+                    log::warn!("Failed to save {}: {}", file.label(), e);
+                    pending.push(PendingSave { file, error: e.to_string(), failed_at: chrono::Utc::now() });
+                    true
+                }
+            }
+        };
+        if changed {
+            self.notify(ChangeEvent::SaveStatus);
+        }
+        result
+    }
+
+    // Path helpers
+
     fn settings_path(&self) -> PathBuf {
         self.app_dir.join("settings.json")
     }
@@ -177,19 +327,44 @@ impl Storage {
     // ========================================================================
 
     fn load_projects(&self) -> Result<(), StorageError> {
-        let path = self.projects_path();
-        if path.exists() {
-            let projects: Vec<Project> = read_json_locked(&path)?;
-            *self.projects.write() = projects;
-        }
+        // Loading writes too, when it migrates or repairs the index
+        self.set_suppress_watcher();
+        let loaded = project_files::load(&self.app_dir);
+        self.clear_suppress_watcher();
+        *self.projects.write() = loaded?;
         Ok(())
     }
 
+    /// Write every project file and the index
     fn save_projects(&self) -> Result<(), StorageError> {
         self.set_suppress_watcher();
-        let result = write_json_locked(&self.projects_path(), &*self.projects.read());
+        let result = project_files::write_all(&self.app_dir, &self.projects.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::Projects, result)
+    }
+
+    /// Write the file of the project `id` only, and the index too when
+    /// `with_index` (the list or a name changed). A deleted project's file
+    /// is removed.
+    fn save_project(&self, id: &str, with_index: bool) -> Result<(), StorageError> {
+        // An earlier failed save may have left other projects unsaved
+        if self.pending_saves.lock().iter().any(|p| p.file == StorageFile::Projects) {
+            return self.save_projects();
+        }
+        self.set_suppress_watcher();
+        let result = (|| -> Result<(), StorageError> {
+            let projects = self.projects.read();
+            match projects.iter().find(|p| p.id == id) {
+                Some(project) => project_files::write_project(&self.app_dir, project)?,
+                None => project_files::remove_project(&self.app_dir, id)?,
+            }
+            if with_index {
+                project_files::write_index(&self.app_dir, &projects)?;
+            }
+            Ok(())
+        })();
+        self.clear_suppress_watcher();
+        self.record_save(StorageFile::Projects, result)
     }
 
     pub fn get_all_projects(&self) -> Vec<Project> {
@@ -200,33 +375,65 @@ impl Storage {
         self.projects.read().iter().find(|p| p.id == id).cloned()
     }
 
+    /// Add a project. Fails if another project has the same root.
     pub fn create_project(&self, project: Project) -> Result<Project, StorageError> {
-        {
+        self.create_project_checked(project, false).map(|saved| saved.project)
+    }
+
+    /// Add a project, reporting the projects whose root is inside or around
+    /// its root. With `allow_duplicate`, a root another project already has
+    /// is reported too instead of refused.
+    pub fn create_project_checked(&self, project: Project, allow_duplicate: bool) -> Result<SavedProject, StorageError> {
+        let overlaps = {
             let mut projects = self.projects.write();
+            let overlaps = check_project_root(&projects, &project.root_path, None, allow_duplicate)?;
             projects.push(project.clone());
-        }
-        self.save_projects()?;
-        Ok(project)
+            overlaps
+        };
+        self.save_project(&project.id, true)?;
+        self.notify(ChangeEvent::Project { id: project.id.clone(), change: ChangeKind::Created });
+        Ok(SavedProject { project, overlaps })
     }
 
+    /// Change a project. Fails if its root is changed to another project's.
     pub fn update_project(
         &self,
         id: &str,
         updater: impl FnOnce(&mut Project),
     ) -> Result<Project, StorageError> {
-        let project = {
+        self.update_project_checked(id, false, updater).map(|saved| saved.project)
+    }
+
+    /// Change a project; when its root changed, checked like in
+    /// [`Storage::create_project_checked`].
+    pub fn update_project_checked(
+        &self,
+        id: &str,
+        allow_duplicate: bool,
+        updater: impl FnOnce(&mut Project),
+    ) -> Result<SavedProject, StorageError> {
+        let (project, overlaps, renamed) = {
             let mut projects = self.projects.write();
-            let project = projects
-                .iter_mut()
-                .find(|p| p.id == id)
+            let index = projects
+                .iter()
+                .position(|p| p.id == id)
                 .ok_or_else(|| StorageError::ProjectNotFound(id.to_string()))?;
 
-            updater(project);
+            let mut project = projects[index].clone();
+            updater(&mut project);
+            let overlaps = if project.root_path != projects[index].root_path {
+                check_project_root(&projects, &project.root_path, Some(id), allow_duplicate)?
+            } else {
+                Vec::new()
+            };
             project.updated_at = chrono::Utc::now();
-            project.clone()
+            let renamed = project.name != projects[index].name;
+            projects[index] = project.clone();
+            (project, overlaps, renamed)
         };
-        self.save_projects()?;
-        Ok(project)
+        self.save_project(&project.id, renamed)?;
+        self.notify(ChangeEvent::Project { id: project.id.clone(), change: ChangeKind::Updated });
+        Ok(SavedProject { project, overlaps })
     }
 
     pub fn delete_project(&self, id: &str) -> Result<(), StorageError> {
@@ -238,10 +445,68 @@ impl Storage {
                 return Err(StorageError::ProjectNotFound(id.to_string()));
             }
         }
-        self.save_projects()?;
+        self.save_project(id, true)?;
+        self.notify(ChangeEvent::Project { id: id.to_string(), change: ChangeKind::Deleted });
         Ok(())
     }
 
+    // ========================================================================
+    // Project variants
+    // ========================================================================
+
+    pub fn add_project_variant(&self, project_id: &str, variant: ProjectVariant) -> Result<Project, StorageError> {
+        self.update_project(project_id, |project| project.variants.push(variant))
+    }
+
+    /// Remove a variant; the project falls back to its own root if it was
+    /// the active one.
+    pub fn remove_project_variant(&self, project_id: &str, variant_id: &str) -> Result<Project, StorageError> {
+        self.check_variant(project_id, variant_id)?;
+        self.update_project(project_id, |project| {
+            project.variants.retain(|v| v.id != variant_id);
+            if project.active_variant_id.as_deref() == Some(variant_id) {
+                project.active_variant_id = None;
+            }
+        })
+    }
+
+    /// Run the project's services and scripts under `variant_id`, or under
+    /// the project's own root with `None`.
+    pub fn set_active_variant(&self, project_id: &str, variant_id: Option<&str>) -> Result<Project, StorageError> {
+        if let Some(variant_id) = variant_id {
+            self.check_variant(project_id, variant_id)?;
+        }
+        self.update_project(project_id, |project| {
+            project.active_variant_id = variant_id.map(str::to_string);
+        })
+    }
+
+    /// Error unless the project exists and has the variant
+    fn check_variant(&self, project_id: &str, variant_id: &str) -> Result<(), StorageError> {
+        let projects = self.projects.read();
+        let project = projects
+            .iter()
+            .find(|p| p.id == project_id)
+            .ok_or_else(|| StorageError::ProjectNotFound(project_id.to_string()))?;
+        if project.variants.iter().any(|v| v.id == variant_id) {
+            Ok(())
+        } else {
+            Err(StorageError::VariantNotFound(variant_id.to_string()))
+        }
+    }
+
+    /// Keys defined with different values by several env files in `dir`
+    /// (absolute, or relative to the project root). Empty if the project
+    /// doesn't exist.
+    pub fn find_duplicate_env_keys(&self, project_id: &str, dir: &str) -> Vec<(String, Vec<String>)> {
+        let projects = self.projects.read();
+        let Some(project) = projects.iter().find(|p| p.id == project_id) else {
+            return Vec::new();
+        };
+        let dir = Path::new(&project.root_path).join(dir);
+        env_file::duplicate_keys(&project.env_files, &dir)
+    }
+
     // ========================================================================
     // Services (within projects)
     // ========================================================================
@@ -262,7 +527,12 @@ impl Storage {
             project.services.push(service);
             project.updated_at = chrono::Utc::now();
         }
-        self.save_projects()?;
+        self.save_project(project_id, false)?;
+        self.notify(ChangeEvent::Service {
+            project_id: project_id.to_string(),
+            id: service_clone.id.clone(),
+            change: ChangeKind::Created,
+        });
         Ok(service_clone)
     }
 
@@ -271,14 +541,14 @@ impl Storage {
         service_id: &str,
         updater: impl FnOnce(&mut Service),
     ) -> Result<Service, StorageError> {
-        let service = {
+        let (project_id, service) = {
             let mut projects = self.projects.write();
             let mut found_service = None;
 
             for project in projects.iter_mut() {
                 if let Some(service) = project.services.iter_mut().find(|s| s.id == service_id) {
                     updater(service);
-                    found_service = Some(service.clone());
+                    found_service = Some((project.id.clone(), service.clone()));
                     project.updated_at = chrono::Utc::now();
                     break;
                 }
@@ -286,30 +556,30 @@ impl Storage {
 
             found_service.ok_or_else(|| StorageError::ServiceNotFound(service_id.to_string()))?
         };
-        self.save_projects()?;
+        self.save_project(&project_id, false)?;
+        self.notify(ChangeEvent::Service { project_id, id: service.id.clone(), change: ChangeKind::Updated });
         Ok(service)
     }
 
     pub fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
-        {
+        let project_id = {
             let mut projects = self.projects.write();
-            let mut found = false;
+            let mut found = None;
 
             for project in projects.iter_mut() {
                 let initial_len = project.services.len();
                 project.services.retain(|s| s.id != service_id);
                 if project.services.len() != initial_len {
                     project.updated_at = chrono::Utc::now();
-                    found = true;
+                    found = Some(project.id.clone());
                     break;
                 }
             }
 
-            if !found {
-                return Err(StorageError::ServiceNotFound(service_id.to_string()));
-            }
-        }
-        self.save_projects()?;
+            found.ok_or_else(|| StorageError::ServiceNotFound(service_id.to_string()))?
+        };
+        self.save_project(&project_id, false)?;
+        self.notify(ChangeEvent::Service { project_id, id: service_id.to_string(), change: ChangeKind::Deleted });
         Ok(())
     }
 
@@ -343,7 +613,12 @@ impl Storage {
             project.scripts.push(script);
             project.updated_at = chrono::Utc::now();
         }
-        self.save_projects()?;
+        self.save_project(project_id, false)?;
+        self.notify(ChangeEvent::Script {
+            project_id: project_id.to_string(),
+            id: script_clone.id.clone(),
+            change: ChangeKind::Created,
+        });
         Ok(script_clone)
     }
 
@@ -352,14 +627,14 @@ impl Storage {
         script_id: &str,
         updater: impl FnOnce(&mut Script),
     ) -> Result<Script, StorageError> {
-        let script = {
+        let (project_id, script) = {
             let mut projects = self.projects.write();
             let mut found_script = None;
 
             for project in projects.iter_mut() {
                 if let Some(script) = project.scripts.iter_mut().find(|s| s.id == script_id) {
                     updater(script);
-                    found_script = Some(script.clone());
+                    found_script = Some((project.id.clone(), script.clone()));
                     project.updated_at = chrono::Utc::now();
                     break;
                 }
@@ -367,30 +642,30 @@ impl Storage {
 
             found_script.ok_or_else(|| StorageError::ScriptNotFound(script_id.to_string()))?
         };
-        self.save_projects()?;
+        self.save_project(&project_id, false)?;
+        self.notify(ChangeEvent::Script { project_id, id: script.id.clone(), change: ChangeKind::Updated });
         Ok(script)
     }
 
     pub fn delete_script(&self, script_id: &str) -> Result<(), StorageError> {
-        {
+        let project_id = {
             let mut projects = self.projects.write();
-            let mut found = false;
+            let mut found = None;
 
             for project in projects.iter_mut() {
                 let initial_len = project.scripts.len();
                 project.scripts.retain(|s| s.id != script_id);
                 if project.scripts.len() != initial_len {
                     project.updated_at = chrono::Utc::now();
-                    found = true;
+                    found = Some(project.id.clone());
                     break;
                 }
             }
 
-            if !found {
-                return Err(StorageError::ScriptNotFound(script_id.to_string()));
-            }
-        }
-        self.save_projects()?;
+            found.ok_or_else(|| StorageError::ScriptNotFound(script_id.to_string()))?
+        };
+        self.save_project(&project_id, false)?;
+        self.notify(ChangeEvent::Script { project_id, id: script_id.to_string(), change: ChangeKind::Deleted });
         Ok(())
     }
 
@@ -421,7 +696,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.settings_path(), &*self.settings.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::Settings, result)
     }
 
     pub fn get_settings(&self) -> AppSettings {
@@ -431,6 +706,7 @@ impl Storage {
     pub fn update_settings(&self, settings: AppSettings) -> Result<(), StorageError> {
         *self.settings.write() = settings;
         self.save_settings()?;
+        self.notify(ChangeEvent::Settings);
         Ok(())
     }
 
@@ -441,8 +717,12 @@ impl Storage {
     fn load_global_scripts(&self) -> Result<(), StorageError> {
         let path = self.global_scripts_path();
         if path.exists() {
-            let scripts: Vec<GlobalScript> = read_json_locked(&path)?;
-            *self.global_scripts.write() = scripts;
+            let loaded: Vec<GlobalScript> = read_json_locked(&path)?;
+            let mut scripts = self.global_scripts.write();
+            *scripts = loaded;
+            if let Some(index) = self.script_index.write().as_mut() {
+                index.sync(&scripts);
+            }
         }
         Ok(())
     }
@@ -451,7 +731,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.global_scripts_path(), &*self.global_scripts.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::GlobalScripts, result)
     }
 
     pub fn get_all_global_scripts(&self) -> Vec<GlobalScript> {
@@ -473,8 +753,10 @@ impl Storage {
         {
             let mut scripts = self.global_scripts.write();
             scripts.push(script.clone());
+            self.update_script_index(|index| index.upsert(&script));
         }
         self.save_global_scripts()?;
+        self.notify(ChangeEvent::GlobalScript { id: script.id.clone(), change: ChangeKind::Created });
         Ok(script)
     }
 
@@ -492,9 +774,11 @@ impl Storage {
 
             updater(script);
             script.updated_at = chrono::Utc::now();
+            self.update_script_index(|index| index.upsert(script));
             script.clone()
         };
         self.save_global_scripts()?;
+        self.notify(ChangeEvent::GlobalScript { id: script.id.clone(), change: ChangeKind::Updated });
         Ok(script)
     }
 
@@ -506,11 +790,166 @@ impl Storage {
             if scripts.len() == initial_len {
                 return Err(StorageError::GlobalScriptNotFound(id.to_string()));
             }
+            self.update_script_index(|index| {
+                index.remove(id);
+            });
         }
         self.save_global_scripts()?;
+        self.notify(ChangeEvent::GlobalScript { id: id.to_string(), change: ChangeKind::Deleted });
         Ok(())
     }
 
+    /// Scripts matching a search query (`tag:`, `folder:` and plain text
+    /// terms, see `ScriptQuery`), in storage order. Uses the search index,
+    /// building it on first use.
+    pub fn search_global_scripts(&self, query: &str) -> Vec<GlobalScript> {
+        let scripts = self.global_scripts.read();
+        let mut index = self.script_index.write();
+        let index = index.get_or_insert_with(|| ScriptSearchIndex::build(&scripts));
+        index.query(query).into_iter().map(|pos| scripts[pos].clone()).collect()
+    }
+
+    /// Apply `f` to the search index if it has been built. Called with the
+    /// `global_scripts` write lock held, so the index never lags behind.
+    fn update_script_index(&self, f: impl FnOnce(&mut ScriptSearchIndex)) {
+        if let Some(index) = self.script_index.write().as_mut() {
+            f(index);
+        }
+    }
+
+    /// Run the configured organize rules over all scripts or `script_ids`.
+    /// A dry run only reports the planned changes; otherwise they're applied
+    /// in a single save.
+    pub fn apply_organize_rules(
+        &self,
+        script_ids: Option<&[String]>,
+        dry_run: bool,
+    ) -> Result<OrganizeResult, StorageError> {
+        let rules = self.settings.read().scripts_config.organize_rules.clone();
+        let changes = {
+            let mut scripts = self.global_scripts.write();
+            let changes = organize_rules::plan_changes(&rules, &scripts, script_ids);
+            if !dry_run {
+                let now = chrono::Utc::now();
+                for change in &changes {
+                    if let Some(script) = scripts.iter_mut().find(|s| s.id == change.script_id) {
+                        change.apply(script);
+                        script.updated_at = now;
+                        self.update_script_index(|index| index.upsert(script));
+                    }
+                }
+            }
+            changes
+        };
+        if !dry_run && !changes.is_empty() {
+            self.save_global_scripts()?;
+            self.observers.notify(changes.iter().map(|change| ChangeEvent::GlobalScript {
+                id: change.script_id.clone(),
+                change: ChangeKind::Updated,
+            }));
+        }
+        Ok(OrganizeResult::new(changes, !dry_run))
+    }
+
+    /// Scan `folder`, or the configured `scripts_config.main_folder` when
+    /// `None`, with the configured extensions, ignored patterns and limits.
+    ///
+    /// Auto-discovered scripts whose file was moved or renamed are pointed at
+    /// the new path (`relocated`); possible moves that can't be told apart are
+    /// left to the user (`unresolved_moves`), and scripts whose file is gone
+    /// for good are flagged (`vanished`). `scripts` lists every file found,
+    /// registered or not.
+    pub fn scan_scripts_folder(&self, folder: Option<&str>) -> Result<ScriptScanResult, StorageError> {
+        let config = self.settings.read().scripts_config.clone();
+        let Some(folder) = folder.map(String::from).or(config.main_folder.clone()).filter(|f| !f.is_empty()) else {
+            return Err(StorageError::NoScriptsFolder);
+        };
+        let mut result = script_discovery::scan_folder(
+            &folder,
+            &config.scan_extensions,
+            &config.ignored_patterns,
+            &ScanBudget::from_config(&config),
+        );
+
+        let scripts = self.get_all_global_scripts();
+        let plan = script_relocation::plan_relocations(&scripts, &result.scripts, |path| Path::new(path).exists());
+        result.unresolved_moves = plan.unresolved;
+        for relocation in plan.relocated {
+            let hash = result
+                .scripts
+                .iter()
+                .find(|s| s.path == relocation.new_path)
+                .and_then(|s| s.content_hash.clone());
+            let updated = self.update_global_script(&relocation.script_id, |script| {
+                script.script_path = Some(relocation.new_path.clone());
+                if script.content_hash.is_none() {
+                    script.content_hash = hash;
+                }
+            });
+            match updated {
+                Ok(_) => result.relocated.push(relocation),
+                Err(e) => log::warn!("Failed to relocate script {}: {}", relocation.script_id, e),
+            }
+        }
+
+        result.vanished = self
+            .global_scripts
+            .read()
+            .iter()
+            .filter(|s| s.auto_discovered)
+            .filter_map(|s| {
+                let path = s.script_path.as_deref()?;
+                (!Path::new(path).exists()).then(|| VanishedScript {
+                    script_id: s.id.clone(),
+                    script_name: s.name.clone(),
+                    path: path.to_string(),
+                })
+            })
+            .collect();
+        Ok(result)
+    }
+
+    /// Import script files found by [`Storage::scan_scripts_folder`] as
+    /// auto-discovered global scripts (see [`script_discovery::to_global_script`]),
+    /// in one save, then sort them with the organize rules. Paths some script
+    /// already points at are skipped, so importing a re-scan never duplicates.
+    pub fn import_discovered_scripts(&self, paths: &[String]) -> Result<DiscoveredImport, StorageError> {
+        let templates = self.settings.read().scripts_config.command_templates.clone();
+        let mut result = DiscoveredImport::default();
+        {
+            let mut scripts = self.global_scripts.write();
+            for path in paths {
+                let discovered = script_discovery::discover_path(Path::new(path));
+                let registered = |p: &str| scripts.iter().any(|s| s.script_path.as_deref() == Some(p));
+                let Some(discovered) = discovered.filter(|d| !registered(path) && !registered(&d.path)) else {
+                    result.skipped.push(path.clone());
+                    continue;
+                };
+                let mut script = script_discovery::to_global_script(&discovered, &templates);
+                script.order = scripts.len() as u32;
+                self.update_script_index(|index| index.upsert(&script));
+                scripts.push(script.clone());
+                result.imported.push(script);
+            }
+        }
+        if result.imported.is_empty() {
+            return Ok(result);
+        }
+        self.save_global_scripts()?;
+        self.observers.notify(result.imported.iter().map(|script| ChangeEvent::GlobalScript {
+            id: script.id.clone(),
+            change: ChangeKind::Created,
+        }));
+
+        let ids: Vec<String> = result.imported.iter().map(|s| s.id.clone()).collect();
+        if !self.settings.read().scripts_config.organize_rules.is_empty() {
+            result.organized = self.apply_organize_rules(Some(&ids), false)?.changes.len();
+            let scripts = self.global_scripts.read();
+            result.imported = scripts.iter().filter(|s| ids.contains(&s.id)).cloned().collect();
+        }
+        Ok(result)
+    }
+
     // ========================================================================
     // Tag Definitions
     // ========================================================================
@@ -528,7 +967,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.tag_definitions_path(), &*self.tag_definitions.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::TagDefinitions, result)
     }
 
     pub fn get_all_tag_definitions(&self) -> Vec<TagDefinition> {
@@ -606,7 +1045,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.execution_history_path(), &*self.execution_history.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::ExecutionHistory, result)
     }
 
     pub fn add_execution_record(
@@ -651,6 +1090,72 @@ impl Storage {
             .collect()
     }
 
+    pub fn get_execution_record(&self, id: &str) -> Option<ExecutionRecord> {
+        self.execution_history.read().iter().find(|r| r.id == id).cloned()
+    }
+
+    // Service runs go into the same history, keyed by service id. A record
+    // opens when the service reports running and closes when it stops; the
+    // exit code, when there is one, arrives right after the stop.
+
+    /// Open a record for a run of `service_id` started at `started_at`,
+    /// closing one still open from a run whose stop was never seen. The
+    /// open run reported running again (after being unhealthy) is kept.
+    pub fn record_service_started(
+        &self,
+        service_id: &str,
+        started_at: chrono::DateTime<chrono::Utc>,
+        mode: Option<&str>,
+        arg_preset: Option<&str>,
+    ) -> Result<(), StorageError> {
+        {
+            let mut history = self.execution_history.write();
+            if let Some(open) = latest_run_mut(&mut history, service_id).filter(|r| r.finished_at.is_none()) {
+                if open.started_at == started_at {
+                    return Ok(());
+                }
+                finish_run(open, true);
+            }
+            let mut record = ExecutionRecord::new(service_id.to_string());
+            record.started_at = started_at;
+            record.preset_name = arg_preset.map(str::to_string);
+            if let Some(mode) = mode {
+                record.parameters_used.insert("mode".to_string(), mode.to_string());
+            }
+            history.push(record);
+        }
+        self.save_execution_history()
+    }
+
+    /// Close the open run of `service_id`, if any. `success` is false when
+    /// the service failed to start.
+    pub fn record_service_stopped(&self, service_id: &str, success: bool) -> Result<(), StorageError> {
+        {
+            let mut history = self.execution_history.write();
+            match latest_run_mut(&mut history, service_id).filter(|r| r.finished_at.is_none()) {
+                Some(open) => finish_run(open, success),
+                None => return Ok(()),
+            }
+        }
+        self.save_execution_history()
+    }
+
+    /// Add the exit code to the run of `service_id` its stop just closed. A
+    /// run only counts as successful if the process exited with 0.
+    pub fn record_service_exit(&self, service_id: &str, exit_code: Option<i32>) -> Result<(), StorageError> {
+        {
+            let mut history = self.execution_history.write();
+            match latest_run_mut(&mut history, service_id).filter(|r| r.finished_at.is_some() && r.exit_code.is_none()) {
+                Some(run) => {
+                    run.exit_code = exit_code;
+                    run.success = run.success && exit_code == Some(0);
+                }
+                None => return Ok(()),
+            }
+        }
+        self.save_execution_history()
+    }
+
     pub fn clear_execution_history(&self, script_id: &str) -> Result<(), StorageError> {
         {
             let mut history = self.execution_history.write();
@@ -677,7 +1182,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.tools_path(), &*self.tools.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::Tools, result)
     }
 
     pub fn get_all_tools(&self) -> Vec<Tool> {
@@ -747,7 +1252,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.aliases_path(), &*self.aliases.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::Aliases, result)
     }
 
     pub fn get_all_aliases(&self) -> Vec<ShellAlias> {
@@ -922,7 +1427,7 @@ impl Storage {
             &*self.status_definitions.read(),
         );
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::StatusDefinitions, result)
     }
 
     pub fn get_all_status_definitions(&self) -> Vec<StatusDefinition> {
@@ -999,7 +1504,7 @@ impl Storage {
         self.set_suppress_watcher();
         let result = write_json_locked(&self.apps_path(), &*self.apps.read());
         self.clear_suppress_watcher();
-        result
+        self.record_save(StorageFile::Apps, result)
     }
 
     pub fn get_all_apps(&self) -> Vec<App> {
@@ -1069,6 +1574,11 @@ impl Storage {
         self.load_aliases()?;
         self.load_status_definitions()?;
         self.load_apps()?;
+        // Memory matches the disk again; unsaved changes are gone
+        let had_pending = !std::mem::take(&mut *self.pending_saves.lock()).is_empty();
+        if had_pending {
+            self.notify(ChangeEvent::SaveStatus);
+        }
         Ok(())
     }
 
@@ -1103,10 +1613,142 @@ impl Storage {
         serde_json::to_string_pretty(&export).map_err(StorageError::Json)
     }
 
+    /// Write the export to `path` and remember its directory for the next
+    /// dialog / prompt. The target is checked before anything is serialized.
+    pub fn export_scripts_config_to(&self, path: &Path) -> Result<(), StorageError> {
+        export_file::check_writable(path)
+            .map_err(|e| StorageError::Io(std::io::Error::new(std::io::ErrorKind::PermissionDenied, e)))?;
+        let json = self.export_scripts_config()?;
+        fs::write(path, json)?;
+        self.remember_export_dir(path)
+    }
+
+    /// Export the global scripts as a directory: one file per script under
+    /// `scripts/` (see [`export_file::script_file_names`]) and a
+    /// `manifest.json` with their order, folders and the tag / status
+    /// definitions. Script files that aren't part of this export are removed,
+    /// so the directory can be committed as is. Returns the number of scripts.
+    pub fn export_scripts_to_dir(&self, dir: &Path) -> Result<usize, StorageError> {
+        let scripts_dir = dir.join(export_file::SCRIPTS_SUBDIR);
+        fs::create_dir_all(&scripts_dir)?;
+
+        let scripts = self.get_all_global_scripts();
+        let names = export_file::script_file_names(&scripts);
+        let mut folders: std::collections::BTreeMap<String, Vec<String>> = Default::default();
+        for (script, name) in scripts.iter().zip(&names) {
+            write_replacing(&scripts_dir.join(name), serde_json::to_string_pretty(script)?.as_bytes())?;
+            if let Some(folder) = script.folder.as_deref().filter(|f| !f.is_empty()) {
+                folders.entry(folder.to_string()).or_default().push(name.clone());
+            }
+        }
+
+        // Deleted or renamed scripts
+        for entry in fs::read_dir(&scripts_dir)? {
+            let path = entry?.path();
+            let stale = match path.file_name().and_then(|n| n.to_str()) {
+                Some(file) => file.ends_with(".json") && !names.iter().any(|n| n == file),
+                None => false,
+            };
+            if stale {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        let manifest = ScriptDirManifest {
+            version: export_file::SCRIPT_DIR_VERSION.to_string(),
+            scripts: names,
+            folders,
+            tag_definitions: self.get_all_tag_definitions(),
+            status_definitions: self.get_all_status_definitions(),
+        };
+        write_replacing(
+            &dir.join(export_file::MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest)?.as_bytes(),
+        )?;
+        Ok(scripts.len())
+    }
+
+    /// Import a directory written by [`Storage::export_scripts_to_dir`]. The
+    /// files the manifest lists come first, in its order, then any other
+    /// script file by name (added by hand or merged in from another branch).
+    /// Merges like `import_scripts_config`: known script ids are skipped.
+    pub fn import_scripts_from_dir(&self, dir: &Path) -> Result<ImportResult, StorageError> {
+        let invalid = |file: &str, e: serde_json::Error| {
+            StorageError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", file, e)))
+        };
+        let manifest_path = dir.join(export_file::MANIFEST_FILE);
+        let manifest: ScriptDirManifest = if manifest_path.exists() {
+            let content = text_file::read_to_string(&manifest_path)?;
+            serde_json::from_str(&content).map_err(|e| invalid(export_file::MANIFEST_FILE, e))?
+        } else {
+            ScriptDirManifest::default()
+        };
+
+        let scripts_dir = dir.join(export_file::SCRIPTS_SUBDIR);
+        let mut files: Vec<String> = fs::read_dir(&scripts_dir)?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| name.ends_with(".json"))
+            .collect();
+        files.sort();
+        let mut ordered: Vec<&String> = manifest.scripts.iter().filter(|n| files.contains(n)).collect();
+        ordered.extend(files.iter().filter(|n| !manifest.scripts.contains(n)));
+
+        let mut scripts = Vec::with_capacity(ordered.len());
+        for file in ordered {
+            let content = text_file::read_to_string(&scripts_dir.join(file))?;
+            scripts.push(serde_json::from_str::<GlobalScript>(&content).map_err(|e| invalid(file.as_str(), e))?);
+        }
+
+        let import = ScriptExport {
+            version: manifest.version,
+            scripts,
+            groups: Vec::new(),
+            tools: Vec::new(),
+            tag_definitions: manifest.tag_definitions,
+            aliases: Vec::new(),
+            apps: Vec::new(),
+            status_definitions: manifest.status_definitions,
+            projects: Vec::new(),
+            settings: None,
+            exported_at: chrono::Utc::now(),
+        };
+        let options = ImportOptions {
+            projects: false,
+            scripts: true,
+            tools: false,
+            apps: false,
+            shell_config: false,
+            tags_and_statuses: true,
+            settings: false,
+        };
+        self.import_export(import, &options)
+    }
+
+    /// Read an import file and preview it (see [`Storage::preview_import`]),
+    /// remembering its directory. Returns the file content for the actual
+    /// import, as UTF-8 whatever encoding the file was saved in.
+    pub fn read_import_file(&self, path: &Path) -> Result<(String, ExportSummary), StorageError> {
+        let json = text_file::read_to_string(path)?;
+        let summary = Self::preview_import(&json)?;
+        self.remember_export_dir(path)?;
+        Ok((json, summary))
+    }
+
+    fn remember_export_dir(&self, path: &Path) -> Result<(), StorageError> {
+        let dir = export_file::dir_to_remember(path);
+        if dir.is_none() || self.settings.read().last_export_dir == dir {
+            return Ok(());
+        }
+        self.settings.write().last_export_dir = dir;
+        self.save_settings()?;
+        self.notify(ChangeEvent::Settings);
+        Ok(())
+    }
+
     /// Preview an import file and return counts per category without side effects
     pub fn preview_import(json: &str) -> Result<ExportSummary, StorageError> {
         let import: ScriptExport =
-            serde_json::from_str(json).map_err(StorageError::Json)?;
+            serde_json::from_str(text_file::strip_bom(json)).map_err(StorageError::Json)?;
         Ok(ExportSummary {
             version: import.version,
             exported_at: import.exported_at,
@@ -1125,8 +1767,52 @@ impl Storage {
     /// Merges with existing data (skips items with duplicate IDs/names).
     pub fn import_scripts_config(&self, json: &str, options: &ImportOptions) -> Result<ImportResult, StorageError> {
         let import: ScriptExport =
-            serde_json::from_str(json).map_err(StorageError::Json)?;
+            serde_json::from_str(text_file::strip_bom(json)).map_err(StorageError::Json)?;
+        self.import_export(import, options)
+    }
+
+    /// Compare the scripts of a newer version of an export with the ones
+    /// imported from it earlier. Nothing is changed until the plan is passed
+    /// to [`Storage::apply_sync_plan`].
+    pub fn sync_with_export(&self, json: &str) -> Result<SyncPlan, StorageError> {
+        let import: ScriptExport = serde_json::from_str(text_file::strip_bom(json)).map_err(StorageError::Json)?;
+        Ok(script_sync::plan_sync(&self.global_scripts.read(), &import.scripts))
+    }
+
+    /// Apply a plan from [`Storage::sync_with_export`], with per-script
+    /// `resolutions` keyed by source id, in one save.
+    pub fn apply_sync_plan(
+        &self,
+        plan: &SyncPlan,
+        resolutions: &HashMap<String, SyncResolution>,
+    ) -> Result<SyncResult, StorageError> {
+        let result = {
+            let mut scripts = self.global_scripts.write();
+            let result = script_sync::apply_plan(&mut scripts, plan, resolutions, chrono::Utc::now());
+            for id in &result.changed_ids {
+                if let Some(script) = scripts.iter().find(|s| &s.id == id) {
+                    self.update_script_index(|index| index.upsert(script));
+                }
+            }
+            result
+        };
+        if !result.changed_ids.is_empty() {
+            self.save_global_scripts()?;
+            let added: Vec<&String> = plan
+                .entries
+                .iter()
+                .filter(|e| e.status == SyncStatus::Added)
+                .map(|e| &e.upstream.id)
+                .collect();
+            self.observers.notify(result.changed_ids.iter().map(|id| ChangeEvent::GlobalScript {
+                id: id.clone(),
+                change: if added.contains(&id) { ChangeKind::Created } else { ChangeKind::Updated },
+            }));
+        }
+        Ok(result)
+    }
 
+    fn import_export(&self, import: ScriptExport, options: &ImportOptions) -> Result<ImportResult, StorageError> {
         let mut scripts_added = 0u32;
         let mut skipped = 0u32;
         let mut tools_added = 0u32;
@@ -1136,6 +1822,7 @@ impl Storage {
         let mut status_definitions_added = 0u32;
         let mut projects_added = 0u32;
         let mut settings_imported = false;
+        let mut changes = Vec::new();
 
         // Import tag definitions
         if options.tags_and_statuses {
@@ -1190,13 +1877,18 @@ impl Storage {
         // Import scripts (legacy import.groups is ignored — feature removed)
         if options.scripts {
             let existing_scripts = self.get_all_global_scripts();
-            for script in import.scripts {
+            let now = chrono::Utc::now();
+            for mut script in import.scripts {
                 if existing_scripts.iter().any(|s| s.id == script.id) {
                     skipped += 1;
                     continue;
                 }
+                // Tracked so a later version of the export can be synced in
+                script.origin = Some(script_sync::origin_of(&script, now));
+                changes.push(ChangeEvent::GlobalScript { id: script.id.clone(), change: ChangeKind::Created });
                 {
                     let mut scripts = self.global_scripts.write();
+                    self.update_script_index(|index| index.upsert(&script));
                     scripts.push(script);
                 }
                 scripts_added += 1;
@@ -1271,6 +1963,7 @@ impl Storage {
                     skipped += 1;
                     continue;
                 }
+                changes.push(ChangeEvent::Project { id: project.id.clone(), change: ChangeKind::Created });
                 {
                     let mut projects = self.projects.write();
                     projects.push(project);
@@ -1288,6 +1981,7 @@ impl Storage {
                 *self.settings.write() = imported_settings;
                 self.save_settings()?;
                 settings_imported = true;
+                changes.push(ChangeEvent::Settings);
             }
         }
 
@@ -1295,6 +1989,7 @@ impl Storage {
         if aliases_added > 0 {
             let _ = crate::shim::sync_all(&self.shim_dir(), &self.get_all_aliases());
         }
+        self.observers.notify(changes);
 
         Ok(ImportResult {
             scripts_added,
@@ -1309,6 +2004,203 @@ impl Storage {
         })
     }
 
+    /// Version written into project exports; imports accept the same major
+    const PROJECT_EXPORT_VERSION: &'static str = "1.0";
+
+    /// Export the projects alone, for moving them to another machine. Env
+    /// values are stripped as in `export_scripts_config`.
+    pub fn export_projects_config(&self) -> Result<String, StorageError> {
+        let export = ProjectExport {
+            version: Self::PROJECT_EXPORT_VERSION.to_string(),
+            projects: self.get_all_projects().iter().map(|p| p.sanitized_for_output()).collect(),
+            exported_at: chrono::Utc::now(),
+        };
+        serde_json::to_string_pretty(&export).map_err(StorageError::Json)
+    }
+
+    /// Merge projects from an `export_projects_config` string. Projects whose
+    /// id is already known are skipped. Added projects whose root path doesn't
+    /// exist here are kept and listed in `needs_path`, to be re-pointed.
+    pub fn import_projects_config(&self, json: &str) -> Result<ProjectImportResult, StorageError> {
+        let import: ProjectExport = serde_json::from_str(json).map_err(StorageError::Json)?;
+        let major = |version: &str| version.split('.').next().unwrap_or("").to_string();
+        if major(&import.version) != major(Self::PROJECT_EXPORT_VERSION) {
+            return Err(StorageError::UnsupportedExportVersion(import.version));
+        }
+
+        let mut result = ProjectImportResult::default();
+        let mut changes = Vec::new();
+        {
+            let mut projects = self.projects.write();
+            for project in import.projects {
+                if projects.iter().any(|p| p.id == project.id) {
+                    result.skipped += 1;
+                    continue;
+                }
+                if !Path::new(&project.root_path).exists() {
+                    result.needs_path.push(PathRemapRequest {
+                        project_id: project.id.clone(),
+                        project_name: project.name.clone(),
+                        old_root_path: project.root_path.clone(),
+                    });
+                }
+                changes.push(ChangeEvent::Project { id: project.id.clone(), change: ChangeKind::Created });
+                projects.push(project);
+                result.projects_added += 1;
+            }
+        }
+        if result.projects_added > 0 {
+            self.save_projects()?;
+        }
+        self.observers.notify(changes);
+        Ok(result)
+    }
+
+    // ========================================================================
+    // Data-dir migration
+    // ========================================================================
+
+    /// Preview merging another machine's data dir into this one. Side-effect
+    /// free: classifies every item, lists projects whose root path is missing
+    /// here (`needs_path`) and images to copy. Apply with `apply_path_mappings`.
+    pub fn import_from_data_dir(
+        &self,
+        dir: &Path,
+        options: &ImportOptions,
+    ) -> Result<MigrationReport, StorageError> {
+        let foreign = migration::read_data_dir(dir)?;
+        let import = &foreign.export;
+        let mut items = Vec::new();
+
+        if options.projects {
+            items.extend(migration::classify(
+                "project",
+                &import.projects,
+                &self.get_all_projects(),
+                |p| (p.id.as_str(), p.name.as_str()),
+                |a, b| a.id == b.id,
+            ));
+        }
+        if options.scripts {
+            items.extend(migration::classify(
+                "script",
+                &import.scripts,
+                &self.get_all_global_scripts(),
+                |s| (s.id.as_str(), s.name.as_str()),
+                |a, b| a.id == b.id,
+            ));
+        }
+        if options.tools {
+            items.extend(migration::classify(
+                "tool",
+                &import.tools,
+                &self.get_all_tools(),
+                |t| (t.id.as_str(), t.name.as_str()),
+                |a, b| a.id == b.id,
+            ));
+        }
+        if options.apps {
+            items.extend(migration::classify(
+                "app",
+                &import.apps,
+                &self.get_all_apps(),
+                |a| (a.id.as_str(), a.name.as_str()),
+                |a, b| a.id == b.id,
+            ));
+        }
+        if options.shell_config {
+            items.extend(migration::classify(
+                "alias",
+                &import.aliases,
+                &self.get_all_aliases(),
+                |a| (a.id.as_str(), a.name.as_str()),
+                |a, b| a.id == b.id || a.name.to_lowercase() == b.name.to_lowercase(),
+            ));
+        }
+        if options.tags_and_statuses {
+            items.extend(migration::classify(
+                "tag",
+                &import.tag_definitions,
+                &self.get_all_tag_definitions(),
+                |d| (d.name.as_str(), d.name.as_str()),
+                |a, b| a.name.to_lowercase() == b.name.to_lowercase(),
+            ));
+            items.extend(migration::classify(
+                "status",
+                &import.status_definitions,
+                &self.get_all_status_definitions(),
+                |d| (d.name.as_str(), d.name.as_str()),
+                |a, b| a.name.to_lowercase() == b.name.to_lowercase(),
+            ));
+        }
+
+        // Only projects that will actually be added need a path or an image
+        let mut needs_path = Vec::new();
+        let mut images_to_copy = Vec::new();
+        for project in &import.projects {
+            let is_new = items.iter().any(|i| {
+                i.kind == "project" && i.id == project.id && i.status == MigrationItemStatus::New
+            });
+            if !is_new {
+                continue;
+            }
+            if !Path::new(&project.root_path).exists() {
+                needs_path.push(PathRemapRequest {
+                    project_id: project.id.clone(),
+                    project_name: project.name.clone(),
+                    old_root_path: project.root_path.clone(),
+                });
+            }
+            if let Some(image) = migration::foreign_image(dir, project) {
+                images_to_copy.push(image.to_string_lossy().to_string());
+            }
+        }
+
+        Ok(MigrationReport {
+            source_dir: dir.to_string_lossy().to_string(),
+            items,
+            needs_path,
+            images_to_copy,
+            has_settings: options.settings && import.settings.is_some(),
+            problems: foreign.problems,
+            ignored_files: foreign.ignored_files,
+        })
+    }
+
+    /// Apply a data-dir migration previewed with `import_from_data_dir`.
+    /// Projects listed in `mappings` are moved to their new root first; the
+    /// rest is merged like a regular import (conflicts are skipped). Images of
+    /// added projects are copied into the local images dir.
+    pub fn apply_path_mappings(
+        &self,
+        dir: &Path,
+        options: &ImportOptions,
+        mappings: &[PathMapping],
+    ) -> Result<ImportResult, StorageError> {
+        let mut foreign = migration::read_data_dir(dir)?;
+        let local_ids: Vec<String> = self.get_all_projects().into_iter().map(|p| p.id).collect();
+
+        for project in &mut foreign.export.projects {
+            if let Some(mapping) = mappings.iter().find(|m| m.project_id == project.id) {
+                migration::remap_project_root(project, &mapping.new_root_path);
+            }
+            if !options.projects || local_ids.contains(&project.id) {
+                continue;
+            }
+            if let Some(src) = migration::foreign_image(dir, project) {
+                if let Some(file_name) = src.file_name() {
+                    let dest = self.images_dir().join(file_name);
+                    if !dest.exists() {
+                        fs::copy(&src, &dest)?;
+                    }
+                    project.image_path = Some(dest.to_string_lossy().to_string());
+                }
+            }
+        }
+
+        self.import_export(foreign.export, options)
+    }
+
     // ========================================================================
     // Git Backup
     // ========================================================================
@@ -1348,9 +2240,8 @@ impl Storage {
         // Copy each data file to the repo
         let mut copied = 0usize;
         for filename in Self::BACKUP_FILES {
-            let src = self.app_dir.join(filename);
-            if src.exists() {
-                fs::copy(&src, repo.join(filename))?;
+            if let Some(content) = self.backup_content(filename)? {
+                fs::write(repo.join(filename), content)?;
                 copied += 1;
             }
         }
@@ -1411,4 +2302,522 @@ impl Storage {
 
         Ok(format!("Backed up and pushed {} files", copied))
     }
+
+    // ========================================================================
+    // Zip Backups
+    // ========================================================================
+
+    /// Data files a zip backup holds: the git backup's plus execution history
+    pub(crate) const ARCHIVE_FILES: &'static [&'static str] = &[
+        "projects.json",
+        "global_scripts.json",
+        "tools.json",
+        "aliases.json",
+        "apps.json",
+        "tag_definitions.json",
+        "status_definitions.json",
+        "settings.json",
+        "execution_history.json",
+    ];
+
+    /// Entries larger than this are refused on restore
+    const MAX_ARCHIVE_ENTRY_BYTES: u64 = 256 * 1024 * 1024;
+
+    /// Content of the data file `name` as backups hold it: the projects are
+    /// gathered from their files into one `projects.json`, which restoring
+    /// splits again like a legacy data dir's.
+    fn backup_content(&self, name: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        if name == project_files::LEGACY_FILE {
+            return Ok(Some(serde_json::to_vec_pretty(&*self.projects.read())?));
+        }
+        let path = self.app_dir.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(&path)?))
+    }
+
+    pub fn backups_dir(&self) -> PathBuf {
+        self.app_dir.join("backups")
+    }
+
+    /// Zip the data files into `backups/cortx-backup-<timestamp>.zip`, then
+    /// prune backups beyond `AppSettings::backups.keep`.
+    pub fn create_backup(&self) -> Result<BackupInfo, StorageError> {
+        let dir = self.backups_dir();
+        fs::create_dir_all(&dir)?;
+        let name = format!("cortx-backup-{}.zip", chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f"));
+        let path = dir.join(&name);
+        let tmp = path.with_extension("zip.tmp");
+
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        let mut writer = zip::ZipWriter::new(File::create(&tmp)?);
+        for filename in Self::ARCHIVE_FILES {
+            let Some(content) = self.backup_content(filename)? else {
+                continue;
+            };
+            writer.start_file(*filename, options).map_err(backup_error)?;
+            writer.write_all(&content)?;
+        }
+        writer.finish().map_err(backup_error)?;
+        fs::rename(&tmp, &path)?;
+
+        self.prune_backups(self.get_settings().backups.keep)?;
+        backup_info(&path).ok_or_else(|| StorageError::Backup(format!("Backup {} disappeared", name)))
+    }
+
+    /// Backups under `backups/`, newest first.
+    pub fn list_backups(&self) -> Vec<BackupInfo> {
+        let Ok(entries) = fs::read_dir(self.backups_dir()) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<BackupInfo> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "zip")
+                    && path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("cortx-backup-"))
+            })
+            .filter_map(|path| backup_info(&path))
+            .collect();
+        // Timestamped names sort chronologically
+        backups.sort_by(|a, b| b.name.cmp(&a.name));
+        backups
+    }
+
+    /// Delete all but the `keep` newest backups (at least one is kept).
+    fn prune_backups(&self, keep: usize) -> Result<(), StorageError> {
+        for old in self.list_backups().into_iter().skip(keep.max(1)) {
+            fs::remove_file(&old.path)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the live data files with those in the backup at `path`.
+    ///
+    /// Every entry must be one of the data files and parse as its type, or
+    /// nothing is touched. The current files are backed up first; files
+    /// the archive lacks are left as they are.
+    pub fn restore_backup(&self, path: &Path) -> Result<RestoreResult, StorageError> {
+        use std::io::Read;
+
+        let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(backup_error)?;
+        let mut files: Vec<(String, String)> = Vec::new();
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i).map_err(backup_error)?;
+            let name = entry.name().to_string();
+            if !Self::ARCHIVE_FILES.contains(&name.as_str()) {
+                return Err(StorageError::Backup(format!("Unexpected file in backup: {}", name)));
+            }
+            if entry.size() > Self::MAX_ARCHIVE_ENTRY_BYTES {
+                return Err(StorageError::Backup(format!("{} is too large", name)));
+            }
+            let mut content = String::new();
+            entry.take(Self::MAX_ARCHIVE_ENTRY_BYTES).read_to_string(&mut content)?;
+            validate_data_file(&name, &content)
+                .map_err(|e| StorageError::Backup(format!("{} is not valid: {}", name, e)))?;
+            files.retain(|(n, _)| *n != name);
+            files.push((name, content));
+        }
+        if files.is_empty() {
+            return Err(StorageError::Backup("Backup contains no data files".to_string()));
+        }
+
+        let previous = self.create_backup()?;
+
+        self.set_suppress_watcher();
+        let written: Result<(), StorageError> = files
+            .iter()
+            .try_for_each(|(name, content)| write_replacing(&self.app_dir.join(name), content.as_bytes()));
+        self.clear_suppress_watcher();
+        written?;
+
+        self.reload_all()?;
+        Ok(RestoreResult {
+            restored_files: files.into_iter().map(|(name, _)| name).collect(),
+            previous,
+        })
+    }
+}
+
+fn backup_error(e: zip::result::ZipError) -> StorageError {
+    StorageError::Backup(e.to_string())
+}
+
+fn backup_info(path: &Path) -> Option<BackupInfo> {
+    let meta = fs::metadata(path).ok()?;
+    Some(BackupInfo {
+        name: path.file_name()?.to_string_lossy().into_owned(),
+        path: path.to_string_lossy().into_owned(),
+        size: meta.len(),
+        created_at: meta.modified().ok()?.into(),
+    })
+}
+
+/// Whether `content` parses as the data file `name` holds.
+pub(crate) fn validate_data_file(name: &str, content: &str) -> Result<(), serde_json::Error> {
+    match name {
+        "projects.json" => serde_json::from_str::<Vec<Project>>(content).map(|_| ()),
+        "global_scripts.json" => serde_json::from_str::<Vec<GlobalScript>>(content).map(|_| ()),
+        "tools.json" => serde_json::from_str::<Vec<Tool>>(content).map(|_| ()),
+        "aliases.json" => serde_json::from_str::<Vec<ShellAlias>>(content).map(|_| ()),
+        "apps.json" => serde_json::from_str::<Vec<App>>(content).map(|_| ()),
+        "tag_definitions.json" => serde_json::from_str::<Vec<TagDefinition>>(content).map(|_| ()),
+        "status_definitions.json" => serde_json::from_str::<Vec<StatusDefinition>>(content).map(|_| ()),
+        "settings.json" => serde_json::from_str::<AppSettings>(content).map(|_| ()),
+        "execution_history.json" => serde_json::from_str::<Vec<ExecutionRecord>>(content).map(|_| ()),
+        _ => serde_json::from_str::<serde_json::Value>(content).map(|_| ()),
+    }
+}
+
+/// The most recent record of `id`'s runs
+fn latest_run_mut<'a>(history: &'a mut [ExecutionRecord], id: &str) -> Option<&'a mut ExecutionRecord> {
+    history.iter_mut().rev().find(|r| r.script_id == id)
+}
+
+fn finish_run(record: &mut ExecutionRecord, success: bool) {
+    let now = chrono::Utc::now();
+    record.finished_at = Some(now);
+    record.duration_ms = Some((now - record.started_at).num_milliseconds().max(0) as u64);
+    record.success = success;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn service_runs_are_recorded_in_the_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let started = chrono::Utc::now() - chrono::Duration::seconds(90);
+
+        // Stopped by the user: no exit code
+        storage.record_service_started("svc", started, Some("dev"), None).unwrap();
+        // Healthy again after a failed health check: still the same run
+        storage.record_service_started("svc", started, Some("dev"), None).unwrap();
+        storage.record_service_stopped("svc", true).unwrap();
+        // Crashed: the stop is followed by its exit
+        storage.record_service_started("svc", chrono::Utc::now(), None, Some("verbose")).unwrap();
+        storage.record_service_stopped("svc", true).unwrap();
+        storage.record_service_exit("svc", Some(3)).unwrap();
+        // Restarted before its stop was seen
+        storage.record_service_started("svc", chrono::Utc::now(), None, None).unwrap();
+        storage.record_service_started("svc", chrono::Utc::now(), None, None).unwrap();
+        // Events for other runs don't touch finished ones
+        storage.record_service_exit("svc", Some(0)).unwrap();
+        storage.record_service_stopped("other", true).unwrap();
+
+        let runs = storage.get_execution_history("svc", 10);
+        assert_eq!(runs.len(), 4);
+        let (open, restarted, crashed, stopped) = (&runs[0], &runs[1], &runs[2], &runs[3]);
+        assert!(open.finished_at.is_none());
+        assert!(restarted.finished_at.is_some() && restarted.success);
+
+        assert!(!crashed.success);
+        assert_eq!(crashed.exit_code, Some(3));
+        assert_eq!(crashed.preset_name.as_deref(), Some("verbose"));
+
+        assert!(stopped.success);
+        assert_eq!(stopped.exit_code, None);
+        assert!(stopped.duration_ms.unwrap() >= 90_000);
+        assert_eq!(stopped.parameters_used.get("mode").map(String::as_str), Some("dev"));
+        assert!(storage.get_execution_history("other", 10).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn variants_switch_the_effective_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let project = storage.create_project(Project::new("app".into(), "/code/app".into())).unwrap();
+        let variant = ProjectVariant::new("hotfix".into(), "/code/app-hotfix".into());
+        let variant_id = variant.id.clone();
+        storage.add_project_variant(&project.id, variant).unwrap();
+
+        let switched = storage.set_active_variant(&project.id, Some(&variant_id)).unwrap();
+        assert_eq!(switched.effective_root(), "/code/app-hotfix");
+        assert_eq!(switched.resolve_dir("api"), "/code/app-hotfix/api");
+        assert_eq!(switched.resolve_dir("/code/app/web"), "/code/app-hotfix/web");
+        assert!(matches!(
+            storage.set_active_variant(&project.id, Some("gone")),
+            Err(StorageError::VariantNotFound(_))
+        ));
+
+        // Removing the active variant falls back to the project's own root
+        let removed = storage.remove_project_variant(&project.id, &variant_id).unwrap();
+        assert_eq!((removed.active_variant_id, removed.variants.len()), (None, 0));
+        assert_eq!(storage.get_project(&project.id).unwrap().resolve_dir("."), "/code/app");
+    }
+
+    #[test]
+    fn backup_round_trip_and_pruning() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        storage
+            .create_global_script(GlobalScript::new("deploy".into(), "./deploy.sh".into(), None))
+            .unwrap();
+        let backup = storage.create_backup().unwrap();
+        assert_eq!(storage.list_backups(), vec![backup.clone()]);
+
+        // A bad edit, then the restore brings the script back
+        fs::write(dir.path().join("global_scripts.json"), "[]").unwrap();
+        storage.reload_all().unwrap();
+        assert!(storage.get_all_global_scripts().is_empty());
+        let restored = storage.restore_backup(Path::new(&backup.path)).unwrap();
+        assert!(restored.restored_files.contains(&"global_scripts.json".to_string()));
+        assert_eq!(storage.get_all_global_scripts()[0].name, "deploy");
+        // The pre-restore state was kept as a backup of its own
+        assert_eq!(storage.list_backups()[0], restored.previous);
+
+        let mut settings = storage.get_settings();
+        settings.backups.keep = 2;
+        storage.update_settings(settings).unwrap();
+        for _ in 0..3 {
+            storage.create_backup().unwrap();
+        }
+        assert_eq!(storage.list_backups().len(), 2);
+    }
+
+    #[test]
+    fn writes_replace_the_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        // Left behind by a write that was killed before its rename
+        fs::write(dir.path().join("global_scripts.json.tmp"), "[{\"trunc").unwrap();
+
+        storage
+            .create_global_script(GlobalScript::new("deploy".into(), "./deploy.sh".into(), None))
+            .unwrap();
+        assert!(!dir.path().join("global_scripts.json.tmp").exists());
+        let on_disk: Vec<GlobalScript> =
+            read_json_locked(&dir.path().join("global_scripts.json")).unwrap();
+        assert_eq!(on_disk[0].name, "deploy");
+    }
+
+    #[test]
+    fn restore_rejects_invalid_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let archive = |name: &str, content: &str| {
+            let path = dir.path().join("bad.zip");
+            let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+            writer.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+            writer.finish().unwrap();
+            path
+        };
+
+        let err = storage.restore_backup(&archive("../evil.json", "[]")).unwrap_err();
+        assert!(err.to_string().contains("Unexpected file"));
+        let err = storage.restore_backup(&archive("projects.json", "{\"not\": \"a list\"}")).unwrap_err();
+        assert!(err.to_string().contains("projects.json is not valid"));
+        // Nothing was touched, not even a pre-restore backup taken
+        assert!(storage.list_backups().is_empty());
+    }
+
+    #[test]
+    fn projects_round_trip_through_an_export() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = Storage::with_app_dir(source_dir.path().to_path_buf()).unwrap();
+        let here = source_dir.path().to_string_lossy().to_string();
+        let present = source.create_project(Project::new("present".into(), here)).unwrap();
+        let moved = source.create_project(Project::new("moved".into(), "/nowhere/on/this/machine".into())).unwrap();
+        let json = source.export_projects_config().unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = Storage::with_app_dir(target_dir.path().to_path_buf()).unwrap();
+        let result = target.import_projects_config(&json).unwrap();
+        assert_eq!((result.projects_added, result.skipped), (2, 0));
+        // Imported anyway, flagged for re-pointing
+        assert_eq!(result.needs_path.len(), 1);
+        assert_eq!(result.needs_path[0].project_id, moved.id);
+        let ids: Vec<String> = target.get_all_projects().into_iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![present.id, moved.id]);
+
+        // A second import only finds duplicates
+        let again = target.import_projects_config(&json).unwrap();
+        assert_eq!((again.projects_added, again.skipped), (0, 2));
+        assert_eq!(target.get_all_projects().len(), 2);
+
+        let newer = json.replace("\"version\": \"1.0\"", "\"version\": \"2.0\"");
+        assert!(matches!(
+            target.import_projects_config(&newer),
+            Err(StorageError::UnsupportedExportVersion(v)) if v == "2.0"
+        ));
+    }
+
+    #[test]
+    fn project_roots_are_not_shared_by_accident() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let root = |rel: &str| dir.path().join(rel).to_string_lossy().to_string();
+        let app = storage.create_project(Project::new("app".into(), root("code/app"))).unwrap();
+
+        // Same folder, spelled differently
+        let err = storage.create_project(Project::new("copy".into(), root("code/./app/"))).unwrap_err();
+        assert!(matches!(&err, StorageError::DuplicateProjectRoot { id, .. } if *id == app.id));
+        assert_eq!(storage.get_all_projects().len(), 1);
+        let saved = storage.create_project_checked(Project::new("copy".into(), root("code/app")), true).unwrap();
+        assert_eq!(saved.overlaps[0].relation, RootRelation::Same);
+
+        // Nested roots are allowed, with a warning
+        let web = storage.create_project_checked(Project::new("web".into(), root("code/app/web")), false).unwrap();
+        let relations: Vec<RootRelation> = web.overlaps.iter().map(|o| o.relation).collect();
+        assert_eq!(relations, vec![RootRelation::Inside, RootRelation::Inside]);
+
+        // Moving a root onto another project's is refused and changes nothing
+        let other = storage.create_project(Project::new("other".into(), root("elsewhere"))).unwrap();
+        assert!(storage.update_project(&other.id, |p| p.root_path = root("code/app/web")).is_err());
+        assert_eq!(storage.get_project(&other.id).unwrap().root_path, root("elsewhere"));
+        // Other changes don't re-check the root
+        storage.update_project(&saved.project.id, |p| p.name = "fork".into()).unwrap();
+    }
+
+    #[test]
+    fn imported_scripts_sync_with_a_newer_export() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = Storage::with_app_dir(source_dir.path().to_path_buf()).unwrap();
+        let deploy = source.create_global_script(GlobalScript::new("deploy".into(), "./deploy.sh".into(), None)).unwrap();
+
+        let (dir, target, events) = observed_storage();
+        target.import_scripts_config(&source.export_scripts_config().unwrap(), &ImportOptions::default()).unwrap();
+        let imported = target.get_global_script(&deploy.id).unwrap();
+        assert_eq!(imported.origin.as_ref().unwrap().source_id, deploy.id);
+
+        source.update_global_script(&deploy.id, |s| s.command = "./deploy.sh --all".into()).unwrap();
+        source.create_global_script(GlobalScript::new("lint".into(), "cargo clippy".into(), None)).unwrap();
+        let plan = target.sync_with_export(&source.export_scripts_config().unwrap()).unwrap();
+        assert_eq!(plan.count(SyncStatus::UpstreamUpdated), 1);
+        assert_eq!(plan.count(SyncStatus::Added), 1);
+
+        events.lock().unwrap().clear();
+        let result = target.apply_sync_plan(&plan, &HashMap::new()).unwrap();
+        assert_eq!((result.updated.len(), result.added.len()), (1, 1));
+        assert_eq!(events.lock().unwrap().len(), 2);
+
+        // Saved
+        let reloaded = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        assert_eq!(reloaded.get_global_script(&deploy.id).unwrap().command, "./deploy.sh --all");
+        assert_eq!(reloaded.get_all_global_scripts().len(), 2);
+    }
+
+    /// Storage in a temp dir, and the events it reported so far
+    fn observed_storage() -> (tempfile::TempDir, Storage, Arc<Mutex<Vec<ChangeEvent>>>) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        storage.subscribe(move |event| sink.lock().unwrap().push(event));
+        (dir, storage, events)
+    }
+
+    #[test]
+    fn every_mutation_reports_one_change() {
+        use crate::change_event::ChangeKind::*;
+        let (_dir, storage, events) = observed_storage();
+        let take = || std::mem::take(&mut *events.lock().unwrap());
+
+        let project = storage.create_project(Project::new("shop".into(), "/tmp/shop".into())).unwrap();
+        let p = project.id.clone();
+        assert_eq!(take(), vec![ChangeEvent::Project { id: p.clone(), change: Created }]);
+        storage.update_project(&p, |project| project.name = "store".into()).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Project { id: p.clone(), change: Updated }]);
+
+        let service = Service::new("web".into(), ".".into(), "npm run dev".into());
+        let s = service.id.clone();
+        storage.add_service(&p, service).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Service { project_id: p.clone(), id: s.clone(), change: Created }]);
+        storage.update_service(&s, |service| service.name = "api".into()).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Service { project_id: p.clone(), id: s.clone(), change: Updated }]);
+        storage.delete_service(&s).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Service { project_id: p.clone(), id: s, change: Deleted }]);
+
+        let script = Script::new("build".into(), ".".into(), "npm run build".into());
+        let sc = script.id.clone();
+        storage.add_script(&p, script).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Script { project_id: p.clone(), id: sc.clone(), change: Created }]);
+        storage.update_script(&sc, |script| script.name = "bundle".into()).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Script { project_id: p.clone(), id: sc.clone(), change: Updated }]);
+        storage.delete_script(&sc).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Script { project_id: p.clone(), id: sc, change: Deleted }]);
+
+        let global = storage
+            .create_global_script(GlobalScript::new("deploy".into(), "./deploy.sh".into(), None))
+            .unwrap();
+        let g = global.id.clone();
+        assert_eq!(take(), vec![ChangeEvent::GlobalScript { id: g.clone(), change: Created }]);
+        storage.update_global_script(&g, |script| script.name = "ship".into()).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::GlobalScript { id: g.clone(), change: Updated }]);
+        storage.delete_global_script(&g).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::GlobalScript { id: g, change: Deleted }]);
+
+        storage.update_settings(storage.get_settings()).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Settings]);
+
+        storage.delete_project(&p).unwrap();
+        assert_eq!(take(), vec![ChangeEvent::Project { id: p, change: Deleted }]);
+
+        // Failed mutations report nothing
+        assert!(storage.delete_project("missing").is_err());
+        assert!(storage.update_service("missing", |_| {}).is_err());
+        assert!(take().is_empty());
+    }
+
+    #[test]
+    fn observers_may_read_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let (reader, sink) = (Arc::downgrade(&storage), seen.clone());
+        storage.subscribe(move |event| {
+            if let (ChangeEvent::Project { id, .. }, Some(storage)) = (event, reader.upgrade()) {
+                let name = storage.get_project(&id).map(|p| p.name);
+                sink.lock().unwrap().push(name);
+            }
+        });
+
+        let project = storage.create_project(Project::new("shop".into(), "/tmp/shop".into())).unwrap();
+        storage.update_project(&project.id, |p| p.name = "store".into()).unwrap();
+        storage.delete_project(&project.id).unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![Some("shop".to_string()), Some("store".to_string()), None]);
+    }
+
+    #[test]
+    fn failed_saves_are_reported_and_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        storage.subscribe(move |event| sink.lock().unwrap().push(event));
+
+        // A directory in place of the file makes every write fail
+        let settings_path = dir.path().join("settings.json");
+        let _ = fs::remove_file(&settings_path);
+        fs::create_dir(&settings_path).unwrap();
+        let mut settings = storage.get_settings();
+        settings.last_export_dir = Some("/backups".into());
+
+        assert!(storage.update_settings(settings).is_err());
+        let pending = storage.pending_saves();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].file, StorageFile::Settings);
+        assert!(!pending[0].error.is_empty());
+        assert_eq!(*events.lock().unwrap(), vec![ChangeEvent::SaveStatus]);
+
+        // Still failing: still pending
+        assert_eq!(storage.retry_pending_saves().len(), 1);
+
+        fs::remove_dir(&settings_path).unwrap();
+        assert!(storage.retry_pending_saves().is_empty());
+        let saved: AppSettings = read_json_locked(&settings_path).unwrap();
+        assert_eq!(saved.last_export_dir.as_deref(), Some("/backups"));
+        assert_eq!(events.lock().unwrap().last(), Some(&ChangeEvent::SaveStatus));
+    }
 }
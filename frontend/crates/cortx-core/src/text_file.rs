@@ -0,0 +1,258 @@
+//! Reading text files the way editors on any platform save them.
+//!
+//! Export files and `.env` files come from Notepad, PowerShell and friends as
+//! often as from Unix tools: with a UTF-8 BOM, as UTF-16 (PowerShell 5's
+//! `Out-File`), with CRLF line endings. [`read`] accepts all of these and
+//! hands out plain UTF-8 text with `\n` line endings, remembering the
+//! original [`TextEncoding`], BOM and [`LineEnding`] so [`DecodedText::encode`]
+//! can write an edited version back in the same form.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use thiserror::Error;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// The style most line breaks of `text` use; `Lf` when there are none.
+    /// A file with mixed endings is written back in its majority style.
+    pub fn detect(text: &str) -> Self {
+        let breaks = text.matches('\n').count();
+        let crlf = text.matches("\r\n").count();
+        if crlf > 0 && crlf * 2 >= breaks {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// `text` (with `\n` line breaks) in this style
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            LineEnding::Lf => text.to_string(),
+            LineEnding::CrLf => text.replace('\n', "\r\n"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TextFileError {
+    #[error("Failed to read {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    /// `encoding` is what the content looked like: a BOM, or the NUL bytes
+    /// of UTF-16, or else UTF-8
+    #[error("{path} is not valid {encoding} text")]
+    NotText { path: String, encoding: TextEncoding },
+}
+
+/// A file's text with `\n` line endings, and how it was stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: TextEncoding,
+    pub bom: bool,
+    pub line_ending: LineEnding,
+}
+
+impl DecodedText {
+    /// `text` (with `\n` line breaks) as this file stores it: same line
+    /// endings, encoding and BOM
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let text = self.line_ending.apply(text);
+        let mut out = Vec::with_capacity(text.len() + 3);
+        match self.encoding {
+            TextEncoding::Utf8 => {
+                if self.bom {
+                    out.extend_from_slice(UTF8_BOM);
+                }
+                out.extend_from_slice(text.as_bytes());
+            }
+            TextEncoding::Utf16Le => {
+                if self.bom {
+                    out.extend_from_slice(UTF16LE_BOM);
+                }
+                out.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            }
+            TextEncoding::Utf16Be => {
+                if self.bom {
+                    out.extend_from_slice(UTF16BE_BOM);
+                }
+                out.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            }
+        }
+        out
+    }
+}
+
+/// Decode file content. On failure the error is the encoding the bytes
+/// looked like.
+pub fn decode(bytes: &[u8]) -> Result<DecodedText, TextEncoding> {
+    let (encoding, bom, body) = if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+        (TextEncoding::Utf8, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16LE_BOM) {
+        (TextEncoding::Utf16Le, true, body)
+    } else if let Some(body) = bytes.strip_prefix(UTF16BE_BOM) {
+        (TextEncoding::Utf16Be, true, body)
+    } else {
+        (guess_encoding(bytes), false, bytes)
+    };
+
+    let raw = match encoding {
+        TextEncoding::Utf8 => std::str::from_utf8(body).map_err(|_| encoding)?.to_string(),
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            if body.len() % 2 != 0 {
+                return Err(encoding);
+            }
+            let units = body.chunks_exact(2).map(|pair| match encoding {
+                TextEncoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units).collect::<Result<String, _>>().map_err(|_| encoding)?
+        }
+    };
+    // A binary file can be valid UTF-8 by chance; NULs never occur in text
+    if raw.contains('\0') {
+        return Err(encoding);
+    }
+
+    let line_ending = LineEnding::detect(&raw);
+    Ok(DecodedText { text: raw.replace("\r\n", "\n"), encoding, bom, line_ending })
+}
+
+/// UTF-16 without a BOM still shows as every other byte being NUL in
+/// mostly-ASCII text; anything else is taken for UTF-8
+fn guess_encoding(bytes: &[u8]) -> TextEncoding {
+    let sample = &bytes[..bytes.len().min(512) & !1];
+    let pairs = sample.len() / 2;
+    if pairs == 0 {
+        return TextEncoding::Utf8;
+    }
+    let zero_at = |offset: usize| sample.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let (even, odd) = (zero_at(0), zero_at(1));
+    if odd * 4 >= pairs * 3 && even == 0 {
+        TextEncoding::Utf16Le
+    } else if even * 4 >= pairs * 3 && odd == 0 {
+        TextEncoding::Utf16Be
+    } else {
+        TextEncoding::Utf8
+    }
+}
+
+/// Read and decode the file at `path`; errors name the file
+pub fn read(path: &Path) -> Result<DecodedText, TextFileError> {
+    let bytes = fs::read(path).map_err(|source| TextFileError::Io { path: path.display().to_string(), source })?;
+    decode(&bytes).map_err(|encoding| TextFileError::NotText { path: path.display().to_string(), encoding })
+}
+
+/// [`read`] for when only the text matters
+pub fn read_to_string(path: &Path) -> Result<String, TextFileError> {
+    read(path).map(|decoded| decoded.text)
+}
+
+/// `text` without a leading BOM, for content that was read elsewhere
+/// (pasted, or passed in by the frontend)
+pub fn strip_bom(text: &str) -> &str {
+    text.strip_prefix('\u{feff}').unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "A=1\nNAME=Zoë\n";
+
+    #[test]
+    fn every_encoding_decodes_and_encodes_back_byte_for_byte() {
+        let fixtures: Vec<(&str, Vec<u8>, TextEncoding, bool, LineEnding)> = vec![
+            ("utf-8", b"A=1\nNAME=Zo\xC3\xAB\n".to_vec(), TextEncoding::Utf8, false, LineEnding::Lf),
+            ("utf-8 bom crlf", b"\xEF\xBB\xBFA=1\r\nNAME=Zo\xC3\xAB\r\n".to_vec(), TextEncoding::Utf8, true, LineEnding::CrLf),
+            (
+                "utf-16le bom crlf",
+                b"\xFF\xFEA\0=\x001\0\r\0\n\0N\0A\0M\0E\0=\0Z\0o\0\xEB\0\r\0\n\0".to_vec(),
+                TextEncoding::Utf16Le,
+                true,
+                LineEnding::CrLf,
+            ),
+            (
+                "utf-16be bom",
+                b"\xFE\xFF\0A\0=\x001\0\n\0N\0A\0M\0E\0=\0Z\0o\0\xEB\0\n".to_vec(),
+                TextEncoding::Utf16Be,
+                true,
+                LineEnding::Lf,
+            ),
+            (
+                "utf-16le without bom",
+                b"A\0=\x001\0\n\0N\0A\0M\0E\0=\0Z\0o\0\xEB\0\n\0".to_vec(),
+                TextEncoding::Utf16Le,
+                false,
+                LineEnding::Lf,
+            ),
+        ];
+        for (name, bytes, encoding, bom, line_ending) in fixtures {
+            let decoded = decode(&bytes).unwrap_or_else(|e| panic!("{}: looked like {}", name, e));
+            assert_eq!(decoded.text, TEXT, "{}", name);
+            assert_eq!((decoded.encoding, decoded.bom, decoded.line_ending), (encoding, bom, line_ending), "{}", name);
+            assert_eq!(decoded.encode(&decoded.text), bytes, "{}", name);
+        }
+    }
+
+    #[test]
+    fn edits_are_written_back_in_the_original_form() {
+        let decoded = decode(b"\xEF\xBB\xBFA=1\r\nB=2\r\n").unwrap();
+        let edited = decoded.text.replace("B=2", "B=3");
+        assert_eq!(decoded.encode(&edited), b"\xEF\xBB\xBFA=1\r\nB=3\r\n");
+
+        // Mixed endings come back in the majority style
+        let decoded = decode(b"A=1\r\nB=2\r\nC=3\n").unwrap();
+        assert_eq!(decoded.text, "A=1\nB=2\nC=3\n");
+        assert_eq!(decoded.encode(&decoded.text), b"A=1\r\nB=2\r\nC=3\r\n");
+        assert_eq!(decode(b"").unwrap().line_ending, LineEnding::Lf);
+    }
+
+    #[test]
+    fn binary_content_is_refused_with_the_encoding_it_resembled() {
+        assert_eq!(decode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Err(TextEncoding::Utf8));
+        assert_eq!(decode(b"caf\xE9\n"), Err(TextEncoding::Utf8));
+        // A lone surrogate, and an odd byte count
+        assert_eq!(decode(b"\xFF\xFE\x00\xD8A\0"), Err(TextEncoding::Utf16Le));
+        assert_eq!(decode(b"\xFE\xFF\0A\0"), Err(TextEncoding::Utf16Be));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logo.png");
+        fs::write(&path, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let message = read(&path).unwrap_err().to_string();
+        assert_eq!(message, format!("{} is not valid UTF-8 text", path.display()));
+        assert!(read(&dir.path().join("missing")).unwrap_err().to_string().starts_with("Failed to read"));
+
+        assert_eq!(strip_bom("\u{feff}{}"), "{}");
+        assert_eq!(strip_bom("{}"), "{}");
+    }
+}
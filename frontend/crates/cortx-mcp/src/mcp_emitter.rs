@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use cortx_core::models::{LogStream, ScriptStatus, ServiceStatus};
 use cortx_core::process_manager::ProcessEventEmitter;
 use parking_lot::Mutex;
@@ -26,9 +27,13 @@ impl McpEmitter {
 // Helper to map ServiceStatus -> ProcessStatus
 fn map_service_status(status: &ServiceStatus) -> ProcessStatus {
     match status {
-        ServiceStatus::Running | ServiceStatus::Starting => ProcessStatus::Running,
+        ServiceStatus::Running
+        | ServiceStatus::Starting
+        | ServiceStatus::Unhealthy
+        | ServiceStatus::Restarting
+        | ServiceStatus::Stopping => ProcessStatus::Running,
         ServiceStatus::Stopped => ProcessStatus::Stopped,
-        ServiceStatus::Error => ProcessStatus::Failed,
+        ServiceStatus::Error | ServiceStatus::CircuitOpen => ProcessStatus::Failed,
     }
 }
 
@@ -58,6 +63,7 @@ impl ProcessEventEmitter for McpEmitter {
         pid: Option<u32>,
         _active_mode: Option<String>,
         _active_arg_preset: Option<String>,
+        _started_at: Option<DateTime<Utc>>,
     ) {
         let mut state = self.state.lock();
         let entry = state
@@ -67,7 +73,7 @@ impl ProcessEventEmitter for McpEmitter {
         entry.status = map_service_status(&status);
     }
 
-    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>) {
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, _error_excerpt: Option<String>) {
         let mut state = self.state.lock();
         if let Some(entry) = state.get_mut(service_id) {
             entry.exit_code = exit_code;
@@ -36,6 +36,8 @@ impl From<ConfigPath> for cortx_core::models::ToolConfigPath {
 pub struct ListGlobalScriptsParams {
     #[schemars(description = "Filter scripts by tag name (case-insensitive match)")]
     pub tag: Option<String>,
+    #[schemars(description = "Search query: space-separated terms that must all match. 'tag:x' (has tag), 'folder:x' (in folder), or plain text (in name, description or command). Case-insensitive.")]
+    pub query: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -110,6 +112,15 @@ pub struct DeleteGlobalScriptParams {
     pub id: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ApplyOrganizeRulesParams {
+    #[schemars(description = "Script UUIDs to organize. If omitted, all global scripts are considered.")]
+    pub script_ids: Option<Vec<String>>,
+    #[schemars(description = "If true, only return the planned changes without saving them (default: false)")]
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct RunGlobalScriptParams {
     #[schemars(description = "Script UUID to run")]
@@ -128,7 +139,7 @@ pub struct RunGlobalScriptParams {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StopGlobalScriptParams {
-    #[schemars(description = "Script UUID of the running script to stop")]
+    #[schemars(description = "Script UUID of the running script to stop, or the run_key returned by run_global_script for one of its concurrent runs")]
     pub id: String,
 }
 
@@ -172,6 +183,8 @@ pub struct CreateProjectParams {
     pub status: Option<String>,
     #[schemars(description = "Toolbox documentation page URL")]
     pub toolbox_url: Option<String>,
+    #[schemars(description = "Create it even if another project has the same root (default false)")]
+    pub allow_duplicate: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -188,6 +201,8 @@ pub struct UpdateProjectParams {
     pub status: Option<String>,
     #[schemars(description = "New toolbox documentation URL")]
     pub toolbox_url: Option<String>,
+    #[schemars(description = "Accept a new root_path another project already has (default false)")]
+    pub allow_duplicate: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
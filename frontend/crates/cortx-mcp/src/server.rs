@@ -16,6 +16,12 @@ use cortx_core::models::*;
 use cortx_core::process_manager::{ProcessManager, RuntimeMeta};
 use cortx_core::runtime_state::RuntimeStore;
 use cortx_core::script_discovery;
+use cortx_core::script_requirements;
+use cortx_core::spawn_env;
+use cortx_core::service_health::HealthCheck;
+use cortx_core::service_idle::IdleTimeout;
+use cortx_core::service_restart::AutoRestart;
+use cortx_core::service_watch::ServiceWatch;
 use cortx_core::storage::Storage;
 use cortx_core::tool_discovery;
 
@@ -50,7 +56,15 @@ impl CortxMcp {
     }
 
     fn reload(&self) -> Result<(), McpError> {
-        self.storage.reload_all().map_err(|e| mcp_err(e.to_string()))
+        self.storage.reload_all().map_err(|e| mcp_err(e.to_string()))?;
+        // Settings may have changed on disk (GUI / CLI); keep the spawn shell,
+        // log persistence, run depth limit and command echo in sync
+        let settings = self.storage.get_settings();
+        self.process_manager.set_shell(settings.shell);
+        self.process_manager.set_logging(settings.logging);
+        self.process_manager.set_max_run_depth(settings.scripts_config.max_run_depth);
+        self.process_manager.set_echo_command(settings.scripts_config.echo_command);
+        Ok(())
     }
 }
 
@@ -97,13 +111,16 @@ impl CortxMcp {
     // Global Scripts (8)
     // ========================================================================
 
-    #[tool(description = "List all global scripts, optionally filtered by tag. Returns script details including name, command, tags, parameters, and presets.", annotations(read_only_hint = true))]
+    #[tool(description = "List all global scripts, optionally filtered by tag or a search query (e.g. 'tag:deploy folder:infra db'). Returns script details including name, command, tags, parameters, and presets.", annotations(read_only_hint = true))]
     fn list_global_scripts(
         &self,
         Parameters(p): Parameters<ListGlobalScriptsParams>,
     ) -> Result<CallToolResult, McpError> {
         self.reload()?;
-        let mut scripts = self.storage.get_all_global_scripts();
+        let mut scripts = match p.query.as_deref() {
+            Some(query) => self.storage.search_global_scripts(query),
+            None => self.storage.get_all_global_scripts(),
+        };
         if let Some(tag) = p.tag {
             scripts.retain(|s| s.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)));
         }
@@ -211,8 +228,21 @@ impl CortxMcp {
         ok_text(format!("Deleted global script {}", p.id))
     }
 
+    #[tool(description = "Apply the organize rules from settings (scriptsConfig.organizeRules) to global scripts: assigns folders, adds tags and sets colors for matching scripts. Returns the per-script changes and any folders that get created. Use dry_run to preview.", annotations(idempotent_hint = true))]
+    fn apply_organize_rules(
+        &self,
+        Parameters(p): Parameters<ApplyOrganizeRulesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.reload()?;
+        let result = self
+            .storage
+            .apply_organize_rules(p.script_ids.as_deref(), p.dry_run)
+            .map_err(|e| mcp_err(e.to_string()))?;
+        ok_json(&result)
+    }
+
     #[tool(
-        description = "EXECUTES: Run a global script. Resolves parameters from: (1) the default preset, (2) the specified 'preset_id', (3) explicit 'parameter_values' (highest priority). Use get_global_script first to see available parameters and presets. Returns immediately with PID; use get_process_status/get_process_logs to monitor.",
+        description = "EXECUTES: Run a global script. Fails if a service listed in the script's 'requires' isn't running (error data lists what's missing). Resolves parameters from: (1) the default preset, (2) the specified 'preset_id', (3) explicit 'parameter_values' (highest priority). Use get_global_script first to see available parameters and presets. Returns immediately with PID; use get_process_status/get_process_logs to monitor.",
         annotations(open_world_hint = true)
     )]
     fn run_global_script(
@@ -225,6 +255,17 @@ impl CortxMcp {
             .get_global_script(&p.id)
             .ok_or_else(|| mcp_err("Global script not found"))?;
 
+        // Required services must already be up; the details go in `data`
+        let projects = self.storage.get_all_projects();
+        script_requirements::ensure_requirements(&script, &projects, |id| {
+            self.process_manager.is_service_running(id)
+        })
+        .map_err(|e| McpError {
+            code: ErrorCode::INVALID_REQUEST,
+            message: format!("{}. Start the missing services with start_service, then retry.", e).into(),
+            data: serde_json::to_value(&e).ok(),
+        })?;
+
         // Resolve parameter values
         let mut param_values: HashMap<String, String> = HashMap::new();
 
@@ -252,6 +293,24 @@ impl CortxMcp {
 
         let working_dir = script.working_dir.clone().unwrap_or_else(|| ".".to_string());
 
+        // Recorded up front: `run_global_script` consumes the values
+        let mut record = ExecutionRecord::new(p.id.clone());
+        record.parameters_used = param_values.clone();
+        record.preset_name = p
+            .preset_id
+            .as_ref()
+            .or(script.default_preset_id.as_ref())
+            .and_then(|id| script.parameter_presets.iter().find(|pr| pr.id == *id))
+            .map(|pr| pr.name.clone());
+        record.working_dir = Some(working_dir.clone());
+        record.extra_args = extra_args.clone();
+        record.parameters_signature = Some(command_builder::parameters_signature(&script));
+        record.log_start = Some(self.process_manager.runtime_store().log_len(&p.id));
+        record.env_snapshot = spawn_env::env_snapshot(
+            script.env_vars.as_ref(),
+            &self.storage.get_settings().scripts_config.env_snapshot,
+        );
+
         let pid = self
             .process_manager
             .run_global_script(
@@ -261,18 +320,29 @@ impl CortxMcp {
                 program,
                 args,
                 script.env_vars.clone(),
-                RuntimeMeta::new(script.name.clone()),
+                &script.env_mode,
+                RuntimeMeta::new(script.name.clone())
+                    .with_allow_concurrent(script.allow_concurrent)
+                    .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
             )
             .map_err(|e| mcp_err(e))?;
 
-        // Record execution
-        let record = ExecutionRecord::new(p.id.clone());
         let _ = self.storage.add_execution_record(record);
 
+        // Concurrent runs are stopped by a key of their own
+        let run_key = self
+            .process_manager
+            .global_script_runs(&p.id)
+            .into_iter()
+            .find(|run| run.pid == pid)
+            .map_or_else(|| p.id.clone(), |run| run.id);
+
         ok_json(&serde_json::json!({
-            "status": "running",
+            // No PID yet while it waits for its mutex group
+            "status": if pid == 0 { "queued" } else { "running" },
             "pid": pid,
             "script_id": p.id,
+            "run_key": run_key,
         }))
     }
 
@@ -330,7 +400,7 @@ impl CortxMcp {
         }
     }
 
-    #[tool(description = "Create a new project. The root_path should point to an existing directory on disk.")]
+    #[tool(description = "Create a new project. The root_path should point to an existing directory on disk. Fails if another project already has that root; projects nested with it are listed in `overlaps`.")]
     fn create_project(
         &self,
         Parameters(p): Parameters<CreateProjectParams>,
@@ -342,11 +412,11 @@ impl CortxMcp {
         project.tags = p.tags.unwrap_or_default();
         project.status = p.status;
         project.toolbox_url = p.toolbox_url;
-        let created = self
+        let saved = self
             .storage
-            .create_project(project)
+            .create_project_checked(project, p.allow_duplicate.unwrap_or(false))
             .map_err(|e| mcp_err(e.to_string()))?;
-        ok_json(&created)
+        ok_json(&saved)
     }
 
     #[tool(description = "Update a project's properties. Only provided fields are changed.", annotations(idempotent_hint = true))]
@@ -355,9 +425,9 @@ impl CortxMcp {
         Parameters(p): Parameters<UpdateProjectParams>,
     ) -> Result<CallToolResult, McpError> {
         self.reload()?;
-        let updated = self
+        let saved = self
             .storage
-            .update_project(&p.id, |proj| {
+            .update_project_checked(&p.id, p.allow_duplicate.unwrap_or(false), |proj| {
                 if let Some(v) = p.name {
                     proj.name = v;
                 }
@@ -381,7 +451,7 @@ impl CortxMcp {
                 }
             })
             .map_err(|e| mcp_err(e.to_string()))?;
-        ok_json(&updated)
+        ok_json(&saved)
     }
 
     #[tool(description = "Delete a project and all its services and scripts permanently.", annotations(destructive_hint = true, idempotent_hint = true))]
@@ -532,18 +602,26 @@ impl CortxMcp {
             }
         }
 
+        let working_dir = project.resolve_dir(&service.working_dir);
         let pid = self
             .process_manager
             .start_service(
                 self.emitter.clone(),
                 p.service_id.clone(),
-                service.working_dir.clone(),
+                working_dir.clone(),
                 command,
-                service.env_vars.clone(),
+                cortx_core::env_file::service_env_vars(&project, &service),
+                &service.env_mode,
                 p.mode.clone(),
                 p.arg_preset.clone(),
                 RuntimeMeta::new(service.name.clone())
-                    .with_project(project.id.clone(), project.name.clone()),
+                    .with_project(project.id.clone(), project.name.clone())
+                    .with_variant(project.active_variant_id.clone()),
+                ServiceWatch::for_service(&service, &working_dir),
+                IdleTimeout::for_service(&service),
+                HealthCheck::for_service(&service),
+                AutoRestart::for_service(&service),
+                service.stop_grace_seconds.map(std::time::Duration::from_secs),
             )
             .map_err(|e| mcp_err(e))?;
 
@@ -651,15 +729,18 @@ impl CortxMcp {
             .run_script(
                 self.emitter.clone(),
                 p.script_id.clone(),
-                script.working_dir.clone(),
+                project.resolve_dir(&script.working_dir),
                 script.command.clone(),
                 RuntimeMeta::new(script.name.clone())
-                    .with_project(project.id.clone(), project.name.clone()),
+                    .with_project(project.id.clone(), project.name.clone())
+                    .with_variant(project.active_variant_id.clone())
+                    .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
             )
             .map_err(|e| mcp_err(e))?;
 
         ok_json(&serde_json::json!({
-            "status": "running",
+            // No PID yet while it waits for its mutex group
+            "status": if pid == 0 { "queued" } else { "running" },
             "pid": pid,
             "script_id": p.script_id,
             "project": project.name,
@@ -1037,7 +1118,7 @@ impl CortxMcp {
     // Discovery (3)
     // ========================================================================
 
-    #[tool(description = "EXECUTES: Scan a folder for script files (.py, .sh, .ps1, .bat, .js, etc.). Uses the configured main scripts folder if no folder is specified. Returns discovered files that can be imported as global scripts.", annotations(read_only_hint = true, open_world_hint = true))]
+    #[tool(description = "EXECUTES: Scan a folder for script files (.py, .sh, .ps1, .bat, .js, etc.). Uses the configured main scripts folder if no folder is specified. Returns discovered files that can be imported as global scripts; `truncated` is set when the scan hit its entry or time limit (scanMaxEntries / scanMaxSeconds in settings).", annotations(read_only_hint = true, open_world_hint = true))]
     fn scan_scripts_folder(
         &self,
         Parameters(p): Parameters<ScanScriptsFolderParams>,
@@ -1056,12 +1137,13 @@ impl CortxMcp {
                 "No folder specified and no main scripts folder configured in settings",
             ));
         }
-        let scripts = script_discovery::scan_folder(
+        let result = script_discovery::scan_folder(
             &folder,
             &settings.scripts_config.scan_extensions,
             &settings.scripts_config.ignored_patterns,
+            &script_discovery::ScanBudget::from_config(&settings.scripts_config),
         );
-        ok_json(&scripts)
+        ok_json(&result)
     }
 
     #[tool(description = "EXECUTES: Discover installed tools from Scoop and Chocolatey package managers. Returns tool names and versions found on the system. Use create_tool to register discovered tools.", annotations(read_only_hint = true, open_world_hint = true))]
@@ -1081,11 +1163,11 @@ impl CortxMcp {
             .get_project(&p.project_id)
             .ok_or_else(|| mcp_err("Project not found"))?;
 
-        let root = std::path::Path::new(&project.root_path);
+        let root = std::path::Path::new(project.effective_root());
         if !root.is_dir() {
             return Err(mcp_err(format!(
                 "Project root path does not exist: {}",
-                project.root_path
+                project.effective_root()
             )));
         }
 
@@ -1105,7 +1187,7 @@ impl CortxMcp {
         ok_json(&serde_json::json!({
             "project_id": p.project_id,
             "project_name": project.name,
-            "root_path": project.root_path,
+            "root_path": project.effective_root(),
             "env_files_found": env_files,
             "existing_env_files": project.env_files.len(),
         }))
@@ -1137,7 +1219,7 @@ impl CortxMcp {
         ok_json(&updated)
     }
 
-    #[tool(description = "Export all scripts, groups, tools, and tag definitions as a JSON backup. Returns the full export as a JSON string.", annotations(read_only_hint = true))]
+    #[tool(description = "Export all scripts, tools, and tag definitions as a JSON backup. Returns the full export as a JSON string.", annotations(read_only_hint = true))]
     fn export_config(&self) -> Result<CallToolResult, McpError> {
         self.reload()?;
         let json = self
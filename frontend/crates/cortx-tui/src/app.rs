@@ -1,13 +1,36 @@
-use cortx_core::models::{GlobalScript, Tool, TagDefinition, ScriptStatus, ScriptParamType, LogStream, ShellAlias, App as CoreApp, StatusDefinition, Project, Service, ServiceStatus};
-use cortx_core::process_manager::ProcessManager;
+use cortx_core::export_file;
+use cortx_core::models::{AppSettings, Theme, TuiConfig, DiscoveredScript, VanishedScript, ExecutionRecord, ExportSummary, GlobalScript, Tool, TagDefinition, ScriptStatus, ScriptParamType, ScriptParameter, LogStream, ShellAlias, App as CoreApp, StatusDefinition, Project, Service, ServiceStatus, PendingSave};
+use cortx_core::organize_rules::OrganizeResult;
+use cortx_core::process_manager::{run_key, split_run_key, ProcessManager, ServiceLaunch};
+use cortx_core::prompt_detect;
+use cortx_core::run_compare::{self, RunComparison};
+use cortx_core::runtime_state::RunningCounts;
+use cortx_core::script_artifacts;
+use cortx_core::script_index::ScriptSearchIndex;
+use cortx_core::script_location;
+use cortx_core::script_query::{folder_targets, hoist_running, FolderTarget};
+use cortx_core::script_requirements::{self, RequirementNotMet, RequirementStatus};
+use cortx_core::script_validation;
+use cortx_core::service_health::HealthCheck;
+use cortx_core::service_idle::IdleTimeout;
+use cortx_core::service_restart::AutoRestart;
+use cortx_core::service_watch::ServiceWatch;
 use cortx_core::storage::Storage;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::Arc;
-
+use nucleo::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo::{Config, Matcher, Utf32Str};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 
+use crate::bookmarks::{self, Bookmark, Jump};
+use crate::frame_budget::LogSource;
+use crate::retention::{select_evictions, RetentionPolicy, RuntimeInfo};
 use crate::tui_emitter::TuiEmitter;
+use crate::util::{command_line, strip_ansi};
 
 /// Current input mode
 #[derive(Debug, Clone, PartialEq)]
@@ -17,6 +40,21 @@ pub enum InputMode {
     Help,
     ParamForm,
     TagFilter,
+    ScriptEditor,
+    ConfirmDelete,
+    PathPrompt,
+    FolderJump,
+    ConfirmRequirements,
+    OrganizePreview,
+    ScanResults,
+    OutputSearch,
+    StdinInput,
+    RunHistory,
+    RunCompare,
+    Settings,
+    Bookmarks,
+    BindSlot,
+    ServiceEditor,
 }
 
 /// Active panel
@@ -36,11 +74,29 @@ pub enum ActiveTab {
     Projects,
 }
 
+/// How long a script that just finished stays highlighted in the list, so
+/// it can be spotted after dropping out of the pinned "Active" section.
+pub const FINISH_HIGHLIGHT: Duration = Duration::from_secs(4);
+
+/// Slots of the quick run bar, `F1`-`F9` (or `Alt+1`-`Alt+9`)
+pub const QUICK_SLOTS: u8 = 9;
+
+/// Smallest output buffer the settings screen accepts
+const MIN_LOG_LINES: usize = 100;
+
+/// Interval of the housekeeping done on ticks (runtime log eviction)
+pub const SLOW_TICK: Duration = Duration::from_secs(10);
+
+/// How often the status bar's running counts are re-read from the runtime store
+const RUNNING_COUNTS_TICK: Duration = Duration::from_secs(1);
+
 /// A log line for display
 #[derive(Debug, Clone)]
 pub struct LogLine {
     pub stream: LogStream,
     pub content: String,
+    /// When the line arrived (or was added, for CortX's own notices)
+    pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
 /// Runtime state for a global script
@@ -53,6 +109,24 @@ pub struct ScriptRuntime {
     pub success: Option<bool>,
     /// The exact command that was executed (with resolved params)
     pub last_command: Option<String>,
+    /// When the current run started (orders the pinned "Active" section)
+    pub started_at: Option<Instant>,
+    /// When the last run finished (drives the post-completion highlight)
+    pub finished_at: Option<Instant>,
+    /// Offset in the script's runtime log where the last run started
+    pub log_start: Option<u64>,
+    /// Log lines dropped by the retention pass (0 while logs are intact)
+    pub evicted_lines: usize,
+    /// Working dir of the last run (artifact patterns are relative to it)
+    pub working_dir: Option<String>,
+    /// Files matching the script's artifact patterns after the last run
+    pub artifacts: Vec<String>,
+    /// Number of the first line in `logs`; lines are numbered as they arrive
+    pub first_seq: u64,
+    /// Bookmarked lines (`m`, marker lines), in line order
+    pub bookmarks: Vec<Bookmark>,
+    /// The prompt the run is stopped at, waiting for input
+    pub awaiting_input: Option<String>,
 }
 
 impl Default for ScriptRuntime {
@@ -64,7 +138,63 @@ impl Default for ScriptRuntime {
             exit_code: None,
             success: None,
             last_command: None,
+            started_at: None,
+            finished_at: None,
+            log_start: None,
+            evicted_lines: 0,
+            working_dir: None,
+            artifacts: Vec::new(),
+            first_seq: 0,
+            bookmarks: Vec::new(),
+            awaiting_input: None,
+        }
+    }
+}
+
+impl ScriptRuntime {
+    /// Free the log buffer, keeping only the last line as a summary
+    pub fn evict_logs(&mut self) {
+        let summary = self.logs.pop();
+        self.evicted_lines += self.logs.len();
+        self.first_seq += self.logs.len() as u64;
+        self.logs = summary.into_iter().collect();
+    }
+
+    /// Number the next line will get
+    pub fn end_seq(&self) -> u64 {
+        self.first_seq + self.logs.len() as u64
+    }
+
+    /// Append output lines, keeping the last `max`. Marker lines become
+    /// bookmarks on the line that follows them instead of being shown.
+    pub fn append_logs(&mut self, lines: Vec<LogLine>, max: usize) {
+        for line in lines {
+            if let Some(label) = bookmarks::parse_marker(&line.content) {
+                let seq = self.end_seq();
+                self.add_bookmark(seq, (!label.is_empty()).then_some(label), line.timestamp);
+                continue;
+            }
+            // Strip \r (carriage returns) which cause garbled display,
+            // but keep ANSI color codes for rendering
+            self.logs.push(LogLine { content: line.content.replace('\r', ""), ..line });
         }
+        self.trim_logs(max);
+    }
+
+    /// Drop the oldest lines beyond `max`
+    pub fn trim_logs(&mut self, max: usize) {
+        if self.logs.len() > max {
+            let drain = self.logs.len() - max;
+            self.logs.drain(..drain);
+            self.first_seq += drain as u64;
+        }
+    }
+
+    /// Bookmark line `seq`; one already there is replaced (a marker names it)
+    pub fn add_bookmark(&mut self, seq: u64, label: Option<String>, created_at: chrono::DateTime<chrono::Utc>) {
+        self.bookmarks.retain(|b| b.seq != seq);
+        let at = self.bookmarks.partition_point(|b| b.seq < seq);
+        self.bookmarks.insert(at, Bookmark { seq, label, created_at });
     }
 }
 
@@ -75,6 +205,8 @@ pub struct ServiceRuntime {
     pub pid: Option<u32>,
     pub logs: Vec<LogLine>,
     pub exit_code: Option<i32>,
+    /// When the running process was spawned
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Default for ServiceRuntime {
@@ -84,8 +216,284 @@ impl Default for ServiceRuntime {
             pid: None,
             logs: Vec::new(),
             exit_code: None,
+            started_at: None,
+        }
+    }
+}
+
+/// A script run waiting on the user to confirm starting its required services
+#[derive(Debug, Clone)]
+pub struct PendingRequirementRun {
+    pub script: GlobalScript,
+    pub command: (String, Vec<String>),
+    pub unmet: RequirementNotMet,
+}
+
+/// How a save-and-restart of a service, done off the UI thread, ended
+pub struct ServiceSaved {
+    /// The service as stored afterwards, saved or not
+    pub service: Option<Service>,
+    pub message: String,
+}
+
+/// Organize-rule changes planned for the listed scripts, awaiting `Enter`
+#[derive(Debug, Clone)]
+pub struct OrganizePreviewState {
+    pub script_ids: Vec<String>,
+    pub plan: OrganizeResult,
+    pub scroll: usize,
+}
+
+/// Files a scan of the scripts folder found that no script points at yet
+/// (`S`). `Space` picks, `Enter` imports the picked ones.
+#[derive(Debug, Clone)]
+pub struct ScanResultsState {
+    pub scripts: Vec<DiscoveredScript>,
+    pub picked: Vec<bool>,
+    pub selected: usize,
+    /// Auto-discovered scripts whose file is gone
+    pub vanished: Vec<VanishedScript>,
+    pub truncated: bool,
+}
+
+/// Past runs of one script (`H`); mark one with `m`, then `=` compares it
+/// with the selected run. `Tab` steps through the selected run's artifacts,
+/// `o` opens the highlighted one and `O` reveals it.
+#[derive(Debug, Clone)]
+pub struct RunHistoryState {
+    pub script_name: String,
+    /// Newest first
+    pub records: Vec<ExecutionRecord>,
+    pub selected: usize,
+    pub marked: Option<usize>,
+    /// Highlighted artifact of the selected run
+    pub artifact: usize,
+}
+
+impl RunHistoryState {
+    pub fn selected_artifact(&self) -> Option<&String> {
+        self.records.get(self.selected)?.artifacts.get(self.artifact)
+    }
+}
+
+/// One row of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsField {
+    PinRunning,
+    FocusOutputOnRun,
+    ConfirmOnQuit,
+    KeepBookmarks,
+    EchoCommand,
+    DetectPrompts,
+    MaxLogLines,
+    RetentionMaxAge,
+    RetentionMaxRetained,
+    Theme,
+}
+
+impl SettingsField {
+    pub const ALL: [SettingsField; 10] = [
+        SettingsField::PinRunning,
+        SettingsField::FocusOutputOnRun,
+        SettingsField::ConfirmOnQuit,
+        SettingsField::KeepBookmarks,
+        SettingsField::EchoCommand,
+        SettingsField::DetectPrompts,
+        SettingsField::MaxLogLines,
+        SettingsField::RetentionMaxAge,
+        SettingsField::RetentionMaxRetained,
+        SettingsField::Theme,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SettingsField::PinRunning => "Pin running scripts",
+            SettingsField::FocusOutputOnRun => "Focus output on run",
+            SettingsField::ConfirmOnQuit => "Confirm quit while running",
+            SettingsField::KeepBookmarks => "Keep bookmarks across runs",
+            SettingsField::EchoCommand => "Echo command before output",
+            SettingsField::DetectPrompts => "Open input at prompts",
+            SettingsField::MaxLogLines => "Output lines kept",
+            SettingsField::RetentionMaxAge => "Keep finished logs (min)",
+            SettingsField::RetentionMaxRetained => "Finished runs with logs",
+            SettingsField::Theme => "Theme (desktop app)",
+        }
+    }
+
+    /// Typed in rather than toggled with `Space`
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            SettingsField::MaxLogLines | SettingsField::RetentionMaxAge | SettingsField::RetentionMaxRetained
+        )
+    }
+}
+
+/// Settings screen (`,`): the TUI-relevant part of [`AppSettings`]. Every
+/// change is saved right away and applied to the running TUI.
+#[derive(Debug, Clone)]
+pub struct SettingsFormState {
+    pub settings: AppSettings,
+    pub selected: usize,
+    /// Text of the numeric field being typed in
+    pub editing: Option<String>,
+    /// Why the typed value was rejected
+    pub error: Option<String>,
+}
+
+impl SettingsFormState {
+    pub fn new(settings: AppSettings) -> Self {
+        Self { settings, selected: 0, editing: None, error: None }
+    }
+
+    pub fn field(&self) -> SettingsField {
+        SettingsField::ALL[self.selected.min(SettingsField::ALL.len() - 1)]
+    }
+
+    pub fn value(&self, field: SettingsField) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        let s = &self.settings;
+        match field {
+            SettingsField::PinRunning => on_off(s.pin_running_scripts),
+            SettingsField::FocusOutputOnRun => on_off(s.tui.focus_output_on_run),
+            SettingsField::ConfirmOnQuit => on_off(s.tui.confirm_on_quit),
+            SettingsField::KeepBookmarks => on_off(s.tui.keep_bookmarks),
+            SettingsField::EchoCommand => on_off(s.scripts_config.echo_command),
+            SettingsField::DetectPrompts => on_off(s.tui.detect_prompts),
+            SettingsField::MaxLogLines => s.tui.max_log_lines.to_string(),
+            SettingsField::RetentionMaxAge => s.script_retention.max_age_minutes.to_string(),
+            SettingsField::RetentionMaxRetained => s.script_retention.max_retained.to_string(),
+            SettingsField::Theme => match s.appearance.theme {
+                Theme::Light => "light",
+                Theme::Dark => "dark",
+                Theme::System => "system",
+            }
+            .to_string(),
+        }
+    }
+
+    /// `Space` / `Enter`: flip a switch, step the theme, or start typing a
+    /// number. Returns whether the settings changed.
+    pub fn activate(&mut self) -> bool {
+        let field = self.field();
+        if field.is_numeric() {
+            self.editing = Some(self.value(field));
+            self.error = None;
+            return false;
+        }
+        let s = &mut self.settings;
+        match field {
+            SettingsField::PinRunning => s.pin_running_scripts = !s.pin_running_scripts,
+            SettingsField::FocusOutputOnRun => s.tui.focus_output_on_run = !s.tui.focus_output_on_run,
+            SettingsField::ConfirmOnQuit => s.tui.confirm_on_quit = !s.tui.confirm_on_quit,
+            SettingsField::KeepBookmarks => s.tui.keep_bookmarks = !s.tui.keep_bookmarks,
+            SettingsField::EchoCommand => s.scripts_config.echo_command = !s.scripts_config.echo_command,
+            SettingsField::DetectPrompts => s.tui.detect_prompts = !s.tui.detect_prompts,
+            SettingsField::Theme => {
+                s.appearance.theme = match s.appearance.theme {
+                    Theme::System => Theme::Light,
+                    Theme::Light => Theme::Dark,
+                    Theme::Dark => Theme::System,
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Apply the typed number to the field. On a bad value the text stays
+    /// open with an error, and `false` is returned.
+    pub fn commit_edit(&mut self) -> bool {
+        let Some(text) = self.editing.as_deref() else { return false };
+        let field = self.field();
+        let value = match text.trim().parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => {
+                self.error = Some(format!("{}: enter a whole number", field.label()));
+                return false;
+            }
+        };
+        let s = &mut self.settings;
+        match field {
+            SettingsField::MaxLogLines if value < MIN_LOG_LINES as u64 => {
+                self.error = Some(format!("{}: at least {}", field.label(), MIN_LOG_LINES));
+                return false;
+            }
+            SettingsField::MaxLogLines => s.tui.max_log_lines = value as usize,
+            SettingsField::RetentionMaxAge => s.script_retention.max_age_minutes = value,
+            SettingsField::RetentionMaxRetained => s.script_retention.max_retained = value as usize,
+            _ => return false,
+        }
+        self.editing = None;
+        self.error = None;
+        true
+    }
+}
+
+/// Comparison of two runs, opened from the run history; `Esc` goes back
+#[derive(Debug, Clone)]
+pub struct RunCompareState {
+    pub comparison: RunComparison,
+    pub scroll: usize,
+}
+
+impl RunCompareState {
+    /// Lines in the scrollable diff: each hunk's header plus its lines
+    pub fn diff_len(&self) -> usize {
+        self.comparison
+            .log_diff
+            .as_ref()
+            .map(|d| d.hunks.iter().map(|h| h.lines.len() + 1).sum())
+            .unwrap_or(0)
+    }
+}
+
+/// Search within the output panel (`/` while it's focused). Matching text
+/// is highlighted; `n` / `N` scroll between the lines that have it.
+#[derive(Debug, Clone, Default)]
+pub struct OutputSearchState {
+    /// Script whose logs are searched; showing another script ends the search
+    pub script_id: Option<String>,
+    pub query: String,
+    /// Match `query` as a regular expression instead of a plain substring
+    pub regex: bool,
+    /// `query` as a case-insensitive pattern (escaped unless `regex`)
+    compiled: Option<Regex>,
+    /// Why `query` isn't a valid regex
+    pub error: Option<String>,
+    /// Number of the line of the match last jumped to (see
+    /// [`ScriptRuntime::first_seq`]), so it stays put as lines are trimmed
+    pub current_seq: Option<u64>,
+}
+
+impl OutputSearchState {
+    fn recompile(&mut self) {
+        self.compiled = None;
+        self.error = None;
+        if !self.query.is_empty() {
+            let pattern = if self.regex { self.query.clone() } else { regex::escape(&self.query) };
+            match RegexBuilder::new(&pattern).case_insensitive(true).build() {
+                Ok(re) => self.compiled = Some(re),
+                // The full message spans several lines; the last one says what's wrong
+                Err(e) => {
+                    let message = e.to_string();
+                    self.error = Some(message.lines().last().unwrap_or("invalid regex").trim().to_string());
+                }
+            }
         }
     }
+
+    /// Case-insensitive match against the line with ANSI codes removed
+    pub fn is_match(&self, content: &str) -> bool {
+        self.compiled.as_ref().is_some_and(|re| re.is_match(&strip_ansi(content)))
+    }
+
+    /// Byte ranges of the matches in `text` (already without ANSI codes),
+    /// for highlighting. Empty matches of a regex are left out.
+    pub fn match_ranges(&self, text: &str) -> Vec<std::ops::Range<usize>> {
+        let Some(re) = &self.compiled else { return Vec::new() };
+        re.find_iter(text).filter(|m| !m.is_empty()).map(|m| m.range()).collect()
+    }
 }
 
 /// State for the parameter form overlay
@@ -110,6 +518,10 @@ pub struct ParamFormState {
     pub picking_preset: bool,
     /// Currently highlighted preset index
     pub preset_index: usize,
+    /// Whether the `--help` popup for the focused parameter is open
+    pub showing_help: bool,
+    /// Result of the last help refresh, shown in the help popup
+    pub help_notice: Option<String>,
 }
 
 impl ParamFormState {
@@ -122,6 +534,22 @@ impl ParamFormState {
     pub fn is_extra_args_focused(&self) -> bool {
         self.focused == self.param_names.len()
     }
+
+    /// Insert pasted text at the cursor of the field being edited, in one
+    /// go. Fields are single-line, so line breaks become spaces.
+    pub fn paste(&mut self, text: &str) {
+        let text = crate::util::single_line(text);
+        let field = if self.is_extra_args_focused() {
+            &mut self.extra_args
+        } else if let Some(name) = self.param_names.get(self.focused) {
+            self.values.entry(name.clone()).or_default()
+        } else {
+            return;
+        };
+        let at = self.cursor_pos.min(field.len());
+        field.insert_str(at, &text);
+        self.cursor_pos = at + text.len();
+    }
 }
 
 impl ParamFormState {
@@ -194,6 +622,8 @@ impl ParamFormState {
             cursor_pos: 0,
             picking_preset: false,
             preset_index: 0,
+            showing_help: false,
+            help_notice: None,
         }
     }
 
@@ -201,6 +631,11 @@ impl ParamFormState {
         self.param_names.get(self.focused).map(|s| s.as_str())
     }
 
+    pub fn focused_param(&self) -> Option<&ScriptParameter> {
+        let name = self.focused_param_name()?;
+        self.script.parameters.iter().find(|p| p.name == name)
+    }
+
     pub fn move_up(&mut self) {
         if self.focused > 0 {
             self.focused -= 1;
@@ -213,7 +648,8 @@ impl ParamFormState {
         }
     }
 
-    /// Toggle enable/disable for optional param at focus
+    /// Toggle enable/disable for optional param at focus. Enabling a member
+    /// of a mutex group disables the rest of the group.
     pub fn toggle_focused(&mut self) {
         if let Some(name) = self.focused_param_name() {
             let name = name.to_string();
@@ -221,7 +657,7 @@ impl ParamFormState {
             if let Some(param) = param {
                 if !param.required {
                     let current = self.enabled.get(&name).copied().unwrap_or(false);
-                    self.enabled.insert(name, !current);
+                    cortx_core::command_builder::set_param_enabled(&self.script, &mut self.enabled, &name, !current);
                 }
             }
         }
@@ -271,11 +707,234 @@ impl ParamFormState {
             }
         }
 
-        let extra: Vec<String> = self.extra_args.split_whitespace().map(|s| s.to_string()).collect();
+        let extra = cortx_core::command_builder::split_args(&self.extra_args);
 
         cortx_core::command_builder::build_command(&self.script, &param_values, &extra)
             .unwrap_or_else(|| (self.script.command.clone(), vec![]))
     }
+
+    /// Parameters with a fixed `nargs` count that got a different number of values
+    pub fn check_value_counts(&self) -> Result<(), String> {
+        let values: HashMap<String, String> = self
+            .values
+            .iter()
+            .filter(|(name, _)| self.enabled.get(*name).copied().unwrap_or(false))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        cortx_core::command_builder::check_value_counts(&self.script, &values).map_err(|e| e.to_string())
+    }
+}
+
+/// Labels of the script editor fields, in display order
+pub const SCRIPT_EDITOR_FIELDS: [&str; 5] = ["Name", "Command", "Working dir", "Tags", "Folder"];
+
+/// State for the global script editor overlay (create / edit)
+#[derive(Debug, Clone)]
+pub struct ScriptEditorState {
+    /// ID of the script being edited (None when creating a new script)
+    pub script_id: Option<String>,
+    /// Current values, indexed like `SCRIPT_EDITOR_FIELDS`
+    pub values: Vec<String>,
+    /// Currently focused field index
+    pub focused: usize,
+    /// Whether we're editing the focused field's value
+    pub editing: bool,
+    /// Cursor position within the currently edited field, in chars
+    pub cursor_pos: usize,
+    /// Validation / storage error shown at the bottom of the form
+    pub error: Option<String>,
+}
+
+impl Default for ScriptEditorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptEditorState {
+    pub const NAME: usize = 0;
+    pub const COMMAND: usize = 1;
+    pub const WORKING_DIR: usize = 2;
+    pub const TAGS: usize = 3;
+    pub const FOLDER: usize = 4;
+
+    /// Empty form for a new script
+    pub fn new() -> Self {
+        Self {
+            script_id: None,
+            values: vec![String::new(); SCRIPT_EDITOR_FIELDS.len()],
+            focused: 0,
+            editing: false,
+            cursor_pos: 0,
+            error: None,
+        }
+    }
+
+    /// Form pre-filled from an existing script
+    pub fn from_script(script: &GlobalScript) -> Self {
+        let mut state = Self::new();
+        state.script_id = Some(script.id.clone());
+        state.values[Self::NAME] = script.name.clone();
+        state.values[Self::COMMAND] = script.command.clone();
+        state.values[Self::WORKING_DIR] = script.working_dir.clone().unwrap_or_default();
+        state.values[Self::TAGS] = script.tags.join(", ");
+        state.values[Self::FOLDER] = script.folder.clone().unwrap_or_default();
+        state
+    }
+
+    pub fn is_new(&self) -> bool {
+        self.script_id.is_none()
+    }
+
+    pub fn move_up(&mut self) {
+        if self.focused > 0 {
+            self.focused -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.focused + 1 < self.values.len() {
+            self.focused += 1;
+        }
+    }
+
+    /// Start editing the focused field with the cursor at the end
+    pub fn start_editing(&mut self) {
+        self.cursor_pos = self.values[self.focused].chars().count();
+        self.editing = true;
+    }
+
+    /// Byte offset of the cursor in the focused field
+    pub fn cursor_byte(&self) -> usize {
+        let value = &self.values[self.focused];
+        value.char_indices().nth(self.cursor_pos).map_or(value.len(), |(at, _)| at)
+    }
+
+    pub fn cursor_left(&mut self) {
+        self.cursor_pos = self.cursor_pos.saturating_sub(1);
+    }
+
+    pub fn cursor_right(&mut self) {
+        if self.cursor_pos < self.values[self.focused].chars().count() {
+            self.cursor_pos += 1;
+        }
+    }
+
+    pub fn cursor_end(&mut self) {
+        self.cursor_pos = self.values[self.focused].chars().count();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let at = self.cursor_byte();
+        self.values[self.focused].insert(at, c);
+        self.cursor_pos += 1;
+    }
+
+    /// Remove the char before the cursor
+    pub fn backspace(&mut self) {
+        if self.cursor_pos > 0 {
+            self.cursor_pos -= 1;
+            let at = self.cursor_byte();
+            self.values[self.focused].remove(at);
+        }
+    }
+
+    /// Remove the char under the cursor
+    pub fn delete(&mut self) {
+        let at = self.cursor_byte();
+        if at < self.values[self.focused].len() {
+            self.values[self.focused].remove(at);
+        }
+    }
+
+    /// Trimmed value of a field, None when empty
+    pub fn optional_value(&self, index: usize) -> Option<String> {
+        let v = self.values[index].trim();
+        if v.is_empty() { None } else { Some(v.to_string()) }
+    }
+
+    /// Tags parsed from the comma-separated tags field
+    pub fn tags(&self) -> Vec<String> {
+        self.values[Self::TAGS]
+            .split(',')
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect()
+    }
+}
+
+/// What the path prompt's file is for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathPromptPurpose {
+    Export,
+    Import,
+}
+
+/// State for the export / import path prompt (`E` / `I` on the Scripts tab)
+#[derive(Debug, Clone)]
+pub struct PathPromptState {
+    pub purpose: PathPromptPurpose,
+    /// Typed path; the cursor is always at the end
+    pub input: String,
+    /// Candidates from the last Tab press, shown under the input
+    pub candidates: Vec<String>,
+    /// Import only: the file was read and previewed; Enter again imports it
+    pub preview: Option<(String, ExportSummary)>,
+    pub error: Option<String>,
+}
+
+/// State for the service command editor (`e` in a project's detail view)
+#[derive(Debug, Clone)]
+pub struct ServiceEditorState {
+    pub service_id: String,
+    pub service_name: String,
+    /// Typed command; the cursor is always at the end
+    pub command: String,
+    pub error: Option<String>,
+}
+
+/// State for the folder-jump overlay (`F` on the Scripts tab)
+#[derive(Debug, Clone)]
+pub struct FolderJumpState {
+    pub query: String,
+    /// Folders of the current list, root group first
+    pub targets: Vec<FolderTarget>,
+    /// Indices into `targets` matching `query`, best match first
+    pub matches: Vec<usize>,
+    /// Highlighted row in `matches`
+    pub selected: usize,
+}
+
+impl FolderJumpState {
+    /// Display name of a target; also what the query is matched against
+    pub fn label(target: &FolderTarget) -> &str {
+        target.folder.as_deref().unwrap_or("(root)")
+    }
+
+    /// Recompute `matches` for the current query with the fuzzy matcher.
+    pub fn update_matches(&mut self) {
+        self.selected = 0;
+        if self.query.is_empty() {
+            self.matches = (0..self.targets.len()).collect();
+            return;
+        }
+        let pattern = Pattern::parse(&self.query, CaseMatching::Ignore, Normalization::Smart);
+        let mut matcher = Matcher::new(Config::DEFAULT.match_paths());
+        let mut buf = Vec::new();
+        let mut scored: Vec<(u32, usize)> = self
+            .targets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, target)| {
+                let score = pattern.score(Utf32Str::new(Self::label(target), &mut buf), &mut matcher)?;
+                Some((score, i))
+            })
+            .collect();
+        // Stable, so equal scores keep the folder order
+        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+    }
 }
 
 pub struct App {
@@ -285,6 +944,8 @@ pub struct App {
 
     // Data
     pub scripts: Vec<GlobalScript>,
+    /// Mirrors `scripts` (same order); kept in step by `set_scripts`
+    search_index: ScriptSearchIndex,
     pub tag_definitions: Vec<TagDefinition>,
     pub runtimes: HashMap<String, ScriptRuntime>,
 
@@ -292,20 +953,95 @@ pub struct App {
     pub input_mode: InputMode,
     pub active_panel: ActivePanel,
     pub selected_index: usize,
+    /// First row the output panel shows. With wrapping a log line can take
+    /// several rows, so this counts rows, not lines (see `output_row_starts`).
     pub output_scroll: usize,
+    /// Lines the output panel showed at the last draw (its page size)
+    pub output_height: usize,
+    /// Row each log line of the active output started on at the last draw
+    pub output_row_starts: Vec<usize>,
+    /// Log line to scroll to once the rows are laid out again (after
+    /// switching wrapping)
+    pub output_anchor: Option<usize>,
+    /// Wrap long output lines (`w` in the output panel); off, they run past
+    /// the edge and `h` / `l` scroll sideways
+    pub wrap_output: bool,
+    /// Columns scrolled to the right while not wrapping
+    pub output_hscroll: usize,
     pub auto_scroll: bool,
+    /// Prefix output lines with their arrival time (`t` in the output panel)
+    pub show_timestamps: bool,
+    /// Last run of the `SLOW_TICK` housekeeping
+    last_slow_tick: Instant,
+    /// Script runtimes whose logs were evicted this session
+    pub evicted_runtimes: usize,
+    /// Services and scripts running in any cortx instance, for the status bar
+    pub running_counts: RunningCounts,
+    /// When `running_counts` was read; `None` after a start or exit
+    running_counts_at: Option<Instant>,
     pub should_quit: bool,
 
     // Search
     pub search_query: String,
     pub filtered_indices: Vec<usize>,
+    /// Pin running scripts to the top of the list (`!` toggles)
+    pub pin_running: bool,
+    /// Number of rows at the start of `filtered_indices` in the pinned section
+    pub pinned_count: usize,
 
-    // Currently viewed script in output panel
+    /// Run shown in the output panel: a script id, or the `run_key` of a
+    /// concurrent run of it
     pub active_script_id: Option<String>,
 
     // Parameter form state
     pub param_form: Option<ParamFormState>,
 
+    // Script editor state (create / edit)
+    pub script_editor: Option<ScriptEditorState>,
+    /// Script awaiting delete confirmation
+    pub pending_delete_id: Option<String>,
+    /// Export / import path prompt
+    pub path_prompt: Option<PathPromptState>,
+    /// Folder-jump overlay
+    pub folder_jump: Option<FolderJumpState>,
+    /// Run held back until its required services are started
+    pub pending_requirements: Option<PendingRequirementRun>,
+    /// Organize-rules preview (`Ctrl+O`)
+    pub organize_preview: Option<OrganizePreviewState>,
+    /// Files found by a scan of the scripts folder (`S`)
+    pub scan_results: Option<ScanResultsState>,
+    /// Log search in the output panel; kept after Enter for `n` / `N`
+    pub output_search: Option<OutputSearchState>,
+    /// Line being typed for the active script's stdin (`i`)
+    pub stdin_input: String,
+    /// Run history of the selected script (`H`)
+    pub run_history: Option<RunHistoryState>,
+    /// Two runs side by side, from the run history (`=`)
+    pub run_compare: Option<RunCompareState>,
+    /// Settings screen (`,`)
+    pub settings_form: Option<SettingsFormState>,
+    /// Highlighted entry of the active script's bookmark list (`'`)
+    pub bookmarks_selected: usize,
+    /// Settings applied to the running TUI (`settings.tui`)
+    pub tui_config: TuiConfig,
+    /// The script being bound to a quick slot (`b`)
+    pub slot_binding: Option<String>,
+    /// A quit was asked for while things were running; `q` again confirms
+    pub quit_pending: bool,
+
+    /// One-shot message shown in the status bar (cleared on next key press)
+    pub status_message: Option<String>,
+    /// Data files whose changes failed to save, read on every tick. Shown
+    /// in the status bar until they're written (`Ctrl+S` retries).
+    pub pending_saves: Vec<PendingSave>,
+    /// Save-and-restarts report here when done, picked up on every tick
+    service_saved_tx: mpsc::Sender<ServiceSaved>,
+    service_saved_rx: mpsc::Receiver<ServiceSaved>,
+
+    /// Opened on first copy and kept: on X11/Wayland the copied text is
+    /// served by its owner, so it would vanish with a dropped clipboard
+    clipboard: Option<arboard::Clipboard>,
+
     // Tabs
     pub active_tab: ActiveTab,
 
@@ -341,6 +1077,8 @@ pub struct App {
     /// Which service's logs are currently shown in the output panel.
     pub active_service_id: Option<String>,
     pub service_runtimes: HashMap<String, ServiceRuntime>,
+    /// The service whose command is being edited (`e`)
+    pub service_editor: Option<ServiceEditorState>,
 
     // Tag filter
     pub active_tag_filter: Option<String>,
@@ -364,30 +1102,68 @@ impl App {
         Self::sort_apps_by_primary_tag(&mut apps, &tag_definitions);
         Self::sort_projects_by_primary_tag(&mut projects, &tag_definitions);
 
+        let search_index = ScriptSearchIndex::build(&scripts);
         let script_count = scripts.len();
         let filtered_indices: Vec<usize> = (0..script_count).collect();
         let tools_filtered_indices: Vec<usize> = (0..tools.len()).collect();
         let aliases_filtered_indices: Vec<usize> = (0..aliases.len()).collect();
         let apps_filtered_indices: Vec<usize> = (0..apps.len()).collect();
         let projects_filtered_indices: Vec<usize> = (0..projects.len()).collect();
+        let settings = storage.get_settings();
+        let pin_running = settings.pin_running_scripts;
+        let (service_saved_tx, service_saved_rx) = mpsc::channel();
 
         Self {
             storage,
             process_manager,
             emitter,
             scripts,
+            search_index,
             tag_definitions,
             runtimes: HashMap::new(),
             input_mode: InputMode::Normal,
             active_panel: ActivePanel::ScriptList,
             selected_index: 0,
             output_scroll: 0,
+            output_height: 0,
+            output_row_starts: Vec::new(),
+            output_anchor: None,
+            wrap_output: true,
+            output_hscroll: 0,
             auto_scroll: true,
+            show_timestamps: false,
+            last_slow_tick: Instant::now(),
+            evicted_runtimes: 0,
+            running_counts: RunningCounts::default(),
+            running_counts_at: None,
             should_quit: false,
             search_query: String::new(),
             filtered_indices,
+            pin_running,
+            pinned_count: 0,
             active_script_id: None,
             param_form: None,
+            script_editor: None,
+            pending_delete_id: None,
+            path_prompt: None,
+            folder_jump: None,
+            pending_requirements: None,
+            organize_preview: None,
+            scan_results: None,
+            run_history: None,
+            run_compare: None,
+            settings_form: None,
+            bookmarks_selected: 0,
+            tui_config: settings.tui,
+            slot_binding: None,
+            quit_pending: false,
+            output_search: None,
+            stdin_input: String::new(),
+            status_message: None,
+            pending_saves: Vec::new(),
+            service_saved_tx,
+            service_saved_rx,
+            clipboard: None,
             active_tab: ActiveTab::Projects,
             tools,
             tools_filtered_indices,
@@ -410,6 +1186,7 @@ impl App {
             services_selected_index: 0,
             active_service_id: None,
             service_runtimes: HashMap::new(),
+            service_editor: None,
             active_tag_filter: None,
             tag_filter_index: 0,
         }
@@ -418,15 +1195,16 @@ impl App {
     /// Refresh data from storage without resetting UI state (search, tag filter, selection).
     /// Called by the file watcher when external changes are detected.
     pub fn refresh_data(&mut self) {
-        self.scripts = self.storage.get_all_global_scripts();
+        let selected_script = self.selected_script_id();
         self.tag_definitions = self.storage.get_all_tag_definitions();
         self.tools = self.storage.get_all_tools();
         self.aliases = self.storage.get_all_aliases();
         self.apps = self.storage.get_all_apps();
         self.status_definitions = self.storage.get_all_status_definitions();
         self.projects = self.storage.get_all_projects();
+        self.tui_config = self.storage.get_settings().tui;
 
-        Self::sort_by_primary_tag(&mut self.scripts, &self.tag_definitions);
+        self.set_scripts(self.storage.get_all_global_scripts());
         Self::sort_tools_by_primary_tag(&mut self.tools, &self.tag_definitions);
         Self::sort_aliases_by_primary_tag(&mut self.aliases, &self.tag_definitions);
         Self::sort_apps_by_primary_tag(&mut self.apps, &self.tag_definitions);
@@ -439,6 +1217,11 @@ impl App {
         self.apply_apps_filter();
         self.apply_projects_filter();
 
+        // Row indices changed under the selection; follow the script by id
+        if let Some(id) = selected_script {
+            self.select_script_by_id(&id);
+        }
+
         // Clamp selection indices to new bounds
         if self.selected_index >= self.filtered_indices.len() && !self.filtered_indices.is_empty() {
             self.selected_index = self.filtered_indices.len() - 1;
@@ -466,16 +1249,56 @@ impl App {
     }
 
     pub fn reload_scripts(&mut self) {
-        self.scripts = self.storage.get_all_global_scripts();
+        let selected_script = self.selected_script_id();
         self.tag_definitions = self.storage.get_all_tag_definitions();
-        Self::sort_by_primary_tag(&mut self.scripts, &self.tag_definitions);
+        self.set_scripts(self.storage.get_all_global_scripts());
         self.search_query.clear();
         self.active_tag_filter = None;
         self.apply_filter();
+        if let Some(id) = selected_script {
+            self.select_script_by_id(&id);
+        }
+    }
+
+    /// Reload scripts from storage, keeping search + tag filter, and move the
+    /// selection onto `script_id`. Filters are cleared if they would hide it.
+    pub fn reload_scripts_and_select(&mut self, script_id: &str) {
+        self.set_scripts(self.storage.get_all_global_scripts());
+        self.apply_filter();
+        if !self.select_script_by_id(script_id) {
+            self.search_query.clear();
+            self.active_tag_filter = None;
+            self.apply_filter();
+            self.select_script_by_id(script_id);
+        }
+    }
+
+    /// Move the selection onto the given script. Returns false if it's filtered out.
+    fn select_script_by_id(&mut self, script_id: &str) -> bool {
+        let pos = self
+            .filtered_indices
+            .iter()
+            .position(|&idx| self.scripts.get(idx).map(|s| s.id == script_id).unwrap_or(false));
+        match pos {
+            Some(p) => {
+                self.selected_index = p;
+                true
+            }
+            None => false,
+        }
     }
 
     /// Sort scripts by primary tag (first tag) definition order, then alphabetically by name.
     /// No-tag first, then by tag definition order (None order = last), then tag name, then script name.
+    /// Replace the script list (sorted by primary tag) and bring the search
+    /// index in step; only scripts whose searchable fields changed are
+    /// re-indexed. Callers re-apply the filter.
+    fn set_scripts(&mut self, mut scripts: Vec<GlobalScript>) {
+        Self::sort_by_primary_tag(&mut scripts, &self.tag_definitions);
+        self.search_index.sync(&scripts);
+        self.scripts = scripts;
+    }
+
     fn sort_by_primary_tag(scripts: &mut [GlobalScript], tag_defs: &[TagDefinition]) {
         scripts.sort_by(|a, b| {
             let ta = a.tags.first().and_then(|t| {
@@ -959,31 +1782,67 @@ impl App {
         (cmd, mode, arg_preset)
     }
 
-    /// Start the currently selected service. No-op if it's already running.
-    pub fn start_selected_service(&mut self) {
-        let Some(svc) = self.selected_service().cloned() else { return };
-        if self.process_manager.is_running(&svc.id) {
-            return;
+    /// How `svc` of `project` starts: its default mode and arg preset.
+    fn service_launch(project: Option<&Project>, svc: &Service) -> ServiceLaunch {
+        let (command, mode, arg_preset) = Self::resolve_service_command(svc);
+        let mut meta = cortx_core::process_manager::RuntimeMeta::new(svc.name.clone());
+        if let Some(p) = project {
+            meta = meta
+                .with_project(p.id.clone(), p.name.clone())
+                .with_variant(p.active_variant_id.clone());
         }
-        let (command, mode, arg_preset) = Self::resolve_service_command(&svc);
-        let meta = self
-            .viewing_project()
-            .map(|p| {
-                cortx_core::process_manager::RuntimeMeta::new(svc.name.clone())
-                    .with_project(p.id.clone(), p.name.clone())
-            })
-            .unwrap_or_else(|| cortx_core::process_manager::RuntimeMeta::new(svc.name.clone()));
-        let emitter = self.emitter.clone();
-        let _ = self.process_manager.start_service(
-            emitter,
-            svc.id.clone(),
-            svc.working_dir.clone(),
+        let working_dir = project.map_or_else(|| svc.working_dir.clone(), |p| p.resolve_dir(&svc.working_dir));
+        ServiceLaunch {
+            service_id: svc.id.clone(),
             command,
-            svc.env_vars.clone(),
+            env_vars: match project {
+                Some(p) => cortx_core::env_file::service_env_vars(p, svc),
+                None => svc.env_vars.clone(),
+            },
+            env_mode: svc.env_mode.clone(),
             mode,
             arg_preset,
             meta,
-        );
+            watch: ServiceWatch::for_service(svc, &working_dir),
+            idle: IdleTimeout::for_service(svc),
+            health: HealthCheck::for_service(svc),
+            port: svc.port,
+            restart: AutoRestart::for_service(svc),
+            stop_grace: svc.stop_grace_seconds.map(Duration::from_secs),
+            working_dir,
+        }
+    }
+
+    /// Start `svc` of `project` with its default mode and arg preset.
+    fn launch_service(&self, project: Option<&Project>, svc: &Service) -> Result<u32, String> {
+        let launch = Self::service_launch(project, svc);
+        self.process_manager.start_service(
+            self.emitter.clone(),
+            launch.service_id,
+            launch.working_dir,
+            launch.command,
+            launch.env_vars,
+            &launch.env_mode,
+            launch.mode,
+            launch.arg_preset,
+            launch.meta,
+            launch.watch,
+            launch.idle,
+            launch.health,
+            launch.restart,
+            launch.stop_grace,
+        )
+    }
+
+    /// Start the currently selected service. No-op if it's already running.
+    pub fn start_selected_service(&mut self) {
+        let Some(svc) = self.selected_service().cloned() else { return };
+        if self.process_manager.is_service_running(&svc.id) {
+            return;
+        }
+        if let Err(e) = self.launch_service(self.viewing_project(), &svc) {
+            self.status_message = Some(format!("Failed to start {}: {}", svc.name, e));
+        }
         self.active_service_id = Some(svc.id.clone());
         self.auto_scroll = true;
         self.output_scroll = 0;
@@ -992,87 +1851,205 @@ impl App {
     /// Stop the currently selected service. No-op if not running.
     pub fn stop_selected_service(&mut self) {
         let Some(svc) = self.selected_service().cloned() else { return };
-        let _ = self.process_manager.stop_service(&*self.emitter, &svc.id);
+        self.stop_services_in_background(vec![svc.id]);
     }
 
-    /// Start every service of the project that isn't already running.
-    pub fn start_all_services(&mut self) {
-        let project_meta = self
-            .viewing_project()
-            .map(|p| (p.id.clone(), p.name.clone()));
-        let services: Vec<Service> = self
-            .viewing_project_services()
-            .into_iter()
-            .cloned()
-            .collect();
-        for svc in services {
-            if self.process_manager.is_running(&svc.id) {
-                continue;
-            }
-            let (command, mode, arg_preset) = Self::resolve_service_command(&svc);
-            let mut meta = cortx_core::process_manager::RuntimeMeta::new(svc.name.clone());
-            if let Some((ref id, ref name)) = project_meta {
-                meta = meta.with_project(id.clone(), name.clone());
-            }
-            let emitter = self.emitter.clone();
-            let _ = self.process_manager.start_service(
-                emitter,
-                svc.id.clone(),
-                svc.working_dir.clone(),
-                command,
-                svc.env_vars.clone(),
-                mode,
-                arg_preset,
-                meta,
-            );
+    /// `R`: close the selected service's open restart circuit without
+    /// starting it (starting it closes it too).
+    pub fn reset_selected_service_circuit(&mut self) {
+        let Some(svc) = self.selected_service().cloned() else { return };
+        if !self.process_manager.reset_circuit(&*self.emitter, &svc.id) {
+            self.status_message = Some(format!("{} is being restarted as usual", svc.name));
         }
     }
 
-    /// Stop every running service of the project.
-    pub fn stop_all_services(&mut self) {
-        let services: Vec<Service> = self
-            .viewing_project_services()
-            .into_iter()
-            .cloned()
-            .collect();
-        for svc in services {
-            let _ = self.process_manager.stop_service(&*self.emitter, &svc.id);
-        }
+    /// `e`: edit the selected service's command
+    pub fn open_service_editor(&mut self) {
+        let Some(svc) = self.selected_service() else { return };
+        self.service_editor = Some(ServiceEditorState {
+            service_id: svc.id.clone(),
+            service_name: svc.name.clone(),
+            command: svc.command.clone(),
+            error: None,
+        });
+        self.input_mode = InputMode::ServiceEditor;
     }
 
-    /// Open the project root in the OS file manager.
-    pub fn open_project_folder(&self) {
-        let Some(p) = self.viewing_project() else { return };
-        if p.root_path.trim().is_empty() {
-            return;
-        }
-        let _ = crate::os_open::open_path(&p.root_path);
+    pub fn close_service_editor(&mut self) {
+        self.service_editor = None;
+        self.input_mode = InputMode::Normal;
     }
 
-    /// Open the project root in VS Code (falls back to file manager if `code` is missing).
-    pub fn open_project_vscode(&self) {
-        let Some(p) = self.viewing_project() else { return };
-        if p.root_path.trim().is_empty() {
+    /// Enter in the service editor: save the command. With `restart`
+    /// (Shift+Enter), a running service is restarted with it right away, the
+    /// save and the restart done as one step so the old command can't come
+    /// back. That step runs off the UI thread, as the old run gets its stop
+    /// grace period; the status bar tells how it went.
+    pub fn save_service_editor(&mut self, restart: bool) {
+        let Some(editor) = self.service_editor.as_mut() else { return };
+        let command = editor.command.trim().to_string();
+        if command.is_empty() {
+            editor.error = Some("The command can't be empty".to_string());
             return;
         }
-        // Try `code <path>` first; if it fails, just open in file manager.
-        let spawned = std::process::Command::new("code")
-            .arg(&p.root_path)
-            .spawn();
-        if spawned.is_err() {
-            let _ = crate::os_open::open_path(&p.root_path);
+        let (service_id, name) = (editor.service_id.clone(), editor.service_name.clone());
+
+        if restart {
+            self.close_service_editor();
+            self.active_service_id = Some(service_id.clone());
+            self.status_message = Some(format!("Restarting {}…", name));
+            self.save_and_restart_in_background(service_id, name, command);
+            return;
         }
-    }
 
-    pub fn get_active_service_logs(&self) -> &[LogLine] {
-        self.active_service_id
-            .as_ref()
-            .and_then(|id| self.service_runtimes.get(id))
-            .map(|r| r.logs.as_slice())
-            .unwrap_or(&[])
+        match self.storage.update_service(&service_id, |service| service.command = command.clone()) {
+            Ok(service) => {
+                self.replace_service(service);
+                self.close_service_editor();
+                self.active_service_id = Some(service_id);
+                self.status_message = Some(format!("Saved {}", name));
+            }
+            Err(e) => {
+                if let Some(editor) = self.service_editor.as_mut() {
+                    editor.error = Some(e.to_string());
+                }
+            }
+        }
     }
 
-    pub fn clear_active_service_logs(&mut self) {
+    /// Save `command` for the service and restart it if it runs, reporting
+    /// to `on_tick` through `service_saved_tx`
+    fn save_and_restart_in_background(&self, service_id: String, name: String, command: String) {
+        let storage = self.storage.clone();
+        let process_manager = self.process_manager.clone();
+        let emitter = self.emitter.clone();
+        let tx = self.service_saved_tx.clone();
+        std::thread::spawn(move || {
+            let save = || {
+                storage
+                    .update_service(&service_id, |service| service.command = command.clone())
+                    .map_err(|e| e.to_string())
+            };
+            let result = process_manager.update_and_restart_service(emitter, &service_id, save, |service| {
+                // Re-read, for the project's current variant and env files
+                let (project, service) = storage
+                    .get_service(&service.id)
+                    .ok_or_else(|| format!("Service not found: {}", service.id))?;
+                Ok(Self::service_launch(Some(&project), &service))
+            });
+            let message = match result {
+                Ok(result) if result.restarted => format!("Saved and restarted {}", name),
+                Ok(_) => format!("Saved {} (not running)", name),
+                Err(e) => format!("{}: {}", name, e),
+            };
+            // The save may have gone through even if the restart failed
+            let service = storage.get_service(&service_id).map(|(_, service)| service);
+            let _ = tx.send(ServiceSaved { service, message });
+        });
+    }
+
+    /// Put `service` in place of its stale copy in `projects`
+    fn replace_service(&mut self, service: Service) {
+        if let Some(slot) = self.projects.iter_mut().flat_map(|p| p.services.iter_mut()).find(|s| s.id == service.id) {
+            *slot = service;
+        }
+    }
+
+    /// Stop services off the UI thread: each gets its stop grace period to
+    /// exit, reported `Stopping` meanwhile.
+    fn stop_services_in_background(&self, service_ids: Vec<String>) {
+        let process_manager = self.process_manager.clone();
+        let emitter = self.emitter.clone();
+        std::thread::spawn(move || {
+            for service_id in service_ids {
+                let _ = process_manager.stop_service(&*emitter, &service_id);
+            }
+        });
+    }
+
+    /// Start every service of the project that isn't already running.
+    /// Services that fail to start are named in the status bar.
+    pub fn start_all_services(&mut self) {
+        let mut failed: Vec<String> = Vec::new();
+        for svc in self.viewing_project_services() {
+            if self.process_manager.is_service_running(&svc.id) {
+                continue;
+            }
+            if self.launch_service(self.viewing_project(), svc).is_err() {
+                failed.push(svc.name.clone());
+            }
+        }
+        if !failed.is_empty() {
+            self.status_message = Some(format!("Failed to start: {}", failed.join(", ")));
+        }
+    }
+
+    /// Stop every running service of the project.
+    pub fn stop_all_services(&mut self) {
+        let service_ids: Vec<String> = self
+            .viewing_project_services()
+            .into_iter()
+            .map(|svc| svc.id.clone())
+            .collect();
+        self.stop_services_in_background(service_ids);
+    }
+
+    /// Open the project root (of the active variant) in the OS file manager.
+    pub fn open_project_folder(&self) {
+        let Some(p) = self.viewing_project() else { return };
+        let root = p.effective_root();
+        if root.trim().is_empty() {
+            return;
+        }
+        let _ = crate::os_open::open_path(root);
+    }
+
+    /// Open the project root (of the active variant) in VS Code (falls back
+    /// to file manager if `code` is missing).
+    pub fn open_project_vscode(&self) {
+        let Some(p) = self.viewing_project() else { return };
+        let root = p.effective_root();
+        if root.trim().is_empty() {
+            return;
+        }
+        // Try `code <path>` first; if it fails, just open in file manager.
+        let spawned = std::process::Command::new("code")
+            .arg(root)
+            .spawn();
+        if spawned.is_err() {
+            let _ = crate::os_open::open_path(root);
+        }
+    }
+
+    /// Status shown for a service: what our own process events reported, or
+    /// Running when another CortX instance (the GUI, another TUI) runs it.
+    pub fn service_status(&self, service_id: &str) -> ServiceStatus {
+        match self.service_runtimes.get(service_id).map(|r| r.status) {
+            Some(status) if status != ServiceStatus::Stopped => status,
+            _ if self.process_manager.is_service_running(service_id) => ServiceStatus::Running,
+            _ => ServiceStatus::Stopped,
+        }
+    }
+
+    /// "up 3m12s" for a running service, measured from its spawn time.
+    pub fn service_uptime_label(&self, service_id: &str) -> Option<String> {
+        let runtime = self.service_runtimes.get(service_id)?;
+        if !runtime.status.is_up() {
+            return None;
+        }
+        let uptime = (chrono::Utc::now() - runtime.started_at?).to_std().unwrap_or_default();
+        Some(format!("up {}", cortx_core::runtime_state::format_uptime(uptime)))
+    }
+
+    pub fn get_active_service_logs(&self) -> &[LogLine] {
+        self.active_service_id
+            .as_ref()
+            .and_then(|id| self.service_runtimes.get(id))
+            .map(|r| r.logs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn clear_active_service_logs(&mut self) {
         if let Some(ref id) = self.active_service_id {
             if let Some(runtime) = self.service_runtimes.get_mut(id) {
                 runtime.logs.clear();
@@ -1113,6 +2090,472 @@ impl App {
         }
     }
 
+    /// Preview the organize rules over the scripts currently listed
+    /// (`Ctrl+O`): the current search / tag filter is the selection.
+    pub fn open_organize_preview(&mut self) {
+        if self.storage.get_settings().scripts_config.organize_rules.is_empty() {
+            self.status_message = Some("No organize rules configured (scriptsConfig.organizeRules)".to_string());
+            return;
+        }
+        let script_ids: Vec<String> = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&i| self.scripts.get(i))
+            .map(|s| s.id.clone())
+            .collect();
+        match self.storage.apply_organize_rules(Some(&script_ids), true) {
+            Ok(plan) if plan.changes.is_empty() => {
+                self.status_message = Some("Organize rules: nothing to change".to_string());
+            }
+            Ok(plan) => {
+                self.organize_preview = Some(OrganizePreviewState { script_ids, plan, scroll: 0 });
+                self.input_mode = InputMode::OrganizePreview;
+            }
+            Err(e) => self.status_message = Some(format!("Organize failed: {}", e)),
+        }
+    }
+
+    /// `Enter` on the preview: apply the rules in one save.
+    pub fn confirm_organize(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(preview) = self.organize_preview.take() else { return };
+        match self.storage.apply_organize_rules(Some(&preview.script_ids), false) {
+            Ok(result) => {
+                self.refresh_data();
+                let mut message = format!("Organized {} script(s)", result.changes.len());
+                if !result.created_folders.is_empty() {
+                    message.push_str(&format!(" — new folders: {}", result.created_folders.join(", ")));
+                }
+                self.status_message = Some(message);
+            }
+            Err(e) => self.status_message = Some(format!("Organize failed: {}", e)),
+        }
+    }
+
+    pub fn cancel_organize(&mut self) {
+        self.organize_preview = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Scan the configured scripts folder (`S`). Moved scripts are followed
+    /// right away; files no script points at are listed for import, all
+    /// picked, along with scripts whose file is gone.
+    pub fn scan_scripts_folder(&mut self) {
+        let result = match self.storage.scan_scripts_folder(None) {
+            Ok(result) => result,
+            Err(e) => {
+                self.status_message = Some(format!("Scan failed: {}", e));
+                return;
+            }
+        };
+        if !result.relocated.is_empty() {
+            self.refresh_data();
+        }
+        let scripts: Vec<DiscoveredScript> = result
+            .scripts
+            .into_iter()
+            .filter(|found| !self.scripts.iter().any(|s| s.script_path.as_deref() == Some(found.path.as_str())))
+            .collect();
+        if scripts.is_empty() && result.vanished.is_empty() {
+            let mut message = "Scan: no new scripts".to_string();
+            if !result.relocated.is_empty() {
+                message.push_str(&format!(", followed {} moved", result.relocated.len()));
+            }
+            self.status_message = Some(message);
+            return;
+        }
+        self.scan_results = Some(ScanResultsState {
+            picked: vec![true; scripts.len()],
+            scripts,
+            selected: 0,
+            vanished: result.vanished,
+            truncated: result.truncated,
+        });
+        self.input_mode = InputMode::ScanResults;
+    }
+
+    /// `Space` on the scan results: pick / unpick the highlighted file
+    pub fn toggle_scan_pick(&mut self) {
+        if let Some(results) = self.scan_results.as_mut() {
+            if let Some(picked) = results.picked.get_mut(results.selected) {
+                *picked = !*picked;
+            }
+        }
+    }
+
+    /// `a` on the scan results: pick all, or none when all are picked
+    pub fn toggle_scan_pick_all(&mut self) {
+        if let Some(results) = self.scan_results.as_mut() {
+            let all = results.picked.iter().all(|&p| p);
+            results.picked.iter_mut().for_each(|p| *p = !all);
+        }
+    }
+
+    /// `Enter` on the scan results: import the picked files in one save.
+    pub fn confirm_scan_import(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(results) = self.scan_results.take() else { return };
+        let paths: Vec<String> = results
+            .scripts
+            .iter()
+            .zip(&results.picked)
+            .filter(|(_, &picked)| picked)
+            .map(|(script, _)| script.path.clone())
+            .collect();
+        if paths.is_empty() {
+            self.status_message = Some("Nothing imported".to_string());
+            return;
+        }
+        match self.storage.import_discovered_scripts(&paths) {
+            Ok(result) => {
+                self.refresh_data();
+                let mut message = format!("Imported {} script(s)", result.imported.len());
+                if result.organized > 0 {
+                    message.push_str(&format!(", {} organized by rules", result.organized));
+                }
+                self.status_message = Some(message);
+            }
+            Err(e) => self.status_message = Some(format!("Import failed: {}", e)),
+        }
+    }
+
+    pub fn cancel_scan(&mut self) {
+        self.scan_results = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Open the run history of the selected script (`H`).
+    pub fn open_run_history(&mut self) {
+        let Some(script) = self.selected_script() else { return };
+        let script_name = script.name.clone();
+        let records = self.storage.get_execution_history(&script.id, 50);
+        if records.is_empty() {
+            self.status_message = Some(format!("No recorded runs of {}", script_name));
+            return;
+        }
+        self.run_history = Some(RunHistoryState { script_name, records, selected: 0, marked: None, artifact: 0 });
+        self.input_mode = InputMode::RunHistory;
+    }
+
+    /// `Tab` in the run history: highlight the selected run's next artifact.
+    pub fn next_run_artifact(&mut self) {
+        if let Some(history) = self.run_history.as_mut() {
+            let count = history.records.get(history.selected).map_or(0, |r| r.artifacts.len());
+            if count > 0 {
+                history.artifact = (history.artifact + 1) % count;
+            }
+        }
+    }
+
+    /// `o` / `O` in the run history: open or reveal the highlighted artifact.
+    pub fn open_run_artifact(&mut self, reveal: bool) {
+        let Some(path) = self.run_history.as_ref().and_then(|h| h.selected_artifact()).cloned() else {
+            self.status_message = Some("This run recorded no artifacts".to_string());
+            return;
+        };
+        self.open_artifact(&path, reveal);
+    }
+
+    /// Where `script` runs: its working dir, or the directory the TUI was
+    /// started from.
+    fn script_working_dir(&self, script: &GlobalScript) -> PathBuf {
+        let base = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        script_location::working_dir(script, &base)
+    }
+
+    /// `P`: pause or resume the scheduled runs of global scripts
+    pub fn toggle_schedules_paused(&mut self) {
+        let scheduler = self.process_manager.scheduler();
+        let paused = !scheduler.is_paused();
+        scheduler.set_paused(paused);
+        self.status_message = Some(if paused {
+            "Scheduled runs paused".to_string()
+        } else {
+            "Scheduled runs resumed".to_string()
+        });
+    }
+
+    /// `w` on the script list: open the selected script's working dir in the
+    /// file manager; `W` (`source`): the folder of its script file instead.
+    pub fn open_script_dir(&mut self, source: bool) {
+        let Some(script) = self.selected_script().cloned() else { return };
+        let dir = if source {
+            let base = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+            match script_location::source_dir(&script, &base) {
+                Some(dir) => dir,
+                None => {
+                    self.status_message = Some(format!("{} has no script file", script.name));
+                    return;
+                }
+            }
+        } else {
+            self.script_working_dir(&script)
+        };
+        if !dir.is_dir() {
+            self.status_message = Some(format!("Directory does not exist: {}", dir.display()));
+            return;
+        }
+        if let Err(e) = script_location::open_in_explorer(&dir.to_string_lossy()) {
+            self.status_message = Some(format!("Failed to open {}: {}", dir.display(), e));
+        }
+    }
+
+    /// `O` on the script list: open the artifact the selected script's last
+    /// run created here, or reveal it among the others when there are several.
+    /// Falls back to the newest recorded run with artifacts.
+    pub fn open_last_artifacts(&mut self) {
+        let Some((script_id, script_name)) = self.selected_script().map(|s| (s.id.clone(), s.name.clone())) else {
+            return;
+        };
+        let mut artifacts = self.runtimes.get(&script_id).map(|r| r.artifacts.clone()).unwrap_or_default();
+        if artifacts.is_empty() {
+            artifacts = self
+                .storage
+                .get_execution_history(&script_id, 50)
+                .into_iter()
+                .find(|r| !r.artifacts.is_empty())
+                .map(|r| r.artifacts)
+                .unwrap_or_default();
+        }
+        match artifacts.as_slice() {
+            [] => self.status_message = Some(format!("No artifacts from {}", script_name)),
+            [only] => self.open_artifact(only, false),
+            [first, ..] => self.open_artifact(first, true),
+        }
+    }
+
+    fn open_artifact(&mut self, path: &str, reveal: bool) {
+        if !Path::new(path).exists() {
+            self.status_message = Some(format!("Artifact no longer exists: {}", path));
+            return;
+        }
+        let opened = if reveal { crate::os_open::reveal_path(path) } else { crate::os_open::open_path(path) };
+        if let Err(e) = opened {
+            self.status_message = Some(e);
+        }
+    }
+
+    pub fn close_run_history(&mut self) {
+        self.run_history = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// `m` in the run history: (un)mark the selected run for comparison.
+    pub fn toggle_run_mark(&mut self) {
+        if let Some(history) = self.run_history.as_mut() {
+            history.marked = match history.marked {
+                Some(i) if i == history.selected => None,
+                _ => Some(history.selected),
+            };
+        }
+    }
+
+    /// `=` in the run history: compare the marked run with the selected one,
+    /// the older of the two as the reference.
+    pub fn compare_marked_run(&mut self) {
+        let Some(history) = self.run_history.as_ref() else { return };
+        let Some(marked) = history.marked.filter(|&m| m != history.selected) else {
+            self.status_message = Some("Mark a run with m, then select another to compare".to_string());
+            return;
+        };
+        // Newest first: the higher index is the older run
+        let (older, newer) = (marked.max(history.selected), marked.min(history.selected));
+        let (id_a, id_b) = (history.records[older].id.clone(), history.records[newer].id.clone());
+        match run_compare::compare_recorded_runs(&self.storage, self.process_manager.runtime_store(), &id_a, &id_b) {
+            Ok(comparison) => {
+                self.run_compare = Some(RunCompareState { comparison, scroll: 0 });
+                self.input_mode = InputMode::RunCompare;
+            }
+            Err(e) => self.status_message = Some(format!("Compare failed: {}", e)),
+        }
+    }
+
+    /// `Esc` on a comparison: back to the run history.
+    pub fn close_run_compare(&mut self) {
+        self.run_compare = None;
+        self.input_mode = if self.run_history.is_some() { InputMode::RunHistory } else { InputMode::Normal };
+    }
+
+    /// Bookmark the top line shown in the output panel (`m`).
+    pub fn add_output_bookmark(&mut self) {
+        let Some(id) = self.active_script_id.clone() else { return };
+        let top = self.output_top_line();
+        let Some(runtime) = self.runtimes.get_mut(&id) else { return };
+        if runtime.logs.is_empty() {
+            self.status_message = Some("No output to bookmark".to_string());
+            return;
+        }
+        let index = top.min(runtime.logs.len() - 1);
+        let timestamp = runtime.logs[index].timestamp;
+        runtime.add_bookmark(runtime.first_seq + index as u64, None, timestamp);
+        self.status_message = Some(format!(
+            "Bookmarked line {} (' to list, {} in total)",
+            index + 1,
+            runtime.bookmarks.len()
+        ));
+    }
+
+    pub fn active_bookmarks(&self) -> &[Bookmark] {
+        self.active_script_id
+            .as_ref()
+            .and_then(|id| self.runtimes.get(id))
+            .map(|r| r.bookmarks.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Preview of a bookmarked line of the active script, if still in the buffer
+    pub fn bookmark_preview(&self, bookmark: &Bookmark) -> Option<&str> {
+        let runtime = self.runtimes.get(self.active_script_id.as_ref()?)?;
+        match bookmarks::locate(bookmark.seq, runtime.first_seq, runtime.logs.len()) {
+            Jump::Line(index) => runtime.logs.get(index).map(|l| l.content.as_str()),
+            Jump::Evicted => None,
+        }
+    }
+
+    /// List the active script's bookmarks (`'`).
+    pub fn open_bookmarks(&mut self) {
+        if self.active_bookmarks().is_empty() {
+            self.status_message = Some("No bookmarks; m in the output panel adds one".to_string());
+            return;
+        }
+        self.bookmarks_selected = self.bookmarks_selected.min(self.active_bookmarks().len() - 1);
+        self.input_mode = InputMode::Bookmarks;
+    }
+
+    pub fn close_bookmarks(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Scroll the output to the selected bookmark. A line that was trimmed
+    /// meanwhile shows the oldest line kept instead.
+    pub fn jump_to_bookmark(&mut self) {
+        let Some(runtime) = self.active_script_id.as_ref().and_then(|id| self.runtimes.get(id)) else { return };
+        let Some(bookmark) = runtime.bookmarks.get(self.bookmarks_selected) else { return };
+        match bookmarks::locate(bookmark.seq, runtime.first_seq, runtime.logs.len()) {
+            Jump::Line(index) => self.output_scroll = self.output_row_of(index),
+            Jump::Evicted => {
+                self.output_scroll = 0;
+                self.status_message = Some("Line no longer in buffer; showing the oldest line kept".to_string());
+            }
+        }
+        self.auto_scroll = false;
+        self.active_panel = ActivePanel::Output;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Remove the selected bookmark (`d` in the list)
+    pub fn delete_bookmark(&mut self) {
+        let Some(runtime) = self.active_script_id.as_ref().and_then(|id| self.runtimes.get_mut(id)) else { return };
+        if self.bookmarks_selected < runtime.bookmarks.len() {
+            runtime.bookmarks.remove(self.bookmarks_selected);
+        }
+        let left = runtime.bookmarks.len();
+        if left == 0 {
+            self.input_mode = InputMode::Normal;
+        }
+        self.bookmarks_selected = self.bookmarks_selected.min(left.saturating_sub(1));
+    }
+
+    /// Open the settings screen (`,`).
+    pub fn open_settings(&mut self) {
+        self.settings_form = Some(SettingsFormState::new(self.storage.get_settings()));
+        self.input_mode = InputMode::Settings;
+    }
+
+    pub fn close_settings(&mut self) {
+        self.settings_form = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// `Space` / `Enter` on the selected setting, or on the number being typed
+    pub fn activate_setting(&mut self) {
+        let Some(form) = self.settings_form.as_mut() else { return };
+        let changed = if form.editing.is_some() { form.commit_edit() } else { form.activate() };
+        if changed {
+            self.save_settings_form();
+        }
+    }
+
+    /// Save the edited settings and apply them without a restart
+    fn save_settings_form(&mut self) {
+        let Some(form) = self.settings_form.as_mut() else { return };
+        let settings = form.settings.clone();
+        if let Err(e) = self.storage.update_settings(settings.clone()) {
+            form.error = Some(format!("Failed to save settings: {}", e));
+            return;
+        }
+        self.tui_config = settings.tui;
+        self.process_manager.set_echo_command(settings.scripts_config.echo_command);
+        self.process_manager.set_prompt_detection(prompt_detect::from_config(&self.tui_config));
+        if self.pin_running != settings.pin_running_scripts {
+            self.pin_running = settings.pin_running_scripts;
+            self.apply_filter();
+        }
+        // Shorter buffers take effect on the next line; trim them now
+        let max = self.tui_config.max_log_lines;
+        for runtime in self.runtimes.values_mut() {
+            runtime.trim_logs(max);
+        }
+        for logs in self.service_runtimes.values_mut().map(|r| &mut r.logs) {
+            if logs.len() > max {
+                logs.drain(..logs.len() - max);
+            }
+        }
+        self.status_message = Some("Settings saved".to_string());
+    }
+
+    /// `q` / `Ctrl+C`. With confirm-on-quit set, quitting while scripts or
+    /// services run takes a second `q` (see `quit_pending`).
+    pub fn request_quit(&mut self) {
+        if self.tui_config.confirm_on_quit {
+            let counts = self.process_manager.count_running();
+            let running = counts.services + counts.scripts + counts.global_scripts;
+            if running > 0 {
+                self.quit_pending = true;
+                self.status_message = Some(format!(
+                    "{} still running; press q again to quit",
+                    if running == 1 { "1 process".to_string() } else { format!("{} processes", running) }
+                ));
+                return;
+            }
+        }
+        self.should_quit = true;
+    }
+
+    /// Open the folder-jump overlay (`F`) over the current list.
+    pub fn open_folder_jump(&mut self) {
+        let targets = folder_targets(&self.scripts, &self.filtered_indices);
+        if targets.is_empty() {
+            self.status_message = Some("No scripts to jump to".to_string());
+            return;
+        }
+        let mut state = FolderJumpState {
+            query: String::new(),
+            targets,
+            matches: Vec::new(),
+            selected: 0,
+        };
+        state.update_matches();
+        self.folder_jump = Some(state);
+        self.input_mode = InputMode::FolderJump;
+    }
+
+    /// Move the selection to the highlighted folder's first script. Filters
+    /// are left alone.
+    pub fn confirm_folder_jump(&mut self) {
+        let Some(state) = self.folder_jump.take() else { return };
+        self.input_mode = InputMode::Normal;
+        if let Some(target) = state.matches.get(state.selected).map(|&i| &state.targets[i]) {
+            self.selected_index = target.first_row.min(self.filtered_indices.len().saturating_sub(1));
+            self.active_panel = ActivePanel::ScriptList;
+        }
+    }
+
+    pub fn close_folder_jump(&mut self) {
+        self.folder_jump = None;
+        self.input_mode = InputMode::Normal;
+    }
+
     pub fn toggle_panel(&mut self) {
         self.active_panel = match self.active_panel {
             ActivePanel::ScriptList => ActivePanel::Output,
@@ -1146,29 +2589,40 @@ impl App {
         self.apply_filter();
     }
 
+    /// Rebuild the visible script rows: filter, then optionally hoist running
+    /// scripts. The selection follows the selected script by id, so it
+    /// survives reloads and scripts moving in or out of the pinned section.
     fn apply_filter(&mut self) {
-        let q = self.search_query.to_lowercase();
-        self.filtered_indices = self
-            .scripts
-            .iter()
-            .enumerate()
-            .filter(|(_, s)| {
+        let selected_id = self.selected_script_id();
+        // Search filter (`tag:`/`folder:` terms + name/description/command text)
+        let rows: Vec<usize> = self
+            .search_index
+            .query(&self.search_query)
+            .into_iter()
+            .filter(|&i| {
                 // Tag filter (from `t` key)
-                if let Some(ref tag) = self.active_tag_filter {
-                    if !s.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
-                        return false;
-                    }
-                }
-                // Search filter (name, description, command only)
-                if !q.is_empty() {
-                    return s.name.to_lowercase().contains(&q)
-                        || s.description.as_deref().unwrap_or("").to_lowercase().contains(&q)
-                        || s.command.to_lowercase().contains(&q);
+                match self.active_tag_filter {
+                    Some(ref tag) => self.scripts[i].tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+                    None => true,
                 }
-                true
             })
-            .map(|(i, _)| i)
             .collect();
+
+        if self.pin_running {
+            let hoisted = hoist_running(&rows, |idx| {
+                let runtime = self.runtimes.get(&self.scripts[idx].id)?;
+                if runtime.status == ScriptStatus::Running { runtime.started_at } else { None }
+            });
+            self.filtered_indices = hoisted.rows;
+            self.pinned_count = hoisted.pinned;
+        } else {
+            self.filtered_indices = rows;
+            self.pinned_count = 0;
+        }
+
+        if selected_id.is_some_and(|id| self.select_script_by_id(&id)) {
+            return;
+        }
         // Clamp selection
         if self.filtered_indices.is_empty() {
             self.selected_index = 0;
@@ -1177,6 +2631,91 @@ impl App {
         }
     }
 
+    /// Toggle pinning of running scripts (`!`).
+    pub fn toggle_pin_running(&mut self) {
+        self.pin_running = !self.pin_running;
+        self.apply_filter();
+        self.status_message = Some(if self.pin_running {
+            "Running scripts pinned to top".to_string()
+        } else {
+            "Running scripts unpinned".to_string()
+        });
+    }
+
+    /// Time since the script finished, while it's still within [`FINISH_HIGHLIGHT`].
+    pub fn recently_finished(&self, script_id: &str) -> Option<Duration> {
+        let runtime = self.runtimes.get(script_id)?;
+        if runtime.status == ScriptStatus::Running {
+            return None;
+        }
+        let elapsed = runtime.finished_at?.elapsed();
+        (elapsed < FINISH_HIGHLIGHT).then_some(elapsed)
+    }
+
+    /// Script of the run shown in the output panel
+    pub fn active_script(&self) -> Option<&GlobalScript> {
+        let (script_id, _) = split_run_key(self.active_script_id.as_deref()?);
+        self.scripts.iter().find(|s| s.id == script_id)
+    }
+
+    /// Runs of `script_id` to pick from in the output panel, oldest first:
+    /// its own run, and the concurrent ones still going or shown
+    pub fn script_runs(&self, script_id: &str) -> Vec<(&str, &ScriptRuntime)> {
+        let mut runs: Vec<(&str, &ScriptRuntime)> = self
+            .runtimes
+            .iter()
+            .filter(|(key, runtime)| match split_run_key(key) {
+                (id, None) => id == script_id,
+                (id, Some(_)) => {
+                    id == script_id
+                        && (runtime.status == ScriptStatus::Running
+                            || self.active_script_id.as_deref() == Some(key.as_str()))
+                }
+            })
+            .map(|(key, runtime)| (key.as_str(), runtime))
+            .collect();
+        runs.sort_by_key(|(key, runtime)| (runtime.started_at, *key));
+        runs
+    }
+
+    /// Runs of `script_id` going right now, its own and concurrent ones
+    pub fn running_count(&self, script_id: &str) -> usize {
+        self.script_runs(script_id)
+            .iter()
+            .filter(|(_, runtime)| runtime.status == ScriptStatus::Running)
+            .count()
+    }
+
+    /// Show the next (or previous) run of the script in the output panel
+    /// (`]` / `[`); `s` then stops that run.
+    pub fn cycle_script_run(&mut self, forward: bool) {
+        let script_id = match &self.active_script_id {
+            Some(key) => split_run_key(key).0.to_string(),
+            None => match self.selected_script_id() {
+                Some(id) => id,
+                None => return,
+            },
+        };
+        let runs: Vec<String> = self.script_runs(&script_id).iter().map(|(key, _)| key.to_string()).collect();
+        if runs.len() < 2 {
+            self.status_message = Some("No other runs of this script".to_string());
+            return;
+        }
+        let current = self
+            .active_script_id
+            .as_ref()
+            .and_then(|key| runs.iter().position(|k| k == key));
+        let next = match current {
+            Some(i) if forward => (i + 1) % runs.len(),
+            Some(i) => (i + runs.len() - 1) % runs.len(),
+            None => 0,
+        };
+        self.active_script_id = Some(runs[next].clone());
+        self.output_scroll = 0;
+        self.auto_scroll = true;
+        self.status_message = Some(format!("Showing run {} of {}", next + 1, runs.len()));
+    }
+
     // === Tools methods ===
 
     pub fn selected_tool(&self) -> Option<&Tool> {
@@ -1499,108 +3038,551 @@ impl App {
         }
     }
 
-    pub fn tag_filter_move_down(&mut self) {
-        let max = self.tag_definitions.len(); // "(All)" + defs, last index = defs.len()
-        if self.tag_filter_index < max {
-            self.tag_filter_index += 1;
-        }
+    pub fn tag_filter_move_down(&mut self) {
+        let max = self.tag_definitions.len(); // "(All)" + defs, last index = defs.len()
+        if self.tag_filter_index < max {
+            self.tag_filter_index += 1;
+        }
+    }
+
+    /// Called when user presses Enter on a script.
+    /// Always opens the run form (params + extra args).
+    pub fn enter_run(&mut self) {
+        let script = match self.selected_script() {
+            Some(s) => s.clone(),
+            None => return,
+        };
+
+        self.param_form = Some(ParamFormState::new(&script));
+        self.input_mode = InputMode::ParamForm;
+    }
+
+    /// Confirm the param form and run the script with the filled parameters
+    pub fn confirm_param_form(&mut self) {
+        let form = match self.param_form.take() {
+            Some(f) => f,
+            None => return,
+        };
+        // Stay in the form to fix the value
+        if let Err(e) = form.check_value_counts() {
+            self.status_message = Some(e);
+            self.param_form = Some(form);
+            return;
+        }
+
+        // Save run state for next time
+        save_run_state(&form.script.id, &SavedRunState {
+            param_values: form.values.clone(),
+            param_enabled: form.enabled.clone(),
+            extra_args: if form.extra_args.trim().is_empty() { None } else { Some(form.extra_args.clone()) },
+        });
+
+        let command = form.build_command();
+        self.input_mode = InputMode::Normal;
+        self.run_script_with_command(&form.script, command);
+    }
+
+    /// Quick-run: skip the param form and reuse last saved state.
+    pub fn quick_run(&mut self) {
+        let script = match self.selected_script() {
+            Some(s) => s.clone(),
+            None => return,
+        };
+        self.quick_run_script(script);
+    }
+
+    /// Quick-run `script` with its last saved parameters
+    fn quick_run_script(&mut self, script: GlobalScript) {
+        // Build a ParamFormState (which loads saved state automatically)
+        let form = ParamFormState::new(&script);
+        if let Err(e) = form.check_value_counts() {
+            self.status_message = Some(format!("{} (Enter to edit)", e));
+            return;
+        }
+        let command = form.build_command();
+
+        // Save state again (refreshes the file)
+        save_run_state(&script.id, &SavedRunState {
+            param_values: form.values,
+            param_enabled: form.enabled,
+            extra_args: if form.extra_args.trim().is_empty() { None } else { Some(form.extra_args.clone()) },
+        });
+
+        self.run_script_with_command(&script, command);
+    }
+
+    /// The script quick slot `slot` runs; `None` when the slot is unbound
+    /// or its script was deleted
+    pub fn quick_slot_script(&self, slot: u8) -> Option<&GlobalScript> {
+        let id = self.tui_config.quick_slots.get(&slot)?;
+        self.scripts.iter().find(|s| &s.id == id)
+    }
+
+    /// `F1`-`F9`: quick-run the script bound to `slot`
+    pub fn run_quick_slot(&mut self, slot: u8) {
+        match self.quick_slot_script(slot).cloned() {
+            Some(script) => self.quick_run_script(script),
+            None => self.status_message = Some(format!("Slot {} is empty; b on a script binds it", slot)),
+        }
+    }
+
+    /// `b`: bind the selected script to a quick slot (asks which)
+    pub fn open_slot_binding(&mut self) {
+        let Some(id) = self.selected_script_id() else { return };
+        self.slot_binding = Some(id);
+        self.input_mode = InputMode::BindSlot;
+    }
+
+    /// Bind the script picked with `b` to `slot`, taking it out of any other
+    /// slot. Saved in the settings right away.
+    pub fn bind_quick_slot(&mut self, slot: u8) {
+        self.input_mode = InputMode::Normal;
+        let Some(id) = self.slot_binding.take() else { return };
+        let name = self.scripts.iter().find(|s| s.id == id).map(|s| s.name.clone()).unwrap_or_default();
+        let saved = self.save_quick_slots(|slots| {
+            slots.retain(|_, bound| *bound != id);
+            slots.insert(slot, id);
+        });
+        if saved {
+            self.status_message = Some(format!("{} bound to F{}", name, slot));
+        }
+    }
+
+    /// `x` while binding: take the script out of its slot
+    pub fn unbind_quick_slot(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(id) = self.slot_binding.take() else { return };
+        if self.save_quick_slots(|slots| slots.retain(|_, bound| *bound != id)) {
+            self.status_message = Some("Removed from the quick run bar".to_string());
+        }
+    }
+
+    pub fn cancel_slot_binding(&mut self) {
+        self.slot_binding = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Change the saved quick slots and apply them; false (and a status
+    /// message) when saving failed
+    fn save_quick_slots(&mut self, edit: impl FnOnce(&mut BTreeMap<u8, String>)) -> bool {
+        let mut settings = self.storage.get_settings();
+        edit(&mut settings.tui.quick_slots);
+        let slots = settings.tui.quick_slots.clone();
+        if let Err(e) = self.storage.update_settings(settings) {
+            self.status_message = Some(format!("Failed to save quick slots: {}", e));
+            return false;
+        }
+        self.tui_config.quick_slots = slots;
+        true
+    }
+
+    /// Copy the selected script's command, built with the last saved
+    /// parameters (as quick-run would run it), to the system clipboard.
+    pub fn copy_selected_command(&mut self) {
+        let (program, args) = match self.selected_script() {
+            Some(script) => ParamFormState::new(script).build_command(),
+            None => return,
+        };
+        let line = command_line(&program, &args);
+
+        let copied = match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(line),
+            None => arboard::Clipboard::new().and_then(|mut clipboard| {
+                clipboard.set_text(line)?;
+                self.clipboard = Some(clipboard);
+                Ok(())
+            }),
+        };
+        self.status_message = Some(match copied {
+            Ok(()) => "Copied command to clipboard".to_string(),
+            Err(e) => format!("Copy failed: {}", e),
+        });
+    }
+
+    /// Cancel the param form
+    pub fn cancel_param_form(&mut self) {
+        self.param_form = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Detect the selected script's parameters from its `--help` (`p`): new
+    /// ones are added, the ones it has are kept as edited. Blocks until the
+    /// command exits or `HELP_TIMEOUT` kills it.
+    pub fn detect_selected_script_params(&mut self) {
+        let Some(script) = self.selected_script() else { return };
+        let (script_id, name, before) = (script.id.clone(), script.name.clone(), script.parameters.len());
+        self.status_message = Some(match cortx_core::help_parser::refresh_help(&self.storage, &script_id) {
+            Ok(script) => {
+                self.reload_scripts_and_select(&script_id);
+                match script.parameters.len().saturating_sub(before) {
+                    0 => format!("No new parameters detected for {}", name),
+                    n => format!("Detected {} new parameter(s) for {}", n, name),
+                }
+            }
+            Err(e) => format!("Detection failed: {}", e),
+        });
+    }
+
+    /// Re-run `--help` for the script in the param form (`r` in the help
+    /// popup): stores the fresh help text and adds new parameters. Values
+    /// already typed in the form are kept.
+    pub fn refresh_param_form_help(&mut self) {
+        let Some(script_id) = self.param_form.as_ref().map(|f| f.script.id.clone()) else { return };
+        let result = cortx_core::help_parser::refresh_help(&self.storage, &script_id);
+        let Some(form) = self.param_form.as_mut() else { return };
+
+        match result {
+            Ok(script) => {
+                let added = script.parameters.len().saturating_sub(form.param_names.len());
+                let mut refreshed = ParamFormState::new(&script);
+                for name in &form.param_names {
+                    if let Some(value) = form.values.get(name) {
+                        refreshed.values.insert(name.clone(), value.clone());
+                    }
+                    if let Some(&enabled) = form.enabled.get(name) {
+                        refreshed.enabled.insert(name.clone(), enabled);
+                    }
+                }
+                refreshed.extra_args = std::mem::take(&mut form.extra_args);
+                refreshed.focused = form.focused.min(refreshed.param_names.len());
+                refreshed.showing_help = true;
+                refreshed.help_notice = Some(match added {
+                    0 => "Help refreshed".to_string(),
+                    n => format!("Help refreshed, {} new parameter(s)", n),
+                });
+                *form = refreshed;
+                self.reload_scripts_and_select(&script_id);
+            }
+            Err(e) => form.help_notice = Some(format!("Refresh failed: {}", e)),
+        }
+    }
+
+    // === Script editor methods ===
+
+    /// Open the editor with an empty form (`a`)
+    pub fn open_new_script_editor(&mut self) {
+        self.script_editor = Some(ScriptEditorState::new());
+        self.input_mode = InputMode::ScriptEditor;
+    }
+
+    /// Open the editor pre-filled with the selected script (`e`)
+    pub fn open_edit_script_editor(&mut self) {
+        let Some(script) = self.selected_script() else { return };
+        self.script_editor = Some(ScriptEditorState::from_script(script));
+        self.input_mode = InputMode::ScriptEditor;
+    }
+
+    pub fn cancel_script_editor(&mut self) {
+        self.script_editor = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Validate the editor form and persist it. On error the form stays open
+    /// with the error message; on success the list reloads onto the saved script.
+    pub fn save_script_editor(&mut self) {
+        let Some(form) = self.script_editor.as_mut() else { return };
+        form.editing = false;
+
+        let name = form.values[ScriptEditorState::NAME].trim().to_string();
+        let command = form.values[ScriptEditorState::COMMAND].trim().to_string();
+        if name.is_empty() {
+            form.error = Some("Name is required".to_string());
+            form.focused = ScriptEditorState::NAME;
+            return;
+        }
+        if command.is_empty() {
+            form.error = Some("Command is required".to_string());
+            form.focused = ScriptEditorState::COMMAND;
+            return;
+        }
+        let working_dir = form.optional_value(ScriptEditorState::WORKING_DIR);
+        let folder = form
+            .optional_value(ScriptEditorState::FOLDER)
+            .map(|f| f.trim_matches('/').to_string())
+            .filter(|f| !f.is_empty());
+        let tags = form.tags();
+
+        let result = match form.script_id.clone() {
+            Some(id) => self.storage.update_global_script(&id, |s| {
+                s.name = name;
+                s.command = command;
+                s.working_dir = working_dir;
+                s.tags = tags;
+                s.folder = folder;
+            }),
+            None => {
+                let mut script = GlobalScript::new(name, command, working_dir);
+                script.tags = tags;
+                script.folder = folder;
+                // Set order to be last
+                script.order = self.scripts.len() as u32;
+                self.storage.create_global_script(script)
+            }
+        };
+
+        match result {
+            Ok(saved) => {
+                let created = form.is_new();
+                self.script_editor = None;
+                self.input_mode = InputMode::Normal;
+                self.reload_scripts_and_select(&saved.id);
+                let issues = script_validation::validate_script_fields(
+                    &saved.command,
+                    saved.script_path.as_deref(),
+                    saved.working_dir.as_deref(),
+                );
+                let mut message = format!("{} '{}'", if created { "Created" } else { "Saved" }, saved.name);
+                if let Some(first) = issues.first() {
+                    message.push_str(&format!(" — warning: {}", first.message));
+                    if issues.len() > 1 {
+                        message.push_str(&format!(" (+{} more)", issues.len() - 1));
+                    }
+                }
+                self.status_message = Some(message);
+            }
+            Err(e) => {
+                form.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Ask for confirmation before deleting the selected script (`d`).
+    /// Running scripts can't be deleted.
+    pub fn request_delete_selected_script(&mut self) {
+        let Some(script) = self.selected_script() else { return };
+        if self.process_manager.is_global_script_running(&script.id) {
+            self.status_message = Some(format!("'{}' is running — stop it before deleting", script.name));
+            return;
+        }
+        self.pending_delete_id = Some(script.id.clone());
+        self.input_mode = InputMode::ConfirmDelete;
+    }
+
+    pub fn confirm_delete_script(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(id) = self.pending_delete_id.take() else { return };
+        // The script may have been started between the prompt and the confirmation
+        if self.process_manager.is_global_script_running(&id) {
+            self.status_message = Some("Script is running — stop it before deleting".to_string());
+            return;
+        }
+        let name = self.scripts.iter().find(|s| s.id == id).map(|s| s.name.clone()).unwrap_or_default();
+        match self.storage.delete_global_script(&id) {
+            Ok(()) => {
+                self.runtimes.retain(|key, _| split_run_key(key).0 != id);
+                if self.active_script_id.as_deref().is_some_and(|key| split_run_key(key).0 == id) {
+                    self.active_script_id = None;
+                }
+                self.set_scripts(self.storage.get_all_global_scripts());
+                self.apply_filter();
+                self.status_message = Some(format!("Deleted '{}'", name));
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Delete failed: {}", e));
+            }
+        }
+    }
+
+    pub fn cancel_delete_script(&mut self) {
+        self.pending_delete_id = None;
+        self.input_mode = InputMode::Normal;
     }
 
-    /// Called when user presses Enter on a script.
-    /// Always opens the run form (params + extra args).
-    pub fn enter_run(&mut self) {
-        let script = match self.selected_script() {
-            Some(s) => s.clone(),
-            None => return,
-        };
-
-        self.param_form = Some(ParamFormState::new(&script));
-        self.input_mode = InputMode::ParamForm;
+    /// Name of the script awaiting delete confirmation
+    pub fn pending_delete_name(&self) -> Option<&str> {
+        let id = self.pending_delete_id.as_ref()?;
+        self.scripts.iter().find(|s| &s.id == id).map(|s| s.name.as_str())
     }
 
-    /// Confirm the param form and run the script with the filled parameters
-    pub fn confirm_param_form(&mut self) {
-        let form = match self.param_form.take() {
-            Some(f) => f,
-            None => return,
+    /// Open the path prompt, prefilled with the remembered directory (plus
+    /// the default filename when exporting).
+    pub fn open_path_prompt(&mut self, purpose: PathPromptPurpose) {
+        let settings = self.storage.get_settings();
+        let dir = export_file::start_dir(settings.last_export_dir.as_deref())
+            .or_else(|| std::env::current_dir().ok())
+            .map(|dir| format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR))
+            .unwrap_or_default();
+        let input = match purpose {
+            PathPromptPurpose::Export => {
+                format!("{}{}", dir, export_file::default_export_filename(chrono::Local::now().date_naive()))
+            }
+            PathPromptPurpose::Import => dir,
         };
-
-        // Save run state for next time
-        save_run_state(&form.script.id, &SavedRunState {
-            param_values: form.values.clone(),
-            param_enabled: form.enabled.clone(),
-            extra_args: if form.extra_args.trim().is_empty() { None } else { Some(form.extra_args.clone()) },
+        self.path_prompt = Some(PathPromptState {
+            purpose,
+            input,
+            candidates: Vec::new(),
+            preview: None,
+            error: None,
         });
-
-        let command = form.build_command();
-        self.input_mode = InputMode::Normal;
-        self.run_script_with_command(&form.script, command);
+        self.input_mode = InputMode::PathPrompt;
     }
 
-    /// Quick-run: skip the param form and reuse last saved state.
-    pub fn quick_run(&mut self) {
-        let script = match self.selected_script() {
-            Some(s) => s.clone(),
-            None => return,
-        };
-
-        // Build a ParamFormState (which loads saved state automatically)
-        let form = ParamFormState::new(&script);
-        let command = form.build_command();
+    pub fn path_prompt_complete(&mut self) {
+        let Some(prompt) = self.path_prompt.as_mut() else { return };
+        let completion = export_file::complete_path(&prompt.input);
+        prompt.input = completion.completed;
+        prompt.candidates = completion.candidates;
+        prompt.preview = None;
+        prompt.error = None;
+    }
 
-        // Save state again (refreshes the file)
-        save_run_state(&script.id, &SavedRunState {
-            param_values: form.values,
-            param_enabled: form.enabled,
-            extra_args: if form.extra_args.trim().is_empty() { None } else { Some(form.extra_args.clone()) },
-        });
+    /// Enter in the path prompt: export, preview the import file, or (after a
+    /// preview) import it.
+    pub fn submit_path_prompt(&mut self) {
+        let Some(prompt) = self.path_prompt.as_mut() else { return };
+        let path = PathBuf::from(prompt.input.trim());
 
-        self.run_script_with_command(&script, command);
+        match (prompt.purpose, prompt.preview.take()) {
+            (PathPromptPurpose::Export, _) => match self.storage.export_scripts_config_to(&path) {
+                Ok(()) => {
+                    self.close_path_prompt();
+                    self.status_message = Some(format!("Exported to {}", path.display()));
+                }
+                Err(e) => prompt.error = Some(e.to_string()),
+            },
+            (PathPromptPurpose::Import, None) => match self.storage.read_import_file(&path) {
+                Ok(preview) => {
+                    prompt.preview = Some(preview);
+                    prompt.candidates.clear();
+                    prompt.error = None;
+                }
+                Err(e) => prompt.error = Some(e.to_string()),
+            },
+            (PathPromptPurpose::Import, Some((json, summary))) => {
+                let options = export_file::import_options_for(&summary);
+                match self.storage.import_scripts_config(&json, &options) {
+                    Ok(result) => {
+                        self.close_path_prompt();
+                        self.refresh_data();
+                        self.status_message = Some(format!(
+                            "Imported {} scripts, {} projects ({} skipped)",
+                            result.scripts_added, result.projects_added, result.skipped
+                        ));
+                    }
+                    Err(e) => prompt.error = Some(e.to_string()),
+                }
+            }
+        }
     }
 
-    /// Cancel the param form
-    pub fn cancel_param_form(&mut self) {
-        self.param_form = None;
+    pub fn close_path_prompt(&mut self) {
+        self.path_prompt = None;
         self.input_mode = InputMode::Normal;
     }
 
+    /// Requirement status of each service `script` requires, for the param form.
+    pub fn requirement_statuses(&self, script: &GlobalScript) -> Vec<RequirementStatus> {
+        script_requirements::check_requirements(&script.requires, &self.projects, |id| {
+            self.process_manager.is_service_running(id)
+        })
+    }
+
+    /// Run `script` once its required services are up. When some are down
+    /// and all of them may be auto-started, ask first (ConfirmRequirements);
+    /// otherwise report what's missing and don't run.
     fn run_script_with_command(&mut self, script: &GlobalScript, command: (String, Vec<String>)) {
-        let working_dir = std::env::current_dir()
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| ".".to_string());
+        let checked = script_requirements::ensure_requirements(script, &self.projects, |id| {
+            self.process_manager.is_service_running(id)
+        });
+        if let Err(unmet) = checked {
+            self.active_script_id = Some(script.id.clone());
+            if unmet.start_and_retry {
+                self.pending_requirements = Some(PendingRequirementRun {
+                    script: script.clone(),
+                    command,
+                    unmet,
+                });
+                self.input_mode = InputMode::ConfirmRequirements;
+            } else {
+                let runtime = self.runtimes.entry(script.id.clone()).or_default();
+                runtime.logs.push(LogLine {
+                    stream: LogStream::Stderr,
+                    content: format!("Not started: {}", unmet),
+                    timestamp: chrono::Utc::now(),
+                });
+                self.status_message = Some(unmet.to_string());
+            }
+            return;
+        }
+
+        let working_dir = self.script_working_dir(script).to_string_lossy().to_string();
 
         let (program, args) = command;
 
-        // Store the resolved command in the runtime
-        let runtime = self.runtimes.entry(script.id.clone()).or_default();
-        runtime.last_command = Some(format!("{} {}", program, args.join(" ")));
+        // Where this run's output will start in its log (to restore evicted
+        // logs from). A concurrent run gets a log of its own.
+        let log_start = self.process_manager.runtime_store().log_len(&script.id);
+        let last_command = command_line(&program, &args);
 
         let emitter = self.emitter.clone();
         match self.process_manager.run_global_script(
             emitter,
             script.id.clone(),
-            working_dir,
+            working_dir.clone(),
             program,
             args,
             script.env_vars.clone(),
-            cortx_core::process_manager::RuntimeMeta::new(script.name.clone()),
+            &script.env_mode,
+            cortx_core::process_manager::RuntimeMeta::new(script.name.clone())
+                .with_allow_concurrent(script.allow_concurrent)
+                .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
         ) {
-            Ok(_pid) => {
-                self.active_script_id = Some(script.id.clone());
+            Ok(pid) => {
+                let key = self
+                    .process_manager
+                    .global_script_runs(&script.id)
+                    .into_iter()
+                    .find(|run| run.pid == pid)
+                    .map_or_else(|| script.id.clone(), |run| run.id);
+                let runtime = self.runtimes.entry(key.clone()).or_default();
+                runtime.last_command = Some(last_command);
+                runtime.log_start = Some(if key == script.id { log_start } else { 0 });
+                runtime.working_dir = Some(working_dir);
+                runtime.artifacts.clear();
+                self.active_script_id = Some(key);
                 self.auto_scroll = true;
                 self.output_scroll = 0;
+                if self.tui_config.focus_output_on_run {
+                    self.active_panel = ActivePanel::Output;
+                }
             }
             Err(e) => {
                 let runtime = self.runtimes.entry(script.id.clone()).or_default();
+                runtime.last_command = Some(last_command);
                 runtime.logs.push(LogLine {
                     stream: LogStream::Stderr,
                     content: format!("Failed to start: {}", e),
+                    timestamp: chrono::Utc::now(),
                 });
                 self.active_script_id = Some(script.id.clone());
             }
         }
     }
 
+    /// `y` on the requirements prompt: start the missing services, then run.
+    pub fn confirm_requirements_run(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(pending) = self.pending_requirements.take() else { return };
+        for missing in &pending.unmet.missing {
+            let (Some(project_id), Some(service_id)) = (&missing.project_id, &missing.service_id) else {
+                continue;
+            };
+            let Some(project) = self.projects.iter().find(|p| &p.id == project_id) else { continue };
+            let Some(svc) = project.services.iter().find(|s| &s.id == service_id) else { continue };
+            if let Err(e) = self.launch_service(Some(project), svc) {
+                self.status_message = Some(format!("Failed to start {}: {}", missing.label, e));
+                return;
+            }
+        }
+        self.run_script_with_command(&pending.script, pending.command);
+    }
+
+    pub fn cancel_requirements_run(&mut self) {
+        self.pending_requirements = None;
+        self.input_mode = InputMode::Normal;
+    }
+
     pub fn stop_selected(&mut self) {
         let script_id = match self.active_script_id.clone().or_else(|| self.selected_script_id()) {
             Some(id) => id,
@@ -1609,9 +3591,128 @@ impl App {
 
         if let Some(runtime) = self.runtimes.get(&script_id) {
             if runtime.status == ScriptStatus::Running {
-                let _ = self.process_manager.stop_global_script(&*self.emitter, &script_id);
+                // Waits out the script's stop grace period
+                let process_manager = self.process_manager.clone();
+                let emitter = self.emitter.clone();
+                std::thread::spawn(move || {
+                    let _ = process_manager.stop_global_script(&*emitter, &script_id);
+                });
+            }
+        }
+    }
+
+    /// Periodic housekeeping, run at most once per `SLOW_TICK`
+    pub fn on_tick(&mut self) {
+        self.pending_saves = self.storage.pending_saves();
+        while let Ok(saved) = self.service_saved_rx.try_recv() {
+            if let Some(service) = saved.service {
+                self.replace_service(service);
+            }
+            self.status_message = Some(saved.message);
+        }
+        if self.running_counts_at.map_or(true, |at| at.elapsed() >= RUNNING_COUNTS_TICK) {
+            self.running_counts = self.process_manager.count_running();
+            self.running_counts_at = Some(Instant::now());
+        }
+        if self.last_slow_tick.elapsed() < SLOW_TICK {
+            return;
+        }
+        self.last_slow_tick = Instant::now();
+        self.evict_finished_runtimes();
+    }
+
+    /// Write the data files that failed to save again (`Ctrl+S`)
+    pub fn retry_pending_saves(&mut self) {
+        let had_pending = !self.storage.pending_saves().is_empty();
+        self.pending_saves = self.storage.retry_pending_saves();
+        self.status_message = Some(match self.pending_saves.first() {
+            Some(pending) => format!("Still failing to save {}: {}", pending.file.label(), pending.error),
+            None if had_pending => "Saved".to_string(),
+            None => "Nothing to save".to_string(),
+        });
+    }
+
+    /// Drop the logs of finished runs past `AppSettings::script_retention`;
+    /// the script in the output panel and the selected one are exempt.
+    fn evict_finished_runtimes(&mut self) {
+        let policy = RetentionPolicy::from(&self.storage.get_settings().script_retention);
+        let exempt: Vec<String> = [self.active_script_id.clone(), self.selected_script_id()]
+            .into_iter()
+            .flatten()
+            .collect();
+        let exempt: Vec<&str> = exempt.iter().map(String::as_str).collect();
+        let infos: Vec<RuntimeInfo> = self
+            .runtimes
+            .iter()
+            .map(|(id, runtime)| RuntimeInfo {
+                id,
+                running: runtime.status == ScriptStatus::Running,
+                finished_at: runtime.finished_at,
+                has_logs: runtime.logs.len() > 1,
+            })
+            .collect();
+        let evict = select_evictions(&infos, &exempt, &policy, Instant::now());
+        if evict.is_empty() {
+            return;
+        }
+        for id in &evict {
+            // Nothing restores a concurrent run's logs, so it goes entirely
+            if split_run_key(id).1.is_some() {
+                self.runtimes.remove(id);
+            } else if let Some(runtime) = self.runtimes.get_mut(id) {
+                runtime.evict_logs();
             }
         }
+        self.evicted_runtimes += evict.len();
+        log::debug!(
+            "Evicted logs of {} finished script run(s), {} this session",
+            evict.len(),
+            self.evicted_runtimes
+        );
+    }
+
+    /// Lines evicted from the selected script's logs, if any
+    pub fn selected_evicted_lines(&self) -> Option<usize> {
+        let id = self.selected_script_id()?;
+        self.runtimes.get(&id).map(|r| r.evicted_lines).filter(|&n| n > 0)
+    }
+
+    /// `L`: reload the selected script's evicted logs from its runtime log
+    /// file (the last run's output) and show them.
+    pub fn restore_evicted_logs(&mut self) {
+        let Some(script) = self.selected_script().cloned() else { return };
+        let Some(runtime) = self.runtimes.get(&script.id) else { return };
+        if runtime.evicted_lines == 0 {
+            return;
+        }
+        let text = runtime
+            .log_start
+            .and_then(|start| self.process_manager.runtime_store().read_log_range(&script.id, start, None));
+        let Some(text) = text else {
+            self.status_message = Some(format!("No log file to restore {} from", script.name));
+            return;
+        };
+
+        let timestamp = chrono::Utc::now();
+        let mut logs: Vec<LogLine> = text
+            .lines()
+            .filter(|line| bookmarks::parse_marker(line).is_none())
+            .map(|line| LogLine { stream: LogStream::Stdout, content: line.replace('\r', ""), timestamp })
+            .collect();
+        let max = self.tui_config.max_log_lines;
+        if logs.len() > max {
+            logs.drain(..logs.len() - max);
+        }
+        let restored = logs.len();
+        if let Some(runtime) = self.runtimes.get_mut(&script.id) {
+            // The run's output ends where the buffer did, so bookmarks line up
+            runtime.first_seq = runtime.end_seq().saturating_sub(restored as u64);
+            runtime.logs = logs;
+            runtime.evicted_lines = 0;
+        }
+        self.active_script_id = Some(script.id.clone());
+        self.auto_scroll = true;
+        self.status_message = Some(format!("Restored {} line(s) of {}'s last run", restored, script.name));
     }
 
     pub fn get_active_logs(&self) -> &[LogLine] {
@@ -1625,6 +3726,7 @@ impl App {
     pub fn clear_active_logs(&mut self) {
         if let Some(ref id) = self.active_script_id {
             if let Some(runtime) = self.runtimes.get_mut(id) {
+                runtime.first_seq = runtime.end_seq();
                 runtime.logs.clear();
                 self.output_scroll = 0;
             }
@@ -1642,55 +3744,430 @@ impl App {
         self.output_scroll += 1;
     }
 
+    /// `PageUp` / `PageDown`, or half a page for `Ctrl+u` / `Ctrl+d`
+    pub fn scroll_output_page(&mut self, down: bool, half: bool) {
+        let page = self.output_height.max(2);
+        let step = if half { page / 2 } else { page - 1 };
+        if down {
+            self.output_scroll += step;
+        } else {
+            self.output_scroll = self.output_scroll.saturating_sub(step);
+            self.auto_scroll = false;
+        }
+    }
+
+    /// `g`: the oldest line kept
+    pub fn scroll_output_top(&mut self) {
+        self.output_scroll = 0;
+        self.auto_scroll = false;
+    }
+
+    /// `G`: the newest line; drawing clamps the scroll to the last page
+    pub fn scroll_output_bottom(&mut self) {
+        self.output_scroll = usize::MAX;
+    }
+
+    pub fn toggle_timestamps(&mut self) {
+        self.show_timestamps = !self.show_timestamps;
+    }
+
+    /// First row of log line `index` in the output panel, as last drawn
+    pub fn output_row_of(&self, index: usize) -> usize {
+        self.output_row_starts.get(index).copied().unwrap_or(index)
+    }
+
+    /// Log line shown in the output panel's top row
+    pub fn output_top_line(&self) -> usize {
+        if self.output_row_starts.is_empty() {
+            return self.output_scroll;
+        }
+        self.output_row_starts
+            .partition_point(|&row| row <= self.output_scroll)
+            .saturating_sub(1)
+    }
+
+    /// `w`: switch wrapping, keeping the line at the top in view
+    pub fn toggle_wrap_output(&mut self) {
+        if !self.auto_scroll {
+            self.output_anchor = Some(self.output_top_line());
+        }
+        self.wrap_output = !self.wrap_output;
+        self.output_hscroll = 0;
+    }
+
+    /// `h` / `l`: scroll sideways while not wrapping
+    pub fn scroll_output_sideways(&mut self, right: bool) {
+        if self.wrap_output {
+            self.status_message = Some("Lines wrap; turn it off with w to scroll sideways".to_string());
+            return;
+        }
+        const STEP: usize = 8;
+        self.output_hscroll = if right {
+            self.output_hscroll + STEP
+        } else {
+            self.output_hscroll.saturating_sub(STEP)
+        };
+    }
+
     pub fn toggle_auto_scroll(&mut self) {
+        // Following new output would scroll away from the matches
+        if self.output_search.is_some() && self.active_tab == ActiveTab::Scripts {
+            self.status_message = Some("Clear the log search (Esc) to follow output".to_string());
+            return;
+        }
         self.auto_scroll = !self.auto_scroll;
     }
 
+    // ── Script input ──
+
+    /// Start typing into the active script's stdin. Needs a running script
+    /// started with interactive stdin (always the case in the TUI).
+    pub fn enter_stdin_input(&mut self) {
+        let Some(script_id) = self.active_script_id.as_deref() else { return };
+        if !self.process_manager.accepts_stdin(script_id) {
+            self.status_message = Some(if self.process_manager.is_global_script_running(script_id) {
+                "The script's input is closed".to_string()
+            } else {
+                "The script is not running".to_string()
+            });
+            return;
+        }
+        self.stdin_input.clear();
+        self.auto_scroll = true;
+        self.input_mode = InputMode::StdinInput;
+    }
+
+    pub fn stdin_input_char(&mut self, c: char) {
+        self.stdin_input.push(c);
+    }
+
+    pub fn stdin_input_backspace(&mut self) {
+        self.stdin_input.pop();
+    }
+
+    /// Enter: send the typed line (and a newline), echoed into the output.
+    /// Stays in input mode for the next line.
+    pub fn send_stdin_line(&mut self) {
+        let Some(script_id) = self.active_script_id.clone() else { return };
+        let line = std::mem::take(&mut self.stdin_input);
+        if let Err(e) = self.process_manager.write_stdin(&script_id, format!("{}\n", line).as_bytes()) {
+            self.status_message = Some(e);
+            self.input_mode = InputMode::Normal;
+            return;
+        }
+        if let Some(runtime) = self.runtimes.get_mut(&script_id) {
+            runtime.awaiting_input = None;
+        }
+        self.handle_log_batch(
+            LogSource::Script(script_id),
+            vec![LogLine { stream: LogStream::Stdout, content: line, timestamp: chrono::Utc::now() }],
+        );
+    }
+
+    /// Ctrl+D: close the script's stdin so it reads end of file.
+    pub fn close_stdin_input(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.stdin_input.clear();
+        let Some(script_id) = self.active_script_id.clone() else { return };
+        match self.process_manager.close_stdin(&script_id) {
+            Ok(()) => self.status_message = Some("Input closed".to_string()),
+            Err(e) => self.status_message = Some(e),
+        }
+    }
+
+    /// Esc: stop typing; the script's stdin stays open.
+    pub fn leave_stdin_input(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.stdin_input.clear();
+    }
+
+    // ── Output search ──
+
+    /// Start (or resume editing) a search in the active script's output.
+    pub fn enter_output_search(&mut self) {
+        if self.active_script_id.is_none() {
+            return;
+        }
+        let script_id = self.active_script_id.clone();
+        let search = self.output_search.get_or_insert_with(Default::default);
+        search.script_id = script_id;
+        self.auto_scroll = false;
+        self.input_mode = InputMode::OutputSearch;
+    }
+
+    pub fn output_search_input(&mut self, c: char) {
+        if let Some(search) = self.output_search.as_mut() {
+            search.query.push(c);
+            search.recompile();
+            search.current_seq = None;
+        }
+        self.jump_to_output_match(true);
+    }
+
+    pub fn output_search_backspace(&mut self) {
+        if let Some(search) = self.output_search.as_mut() {
+            search.query.pop();
+            search.recompile();
+            search.current_seq = None;
+        }
+        self.jump_to_output_match(true);
+    }
+
+    /// Switch between substring and regex matching (`Ctrl+R`).
+    pub fn toggle_output_search_regex(&mut self) {
+        if let Some(search) = self.output_search.as_mut() {
+            search.regex = !search.regex;
+            search.recompile();
+            search.current_seq = None;
+        }
+        self.jump_to_output_match(true);
+    }
+
+    /// Enter: stop typing but keep the highlights and `n` / `N`.
+    pub fn confirm_output_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if self.output_search.as_ref().is_some_and(|s| s.query.is_empty()) {
+            self.output_search = None;
+        }
+    }
+
+    pub fn clear_output_search(&mut self) {
+        self.output_search = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Log line indices of the active output that match the search.
+    pub fn output_search_matches(&self) -> Vec<usize> {
+        let Some(search) = &self.output_search else { return Vec::new() };
+        if search.script_id != self.active_script_id {
+            return Vec::new();
+        }
+        self.get_active_logs()
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| search.is_match(&log.content))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// `n` / `N`: scroll to the next / previous match, wrapping around.
+    pub fn jump_to_output_match(&mut self, forward: bool) {
+        let matches = self.output_search_matches();
+        let first_seq = self.active_first_seq();
+        let current = self.output_search_current();
+        let Some(search) = self.output_search.as_mut() else { return };
+        if matches.is_empty() {
+            search.current_seq = None;
+            if !search.query.is_empty() && search.error.is_none() && self.input_mode != InputMode::OutputSearch {
+                self.status_message = Some(format!("No matches for '{}'", search.query));
+            }
+            return;
+        }
+        let target = match (current, forward) {
+            // First jump: the first match at or below the current view
+            (None, _) => matches
+                .iter()
+                .copied()
+                .find(|&i| i >= self.output_scroll)
+                .unwrap_or(matches[0]),
+            (Some(cur), true) => matches.iter().copied().find(|&i| i > cur).unwrap_or(matches[0]),
+            (Some(cur), false) => matches
+                .iter()
+                .rev()
+                .copied()
+                .find(|&i| i < cur)
+                .unwrap_or(matches[matches.len() - 1]),
+        };
+        search.current_seq = Some(first_seq + target as u64);
+        self.output_scroll = self.output_row_of(target);
+        self.auto_scroll = false;
+    }
+
+    /// Number of the active script's oldest line in its buffer
+    fn active_first_seq(&self) -> u64 {
+        self.active_script_id
+            .as_ref()
+            .and_then(|id| self.runtimes.get(id))
+            .map_or(0, |r| r.first_seq)
+    }
+
+    /// Index in the active logs of the match last jumped to, while it's
+    /// still in the buffer
+    pub fn output_search_current(&self) -> Option<usize> {
+        let seq = self.output_search.as_ref()?.current_seq?;
+        seq.checked_sub(self.active_first_seq()).map(|i| i as usize)
+    }
+
     /// Handle a process event from the TUI emitter channel
+    /// Append a batch of output lines of one script or service with a single
+    /// runtime lookup. Single log events come through here too.
+    pub fn handle_log_batch(&mut self, source: LogSource, lines: Vec<LogLine>) {
+        let max = self.tui_config.max_log_lines;
+        let logs = match source {
+            LogSource::Script(id) => {
+                let runtime = self.runtimes.entry(id.clone()).or_default();
+                let first_seq = runtime.first_seq;
+                runtime.append_logs(lines, max);
+                // A scrolled-back view stays on its lines as old ones are trimmed
+                let trimmed = (runtime.first_seq - first_seq) as usize;
+                if trimmed > 0 && !self.auto_scroll && self.active_script_id.as_deref() == Some(id.as_str()) {
+                    let rows = self.output_row_of(trimmed);
+                    self.output_scroll = self.output_scroll.saturating_sub(rows);
+                    let kept = trimmed.min(self.output_row_starts.len());
+                    self.output_row_starts.drain(..kept);
+                    for row in &mut self.output_row_starts {
+                        *row = row.saturating_sub(rows);
+                    }
+                }
+                return;
+            }
+            LogSource::Service(id) => &mut self.service_runtimes.entry(id).or_default().logs,
+        };
+        // Strip \r (carriage returns) which cause garbled display,
+        // but keep ANSI color codes for rendering
+        logs.extend(lines.into_iter().map(|line| LogLine { content: line.content.replace('\r', ""), ..line }));
+        // Keep the last `max_log_lines` lines
+        if logs.len() > max {
+            let drain = logs.len() - max;
+            logs.drain(..drain);
+        }
+    }
+
     pub fn handle_process_event(&mut self, event: ProcessEvent) {
         match event {
-            ProcessEvent::Log { script_id, stream, content } => {
-                let runtime = self.runtimes.entry(script_id).or_default();
-                // Strip \r (carriage returns) which cause garbled display,
-                // but keep ANSI color codes for rendering
-                let clean = content.replace('\r', "");
-                runtime.logs.push(LogLine { stream, content: clean });
-                // Keep last 5000 lines
-                if runtime.logs.len() > 5000 {
-                    let drain = runtime.logs.len() - 5000;
-                    runtime.logs.drain(..drain);
+            ProcessEvent::Log { script_id, run_id, stream, content, timestamp } => {
+                let key = run_key(&script_id, run_id.as_deref());
+                // Output after a prompt: it got past it
+                if let Some(runtime) = self.runtimes.get_mut(&key) {
+                    runtime.awaiting_input = None;
                 }
+                self.handle_log_batch(LogSource::Script(key), vec![LogLine { stream, content, timestamp }]);
             }
-            ProcessEvent::Status { script_id, status, pid } => {
+            ProcessEvent::Status { script_id, run_id, status, pid } => {
+                let script_id = run_key(&script_id, run_id.as_deref());
+                // Nothing left to type into
+                if status != ScriptStatus::Running
+                    && self.input_mode == InputMode::StdinInput
+                    && self.active_script_id.as_ref() == Some(&script_id)
+                {
+                    self.leave_stdin_input();
+                }
+                let keep_bookmarks = self.tui_config.keep_bookmarks;
                 let runtime = self.runtimes.entry(script_id).or_default();
+                if status == ScriptStatus::Running && runtime.status != ScriptStatus::Running {
+                    runtime.started_at = Some(Instant::now());
+                    runtime.finished_at = None;
+                    runtime.evicted_lines = 0;
+                    if !keep_bookmarks {
+                        runtime.bookmarks.clear();
+                    }
+                } else if status != ScriptStatus::Running && runtime.status == ScriptStatus::Running {
+                    runtime.finished_at = Some(Instant::now());
+                }
+                if status != ScriptStatus::Running {
+                    runtime.awaiting_input = None;
+                }
                 runtime.status = status;
                 runtime.pid = pid;
+                self.running_counts_at = None;
+                if self.pin_running {
+                    self.apply_filter();
+                }
             }
-            ProcessEvent::Exit { script_id, exit_code, success } => {
-                let runtime = self.runtimes.entry(script_id).or_default();
+            ProcessEvent::Prompt { script_id, run_id, prompt } => {
+                let key = run_key(&script_id, run_id.as_deref());
+                self.runtimes.entry(key.clone()).or_default().awaiting_input = Some(prompt.trim().to_string());
+                if self.active_script_id.as_deref() == Some(key.as_str()) {
+                    // Not while a form or a search is being typed in
+                    if self.input_mode == InputMode::Normal {
+                        self.enter_stdin_input();
+                    }
+                } else {
+                    let name = self.scripts.iter().find(|s| s.id == script_id).map_or(script_id.as_str(), |s| s.name.as_str());
+                    self.status_message = Some(format!("{} is waiting for input", name));
+                }
+            }
+            ProcessEvent::Exit { script_id, run_id, exit_code, success } => {
+                let patterns = self
+                    .scripts
+                    .iter()
+                    .find(|s| s.id == script_id)
+                    .map(|s| s.artifacts.clone())
+                    .unwrap_or_default();
+                let runtime = self.runtimes.entry(run_key(&script_id, run_id.as_deref())).or_default();
                 runtime.exit_code = exit_code;
                 runtime.success = Some(success);
                 runtime.status = if success { ScriptStatus::Completed } else { ScriptStatus::Failed };
-            }
-            ProcessEvent::ServiceLog { service_id, stream, content } => {
-                let runtime = self.service_runtimes.entry(service_id).or_default();
-                let clean = content.replace('\r', "");
-                runtime.logs.push(LogLine { stream, content: clean });
-                if runtime.logs.len() > 5000 {
-                    let drain = runtime.logs.len() - 5000;
-                    runtime.logs.drain(..drain);
+                runtime.finished_at = Some(Instant::now());
+                if success && !patterns.is_empty() {
+                    if let Some(working_dir) = &runtime.working_dir {
+                        runtime.artifacts = script_artifacts::resolve_artifacts(Path::new(working_dir), &patterns);
+                        for path in &runtime.artifacts {
+                            runtime.logs.push(LogLine {
+                                stream: LogStream::Stdout,
+                                content: format!("[cortx] artifact: {}", path),
+                                timestamp: chrono::Utc::now(),
+                            });
+                        }
+                        if !runtime.artifacts.is_empty() {
+                            self.status_message =
+                                Some(format!("{} artifact(s) created, O to open", runtime.artifacts.len()));
+                        }
+                    }
+                }
+                // Scheduled runs are the only ones the TUI records in the history
+                if let Some(record) = self.storage.get_execution_history(&script_id, 1).first() {
+                    if record.scheduled && record.finished_at.is_none() {
+                        let started_at = record.started_at;
+                        let _ = self.storage.update_execution_record(&record.id, |r| {
+                            r.finished_at = Some(chrono::Utc::now());
+                            r.success = success;
+                            r.exit_code = exit_code;
+                            r.duration_ms = Some((chrono::Utc::now() - started_at).num_milliseconds().max(0) as u64);
+                        });
+                    }
                 }
+                self.running_counts_at = None;
+                if self.pin_running {
+                    self.apply_filter();
+                }
+            }
+            ProcessEvent::ServiceLog { service_id, stream, content, timestamp } => {
+                self.handle_log_batch(LogSource::Service(service_id), vec![LogLine { stream, content, timestamp }]);
             }
-            ProcessEvent::ServiceStatus { service_id, status, pid } => {
+            ProcessEvent::ServiceStatus { service_id, status, pid, started_at } => {
                 let runtime = self.service_runtimes.entry(service_id).or_default();
                 runtime.status = status;
                 runtime.pid = pid;
+                runtime.started_at = started_at;
+                self.running_counts_at = None;
             }
-            ProcessEvent::ServiceExit { service_id, exit_code } => {
+            ProcessEvent::ServiceExit { service_id, exit_code, error_excerpt } => {
+                if let Some(excerpt) = &error_excerpt {
+                    let name = self
+                        .projects
+                        .iter()
+                        .flat_map(|p| &p.services)
+                        .find(|s| s.id == service_id)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_else(|| service_id.clone());
+                    let reason = excerpt.lines().last().unwrap_or("no output");
+                    self.status_message = Some(format!(
+                        "{} failed to start (exit {}): {}",
+                        name,
+                        exit_code.map(|c| c.to_string()).unwrap_or_else(|| "?".to_string()),
+                        reason,
+                    ));
+                }
                 let runtime = self.service_runtimes.entry(service_id).or_default();
                 runtime.exit_code = exit_code;
-                runtime.status = ServiceStatus::Stopped;
+                // A failed start keeps the Error status it was just given
+                if error_excerpt.is_none() {
+                    runtime.status = ServiceStatus::Stopped;
+                }
                 runtime.pid = None;
+                runtime.started_at = None;
+                self.running_counts_at = None;
             }
         }
     }
@@ -1701,32 +4178,47 @@ impl App {
 pub enum ProcessEvent {
     Log {
         script_id: String,
+        /// Set on the concurrent runs of a global script (see `run_key`)
+        run_id: Option<String>,
         stream: LogStream,
         content: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
     },
     Status {
         script_id: String,
+        run_id: Option<String>,
         status: ScriptStatus,
         pid: Option<u32>,
     },
     Exit {
         script_id: String,
+        run_id: Option<String>,
         exit_code: Option<i32>,
         success: bool,
     },
+    /// The run stopped at an interactive prompt, waiting for input
+    Prompt {
+        script_id: String,
+        run_id: Option<String>,
+        prompt: String,
+    },
     ServiceLog {
         service_id: String,
         stream: LogStream,
         content: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
     },
     ServiceStatus {
         service_id: String,
         status: ServiceStatus,
         pid: Option<u32>,
+        started_at: Option<chrono::DateTime<chrono::Utc>>,
     },
     ServiceExit {
         service_id: String,
         exit_code: Option<i32>,
+        /// Set when the service failed right after starting
+        error_excerpt: Option<String>,
     },
 }
 
@@ -1770,3 +4262,366 @@ pub fn save_run_state(script_id: &str, state: &SavedRunState) {
         let _ = std::fs::write(&path, json);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortx_core::runtime_state::RuntimeStore;
+
+    #[test]
+    fn paste_inserts_the_whole_text_at_the_cursor() {
+        let script = GlobalScript::new("deploy".into(), "deploy".into(), None);
+        let mut form = ParamFormState::new(&script);
+        form.param_names = vec!["token".into()];
+        form.values.insert("token".into(), "Bearer ".into());
+        form.editing = true;
+        form.cursor_pos = 7;
+
+        form.paste("sk-abc123\n");
+        assert_eq!(form.values["token"], "Bearer sk-abc123");
+        assert_eq!(form.cursor_pos, 16);
+
+        // Extra args: in the middle, line breaks as spaces
+        form.move_down();
+        form.extra_args = "-v --fast".into();
+        form.cursor_pos = 3;
+        form.paste("--env prod\n--dry-run ");
+        assert_eq!(form.extra_args, "-v --env prod --dry-run --fast");
+        assert_eq!(form.cursor_pos, 24);
+    }
+
+    #[test]
+    fn the_script_editor_cursor_steps_over_whole_characters() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let press = |app: &mut App, code: KeyCode| crate::input::handle_key(app, KeyEvent::new(code, KeyModifiers::NONE));
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+        app.open_new_script_editor();
+        press(&mut app, KeyCode::Enter);
+
+        press(&mut app, KeyCode::Char('é'));
+        press(&mut app, KeyCode::Char('t'));
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Char('r'));
+        press(&mut app, KeyCode::End);
+        crate::input::handle_paste(&mut app, "é 🚀");
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Delete);
+        let form = app.script_editor.as_ref().unwrap();
+        assert_eq!(form.values[ScriptEditorState::NAME], "rété");
+        assert_eq!((form.cursor_pos, form.cursor_byte()), (4, 6));
+
+        // Drawing splits the value at the cursor
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| crate::ui::draw(f, &mut app)).unwrap();
+    }
+
+    #[test]
+    fn settings_changed_in_the_overlay_persist_and_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+
+        app.open_settings();
+        let select = |app: &mut App, field: SettingsField| {
+            app.settings_form.as_mut().unwrap().selected =
+                SettingsField::ALL.iter().position(|f| *f == field).unwrap();
+        };
+
+        select(&mut app, SettingsField::ConfirmOnQuit);
+        app.activate_setting();
+
+        // Numbers are validated before anything is saved
+        select(&mut app, SettingsField::MaxLogLines);
+        app.activate_setting();
+        app.settings_form.as_mut().unwrap().editing = Some("12".into());
+        app.activate_setting();
+        assert!(app.settings_form.as_ref().unwrap().error.is_some());
+        app.settings_form.as_mut().unwrap().editing = Some("800".into());
+        app.activate_setting();
+        assert!(app.settings_form.as_ref().unwrap().editing.is_none());
+
+        // Applied without a restart...
+        assert!(app.tui_config.confirm_on_quit);
+        assert_eq!(app.tui_config.max_log_lines, 800);
+
+        // ...and saved for the next one
+        let reopened = Storage::with_app_dir(dir.path().to_path_buf()).unwrap();
+        let tui = reopened.get_settings().tui;
+        assert!(tui.confirm_on_quit && !tui.focus_output_on_run);
+        assert_eq!(tui.max_log_lines, 800);
+    }
+
+    #[test]
+    fn concurrent_runs_are_listed_and_shown_one_at_a_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+        let script = GlobalScript::new("deploy".into(), "deploy".into(), None);
+        let id = script.id.clone();
+        app.scripts = vec![script];
+
+        for run_id in [None, Some("r2")] {
+            let run_id = run_id.map(String::from);
+            app.handle_process_event(ProcessEvent::Status {
+                script_id: id.clone(),
+                run_id: run_id.clone(),
+                status: ScriptStatus::Running,
+                pid: Some(1),
+            });
+            let content = format!("from {}", run_id.as_deref().unwrap_or("first"));
+            app.handle_process_event(ProcessEvent::Log {
+                script_id: id.clone(),
+                run_id,
+                stream: LogStream::Stdout,
+                content,
+                timestamp: chrono::Utc::now(),
+            });
+        }
+        assert_eq!(app.script_runs(&id).len(), 2);
+        assert_eq!(app.running_count(&id), 2);
+
+        // Each run keeps its own output
+        app.active_script_id = Some(id.clone());
+        assert_eq!(app.get_active_logs()[0].content, "from first");
+        app.cycle_script_run(true);
+        assert_eq!(app.active_script_id, Some(format!("{}#r2", id)));
+        assert_eq!(app.active_script().map(|s| s.name.as_str()), Some("deploy"));
+        assert_eq!(app.get_active_logs()[0].content, "from r2");
+        app.cycle_script_run(true);
+        assert_eq!(app.active_script_id.as_deref(), Some(id.as_str()));
+
+        // A finished concurrent run drops out of the list once it isn't shown
+        app.handle_process_event(ProcessEvent::Exit {
+            script_id: id.clone(),
+            run_id: Some("r2".into()),
+            exit_code: Some(0),
+            success: true,
+        });
+        assert_eq!(app.running_count(&id), 1);
+        assert_eq!(app.script_runs(&id).len(), 1);
+    }
+
+    #[test]
+    fn a_prompt_in_the_background_is_announced_until_output_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+        let script = GlobalScript::new("migrate".into(), "migrate".into(), None);
+        let id = script.id.clone();
+        app.scripts = vec![script];
+
+        app.handle_process_event(ProcessEvent::Prompt { script_id: id.clone(), run_id: None, prompt: "Continue? [y/N] ".into() });
+        assert_eq!(app.runtimes[&id].awaiting_input.as_deref(), Some("Continue? [y/N]"));
+        assert_eq!(app.status_message.as_deref(), Some("migrate is waiting for input"));
+        assert_eq!(app.input_mode, InputMode::Normal);
+
+        // Answered from elsewhere: the script prints again
+        app.handle_process_event(ProcessEvent::Log {
+            script_id: id.clone(),
+            run_id: None,
+            stream: LogStream::Stdout,
+            content: "migrating".into(),
+            timestamp: chrono::Utc::now(),
+        });
+        assert_eq!(app.runtimes[&id].awaiting_input, None);
+    }
+
+    fn out(content: &str) -> LogLine {
+        LogLine { stream: LogStream::Stdout, content: content.into(), timestamp: chrono::Utc::now() }
+    }
+
+    #[test]
+    fn marker_lines_become_bookmarks_that_survive_trimming() {
+        let mut runtime = ScriptRuntime::default();
+        runtime.append_logs(vec![out("a"), out("::cortx-mark::build"), out("b"), out("c")], 3);
+        // The marker isn't shown and names the line after it
+        let contents: Vec<&str> = runtime.logs.iter().map(|l| l.content.as_str()).collect();
+        assert_eq!(contents, vec!["a", "b", "c"]);
+        assert_eq!(runtime.bookmarks[0].label.as_deref(), Some("build"));
+        assert_eq!(bookmarks::locate(runtime.bookmarks[0].seq, runtime.first_seq, runtime.logs.len()), Jump::Line(1));
+
+        // Trimmed once: still on "b"
+        runtime.append_logs(vec![out("d")], 3);
+        assert_eq!(bookmarks::locate(runtime.bookmarks[0].seq, runtime.first_seq, runtime.logs.len()), Jump::Line(0));
+        assert_eq!(runtime.logs[0].content, "b");
+
+        // Trimmed past it, then evicted as a finished run
+        runtime.append_logs(vec![out("e")], 3);
+        assert_eq!(bookmarks::locate(runtime.bookmarks[0].seq, runtime.first_seq, runtime.logs.len()), Jump::Evicted);
+        runtime.add_bookmark(runtime.end_seq() - 1, None, chrono::Utc::now());
+        runtime.evict_logs();
+        assert_eq!(bookmarks::locate(runtime.bookmarks[1].seq, runtime.first_seq, runtime.logs.len()), Jump::Line(0));
+    }
+
+    #[test]
+    fn output_search_keeps_its_place_while_logs_stream_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+        app.tui_config.max_log_lines = 5;
+        app.active_script_id = Some("s".into());
+        let source = || LogSource::Script("s".into());
+        app.handle_log_batch(source(), vec![out("ok"), out("Error: a"), out("ok"), out("error: b"), out("ok")]);
+
+        app.enter_output_search();
+        for c in "error".chars() {
+            app.output_search_input(c);
+        }
+        app.confirm_output_search();
+        assert_eq!(app.output_search_matches(), vec![1, 3]);
+        app.jump_to_output_match(true);
+        assert_eq!(app.output_search_current(), Some(3));
+        assert_eq!(app.output_scroll, 3);
+
+        // Two lines trimmed: still on "error: b", and so is the view
+        app.handle_log_batch(source(), vec![out("ok"), out("ok")]);
+        assert_eq!(app.output_search_current(), Some(1));
+        assert_eq!(app.output_scroll, 1);
+        assert_eq!(app.get_active_logs()[1].content, "error: b");
+
+        // Plain text isn't a pattern; every occurrence is highlighted
+        let search = app.output_search.as_mut().unwrap();
+        search.query = "b.".into();
+        search.recompile();
+        assert!(search.match_ranges("error: b").is_empty());
+        assert_eq!(search.match_ranges("B. and b."), vec![0..2, 7..9]);
+    }
+
+    #[test]
+    fn wrapped_output_scrolls_by_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage, process_manager, Arc::new(TuiEmitter::new(tx)));
+        app.tui_config.max_log_lines = 4;
+        app.active_script_id = Some("s".into());
+        app.handle_log_batch(LogSource::Script("s".into()), vec![out("a"), out("b"), out("c"), out("d")]);
+
+        // As drawn wrapped: "b" took three rows
+        app.output_row_starts = vec![0, 1, 4, 5];
+        app.auto_scroll = false;
+        app.output_scroll = 3;
+        assert_eq!(app.output_top_line(), 1);
+        assert_eq!(app.output_row_of(2), 4);
+
+        // Unwrapping keeps "b" on top once redrawn
+        app.toggle_wrap_output();
+        assert_eq!(app.output_anchor, Some(1));
+
+        // Trimming "a" takes its row off the view's offset
+        app.handle_log_batch(LogSource::Script("s".into()), vec![out("e")]);
+        assert_eq!(app.output_scroll, 2);
+        assert_eq!(app.output_row_starts, vec![0, 3, 4]);
+        assert_eq!(app.output_top_line(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_bound_quick_slot_key_runs_its_script() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let press = |app: &mut App, code: KeyCode| crate::input::handle_key(app, KeyEvent::new(code, KeyModifiers::NONE));
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage.clone(), process_manager.clone(), Arc::new(TuiEmitter::new(tx)));
+        let script = storage.create_global_script(GlobalScript::new("nap".into(), "sleep 5".into(), None)).unwrap();
+        app.refresh_data();
+        app.active_tab = ActiveTab::Scripts;
+
+        // `b` then a digit binds the selected script, saved right away
+        press(&mut app, KeyCode::Char('b'));
+        assert_eq!(app.input_mode, InputMode::BindSlot);
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.tui_config.quick_slots.get(&3), Some(&script.id));
+        assert_eq!(storage.get_settings().tui.quick_slots.get(&3), Some(&script.id));
+
+        // From another tab, F3 runs it
+        app.active_tab = ActiveTab::Tools;
+        press(&mut app, KeyCode::F(3));
+        assert!(process_manager.is_global_script_running(&script.id));
+        assert_eq!(app.active_script_id.as_deref(), Some(script.id.as_str()));
+        process_manager.stop_all();
+
+        // Its script deleted, the slot is shown as empty and runs nothing
+        storage.delete_global_script(&script.id).unwrap();
+        app.refresh_data();
+        assert!(app.quick_slot_script(3).is_none());
+        press(&mut app, KeyCode::F(3));
+        assert_eq!(app.status_message.as_deref(), Some("Slot 3 is empty; b on a script binds it"));
+        press(&mut app, KeyCode::F(4));
+        assert_eq!(app.status_message.as_deref(), Some("Slot 4 is empty; b on a script binds it"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shift_enter_in_the_service_editor_restarts_with_the_new_command() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let press = |app: &mut App, code: KeyCode, modifiers: KeyModifiers| {
+            crate::input::handle_key(app, KeyEvent::new(code, modifiers))
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(Storage::with_app_dir(dir.path().to_path_buf()).unwrap());
+        let process_manager = Arc::new(ProcessManager::new(Arc::new(RuntimeStore::new(dir.path()).unwrap())));
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let mut app = App::new(storage.clone(), process_manager.clone(), Arc::new(TuiEmitter::new(tx)));
+        let project = storage.create_project(Project::new("shop".into(), dir.path().to_string_lossy().to_string())).unwrap();
+        let service = storage.add_service(&project.id, Service::new("api".into(), ".".into(), "sleep 30".into())).unwrap();
+        app.refresh_data();
+        app.active_tab = ActiveTab::Projects;
+        app.enter_project_detail();
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(process_manager.is_service_running(&service.id));
+
+        // `e`, change the command, Shift+Enter: saved, and running the new one
+        press(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert_eq!(app.input_mode, InputMode::ServiceEditor);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut app, KeyCode::Char('1'), KeyModifiers::NONE);
+        press(&mut app, KeyCode::Enter, KeyModifiers::SHIFT);
+        assert_eq!(app.input_mode, InputMode::Normal);
+        // The restart happens in the background, reported on a later tick
+        assert_eq!(app.status_message.as_deref(), Some("Restarting api…"));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.status_message.as_deref() == Some("Restarting api…") {
+            assert!(Instant::now() < deadline, "the restart never finished");
+            std::thread::sleep(Duration::from_millis(20));
+            app.on_tick();
+        }
+        assert_eq!(app.status_message.as_deref(), Some("Saved and restarted api"));
+        assert_eq!(storage.get_service(&service.id).unwrap().1.command, "sleep 31");
+        assert_eq!(app.selected_service().map(|s| s.command.as_str()), Some("sleep 31"));
+        let running = process_manager.runtime_store().get(&service.id).unwrap();
+        assert_eq!(running.command, "sleep 31");
+
+        // A plain Enter only saves
+        press(&mut app, KeyCode::Char('e'), KeyModifiers::NONE);
+        press(&mut app, KeyCode::Char('0'), KeyModifiers::NONE);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.status_message.as_deref(), Some("Saved api"));
+        assert_eq!(storage.get_service(&service.id).unwrap().1.command, "sleep 310");
+        assert_eq!(process_manager.runtime_store().get(&service.id).unwrap().pid, running.pid);
+        process_manager.stop_all();
+    }
+}
@@ -0,0 +1,77 @@
+//! Bookmarks in a script's output (`m` in the output panel, listed with `'`).
+//!
+//! Output lines are numbered as they arrive, and a runtime remembers the
+//! number of its oldest line. A bookmark holds a line number, so it still
+//! finds its line after older lines were trimmed, and can tell when its own
+//! line was. Scripts bookmark themselves by printing a marker line
+//! (`::cortx-mark::<label>`), which is taken out of the output.
+
+use chrono::{DateTime, Utc};
+
+use crate::util::strip_ansi;
+
+/// Prefix of the line a script prints to bookmark its output
+pub const MARKER: &str = "::cortx-mark::";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    /// Number of the bookmarked line
+    pub seq: u64,
+    /// Given by a marker line; bookmarks added with `m` have none
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Where jumping to a bookmark lands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    /// Index of the bookmarked line in the buffer
+    Line(usize),
+    /// The line was trimmed from the buffer
+    Evicted,
+}
+
+/// Find line `seq` in a buffer whose oldest line is `first_seq`. A marker
+/// at the very end points past the last line, which is used instead.
+pub fn locate(seq: u64, first_seq: u64, len: usize) -> Jump {
+    if seq < first_seq {
+        return Jump::Evicted;
+    }
+    let index = usize::try_from(seq - first_seq).unwrap_or(usize::MAX);
+    Jump::Line(index.min(len.saturating_sub(1)))
+}
+
+/// The label of a marker line: `None` if `line` isn't one, an empty label
+/// for a bare marker. Colors and surrounding whitespace are ignored.
+pub fn parse_marker(line: &str) -> Option<String> {
+    let text = strip_ansi(line);
+    text.trim().strip_prefix(MARKER).map(|label| label.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_marker_lines() {
+        assert_eq!(parse_marker("::cortx-mark::tests done"), Some("tests done".to_string()));
+        assert_eq!(parse_marker("  ::cortx-mark:: linking \r"), Some("linking".to_string()));
+        assert_eq!(parse_marker("\x1b[32m::cortx-mark::green\x1b[0m"), Some("green".to_string()));
+        assert_eq!(parse_marker("::cortx-mark::"), Some(String::new()));
+        assert_eq!(parse_marker("echo ::cortx-mark::late"), None);
+        assert_eq!(parse_marker("::cortx-mark:"), None);
+    }
+
+    #[test]
+    fn bookmarks_follow_their_line_through_trimming() {
+        // Lines 0..10 arrived, the bookmark is on line 6
+        assert_eq!(locate(6, 0, 10), Jump::Line(6));
+        // Four oldest lines trimmed: same line, new index
+        assert_eq!(locate(6, 4, 6), Jump::Line(2));
+        // Its line trimmed too
+        assert_eq!(locate(6, 7, 3), Jump::Evicted);
+        // A marker printed last points just past the buffer
+        assert_eq!(locate(10, 4, 6), Jump::Line(5));
+        assert_eq!(locate(3, 3, 0), Jump::Line(0));
+    }
+}
@@ -18,6 +18,9 @@ pub enum ErrorCode {
     StorageError,
     AlreadyRunning,
     NotRunning,
+    RequirementNotMet,
+    RunRefused,
+    CheckFailed,
     PermissionDenied,
     Internal,
 }
@@ -113,6 +116,31 @@ impl CortxError {
         .with_identifier(identifier)
     }
 
+    /// A script's required services aren't running; each missing one is
+    /// listed (as "project / service") in `suggestions`.
+    pub fn requirement_not_met(err: &cortx_core::script_requirements::RequirementNotMet) -> Self {
+        Self::new(ErrorCode::RequirementNotMet, err.to_string())
+            .with_resource("global_script")
+            .with_identifier(&err.script_name)
+            .with_suggestions(err.missing.iter().map(|m| m.label.clone()).collect())
+    }
+
+    /// A script run from inside another cortx run would loop back on itself
+    /// or nest past `scriptsConfig.maxRunDepth`.
+    pub fn run_refused(err: &cortx_core::run_guard::RunGuardError, script_name: &str) -> Self {
+        Self::new(ErrorCode::RunRefused, err.to_string())
+            .with_resource("global_script")
+            .with_identifier(script_name)
+    }
+
+    /// `cortx doctor` found problems that keep cortx from working.
+    pub fn checks_failed(count: usize) -> Self {
+        Self::new(
+            ErrorCode::CheckFailed,
+            format!("{} critical check{} failed", count, if count == 1 { "" } else { "s" }),
+        )
+    }
+
     pub fn invalid_argument(message: impl Into<String>) -> Self {
         Self::new(ErrorCode::InvalidArgument, message)
     }
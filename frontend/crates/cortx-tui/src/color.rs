@@ -0,0 +1,253 @@
+//! How many colors the terminal takes, and hex colors (tag / status colors)
+//! brought down to that.
+//!
+//! Truecolor escapes show up as garbage on terminals without support (older
+//! Windows consoles, some CI logs), so the CLI picks a [`ColorLevel`] once at
+//! startup and every hex color goes through [`paint`]. The TUI theme maps
+//! hex colors the same way, from the terminal's capability alone.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use ratatui::style::Color;
+
+/// Colors the output may use, from none to 24-bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    None,
+    /// The 16 basic ANSI colors
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+/// Level of the CLI's output, set once by [`init`]
+static LEVEL: AtomicU8 = AtomicU8::new(ColorLevel::None as u8);
+
+pub fn init(level: ColorLevel) {
+    LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+pub fn level() -> ColorLevel {
+    match LEVEL.load(Ordering::SeqCst) {
+        1 => ColorLevel::Basic,
+        2 => ColorLevel::Ansi256,
+        3 => ColorLevel::TrueColor,
+        _ => ColorLevel::None,
+    }
+}
+
+/// What the terminal described by `env` can show: `COLORTERM` announces
+/// truecolor, `TERM` 256 colors or none at all (`dumb`). Windows Terminal
+/// sets `WT_SESSION` instead; anything else gets the basic colors.
+pub fn terminal_level(env: impl Fn(&str) -> Option<String>) -> ColorLevel {
+    let colorterm = env("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" || env("WT_SESSION").is_some() {
+        return ColorLevel::TrueColor;
+    }
+    let term = env("TERM").unwrap_or_default().to_lowercase();
+    if term == "dumb" {
+        ColorLevel::None
+    } else if term.contains("256color") || term.contains("truecolor") || term.contains("direct") {
+        ColorLevel::Ansi256
+    } else {
+        ColorLevel::Basic
+    }
+}
+
+/// Level of the CLI's output, using the standard precedence:
+/// 1. `--no-color` flag → none
+/// 2. `CLICOLOR_FORCE` (any non-empty value) → on, even off a terminal
+/// 3. `NO_COLOR` (any non-empty value, https://no-color.org) → none
+/// 4. stdout not a terminal → none
+///
+/// and when on, as many colors as [`terminal_level`] allows.
+pub fn cli_level(no_color_flag: bool, env: impl Fn(&str) -> Option<String>, is_terminal: bool) -> ColorLevel {
+    let set = |name: &str| env(name).is_some_and(|v| !v.is_empty());
+    if no_color_flag {
+        ColorLevel::None
+    } else if set("CLICOLOR_FORCE") {
+        terminal_level(&env).max(ColorLevel::Basic)
+    } else if set("NO_COLOR") || !is_terminal {
+        ColorLevel::None
+    } else {
+        terminal_level(&env)
+    }
+}
+
+/// `#rrggbb` (the `#` is optional) as its components
+pub fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// xterm's defaults for the 16 basic colors, by index
+const BASIC_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each channel in the 6×6×6 cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+/// Index (0–15) of the basic color closest to `rgb`
+pub fn nearest_basic(rgb: (u8, u8, u8)) -> u8 {
+    (0..16u8).min_by_key(|&i| distance(rgb, BASIC_PALETTE[i as usize])).unwrap_or(7)
+}
+
+/// Index (16–255) of the closest color in the cube or the gray ramp of the
+/// 256-color palette. The first 16 are left out: terminals theme them.
+pub fn nearest_256((r, g, b): (u8, u8, u8)) -> u8 {
+    let level = |v: u8| CUBE_LEVELS.iter().enumerate().min_by_key(|(_, &l)| (l as i32 - v as i32).abs()).map_or(0, |(i, _)| i);
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8;
+
+    // Grays run from 8 to 238 in steps of 10
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * step;
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        232 + step
+    } else {
+        cube_index
+    }
+}
+
+/// Escape that sets the foreground to `rgb` at `level`
+pub fn fg_escape(level: ColorLevel, rgb: (u8, u8, u8)) -> Option<String> {
+    match level {
+        ColorLevel::None => None,
+        ColorLevel::Basic => {
+            let i = nearest_basic(rgb);
+            Some(format!("\x1b[{}m", if i < 8 { 30 + i } else { 90 + i - 8 }))
+        }
+        ColorLevel::Ansi256 => Some(format!("\x1b[38;5;{}m", nearest_256(rgb))),
+        ColorLevel::TrueColor => Some(format!("\x1b[38;2;{};{};{}m", rgb.0, rgb.1, rgb.2)),
+    }
+}
+
+/// `text` in the hex color `hex`, at the CLI's level. Plain when color is
+/// off or `hex` isn't a color.
+pub fn paint(hex: &str, text: &str) -> String {
+    match parse_hex(hex).and_then(|rgb| fg_escape(level(), rgb)) {
+        Some(escape) => format!("{}{}\x1b[0m", escape, text),
+        None => text.to_string(),
+    }
+}
+
+/// `rgb` as a ratatui color the terminal at `level` can show. The TUI always
+/// draws some color, so `None` is treated as basic.
+pub fn tui_color(level: ColorLevel, rgb: (u8, u8, u8)) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    match level {
+        ColorLevel::TrueColor => Color::Rgb(rgb.0, rgb.1, rgb.2),
+        ColorLevel::Ansi256 => Color::Indexed(nearest_256(rgb)),
+        ColorLevel::Basic | ColorLevel::None => NAMED[nearest_basic(rgb) as usize],
+    }
+}
+
+/// What the terminal the TUI draws on can show (read once)
+pub fn tui_level() -> ColorLevel {
+    static TUI_LEVEL: OnceLock<ColorLevel> = OnceLock::new();
+    *TUI_LEVEL.get_or_init(|| terminal_level(|name| std::env::var(name).ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn hex_colors_come_down_to_the_nearest_palette_entry() {
+        assert_eq!(parse_hex("#3b82f6"), Some((0x3b, 0x82, 0xf6)));
+        assert_eq!(parse_hex("3b82f"), None);
+        assert_eq!(parse_hex("#zz0000"), None);
+
+        // Cube corners and the gray ramp
+        assert_eq!(nearest_256((255, 0, 0)), 196);
+        assert_eq!(nearest_256((0, 0, 0)), 16);
+        assert_eq!(nearest_256((255, 255, 255)), 231);
+        assert_eq!(nearest_256((128, 128, 128)), 244);
+        assert_eq!(nearest_256((0x3b, 0x82, 0xf6)), 69);
+
+        assert_eq!(nearest_basic((250, 10, 10)), 9);
+        assert_eq!(nearest_basic((200, 0, 0)), 1);
+        assert_eq!(nearest_basic((0x3b, 0x82, 0xf6)), 12);
+        assert_eq!(nearest_basic((30, 30, 30)), 0);
+
+        let blue = (0x3b, 0x82, 0xf6);
+        assert_eq!(fg_escape(ColorLevel::TrueColor, blue).unwrap(), "\x1b[38;2;59;130;246m");
+        assert_eq!(fg_escape(ColorLevel::Ansi256, blue).unwrap(), "\x1b[38;5;69m");
+        assert_eq!(fg_escape(ColorLevel::Basic, blue).unwrap(), "\x1b[94m");
+        assert_eq!(fg_escape(ColorLevel::Basic, (200, 0, 0)).unwrap(), "\x1b[31m");
+        assert_eq!(fg_escape(ColorLevel::None, blue), None);
+    }
+
+    #[test]
+    fn color_level_follows_flags_and_terminal() {
+        let truecolor = [("COLORTERM", "truecolor"), ("TERM", "xterm-256color")];
+        assert_eq!(cli_level(false, env(&truecolor), true), ColorLevel::TrueColor);
+        assert_eq!(cli_level(false, env(&[("TERM", "xterm-256color")]), true), ColorLevel::Ansi256);
+        assert_eq!(cli_level(false, env(&[("TERM", "xterm")]), true), ColorLevel::Basic);
+        assert_eq!(cli_level(false, env(&[]), true), ColorLevel::Basic);
+        assert_eq!(cli_level(false, env(&[("TERM", "dumb")]), true), ColorLevel::None);
+        assert_eq!(cli_level(false, env(&[("WT_SESSION", "1")]), true), ColorLevel::TrueColor);
+
+        // Suppressed by NO_COLOR, the flag, or a pipe
+        assert_eq!(cli_level(false, env(&[("NO_COLOR", "1"), truecolor[0]]), true), ColorLevel::None);
+        assert_eq!(cli_level(false, env(&[("NO_COLOR", ""), truecolor[0]]), true), ColorLevel::TrueColor);
+        assert_eq!(cli_level(true, env(&truecolor), true), ColorLevel::None);
+        assert_eq!(cli_level(false, env(&truecolor), false), ColorLevel::None);
+
+        // CLICOLOR_FORCE wins over NO_COLOR and pipes, never over the flag
+        let forced = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")];
+        assert_eq!(cli_level(false, env(&forced), false), ColorLevel::Basic);
+        assert_eq!(cli_level(true, env(&forced), false), ColorLevel::None);
+    }
+}
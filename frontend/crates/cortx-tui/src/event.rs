@@ -8,6 +8,8 @@ use crate::app::ProcessEvent;
 pub enum Event {
     /// Keyboard/mouse input
     Key(KeyEvent),
+    /// Text pasted into the terminal, in one piece (bracketed paste)
+    Paste(String),
     /// Process event from the emitter channel
     Process(ProcessEvent),
     /// External data change detected by file watcher
@@ -50,6 +52,11 @@ pub fn event_loop(
                         }
                     }
                 }
+                Ok(CEvent::Paste(text)) => {
+                    if event_tx.send(Event::Paste(text)).is_err() {
+                        return;
+                    }
+                }
                 Ok(CEvent::Resize(_, _)) => {
                     // Terminal auto-handles resize on next draw
                 }
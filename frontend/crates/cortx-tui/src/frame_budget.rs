@@ -0,0 +1,238 @@
+//! How the main loop splits queued events into frames.
+//!
+//! A script printing tens of thousands of lines a second would otherwise
+//! have every line handled, one entry lookup at a time, before the next
+//! draw. Each frame takes at most `max_events` queued events, handles key
+//! presses and pastes first, and turns runs of log lines into one batch per
+//! script or service. What the time budget doesn't cover is carried over.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crossterm::event::KeyEvent;
+
+use cortx_core::models::TuiEventBudgetConfig;
+use cortx_core::process_manager::run_key;
+
+use crate::app::{LogLine, ProcessEvent};
+use crate::event::Event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameBudget {
+    /// Queued events taken per frame
+    pub max_events: usize,
+    /// Time spent handling work before drawing anyway
+    pub max_time: Duration,
+}
+
+impl From<&TuiEventBudgetConfig> for FrameBudget {
+    fn from(config: &TuiEventBudgetConfig) -> Self {
+        Self {
+            // 0 would never make progress
+            max_events: config.max_events.max(1),
+            max_time: Duration::from_millis(config.max_millis),
+        }
+    }
+}
+
+/// Whose output a log batch is
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogSource {
+    Script(String),
+    Service(String),
+}
+
+/// Something the user typed or pasted
+pub enum UserInput {
+    Key(KeyEvent),
+    Paste(String),
+}
+
+/// One step of a frame, after user input
+pub enum Work {
+    Event(Event),
+    /// Consecutive lines of one source, applied with a single lookup
+    Logs { source: LogSource, lines: Vec<LogLine> },
+}
+
+/// A frame's events, reordered: `keys` run first, then `work` in order.
+pub struct Frame {
+    /// Key presses and pastes, in the order they were made
+    pub keys: Vec<UserInput>,
+    pub work: Vec<Work>,
+}
+
+/// Split `events` (in arrival order) into a [`Frame`].
+///
+/// Key presses and pastes jump ahead of everything else, keeping their own
+/// order. A
+/// log line joins the open batch of its source, so each source's lines stay
+/// in order. Any other event for that source (status, exit) closes its
+/// batch, and data reloads and ticks close all of them, so those events
+/// still see exactly the lines that arrived before them.
+pub fn plan_frame(events: Vec<Event>) -> Frame {
+    let mut keys = Vec::new();
+    let mut work = Vec::new();
+    // Source → index of its open batch in `work`
+    let mut open: HashMap<LogSource, usize> = HashMap::new();
+
+    for event in events {
+        let (source, line) = match event {
+            Event::Key(key) => {
+                keys.push(UserInput::Key(key));
+                continue;
+            }
+            Event::Paste(text) => {
+                keys.push(UserInput::Paste(text));
+                continue;
+            }
+            Event::Process(ProcessEvent::Log { script_id, run_id, stream, content, timestamp }) => {
+                (LogSource::Script(run_key(&script_id, run_id.as_deref())), LogLine { stream, content, timestamp })
+            }
+            Event::Process(ProcessEvent::ServiceLog { service_id, stream, content, timestamp }) => {
+                (LogSource::Service(service_id), LogLine { stream, content, timestamp })
+            }
+            Event::Process(pe) => {
+                if let Some(source) = source_of(&pe) {
+                    open.remove(&source);
+                }
+                work.push(Work::Event(Event::Process(pe)));
+                continue;
+            }
+            other => {
+                open.clear();
+                work.push(Work::Event(other));
+                continue;
+            }
+        };
+
+        match open.get(&source) {
+            Some(&i) => {
+                if let Work::Logs { lines, .. } = &mut work[i] {
+                    lines.push(line);
+                }
+            }
+            None => {
+                open.insert(source.clone(), work.len());
+                work.push(Work::Logs { source, lines: vec![line] });
+            }
+        }
+    }
+
+    Frame { keys, work }
+}
+
+/// The script or service a non-log process event is about
+fn source_of(event: &ProcessEvent) -> Option<LogSource> {
+    match event {
+        ProcessEvent::Status { script_id, run_id, .. }
+        | ProcessEvent::Exit { script_id, run_id, .. }
+        | ProcessEvent::Prompt { script_id, run_id, .. } => {
+            Some(LogSource::Script(run_key(script_id, run_id.as_deref())))
+        }
+        ProcessEvent::ServiceStatus { service_id, .. } | ProcessEvent::ServiceExit { service_id, .. } => {
+            Some(LogSource::Service(service_id.clone()))
+        }
+        ProcessEvent::Log { .. } | ProcessEvent::ServiceLog { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortx_core::models::{LogStream, ScriptStatus};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn log(script: &str, content: &str) -> Event {
+        Event::Process(ProcessEvent::Log {
+            script_id: script.into(),
+            run_id: None,
+            stream: LogStream::Stdout,
+            content: content.into(),
+            timestamp: chrono::Utc::now(),
+        })
+    }
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    /// The key of a key press, `Null` for a paste
+    fn key_code(input: &UserInput) -> KeyCode {
+        match input {
+            UserInput::Key(key) => key.code,
+            UserInput::Paste(_) => KeyCode::Null,
+        }
+    }
+
+    fn status(script: &str) -> Event {
+        Event::Process(ProcessEvent::Status {
+            script_id: script.into(),
+            run_id: None,
+            status: ScriptStatus::Completed,
+            pid: None,
+        })
+    }
+
+    /// `a:1,2` for a batch, `status a` / `tick` / ... for other work
+    fn describe(work: &[Work]) -> Vec<String> {
+        work.iter()
+            .map(|w| match w {
+                Work::Logs { source: LogSource::Script(id) | LogSource::Service(id), lines } => {
+                    let lines: Vec<&str> = lines.iter().map(|l| l.content.as_str()).collect();
+                    format!("{}:{}", id, lines.join(","))
+                }
+                Work::Event(Event::Process(ProcessEvent::Status { script_id, .. })) => format!("status {}", script_id),
+                Work::Event(Event::Tick) => "tick".to_string(),
+                Work::Event(_) => "other".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keys_first_and_logs_batched_per_script_in_order() {
+        let events = vec![
+            log("a", "1"),
+            log("b", "1"),
+            key('j'),
+            log("a", "2"),
+            log("b", "2"),
+            key('k'),
+            log("a", "3"),
+        ];
+        let frame = plan_frame(events);
+
+        let keys: Vec<KeyCode> = frame.keys.iter().map(key_code).collect();
+        assert_eq!(keys, vec![KeyCode::Char('j'), KeyCode::Char('k')]);
+        assert_eq!(describe(&frame.work), vec!["a:1,2,3", "b:1,2"]);
+    }
+
+    #[test]
+    fn other_events_keep_their_place_relative_to_logs() {
+        let events = vec![
+            log("a", "1"),
+            log("b", "1"),
+            status("a"),
+            log("a", "2"),
+            log("b", "2"),
+            Event::Tick,
+            log("b", "3"),
+        ];
+        let frame = plan_frame(events);
+
+        // a's status sees line 1 only; b's batch stays open across it, and
+        // the tick closes everything
+        assert_eq!(describe(&frame.work), vec!["a:1", "b:1,2", "status a", "a:2", "tick", "b:3"]);
+    }
+
+    #[test]
+    fn pastes_keep_their_place_among_keys() {
+        let events = vec![log("a", "1"), key('i'), Event::Paste("sk-abc123".into()), log("a", "2"), key('x')];
+        let frame = plan_frame(events);
+
+        let keys: Vec<KeyCode> = frame.keys.iter().map(key_code).collect();
+        assert_eq!(keys, vec![KeyCode::Char('i'), KeyCode::Null, KeyCode::Char('x')]);
+        assert!(matches!(&frame.keys[1], UserInput::Paste(text) if text == "sk-abc123"));
+        assert_eq!(describe(&frame.work), vec!["a:1,2"]);
+    }
+}
@@ -1,19 +1,101 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use crate::app::{App, InputMode, ActivePanel, ActiveTab};
+use crate::app::{App, InputMode, ActivePanel, ActiveTab, PathPromptPurpose, SettingsField, QUICK_SLOTS};
+use crate::util::single_line;
 use cortx_core::models::ScriptParamType;
 
 pub fn handle_key(app: &mut App, key: KeyEvent) {
+    // Status-bar messages are one-shot: any key press dismisses them
+    app.status_message = None;
+
+    // Second `q` after a quit asked for confirmation; any other key cancels it
+    if std::mem::take(&mut app.quit_pending) {
+        let confirm = key.code == KeyCode::Char('q')
+            || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL));
+        if confirm {
+            app.should_quit = true;
+            return;
+        }
+    }
+
     match app.input_mode {
         InputMode::Normal => handle_normal(app, key),
         InputMode::Search => handle_search(app, key),
         InputMode::Help => handle_help(app, key),
         InputMode::ParamForm => handle_param_form(app, key),
         InputMode::TagFilter => handle_tag_filter(app, key),
+        InputMode::ScriptEditor => handle_script_editor(app, key),
+        InputMode::ConfirmDelete => handle_confirm_delete(app, key),
+        InputMode::PathPrompt => handle_path_prompt(app, key),
+        InputMode::FolderJump => handle_folder_jump(app, key),
+        InputMode::ConfirmRequirements => handle_confirm_requirements(app, key),
+        InputMode::OrganizePreview => handle_organize_preview(app, key),
+        InputMode::ScanResults => handle_scan_results(app, key),
+        InputMode::OutputSearch => handle_output_search(app, key),
+        InputMode::StdinInput => handle_stdin_input(app, key),
+        InputMode::RunHistory => handle_run_history(app, key),
+        InputMode::RunCompare => handle_run_compare(app, key),
+        InputMode::Settings => handle_settings(app, key),
+        InputMode::Bookmarks => handle_bookmarks(app, key),
+        InputMode::BindSlot => handle_bind_slot(app, key),
+        InputMode::ServiceEditor => handle_service_editor(app, key),
+    }
+}
+
+/// The quick slot `key` launches: `F1`-`F9`, or `Alt+1`-`Alt+9` for
+/// terminals that keep the function keys to themselves
+fn quick_slot_key(key: KeyEvent) -> Option<u8> {
+    let slot = match key.code {
+        KeyCode::F(n) => n,
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => c as u8 - b'0',
+        _ => return None,
+    };
+    (1..=QUICK_SLOTS).contains(&slot).then_some(slot)
+}
+
+/// Text pasted into the terminal. The param form inserts it at the cursor
+/// in one edit; other text inputs take it as typed characters. Everything
+/// else ignores it, so a paste never triggers shortcuts.
+pub fn handle_paste(app: &mut App, text: &str) {
+    app.status_message = None;
+
+    let typing = match app.input_mode {
+        InputMode::ParamForm => {
+            if let Some(form) = app.param_form.as_mut() {
+                if form.editing && !form.picking_preset && !form.showing_help {
+                    form.paste(text);
+                }
+            }
+            return;
+        }
+        InputMode::ScriptEditor => app.script_editor.as_ref().is_some_and(|f| f.editing),
+        InputMode::Search
+        | InputMode::OutputSearch
+        | InputMode::StdinInput
+        | InputMode::FolderJump
+        | InputMode::PathPrompt
+        | InputMode::ServiceEditor => true,
+        _ => false,
+    };
+    if typing {
+        for c in single_line(text).chars() {
+            handle_key(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
     }
 }
 
 fn handle_normal(app: &mut App, key: KeyEvent) {
+    // Works everywhere, project detail included
+    if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        app.retry_pending_saves();
+        return;
+    }
+    // The quick run bar works from every tab
+    if let Some(slot) = quick_slot_key(key) {
+        app.run_quick_slot(slot);
+        return;
+    }
+
     // While drilled into a project, route everything through the detail handler.
     // Top-level shortcuts (tab switch, quit, help) still apply, but search /
     // tag filter / Esc-clear are scoped to "exit detail" instead.
@@ -24,9 +106,9 @@ fn handle_normal(app: &mut App, key: KeyEvent) {
 
     match key.code {
         // Quit
-        KeyCode::Char('q') => app.should_quit = true,
+        KeyCode::Char('q') => app.request_quit(),
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.should_quit = true;
+            app.request_quit();
         }
 
         // Tab switching
@@ -39,9 +121,13 @@ fn handle_normal(app: &mut App, key: KeyEvent) {
         // Help
         KeyCode::Char('?') => app.input_mode = InputMode::Help,
 
+        // Settings
+        KeyCode::Char(',') => app.open_settings(),
+
         // Search — dispatch based on active tab
         KeyCode::Char('/') => {
             match app.active_tab {
+                ActiveTab::Scripts if app.active_panel == ActivePanel::Output => app.enter_output_search(),
                 ActiveTab::Scripts => app.enter_search(),
                 ActiveTab::Tools => app.enter_tools_search(),
                 ActiveTab::Aliases => app.enter_aliases_search(),
@@ -50,12 +136,17 @@ fn handle_normal(app: &mut App, key: KeyEvent) {
             }
         }
 
-        // Tag filter
+        // Timestamps in the output panel, otherwise the tag filter
+        KeyCode::Char('t') if app.active_tab == ActiveTab::Scripts && app.active_panel == ActivePanel::Output => {
+            app.toggle_timestamps();
+        }
         KeyCode::Char('t') => app.enter_tag_filter(),
 
         // Clear filter — dispatch based on active tab
         KeyCode::Esc => {
             match app.active_tab {
+                // A log search is cleared before the list filters
+                ActiveTab::Scripts if app.output_search.is_some() => app.clear_output_search(),
                 ActiveTab::Scripts => app.clear_filter(),
                 ActiveTab::Tools => app.clear_tools_filter(),
                 ActiveTab::Aliases => app.clear_aliases_filter(),
@@ -103,8 +194,22 @@ fn handle_normal_scripts(app: &mut App, key: KeyEvent) {
                 ActivePanel::Output => app.scroll_output_up(),
             }
         }
-        KeyCode::Char('g') => app.move_top(),
-        KeyCode::Char('G') => app.move_bottom(),
+        KeyCode::Char('g') => {
+            match app.active_panel {
+                ActivePanel::ScriptList => app.move_top(),
+                ActivePanel::Output => app.scroll_output_top(),
+            }
+        }
+        KeyCode::Char('G') => {
+            match app.active_panel {
+                ActivePanel::ScriptList => app.move_bottom(),
+                ActivePanel::Output => app.scroll_output_bottom(),
+            }
+        }
+        KeyCode::PageDown => app.scroll_output_page(true, false),
+        KeyCode::PageUp => app.scroll_output_page(false, false),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_output_page(true, true),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_output_page(false, true),
 
         // Panel switch
         KeyCode::Tab => app.toggle_panel(),
@@ -115,6 +220,85 @@ fn handle_normal_scripts(app: &mut App, key: KeyEvent) {
         }
         KeyCode::Enter => app.enter_run(),
         KeyCode::Char('s') => app.stop_selected(),
+        KeyCode::Char(']') => app.cycle_script_run(true),
+        KeyCode::Char('[') => app.cycle_script_run(false),
+        KeyCode::Char('!') => app.toggle_pin_running(),
+        KeyCode::Char('y') => app.copy_selected_command(),
+        KeyCode::Char('H') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.open_run_history();
+            }
+        }
+        KeyCode::Char('L') => app.restore_evicted_logs(),
+        KeyCode::Char('m') => {
+            if app.active_panel == ActivePanel::Output {
+                app.add_output_bookmark();
+            }
+        }
+        KeyCode::Char('\'') => app.open_bookmarks(),
+        KeyCode::Char('w') => {
+            match app.active_panel {
+                ActivePanel::ScriptList => app.open_script_dir(false),
+                ActivePanel::Output => app.toggle_wrap_output(),
+            }
+        }
+        KeyCode::Char('h') | KeyCode::Left => {
+            if app.active_panel == ActivePanel::Output {
+                app.scroll_output_sideways(false);
+            }
+        }
+        KeyCode::Char('l') | KeyCode::Right => {
+            if app.active_panel == ActivePanel::Output {
+                app.scroll_output_sideways(true);
+            }
+        }
+        KeyCode::Char('W') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.open_script_dir(true);
+            }
+        }
+        KeyCode::Char('O') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.open_last_artifacts();
+            }
+        }
+        KeyCode::Char('P') => app.toggle_schedules_paused(),
+        KeyCode::Char('b') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.open_slot_binding();
+            }
+        }
+        KeyCode::Char('p') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.detect_selected_script_params();
+            }
+        }
+        KeyCode::Char('S') => app.scan_scripts_folder(),
+
+        // Script management
+        KeyCode::Char('a') => app.open_new_script_editor(),
+        KeyCode::Char('e') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.open_edit_script_editor();
+            }
+        }
+        KeyCode::Char('d') => {
+            if app.active_panel == ActivePanel::ScriptList {
+                app.request_delete_selected_script();
+            }
+        }
+
+        // Navigation-only: jump to a folder's first script
+        KeyCode::Char('F') => app.open_folder_jump(),
+
+        // Organize rules over the listed scripts (preview first)
+        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.open_organize_preview();
+        }
+
+        // Export / import
+        KeyCode::Char('E') => app.open_path_prompt(PathPromptPurpose::Export),
+        KeyCode::Char('I') => app.open_path_prompt(PathPromptPurpose::Import),
 
         // Output controls
         KeyCode::Char('c') => {
@@ -127,6 +311,21 @@ fn handle_normal_scripts(app: &mut App, key: KeyEvent) {
                 app.toggle_auto_scroll();
             }
         }
+        KeyCode::Char('n') => {
+            if app.active_panel == ActivePanel::Output {
+                app.jump_to_output_match(true);
+            }
+        }
+        KeyCode::Char('N') => {
+            if app.active_panel == ActivePanel::Output {
+                app.jump_to_output_match(false);
+            }
+        }
+        KeyCode::Char('i') => {
+            if app.active_panel == ActivePanel::Output {
+                app.enter_stdin_input();
+            }
+        }
 
         _ => {}
     }
@@ -192,14 +391,17 @@ fn handle_normal_projects(app: &mut App, key: KeyEvent) {
 fn handle_normal_project_detail(app: &mut App, key: KeyEvent) {
     match key.code {
         // Quit
-        KeyCode::Char('q') => app.should_quit = true,
+        KeyCode::Char('q') => app.request_quit(),
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            app.should_quit = true;
+            app.request_quit();
         }
 
         // Help
         KeyCode::Char('?') => app.input_mode = InputMode::Help,
 
+        // Settings
+        KeyCode::Char(',') => app.open_settings(),
+
         // Switching tabs implicitly leaves the detail view.
         KeyCode::Char('1') => { app.exit_project_detail(); app.active_tab = ActiveTab::Projects; }
         KeyCode::Char('2') => { app.exit_project_detail(); app.active_tab = ActiveTab::Scripts; }
@@ -223,8 +425,22 @@ fn handle_normal_project_detail(app: &mut App, key: KeyEvent) {
                 ActivePanel::Output => app.scroll_output_up(),
             }
         }
-        KeyCode::Char('g') => app.services_move_top(),
-        KeyCode::Char('G') => app.services_move_bottom(),
+        KeyCode::Char('g') => {
+            match app.active_panel {
+                ActivePanel::ScriptList => app.services_move_top(),
+                ActivePanel::Output => app.scroll_output_top(),
+            }
+        }
+        KeyCode::Char('G') => {
+            match app.active_panel {
+                ActivePanel::ScriptList => app.services_move_bottom(),
+                ActivePanel::Output => app.scroll_output_bottom(),
+            }
+        }
+        KeyCode::PageDown => app.scroll_output_page(true, false),
+        KeyCode::PageUp => app.scroll_output_page(false, false),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_output_page(true, true),
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => app.scroll_output_page(false, true),
 
         // Panel switch (ServiceList <-> Output)
         KeyCode::Tab => app.toggle_panel(),
@@ -234,6 +450,8 @@ fn handle_normal_project_detail(app: &mut App, key: KeyEvent) {
         KeyCode::Char('s') => app.stop_selected_service(),
         KeyCode::Char('A') => app.start_all_services(),
         KeyCode::Char('S') => app.stop_all_services(),
+        KeyCode::Char('R') => app.reset_selected_service_circuit(),
+        KeyCode::Char('e') => app.open_service_editor(),
 
         // Project-level shortcuts
         KeyCode::Char('o') => app.open_project_folder(),
@@ -361,6 +579,19 @@ fn handle_param_form(app: &mut App, key: KeyEvent) {
         return;
     }
 
+    // Parameter help popup
+    if form.showing_help {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+                form.showing_help = false;
+                form.help_notice = None;
+            }
+            KeyCode::Char('r') => app.refresh_param_form_help(),
+            _ => {}
+        }
+        return;
+    }
+
     if form.editing {
         // Text editing mode for the focused field
         match key.code {
@@ -461,6 +692,14 @@ fn handle_param_form(app: &mut App, key: KeyEvent) {
                 }
             }
         }
+        // Help for the focused parameter
+        KeyCode::Char('?') => {
+            if let Some(f) = app.param_form.as_mut() {
+                if !f.is_extra_args_focused() {
+                    f.showing_help = true;
+                }
+            }
+        }
         // Navigate fields
         KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => {
             if let Some(f) = app.param_form.as_mut() {
@@ -514,3 +753,319 @@ fn handle_param_form(app: &mut App, key: KeyEvent) {
         _ => {}
     }
 }
+
+fn handle_confirm_delete(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_delete_script(),
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+            app.cancel_delete_script();
+        }
+        _ => {}
+    }
+}
+
+fn handle_confirm_requirements(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.confirm_requirements_run(),
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+            app.cancel_requirements_run();
+        }
+        _ => {}
+    }
+}
+
+fn handle_output_search(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.clear_output_search(),
+        KeyCode::Enter => app.confirm_output_search(),
+        KeyCode::Backspace => app.output_search_backspace(),
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_output_search_regex();
+        }
+        KeyCode::Char(c) => app.output_search_input(c),
+        _ => {}
+    }
+}
+
+fn handle_stdin_input(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.leave_stdin_input(),
+        KeyCode::Enter => app.send_stdin_line(),
+        KeyCode::Backspace => app.stdin_input_backspace(),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => app.close_stdin_input(),
+        KeyCode::Char(c) => app.stdin_input_char(c),
+        _ => {}
+    }
+}
+
+fn handle_organize_preview(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_organize(),
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') => app.cancel_organize(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            if let Some(preview) = app.organize_preview.as_mut() {
+                if preview.scroll + 1 < preview.plan.changes.len() {
+                    preview.scroll += 1;
+                }
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            if let Some(preview) = app.organize_preview.as_mut() {
+                preview.scroll = preview.scroll.saturating_sub(1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_scan_results(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Enter => app.confirm_scan_import(),
+        KeyCode::Esc | KeyCode::Char('q') => app.cancel_scan(),
+        KeyCode::Char(' ') => app.toggle_scan_pick(),
+        KeyCode::Char('a') => app.toggle_scan_pick_all(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            if let Some(results) = app.scan_results.as_mut() {
+                if results.selected + 1 < results.scripts.len() {
+                    results.selected += 1;
+                }
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            if let Some(results) = app.scan_results.as_mut() {
+                results.selected = results.selected.saturating_sub(1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_run_history(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_run_history(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            if let Some(history) = app.run_history.as_mut() {
+                if history.selected + 1 < history.records.len() {
+                    history.selected += 1;
+                    history.artifact = 0;
+                }
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            if let Some(history) = app.run_history.as_mut() {
+                history.selected = history.selected.saturating_sub(1);
+                history.artifact = 0;
+            }
+        }
+        KeyCode::Char('m') | KeyCode::Char(' ') => app.toggle_run_mark(),
+        KeyCode::Char('=') | KeyCode::Enter => app.compare_marked_run(),
+        KeyCode::Tab => app.next_run_artifact(),
+        KeyCode::Char('o') => app.open_run_artifact(false),
+        KeyCode::Char('O') => app.open_run_artifact(true),
+        _ => {}
+    }
+}
+
+fn handle_run_compare(app: &mut App, key: KeyEvent) {
+    let Some(state) = app.run_compare.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+    let last = state.diff_len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_run_compare(),
+        KeyCode::Char('j') | KeyCode::Down => state.scroll = (state.scroll + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+        KeyCode::PageDown => state.scroll = (state.scroll + 20).min(last),
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(20),
+        KeyCode::Char('g') => state.scroll = 0,
+        KeyCode::Char('G') => state.scroll = last,
+        _ => {}
+    }
+}
+
+fn handle_bookmarks(app: &mut App, key: KeyEvent) {
+    let count = app.active_bookmarks().len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('\'') => app.close_bookmarks(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            if app.bookmarks_selected + 1 < count {
+                app.bookmarks_selected += 1;
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => app.bookmarks_selected = app.bookmarks_selected.saturating_sub(1),
+        KeyCode::Enter => app.jump_to_bookmark(),
+        KeyCode::Char('d') => app.delete_bookmark(),
+        _ => {}
+    }
+}
+
+fn handle_bind_slot(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.cancel_slot_binding(),
+        KeyCode::Char('x') | KeyCode::Backspace | KeyCode::Delete => app.unbind_quick_slot(),
+        KeyCode::Char(c @ '1'..='9') => app.bind_quick_slot(c as u8 - b'0'),
+        _ => {
+            if let Some(slot) = quick_slot_key(key) {
+                app.bind_quick_slot(slot);
+            }
+        }
+    }
+}
+
+fn handle_settings(app: &mut App, key: KeyEvent) {
+    let Some(form) = app.settings_form.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+
+    // Typing a number
+    if let Some(text) = form.editing.as_mut() {
+        match key.code {
+            KeyCode::Esc => {
+                form.editing = None;
+                form.error = None;
+            }
+            KeyCode::Enter => app.activate_setting(),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => text.push(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char(',') => app.close_settings(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            if form.selected + 1 < SettingsField::ALL.len() {
+                form.selected += 1;
+            }
+            form.error = None;
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            form.selected = form.selected.saturating_sub(1);
+            form.error = None;
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => app.activate_setting(),
+        _ => {}
+    }
+}
+
+fn handle_folder_jump(app: &mut App, key: KeyEvent) {
+    let Some(state) = app.folder_jump.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+
+    match key.code {
+        KeyCode::Esc => app.close_folder_jump(),
+        KeyCode::Enter => app.confirm_folder_jump(),
+        KeyCode::Down => {
+            if state.selected + 1 < state.matches.len() {
+                state.selected += 1;
+            }
+        }
+        KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Backspace => {
+            state.query.pop();
+            state.update_matches();
+        }
+        KeyCode::Char(c) => {
+            state.query.push(c);
+            state.update_matches();
+        }
+        _ => {}
+    }
+}
+
+fn handle_path_prompt(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.path_prompt.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+
+    match key.code {
+        KeyCode::Esc => app.close_path_prompt(),
+        KeyCode::Enter => app.submit_path_prompt(),
+        KeyCode::Tab => app.path_prompt_complete(),
+        KeyCode::Backspace => {
+            prompt.input.pop();
+            prompt.preview = None;
+            prompt.error = None;
+        }
+        KeyCode::Char(c) => {
+            prompt.input.push(c);
+            prompt.preview = None;
+            prompt.error = None;
+        }
+        _ => {}
+    }
+}
+
+/// Enter saves; Shift+Enter (or Alt+Enter, for terminals that don't report
+/// Shift on Enter) saves and restarts the service if it's running
+fn handle_service_editor(app: &mut App, key: KeyEvent) {
+    let Some(editor) = app.service_editor.as_mut() else {
+        app.input_mode = InputMode::Normal;
+        return;
+    };
+
+    match key.code {
+        KeyCode::Esc => app.close_service_editor(),
+        KeyCode::Enter => {
+            let restart = key.modifiers.intersects(KeyModifiers::SHIFT | KeyModifiers::ALT);
+            app.save_service_editor(restart);
+        }
+        KeyCode::Backspace => {
+            editor.command.pop();
+            editor.error = None;
+        }
+        KeyCode::Char(c) => {
+            editor.command.push(c);
+            editor.error = None;
+        }
+        _ => {}
+    }
+}
+
+fn handle_script_editor(app: &mut App, key: KeyEvent) {
+    let form = match app.script_editor.as_mut() {
+        Some(f) => f,
+        None => {
+            app.input_mode = InputMode::Normal;
+            return;
+        }
+    };
+
+    if form.editing {
+        // Text editing mode for the focused field
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                form.editing = false;
+            }
+            KeyCode::Left => form.cursor_left(),
+            KeyCode::Right => form.cursor_right(),
+            KeyCode::Home => form.cursor_pos = 0,
+            KeyCode::End => form.cursor_end(),
+            KeyCode::Backspace => form.backspace(),
+            KeyCode::Delete => form.delete(),
+            KeyCode::Char(c) => form.insert_char(c),
+            _ => {}
+        }
+        return;
+    }
+
+    // Navigation mode
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => app.cancel_script_editor(),
+        // Save
+        KeyCode::Char('s') => app.save_script_editor(),
+        KeyCode::Char('j') | KeyCode::Down | KeyCode::Tab => form.move_down(),
+        KeyCode::Char('k') | KeyCode::Up | KeyCode::BackTab => form.move_up(),
+        // Enter edit mode
+        KeyCode::Enter | KeyCode::Char(' ') => form.start_editing(),
+        _ => {}
+    }
+}
@@ -1,70 +1,63 @@
 mod app;
+mod bookmarks;
 mod cli_error;
+mod color;
 mod event;
+mod frame_budget;
 mod input;
 mod os_open;
+mod retention;
 mod tui_emitter;
 mod ui;
 mod util;
 
+use std::collections::VecDeque;
 use std::io;
 use std::process::ExitCode;
-use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc};
+use std::time::Instant;
 
 use cli_error::{report_error, CortxError};
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
 
+use cortx_core::command_builder::{shell_quote, QuoteStyle};
 use cortx_core::file_watcher;
 use cortx_core::models::{
-    App as CoreApp, GlobalScript, ImportOptions, Project,
-    Script, Service, ShellAlias, StatusDefinition, TagDefinition, Tool,
+    App as CoreApp, EnvMode, GlobalScript, ImportOptions, MigrationItemStatus, PathMapping,
+    Project, RootOverlap, RootRelation, Script, Service, ShellAlias, StatusDefinition, TagDefinition, Tool,
 };
 use cortx_core::process_manager::{ProcessManager, RuntimeMeta};
 use cortx_core::runtime_state::{
-    self, EntityKind, RuntimeEntry, RuntimeStore,
+    self, new_run_id, EntityKind, RuntimeEntry, RuntimeStore,
 };
+use cortx_core::script_location;
 use cortx_core::storage::Storage;
 
 use app::{App, ProcessEvent};
+use frame_budget::{FrameBudget, UserInput, Work};
 use tui_emitter::TuiEmitter;
 
 // ============================================================================
 // Color control (#17)
 // ============================================================================
 
-/// Whether the current run should emit ANSI color escapes. Set once at
-/// startup by `init_color()` from the CLI flag + env vars + isatty check,
-/// then read by every truecolor formatter.
-static SHOULD_COLORIZE: AtomicBool = AtomicBool::new(false);
-
-/// Resolve color preference using the standard precedence:
-/// 1. `--no-color` flag → off
-/// 2. `CLICOLOR_FORCE` env var (any non-empty value) → on (escape hatch)
-/// 3. `NO_COLOR` env var (any non-empty value, https://no-color.org) → off
-/// 4. stdout isatty → on; otherwise off
+/// Pick the colors CLI output may use from the `--no-color` flag, env vars,
+/// whether stdout is a terminal and what it supports (see `color::cli_level`).
 fn init_color(no_color_flag: bool) {
     use std::io::IsTerminal;
-    let enabled = if no_color_flag {
-        false
-    } else if std::env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty()) {
-        true
-    } else if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
-        false
-    } else {
-        std::io::stdout().is_terminal()
-    };
-    SHOULD_COLORIZE.store(enabled, Ordering::SeqCst);
+    let level = color::cli_level(no_color_flag, |name| std::env::var(name).ok(), std::io::stdout().is_terminal());
+    color::init(level);
 }
 
 fn should_colorize() -> bool {
-    SHOULD_COLORIZE.load(Ordering::SeqCst)
+    color::level() != color::ColorLevel::None
 }
 
 // ============================================================================
@@ -84,6 +77,10 @@ struct Cli {
     #[arg(long, global = true)]
     json: bool,
 
+    /// Output format; `--format json` is the same as `--json`
+    #[arg(long, global = true, value_enum, conflicts_with = "json")]
+    format: Option<OutputFormat>,
+
     /// Disable ANSI color output regardless of terminal type.
     /// Color is also auto-disabled when stdout is not a TTY or when the
     /// NO_COLOR environment variable is set. Use CLICOLOR_FORCE=1 to
@@ -95,6 +92,12 @@ struct Cli {
     command: Option<Command>,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Run a script by name
@@ -107,6 +110,13 @@ enum Command {
         /// Use a specific parameter preset
         #[arg(short, long)]
         preset: Option<String>,
+        /// Set a declared parameter (repeatable), e.g. `--param env=staging`.
+        /// Overrides the preset's value.
+        #[arg(long = "param", value_name = "KEY=VALUE")]
+        params: Vec<String>,
+        /// Turn on a bool parameter (repeatable), e.g. `--flag dry_run`
+        #[arg(long = "flag", value_name = "NAME")]
+        flags: Vec<String>,
         /// Run detached in the background and return immediately with the PID.
         /// Logs are written to <app_dir>/runtime/<script_id>.log and can be
         /// followed with `cortx logs <name>` or stopped with `cortx stop <name>`.
@@ -223,12 +233,24 @@ enum Command {
         all: bool,
     },
 
+    /// Merge another machine's CortX data directory into this install
+    Migrate {
+        /// Path to the old data directory (the folder containing projects.json or projects/)
+        path: String,
+        /// Apply without prompting; projects with a missing root path keep it
+        #[arg(long)]
+        yes: bool,
+    },
+
     /// Backup data to configured git repo
     Backup,
 
     /// List CLI-managed running processes (services, project scripts, global scripts).
     Ps,
 
+    /// Check the data dir, data files, shell, terminal and VS Code setup
+    Doctor,
+
     /// Run the MCP server on stdio. Alias for the `cortx-mcp` binary so
     /// MCP client configs can use a single command name regardless of
     /// install path.
@@ -259,6 +281,17 @@ enum ScriptAction {
         /// Filter by name (case-insensitive substring)
         #[arg(long)]
         name: Option<String>,
+        /// Keep scripts whose name, command, tags or description contain
+        /// PATTERN (case-insensitive), highlighting the match. With --json
+        /// it only filters.
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+        /// Show at most N scripts, after sorting and filtering
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Skip the first N scripts (with --limit, to page through the list)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        offset: usize,
     },
     /// Show details for a script
     Get {
@@ -377,6 +410,9 @@ enum ProjectAction {
         /// Toolbox documentation URL
         #[arg(long)]
         toolbox_url: Option<String>,
+        /// Create it even if another project has the same root
+        #[arg(long)]
+        allow_duplicate: bool,
     },
     /// Update an existing project
     Update {
@@ -400,6 +436,9 @@ enum ProjectAction {
         /// Toolbox documentation URL
         #[arg(long)]
         toolbox_url: Option<String>,
+        /// Accept a new path another project already has
+        #[arg(long)]
+        allow_duplicate: bool,
     },
     /// Delete a project
     Delete {
@@ -1021,7 +1060,7 @@ fn main() -> ExitCode {
     env_logger::init();
 
     let cli = Cli::parse();
-    let json = cli.json;
+    let json = cli.json || cli.format == Some(OutputFormat::Json);
     init_color(cli.no_color);
 
     match run(cli, json) {
@@ -1031,21 +1070,31 @@ fn main() -> ExitCode {
 }
 
 fn run(cli: Cli, json: bool) -> anyhow::Result<()> {
+    // Before opening storage, which fails on the very problems doctor reports
+    if let Some(Command::Doctor) = cli.command {
+        return cmd_doctor(json);
+    }
+
     let storage = Arc::new(Storage::new()?);
     let runtime_store = Arc::new(RuntimeStore::new(storage.app_dir())?);
     let process_manager = Arc::new(ProcessManager::new(runtime_store.clone()));
+    let settings = storage.get_settings();
+    process_manager.set_shell(settings.shell);
+    process_manager.set_logging(settings.logging);
+    process_manager.set_max_run_depth(settings.scripts_config.max_run_depth);
+    process_manager.set_echo_command(settings.scripts_config.echo_command);
 
     match cli.command {
         // Legacy shortcuts
-        Some(Command::Scripts) => cmd_script_list(&storage, None, None, json),
+        Some(Command::Scripts) => cmd_script_list(&storage, None, None, None, 0, None, json),
         Some(Command::Tools { scan }) => cmd_tool_list(&storage, None, None, scan, json),
 
         Some(Command::Init { shell }) => cmd_init(&storage, &shell),
 
         // Script group
         Some(Command::Script { action }) => match action {
-            ScriptAction::List { tag, name } => {
-                cmd_script_list(&storage, tag.as_deref(), name.as_deref(), json)
+            ScriptAction::List { tag, name, grep, limit, offset } => {
+                cmd_script_list(&storage, tag.as_deref(), name.as_deref(), grep.as_deref(), offset, limit, json)
             }
             ScriptAction::Get { name_or_id } => cmd_script_get(&storage, &name_or_id, json),
             ScriptAction::Create { name, command, dir, script_path, color, tag, description, status } => {
@@ -1068,11 +1117,11 @@ fn run(cli: Cli, json: bool) -> anyhow::Result<()> {
                 json,
             ),
             ProjectAction::Get { name_or_id } => cmd_project_get(&storage, &name_or_id, json),
-            ProjectAction::Create { name, path, description, tag, status, toolbox_url } => {
-                cmd_project_create(&storage, &name, &path, description.as_deref(), tag, status.as_deref(), toolbox_url.as_deref(), json)
+            ProjectAction::Create { name, path, description, tag, status, toolbox_url, allow_duplicate } => {
+                cmd_project_create(&storage, &name, &path, description.as_deref(), tag, status.as_deref(), toolbox_url.as_deref(), allow_duplicate, json)
             }
-            ProjectAction::Update { name_or_id, name, path, description, tag, status, toolbox_url } => {
-                cmd_project_update(&storage, &name_or_id, name, path, description, tag, status, toolbox_url, json)
+            ProjectAction::Update { name_or_id, name, path, description, tag, status, toolbox_url, allow_duplicate } => {
+                cmd_project_update(&storage, &name_or_id, name, path, description, tag, status, toolbox_url, allow_duplicate, json)
             }
             ProjectAction::Delete { name_or_id, yes } => cmd_project_delete(&storage, &name_or_id, yes),
             ProjectAction::Run { project, script, args } => {
@@ -1188,19 +1237,22 @@ fn run(cli: Cli, json: bool) -> anyhow::Result<()> {
         // Data commands
         Some(Command::Export { file }) => cmd_export(&storage, file.as_deref()),
         Some(Command::Import { file, all }) => cmd_import(&storage, &file, all),
+        Some(Command::Migrate { path, yes }) => cmd_migrate(&storage, &path, yes),
         Some(Command::Backup) => cmd_backup(&storage),
         Some(Command::Docs) => cmd_docs(),
         Some(Command::Ps) => cmd_ps(&storage, json),
+        Some(Command::Doctor) => cmd_doctor(json),
         Some(Command::Mcp { action }) => match action {
             McpAction::Serve => cmd_mcp_serve(),
         },
 
         // Run shortcuts
-        Some(Command::Run { script, args, preset, detach }) => {
+        Some(Command::Run { script, args, preset, params, flags, detach }) => {
+            let params = ParamArgs { preset: preset.as_deref(), params: &params, flags: &flags };
             if detach {
-                cmd_run_detached(&storage, &script, preset.as_deref(), &args, json)
+                cmd_run_detached(&storage, &script, &params, &args, json)
             } else {
-                cmd_run(&storage, &process_manager, &script, preset.as_deref(), &args)
+                cmd_run(&storage, &process_manager, &script, &params, &args)
             }
         }
         Some(Command::Stop { script }) => cmd_global_script_stop(&storage, &script, json),
@@ -1208,7 +1260,9 @@ fn run(cli: Cli, json: bool) -> anyhow::Result<()> {
             cmd_global_script_logs(&storage, &script, tail, follow)
         }
         Some(Command::External(args)) => {
-            cmd_run(&storage, &process_manager, &args[0], None, &args[1..].to_vec())
+            let (params, flags, rest) = cortx_core::command_builder::extract_param_args(&args[1..]);
+            let params = ParamArgs { preset: None, params: &params, flags: &flags };
+            cmd_run(&storage, &process_manager, &args[0], &params, &rest)
         }
 
         // No subcommand: launch the TUI when stdout is a terminal,
@@ -1240,9 +1294,13 @@ fn cmd_script_list(
     storage: &Storage,
     tag_filter: Option<&str>,
     name_filter: Option<&str>,
+    grep: Option<&str>,
+    offset: usize,
+    limit: Option<usize>,
     json: bool,
 ) -> anyhow::Result<()> {
     let scripts = storage.get_all_global_scripts();
+    let grep = grep.filter(|p| !p.is_empty());
 
     let tag_lower = tag_filter.map(|t| t.to_lowercase());
     let filtered: Vec<&GlobalScript> = scripts
@@ -1252,51 +1310,31 @@ fn cmd_script_list(
             None => true,
         })
         .filter(|s| matches_substr(&s.name, name_filter))
+        .filter(|s| grep.map_or(true, |p| util::script_matches_grep(s, p)))
         .collect();
 
+    // Sorted by primary tag order, then alphabetically by name; paged after
+    let tag_defs = storage.get_all_tag_definitions();
+    let mut sorted = filtered;
+    util::sort_script_list(&mut sorted, &tag_defs);
+    let paged = offset > 0 || limit.is_some();
+    let (sorted, total) = util::paginate(sorted, offset, limit);
+
     if json {
-        let items: Vec<&GlobalScript> = filtered;
+        let items: Vec<&GlobalScript> = sorted;
         println!("{}", serde_json::to_string_pretty(&items)?);
+        if paged {
+            // Kept off stdout so the JSON stays parseable
+            eprintln!("showing {} of {}", items.len(), total);
+        }
         return Ok(());
     }
 
-    if filtered.is_empty() {
+    if total == 0 {
         println!("No global scripts configured.");
         return Ok(());
     }
 
-    let tag_defs = storage.get_all_tag_definitions();
-
-    // Sort scripts by primary tag order, then alphabetically by name
-    let mut sorted = filtered;
-    sorted.sort_by(|a, b| {
-        let ta = a.tags.first().and_then(|t| {
-            let tl = t.to_lowercase();
-            tag_defs.iter().find(|d| d.name.to_lowercase() == tl)
-        });
-        let tb = b.tags.first().and_then(|t| {
-            let tl = t.to_lowercase();
-            tag_defs.iter().find(|d| d.name.to_lowercase() == tl)
-        });
-        let tag_ord = match (a.tags.first(), b.tags.first()) {
-            (None, None) => std::cmp::Ordering::Equal,
-            (None, Some(_)) => std::cmp::Ordering::Less,
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            (Some(at), Some(bt)) => {
-                let ao = ta.and_then(|d| d.order);
-                let bo = tb.and_then(|d| d.order);
-                match (ao, bo) {
-                    (Some(ao), Some(bo)) => ao.cmp(&bo),
-                    (Some(_), None) => std::cmp::Ordering::Less,
-                    (None, Some(_)) => std::cmp::Ordering::Greater,
-                    (None, None) => std::cmp::Ordering::Equal,
-                }
-                .then_with(|| at.to_lowercase().cmp(&bt.to_lowercase()))
-            }
-        };
-        tag_ord.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-    });
-
     println!("{:<40} {:<15} {}", "NAME", "TAGS", "COMMAND");
     println!("{}", "-".repeat(75));
 
@@ -1313,9 +1351,26 @@ fn cmd_script_list(
         };
         let display_cmd = util::format_command_display(&s.command, s.script_path.as_deref());
         let tags_pad = if tags_visible < 15 { 15 - tags_visible } else { 1 };
-        println!("{:<40} {}{:tags_pad$} {}", s.name, tags_display, "", display_cmd, tags_pad = tags_pad);
+        let (name_display, display_cmd) = match grep {
+            Some(p) => (
+                util::highlight_matches(&s.name, p, should_colorize()),
+                util::highlight_matches(&display_cmd, p, should_colorize()),
+            ),
+            None => (s.name.clone(), display_cmd),
+        };
+        // Padded by hand: highlight escapes don't take up columns
+        let name_pad = 40usize.saturating_sub(s.name.chars().count());
+        println!(
+            "{}{:name_pad$} {}{:tags_pad$} {}",
+            name_display, "", tags_display, "", display_cmd,
+            name_pad = name_pad, tags_pad = tags_pad
+        );
+    }
+    if paged {
+        println!("\nshowing {} of {} script(s)", sorted.len(), total);
+    } else {
+        println!("\n{} script(s)", sorted.len());
     }
-    println!("\n{} script(s)", sorted.len());
     Ok(())
 }
 
@@ -1376,6 +1431,7 @@ fn cmd_script_create(
     script.status = status.map(|s| s.to_string());
 
     let created = storage.create_global_script(script).map_err(|e| anyhow::anyhow!("{}", e))?;
+    warn_script_issues(&created);
 
     if json {
         println!("{}", serde_json::to_string_pretty(&created)?);
@@ -1412,6 +1468,7 @@ fn cmd_script_update(
         if let Some(ref d) = description { s.description = Some(d.clone()); }
         if let Some(ref st) = status { s.status = Some(st.clone()); }
     }).map_err(|e| anyhow::anyhow!("{}", e))?;
+    warn_script_issues(&updated);
 
     if json {
         println!("{}", serde_json::to_string_pretty(&updated)?);
@@ -1421,6 +1478,18 @@ fn cmd_script_update(
     Ok(())
 }
 
+/// Print pre-save validation warnings to stderr (stdout may be JSON).
+fn warn_script_issues(script: &GlobalScript) {
+    let issues = cortx_core::script_validation::validate_script_fields(
+        &script.command,
+        script.script_path.as_deref(),
+        script.working_dir.as_deref(),
+    );
+    for issue in issues {
+        eprintln!("Warning: {}", issue.message);
+    }
+}
+
 fn cmd_script_delete(storage: &Storage, name_or_id: &str, yes: bool) -> anyhow::Result<()> {
     let scripts = storage.get_all_global_scripts();
     let script = resolve_by_name_or_id(&scripts, name_or_id, "global_script", |s| &s.id, |s| &s.name)?;
@@ -1641,6 +1710,7 @@ fn cmd_project_create(
     tags: Option<Vec<String>>,
     status: Option<&str>,
     toolbox_url: Option<&str>,
+    allow_duplicate: bool,
     json: bool,
 ) -> anyhow::Result<()> {
     let mut project = Project::new(name.to_string(), path.to_string());
@@ -1651,16 +1721,31 @@ fn cmd_project_create(
     project.status = status.map(|s| s.to_string());
     project.toolbox_url = toolbox_url.map(|s| s.to_string());
 
-    let created = storage.create_project(project).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let saved = storage
+        .create_project_checked(project, allow_duplicate)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    warn_root_overlaps(&saved.overlaps);
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&created)?);
+        println!("{}", serde_json::to_string_pretty(&saved)?);
     } else {
-        println!("Project '{}' created (ID: {}).", created.name, created.id);
+        println!("Project '{}' created (ID: {}).", saved.project.name, saved.project.id);
     }
     Ok(())
 }
 
+/// Other projects on the same folder, or one inside the other
+fn warn_root_overlaps(overlaps: &[RootOverlap]) {
+    for overlap in overlaps {
+        let relation = match overlap.relation {
+            RootRelation::Same => "has the same root",
+            RootRelation::Inside => "contains this folder",
+            RootRelation::Contains => "is inside this folder",
+        };
+        eprintln!("Warning: project '{}' {} ({})", overlap.project_name, relation, overlap.root_path);
+    }
+}
+
 fn cmd_project_update(
     storage: &Storage,
     name_or_id: &str,
@@ -1670,13 +1755,14 @@ fn cmd_project_update(
     tags: Option<Vec<String>>,
     status: Option<String>,
     toolbox_url: Option<String>,
+    allow_duplicate: bool,
     json: bool,
 ) -> anyhow::Result<()> {
     let projects = storage.get_all_projects();
     let existing = resolve_by_name_or_id(&projects, name_or_id, "project", |p| &p.id, |p| &p.name)?;
     let id = existing.id.clone();
 
-    let updated = storage.update_project(&id, |p| {
+    let saved = storage.update_project_checked(&id, allow_duplicate, |p| {
         if let Some(ref n) = name { p.name = n.clone(); }
         if let Some(ref pa) = path { p.root_path = pa.clone(); }
         if let Some(ref d) = description { p.description = Some(d.clone()); }
@@ -1684,11 +1770,12 @@ fn cmd_project_update(
         if let Some(ref s) = status { p.status = Some(s.clone()); }
         if let Some(ref u) = toolbox_url { p.toolbox_url = Some(u.clone()); }
     }).map_err(|e| anyhow::anyhow!("{}", e))?;
+    warn_root_overlaps(&saved.overlaps);
 
     if json {
-        println!("{}", serde_json::to_string_pretty(&updated)?);
+        println!("{}", serde_json::to_string_pretty(&saved)?);
     } else {
-        println!("Project '{}' updated.", updated.name);
+        println!("Project '{}' updated.", saved.project.name);
     }
     Ok(())
 }
@@ -1893,23 +1980,6 @@ fn build_service_command_string(
     (command, active_mode, active_preset)
 }
 
-fn resolve_service_working_dir(project: &Project, service: &Service) -> String {
-    let dir = service.working_dir.trim();
-    if dir.is_empty() || dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = std::path::Path::new(dir);
-        if path.is_absolute() {
-            dir.to_string()
-        } else {
-            std::path::Path::new(&project.root_path)
-                .join(dir)
-                .to_string_lossy()
-                .to_string()
-        }
-    }
-}
-
 fn cmd_service_start(
     storage: &Storage,
     project_ref: &str,
@@ -1931,15 +2001,22 @@ fn cmd_service_start(
 
     let (command, active_mode, active_preset) =
         build_service_command_string(&service, mode, arg_preset);
-    let working_dir = resolve_service_working_dir(&project, &service);
-    let (program, args) = runtime_state::shell_wrap(&command);
+    let env_vars = cortx_core::env_file::service_env_vars(&project, &service);
+    let command = cortx_core::command_builder::expand_command(
+        &command,
+        &service.env_mode,
+        env_vars.as_ref(),
+    )?;
+    let working_dir = project.resolve_dir(&service.working_dir);
+    let (program, args) = runtime_state::shell_wrap_with(&storage.get_settings().shell, &command);
     let log_path = store.log_path(&service.id);
 
     let pid = runtime_state::spawn_detached(
         &program,
         &args,
         &working_dir,
-        service.env_vars.as_ref(),
+        env_vars.as_ref(),
+        &service.env_mode,
         &log_path,
     )
     .map_err(|e| anyhow::anyhow!("Failed to spawn service: {}", e))?;
@@ -1956,6 +2033,8 @@ fn cmd_service_start(
         project_name: Some(project.name.clone()),
         mode: active_mode,
         arg_preset: active_preset,
+        run_id: new_run_id(),
+        variant_id: project.active_variant_id.clone(),
     };
     store.register(&entry)?;
 
@@ -2149,23 +2228,6 @@ fn resolve_project_script(
     Ok((project, script))
 }
 
-fn resolve_script_working_dir(project: &Project, script: &Script) -> String {
-    let dir = script.working_dir.trim();
-    if dir.is_empty() || dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = std::path::Path::new(dir);
-        if path.is_absolute() {
-            dir.to_string()
-        } else {
-            std::path::Path::new(&project.root_path)
-                .join(dir)
-                .to_string_lossy()
-                .to_string()
-        }
-    }
-}
-
 fn cmd_project_run(
     storage: &Storage,
     project_ref: &str,
@@ -2185,17 +2247,21 @@ fn cmd_project_run(
         store.unregister(&script.id)?;
     }
 
-    let mut command = if let Some(ref path) = script.script_path {
+    let command = if let Some(ref path) = script.script_path {
         script.command.replace("{{SCRIPT_FILE}}", path)
     } else {
         script.command.clone()
     };
-    if !extra_args.is_empty() {
-        command = format!("{} {}", command, extra_args.join(" "));
-    }
-
-    let working_dir = resolve_script_working_dir(&project, &script);
-    let (program, args) = runtime_state::shell_wrap(&command);
+    let mut command = cortx_core::command_builder::expand_command(&command, &EnvMode::Inherit, None)?;
+    let shell = storage.get_settings().shell;
+    // The command goes through a shell: quote extra args so they arrive as given
+    let style = QuoteStyle::for_shell(&shell);
+    for arg in extra_args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg, style));
+    }
+    let working_dir = project.resolve_dir(&script.working_dir);
+    let (program, args) = runtime_state::shell_wrap_with(&shell, &command);
     let log_path = store.log_path(&script.id);
 
     let pid = runtime_state::spawn_detached(
@@ -2203,6 +2269,7 @@ fn cmd_project_run(
         &args,
         &working_dir,
         None,
+        &EnvMode::Inherit,
         &log_path,
     )
     .map_err(|e| anyhow::anyhow!("Failed to spawn project script: {}", e))?;
@@ -2219,6 +2286,8 @@ fn cmd_project_run(
         project_name: Some(project.name.clone()),
         mode: None,
         arg_preset: None,
+        run_id: new_run_id(),
+        variant_id: project.active_variant_id.clone(),
     };
     store.register(&entry)?;
 
@@ -3343,23 +3412,9 @@ fn cmd_status_list(storage: &Storage, json: bool) -> anyhow::Result<()> {
         let color = d.color.as_deref().unwrap_or("-");
         let order = d.order.map(|o| o.to_string()).unwrap_or_else(|| "-".to_string());
         // Colorize status name with its own color (only when color is enabled)
-        let name_display = if let (true, Some(hex)) = (should_colorize(), d.color.as_deref()) {
-            let hex = hex.trim_start_matches('#');
-            if hex.len() == 6 {
-                if let (Ok(r), Ok(g), Ok(b)) = (
-                    u8::from_str_radix(&hex[0..2], 16),
-                    u8::from_str_radix(&hex[2..4], 16),
-                    u8::from_str_radix(&hex[4..6], 16),
-                ) {
-                    format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, d.name)
-                } else {
-                    d.name.clone()
-                }
-            } else {
-                d.name.clone()
-            }
-        } else {
-            d.name.clone()
+        let name_display = match d.color.as_deref() {
+            Some(hex) => color::paint(hex, &d.name),
+            None => d.name.clone(),
         };
         let name_visible = d.name.len();
         let name_pad = if name_visible < 25 { 25 - name_visible } else { 1 };
@@ -3474,9 +3529,32 @@ fn cmd_settings_set(storage: &Storage, key: &str, value: &str) -> anyhow::Result
             };
             settings.appearance.theme = theme;
         }
+        "shell" | "shell.kind" => {
+            let kind = match value.to_lowercase().as_str() {
+                "default" => cortx_core::models::ShellKind::Default,
+                "sh" => cortx_core::models::ShellKind::Sh,
+                "bash" => cortx_core::models::ShellKind::Bash,
+                "zsh" => cortx_core::models::ShellKind::Zsh,
+                "cmd" => cortx_core::models::ShellKind::Cmd,
+                "powershell" => cortx_core::models::ShellKind::Powershell,
+                "custom" => cortx_core::models::ShellKind::Custom,
+                _ => return Err(anyhow::anyhow!("Unknown shell '{}'. Options: default, sh, bash, zsh, cmd, powershell, custom", value)),
+            };
+            settings.shell.kind = kind;
+        }
+        "shell.customPath" | "shell.custom_path" => {
+            settings.shell.custom_path = if value.is_empty() { None } else { Some(value.to_string()) };
+        }
+        "pinRunningScripts" | "pin_running_scripts" => {
+            settings.pin_running_scripts = match value {
+                "true" | "on" | "1" => true,
+                "false" | "off" | "0" => false,
+                _ => return Err(anyhow::anyhow!("Invalid value '{}'. Options: true, false", value)),
+            };
+        }
         _ => {
             return Err(anyhow::anyhow!(
-                "Unknown setting key '{}'. Valid keys: terminal.preset, toolboxBaseUrl, backupRepoPath, defaults.launchMethod, appearance.theme",
+                "Unknown setting key '{}'. Valid keys: terminal.preset, toolboxBaseUrl, backupRepoPath, defaults.launchMethod, appearance.theme, shell, shell.customPath, pinRunningScripts",
                 key
             ));
         }
@@ -3507,8 +3585,7 @@ fn cmd_export(storage: &Storage, file: Option<&str>) -> anyhow::Result<()> {
 }
 
 fn cmd_import(storage: &Storage, file: &str, all: bool) -> anyhow::Result<()> {
-    let content = std::fs::read_to_string(file)
-        .map_err(|e| anyhow::anyhow!("Failed to read '{}': {}", file, e))?;
+    let content = cortx_core::text_file::read_to_string(std::path::Path::new(file))?;
 
     // Preview
     let summary = Storage::preview_import(&content).map_err(|e| anyhow::anyhow!("{}", e))?;
@@ -3550,12 +3627,139 @@ fn cmd_import(storage: &Storage, file: &str, all: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn cmd_migrate(storage: &Storage, path: &str, yes: bool) -> anyhow::Result<()> {
+    let dir = std::path::Path::new(path);
+    let options = ImportOptions::default();
+    let report = storage
+        .import_from_data_dir(dir, &options)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    println!("Migration preview from '{}':", report.source_dir);
+    let kinds = [
+        ("project", "Projects"),
+        ("script", "Scripts"),
+        ("tool", "Tools"),
+        ("app", "Apps"),
+        ("alias", "Aliases"),
+        ("tag", "Tags"),
+        ("status", "Statuses"),
+    ];
+    for (kind, label) in kinds {
+        let of_kind: Vec<_> = report.items.iter().filter(|i| i.kind == kind).collect();
+        if of_kind.is_empty() {
+            continue;
+        }
+        let count = |status: MigrationItemStatus| of_kind.iter().filter(|i| i.status == status).count();
+        println!(
+            "  {:<10} {} new, {} identical, {} conflicting (skipped)",
+            format!("{}:", label),
+            count(MigrationItemStatus::New),
+            count(MigrationItemStatus::Identical),
+            count(MigrationItemStatus::Conflict),
+        );
+    }
+    println!("  Settings:  {}", if report.has_settings { "yes (replaces current)" } else { "no" });
+    if !report.images_to_copy.is_empty() {
+        println!("  Images:    {} to copy", report.images_to_copy.len());
+    }
+    for problem in &report.problems {
+        println!("  ! {}", problem);
+    }
+    for file in &report.ignored_files {
+        println!("  - {} is not imported by this version", file);
+    }
+
+    let nothing_new = report.items.iter().all(|i| i.status != MigrationItemStatus::New);
+    if nothing_new && !report.has_settings {
+        println!("\nNothing to import.");
+        return Ok(());
+    }
+
+    // Ask for a new location for every project whose root is missing here
+    let mut mappings = Vec::new();
+    if !yes && !report.needs_path.is_empty() {
+        println!("\nSome project folders don't exist on this machine.");
+        for request in &report.needs_path {
+            eprint!(
+                "New location for '{}' (was {}) [Enter to keep]: ",
+                request.project_name, request.old_root_path
+            );
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let new_root = input.trim();
+            if !new_root.is_empty() {
+                mappings.push(PathMapping {
+                    project_id: request.project_id.clone(),
+                    new_root_path: new_root.to_string(),
+                });
+            }
+        }
+    }
+
+    if !yes {
+        eprint!("\nApply migration? [y/N] ");
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let result = storage
+        .apply_path_mappings(dir, &options, &mappings)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    println!("\nMigration results:");
+    println!("  Projects added:           {}", result.projects_added);
+    println!("  Scripts added:            {}", result.scripts_added);
+    println!("  Tools added:              {}", result.tools_added);
+    println!("  Apps added:               {}", result.apps_added);
+    println!("  Aliases added:            {}", result.aliases_added);
+    println!("  Tag definitions added:    {}", result.tag_definitions_added);
+    println!("  Status definitions added: {}", result.status_definitions_added);
+    println!("  Settings imported:        {}", result.settings_imported);
+    println!("  Skipped (duplicates):     {}", result.skipped);
+    Ok(())
+}
+
 fn cmd_backup(storage: &Storage) -> anyhow::Result<()> {
     let result = storage.backup_to_git().map_err(|e| anyhow::anyhow!("{}", e))?;
     println!("{}", result);
     Ok(())
 }
 
+/// Run the environment checks; any failed (critical) check makes the exit code non-zero
+fn cmd_doctor(json: bool) -> anyhow::Result<()> {
+    use cortx_core::doctor::{self, CheckStatus};
+
+    let app_dir = Storage::default_app_dir().map_err(CortxError::from)?;
+    let path_var = std::env::var_os("PATH");
+    let results = doctor::run_checks(&app_dir, path_var.as_deref());
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        for result in &results {
+            let (mark, color) = match result.status {
+                CheckStatus::Pass => ("ok  ", "\x1b[32m"),
+                CheckStatus::Warn => ("warn", "\x1b[33m"),
+                CheckStatus::Fail => ("FAIL", "\x1b[31m"),
+            };
+            let mark = if should_colorize() { format!("{}{}\x1b[0m", color, mark) } else { mark.to_string() };
+            println!("[{}] {:<15} {}", mark, result.name, result.message);
+            if let Some(hint) = &result.hint {
+                println!("       {:<15} → {}", "", hint);
+            }
+        }
+    }
+
+    if doctor::has_failures(&results) {
+        let failed = results.iter().filter(|r| r.status == CheckStatus::Fail).count();
+        return Err(CortxError::checks_failed(failed).into());
+    }
+    Ok(())
+}
+
 /// Print full CLI documentation — intended to be read by AI agents first
 fn cmd_docs() -> anyhow::Result<()> {
     print!("{}", include_str!("docs.md"));
@@ -3605,28 +3809,16 @@ fn cmd_mcp_serve() -> anyhow::Result<()> {
 // Colorize tags (unchanged)
 // ============================================================================
 
-/// Colorize a list of tags using ANSI truecolor from tag definitions.
-/// Falls back to plain text when color is disabled (see [`should_colorize`]).
+/// Colorize a list of tags with their colors from tag definitions, as far
+/// as the terminal supports (see [`color::paint`]).
 fn colorize_tags(tags: &[String], tag_defs: &[TagDefinition]) -> String {
-    if !should_colorize() {
-        return tags.join(", ");
-    }
     tags.iter()
         .map(|tag| {
             let def = tag_defs.iter().find(|d| d.name.eq_ignore_ascii_case(tag));
-            if let Some(hex) = def.and_then(|d| d.color.as_deref()) {
-                let hex = hex.trim_start_matches('#');
-                if hex.len() == 6 {
-                    if let (Ok(r), Ok(g), Ok(b)) = (
-                        u8::from_str_radix(&hex[0..2], 16),
-                        u8::from_str_radix(&hex[2..4], 16),
-                        u8::from_str_radix(&hex[4..6], 16),
-                    ) {
-                        return format!("\x1b[38;2;{};{};{}m{}\x1b[0m", r, g, b, tag);
-                    }
-                }
+            match def.and_then(|d| d.color.as_deref()) {
+                Some(hex) => color::paint(hex, tag),
+                None => tag.clone(),
             }
-            tag.clone()
         })
         .collect::<Vec<_>>()
         .join(", ")
@@ -3660,46 +3852,85 @@ fn resolve_global_script(storage: &Storage, name: &str) -> anyhow::Result<Global
 
 /// Build the `(program, args)` for a global script, applying a preset if
 /// requested. Extracted so `cmd_run` and `cmd_run_detached` stay in sync.
+/// Parameter selection for a global script run: `--preset`, then `--param`
+/// / `--flag` on top.
+struct ParamArgs<'a> {
+    preset: Option<&'a str>,
+    params: &'a [String],
+    flags: &'a [String],
+}
+
 fn build_global_command(
     script: &GlobalScript,
-    preset_name: Option<&str>,
+    params: &ParamArgs,
     extra_args: &[String],
 ) -> anyhow::Result<(String, Vec<String>)> {
-    let mut param_values = std::collections::HashMap::new();
-    if let Some(preset_name) = preset_name {
-        let preset = script
-            .parameter_presets
-            .iter()
-            .find(|p| p.name.eq_ignore_ascii_case(preset_name))
-            .ok_or_else(|| CortxError::not_found("preset", preset_name))?;
-
-        for param in &script.parameters {
-            let is_enabled = if !preset.enabled.is_empty() {
-                preset.enabled.get(&param.name).copied().unwrap_or(false) || param.required
-            } else {
-                preset.values.contains_key(&param.name)
-            };
-            if !is_enabled {
-                continue;
-            }
-            if let Some(value) = preset.values.get(&param.name) {
-                param_values.insert(param.name.clone(), value.clone());
-            }
-        }
-    }
+    let preset = match params.preset {
+        Some(preset_name) => Some(
+            script
+                .parameter_presets
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case(preset_name))
+                .ok_or_else(|| CortxError::not_found("preset", preset_name))?,
+        ),
+        None => None,
+    };
+    let param_values =
+        cortx_core::command_builder::resolve_param_values(script, preset, params.params, params.flags)
+            .map_err(|e| param_error(script, e))?;
     cortx_core::command_builder::build_command(script, &param_values, extra_args)
         .ok_or_else(|| anyhow::anyhow!("Empty command"))
 }
 
+/// Turn a `--param` problem into an INVALID_ARGUMENT error; unknown names
+/// get "did you mean" suggestions from the declared parameters.
+fn param_error(script: &GlobalScript, err: cortx_core::command_builder::ParamError) -> CortxError {
+    let suggestions = match &err {
+        cortx_core::command_builder::ParamError::Unknown { name, valid } => {
+            let valid: Vec<&str> = valid.iter().map(String::as_str).collect();
+            cli_error::suggest_names(name, &valid, 3)
+        }
+        _ => Vec::new(),
+    };
+    CortxError::invalid_argument(format!("{}: {}", script.name, err))
+        .with_resource("parameter")
+        .with_suggestions(suggestions)
+}
+
+/// Fail with REQUIREMENT_NOT_MET unless every service the script requires is
+/// running. The CLI never starts them itself.
+fn check_requirements(
+    storage: &Storage,
+    script: &GlobalScript,
+    is_running: impl Fn(&str) -> bool,
+) -> anyhow::Result<()> {
+    cortx_core::script_requirements::ensure_requirements(script, &storage.get_all_projects(), is_running)
+        .map_err(|e| CortxError::requirement_not_met(&e).into())
+}
+
+/// Fail with RUN_REFUSED when this cortx was started by a script run and
+/// running `script` would loop or nest too deep; otherwise the env vars
+/// that carry the run chain down to the script.
+fn check_run_guard(storage: &Storage, script: &GlobalScript) -> anyhow::Result<[(&'static str, String); 2]> {
+    let max_depth = storage.get_settings().scripts_config.max_run_depth;
+    cortx_core::run_guard::guard_run(&script.id, max_depth)
+        .map_err(|e| CortxError::run_refused(&e, &script.name).into())
+}
+
 fn cmd_run_detached(
     storage: &Storage,
     name: &str,
-    preset_name: Option<&str>,
+    params: &ParamArgs,
     extra_args: &[String],
     json: bool,
 ) -> anyhow::Result<()> {
     let script = resolve_global_script(storage, name)?;
     let store = RuntimeStore::new(storage.app_dir())?;
+    check_requirements(storage, &script, |id| {
+        store
+            .get(id)
+            .is_some_and(|e| matches!(e.kind, EntityKind::Service) && runtime_state::is_pid_alive(e.pid))
+    })?;
 
     if let Some(existing) = store.get(&script.id) {
         if runtime_state::is_pid_alive(existing.pid) {
@@ -3710,23 +3941,25 @@ fn cmd_run_detached(
         store.unregister(&script.id)?;
     }
 
-    let (program, args) = build_global_command(&script, preset_name, extra_args)?;
-    let working_dir = script
-        .working_dir
-        .clone()
-        .unwrap_or_else(|| {
-            std::env::current_dir()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|_| ".".to_string())
-        });
+    let lineage_env = check_run_guard(storage, &script)?;
+
+    let (program, args) = build_global_command(&script, params, extra_args)?;
+    let base = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    let working_dir = script_location::working_dir(&script, &base)
+        .to_string_lossy()
+        .to_string();
     let log_path = store.log_path(&script.id);
-    let command_display = format!("{} {}", program, args.join(" "));
+    let command_display = util::command_line(&program, &args);
+
+    let mut env_vars = script.env_vars.clone().unwrap_or_default();
+    env_vars.extend(lineage_env.map(|(key, value)| (key.to_string(), value)));
 
     let pid = runtime_state::spawn_detached(
         &program,
         &args,
         &working_dir,
-        script.env_vars.as_ref(),
+        Some(&env_vars),
+        &script.env_mode,
         &log_path,
     )
     .map_err(|e| anyhow::anyhow!("Failed to spawn global script: {}", e))?;
@@ -3742,7 +3975,9 @@ fn cmd_run_detached(
         project_id: None,
         project_name: None,
         mode: None,
-        arg_preset: preset_name.map(String::from),
+        arg_preset: params.preset.map(String::from),
+        run_id: new_run_id(),
+        variant_id: None,
     };
     store.register(&entry)?;
 
@@ -3814,40 +4049,18 @@ fn cmd_global_script_logs(
 /// Run a script directly by name
 fn cmd_run(
     storage: &Storage,
-    process_manager: &ProcessManager,
+    process_manager: &Arc<ProcessManager>,
     name: &str,
-    preset_name: Option<&str>,
+    params: &ParamArgs,
     extra_args: &[String],
 ) -> anyhow::Result<()> {
     let script = resolve_global_script(storage, name)?;
     let script = &script;
+    check_requirements(storage, script, |id| process_manager.is_service_running(id))?;
+    // `run_global_script` enforces this too; checked here for a RUN_REFUSED error
+    check_run_guard(storage, script)?;
 
-    // Build param_values HashMap from preset (if any)
-    let mut param_values = std::collections::HashMap::new();
-    if let Some(preset_name) = preset_name {
-        let preset = script
-            .parameter_presets
-            .iter()
-            .find(|p| p.name.eq_ignore_ascii_case(preset_name))
-            .ok_or_else(|| CortxError::not_found("preset", preset_name))?;
-
-        for param in &script.parameters {
-            let is_enabled = if !preset.enabled.is_empty() {
-                preset.enabled.get(&param.name).copied().unwrap_or(false) || param.required
-            } else {
-                preset.values.contains_key(&param.name)
-            };
-            if !is_enabled {
-                continue;
-            }
-            if let Some(value) = preset.values.get(&param.name) {
-                param_values.insert(param.name.clone(), value.clone());
-            }
-        }
-    }
-
-    let (program, args) = cortx_core::command_builder::build_command(script, &param_values, extra_args)
-        .ok_or_else(|| anyhow::anyhow!("Empty command"))?;
+    let (program, args) = build_global_command(script, params, extra_args)?;
 
     // Use a simple channel-based emitter that prints to stdout
     let (tx, rx) = mpsc::channel::<ProcessEvent>();
@@ -3857,7 +4070,7 @@ fn cmd_run(
         .map(|p| p.to_string_lossy().to_string())
         .unwrap_or_else(|_| ".".to_string());
 
-    println!("Running: {} {}", program, args.join(" "));
+    println!("Running: {}", util::command_line(&program, &args));
     println!("Working dir: {}", working_dir);
     println!("{}", "-".repeat(50));
 
@@ -3869,7 +4082,10 @@ fn cmd_run(
             program,
             args,
             script.env_vars.clone(),
-            RuntimeMeta::new(script.name.clone()),
+            &script.env_mode,
+            RuntimeMeta::new(script.name.clone())
+                .with_allow_concurrent(script.allow_concurrent)
+                .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
         )
         .map_err(|e| anyhow::anyhow!(e))?;
 
@@ -3879,7 +4095,10 @@ fn cmd_run(
             Ok(ProcessEvent::Log { content, stream, .. }) => {
                 match stream {
                     cortx_core::models::LogStream::Stdout => println!("{}", content),
-                    cortx_core::models::LogStream::Stderr => eprintln!("{}", content),
+                    // Kept off stdout, which may be piped on
+                    cortx_core::models::LogStream::Stderr | cortx_core::models::LogStream::Command => {
+                        eprintln!("{}", content)
+                    }
                 }
             }
             Ok(ProcessEvent::Exit { exit_code, success, .. }) => {
@@ -3891,7 +4110,7 @@ fn cmd_run(
                 }
                 break;
             }
-            Ok(ProcessEvent::Status { .. }) => {}
+            Ok(ProcessEvent::Status { .. }) | Ok(ProcessEvent::Prompt { .. }) => {}
             // Service events are not surfaced by `cortx run` (it runs global
             // scripts only). Ignore them if any slip through.
             Ok(ProcessEvent::ServiceLog { .. })
@@ -3934,7 +4153,13 @@ fn run_tui(storage: Arc<Storage>, process_manager: Arc<ProcessManager>) -> anyho
     let emitter = Arc::new(TuiEmitter::new(process_tx));
 
     // Create app
-    let mut app = App::new(storage.clone(), process_manager.clone(), emitter);
+    // The terminal is ours: scripts get a pipe the output panel types into
+    process_manager.set_interactive_stdin(true);
+    process_manager.set_prompt_detection(cortx_core::prompt_detect::from_config(&storage.get_settings().tui));
+    let mut app = App::new(storage.clone(), process_manager.clone(), emitter.clone());
+
+    // Global scripts with a schedule run while the TUI is open
+    cortx_core::scheduler::spawn(&process_manager, storage.clone(), emitter);
 
     // Start file watcher
     let watch_dir = storage.app_dir().to_path_buf();
@@ -3957,7 +4182,8 @@ fn run_tui(storage: Arc<Storage>, process_manager: Arc<ProcessManager>) -> anyho
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    // Pastes arrive as one event instead of a stream of key presses
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -3966,7 +4192,7 @@ fn run_tui(storage: Arc<Storage>, process_manager: Arc<ProcessManager>) -> anyho
 
     // Cleanup
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableBracketedPaste, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     // Stop all running processes
@@ -3980,24 +4206,28 @@ fn main_loop(
     app: &mut App,
     event_rx: &mpsc::Receiver<event::Event>,
 ) -> anyhow::Result<()> {
+    let budget = FrameBudget::from(&app.storage.get_settings().tui_event_budget);
+    // Work the previous frame's time budget didn't cover
+    let mut pending: VecDeque<Work> = VecDeque::new();
+
     loop {
         // Draw
         terminal.draw(|f| ui::draw(f, app))?;
 
-        // Wait for first event (blocking)
-        let first = match event_rx.recv() {
-            Ok(ev) => ev,
-            Err(_) => break,
-        };
-        handle_event(app, first);
+        // Wait for the next event, unless there's leftover work
+        let mut events = Vec::new();
+        if pending.is_empty() {
+            match event_rx.recv() {
+                Ok(ev) => events.push(ev),
+                Err(_) => break,
+            }
+        }
 
-        // Drain all remaining pending events before redrawing.
-        // This prevents the TUI from freezing when a script produces
-        // lots of output — we batch-process all queued events, then
-        // draw once instead of drawing after every single event.
-        loop {
+        // Take what's queued, up to the budget, so a flood of output is
+        // handled in batches and draws still happen in between
+        while events.len() < budget.max_events {
             match event_rx.try_recv() {
-                Ok(ev) => handle_event(app, ev),
+                Ok(ev) => events.push(ev),
                 Err(mpsc::TryRecvError::Empty) => break,
                 Err(mpsc::TryRecvError::Disconnected) => {
                     app.should_quit = true;
@@ -4006,6 +4236,27 @@ fn main_loop(
             }
         }
 
+        // Keys go before everything else, including leftover work
+        let frame = frame_budget::plan_frame(events);
+        for user_input in frame.keys {
+            match user_input {
+                UserInput::Key(key) => input::handle_key(app, key),
+                UserInput::Paste(text) => input::handle_paste(app, &text),
+            }
+        }
+        pending.extend(frame.work);
+
+        let deadline = Instant::now() + budget.max_time;
+        while let Some(work) = pending.pop_front() {
+            match work {
+                Work::Event(ev) => handle_event(app, ev),
+                Work::Logs { source, lines } => app.handle_log_batch(source, lines),
+            }
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+
         if app.should_quit {
             break;
         }
@@ -4017,6 +4268,7 @@ fn main_loop(
 fn handle_event(app: &mut App, ev: event::Event) {
     match ev {
         event::Event::Key(key) => input::handle_key(app, key),
+        event::Event::Paste(text) => input::handle_paste(app, &text),
         event::Event::Process(pe) => app.handle_process_event(pe),
         event::Event::DataChanged => {
             if let Err(e) = app.storage.reload_all() {
@@ -4025,6 +4277,6 @@ fn handle_event(app: &mut App, ev: event::Event) {
             }
             app.refresh_data();
         }
-        event::Event::Tick => {}
+        event::Event::Tick => app.on_tick(),
     }
 }
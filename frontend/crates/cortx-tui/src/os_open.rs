@@ -29,6 +29,35 @@ pub fn open_path(path: &str) -> Result<(), String> {
     }
 }
 
+/// Show a file in the file manager, selected where the platform allows it.
+pub fn reveal_path(path: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        Command::new("explorer")
+            .arg(format!("/select,{}", path))
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reveal '{}': {}", path, e))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open")
+            .args(["-R", path])
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to reveal '{}': {}", path, e))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        // No portable way to select a file: open its folder instead
+        let folder = std::path::Path::new(path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+        open_path(&folder)
+    }
+}
+
 /// Open a URL in the system's default browser.
 pub fn open_url(url: &str) -> Result<(), String> {
     // Same handlers — `start`, `open`, and `xdg-open` all route URLs to the
@@ -0,0 +1,119 @@
+//! Which finished script runtimes give up their log buffers.
+//!
+//! A long-lived TUI would otherwise keep every run's output in memory.
+//! [`select_evictions`] only looks at runtime metadata; the caller drops
+//! the logs of what it returns (keeping status, exit code and command).
+
+use std::time::{Duration, Instant};
+
+use cortx_core::models::ScriptRetentionConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Finished runs older than this lose their logs
+    pub max_age: Duration,
+    /// Only the most recently finished runs keep their logs
+    pub max_retained: usize,
+}
+
+impl From<&ScriptRetentionConfig> for RetentionPolicy {
+    fn from(config: &ScriptRetentionConfig) -> Self {
+        Self {
+            max_age: Duration::from_secs(config.max_age_minutes.saturating_mul(60)),
+            max_retained: config.max_retained,
+        }
+    }
+}
+
+/// What the policy knows about one script runtime
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeInfo<'a> {
+    pub id: &'a str,
+    pub running: bool,
+    pub finished_at: Option<Instant>,
+    /// Whether it still holds a log buffer worth dropping
+    pub has_logs: bool,
+}
+
+/// Ids of the runtimes whose logs should be dropped, oldest first.
+///
+/// Only finished runtimes that still hold logs are considered. Walking from
+/// the most recently finished, a runtime is evicted once it is older than
+/// `max_age` or `max_retained` newer ones were kept. `exempt` ids (the
+/// script being viewed) are never evicted but count as kept.
+pub fn select_evictions(
+    runtimes: &[RuntimeInfo],
+    exempt: &[&str],
+    policy: &RetentionPolicy,
+    now: Instant,
+) -> Vec<String> {
+    let mut finished: Vec<(&str, Instant)> = runtimes
+        .iter()
+        .filter(|r| !r.running && r.has_logs)
+        .filter_map(|r| r.finished_at.map(|at| (r.id, at)))
+        .collect();
+    // Newest first; ids break ties so the result is stable
+    finished.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut kept = 0;
+    let mut evicted = Vec::new();
+    for (id, finished_at) in finished {
+        if exempt.contains(&id) {
+            kept += 1;
+            continue;
+        }
+        let too_old = now.saturating_duration_since(finished_at) > policy.max_age;
+        if too_old || kept >= policy.max_retained {
+            evicted.push(id.to_string());
+        } else {
+            kept += 1;
+        }
+    }
+    evicted.reverse();
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_age_secs: u64, max_retained: usize) -> RetentionPolicy {
+        RetentionPolicy { max_age: Duration::from_secs(max_age_secs), max_retained }
+    }
+
+    /// Runtimes finished `ago` seconds before `now`
+    fn finished<'a>(now: Instant, runs: &[(&'a str, u64)]) -> Vec<RuntimeInfo<'a>> {
+        runs.iter()
+            .map(|&(id, ago)| RuntimeInfo {
+                id,
+                running: false,
+                finished_at: Some(now - Duration::from_secs(ago)),
+                has_logs: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn evicts_by_age_and_count_oldest_first() {
+        let now = Instant::now() + Duration::from_secs(10_000);
+        let runs = finished(now, &[("a", 10), ("b", 20), ("c", 30), ("d", 5000)]);
+
+        assert!(select_evictions(&runs, &[], &policy(7200, 20), now).is_empty());
+        assert_eq!(select_evictions(&runs, &[], &policy(60, 20), now), vec!["d"]);
+        assert_eq!(select_evictions(&runs, &[], &policy(7200, 2), now), vec!["d", "c"]);
+        assert_eq!(select_evictions(&runs, &[], &policy(7200, 0), now), vec!["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn exempt_running_and_already_evicted_are_kept() {
+        let now = Instant::now() + Duration::from_secs(10_000);
+        let mut runs = finished(now, &[("viewed", 5000), ("a", 10), ("b", 20)]);
+        runs.push(RuntimeInfo { id: "running", running: true, finished_at: None, has_logs: true });
+        runs.push(RuntimeInfo { id: "rerun", running: true, finished_at: Some(now - Duration::from_secs(9000)), has_logs: true });
+        runs.push(RuntimeInfo { id: "empty", running: false, finished_at: Some(now - Duration::from_secs(9000)), has_logs: false });
+
+        // The viewed script survives its age
+        assert_eq!(select_evictions(&runs, &["viewed"], &policy(60, 1), now), vec!["b"]);
+        assert_eq!(select_evictions(&runs, &["viewed"], &policy(7200, 0), now), vec!["b", "a"]);
+    }
+}
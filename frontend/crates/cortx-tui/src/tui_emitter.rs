@@ -1,5 +1,6 @@
+use chrono::{DateTime, Utc};
 use cortx_core::models::{LogStream, ScriptStatus, ServiceStatus};
-use cortx_core::process_manager::ProcessEventEmitter;
+use cortx_core::process_manager::{split_run_key, ProcessEventEmitter};
 use std::sync::mpsc;
 
 use crate::app::ProcessEvent;
@@ -23,22 +24,26 @@ impl ProcessEventEmitter for TuiEmitter {
             service_id: service_id.to_string(),
             stream,
             content,
+            timestamp: Utc::now(),
         });
     }
 
     fn emit_service_status(&self, service_id: &str, status: ServiceStatus, pid: Option<u32>,
-                           _active_mode: Option<String>, _active_arg_preset: Option<String>) {
+                           _active_mode: Option<String>, _active_arg_preset: Option<String>,
+                           started_at: Option<DateTime<Utc>>) {
         let _ = self.tx.send(ProcessEvent::ServiceStatus {
             service_id: service_id.to_string(),
             status,
             pid,
+            started_at,
         });
     }
 
-    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>) {
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>) {
         let _ = self.tx.send(ProcessEvent::ServiceExit {
             service_id: service_id.to_string(),
             exit_code,
+            error_excerpt,
         });
     }
 
@@ -47,28 +52,45 @@ impl ProcessEventEmitter for TuiEmitter {
     fn emit_script_status(&self, _script_id: &str, _status: ScriptStatus, _pid: Option<u32>) {}
     fn emit_script_exit(&self, _script_id: &str, _exit_code: Option<i32>, _success: bool) {}
 
-    // Global script events - these are the ones we care about
+    // Global script events - these are the ones we care about. Concurrent
+    // runs come in under their run key, split back into script and run.
     fn emit_global_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.tx.send(ProcessEvent::Log {
             script_id: script_id.to_string(),
+            run_id: run_id.map(String::from),
             stream,
             content,
+            timestamp: Utc::now(),
         });
     }
 
     fn emit_global_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.tx.send(ProcessEvent::Status {
             script_id: script_id.to_string(),
+            run_id: run_id.map(String::from),
             status,
             pid,
         });
     }
 
     fn emit_global_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.tx.send(ProcessEvent::Exit {
             script_id: script_id.to_string(),
+            run_id: run_id.map(String::from),
             exit_code,
             success,
         });
     }
+
+    fn emit_global_script_prompt(&self, script_id: &str, prompt: String) {
+        let (script_id, run_id) = split_run_key(script_id);
+        let _ = self.tx.send(ProcessEvent::Prompt {
+            script_id: script_id.to_string(),
+            run_id: run_id.map(String::from),
+            prompt,
+        });
+    }
 }
@@ -0,0 +1,69 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+use crate::util::strip_ansi;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::Bookmarks {
+        return;
+    }
+
+    let bookmarks = app.active_bookmarks();
+
+    let area = f.area();
+    let popup_width = 80u16.min(area.width.saturating_sub(4));
+    let content_height = bookmarks.len().max(1) as u16;
+    let popup_height = (content_height + 2).min(area.height.saturating_sub(4)); // +2 for borders
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+    f.render_widget(Clear, popup_area);
+
+    let now = chrono::Utc::now();
+    let items: Vec<ListItem> = bookmarks
+        .iter()
+        .map(|bookmark| {
+            let ago = (now - bookmark.created_at).to_std().unwrap_or_default();
+            let mut spans = vec![Span::styled(
+                format!("{:>7} ago  ", cortx_core::runtime_state::format_uptime(ago)),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            )];
+            if let Some(label) = &bookmark.label {
+                spans.push(Span::styled(
+                    format!("{}  ", label),
+                    Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD),
+                ));
+            }
+            match app.bookmark_preview(bookmark) {
+                Some(line) => spans.push(Span::styled(
+                    strip_ansi(line).trim().to_string(),
+                    Style::default().fg(theme::TEXT_PRIMARY),
+                )),
+                None => spans.push(Span::styled(
+                    "(line no longer in buffer)",
+                    Style::default().fg(theme::TEXT_MUTED),
+                )),
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.bookmarks_selected));
+
+    let block = Block::default()
+        .title(format!(" Bookmarks ({}) ", bookmarks.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme::style_selected())
+        .highlight_symbol("\u{25b6} "); // ▶
+
+    f.render_stateful_widget(list, popup_area, &mut list_state);
+}
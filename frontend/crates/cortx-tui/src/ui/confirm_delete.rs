@@ -0,0 +1,46 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::ConfirmDelete {
+        return;
+    }
+
+    let name = app.pending_delete_name().unwrap_or("this script");
+
+    let area = f.area();
+    let popup_width = 50u16.min(area.width.saturating_sub(4));
+    let popup_height = 5u16;
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Delete Script ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::STATUS_FAILED));
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Delete ", Style::default().fg(theme::TEXT_PRIMARY)),
+            Span::styled(name, Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD)),
+            Span::styled("?", Style::default().fg(theme::TEXT_PRIMARY)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Delete  "),
+            Span::styled("n/Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Cancel"),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, popup_area);
+}
@@ -0,0 +1,57 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::ConfirmRequirements {
+        return;
+    }
+
+    let pending = match &app.pending_requirements {
+        Some(p) => p,
+        None => return,
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                pending.script.name.as_str(),
+                Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" needs these services running:", Style::default().fg(theme::TEXT_PRIMARY)),
+        ]),
+        Line::from(""),
+    ];
+    for missing in &pending.unmet.missing {
+        lines.push(Line::from(vec![
+            Span::styled("  \u{25cb} ", Style::default().fg(theme::STATUS_FAILED)), // ○
+            Span::styled(missing.label.as_str(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("y", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        Span::raw(" Start them and run  "),
+        Span::styled("n/Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        Span::raw(" Cancel"),
+    ]));
+
+    let area = f.area();
+    let popup_width = 60u16.min(area.width.saturating_sub(4));
+    let popup_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Required Services ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, popup_area);
+}
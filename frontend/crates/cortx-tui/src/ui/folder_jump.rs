@@ -0,0 +1,68 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+
+use crate::app::{App, FolderJumpState, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::FolderJump {
+        return;
+    }
+
+    let state = match &app.folder_jump {
+        Some(s) => s,
+        None => return,
+    };
+
+    let area = f.area();
+    let popup_width = 44u16.min(area.width.saturating_sub(4));
+    let content_height = state.matches.len().max(1) as u16;
+    let popup_height = (content_height + 2).min(area.height.saturating_sub(4)); // +2 for borders
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+    f.render_widget(Clear, popup_area);
+
+    let items: Vec<ListItem> = if state.matches.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "No matching folder",
+            Style::default().fg(theme::TEXT_MUTED),
+        )))]
+    } else {
+        state
+            .matches
+            .iter()
+            .map(|&i| {
+                let target = &state.targets[i];
+                let name_style = if target.folder.is_some() {
+                    Style::default().fg(theme::TEXT_PRIMARY)
+                } else {
+                    Style::default().fg(theme::TEXT_SECONDARY).add_modifier(Modifier::ITALIC)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(FolderJumpState::label(target).to_string(), name_style),
+                    Span::styled(format!(" ({})", target.count), Style::default().fg(theme::TEXT_MUTED)),
+                ]))
+            })
+            .collect()
+    };
+
+    let mut list_state = ListState::default();
+    if !state.matches.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+
+    let block = Block::default()
+        .title(" Jump to Folder ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme::style_selected())
+        .highlight_symbol("\u{25b6} "); // ▶
+
+    f.render_stateful_widget(list, popup_area, &mut list_state);
+}
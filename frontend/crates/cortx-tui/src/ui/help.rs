@@ -11,7 +11,7 @@ pub fn render(f: &mut Frame, app: &App) {
 
     let area = f.area();
     let popup_width = 60u16.min(area.width.saturating_sub(4));
-    let popup_height = 46u16.min(area.height.saturating_sub(4));
+    let popup_height = 61u16.min(area.height.saturating_sub(4));
 
     let x = (area.width.saturating_sub(popup_width)) / 2;
     let y = (area.height.saturating_sub(popup_height)) / 2;
@@ -33,11 +33,31 @@ pub fn render(f: &mut Frame, app: &App) {
         help_line("Tab", "Switch panel (Scripts / Output)"),
         help_line("Enter", "Run script (opens run form)"),
         help_line("C-Enter", "Quick-run with last params"),
+        help_line("y", "Copy command (last params) to clipboard"),
+        help_line("H", "Run history (m mark, = compare two runs, o/O open artifact)"),
+        help_line("O", "Open the files the last run created"),
+        help_line("L", "Restore logs evicted from a finished run"),
+        help_line("m / '", "Bookmark output line / list bookmarks (d delete)"),
+        help_line("w / W", "Open working dir / script file's folder"),
         help_line("s", "Stop active script"),
-        help_line("/", "Search by name"),
+        help_line("[ / ]", "Show previous / next run (concurrent scripts)"),
+        help_line("!", "Pin running scripts to top"),
+        help_line("P", "Pause / resume scheduled runs"),
+        help_line("b", "Bind to a quick slot (1-9, x unbinds)"),
+        help_line("F1-F9", "Quick-run a slot from any tab (also Alt+1-9)"),
+        help_line("a", "Add a new script"),
+        help_line("e", "Edit selected script"),
+        help_line("p", "Detect parameters from --help (keeps edited ones)"),
+        help_line("d", "Delete selected script"),
+        help_line("E / I", "Export / import data (JSON file)"),
+        help_line("/", "Search (tag:x folder:y text)"),
         help_line("t", "Filter by tag"),
+        help_line("F", "Jump to folder"),
+        help_line("C-o", "Apply organize rules to listed scripts"),
+        help_line("S", "Scan the scripts folder and import new scripts"),
         help_line("Esc", "Clear all filters"),
         help_line("r", "Reload data"),
+        help_line("C-s", "Retry failed saves"),
         Line::from(""),
         Line::from(Span::styled(
             "Tabs:",
@@ -55,7 +75,14 @@ pub fn render(f: &mut Frame, app: &App) {
         )),
         help_line("c", "Clear output"),
         help_line("f", "Toggle auto-scroll"),
+        help_line("t", "Toggle timestamps"),
+        help_line("/", "Search output (C-r: regex)"),
+        help_line("n / N", "Next / previous match"),
+        help_line("i", "Type into the running script (C-d: end input)"),
         help_line("j / k", "Scroll output"),
+        help_line("PgUp / PgDn", "Scroll a page (C-u / C-d: half a page)"),
+        help_line("g / G", "Top / bottom of the output"),
+        help_line("w", "Wrap long lines (off: h / l scroll sideways)"),
         Line::from(""),
         Line::from(Span::styled(
             "Tools:",
@@ -86,12 +113,15 @@ pub fn render(f: &mut Frame, app: &App) {
         help_line("s", "Stop selected service"),
         help_line("A", "Start all services"),
         help_line("S", "Stop all services"),
+        help_line("R", "Reset a crash-looping service's restart circuit"),
+        help_line("e", "Edit command (Shift+Enter saves and restarts)"),
         help_line("Tab", "Toggle list / output"),
         help_line("o", "Open project folder"),
         help_line("v", "Open in VS Code"),
         help_line("Esc", "Back to projects"),
         Line::from(""),
         help_line("?", "Toggle this help"),
+        help_line(",", "Settings"),
         help_line("q", "Quit"),
     ];
 
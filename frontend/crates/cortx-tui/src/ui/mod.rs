@@ -17,6 +17,19 @@ mod app_info;
 mod projects_list;
 mod project_info;
 mod project_detail;
+mod script_editor;
+mod confirm_delete;
+mod path_prompt;
+mod folder_jump;
+mod confirm_requirements;
+mod organize_preview;
+mod scan_results;
+mod run_history;
+mod run_compare;
+mod settings;
+mod bookmarks;
+mod service_editor;
+mod quick_bar;
 
 use ratatui::prelude::*;
 
@@ -25,19 +38,23 @@ use crate::app::{App, ActiveTab};
 pub fn draw(f: &mut Frame, app: &mut App) {
     let area = f.area();
 
-    // Main layout: tab bar (1 line) + body + status bar (1 line)
+    // Main layout: tab bar (1 line) + body + quick run bar (1 line, once a
+    // slot is bound) + status bar (1 line)
+    let quick_bar_height = u16::from(!app.tui_config.quick_slots.is_empty());
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1),
             Constraint::Min(0),
+            Constraint::Length(quick_bar_height),
             Constraint::Length(1),
         ])
         .split(area);
 
     let tab_area = main_chunks[0];
     let body = main_chunks[1];
-    let status = main_chunks[2];
+    let quick_bar_area = main_chunks[2];
+    let status = main_chunks[3];
 
     // Tab bar
     tab_bar::render(f, tab_area, app);
@@ -113,11 +130,26 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         }
     }
 
+    if quick_bar_height > 0 {
+        quick_bar::render(f, quick_bar_area, app);
+    }
     status_bar::render(f, status, app);
 
-    // Overlays (search popup, help screen, param form, tag filter)
+    // Overlays (search popup, help screen, param form, tag filter, script editor)
     search::render(f, app);
     help::render(f, app);
     param_form::render(f, app);
     tag_filter::render(f, app);
+    script_editor::render(f, app);
+    confirm_delete::render(f, app);
+    path_prompt::render(f, app);
+    folder_jump::render(f, app);
+    confirm_requirements::render(f, app);
+    organize_preview::render(f, app);
+    scan_results::render(f, app);
+    run_history::render(f, app);
+    run_compare::render(f, app);
+    settings::render(f, app);
+    bookmarks::render(f, app);
+    service_editor::render(f, app);
 }
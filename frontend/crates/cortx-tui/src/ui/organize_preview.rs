@@ -0,0 +1,71 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::OrganizePreview {
+        return;
+    }
+
+    let preview = match &app.organize_preview {
+        Some(p) => p,
+        None => return,
+    };
+
+    let area = f.area();
+    let popup_width = 80u16.min(area.width.saturating_sub(4));
+    let max_height = area.height.saturating_sub(4);
+
+    let mut header = vec![Line::from(Span::styled(
+        format!(
+            "{} of {} listed script(s) would change",
+            preview.plan.changes.len(),
+            preview.script_ids.len()
+        ),
+        Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD),
+    ))];
+    if !preview.plan.created_folders.is_empty() {
+        header.push(Line::from(Span::styled(
+            format!("New folders: {}", preview.plan.created_folders.join(", ")),
+            Style::default().fg(theme::TEXT_HIGHLIGHT),
+        )));
+    }
+    header.push(Line::from(""));
+
+    // +2 borders; the rest of the height lists changes from `scroll` on
+    let visible = (max_height as usize).saturating_sub(header.len() + 2).max(1);
+    let mut lines = header;
+    for change in preview.plan.changes.iter().skip(preview.scroll).take(visible) {
+        lines.push(Line::from(vec![
+            Span::styled(change.script_name.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+            Span::styled("  ", Style::default()),
+            Span::styled(change.summary(), Style::default().fg(theme::TEXT_SECONDARY)),
+        ]));
+    }
+    let hidden = preview.plan.changes.len().saturating_sub(preview.scroll + visible);
+    if hidden > 0 {
+        if let Some(last) = lines.last_mut() {
+            *last = Line::from(Span::styled(
+                format!("… {} more", hidden + 1),
+                Style::default().fg(theme::TEXT_MUTED),
+            ));
+        }
+    }
+
+    let popup_height = (lines.len() as u16 + 2).min(max_height);
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Organize Scripts ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, popup_area);
+}
@@ -1,11 +1,13 @@
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Block, Borders, Paragraph};
 
 use ansi_to_tui::IntoText;
-use cortx_core::models::LogStream;
+use cortx_core::models::{LogStream, OutputFormat};
+use cortx_core::output_format::format_output;
 
-use crate::app::{App, ActivePanel};
+use crate::app::{App, ActivePanel, LogLine, OutputSearchState};
 use crate::ui::theme;
+use crate::util::{strip_ansi, wrap_line};
 
 pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
     let is_active = app.active_panel == ActivePanel::Output;
@@ -16,19 +18,52 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
         theme::style_border_inactive()
     };
 
+    // A search over another script's logs ends once it's no longer shown
+    if app.output_search.as_ref().is_some_and(|s| s.script_id != app.active_script_id) {
+        app.output_search = None;
+    }
+    let matches = app.output_search_matches();
+    let current_match = app.output_search_current();
+
+    let show_timestamps = app.show_timestamps;
     let logs = app.get_active_logs();
     let log_count = logs.len();
+    // The script's output format only changes how stdout is shown
+    let output_format = app.active_script().map(|s| s.output_format).unwrap_or_default();
 
     let scroll_indicator = if app.auto_scroll { "auto" } else { "manual" };
+    let format_indicator = if output_format.is_raw() {
+        String::new()
+    } else {
+        format!(", {}", output_format.label())
+    };
+    let bookmark_count = app.active_bookmarks().len();
+    let bookmark_indicator = if bookmark_count > 0 { format!(" [{} marks]", bookmark_count) } else { String::new() };
+    let search_indicator = match &app.output_search {
+        Some(search) if !search.query.is_empty() => {
+            let position = current_match
+                .and_then(|cur| matches.iter().position(|&i| i == cur))
+                .map(|p| format!("{}/", p + 1))
+                .unwrap_or_default();
+            let mode = if search.regex { " regex" } else { "" };
+            format!(" [/{}{} {}{}]", search.query, mode, position, matches.len())
+        }
+        _ => String::new(),
+    };
     let title = match &app.active_script_id {
-        Some(id) => {
-            let name = app
-                .scripts
-                .iter()
-                .find(|s| s.id == *id)
-                .map(|s| s.name.as_str())
-                .unwrap_or("?");
-            format!(" Output: {} [{}] ({} lines) ", name, scroll_indicator, log_count)
+        Some(key) => {
+            let script = app.active_script();
+            let name = script.map(|s| s.name.as_str()).unwrap_or("?");
+            // Which run, when the script has several
+            let runs = script.map(|s| app.script_runs(&s.id)).unwrap_or_default();
+            let run_indicator = match runs.iter().position(|(k, _)| k == key) {
+                Some(i) if runs.len() > 1 => format!(" run {}/{}", i + 1, runs.len()),
+                _ => String::new(),
+            };
+            format!(
+                " Output: {}{} [{}{}] ({} lines){}{} ",
+                name, run_indicator, scroll_indicator, format_indicator, log_count, bookmark_indicator, search_indicator
+            )
         }
         None => " Output ".to_string(),
     };
@@ -46,29 +81,65 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
         return;
     }
 
-    let lines: Vec<Line> = logs
-        .iter()
-        .flat_map(|log| {
-            match log.stream {
-                LogStream::Stdout => {
-                    // Parse ANSI codes into styled spans
-                    if let Ok(text) = log.content.as_bytes().into_text() {
-                        text.lines.into_iter().collect::<Vec<_>>()
-                    } else {
-                        vec![Line::styled(log.content.clone(), Style::default().fg(theme::LOG_STDOUT))]
-                    }
-                }
-                LogStream::Stderr => {
-                    // Stderr always in red, strip any ANSI codes
-                    vec![Line::styled(log.content.clone(), Style::default().fg(theme::LOG_STDERR))]
+    let entries = display_entries(logs, output_format);
+    let search = app.output_search.as_ref();
+
+    // Wrapping is done here rather than by the paragraph, so the rows each
+    // log line takes are known and scrolling can count rows
+    let width = area.width.saturating_sub(2) as usize;
+    let wrap = app.wrap_output;
+    let mut previous = None;
+    let mut lines: Vec<Line> = Vec::new();
+    let mut row_starts = vec![0; log_count];
+    for (i, formatted) in entries {
+        let log = &logs[i];
+        // Search hits get a background; the one jumped to stands out
+        let highlight = if current_match == Some(i) {
+            Some(Style::default().bg(theme::SEARCH_MATCH).fg(Color::Black))
+        } else if matches.binary_search(&i).is_ok() {
+            Some(Style::default().bg(Color::DarkGray))
+        } else {
+            None
+        };
+        let mut log_rows = match (search, highlight) {
+            (Some(search), Some(hit)) => {
+                let text = formatted.unwrap_or_else(|| strip_ansi(&log.content));
+                let base = Style::default().fg(match log.stream {
+                    LogStream::Stdout => theme::LOG_STDOUT,
+                    LogStream::Stderr => theme::LOG_STDERR,
+                    LogStream::Command => theme::LOG_COMMAND,
+                });
+                vec![highlight_matches(&text, search, base, hit)]
+            }
+            _ => log_lines(log, formatted),
+        };
+        // Once per log line, even when it's shown over several lines
+        if previous != Some(i) {
+            row_starts[i] = lines.len();
+            if show_timestamps {
+                if let Some(first) = log_rows.first_mut() {
+                    let time = log.timestamp.with_timezone(&chrono::Local).format("%H:%M:%S%.3f");
+                    first.spans.insert(0, Span::styled(format!("{} ", time), Style::default().fg(theme::TEXT_MUTED)));
                 }
             }
-        })
-        .collect();
+        }
+        previous = Some(i);
+        if wrap {
+            lines.extend(log_rows.into_iter().flat_map(|line| wrap_line(line, width)));
+        } else {
+            lines.append(&mut log_rows);
+        }
+    }
+    let line_count = lines.len();
 
     // Calculate visible height (area height minus 2 for borders)
     let visible_height = area.height.saturating_sub(2) as usize;
-    let max_scroll = log_count.saturating_sub(visible_height);
+    let max_scroll = line_count.saturating_sub(visible_height);
+    app.output_height = visible_height;
+    if let Some(index) = app.output_anchor.take() {
+        app.output_scroll = row_starts.get(index).copied().unwrap_or(0);
+    }
+    app.output_row_starts = row_starts;
 
     // Auto-scroll: always show latest
     if app.auto_scroll {
@@ -80,10 +151,89 @@ pub fn render(f: &mut Frame, area: Rect, app: &mut App) {
         }
     }
 
+    let hscroll = if wrap { 0 } else { app.output_hscroll };
     let paragraph = Paragraph::new(lines)
         .block(block)
-        .wrap(Wrap { trim: false })
-        .scroll((app.output_scroll as u16, 0));
+        .scroll((app.output_scroll as u16, hscroll as u16));
 
     f.render_widget(paragraph, area);
 }
+
+/// A log line as styled lines: stdout keeps its ANSI colors unless it was
+/// reformatted, stderr is always red and an echoed command stands out
+fn log_lines(log: &LogLine, formatted: Option<String>) -> Vec<Line<'static>> {
+    match (&log.stream, formatted) {
+        (LogStream::Stdout, Some(text)) => {
+            vec![Line::styled(text, Style::default().fg(theme::LOG_STDOUT))]
+        }
+        (LogStream::Stdout, None) => {
+            // Parse ANSI codes into styled spans
+            if let Ok(text) = log.content.as_bytes().into_text() {
+                text.lines.into_iter().collect::<Vec<_>>()
+            } else {
+                vec![Line::styled(log.content.clone(), Style::default().fg(theme::LOG_STDOUT))]
+            }
+        }
+        (LogStream::Stderr, _) => {
+            // Stderr always in red, strip any ANSI codes
+            vec![Line::styled(log.content.clone(), Style::default().fg(theme::LOG_STDERR))]
+        }
+        (LogStream::Command, _) => {
+            vec![Line::styled(log.content.clone(), Style::default().fg(theme::LOG_COMMAND).add_modifier(Modifier::BOLD))]
+        }
+    }
+}
+
+/// `text` with the search's matches in `hit`. A line that matched without
+/// any text to point at (e.g. a regex matching empty) is all in `hit`.
+fn highlight_matches(text: &str, search: &OutputSearchState, base: Style, hit: Style) -> Line<'static> {
+    let ranges = search.match_ranges(text);
+    if ranges.is_empty() {
+        return Line::styled(text.to_string(), base.patch(hit));
+    }
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for range in ranges {
+        if range.start > end {
+            spans.push(Span::styled(text[end..range.start].to_string(), base));
+        }
+        spans.push(Span::styled(text[range.clone()].to_string(), base.patch(hit)));
+        end = range.end;
+    }
+    if end < text.len() {
+        spans.push(Span::styled(text[end..].to_string(), base));
+    }
+    Line::from(spans)
+}
+
+/// The log lines to show, by index, with the reformatted text of the stdout
+/// ones the output format changed. Formatting runs over each stretch of
+/// stdout so JSON printed over several lines is seen whole.
+fn display_entries(logs: &[LogLine], format: OutputFormat) -> Vec<(usize, Option<String>)> {
+    if format.is_raw() {
+        return (0..logs.len()).map(|i| (i, None)).collect();
+    }
+    let mut entries = Vec::with_capacity(logs.len());
+    let mut i = 0;
+    while i < logs.len() {
+        if !matches!(logs[i].stream, LogStream::Stdout) {
+            entries.push((i, None));
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < logs.len() && matches!(logs[i].stream, LogStream::Stdout) {
+            i += 1;
+        }
+        let run: Vec<&str> = logs[start..i].iter().map(|log| log.content.as_str()).collect();
+        let formatted = format_output(format, &run);
+        for (n, line) in formatted.iter().enumerate() {
+            // A line passed through as is keeps its ANSI styling
+            let alone = !formatted.get(n + 1).is_some_and(|next| next.source == line.source)
+                && (n == 0 || formatted[n - 1].source != line.source);
+            let unchanged = alone && line.text == run[line.source];
+            entries.push((start + line.source, (!unchanged).then(|| line.text.clone())));
+        }
+    }
+    entries
+}
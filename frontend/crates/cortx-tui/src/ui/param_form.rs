@@ -3,6 +3,8 @@ use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
 use crate::app::{App, InputMode};
 use crate::ui::theme;
+use crate::util::command_line;
+use cortx_core::help_parser::locate_param_help;
 use cortx_core::models::ScriptParamType;
 
 pub fn render(f: &mut Frame, app: &App) {
@@ -19,7 +21,9 @@ pub fn render(f: &mut Frame, app: &App) {
     let param_count = form.param_names.len();
 
     // Size: width 70, height = command(2) + params(2 each) + extra_args(3) + hints(2) + padding
-    let popup_height = (4 + param_count * 2 + 5 + 3) as u16;
+    let env_line = usize::from(!form.script.env_mode.is_inherit());
+    let requirements = app.requirement_statuses(&form.script);
+    let popup_height = (4 + param_count * 2 + 5 + 3 + env_line + requirements.len()) as u16;
     let popup_width = 70u16.min(area.width.saturating_sub(4));
     let popup_height = popup_height.min(area.height.saturating_sub(2));
 
@@ -43,7 +47,7 @@ pub fn render(f: &mut Frame, app: &App) {
 
     // Command preview
     let (prog, cmd_args) = form.build_command();
-    let preview = format!("{} {}", prog, cmd_args.join(" "));
+    let preview = command_line(&prog, &cmd_args);
     lines.push(Line::from(vec![
         Span::styled("Command: ", Style::default().fg(theme::TEXT_SECONDARY)),
         Span::styled(
@@ -51,6 +55,35 @@ pub fn render(f: &mut Frame, app: &App) {
             Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::ITALIC),
         ),
     ]));
+    // Sanitized environments surprise people ("why is my var missing"), so call it out
+    if !form.script.env_mode.is_inherit() {
+        lines.push(Line::from(vec![
+            Span::styled("Env:     ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                form.script.env_mode.label(),
+                Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    // Required services, so a missing one is visible before running
+    for (i, status) in requirements.iter().enumerate() {
+        let (dot, dot_color) = if status.is_met() {
+            ("\u{25cf}", theme::STATUS_RUNNING) // ●
+        } else {
+            ("\u{25cb}", theme::STATUS_FAILED) // ○
+        };
+        let mut spans = vec![
+            Span::styled(if i == 0 { "Needs:   " } else { "         " }, Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(format!("{} ", dot), Style::default().fg(dot_color)),
+            Span::styled(status.label.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+        ];
+        if let Some(problem) = &status.problem {
+            spans.push(Span::styled(format!("  {}", problem), Style::default().fg(theme::TEXT_MUTED)));
+        } else if status.can_auto_start() {
+            spans.push(Span::styled("  auto-start", Style::default().fg(theme::TEXT_MUTED)));
+        }
+        lines.push(Line::from(spans));
+    }
     lines.push(Line::from(""));
 
     // Parameters
@@ -97,6 +130,12 @@ pub fn render(f: &mut Frame, app: &App) {
             format!(" {}", flag_str),
             Style::default().fg(theme::TEXT_SECONDARY),
         ));
+        if let Some(group) = &param_def.mutex_group {
+            spans.push(Span::styled(
+                format!("  one of {}", group),
+                Style::default().fg(theme::TEXT_MUTED),
+            ));
+        }
 
         lines.push(Line::from(spans));
 
@@ -236,6 +275,10 @@ pub fn render(f: &mut Frame, app: &App) {
             spans.push(Span::styled("p", Style::default().fg(theme::TEXT_HIGHLIGHT)));
             spans.push(Span::raw(" Preset  "));
         }
+        if !extra_focused {
+            spans.push(Span::styled("?", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+            spans.push(Span::raw(" Help  "));
+        }
         spans.push(Span::styled("x", Style::default().fg(theme::TEXT_HIGHLIGHT)));
         spans.push(Span::raw(" Run  "));
         spans.push(Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)));
@@ -251,6 +294,87 @@ pub fn render(f: &mut Frame, app: &App) {
     if form.picking_preset {
         render_preset_picker(f, popup_area, form);
     }
+
+    if form.showing_help {
+        render_param_help(f, popup_area, form);
+    }
+}
+
+/// Help lines shown above and below the parameter's own entry
+const HELP_CONTEXT_LINES: usize = 2;
+
+/// `--help` excerpt for the focused parameter: its entry from the stored raw
+/// help, with a little context, or the stored description as a fallback.
+fn render_param_help(f: &mut Frame, parent_area: Rect, form: &crate::app::ParamFormState) {
+    let Some(param) = form.focused_param() else { return };
+
+    let mut lines: Vec<Line> = Vec::new();
+    let located = form
+        .script
+        .raw_help
+        .as_deref()
+        .and_then(|help| locate_param_help(help, param).map(|range| (help, range)));
+
+    match located {
+        Some((help, range)) => {
+            let help_lines: Vec<&str> = help.lines().collect();
+            let start = range.start.saturating_sub(HELP_CONTEXT_LINES);
+            let end = (range.end + HELP_CONTEXT_LINES).min(help_lines.len());
+            for (i, line) in help_lines.iter().enumerate().take(end).skip(start) {
+                let style = if range.contains(&i) {
+                    Style::default().fg(theme::TEXT_PRIMARY)
+                } else {
+                    Style::default().fg(theme::TEXT_MUTED)
+                };
+                lines.push(Line::from(Span::styled(line.to_string(), style)));
+            }
+        }
+        None => {
+            let description = param.description.as_deref().unwrap_or("No description");
+            lines.push(Line::from(Span::styled(
+                description.to_string(),
+                Style::default().fg(theme::TEXT_PRIMARY),
+            )));
+            lines.push(Line::from(""));
+            let reason = if form.script.raw_help.is_some() {
+                "Not found in the stored --help output"
+            } else {
+                "No --help output stored for this script"
+            };
+            lines.push(Line::from(Span::styled(reason, Style::default().fg(theme::TEXT_MUTED))));
+        }
+    }
+
+    lines.push(Line::from(""));
+    if let Some(ref notice) = form.help_notice {
+        lines.push(Line::from(Span::styled(
+            notice.clone(),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )));
+    }
+    lines.push(Line::from(vec![
+        Span::styled("r", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        Span::raw(" Re-run --help  "),
+        Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        Span::raw(" Close"),
+    ]));
+
+    let help_width = parent_area.width.saturating_sub(4).max(20);
+    let help_height = (lines.len() as u16 + 2).min(parent_area.height.saturating_sub(2));
+    let x = parent_area.x + (parent_area.width.saturating_sub(help_width)) / 2;
+    let y = parent_area.y + (parent_area.height.saturating_sub(help_height)) / 2;
+    let help_area = Rect::new(x, y, help_width, help_height);
+
+    f.render_widget(Clear, help_area);
+
+    let block = Block::default()
+        .title(format!(" Help: {} ", param.name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::TEXT_HIGHLIGHT));
+
+    // Keep leading spaces: help output is column-aligned
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, help_area);
 }
 
 fn render_preset_picker(f: &mut Frame, parent_area: Rect, form: &crate::app::ParamFormState) {
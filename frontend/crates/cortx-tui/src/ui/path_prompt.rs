@@ -0,0 +1,105 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::{App, InputMode, PathPromptPurpose};
+use crate::ui::theme;
+
+/// Candidates shown under the input after Tab
+const MAX_CANDIDATES: usize = 6;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::PathPrompt {
+        return;
+    }
+
+    let prompt = match &app.path_prompt {
+        Some(p) => p,
+        None => return,
+    };
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            Span::styled("Path: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(prompt.input.clone(), Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::styled("█", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        ]),
+    ];
+
+    if !prompt.candidates.is_empty() {
+        lines.push(Line::from(""));
+        for candidate in prompt.candidates.iter().take(MAX_CANDIDATES) {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", candidate),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            )));
+        }
+        if prompt.candidates.len() > MAX_CANDIDATES {
+            lines.push(Line::from(Span::styled(
+                format!("  … {} more", prompt.candidates.len() - MAX_CANDIDATES),
+                Style::default().fg(theme::TEXT_MUTED),
+            )));
+        }
+    }
+
+    if let Some((_, summary)) = &prompt.preview {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Export v{} from {}", summary.version, summary.exported_at.format("%Y-%m-%d %H:%M")),
+            Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD),
+        )));
+        let counts = [
+            ("projects", summary.projects_count),
+            ("scripts", summary.scripts_count),
+            ("tools", summary.tools_count),
+            ("apps", summary.apps_count),
+            ("aliases", summary.aliases_count),
+            ("tags", summary.tag_definitions_count),
+            ("statuses", summary.status_definitions_count),
+        ];
+        let mut parts: Vec<String> = counts
+            .iter()
+            .filter(|(_, n)| *n > 0)
+            .map(|(label, n)| format!("{} {}", n, label))
+            .collect();
+        if summary.has_settings {
+            parts.push("settings".to_string());
+        }
+        lines.push(Line::from(Span::styled(
+            parts.join(", "),
+            Style::default().fg(theme::TEXT_PRIMARY),
+        )));
+        lines.push(Line::from(Span::styled(
+            "Existing items are kept; Enter imports the rest",
+            Style::default().fg(theme::TEXT_MUTED),
+        )));
+    }
+
+    if let Some(error) = &prompt.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(theme::STATUS_FAILED),
+        )));
+    }
+
+    let area = f.area();
+    let popup_width = 80u16.min(area.width.saturating_sub(4));
+    let popup_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let title = match prompt.purpose {
+        PathPromptPurpose::Export => " Export to ",
+        PathPromptPurpose::Import => " Import from ",
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, popup_area);
+}
@@ -75,17 +75,15 @@ fn render_services_list(f: &mut Frame, area: Rect, app: &App) {
     let items: Vec<ListItem> = services
         .iter()
         .map(|svc| {
-            let status = app
-                .service_runtimes
-                .get(&svc.id)
-                .map(|r| r.status)
-                .unwrap_or(ServiceStatus::Stopped);
-            let (sym, style) = service_status_style(status);
-            let line = Line::from(vec![
+            let (sym, style) = service_status_style(app.service_status(&svc.id));
+            let mut spans = vec![
                 Span::styled(format!("{} ", sym), style),
                 Span::styled(svc.name.as_str(), Style::default().fg(theme::TEXT_PRIMARY)),
-            ]);
-            ListItem::new(line)
+            ];
+            if let Some(uptime) = app.service_uptime_label(&svc.id) {
+                spans.push(Span::styled(format!("  {}", uptime), Style::default().fg(theme::TEXT_MUTED)));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -119,11 +117,7 @@ fn render_service_info(f: &mut Frame, area: Rect, app: &App) {
         }
     };
 
-    let status = app
-        .service_runtimes
-        .get(&svc.id)
-        .map(|r| r.status)
-        .unwrap_or(ServiceStatus::Stopped);
+    let status = app.service_status(&svc.id);
     let (sym, status_style) = service_status_style(status);
 
     let mut lines = vec![
@@ -139,7 +133,32 @@ fn render_service_info(f: &mut Frame, area: Rect, app: &App) {
         Line::from(vec![
             Span::styled("Status: ", Style::default().fg(theme::TEXT_SECONDARY)),
             Span::styled(format!("{} {:?}", sym, status), status_style),
+            Span::styled(
+                app.service_uptime_label(&svc.id)
+                    .map(|uptime| format!("  ({})", uptime))
+                    .unwrap_or_default(),
+                Style::default().fg(theme::TEXT_SECONDARY),
+            ),
         ]),
+    ];
+
+    // Crashed too often: say why it's down and how to bring it back
+    if let Some(circuit) = app.process_manager.service_circuit(&svc.id) {
+        lines.push(Line::from(Span::styled(
+            format!("Restarts stopped: {} failed runs, last exited {}", circuit.failures, circuit.last_exit),
+            Style::default().fg(theme::STATUS_FAILED).add_modifier(Modifier::BOLD),
+        )));
+        let retry = circuit
+            .retry_at
+            .map(|at| format!("retry at {} · ", at.with_timezone(&chrono::Local).format("%H:%M:%S")))
+            .unwrap_or_default();
+        lines.push(Line::from(Span::styled(
+            format!("{}Enter start · R reset", retry),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )));
+    }
+
+    lines.extend([
         Line::from(vec![
             Span::styled("Command: ", Style::default().fg(theme::TEXT_SECONDARY)),
             Span::styled(svc.command.as_str(), Style::default().fg(theme::TEXT_HIGHLIGHT)),
@@ -151,7 +170,7 @@ fn render_service_info(f: &mut Frame, area: Rect, app: &App) {
                 Style::default().fg(theme::TEXT_PRIMARY),
             ),
         ]),
-    ];
+    ]);
 
     if let Some(port) = svc.port {
         lines.push(Line::from(vec![
@@ -244,11 +263,16 @@ fn render_service_output(f: &mut Frame, area: Rect, app: &mut App) {
                 log.content.clone(),
                 Style::default().fg(theme::LOG_STDERR),
             )],
+            LogStream::Command => vec![Line::styled(
+                log.content.clone(),
+                Style::default().fg(theme::LOG_COMMAND).add_modifier(Modifier::BOLD),
+            )],
         })
         .collect();
 
     let visible_height = area.height.saturating_sub(2) as usize;
     let max_scroll = log_count.saturating_sub(visible_height);
+    app.output_height = visible_height;
 
     if app.auto_scroll {
         app.output_scroll = max_scroll;
@@ -264,11 +288,18 @@ fn render_service_output(f: &mut Frame, area: Rect, app: &mut App) {
     f.render_widget(paragraph, area);
 }
 
-fn service_status_style(status: ServiceStatus) -> (&'static str, Style) {
+pub(super) fn service_status_style(status: ServiceStatus) -> (&'static str, Style) {
     match status {
         ServiceStatus::Running => ("\u{25cf}", Style::default().fg(theme::STATUS_RUNNING)),
         ServiceStatus::Starting => ("\u{25cb}", Style::default().fg(theme::STATUS_RUNNING)),
+        ServiceStatus::Unhealthy => ("\u{25cf}", Style::default().fg(theme::STATUS_UNHEALTHY)),
+        ServiceStatus::Restarting => ("\u{25cb}", Style::default().fg(theme::STATUS_UNHEALTHY)),
+        ServiceStatus::Stopping => ("\u{25cb}", Style::default().fg(theme::STATUS_IDLE)),
         ServiceStatus::Stopped => ("\u{25cb}", Style::default().fg(theme::STATUS_IDLE)),
         ServiceStatus::Error => ("\u{25cf}", Style::default().fg(theme::STATUS_FAILED)),
+        ServiceStatus::CircuitOpen => (
+            "\u{2298}", // ⊘
+            Style::default().fg(theme::STATUS_FAILED).add_modifier(Modifier::BOLD),
+        ),
     }
 }
@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 
 use crate::app::App;
+use crate::ui::project_detail::service_status_style;
 use crate::ui::theme;
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
@@ -53,6 +54,15 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         Span::styled("Path: ", Style::default().fg(theme::TEXT_SECONDARY)),
         Span::styled(&project.root_path, Style::default().fg(theme::TEXT_HIGHLIGHT)),
     ]));
+    if let Some(variant) = project.active_variant() {
+        lines.push(Line::from(vec![
+            Span::styled("Variant: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(
+                format!("{} ({})", variant.name, variant.root_path),
+                Style::default().fg(theme::TEXT_HIGHLIGHT),
+            ),
+        ]));
+    }
 
     if let Some(ref desc) = project.description {
         if !desc.is_empty() {
@@ -100,9 +110,10 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             ),
         ]));
         for svc in &project.services {
+            let (sym, status_style) = service_status_style(app.service_status(&svc.id));
             let mut svc_spans = vec![
                 Span::raw("  "),
-                Span::styled("\u{25b8} ", Style::default().fg(theme::TEXT_MUTED)), // ▸
+                Span::styled(format!("{} ", sym), status_style),
                 Span::styled(&svc.name, Style::default().fg(theme::TEXT_PRIMARY)),
             ];
             if !svc.command.is_empty() {
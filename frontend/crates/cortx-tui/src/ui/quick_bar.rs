@@ -0,0 +1,27 @@
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use crate::app::App;
+use crate::ui::theme;
+
+/// The bound quick slots, one line above the status bar. Not shown while
+/// no slot is bound; a slot whose script was deleted shows as empty.
+pub fn render(f: &mut Frame, area: Rect, app: &App) {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, &slot) in app.tui_config.quick_slots.keys().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" | ", Style::default().fg(theme::SEPARATOR_COLOR)));
+        }
+        spans.push(Span::styled(format!("F{} ", slot), Style::default().fg(theme::TEXT_HIGHLIGHT)));
+        spans.push(match app.quick_slot_script(slot) {
+            Some(script) if app.running_count(&script.id) > 0 => {
+                Span::styled(script.name.clone(), Style::default().fg(theme::STATUS_RUNNING))
+            }
+            Some(script) => Span::styled(script.name.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+            None => Span::styled("(empty)", Style::default().fg(theme::TEXT_MUTED)),
+        });
+    }
+
+    let para = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Rgb(30, 30, 40)));
+    f.render_widget(para, area);
+}
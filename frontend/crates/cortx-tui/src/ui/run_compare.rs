@@ -0,0 +1,110 @@
+use cortx_core::run_compare::{LineChange, RunSide};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+use crate::util::format_duration_ms;
+
+fn side_line(label: &str, side: &RunSide) -> Line<'static> {
+    let exit = match side.exit_code {
+        Some(code) => format!("exit {}", code),
+        None if side.success => "ok".to_string(),
+        None => "no exit code".to_string(),
+    };
+    let color = if side.success { theme::STATUS_COMPLETED } else { theme::STATUS_FAILED };
+    let mut spans = vec![
+        Span::styled(format!("{} ", label), Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+        Span::styled(
+            side.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            Style::default().fg(theme::TEXT_PRIMARY),
+        ),
+        Span::styled(format!("  {}", exit), Style::default().fg(color)),
+    ];
+    if let Some(ms) = side.duration_ms {
+        spans.push(Span::styled(format!("  {}", format_duration_ms(ms)), Style::default().fg(theme::TEXT_SECONDARY)));
+    }
+    if let Some(preset) = &side.preset_name {
+        spans.push(Span::styled(format!(" [{}]", preset), Style::default().fg(theme::TAG_COLOR)));
+    }
+    Line::from(spans)
+}
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::RunCompare {
+        return;
+    }
+
+    let state = match &app.run_compare {
+        Some(s) => s,
+        None => return,
+    };
+    let cmp = &state.comparison;
+
+    let area = f.area();
+    let popup_width = 100u16.min(area.width.saturating_sub(4));
+    let max_height = area.height.saturating_sub(4);
+
+    let muted = Style::default().fg(theme::TEXT_MUTED);
+    let mut header = vec![side_line("A", &cmp.a), side_line("B", &cmp.b)];
+    if let Some(delta) = cmp.duration_delta_ms {
+        let sign = if delta < 0 { "-" } else { "+" };
+        header.push(Line::from(Span::styled(
+            format!("Duration {}{}", sign, format_duration_ms(delta.unsigned_abs())),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )));
+    }
+    for change in &cmp.param_changes {
+        header.push(Line::from(vec![
+            Span::styled(format!("{}: ", change.key), Style::default().fg(theme::TEXT_PRIMARY)),
+            Span::styled(change.a.clone().unwrap_or_else(|| "(unset)".to_string()), Style::default().fg(theme::LOG_STDERR)),
+            Span::styled(" → ", muted),
+            Span::styled(change.b.clone().unwrap_or_else(|| "(unset)".to_string()), Style::default().fg(theme::STATUS_RUNNING)),
+        ]));
+    }
+    match &cmp.log_diff {
+        None => header.push(Line::from(Span::styled("Output not available for both runs", muted))),
+        Some(diff) if diff.is_identical() => {
+            header.push(Line::from(Span::styled("Output identical (timestamps and ids masked)", muted)))
+        }
+        Some(diff) => header.push(Line::from(Span::styled(
+            format!("Output: +{} -{} line(s)", diff.added, diff.removed),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        ))),
+    }
+    header.push(Line::from(""));
+
+    // Unified diff body, scrolled by `state.scroll`
+    let mut body = Vec::new();
+    for hunk in cmp.log_diff.iter().flat_map(|d| d.hunks.iter()) {
+        body.push(Line::from(Span::styled(hunk.header(), Style::default().fg(theme::BORDER_ACTIVE))));
+        for line in &hunk.lines {
+            let (prefix, style) = match line.change {
+                LineChange::Equal => (" ", Style::default().fg(theme::TEXT_SECONDARY)),
+                LineChange::Removed => ("-", Style::default().fg(theme::LOG_STDERR)),
+                LineChange::Added => ("+", Style::default().fg(theme::STATUS_RUNNING)),
+            };
+            body.push(Line::from(Span::styled(format!("{}{}", prefix, line.text), style)));
+        }
+    }
+
+    // +2 borders
+    let visible = (max_height as usize).saturating_sub(header.len() + 2).max(1);
+    let mut lines = header;
+    lines.extend(body.into_iter().skip(state.scroll).take(visible));
+
+    let popup_height = (lines.len() as u16 + 2).min(max_height);
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Compare Runs (A → B) ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(paragraph, popup_area);
+}
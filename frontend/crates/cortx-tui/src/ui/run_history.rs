@@ -0,0 +1,101 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+use crate::util::format_duration_ms;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::RunHistory {
+        return;
+    }
+
+    let history = match &app.run_history {
+        Some(h) => h,
+        None => return,
+    };
+
+    let area = f.area();
+    let popup_width = 80u16.min(area.width.saturating_sub(4));
+    let content_height = history.records.len().max(1) as u16;
+    let popup_height = (content_height + 2).min(area.height.saturating_sub(4)); // +2 for borders
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+    f.render_widget(Clear, popup_area);
+
+    let items: Vec<ListItem> = history
+        .records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let (symbol, color) = match (record.finished_at, record.success) {
+                (None, _) => ("●", theme::STATUS_RUNNING),
+                (Some(_), true) => ("✓", theme::STATUS_COMPLETED),
+                (Some(_), false) => ("✗", theme::STATUS_FAILED),
+            };
+            let exit = record.exit_code.map(|c| format!("exit {}", c)).unwrap_or_default();
+            let duration = record.duration_ms.map(format_duration_ms).unwrap_or_default();
+            let mark = if history.marked == Some(i) { "= " } else { "  " };
+
+            let mut spans = vec![
+                Span::styled(mark, Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{} ", symbol), Style::default().fg(color)),
+                Span::styled(
+                    record.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    Style::default().fg(theme::TEXT_PRIMARY),
+                ),
+                Span::styled(format!("  {:>7}  {:<8}", duration, exit), Style::default().fg(theme::TEXT_SECONDARY)),
+            ];
+            if let Some(preset) = &record.preset_name {
+                spans.push(Span::styled(format!("[{}]", preset), Style::default().fg(theme::TAG_COLOR)));
+            }
+            if !record.artifacts.is_empty() {
+                spans.push(Span::styled(
+                    format!(" {} artifact(s)", record.artifacts.len()),
+                    Style::default().fg(theme::TEXT_SECONDARY),
+                ));
+            }
+            if record.log_start.is_none() {
+                spans.push(Span::styled(" (no output recorded)", Style::default().fg(theme::TEXT_MUTED)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(history.selected));
+
+    let mut block = Block::default()
+        .title(format!(" Runs: {} ", history.script_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    // Highlighted artifact of the selected run (Tab for the next one)
+    if let Some(path) = history.selected_artifact() {
+        let count = history.records[history.selected].artifacts.len();
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" artifact {}/{}: {} ", history.artifact + 1, count, path),
+            Style::default().fg(theme::TEXT_HIGHLIGHT),
+        )));
+    }
+
+    // Env vars the selected run injected
+    if let Some(env) = history.records.get(history.selected).and_then(|r| r.env_snapshot.as_ref()) {
+        let mut vars: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        vars.sort();
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" env: {} ", vars.join(" ")),
+            Style::default().fg(theme::TEXT_SECONDARY),
+        )));
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme::style_selected())
+        .highlight_symbol("\u{25b6} "); // ▶
+
+    f.render_stateful_widget(list, popup_area, &mut list_state);
+}
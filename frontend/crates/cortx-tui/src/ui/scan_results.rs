@@ -0,0 +1,104 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::ScanResults {
+        return;
+    }
+
+    let results = match &app.scan_results {
+        Some(r) => r,
+        None => return,
+    };
+
+    // Below the list: a truncated scan, then the scripts whose file is gone
+    let mut notes = Vec::new();
+    if results.truncated {
+        notes.push(Line::from(Span::styled(
+            "Scan stopped early: the folder is too large, some scripts may be missing",
+            Style::default().fg(theme::STATUS_UNHEALTHY),
+        )));
+    }
+    if !results.vanished.is_empty() {
+        notes.push(Line::from(Span::styled(
+            format!("{} script file(s) are missing:", results.vanished.len()),
+            Style::default().fg(theme::STATUS_UNHEALTHY),
+        )));
+        for vanished in &results.vanished {
+            notes.push(Line::from(vec![
+                Span::styled(format!("  {}  ", vanished.script_name), Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled(vanished.path.clone(), Style::default().fg(theme::TEXT_MUTED)),
+            ]));
+        }
+    }
+
+    let area = f.area();
+    let popup_width = 90u16.min(area.width.saturating_sub(4));
+    let max_height = area.height.saturating_sub(4);
+    let list_height = results.scripts.len().max(1) as u16;
+    let notes_height = notes.len() as u16 + u16::from(!notes.is_empty());
+    let popup_height = (list_height + notes_height + 2).min(max_height); // +2 for borders
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+    f.render_widget(Clear, popup_area);
+
+    let picked = results.picked.iter().filter(|&&p| p).count();
+    let block = Block::default()
+        .title(format!(" New Scripts ({} of {} picked) ", picked, results.scripts.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(notes_height)])
+        .split(inner);
+    let (list_area, notes_area) = (chunks[0], chunks[1]);
+
+    let items: Vec<ListItem> = if results.scripts.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "No new scripts",
+            Style::default().fg(theme::TEXT_MUTED),
+        )))]
+    } else {
+        results
+            .scripts
+            .iter()
+            .zip(&results.picked)
+            .map(|(script, &picked)| {
+                let mut spans = vec![
+                    Span::styled(
+                        if picked { "[x] " } else { "[ ] " },
+                        Style::default().fg(theme::TEXT_HIGHLIGHT),
+                    ),
+                    Span::styled(script.name.clone(), Style::default().fg(theme::TEXT_PRIMARY)),
+                ];
+                if let Some(description) = &script.description {
+                    spans.push(Span::styled(format!("  {}", description), Style::default().fg(theme::TEXT_SECONDARY)));
+                }
+                spans.push(Span::styled(format!("  {}", script.path), Style::default().fg(theme::TEXT_MUTED)));
+                ListItem::new(Line::from(spans))
+            })
+            .collect()
+    };
+
+    let mut list_state = ListState::default();
+    if !results.scripts.is_empty() {
+        list_state.select(Some(results.selected));
+    }
+    let list = List::new(items)
+        .highlight_style(theme::style_selected())
+        .highlight_symbol("\u{25b6} "); // ▶
+    f.render_stateful_widget(list, list_area, &mut list_state);
+
+    if !notes.is_empty() {
+        notes.insert(0, Line::from(""));
+        f.render_widget(Paragraph::new(notes), notes_area);
+    }
+}
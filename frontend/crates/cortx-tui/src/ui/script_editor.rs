@@ -0,0 +1,121 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::{App, InputMode, ScriptEditorState, SCRIPT_EDITOR_FIELDS};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::ScriptEditor {
+        return;
+    }
+
+    let form = match &app.script_editor {
+        Some(f) => f,
+        None => return,
+    };
+
+    let area = f.area();
+
+    // Size: width 70, height = fields(2 each) + error(2) + hints(2) + borders
+    let popup_height = (SCRIPT_EDITOR_FIELDS.len() * 2 + 2 + 2 + 2) as u16;
+    let popup_width = 70u16.min(area.width.saturating_sub(4));
+    let popup_height = popup_height.min(area.height.saturating_sub(2));
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let title = if form.is_new() { " New Script ".to_string() } else { " Edit Script ".to_string() };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (i, label) in SCRIPT_EDITOR_FIELDS.iter().enumerate() {
+        let is_focused = i == form.focused;
+        let value = &form.values[i];
+
+        // Label line
+        let mut spans: Vec<Span> = Vec::new();
+        if is_focused {
+            spans.push(Span::styled("▶ ", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+        } else {
+            spans.push(Span::raw("  "));
+        }
+        let label_style = if is_focused {
+            Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme::TEXT_SECONDARY)
+        };
+        spans.push(Span::styled(label.to_string(), label_style));
+        if i == ScriptEditorState::NAME || i == ScriptEditorState::COMMAND {
+            spans.push(Span::styled(" *", Style::default().fg(theme::STATUS_RUNNING)));
+        }
+        lines.push(Line::from(spans));
+
+        // Value line (indented)
+        let mut val_spans: Vec<Span> = Vec::new();
+        val_spans.push(Span::raw("    "));
+        if is_focused && form.editing {
+            let (before, after) = value.split_at(form.cursor_byte());
+            val_spans.push(Span::styled(before.to_string(), Style::default().fg(theme::TEXT_HIGHLIGHT)));
+            val_spans.push(Span::styled("█", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+            val_spans.push(Span::styled(after.to_string(), Style::default().fg(theme::TEXT_HIGHLIGHT)));
+        } else if value.is_empty() {
+            val_spans.push(Span::styled(placeholder(i), Style::default().fg(theme::TEXT_MUTED)));
+        } else {
+            val_spans.push(Span::styled(value.clone(), Style::default().fg(theme::TEXT_PRIMARY)));
+        }
+        lines.push(Line::from(val_spans));
+    }
+
+    // Error line
+    lines.push(Line::from(""));
+    if let Some(ref err) = form.error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", err),
+            Style::default().fg(theme::STATUS_FAILED),
+        )));
+    } else {
+        lines.push(Line::from(""));
+    }
+
+    // Hints
+    let hint_line = if form.editing {
+        Line::from(vec![
+            Span::styled("Esc/Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Stop editing"),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("j/k", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Nav  "),
+            Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Edit  "),
+            Span::styled("s", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Save  "),
+            Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::raw(" Cancel"),
+        ])
+    };
+    lines.push(hint_line);
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, inner);
+}
+
+fn placeholder(field: usize) -> &'static str {
+    match field {
+        ScriptEditorState::WORKING_DIR => "(current directory)",
+        ScriptEditorState::TAGS => "(comma-separated)",
+        ScriptEditorState::FOLDER => "(none, e.g. deploy/aws)",
+        _ => "(empty)",
+    }
+}
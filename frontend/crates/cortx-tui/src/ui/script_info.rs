@@ -85,6 +85,42 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         ]));
     }
 
+    if let Some(ref group) = script.mutex_group {
+        let holder = match app.process_manager.mutex_group_holder(group) {
+            Some(holder) if holder.id == script.id => " (held by this script)".to_string(),
+            Some(holder) => {
+                let running_for = (chrono::Utc::now() - holder.since).to_std().unwrap_or_default();
+                format!(
+                    " (held by '{}' for {})",
+                    holder.name,
+                    cortx_core::runtime_state::format_uptime(running_for)
+                )
+            }
+            None => String::new(),
+        };
+        let mode = if script.queue_on_mutex { ", queues" } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled("Mutex group: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(format!("{}{}{}", group, mode, holder), Style::default().fg(theme::TEXT_PRIMARY)),
+        ]));
+    }
+
+    if let Some(ref schedule) = script.schedule {
+        let scheduler = app.process_manager.scheduler();
+        let next = match scheduler.next_run(&script.id) {
+            Some(at) => format!(
+                " (next {})",
+                at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            None => String::new(),
+        };
+        let paused = if scheduler.is_paused() { ", paused" } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled("Schedule: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(format!("{}{}{}", schedule, next, paused), Style::default().fg(theme::TEXT_PRIMARY)),
+        ]));
+    }
+
     // Show runtime info
     if let Some(runtime) = app.runtimes.get(&script.id) {
         if let Some(ref cmd) = runtime.last_command {
@@ -114,6 +150,34 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         }
     }
 
+    // Concurrent runs: pick the one the output panel shows with [ / ]
+    let runs = app.script_runs(&script.id);
+    if runs.len() > 1 {
+        lines.push(Line::from(vec![
+            Span::styled("Runs: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled("[ / ] to show, s to stop", Style::default().fg(theme::TEXT_MUTED)),
+        ]));
+        for (i, (key, runtime)) in runs.iter().enumerate() {
+            let shown = app.active_script_id.as_deref() == Some(*key);
+            let since = runtime
+                .started_at
+                .map(|at| format!("  {}", cortx_core::runtime_state::format_uptime(at.elapsed())))
+                .unwrap_or_default();
+            let pid = runtime.pid.map(|pid| format!("  PID {}", pid)).unwrap_or_default();
+            let mut spans = vec![
+                Span::styled(
+                    format!("{} #{}", theme::style_status_symbol(&runtime.status), i + 1),
+                    theme::style_status(&runtime.status),
+                ),
+                Span::styled(format!("{}{}", pid, since), Style::default().fg(theme::TEXT_PRIMARY)),
+            ];
+            if shown {
+                spans.push(Span::styled("  (shown)", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
     let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
 
     f.render_widget(paragraph, area);
@@ -1,7 +1,7 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
-use crate::app::{App, ActivePanel};
+use crate::app::{App, ActivePanel, FINISH_HIGHLIGHT};
 use crate::ui::theme;
 
 pub fn render(f: &mut Frame, area: Rect, app: &App) {
@@ -25,14 +25,22 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
 
     for (fi, &idx) in app.filtered_indices.iter().enumerate() {
         let script = &app.scripts[idx];
+        let pinned = fi < app.pinned_count;
         let primary_tag = script.tags.first().map(|s| s.as_str());
 
-        // Insert separator when primary tag changes
-        if primary_tag != last_primary_tag {
-            let (label, color) = match primary_tag {
+        // Insert separator at the top of the pinned section, after it, and
+        // whenever the primary tag changes
+        let header = if pinned {
+            (fi == 0).then_some(("active", theme::STATUS_RUNNING))
+        } else if (fi > 0 && fi == app.pinned_count) || primary_tag != last_primary_tag {
+            Some(match primary_tag {
                 Some(t) => (t, theme::tag_color(t, &app.tag_definitions)),
                 None => ("other", theme::TEXT_MUTED),
-            };
+            })
+        } else {
+            None
+        };
+        if let Some((label, color)) = header {
             let sep_line = Line::from(vec![
                 Span::styled("── ", Style::default().fg(theme::SEPARATOR_COLOR)),
                 Span::styled(label, Style::default().fg(color)),
@@ -43,23 +51,52 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             if fi <= app.selected_index {
                 display_selected += 1;
             }
+        }
+        if !pinned {
             last_primary_tag = primary_tag;
         }
 
         // Build normal item
-        let status = app
-            .runtimes
-            .get(&script.id)
-            .map(|r| &r.status)
-            .unwrap_or(&cortx_core::models::ScriptStatus::Idle);
+        // A concurrent run keeps the script running after its first run ends
+        let running = app.running_count(&script.id);
+        let status = if running > 0 {
+            &cortx_core::models::ScriptStatus::Running
+        } else {
+            app.runtimes
+                .get(&script.id)
+                .map(|r| &r.status)
+                .unwrap_or(&cortx_core::models::ScriptStatus::Idle)
+        };
 
         let symbol = theme::style_status_symbol(status);
         let status_style = theme::style_status(status);
 
-        let line = Line::from(vec![
+        // Just finished: highlight where it landed, bold for the first half
+        let name_style = match app.recently_finished(&script.id) {
+            Some(elapsed) if elapsed < FINISH_HIGHLIGHT / 2 => Style::default()
+                .fg(theme::TEXT_HIGHLIGHT)
+                .add_modifier(Modifier::BOLD),
+            Some(_) => Style::default().fg(theme::TEXT_HIGHLIGHT),
+            None => Style::default().fg(theme::TEXT_PRIMARY),
+        };
+
+        let mut spans = vec![
             Span::styled(format!("{} ", symbol), status_style),
-            Span::styled(&script.name, Style::default().fg(theme::TEXT_PRIMARY)),
-        ]);
+            Span::styled(&script.name, name_style),
+        ];
+        if running > 1 {
+            spans.push(Span::styled(format!(" \u{d7}{}", running), status_style)); // ×
+        }
+        // Another script holds its mutex group: running it now is refused
+        let blocked = script
+            .mutex_group
+            .as_deref()
+            .and_then(|group| app.process_manager.mutex_group_holder(group))
+            .is_some_and(|holder| holder.id != script.id);
+        if blocked {
+            spans.push(Span::styled(" \u{1f512}", Style::default().fg(theme::TEXT_MUTED))); // 🔒
+        }
+        let line = Line::from(spans);
 
         items.push(ListItem::new(line));
     }
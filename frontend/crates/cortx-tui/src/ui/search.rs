@@ -3,16 +3,24 @@ use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
 use crate::app::{App, ActiveTab, InputMode};
 use crate::ui::theme;
+use cortx_core::script_query::ScriptQuery;
 
 pub fn render(f: &mut Frame, app: &App) {
     if app.input_mode != InputMode::Search {
         return;
     }
 
+    // Scripts search understands `tag:`/`folder:` terms; echo what was parsed
+    let parsed = if app.active_tab == ActiveTab::Scripts {
+        Some(ScriptQuery::parse(&app.search_query)).filter(|q| !q.is_empty())
+    } else {
+        None
+    };
+
     // Overlay in center top area
     let area = f.area();
     let popup_width = 50u16.min(area.width.saturating_sub(4));
-    let popup_height = 3u16;
+    let popup_height = if parsed.is_some() { 4u16 } else { 3u16 };
 
     let x = (area.width.saturating_sub(popup_width)) / 2;
     let y = area.height / 4;
@@ -36,11 +44,18 @@ pub fn render(f: &mut Frame, app: &App) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme::SEARCH_MATCH));
 
-    let paragraph = Paragraph::new(Line::from(vec![
+    let mut lines = vec![Line::from(vec![
         Span::styled(&input_text, Style::default().fg(theme::TEXT_PRIMARY)),
         Span::styled("█", Style::default().fg(theme::SEARCH_MATCH)),
-    ]))
-    .block(block);
+    ])];
+    if let Some(query) = parsed {
+        lines.push(Line::from(vec![
+            Span::styled("Filter: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(query.describe(), Style::default().fg(theme::TEXT_MUTED)),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(lines).block(block);
 
     f.render_widget(paragraph, popup_area);
 }
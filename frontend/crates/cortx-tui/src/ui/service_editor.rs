@@ -0,0 +1,54 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+use crate::app::{App, InputMode};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::ServiceEditor {
+        return;
+    }
+
+    let editor = match &app.service_editor {
+        Some(e) => e,
+        None => return,
+    };
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(vec![
+            Span::styled("Command: ", Style::default().fg(theme::TEXT_SECONDARY)),
+            Span::styled(editor.command.clone(), Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            Span::styled("█", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "Enter saves; Shift+Enter also restarts the service if it's running",
+            Style::default().fg(theme::TEXT_MUTED),
+        )),
+    ];
+
+    if let Some(error) = &editor.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(theme::STATUS_FAILED),
+        )));
+    }
+
+    let area = f.area();
+    let popup_width = 80u16.min(area.width.saturating_sub(4));
+    let popup_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+
+    f.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Edit {} ", editor.service_name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE));
+
+    let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(paragraph, popup_area);
+}
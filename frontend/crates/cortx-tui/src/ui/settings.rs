@@ -0,0 +1,70 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+
+use crate::app::{App, InputMode, SettingsField};
+use crate::ui::theme;
+
+pub fn render(f: &mut Frame, app: &App) {
+    if app.input_mode != InputMode::Settings {
+        return;
+    }
+
+    let form = match &app.settings_form {
+        Some(form) => form,
+        None => return,
+    };
+
+    let area = f.area();
+    let popup_width = 52u16.min(area.width.saturating_sub(4));
+    let popup_height = (SettingsField::ALL.len() as u16 + 2).min(area.height.saturating_sub(4)); // +2 for borders
+
+    let x = (area.width.saturating_sub(popup_width)) / 2;
+    let y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect::new(x, y, popup_width, popup_height);
+    f.render_widget(Clear, popup_area);
+
+    let items: Vec<ListItem> = SettingsField::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            // The number being typed replaces the saved value, with a cursor
+            let value = match &form.editing {
+                Some(text) if i == form.selected => format!("{}\u{2588}", text),
+                _ => form.value(*field),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<28}", field.label()), Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled(value, Style::default().fg(theme::TEXT_HIGHLIGHT)),
+            ]))
+        })
+        .collect();
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(form.selected));
+
+    let hint = if form.editing.is_some() {
+        " Enter save · Esc cancel "
+    } else {
+        " Space/Enter change · Esc close "
+    };
+    let mut block = Block::default()
+        .title(" Settings ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme::BORDER_ACTIVE))
+        .title_bottom(Line::from(Span::styled(hint, Style::default().fg(theme::TEXT_SECONDARY))));
+
+    if let Some(error) = &form.error {
+        block = block.title_bottom(Line::from(Span::styled(
+            format!(" {} ", error),
+            Style::default().fg(theme::STATUS_FAILED),
+        )));
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme::style_selected())
+        .highlight_symbol("\u{25b6} "); // ▶
+
+    f.render_stateful_widget(list, popup_area, &mut list_state);
+}
@@ -1,7 +1,7 @@
 use ratatui::prelude::*;
 use ratatui::widgets::Paragraph;
 
-use crate::app::{App, InputMode, ActivePanel, ActiveTab};
+use crate::app::{App, InputMode, ActivePanel, ActiveTab, PathPromptPurpose};
 use crate::ui::theme;
 
 fn tag_filter_spans(app: &App) -> Vec<Span<'_>> {
@@ -38,6 +38,275 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             };
             (left, right)
         }
+        InputMode::ScriptEditor => {
+            let is_new = app.script_editor.as_ref().map(|f| f.is_new()).unwrap_or(true);
+            let left = Line::from(vec![
+                Span::styled(
+                    if is_new { " New Script" } else { " Edit Script" },
+                    Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD),
+                ),
+            ]);
+            let editing = app.script_editor.as_ref().map(|f| f.editing).unwrap_or(false);
+            let right = if editing {
+                Line::from(vec![
+                    Span::styled("Esc/Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                    Span::raw(" Stop editing"),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("s", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                    Span::raw(" Save  "),
+                    Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                    Span::raw(" Cancel"),
+                ])
+            };
+            (left, right)
+        }
+        InputMode::ConfirmDelete => {
+            let left = Line::from(vec![
+                Span::styled(" Delete Script", Style::default().fg(theme::STATUS_FAILED).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("y", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Confirm  "),
+                Span::styled("n/Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::ConfirmRequirements => {
+            let left = Line::from(vec![
+                Span::styled(" Required Services", Style::default().fg(theme::STATUS_FAILED).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("y", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Start & run  "),
+                Span::styled("n/Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::OrganizePreview => {
+            let count = app.organize_preview.as_ref().map(|p| p.plan.changes.len()).unwrap_or(0);
+            let left = Line::from(vec![
+                Span::styled(" Organize", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" {} script(s)", count), Style::default().fg(theme::TEXT_SECONDARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("j/k", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Scroll  "),
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Apply  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::ScanResults => {
+            let picked = app.scan_results.as_ref().map(|r| r.picked.iter().filter(|&&p| p).count()).unwrap_or(0);
+            let left = Line::from(vec![
+                Span::styled(" Scan", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" {} picked", picked), Style::default().fg(theme::TEXT_SECONDARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("j/k", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Move  "),
+                Span::styled("Space", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Pick  "),
+                Span::styled("a", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" All  "),
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Import  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::BindSlot => {
+            let name = app
+                .slot_binding
+                .as_ref()
+                .and_then(|id| app.scripts.iter().find(|s| &s.id == id))
+                .map(|s| s.name.as_str())
+                .unwrap_or_default();
+            let left = Line::from(vec![
+                Span::styled(" Bind", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" {} to a quick slot", name), Style::default().fg(theme::TEXT_SECONDARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("1-9", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Slot  "),
+                Span::styled("x", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Unbind  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::RunHistory => {
+            let count = app.run_history.as_ref().map(|h| h.records.len()).unwrap_or(0);
+            let left = Line::from(vec![
+                Span::styled(" History", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(" {} run(s)", count), Style::default().fg(theme::TEXT_SECONDARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("m", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Mark  "),
+                Span::styled("=", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Compare with marked  "),
+                Span::styled("Tab", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Artifact  "),
+                Span::styled("o/O", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Open/Reveal  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Close"),
+            ]);
+            (left, right)
+        }
+        InputMode::RunCompare => {
+            let left = Line::from(vec![
+                Span::styled(" Compare", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("j/k PgUp/PgDn", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Scroll  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Back"),
+            ]);
+            (left, right)
+        }
+        InputMode::Bookmarks => {
+            let left = Line::from(vec![
+                Span::styled(" Bookmarks", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Jump  "),
+                Span::styled("d", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Delete  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Close"),
+            ]);
+            (left, right)
+        }
+        InputMode::Settings => {
+            let left = Line::from(vec![
+                Span::styled(" Settings", Style::default().fg(theme::TEXT_HIGHLIGHT).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("j/k", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Select  "),
+                Span::styled("Space/Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Change  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Close"),
+            ]);
+            (left, right)
+        }
+        InputMode::FolderJump => {
+            let query = app.folder_jump.as_ref().map(|s| s.query.as_str()).unwrap_or("");
+            let left = Line::from(vec![
+                Span::styled(" Folder: ", Style::default().fg(theme::SEARCH_MATCH).add_modifier(Modifier::BOLD)),
+                Span::styled(query, Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled("\u{2588}", Style::default().fg(theme::TEXT_PRIMARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("↑/↓", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Navigate  "),
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Jump  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::ServiceEditor => {
+            let left = Line::from(vec![
+                Span::styled(" Edit", Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Save  "),
+                Span::styled("Shift+Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Save & Restart  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::PathPrompt => {
+            let (title, confirm) = match &app.path_prompt {
+                Some(p) if p.purpose == PathPromptPurpose::Export => (" Export", " Export  "),
+                Some(p) if p.preview.is_some() => (" Import", " Import  "),
+                _ => (" Import", " Preview  "),
+            };
+            let left = Line::from(vec![
+                Span::styled(title, Style::default().fg(theme::TEXT_PRIMARY).add_modifier(Modifier::BOLD)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("Tab", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Complete  "),
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(confirm),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Cancel"),
+            ]);
+            (left, right)
+        }
+        InputMode::OutputSearch => {
+            let search = app.output_search.as_ref();
+            let query = search.map(|s| s.query.as_str()).unwrap_or("");
+            let regex = search.is_some_and(|s| s.regex);
+            let mut left_spans = vec![
+                Span::styled(" Log /", Style::default().fg(theme::SEARCH_MATCH).add_modifier(Modifier::BOLD)),
+                Span::styled(query, Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled("\u{2588}", Style::default().fg(theme::TEXT_PRIMARY)),
+            ];
+            if let Some(error) = search.and_then(|s| s.error.as_deref()) {
+                left_spans.push(Span::styled(format!("  {}", error), Style::default().fg(theme::STATUS_FAILED)));
+            } else if !query.is_empty() {
+                let count = app.output_search_matches().len();
+                left_spans.push(Span::styled(
+                    format!("  {} match{}", count, if count == 1 { "" } else { "es" }),
+                    Style::default().fg(theme::TEXT_SECONDARY),
+                ));
+            }
+            let right = Line::from(vec![
+                Span::styled("C-r", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(if regex { " Regex: on  " } else { " Regex: off  " }),
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Keep  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Clear"),
+            ]);
+            (Line::from(left_spans), right)
+        }
+        InputMode::StdinInput => {
+            // Opened by a prompt: say what's asked
+            let prompt = app
+                .active_script_id
+                .as_ref()
+                .and_then(|id| app.runtimes.get(id))
+                .and_then(|r| r.awaiting_input.as_deref());
+            let label = match prompt {
+                Some(prompt) => format!(" Waiting for input ({})> ", prompt),
+                None => " Input> ".to_string(),
+            };
+            let left = Line::from(vec![
+                Span::styled(label, Style::default().fg(theme::SEARCH_MATCH).add_modifier(Modifier::BOLD)),
+                Span::styled(app.stdin_input.as_str(), Style::default().fg(theme::TEXT_PRIMARY)),
+                Span::styled("\u{2588}", Style::default().fg(theme::TEXT_PRIMARY)),
+            ]);
+            let right = Line::from(vec![
+                Span::styled("Enter", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Send  "),
+                Span::styled("C-d", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" End input  "),
+                Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                Span::raw(" Done"),
+            ]);
+            (left, right)
+        }
         InputMode::Search => {
             let query = match app.active_tab {
                 ActiveTab::Scripts => &app.search_query,
@@ -87,11 +356,7 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
             match app.active_tab {
                 ActiveTab::Scripts => {
                     let script_count = app.filtered_indices.len();
-                    let running_count = app
-                        .runtimes
-                        .values()
-                        .filter(|r| r.status == cortx_core::models::ScriptStatus::Running)
-                        .count();
+                    let running_count = app.running_counts.global_scripts;
 
                     let hints = match app.active_panel {
                         ActivePanel::ScriptList => {
@@ -108,6 +373,8 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                                 Span::raw(" Quick  "),
                                 Span::styled("s", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                                 Span::raw(" Stop  "),
+                                Span::styled("a/e/d", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                                Span::raw(" Add/Edit/Del  "),
                                 Span::styled("Tab", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                                 Span::raw(" Output  "),
                                 Span::styled("?", Style::default().fg(theme::TEXT_HIGHLIGHT)),
@@ -118,6 +385,15 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                                 hints.push(Span::styled("Esc", Style::default().fg(theme::TEXT_HIGHLIGHT)));
                                 hints.push(Span::raw(" Clear"));
                             }
+                            if let Some(evicted) = app.selected_evicted_lines() {
+                                hints.push(Span::raw("  "));
+                                hints.push(Span::styled("L", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+                                hints.push(Span::raw(" Restore logs "));
+                                hints.push(Span::styled(
+                                    format!("({} lines evicted)", evicted),
+                                    Style::default().fg(theme::TEXT_MUTED),
+                                ));
+                            }
                             hints
                         },
                         ActivePanel::Output => vec![
@@ -125,6 +401,10 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                             Span::raw(" Clear  "),
                             Span::styled("f", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                             Span::raw(" Auto-scroll  "),
+                            Span::styled("t", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                            Span::raw(" Timestamps  "),
+                            Span::styled("w", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                            Span::raw(" Wrap  "),
                             Span::styled("j/k", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                             Span::raw(" Scroll  "),
                             Span::styled("Tab", Style::default().fg(theme::TEXT_HIGHLIGHT)),
@@ -144,6 +424,26 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                         ),
                     ];
                     right_spans.extend(tag_filter_spans(app));
+                    if app.pin_running {
+                        right_spans.push(Span::styled("  pin:on", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+                    } else {
+                        right_spans.push(Span::styled("  pin:off", Style::default().fg(theme::TEXT_MUTED)));
+                    }
+                    if app.show_timestamps {
+                        right_spans.push(Span::styled("  ts:on", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+                    } else {
+                        right_spans.push(Span::styled("  ts:off", Style::default().fg(theme::TEXT_MUTED)));
+                    }
+                    if app.wrap_output {
+                        right_spans.push(Span::styled("  wrap:on", Style::default().fg(theme::TEXT_HIGHLIGHT)));
+                    } else if app.output_hscroll > 0 {
+                        right_spans.push(Span::styled(
+                            format!("  wrap:off +{}", app.output_hscroll),
+                            Style::default().fg(theme::TEXT_MUTED),
+                        ));
+                    } else {
+                        right_spans.push(Span::styled("  wrap:off", Style::default().fg(theme::TEXT_MUTED)));
+                    }
                     if running_count > 0 {
                         right_spans.push(Span::styled(
                             format!("  {} running ", running_count),
@@ -265,12 +565,7 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                         let running_count = app
                             .viewing_project_services()
                             .iter()
-                            .filter(|s| {
-                                app.service_runtimes
-                                    .get(&s.id)
-                                    .map(|r| r.status == cortx_core::models::ServiceStatus::Running)
-                                    .unwrap_or(false)
-                            })
+                            .filter(|s| app.service_status(&s.id).is_up())
                             .count();
 
                         let hints = match app.active_panel {
@@ -283,6 +578,8 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                                 Span::raw(" Stop  "),
                                 Span::styled("A/S", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                                 Span::raw(" Start/Stop All  "),
+                                Span::styled("e", Style::default().fg(theme::TEXT_HIGHLIGHT)),
+                                Span::raw(" Edit  "),
                                 Span::styled("Tab", Style::default().fg(theme::TEXT_HIGHLIGHT)),
                                 Span::raw(" Output  "),
                                 Span::styled("o", Style::default().fg(theme::TEXT_HIGHLIGHT)),
@@ -351,7 +648,15 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
                             ),
                         ];
                         right_spans.extend(tag_filter_spans(app));
-                        right_spans.push(Span::raw(" "));
+                        let running_services = app.running_counts.services;
+                        if running_services > 0 {
+                            right_spans.push(Span::styled(
+                                format!("  {} service{} running ", running_services, if running_services == 1 { "" } else { "s" }),
+                                Style::default().fg(theme::STATUS_RUNNING),
+                            ));
+                        } else {
+                            right_spans.push(Span::raw(" "));
+                        }
                         let right = Line::from(right_spans);
                         (left, right)
                     }
@@ -360,6 +665,27 @@ pub fn render(f: &mut Frame, area: Rect, app: &App) {
         }
     };
 
+    // Unsaved data stays visible until it's written
+    let mut right_text = right_text;
+    if !app.pending_saves.is_empty() {
+        let files: Vec<&str> = app.pending_saves.iter().map(|p| p.file.label()).collect();
+        right_text.spans.insert(
+            0,
+            Span::styled(
+                format!("\u{26a0} failed to save {} (C-s retry)  ", files.join(", ")),
+                Style::default().fg(theme::STATUS_FAILED).add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+
+    // A pending one-shot message replaces the key hints
+    let left_text = match app.status_message {
+        Some(ref msg) => Line::from(vec![
+            Span::styled(format!(" {}", msg), Style::default().fg(theme::TEXT_HIGHLIGHT)),
+        ]),
+        None => left_text,
+    };
+
     // Split area: left side for hints, right side for stats
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -1,5 +1,7 @@
 use ratatui::style::{Color, Modifier, Style};
 
+use crate::color;
+
 // Border colors
 pub const BORDER_ACTIVE: Color = Color::Cyan;
 pub const BORDER_INACTIVE: Color = Color::DarkGray;
@@ -9,6 +11,7 @@ pub const BORDER_INACTIVE: Color = Color::DarkGray;
 pub const STATUS_RUNNING: Color = Color::Green;
 pub const STATUS_COMPLETED: Color = Color::Blue;
 pub const STATUS_FAILED: Color = Color::Red;
+pub const STATUS_UNHEALTHY: Color = Color::Yellow;
 pub const STATUS_IDLE: Color = Color::DarkGray;
 
 // Text colors. Color::Reset adopts the terminal's default foreground, so the
@@ -22,6 +25,7 @@ pub const TEXT_HIGHLIGHT: Color = Color::Yellow;
 // Log colors
 pub const LOG_STDOUT: Color = Color::Reset;
 pub const LOG_STDERR: Color = Color::Red;
+pub const LOG_COMMAND: Color = Color::Cyan;
 
 // Search
 pub const SEARCH_MATCH: Color = Color::Yellow;
@@ -30,19 +34,10 @@ pub const SEARCH_MATCH: Color = Color::Yellow;
 pub const TAG_COLOR: Color = Color::Cyan;
 pub const SEPARATOR_COLOR: Color = Color::DarkGray;
 
-/// Parse a hex color string like "#3b82f6" into a ratatui Color
+/// Parse a hex color string like "#3b82f6" into a ratatui Color, the
+/// nearest one the terminal can show when it lacks truecolor
 pub fn color_from_hex(hex: &str) -> Option<Color> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() == 6 {
-        if let (Ok(r), Ok(g), Ok(b)) = (
-            u8::from_str_radix(&hex[0..2], 16),
-            u8::from_str_radix(&hex[2..4], 16),
-            u8::from_str_radix(&hex[4..6], 16),
-        ) {
-            return Some(Color::Rgb(r, g, b));
-        }
-    }
-    None
+    color::parse_hex(hex).map(|rgb| color::tui_color(color::tui_level(), rgb))
 }
 
 /// Resolve the color for a tag name from tag definitions, falling back to TAG_COLOR
@@ -1,5 +1,14 @@
 use std::path::Path;
 
+use cortx_core::command_builder::{self, QuoteStyle};
+use cortx_core::models::{GlobalScript, TagDefinition};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthChar;
+
+/// ANSI codes around a `--grep` match: bold yellow, then reset
+const MATCH_ON: &str = "\x1b[1;33m";
+const MATCH_OFF: &str = "\x1b[0m";
+
 /// Format a command for display: replace {{SCRIPT_FILE}} with {{SCRIPT_FILE:filename}}
 pub fn format_command_display(command: &str, script_path: Option<&str>) -> String {
     match script_path {
@@ -13,3 +22,275 @@ pub fn format_command_display(command: &str, script_path: Option<&str>) -> Strin
         _ => command.to_string(),
     }
 }
+
+/// One-line form of a built `(program, args)` command to paste into a shell.
+/// Every word is quoted for the platform's shell where needed, so it sees
+/// the same arguments the script gets (`$VAR` in a value stays literal).
+pub fn command_line(program: &str, args: &[String]) -> String {
+    command_builder::shell_join(program, args, QuoteStyle::native())
+}
+
+/// Remove ANSI escape sequences (colors, cursor moves) from a log line.
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'[') {
+            // CSI: parameters run until a final byte in '@'..='~'
+            chars.next();
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            chars.next();
+        }
+    }
+    out
+}
+
+/// Byte ranges of the case-insensitive, non-overlapping matches of `pattern`
+/// in `text`.
+pub fn match_ranges(text: &str, pattern: &str) -> Vec<(usize, usize)> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    let mut ranges = Vec::new();
+    let mut next_free = 0;
+    for (start, _) in text.char_indices() {
+        if start < next_free {
+            continue;
+        }
+        let mut chars = text[start..].char_indices();
+        let mut end = start;
+        let matched = pattern.iter().all(|p| match chars.next() {
+            Some((offset, c)) if c.to_lowercase().eq(p.to_lowercase()) => {
+                end = start + offset + c.len_utf8();
+                true
+            }
+            _ => false,
+        });
+        if matched {
+            ranges.push((start, end));
+            next_free = end;
+        }
+    }
+    ranges
+}
+
+/// Whether `cortx script list --grep` keeps `script`: the pattern occurs in
+/// its name, command, a tag or its description, ignoring case.
+pub fn script_matches_grep(script: &GlobalScript, pattern: &str) -> bool {
+    let fields = [script.name.as_str(), script.command.as_str(), script.description.as_deref().unwrap_or("")];
+    fields
+        .into_iter()
+        .chain(script.tags.iter().map(String::as_str))
+        .any(|field| !match_ranges(field, pattern).is_empty())
+}
+
+/// Order of `cortx script list`: by primary tag (tag definition order, then
+/// name; untagged first), then by name, ignoring case.
+pub fn sort_script_list(scripts: &mut [&GlobalScript], tag_defs: &[TagDefinition]) {
+    let tag_order = |tag: &str| {
+        let tag = tag.to_lowercase();
+        tag_defs.iter().find(|d| d.name.to_lowercase() == tag).and_then(|d| d.order)
+    };
+    scripts.sort_by(|a, b| {
+        let tag_ord = match (a.tags.first(), b.tags.first()) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => std::cmp::Ordering::Less,
+            (Some(_), None) => std::cmp::Ordering::Greater,
+            (Some(at), Some(bt)) => {
+                match (tag_order(at), tag_order(bt)) {
+                    (Some(ao), Some(bo)) => ao.cmp(&bo),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                }
+                .then_with(|| at.to_lowercase().cmp(&bt.to_lowercase()))
+            }
+        };
+        tag_ord.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+/// The page of `items` that `--offset` / `--limit` select, and how many
+/// items there were before paging. No limit keeps everything after `offset`.
+pub fn paginate<T>(items: Vec<T>, offset: usize, limit: Option<usize>) -> (Vec<T>, usize) {
+    let total = items.len();
+    let page = items.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)).collect();
+    (page, total)
+}
+
+/// `text` with every match of `pattern` highlighted, or unchanged when color
+/// is off.
+pub fn highlight_matches(text: &str, pattern: &str, colorize: bool) -> String {
+    if !colorize {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in match_ranges(text, pattern) {
+        out.push_str(&text[last..start]);
+        out.push_str(MATCH_ON);
+        out.push_str(&text[start..end]);
+        out.push_str(MATCH_OFF);
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Pasted text for a single-line field: trailing line breaks dropped, inner
+/// ones (and tabs) turned into spaces.
+pub fn single_line(text: &str) -> String {
+    text.trim_end_matches(['\r', '\n'])
+        .split("\r\n")
+        .flat_map(|part| part.split(['\r', '\n']))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('\t', " ")
+}
+
+/// Run duration for display: `850ms` below a second, then `format_uptime`'s
+/// compact form (`12s`, `3m05s`, ...).
+pub fn format_duration_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        cortx_core::runtime_state::format_uptime(std::time::Duration::from_millis(ms))
+    }
+}
+
+/// Split `line` into rows at most `width` columns wide. Breaks fall
+/// anywhere, not between words: stack traces and minified output have few
+/// spaces to break at. Each piece keeps its style.
+pub fn wrap_line(line: Line<'static>, width: usize) -> Vec<Line<'static>> {
+    if width == 0 || line.width() <= width {
+        return vec![line];
+    }
+    let mut rows = Vec::new();
+    let mut row: Vec<Span<'static>> = Vec::new();
+    let mut used = 0;
+    for span in line.spans {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w > width && used > 0 {
+                if !text.is_empty() {
+                    row.push(Span::styled(std::mem::take(&mut text), span.style));
+                }
+                rows.push(Line::from(std::mem::take(&mut row)).style(line.style));
+                used = 0;
+            }
+            text.push(c);
+            used += w;
+        }
+        if !text.is_empty() {
+            row.push(Span::styled(text, span.style));
+        }
+    }
+    rows.push(Line::from(row).style(line.style));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(name: &str, command: &str, tags: &[&str], description: Option<&str>) -> GlobalScript {
+        let mut s = GlobalScript::new(name.to_string(), command.to_string(), None);
+        s.tags = tags.iter().map(|t| t.to_string()).collect();
+        s.description = description.map(str::to_string);
+        s
+    }
+
+    #[test]
+    fn grep_matches_any_field_ignoring_case() {
+        let s = script("Deploy-API", "kubectl apply -f api.yaml", &["Infra"], Some("Ships the API to Staging"));
+        assert!(script_matches_grep(&s, "deploy"));
+        assert!(script_matches_grep(&s, "KUBECTL"));
+        assert!(script_matches_grep(&s, "infra"));
+        assert!(script_matches_grep(&s, "staging"));
+        assert!(!script_matches_grep(&s, "prod"));
+    }
+
+    #[test]
+    fn limit_and_offset_page_the_sorted_list() {
+        let scripts: Vec<GlobalScript> = [("b", &["ops"][..]), ("a", &[]), ("c", &["dev"]), ("d", &["ops"]), ("e", &[])]
+            .iter()
+            .map(|(name, tags)| script(name, "true", tags, None))
+            .collect();
+        let tag_defs = vec![
+            TagDefinition { name: "ops".into(), color: None, order: Some(0) },
+            TagDefinition { name: "dev".into(), color: None, order: Some(1) },
+        ];
+        let mut sorted: Vec<&GlobalScript> = scripts.iter().collect();
+        sort_script_list(&mut sorted, &tag_defs);
+        let names = |page: &[&GlobalScript]| page.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&sorted), vec!["a", "e", "b", "d", "c"]);
+
+        let (page, total) = paginate(sorted.clone(), 1, Some(2));
+        assert_eq!((names(&page), total), (vec!["e".to_string(), "b".to_string()], 5));
+        let (page, total) = paginate(sorted.clone(), 3, None);
+        assert_eq!((names(&page), total), (vec!["d".to_string(), "c".to_string()], 5));
+        let (page, total) = paginate(sorted.clone(), 0, Some(10));
+        assert_eq!((page.len(), total), (5, 5));
+        let (page, total) = paginate(sorted, 7, Some(2));
+        assert_eq!((page.len(), total), (0, 5));
+    }
+
+    #[test]
+    fn highlights_each_match() {
+        assert_eq!(match_ranges("aXa xa", "xA"), vec![(1, 3), (4, 6)]);
+        assert_eq!(match_ranges("ÄÖ-äö", "äö"), vec![(0, 4), (5, 9)]);
+        assert_eq!(
+            highlight_matches("run API api", "api", true),
+            "run \x1b[1;33mAPI\x1b[0m \x1b[1;33mapi\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn no_highlight_without_color() {
+        assert_eq!(highlight_matches("run API api", "api", false), "run API api");
+    }
+
+    #[test]
+    fn formats_run_durations() {
+        assert_eq!(format_duration_ms(850), "850ms");
+        assert_eq!(format_duration_ms(12_400), "12s");
+        assert_eq!(format_duration_ms(185_000), "3m05s");
+    }
+
+    #[test]
+    fn pasted_text_is_made_single_line() {
+        assert_eq!(single_line("sk-abc123\n"), "sk-abc123");
+        assert_eq!(single_line("--env prod\r\n--dry-run\r\n"), "--env prod --dry-run");
+        assert_eq!(single_line("a\tb\nc"), "a b c");
+    }
+
+    #[test]
+    fn wraps_long_lines_into_rows_keeping_styles() {
+        use ratatui::style::{Color, Style};
+        let red = Style::default().fg(Color::Red);
+        let line = Line::from(vec![Span::raw("abcde"), Span::styled("fghij", red)]);
+        let rows = wrap_line(line, 4);
+        let text: Vec<String> = rows.iter().map(|r| r.to_string()).collect();
+        assert_eq!(text, vec!["abcd", "efgh", "ij"]);
+        // The row across the two spans has one piece of each
+        assert_eq!(rows[1].spans[0].style, Style::default());
+        assert_eq!(rows[1].spans[1].style, red);
+
+        // Wide characters aren't split across rows
+        let rows = wrap_line(Line::raw("ab日本"), 3);
+        assert_eq!(rows.iter().map(|r| r.to_string()).collect::<Vec<_>>(), vec!["ab", "日", "本"]);
+
+        assert_eq!(wrap_line(Line::raw("short"), 10).len(), 1);
+    }
+}
@@ -1,24 +1,51 @@
 use crate::models::{
-    AddEnvFileInput, App, AppSettings, CreateAppInput, CreateGlobalScriptInput,
-    CreateProjectInput, CreateScriptInput, CreateServiceInput,
+    AddEnvFileInput, App, AppSettings, BackupInfo, CreateAppInput, CreateGlobalScriptInput,
+    CreateProjectInput, CreateProjectVariantInput, CreateScriptInput, CreateServiceInput,
     CreateShellAliasInput, CreateStatusDefinitionInput, CreateToolInput, CreateTagDefinitionInput,
-    DiscoverEnvFilesInput, DiscoveredTool, EnvComparison, EnvFile, EnvFileVariant, EnvVariable,
-    DiscoveredScript, ExecutionRecord, ExportSummary, GlobalScript, ImportOptions, ImportResult,
-    LinkEnvToServiceInput, Project, Script,
-    ScriptParameter, ScriptsConfig, Service, ShellAlias, StatusDefinition, TagDefinition, Tool,
+    DetectedHelp, DiscoverEnvFilesInput, DiscoveredImport, EnvDiscoveryComplete, DiscoveredTool, EnvComparison, EnvFile, EnvFileVariant, EnvMatrix,
+    ExecutionRecord, ExportSummary, GlobalScript, ImportFilePreview, ImportOptions, ImportResult,
+    LinkEnvToServiceInput, MigrationReport, PathMapping, PendingSave, Project, ProjectImportResult, ProjectVariant, RestartResult, RestoreResult, Script,
+    SavedProject, ScriptDependency, ScriptParameter, ScriptNodeResult, ScriptScanResult, ScriptsConfig, Service, ServiceCircuit, ServiceGroupResult, ServiceHealth, ShellAlias, ShortcutAction, ShortcutWarning, StartWait, StatusDefinition, TagDefinition,
+    Tool,
     UpdateAppInput, UpdateTagDefinitionInput, UpdateGlobalScriptInput, UpdateProjectInput,
     UpdateScriptInput, UpdateServiceInput, UpdateShellAliasInput,
-    UpdateStatusDefinitionInput, UpdateToolInput,
+    UpdateStatusDefinitionInput, UpdateToolInput, VariantSwitch,
 };
-use crate::process_manager::{ProcessEventEmitter, ProcessManager};
+use crate::process_manager::{ProcessEventEmitter, ProcessKind, ProcessManager, ServiceLaunch};
 use crate::storage::Storage;
-use crate::tauri_emitter::TauriEmitter;
+use crate::tauri_emitter::{ServiceHistoryEmitter, TauriEmitter};
+use cortx_core::command_builder::{self, plan_replay, ReplayError};
+use cortx_core::env_discovery::{self, find_matching_service, DiscoveryOutcome, EnvDiscovery};
+use cortx_core::global_shortcuts::ShortcutRegistry;
+use cortx_core::help_parser::{HelpDetection, HelpDetectionOutcome};
+use cortx_core::export_file;
+use cortx_core::env_file::{
+    apply_refresh, detect_variant, edit_env_file, env_matrix, mask_content, mask_matrix, mask_variables, parse_env_file,
+    parse_env_file_lenient, scaffold_env_file, service_env_vars, set_variant, EnvEdit,
+};
+use cortx_core::organize_rules::OrganizeResult;
+use cortx_core::param_fill;
+use cortx_core::run_compare::{self, RunComparison};
+use cortx_core::runtime_state::{self, RunningCounts, RunningProcess};
+use cortx_core::script_artifacts;
+use cortx_core::script_dag::{self, ScriptExitWaiter};
+use cortx_core::script_location;
+use cortx_core::script_discovery::ScanBudget;
+use cortx_core::script_relocation::content_hash;
+use cortx_core::script_requirements::{self, RequirementNotMet, RequirementStatus};
+use cortx_core::script_sync::{SyncPlan, SyncResolution, SyncResult};
+use cortx_core::script_validation::{self, ValidationIssue};
+use cortx_core::service_log::{ServiceLogFile, ServiceLogPage};
+use cortx_core::service_order;
+use cortx_core::service_health::HealthCheck;
+use cortx_core::service_idle::IdleTimeout;
+use cortx_core::service_restart::AutoRestart;
+use cortx_core::text_file;
+use cortx_core::service_watch::ServiceWatch;
 use chrono::Utc;
-use std::fs;
 use std::path::Path;
 use std::sync::Arc;
-use tauri::{AppHandle, State};
-use walkdir::WalkDir;
+use tauri::{AppHandle, Emitter, State};
 
 pub struct AppState {
     pub storage: Arc<Storage>,
@@ -26,6 +53,15 @@ pub struct AppState {
     /// Set to true to opt out of "close = hide-to-tray" and run the real
     /// quit cleanup flow when the next CloseRequested event fires.
     pub quitting: Arc<std::sync::atomic::AtomicBool>,
+    /// Env file discoveries still walking, by id, so they can be cancelled
+    pub env_discoveries: Arc<std::sync::Mutex<std::collections::HashMap<String, Arc<EnvDiscovery>>>>,
+    /// `--help` parameter detections still running, by the frontend's token,
+    /// so they can be cancelled
+    pub help_detections: Arc<std::sync::Mutex<std::collections::HashMap<String, Arc<HelpDetection>>>>,
+    /// OS-wide shortcuts registered from the settings, see `apply_global_shortcuts`
+    pub shortcuts: Arc<std::sync::Mutex<ShortcutRegistry>>,
+    /// Why some of them aren't registered, as of the last update
+    pub shortcut_warnings: Arc<std::sync::Mutex<Vec<ShortcutWarning>>>,
 }
 
 // Project commands
@@ -43,8 +79,9 @@ pub fn get_project(state: State<AppState>, id: String) -> Result<Project, String
         .ok_or_else(|| format!("Project not found: {}", id))
 }
 
+/// The project, plus the projects its root is inside of or around
 #[tauri::command]
-pub fn create_project(state: State<AppState>, input: CreateProjectInput) -> Result<Project, String> {
+pub fn create_project(state: State<AppState>, input: CreateProjectInput) -> Result<SavedProject, String> {
     // Validate path exists
     if !Path::new(&input.root_path).exists() {
         return Err(format!("Path does not exist: {}", input.root_path));
@@ -59,7 +96,7 @@ pub fn create_project(state: State<AppState>, input: CreateProjectInput) -> Resu
 
     state
         .storage
-        .create_project(project)
+        .create_project_checked(project, input.allow_duplicate)
         .map_err(|e| e.to_string())
 }
 
@@ -68,7 +105,7 @@ pub fn update_project(
     state: State<AppState>,
     id: String,
     input: UpdateProjectInput,
-) -> Result<Project, String> {
+) -> Result<SavedProject, String> {
     // Validate path if provided
     if let Some(ref path) = input.root_path {
         if !Path::new(path).exists() {
@@ -78,7 +115,7 @@ pub fn update_project(
 
     state
         .storage
-        .update_project(&id, |project| {
+        .update_project_checked(&id, input.allow_duplicate, |project| {
             if let Some(name) = input.name {
                 project.name = name;
             }
@@ -107,7 +144,7 @@ pub fn update_project(
 #[tauri::command]
 pub fn delete_project(app_handle: AppHandle, state: State<AppState>, id: String) -> Result<(), String> {
     // Stop any running services for this project
-    let emitter = TauriEmitter::new(app_handle);
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
     if let Some(project) = state.storage.get_project(&id) {
         for service in &project.services {
             let _ = state.process_manager.stop_service(&emitter, &service.id);
@@ -128,6 +165,59 @@ pub fn update_project_last_opened(state: State<AppState>, id: String) -> Result<
     Ok(())
 }
 
+// Project variant commands
+
+#[tauri::command]
+pub fn add_project_variant(
+    state: State<AppState>,
+    project_id: String,
+    input: CreateProjectVariantInput,
+) -> Result<Project, String> {
+    if !Path::new(&input.root_path).exists() {
+        return Err(format!("Path does not exist: {}", input.root_path));
+    }
+    let mut variant = ProjectVariant::new(input.name, input.root_path);
+    variant.env_var_overrides = input.env_var_overrides.unwrap_or_default();
+
+    state
+        .storage
+        .add_project_variant(&project_id, variant)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn remove_project_variant(
+    state: State<AppState>,
+    project_id: String,
+    variant_id: String,
+) -> Result<Project, String> {
+    state
+        .storage
+        .remove_project_variant(&project_id, &variant_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Switch the variant services and scripts start under (`None`: the
+/// project's own root). Running services keep running; the result lists
+/// those started under another variant, so the GUI can warn.
+#[tauri::command]
+pub fn set_active_variant(
+    state: State<AppState>,
+    project_id: String,
+    variant_id: Option<String>,
+) -> Result<VariantSwitch, String> {
+    let project = state
+        .storage
+        .set_active_variant(&project_id, variant_id.as_deref())
+        .map_err(|e| e.to_string())?;
+    let running = state.process_manager.list_running();
+    let still_running = runtime_state::running_under_other_variant(&project, &running)
+        .into_iter()
+        .map(|p| p.target_id.clone())
+        .collect();
+    Ok(VariantSwitch { project, still_running })
+}
+
 // Service commands
 
 #[tauri::command]
@@ -144,7 +234,18 @@ pub fn add_service(
     service.default_arg_preset = input.default_arg_preset;
     service.color = input.color;
     service.port = input.port;
+    service.health_check = input.health_check;
     service.env_vars = input.env_vars;
+    service.env_mode = input.env_mode.unwrap_or_default();
+    service.watch_paths = input.watch_paths.unwrap_or_default();
+    service.watch_debounce_ms = input.watch_debounce_ms;
+    service.idle_timeout_secs = input.idle_timeout_secs;
+    service.idle_action = input.idle_action.unwrap_or_default();
+    service.restart_policy = input.restart_policy.unwrap_or_default();
+    service.restart_backoff_ms = input.restart_backoff_ms;
+    service.restart_circuit = input.restart_circuit;
+    service.stop_grace_seconds = input.stop_grace_seconds;
+    service.depends_on = input.depends_on.unwrap_or_default();
 
     // Set order to be last
     if let Some(project) = state.storage.get_project(&project_id) {
@@ -165,36 +266,57 @@ pub fn update_service(
 ) -> Result<Service, String> {
     state
         .storage
-        .update_service(&service_id, |service| {
-            if let Some(name) = input.name {
-                service.name = name;
-            }
-            if let Some(working_dir) = input.working_dir {
-                service.working_dir = working_dir;
-            }
-            if let Some(command) = input.command {
-                service.command = command;
-            }
-            // Always update modes and default_mode to allow clearing them
-            // The frontend sends these fields on every update
-            service.modes = input.modes;
-            service.default_mode = input.default_mode;
-            // Same for arg presets - always update to allow clearing
-            service.extra_args = input.extra_args;
-            service.arg_presets = input.arg_presets;
-            service.default_arg_preset = input.default_arg_preset;
-            // Always assign so the user can clear these fields by submitting them empty.
-            service.color = input.color;
-            service.port = input.port;
-            service.env_vars = input.env_vars;
-        })
+        .update_service(&service_id, |service| input.apply_to(service))
         .map_err(|e| e.to_string())
 }
 
+/// Save a service and, if it's running, restart it with the saved
+/// definition, re-read from storage, in the mode / arg preset it ran with
+/// (when the service still has them). Async: the restart waits up to its
+/// stop grace period for the old run to exit.
+#[tauri::command]
+pub async fn update_and_restart_service(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    service_id: String,
+    input: UpdateServiceInput,
+) -> Result<RestartResult, String> {
+    let storage = state.storage.clone();
+    let process_manager = state.process_manager.clone();
+    let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(ServiceHistoryEmitter::new(app_handle, storage.clone()));
+    tauri::async_runtime::spawn_blocking(move || {
+        process_manager.update_and_restart_service(
+            emitter,
+            &service_id,
+            || {
+                storage
+                    .update_service(&service_id, |service| input.apply_to(service))
+                    .map_err(|e| e.to_string())
+            },
+            |_| {
+                let (project, service) = storage
+                    .get_service(&service_id)
+                    .ok_or_else(|| format!("Service not found: {}", service_id))?;
+                let running = process_manager.runtime_store().get(&service_id);
+                let mode = running
+                    .as_ref()
+                    .and_then(|entry| entry.mode.clone())
+                    .filter(|mode| service.modes.as_ref().is_some_and(|modes| modes.contains_key(mode)));
+                let arg_preset = running
+                    .and_then(|entry| entry.arg_preset)
+                    .filter(|preset| service.arg_presets.as_ref().is_some_and(|presets| presets.contains_key(preset)));
+                resolve_service_launch(&project, service, mode, arg_preset)
+            },
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub fn delete_service(app_handle: AppHandle, state: State<AppState>, service_id: String) -> Result<(), String> {
     // Stop if running
-    let emitter = TauriEmitter::new(app_handle);
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
     let _ = state.process_manager.stop_service(&emitter, &service_id);
 
     state
@@ -236,6 +358,8 @@ pub fn add_script(
     script.script_path = input.script_path;
     script.color = input.color;
     script.linked_service_ids = input.linked_service_ids.unwrap_or_default();
+    script.mutex_group = input.mutex_group.filter(|g| !g.trim().is_empty());
+    script.queue_on_mutex = input.queue_on_mutex.unwrap_or(false);
 
     // Set order to be last
     if let Some(project) = state.storage.get_project(&project_id) {
@@ -278,6 +402,13 @@ pub fn update_script(
             if let Some(linked_service_ids) = input.linked_service_ids {
                 script.linked_service_ids = linked_service_ids;
             }
+            // An empty group leaves the script's group
+            if let Some(mutex_group) = input.mutex_group {
+                script.mutex_group = Some(mutex_group).filter(|g| !g.trim().is_empty());
+            }
+            if let Some(queue_on_mutex) = input.queue_on_mutex {
+                script.queue_on_mutex = queue_on_mutex;
+            }
         })
         .map_err(|e| e.to_string())
 }
@@ -323,12 +454,7 @@ pub fn get_launch_command(state: State<AppState>, service_id: String) -> Result<
         .get_service(&service_id)
         .ok_or_else(|| format!("Service not found: {}", service_id))?;
 
-    let working_dir = if service.working_dir.is_empty() || service.working_dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = Path::new(&project.root_path).join(&service.working_dir);
-        path.to_string_lossy().to_string()
-    };
+    let working_dir = project.resolve_dir(&service.working_dir);
 
     #[cfg(target_os = "windows")]
     {
@@ -356,12 +482,7 @@ pub async fn launch_external_terminal(
 
     let settings = state.storage.get_settings();
 
-    let working_dir = if service.working_dir.is_empty() || service.working_dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = Path::new(&project.root_path).join(&service.working_dir);
-        path.to_string_lossy().to_string()
-    };
+    let working_dir = project.resolve_dir(&service.working_dir);
 
     #[cfg(target_os = "windows")]
     {
@@ -624,25 +745,15 @@ fn urlencoding(s: &str) -> String {
         .collect()
 }
 
-#[tauri::command]
-pub fn start_integrated_service(
-    app_handle: AppHandle,
-    state: State<AppState>,
-    service_id: String,
+/// Resolve a service's working dir and final command (mode + extra args +
+/// arg preset) into a ready-to-spawn launch.
+fn resolve_service_launch(
+    project: &Project,
+    service: Service,
     mode: Option<String>,
     arg_preset: Option<String>,
-) -> Result<u32, String> {
-    let (project, service) = state
-        .storage
-        .get_service(&service_id)
-        .ok_or_else(|| format!("Service not found: {}", service_id))?;
-
-    let working_dir = if service.working_dir.is_empty() || service.working_dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = Path::new(&project.root_path).join(&service.working_dir);
-        path.to_string_lossy().to_string()
-    };
+) -> Result<ServiceLaunch, String> {
+    let working_dir = project.resolve_dir(&service.working_dir);
 
     // Resolve effective mode: explicit mode > default_mode > none
     let effective_mode = mode.or_else(|| service.default_mode.clone());
@@ -692,36 +803,317 @@ pub fn start_integrated_service(
         }
     }
 
-    let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(TauriEmitter::new(app_handle));
+    let watch = ServiceWatch::for_service(&service, &working_dir);
+    let idle = IdleTimeout::for_service(&service);
+    let health = HealthCheck::for_service(&service);
+    let restart = AutoRestart::for_service(&service);
+    let stop_grace = service.stop_grace_seconds.map(std::time::Duration::from_secs);
+    let env_vars = service_env_vars(project, &service);
+
+    Ok(ServiceLaunch {
+        meta: cortx_core::process_manager::RuntimeMeta::new(service.name.clone())
+            .with_project(project.id.clone(), project.name.clone())
+            .with_variant(project.active_variant_id.clone()),
+        service_id: service.id,
+        working_dir,
+        command: final_command,
+        env_vars,
+        env_mode: service.env_mode,
+        mode: effective_mode,
+        arg_preset: effective_arg_preset,
+        watch,
+        idle,
+        health,
+        port: service.port,
+        restart,
+        stop_grace,
+    })
+}
+
+#[tauri::command]
+pub fn start_integrated_service(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    service_id: String,
+    mode: Option<String>,
+    arg_preset: Option<String>,
+) -> Result<u32, String> {
+    let (project, service) = state
+        .storage
+        .get_service(&service_id)
+        .ok_or_else(|| format!("Service not found: {}", service_id))?;
+
+    let launch = resolve_service_launch(&project, service, mode, arg_preset)?;
+
+    let emitter: Arc<dyn ProcessEventEmitter> =
+        Arc::new(ServiceHistoryEmitter::new(app_handle, state.storage.clone()));
     state.process_manager.start_service(
         emitter,
-        service_id,
-        working_dir,
-        final_command,
-        service.env_vars,
-        effective_mode,
-        effective_arg_preset,
-        cortx_core::process_manager::RuntimeMeta::new(service.name.clone())
-            .with_project(project.id.clone(), project.name.clone()),
+        launch.service_id,
+        launch.working_dir,
+        launch.command,
+        launch.env_vars,
+        &launch.env_mode,
+        launch.mode,
+        launch.arg_preset,
+        launch.meta,
+        launch.watch,
+        launch.idle,
+        launch.health,
+        launch.restart,
+        launch.stop_grace,
     )
 }
 
+/// Start several services of a project with their default mode / preset.
+/// Sequential mode waits for each service to come up before starting the
+/// next one, and stops at the first failure. Async so the wait doesn't
+/// block the main thread.
 #[tauri::command]
-pub fn stop_integrated_service(app_handle: AppHandle, state: State<AppState>, service_id: String) -> Result<(), String> {
-    let emitter = TauriEmitter::new(app_handle);
-    state.process_manager.stop_service(&emitter, &service_id)
+pub async fn run_service_group(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    service_ids: Vec<String>,
+    sequential: bool,
+) -> Result<Vec<ServiceGroupResult>, String> {
+    let project = state
+        .storage
+        .get_project(&project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    // Resolve everything up front; services that can't be resolved are
+    // reported as failures and never spawned.
+    let mut launches = Vec::new();
+    let mut unresolved = Vec::new();
+    for service_id in &service_ids {
+        let resolved = match state.storage.get_service(service_id) {
+            Some((owner, service)) if owner.id == project.id => {
+                resolve_service_launch(&project, service, None, None)
+            }
+            Some(_) => Err(format!("Service {} does not belong to this project", service_id)),
+            None => Err(format!("Service not found: {}", service_id)),
+        };
+        match resolved {
+            Ok(launch) => launches.push(launch),
+            Err(e) => unresolved.push(ServiceGroupResult::failed(service_id.clone(), e)),
+        }
+    }
+
+    let emitter: Arc<dyn ProcessEventEmitter> =
+        Arc::new(ServiceHistoryEmitter::new(app_handle, state.storage.clone()));
+    let process_manager = state.process_manager.clone();
+    let started = tauri::async_runtime::spawn_blocking(move || {
+        process_manager.run_service_group(emitter, launches, sequential)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Report in the order requested
+    let mut by_id: std::collections::HashMap<String, ServiceGroupResult> = started
+        .into_iter()
+        .chain(unresolved)
+        .map(|r| (r.service_id.clone(), r))
+        .collect();
+    Ok(service_ids
+        .iter()
+        .filter_map(|id| by_id.remove(id))
+        .collect())
+}
+
+/// Start every service of a project, dependencies first (then by `order`),
+/// waiting as `wait` says after each (by default until it has stayed up for
+/// a moment). Already running services are skipped; after a failure the
+/// remaining services are not started. Async so the waits don't block the
+/// main thread.
+#[tauri::command]
+pub async fn start_all_services(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+    wait: Option<StartWait>,
+) -> Result<Vec<ServiceGroupResult>, String> {
+    let storage = state.storage.clone();
+    let process_manager = state.process_manager.clone();
+    let wait = wait.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        start_project_services(app_handle, &storage, &process_manager, &project_id, wait)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// [`start_all_services`] on the calling thread, which it blocks during the
+/// waits. Also what the StartWorkspace global shortcut runs.
+pub fn start_project_services(
+    app_handle: AppHandle,
+    storage: &Arc<Storage>,
+    process_manager: &Arc<ProcessManager>,
+    project_id: &str,
+    wait: StartWait,
+) -> Result<Vec<ServiceGroupResult>, String> {
+    let project = storage
+        .get_project(project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    let ordered = service_order::start_order(&project.services).map_err(|e| e.to_string())?;
+
+    // Services that can't be resolved are reported as failures and never
+    // spawned
+    let service_ids: Vec<String> = ordered.iter().map(|s| s.id.clone()).collect();
+    let mut launches = Vec::new();
+    let mut unresolved = Vec::new();
+    for service in ordered {
+        match resolve_service_launch(&project, service.clone(), None, None) {
+            Ok(launch) => launches.push(launch),
+            Err(e) => unresolved.push(ServiceGroupResult::failed(service.id.clone(), e)),
+        }
+    }
+
+    let emitter: Arc<dyn ProcessEventEmitter> =
+        Arc::new(ServiceHistoryEmitter::new(app_handle, storage.clone()));
+    let started = process_manager.start_services_in_order(emitter, launches, wait);
+
+    // Report in start order
+    let mut by_id: std::collections::HashMap<String, ServiceGroupResult> = started
+        .into_iter()
+        .chain(unresolved)
+        .map(|r| (r.service_id.clone(), r))
+        .collect();
+    Ok(service_ids
+        .iter()
+        .filter_map(|id| by_id.remove(id))
+        .collect())
+}
+
+/// Stop every running service of a project, in the reverse of the
+/// start-all order. Services that aren't running are reported as skipped.
+/// Async so their stop grace periods don't block the main thread.
+#[tauri::command]
+pub async fn stop_all_services(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<ServiceGroupResult>, String> {
+    let project = state
+        .storage
+        .get_project(&project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    let service_ids = service_order::stop_order(&project.services)
+        .into_iter()
+        .map(|s| s.id.clone())
+        .collect();
+
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
+    let process_manager = state.process_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || process_manager.stop_services_in_order(&emitter, service_ids))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Async: waits up to the service's stop grace period for it to exit
+#[tauri::command]
+pub async fn stop_integrated_service(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    service_id: String,
+) -> Result<(), String> {
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
+    let process_manager = state.process_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || process_manager.stop_service(&emitter, &service_id))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
 pub fn is_service_running(state: State<AppState>, service_id: String) -> bool {
-    state.process_manager.is_running(&service_id)
+    state.process_manager.is_service_running(&service_id)
 }
 
+/// Running service ids, in `list_running` order. Kept for older callers.
 #[tauri::command]
 pub fn get_running_services(state: State<AppState>) -> Vec<String> {
     state.process_manager.get_running_services()
 }
 
+/// Every running service and script, in a stable order
+#[tauri::command]
+pub fn list_running(state: State<AppState>) -> Vec<RunningProcess> {
+    state.process_manager.list_running()
+}
+
+/// Running services and scripts per kind, for badges
+#[tauri::command]
+pub fn count_running(state: State<AppState>) -> RunningCounts {
+    state.process_manager.count_running()
+}
+
+/// Seconds the service has been running, or None if it's stopped
+#[tauri::command]
+pub fn get_service_uptime(state: State<AppState>, service_id: String) -> Option<u64> {
+    state
+        .process_manager
+        .service_uptime(&service_id)
+        .map(|uptime| uptime.as_secs())
+}
+
+/// Latest health-check results of a running service, or None if it has no
+/// health check or isn't running
+#[tauri::command]
+pub fn get_service_health(state: State<AppState>, service_id: String) -> Option<ServiceHealth> {
+    state.process_manager.service_health(&service_id)
+}
+
+/// The open restart circuit of a service that crashed too often, if any
+#[tauri::command]
+pub fn get_service_circuit(state: State<AppState>, service_id: String) -> Option<ServiceCircuit> {
+    state.process_manager.service_circuit(&service_id)
+}
+
+/// Close a service's open restart circuit without starting it. Returns
+/// whether it was open.
+#[tauri::command]
+pub fn reset_service_circuit(app_handle: AppHandle, state: State<AppState>, service_id: String) -> bool {
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
+    state.process_manager.reset_circuit(&emitter, &service_id)
+}
+
+/// The env vars the service's next start injects: its linked env files
+/// overlaid with its own `env_vars`, sorted by name
+#[tauri::command]
+pub fn get_effective_env(state: State<AppState>, service_id: String) -> Result<std::collections::BTreeMap<String, String>, String> {
+    let (project, service) = state
+        .storage
+        .get_service(&service_id)
+        .ok_or_else(|| format!("Service not found: {}", service_id))?;
+    Ok(service_env_vars(&project, &service).unwrap_or_default().into_iter().collect())
+}
+
+/// Persisted log files of a service (one per session), newest first
+#[tauri::command]
+pub fn get_service_log_files(state: State<AppState>, service_id: String) -> Result<Vec<ServiceLogFile>, String> {
+    state
+        .process_manager
+        .service_logs()
+        .list_files(&service_id)
+        .map_err(|e| e.to_string())
+}
+
+/// A page of a persisted service log: `limit` lines (default 1000) from line `offset`
+#[tauri::command]
+pub fn read_service_log(
+    state: State<AppState>,
+    service_id: String,
+    file: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ServiceLogPage, String> {
+    state
+        .process_manager
+        .service_logs()
+        .read(&service_id, &file, offset.unwrap_or(0), limit.unwrap_or(1000))
+        .map_err(|e| e.to_string())
+}
+
 // Script execution commands
 
 #[tauri::command]
@@ -735,12 +1127,7 @@ pub fn run_script(
         .get_script(&script_id)
         .ok_or_else(|| format!("Script not found: {}", script_id))?;
 
-    let working_dir = if script.working_dir.is_empty() || script.working_dir == "." {
-        project.root_path.clone()
-    } else {
-        let path = Path::new(&project.root_path).join(&script.working_dir);
-        path.to_string_lossy().to_string()
-    };
+    let working_dir = project.resolve_dir(&script.working_dir);
 
     let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(TauriEmitter::new(app_handle));
     state.process_manager.run_script(
@@ -749,14 +1136,19 @@ pub fn run_script(
         working_dir,
         script.command,
         cortx_core::process_manager::RuntimeMeta::new(script.name.clone())
-            .with_project(project.id.clone(), project.name.clone()),
+            .with_project(project.id.clone(), project.name.clone())
+            .with_variant(project.active_variant_id.clone())
+            .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
     )
 }
 
 #[tauri::command]
-pub fn stop_script(app_handle: AppHandle, state: State<AppState>, script_id: String) -> Result<(), String> {
+pub async fn stop_script(app_handle: AppHandle, state: State<'_, AppState>, script_id: String) -> Result<(), String> {
     let emitter = TauriEmitter::new(app_handle);
-    state.process_manager.stop_script(&emitter, &script_id)
+    let process_manager = state.process_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || process_manager.stop_script(&emitter, &script_id))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -771,19 +1163,58 @@ pub fn get_settings(state: State<AppState>) -> AppSettings {
     state.storage.get_settings()
 }
 
+/// Save the settings and apply them to the running app. Global shortcuts
+/// that can't be registered don't fail the save; they are reported by
+/// `get_global_shortcut_warnings` and the `global-shortcut-warnings` event.
 #[tauri::command]
-pub fn update_settings(state: State<AppState>, settings: AppSettings) -> Result<(), String> {
+pub fn update_settings(app_handle: AppHandle, state: State<AppState>, settings: AppSettings) -> Result<(), String> {
+    let shell = settings.shell.clone();
+    let logging = settings.logging.clone();
+    let max_run_depth = settings.scripts_config.max_run_depth;
+    let echo_command = settings.scripts_config.echo_command;
     state
         .storage
         .update_settings(settings)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    state.process_manager.set_shell(shell);
+    state.process_manager.set_logging(logging);
+    state.process_manager.set_max_run_depth(max_run_depth);
+    state.process_manager.set_echo_command(echo_command);
+    crate::apply_global_shortcuts(&app_handle, &state.storage.get_settings());
+    Ok(())
+}
+
+/// Data files whose latest changes couldn't be written (disk full,
+/// permissions...). Empty when everything is saved.
+#[tauri::command]
+pub fn get_pending_saves(state: State<AppState>) -> Vec<PendingSave> {
+    state.storage.pending_saves()
+}
+
+/// Write the pending files again; returns what still failed
+#[tauri::command]
+pub fn retry_pending_saves(state: State<AppState>) -> Vec<PendingSave> {
+    state.storage.retry_pending_saves()
 }
 
 /// (Re-)register the global hotkey. Pass an empty string to unregister
 /// (effectively disables "open palette from anywhere").
 #[tauri::command]
-pub fn set_global_hotkey(app: tauri::AppHandle, combo: String) -> Result<(), String> {
-    crate::register_hotkey(&app, &combo)
+pub fn set_global_hotkey(app: tauri::AppHandle, state: State<AppState>, combo: String) -> Result<(), String> {
+    let mut settings = state.storage.get_settings();
+    settings.global_hotkey = Some(combo);
+    let warnings = crate::apply_global_shortcuts(&app, &settings);
+    match warnings.into_iter().find(|w| w.action == ShortcutAction::OpenPalette) {
+        Some(warning) => Err(warning.message),
+        None => Ok(()),
+    }
+}
+
+/// Global shortcuts from the settings that aren't registered, and why
+/// (invalid, used twice, or taken by the OS or another app)
+#[tauri::command]
+pub fn get_global_shortcut_warnings(state: State<AppState>) -> Vec<ShortcutWarning> {
+    state.shortcut_warnings.lock().map(|w| w.clone()).unwrap_or_default()
 }
 
 /// Real quit (as opposed to the X button which now hides to tray).
@@ -809,10 +1240,16 @@ pub fn quit_app(app: tauri::AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub fn open_in_explorer(path: String) -> Result<(), String> {
+    script_location::open_in_explorer(&path)
+}
+
+/// Show a file in the file manager, selected where the platform allows it
+#[tauri::command]
+pub fn reveal_in_explorer(path: String) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
-            .arg(&path)
+            .arg(format!("/select,{}", path))
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -820,15 +1257,20 @@ pub fn open_in_explorer(path: String) -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
         std::process::Command::new("open")
-            .arg(&path)
+            .args(["-R", &path])
             .spawn()
             .map_err(|e| e.to_string())?;
     }
 
     #[cfg(target_os = "linux")]
     {
+        // No portable way to select a file: open its folder instead
+        let folder = Path::new(&path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or(path);
         std::process::Command::new("xdg-open")
-            .arg(&path)
+            .arg(&folder)
             .spawn()
             .map_err(|e| e.to_string())?;
     }
@@ -873,224 +1315,120 @@ pub fn open_in_vscode(path: String) -> Result<(), String> {
 
 // Environment file commands
 
-/// Directories to skip during env file discovery
-const IGNORED_DIRECTORIES: &[&str] = &[
-    "node_modules",
-    ".git",
-    "target",
-    "dist",
-    "build",
-    ".next",
-    ".nuxt",
-    "__pycache__",
-    "venv",
-    ".venv",
-    "vendor",
-    ".cargo",
-    ".cache",
-];
-
-/// Check if a filename is an env file
-fn is_env_file(filename: &str) -> bool {
-    let lower = filename.to_lowercase();
-    lower == ".env"
-        || lower.starts_with(".env.")
-        || lower == ".env.local"
-        || lower == ".env.development"
-        || lower == ".env.production"
-        || lower == ".env.test"
-        || lower == ".env.staging"
-        || lower == ".env.example"
-        || lower == ".env.sample"
-}
-
-/// Determine the variant type from filename
-fn detect_variant(filename: &str) -> EnvFileVariant {
-    let lower = filename.to_lowercase();
-    match lower.as_str() {
-        ".env" => EnvFileVariant::Base,
-        ".env.local" => EnvFileVariant::Local,
-        s if s.contains("development") || s.contains(".dev") => EnvFileVariant::Development,
-        s if s.contains("production") || s.contains(".prod") => EnvFileVariant::Production,
-        s if s.contains("test") => EnvFileVariant::Test,
-        s if s.contains("staging") => EnvFileVariant::Staging,
-        s if s.contains("example") || s.contains("sample") => EnvFileVariant::Example,
-        _ => EnvFileVariant::Other,
-    }
-}
-
-/// Strip surrounding quotes from a value
-fn strip_quotes(s: &str) -> String {
-    let trimmed = s.trim();
-    if (trimmed.starts_with('"') && trimmed.ends_with('"'))
-        || (trimmed.starts_with('\'') && trimmed.ends_with('\''))
-    {
-        trimmed[1..trimmed.len() - 1].to_string()
-    } else {
-        trimmed.to_string()
+/// Start discovering the .env files of a project and return the discovery's
+/// id right away. The walk runs on a worker thread, reporting
+/// `env-discovery-progress` as it goes and `env-discovery-complete` at the
+/// end; the project is only updated if it completes.
+#[tauri::command]
+pub fn discover_env_files(
+    app_handle: AppHandle,
+    state: State<AppState>,
+    project_id: String,
+    input: DiscoverEnvFilesInput,
+) -> Result<String, String> {
+    if state.storage.get_project(&project_id).is_none() {
+        return Err(format!("Project not found: {}", project_id));
     }
-}
-
-/// Parse .env file contents into key-value pairs
-fn parse_env_file(path: &Path) -> Result<Vec<EnvVariable>, String> {
-    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
-    let mut variables = Vec::new();
 
-    for (line_num, line) in content.lines().enumerate() {
-        let trimmed = line.trim();
-
-        // Skip empty lines and comments
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
-        }
-
-        // Parse KEY=VALUE format
-        if let Some(eq_pos) = trimmed.find('=') {
-            let key = trimmed[..eq_pos].trim().to_string();
-            let value = trimmed[eq_pos + 1..].trim().to_string();
-
-            // Remove surrounding quotes if present
-            let value = strip_quotes(&value);
-
-            if !key.is_empty() {
-                variables.push(EnvVariable {
-                    key,
-                    value,
-                    line_number: (line_num + 1) as u32,
-                });
-            }
+    let discovery = Arc::new(EnvDiscovery::new());
+    let discovery_id = discovery.id.clone();
+    state
+        .env_discoveries
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(discovery_id.clone(), discovery.clone());
+
+    let storage = state.storage.clone();
+    let discoveries = state.env_discoveries.clone();
+    let budget = ScanBudget::from_config(&storage.get_settings().scripts_config);
+    std::thread::spawn(move || {
+        let progress_handle = app_handle.clone();
+        let outcome = discovery.run(
+            &storage,
+            &project_id,
+            input.force,
+            &budget,
+            env_discovery::PROGRESS_INTERVAL,
+            |progress| {
+                let _ = progress_handle.emit("env-discovery-progress", progress);
+            },
+        );
+        if let Ok(mut discoveries) = discoveries.lock() {
+            discoveries.remove(&discovery.id);
         }
-    }
 
-    Ok(variables)
-}
-
-/// Auto-link env file to service if in same directory
-fn find_matching_service(env_file_dir: &Path, project: &Project) -> Option<String> {
-    for service in &project.services {
-        let service_dir = if service.working_dir.is_empty() || service.working_dir == "." {
-            Path::new(&project.root_path).to_path_buf()
-        } else {
-            Path::new(&project.root_path).join(&service.working_dir)
+        let mut complete = EnvDiscoveryComplete {
+            discovery_id: discovery.id.clone(),
+            project_id,
+            env_files: Vec::new(),
+            truncated: false,
+            cancelled: false,
+            error: None,
         };
-
-        // Check if the env file is in the service directory
-        if let Ok(env_canonical) = env_file_dir.canonicalize() {
-            if let Ok(service_canonical) = service_dir.canonicalize() {
-                if env_canonical == service_canonical {
-                    return Some(service.id.clone());
-                }
+        match outcome {
+            Ok(DiscoveryOutcome::Completed { env_files, truncated }) => {
+                complete.env_files = env_files;
+                complete.truncated = truncated;
             }
+            Ok(DiscoveryOutcome::Cancelled) => complete.cancelled = true,
+            Err(e) => complete.error = Some(e),
         }
-    }
+        let _ = app_handle.emit("env-discovery-complete", complete);
+    });
 
-    None
+    Ok(discovery_id)
 }
 
-/// Discover all .env files in a project directory
+/// Stop a running env file discovery; the project is left as it was.
 #[tauri::command]
-pub fn discover_env_files(
-    state: State<AppState>,
-    project_id: String,
-    input: DiscoverEnvFilesInput,
-) -> Result<Vec<EnvFile>, String> {
-    let project = state
-        .storage
-        .get_project(&project_id)
-        .ok_or_else(|| format!("Project not found: {}", project_id))?;
-
-    // Skip if already discovered and not forcing
-    if project.env_files_discovered && !input.force {
-        return Ok(project.env_files.clone());
-    }
-
-    let root_path = Path::new(&project.root_path);
-    if !root_path.exists() {
-        return Err(format!("Project root path does not exist: {}", project.root_path));
-    }
-
-    let mut discovered_files: Vec<EnvFile> = Vec::new();
+pub fn cancel_env_discovery(state: State<AppState>, discovery_id: String) -> Result<(), String> {
+    let discoveries = state.env_discoveries.lock().map_err(|e| e.to_string())?;
+    let discovery = discoveries
+        .get(&discovery_id)
+        .ok_or_else(|| format!("No env discovery running with id {}", discovery_id))?;
+    discovery.cancel();
+    Ok(())
+}
 
-    // Walk the directory tree
-    for entry in WalkDir::new(root_path)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| {
-            // Skip ignored directories
-            if e.file_type().is_dir() {
-                if let Some(name) = e.file_name().to_str() {
-                    return !IGNORED_DIRECTORIES.contains(&name);
-                }
-            }
-            true
-        })
-    {
-        if let Ok(entry) = entry {
-            if entry.file_type().is_file() {
-                if let Some(filename) = entry.file_name().to_str() {
-                    if is_env_file(filename) {
-                        let full_path = entry.path();
-                        let relative_path = full_path
-                            .strip_prefix(root_path)
-                            .unwrap_or(full_path)
-                            .to_string_lossy()
-                            .to_string();
-
-                        // Parse the env file
-                        let variables = parse_env_file(full_path).unwrap_or_default();
-                        let variant = detect_variant(filename);
-
-                        let mut env_file = EnvFile::new(
-                            full_path.to_string_lossy().to_string(),
-                            relative_path,
-                            filename.to_string(),
-                            variant,
-                            variables,
-                            false, // not manually added
-                        );
-
-                        // Try to link to a service
-                        if let Some(parent_dir) = full_path.parent() {
-                            env_file.linked_service_id = find_matching_service(parent_dir, &project);
-                        }
-
-                        discovered_files.push(env_file);
-                    }
-                }
-            }
-        }
-    }
+/// A newly tracked env file at `file_path`, read and linked to the service
+/// whose directory it's in
+fn tracked_env_file(
+    project: &cortx_core::models::Project,
+    file_path: String,
+    manually_added: bool,
+) -> Result<EnvFile, String> {
+    let path = Path::new(&file_path);
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(".env")
+        .to_string();
 
-    // If forcing, preserve manually added files
-    let manually_added: Vec<EnvFile> = if input.force {
-        project
-            .env_files
-            .iter()
-            .filter(|f| f.is_manually_added)
-            .cloned()
-            .collect()
-    } else {
-        Vec::new()
-    };
+    let root_path = Path::new(project.effective_root());
+    let relative_path = path
+        .strip_prefix(root_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| file_path.clone());
 
-    // Merge manually added files with discovered files
-    let mut final_files = discovered_files;
-    for manual_file in manually_added {
-        if !final_files.iter().any(|f| f.path == manual_file.path) {
-            final_files.push(manual_file);
-        }
-    }
+    let parsed = parse_env_file(path)?;
+    let variant = detect_variant(&filename);
 
-    // Update project with discovered files
-    state
-        .storage
-        .update_project(&project_id, |p| {
-            p.env_files = final_files.clone();
-            p.env_files_discovered = true;
-        })
-        .map_err(|e| e.to_string())?;
+    // Compute linked service before moving file_path
+    let linked_service_id = path
+        .parent()
+        .and_then(|parent_dir| find_matching_service(parent_dir, project));
 
-    Ok(final_files)
+    let mut env_file = EnvFile::new(
+        file_path,
+        relative_path,
+        filename,
+        variant,
+        parsed.variables,
+        manually_added,
+    );
+    env_file.parse_errors = parsed.errors;
+    env_file.linked_service_id = linked_service_id;
+    Ok(env_file)
 }
 
 /// Add a single .env file manually by path
@@ -1120,35 +1458,7 @@ pub fn add_env_file(
         return Err("File is already tracked".to_string());
     }
 
-    let filename = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(".env")
-        .to_string();
-
-    let root_path = Path::new(&project.root_path);
-    let relative_path = path
-        .strip_prefix(root_path)
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|_| file_path.clone());
-
-    let variables = parse_env_file(path)?;
-    let variant = detect_variant(&filename);
-
-    // Compute linked service before moving file_path
-    let linked_service_id = path
-        .parent()
-        .and_then(|parent_dir| find_matching_service(parent_dir, &project));
-
-    let mut env_file = EnvFile::new(
-        file_path,
-        relative_path,
-        filename,
-        variant,
-        variables,
-        true, // manually added
-    );
-    env_file.linked_service_id = linked_service_id;
+    let env_file = tracked_env_file(&project, file_path, true)?;
 
     let env_file_clone = env_file.clone();
 
@@ -1202,7 +1512,7 @@ pub fn refresh_env_file(
         return Err(format!("File no longer exists: {}", env_file.path));
     }
 
-    let variables = parse_env_file(path)?;
+    let parsed = parse_env_file(path)?;
     let updated_file_id = env_file_id.clone();
 
     let mut result_file: Option<EnvFile> = None;
@@ -1211,8 +1521,7 @@ pub fn refresh_env_file(
         .storage
         .update_project(&project_id, |p| {
             if let Some(f) = p.env_files.iter_mut().find(|f| f.id == updated_file_id) {
-                f.variables = variables.clone();
-                f.last_read_at = Utc::now();
+                apply_refresh(f, parsed.clone());
                 result_file = Some(f.clone());
             }
         })
@@ -1237,15 +1546,14 @@ pub fn refresh_all_env_files(
     for env_file in &project.env_files {
         let path = Path::new(&env_file.path);
         if path.exists() {
-            let variables = parse_env_file(path).unwrap_or_default();
             let mut updated = env_file.clone();
-            updated.variables = variables;
-            updated.last_read_at = Utc::now();
+            apply_refresh(&mut updated, parse_env_file_lenient(path));
             updated_files.push(updated);
         } else {
             // Keep the file in the list but with empty variables
             let mut updated = env_file.clone();
             updated.variables = Vec::new();
+            updated.parse_errors = Vec::new();
             updated_files.push(updated);
         }
     }
@@ -1262,23 +1570,41 @@ pub fn refresh_all_env_files(
     Ok(updated_files)
 }
 
-/// Get env files for a project
+/// Whether env file views hide sensitive values: `mask_values` when given,
+/// otherwise the `mask_env_values` setting
+fn should_mask(state: &State<AppState>, mask_values: Option<bool>) -> bool {
+    mask_values.unwrap_or_else(|| state.storage.get_settings().mask_env_values)
+}
+
+/// Get env files for a project. Sensitive values are masked per `should_mask`.
 #[tauri::command]
-pub fn get_env_files(state: State<AppState>, project_id: String) -> Result<Vec<EnvFile>, String> {
+pub fn get_env_files(
+    state: State<AppState>,
+    project_id: String,
+    mask_values: Option<bool>,
+) -> Result<Vec<EnvFile>, String> {
     let project = state
         .storage
         .get_project(&project_id)
         .ok_or_else(|| format!("Project not found: {}", project_id))?;
 
-    Ok(project.env_files)
+    let mut env_files = project.env_files;
+    if should_mask(&state, mask_values) {
+        for file in &mut env_files {
+            mask_variables(&mut file.variables);
+        }
+    }
+    Ok(env_files)
 }
 
-/// Get the raw content of an env file
+/// Get the raw content of an env file, decoded to UTF-8 with `\n` line
+/// endings. Sensitive values are masked per `should_mask`.
 #[tauri::command]
 pub fn get_env_file_content(
     state: State<AppState>,
     project_id: String,
     env_file_id: String,
+    mask_values: Option<bool>,
 ) -> Result<String, String> {
     let project = state
         .storage
@@ -1296,11 +1622,81 @@ pub fn get_env_file_content(
         return Err(format!("File no longer exists: {}", env_file.path));
     }
 
-    std::fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read file: {}", e))
+    let content = text_file::read_to_string(path).map_err(|e| e.to_string())?;
+    Ok(if should_mask(&state, mask_values) { mask_content(&content) } else { content })
+}
+
+/// Write one variable change to an env file and store the re-parsed result.
+/// Fails without touching the file when it changed on disk since it was last read.
+fn edit_env_variable(
+    state: &State<AppState>,
+    project_id: &str,
+    env_file_id: &str,
+    edit: EnvEdit,
+) -> Result<EnvFile, String> {
+    let project = state
+        .storage
+        .get_project(project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let mut env_file = project
+        .env_files
+        .into_iter()
+        .find(|f| f.id == env_file_id)
+        .ok_or_else(|| format!("Env file not found: {}", env_file_id))?;
+
+    edit_env_file(&mut env_file, &edit).map_err(|e| e.to_string())?;
+
+    let updated = env_file.clone();
+    state
+        .storage
+        .update_project(project_id, |p| {
+            if let Some(f) = p.env_files.iter_mut().find(|f| f.id == updated.id) {
+                *f = updated;
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(env_file)
+}
+
+/// Change the value of a variable in an env file, appending it if it isn't there
+#[tauri::command]
+pub fn update_env_variable(
+    state: State<AppState>,
+    project_id: String,
+    env_file_id: String,
+    key: String,
+    value: String,
+) -> Result<EnvFile, String> {
+    edit_env_variable(&state, &project_id, &env_file_id, EnvEdit::Set { key, value })
+}
+
+/// Append a new variable to an env file
+#[tauri::command]
+pub fn add_env_variable(
+    state: State<AppState>,
+    project_id: String,
+    env_file_id: String,
+    key: String,
+    value: String,
+) -> Result<EnvFile, String> {
+    edit_env_variable(&state, &project_id, &env_file_id, EnvEdit::Add { key, value })
+}
+
+/// Remove a variable from an env file
+#[tauri::command]
+pub fn remove_env_variable(
+    state: State<AppState>,
+    project_id: String,
+    env_file_id: String,
+    key: String,
+) -> Result<EnvFile, String> {
+    edit_env_variable(&state, &project_id, &env_file_id, EnvEdit::Remove { key })
 }
 
-/// Compare .env with .env.example in the same directory
+/// Compare .env with .env.example in the same directory. Only keys are
+/// compared, so masking doesn't apply.
 #[tauri::command]
 pub fn compare_env_files(
     state: State<AppState>,
@@ -1357,6 +1753,124 @@ pub fn compare_env_files(
     })
 }
 
+/// Every key of every env file of the project in one table: which files
+/// define it, whether their values differ, and which `.env` files lack a
+/// key their `.env.example` has. Values are compared unmasked, then masked
+/// per `should_mask`.
+#[tauri::command]
+pub fn compare_all_env_files(
+    state: State<AppState>,
+    project_id: String,
+    mask_values: Option<bool>,
+) -> Result<EnvMatrix, String> {
+    let project = state
+        .storage
+        .get_project(&project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let mut matrix = env_matrix(&project.env_files);
+    if should_mask(&state, mask_values) {
+        mask_matrix(&mut matrix);
+    }
+    Ok(matrix)
+}
+
+/// Create an env file from an example file: every key of the example with an
+/// empty value, comments and order kept. `target_path` (absolute or relative
+/// to the project root) defaults to `.env` next to the example. An existing
+/// target only gets the keys it lacks appended. The file is tracked (or
+/// re-read when it already was) and returned.
+#[tauri::command]
+pub fn scaffold_env_from_example(
+    state: State<AppState>,
+    project_id: String,
+    example_file_id: String,
+    target_path: Option<String>,
+) -> Result<EnvFile, String> {
+    let project = state
+        .storage
+        .get_project(&project_id)
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let example_file = project
+        .env_files
+        .iter()
+        .find(|f| f.id == example_file_id)
+        .ok_or_else(|| format!("Example file not found: {}", example_file_id))?;
+    let example_path = Path::new(&example_file.path);
+
+    let target = match target_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => Path::new(project.effective_root()).join(p),
+        None => example_path.with_file_name(".env"),
+    };
+    if target == example_path {
+        return Err("The target is the example file itself".to_string());
+    }
+
+    scaffold_env_file(example_path, &target)?;
+
+    let target_path = target.to_string_lossy().to_string();
+    let parsed = parse_env_file(&target)?;
+    let mut result_file: Option<EnvFile> = None;
+    let new_file = if project.env_files.iter().any(|f| f.path == target_path) {
+        None
+    } else {
+        Some(tracked_env_file(&project, target_path.clone(), true)?)
+    };
+
+    state
+        .storage
+        .update_project(&project_id, |p| {
+            if let Some(f) = p.env_files.iter_mut().find(|f| f.path == target_path) {
+                apply_refresh(f, parsed.clone());
+                result_file = Some(f.clone());
+            } else if let Some(new_file) = new_file {
+                p.env_files.push(new_file.clone());
+                result_file = Some(new_file);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    result_file.ok_or_else(|| "Failed to track env file".to_string())
+}
+
+/// Keys that several env files in one directory define with different values.
+/// `dir` is absolute or relative to the project root (`.` for the root).
+#[tauri::command]
+pub fn find_duplicate_env_keys(
+    state: State<AppState>,
+    project_id: String,
+    dir: String,
+) -> Result<Vec<(String, Vec<String>)>, String> {
+    if state.storage.get_project(&project_id).is_none() {
+        return Err(format!("Project not found: {}", project_id));
+    }
+    Ok(state.storage.find_duplicate_env_keys(&project_id, &dir))
+}
+
+/// Override an env file's variant (`None` goes back to detecting it from the filename)
+#[tauri::command]
+pub fn update_env_file(
+    state: State<AppState>,
+    project_id: String,
+    env_file_id: String,
+    variant: Option<EnvFileVariant>,
+) -> Result<EnvFile, String> {
+    let mut result_file: Option<EnvFile> = None;
+
+    state
+        .storage
+        .update_project(&project_id, |p| {
+            if let Some(f) = p.env_files.iter_mut().find(|f| f.id == env_file_id) {
+                set_variant(f, variant.clone());
+                result_file = Some(f.clone());
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    result_file.ok_or_else(|| format!("Env file not found: {}", env_file_id))
+}
+
 /// Link an env file to a service
 #[tauri::command]
 pub fn link_env_to_service(
@@ -1407,10 +1921,24 @@ pub fn create_global_script(
     script.script_path = input.script_path;
     script.color = input.color;
     script.tags = input.tags.unwrap_or_default();
+    script.folder = input.folder;
     script.parameters = input.parameters.unwrap_or_default();
     script.parameter_presets = input.parameter_presets.unwrap_or_default();
     script.env_vars = input.env_vars;
+    script.env_mode = input.env_mode.unwrap_or_default();
     script.status = input.status;
+    script.requires = input.requires.unwrap_or_default();
+    script.artifacts = input.artifacts.unwrap_or_default();
+    script.output_format = input.output_format.unwrap_or_default();
+    script.allow_concurrent = input.allow_concurrent.unwrap_or(false);
+    script.mutex_group = input.mutex_group.filter(|g| !g.trim().is_empty());
+    script.queue_on_mutex = input.queue_on_mutex.unwrap_or(false);
+    script.schedule = checked_schedule(input.schedule)?;
+    script.auto_discovered = input.auto_discovered.unwrap_or(false);
+    if script.auto_discovered {
+        // Lets a later scan follow the file if it moves
+        script.content_hash = script.script_path.as_deref().and_then(|p| content_hash(Path::new(p)));
+    }
 
     // Set order to be last
     let all = state.storage.get_all_global_scripts();
@@ -1422,12 +1950,21 @@ pub fn create_global_script(
         .map_err(|e| e.to_string())
 }
 
+/// Warnings about a script's command, file and working dir. Called by the
+/// editor before saving; the save itself never fails on these.
+#[tauri::command]
+pub fn validate_global_script(input: CreateGlobalScriptInput) -> Vec<ValidationIssue> {
+    script_validation::validate_global_script(&input)
+}
+
 #[tauri::command]
 pub fn update_global_script(
     state: State<AppState>,
     id: String,
     input: UpdateGlobalScriptInput,
 ) -> Result<GlobalScript, String> {
+    // An empty schedule clears it
+    let schedule = input.schedule.clone().map(|s| checked_schedule(Some(s))).transpose()?;
     state
         .storage
         .update_global_script(&id, |script| {
@@ -1452,6 +1989,9 @@ pub fn update_global_script(
             if let Some(tags) = input.tags {
                 script.tags = tags;
             }
+            if input.folder.is_some() {
+                script.folder = input.folder;
+            }
             if let Some(parameters) = input.parameters {
                 script.parameters = parameters;
             }
@@ -1464,22 +2004,64 @@ pub fn update_global_script(
             if input.env_vars.is_some() {
                 script.env_vars = input.env_vars;
             }
+            if let Some(env_mode) = input.env_mode {
+                script.env_mode = env_mode;
+            }
             if input.status.is_some() {
                 script.status = input.status;
             }
+            if input.raw_help.is_some() {
+                script.raw_help = input.raw_help;
+            }
+            if let Some(requires) = input.requires {
+                script.requires = requires;
+            }
+            if let Some(artifacts) = input.artifacts {
+                script.artifacts = artifacts;
+            }
+            if let Some(output_format) = input.output_format {
+                script.output_format = output_format;
+            }
+            if let Some(allow_concurrent) = input.allow_concurrent {
+                script.allow_concurrent = allow_concurrent;
+            }
+            // An empty group leaves the script's group
+            if let Some(mutex_group) = input.mutex_group {
+                script.mutex_group = Some(mutex_group).filter(|g| !g.trim().is_empty());
+            }
+            if let Some(queue_on_mutex) = input.queue_on_mutex {
+                script.queue_on_mutex = queue_on_mutex;
+            }
+            if let Some(schedule) = schedule {
+                script.schedule = schedule;
+            }
         })
         .map_err(|e| e.to_string())
 }
 
+/// A global script's cron schedule, trimmed, or `None` when blank. Refused
+/// when the scheduler couldn't read it.
+fn checked_schedule(schedule: Option<String>) -> Result<Option<String>, String> {
+    match schedule.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(expr) => {
+            cortx_core::scheduler::parse_schedule(expr)?;
+            Ok(Some(expr.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
 #[tauri::command]
 pub fn delete_global_script(
     app_handle: AppHandle,
     state: State<AppState>,
     id: String,
 ) -> Result<(), String> {
-    // Stop if running
+    // Stop every run still going
     let emitter = TauriEmitter::new(app_handle);
-    let _ = state.process_manager.stop_global_script(&emitter, &id);
+    for run in state.process_manager.global_script_runs(&id) {
+        let _ = state.process_manager.stop_global_script(&emitter, &run.id);
+    }
 
     state
         .storage
@@ -1503,6 +2085,35 @@ pub fn reorder_global_scripts(
     Ok(())
 }
 
+/// Run the organize rules from the scripts config over `script_ids` (all
+/// scripts when omitted). With `dry_run` nothing is saved.
+#[tauri::command]
+pub fn apply_organize_rules(
+    state: State<AppState>,
+    script_ids: Option<Vec<String>>,
+    dry_run: bool,
+) -> Result<OrganizeResult, String> {
+    state
+        .storage
+        .apply_organize_rules(script_ids.as_deref(), dry_run)
+        .map_err(|e| e.to_string())
+}
+
+/// Error of `run_global_script`. Unmet requirements stay structured so the
+/// GUI can list them and offer to start the services and retry.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RunScriptError {
+    RequirementNotMet(RequirementNotMet),
+    Failed { message: String },
+}
+
+impl From<String> for RunScriptError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
 #[tauri::command]
 pub fn run_global_script(
     app_handle: AppHandle,
@@ -1511,54 +2122,217 @@ pub fn run_global_script(
     working_dir: String,
     parameter_values: Option<std::collections::HashMap<String, String>>,
     extra_args: Option<String>,
-) -> Result<u32, String> {
-    let script = state
-        .storage
+) -> Result<u32, RunScriptError> {
+    let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(TauriEmitter::new(app_handle));
+    start_global_script(
+        &state.storage,
+        &state.process_manager,
+        emitter,
+        script_id,
+        working_dir,
+        parameter_values,
+        extra_args,
+    )
+}
+
+/// Check the requirements of a global script, record the execution and
+/// spawn it. Shared by `run_global_script` and `run_script_dag`.
+fn start_global_script(
+    storage: &Storage,
+    process_manager: &Arc<ProcessManager>,
+    emitter: Arc<dyn ProcessEventEmitter>,
+    script_id: String,
+    working_dir: String,
+    parameter_values: Option<std::collections::HashMap<String, String>>,
+    extra_args: Option<String>,
+) -> Result<u32, RunScriptError> {
+    let script = storage
         .get_global_script(&script_id)
         .ok_or_else(|| format!("Global script not found: {}", script_id))?;
 
+    let projects = storage.get_all_projects();
+    script_requirements::ensure_requirements(&script, &projects, |id| process_manager.is_service_running(id))
+        .map_err(RunScriptError::RequirementNotMet)?;
+
     // Build program + args via shared builder
-    let extra: Vec<String> = extra_args
-        .as_deref()
-        .map(|e| e.split_whitespace().map(|s| s.to_string()).collect())
-        .unwrap_or_default();
+    let extra: Vec<String> = extra_args.as_deref().map(command_builder::split_args).unwrap_or_default();
     let param_map = parameter_values.clone().unwrap_or_default();
+    command_builder::check_value_counts(&script, &param_map).map_err(|e| e.to_string())?;
 
     let (program, args) = cortx_core::command_builder::build_command(&script, &param_map, &extra)
         .ok_or_else(|| "Empty command".to_string())?;
 
-    // Record execution start
+    // Record execution start, with enough to replay it later
     let mut record = ExecutionRecord::new(script_id.clone());
     if let Some(ref params) = parameter_values {
         record.parameters_used = params.clone();
     }
-    let _ = state.storage.add_execution_record(record);
+    record.working_dir = Some(working_dir.clone());
+    record.extra_args = extra;
+    record.parameters_signature = Some(cortx_core::command_builder::parameters_signature(&script));
+    record.log_start = Some(process_manager.runtime_store().log_len(&script_id));
+    record.env_snapshot = cortx_core::spawn_env::env_snapshot(
+        script.env_vars.as_ref(),
+        &storage.get_settings().scripts_config.env_snapshot,
+    );
+    let _ = storage.add_execution_record(record);
 
-    let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(TauriEmitter::new(app_handle));
     let script_name = script.name.clone();
-    let pid = state.process_manager.run_global_script(
+    let allow_concurrent = script.allow_concurrent;
+    let (mutex_group, queue_on_mutex) = (script.mutex_group.clone(), script.queue_on_mutex);
+    let pid = process_manager.run_global_script(
         emitter,
         script_id.clone(),
         working_dir,
         program,
         args,
         script.env_vars,
-        cortx_core::process_manager::RuntimeMeta::new(script_name),
+        &script.env_mode,
+        cortx_core::process_manager::RuntimeMeta::new(script_name)
+            .with_allow_concurrent(allow_concurrent)
+            .with_mutex_group(mutex_group, queue_on_mutex),
     )?;
 
     Ok(pid)
 }
 
+/// Run global scripts in dependency order: a script starts once the
+/// scripts it depends on exited successfully, independent ones run side by
+/// side (up to `max_parallel`). Scripts run in their own working directory
+/// with their default parameters. Async so the waits don't block the main
+/// thread.
+#[tauri::command]
+pub async fn run_script_dag(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    nodes: Vec<ScriptDependency>,
+    max_parallel: Option<usize>,
+) -> Result<Vec<ScriptNodeResult>, String> {
+    let waiter = Arc::new(ScriptExitWaiter::new(Arc::new(TauriEmitter::new(app_handle))));
+    let storage = state.storage.clone();
+    let process_manager = state.process_manager.clone();
+    let quitting = state.quitting.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        script_dag::run_dag(&nodes, max_parallel.unwrap_or(script_dag::DEFAULT_MAX_PARALLEL), |script_id| {
+            let working_dir = storage
+                .get_global_script(script_id)
+                .ok_or_else(|| format!("Global script not found: {}", script_id))?
+                .working_dir
+                .ok_or_else(|| "The script has no working directory".to_string())?;
+            waiter.forget(script_id);
+            let emitter: Arc<dyn ProcessEventEmitter> = waiter.clone();
+            start_global_script(&storage, &process_manager, emitter, script_id.to_string(), working_dir, None, None)
+                .map_err(|e| match e {
+                    RunScriptError::RequirementNotMet(unmet) => unmet.to_string(),
+                    RunScriptError::Failed { message } => message,
+                })?;
+            if waiter.wait(script_id, &quitting) {
+                Ok(())
+            } else {
+                Err("Exited with an error".to_string())
+            }
+        })
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayOutcome {
+    pub pid: u32,
+    /// e.g. the script's parameters changed since the recorded run
+    pub warnings: Vec<String>,
+}
+
+/// Re-run a past execution with its recorded parameters, extra args and
+/// working directory.
 #[tauri::command]
-pub fn stop_global_script(
+pub fn replay_execution(
     app_handle: AppHandle,
     state: State<AppState>,
+    record_id: String,
+) -> Result<ReplayOutcome, RunScriptError> {
+    let past = state
+        .storage
+        .get_execution_record(&record_id)
+        .ok_or_else(|| format!("Execution record not found: {}", record_id))?;
+    let script = state
+        .storage
+        .get_global_script(&past.script_id)
+        .ok_or_else(|| ReplayError::ScriptMissing(past.script_id.clone()).to_string())?;
+    let plan = plan_replay(&past, Some(&script)).map_err(|e| e.to_string())?;
+
+    let working_dir = plan
+        .working_dir
+        .clone()
+        .or_else(|| script.working_dir.clone())
+        .ok_or_else(|| "This run didn't record a working directory; run the script from its dialog".to_string())?;
+
+    let projects = state.storage.get_all_projects();
+    script_requirements::ensure_requirements(&script, &projects, |id| {
+        state.process_manager.is_service_running(id)
+    })
+    .map_err(RunScriptError::RequirementNotMet)?;
+
+    let mut record = ExecutionRecord::new(script.id.clone());
+    record.parameters_used = plan.param_values.clone();
+    record.preset_name = past.preset_name.clone();
+    record.working_dir = Some(working_dir.clone());
+    record.extra_args = past.extra_args.clone();
+    record.parameters_signature = Some(cortx_core::command_builder::parameters_signature(&script));
+    record.log_start = Some(state.process_manager.runtime_store().log_len(&script.id));
+    record.env_snapshot = cortx_core::spawn_env::env_snapshot(
+        script.env_vars.as_ref(),
+        &state.storage.get_settings().scripts_config.env_snapshot,
+    );
+    let _ = state.storage.add_execution_record(record);
+
+    let emitter: Arc<dyn ProcessEventEmitter> = Arc::new(TauriEmitter::new(app_handle));
+    let pid = state.process_manager.run_global_script(
+        emitter,
+        script.id.clone(),
+        working_dir,
+        plan.program,
+        plan.args,
+        script.env_vars.clone(),
+        &script.env_mode,
+        cortx_core::process_manager::RuntimeMeta::new(script.name.clone())
+            .with_allow_concurrent(script.allow_concurrent)
+            .with_mutex_group(script.mutex_group.clone(), script.queue_on_mutex),
+    )?;
+
+    Ok(ReplayOutcome { pid, warnings: plan.warnings })
+}
+
+/// Status of each service a global script requires (for the run dialog).
+#[tauri::command]
+pub fn check_script_requirements(
+    state: State<AppState>,
+    script_id: String,
+) -> Result<Vec<RequirementStatus>, String> {
+    let script = state
+        .storage
+        .get_global_script(&script_id)
+        .ok_or_else(|| format!("Global script not found: {}", script_id))?;
+    let projects = state.storage.get_all_projects();
+    Ok(script_requirements::check_requirements(&script.requires, &projects, |id| {
+        state.process_manager.is_service_running(id)
+    }))
+}
+
+#[tauri::command]
+pub async fn stop_global_script(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
     script_id: String,
 ) -> Result<(), String> {
     let emitter = TauriEmitter::new(app_handle);
-    state
-        .process_manager
-        .stop_global_script(&emitter, &script_id)
+    let process_manager = state.process_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || process_manager.stop_global_script(&emitter, &script_id))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
 #[tauri::command]
@@ -1566,6 +2340,50 @@ pub fn is_global_script_running(state: State<AppState>, script_id: String) -> bo
     state.process_manager.is_global_script_running(&script_id)
 }
 
+/// Stop a running service, project script or global script by kind and id,
+/// e.g. a row of `list_running`
+#[tauri::command]
+pub async fn stop_process(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    kind: ProcessKind,
+    id: String,
+) -> Result<(), String> {
+    let emitter = ServiceHistoryEmitter::new(app_handle, state.storage.clone());
+    let process_manager = state.process_manager.clone();
+    tauri::async_runtime::spawn_blocking(move || process_manager.stop(kind, &id, &emitter))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn is_process_running(state: State<AppState>, kind: ProcessKind, id: String) -> bool {
+    state.process_manager.is_running(kind, &id)
+}
+
+/// Environment a global script or service would be launched with, after its
+/// `env_mode` and `env_vars` are applied. `id` may be either kind of entity.
+#[tauri::command]
+pub fn get_effective_env(
+    state: State<AppState>,
+    id: String,
+) -> Result<std::collections::BTreeMap<String, String>, String> {
+    if let Some(script) = state.storage.get_global_script(&id) {
+        return Ok(cortx_core::spawn_env::effective_env(
+            &script.env_mode,
+            script.env_vars.as_ref(),
+        ));
+    }
+    let (_, service) = state
+        .storage
+        .get_service(&id)
+        .ok_or_else(|| format!("Script or service not found: {}", id))?;
+    Ok(cortx_core::spawn_env::effective_env(
+        &service.env_mode,
+        service.env_vars.as_ref(),
+    ))
+}
+
 // ============================================================================
 // Tag Definition commands
 // ============================================================================
@@ -1638,6 +2456,34 @@ pub fn get_execution_history(
         .get_execution_history(&script_id, limit.unwrap_or(50))
 }
 
+/// Past runs of a service, newest first
+#[tauri::command]
+pub fn get_service_history(
+    state: State<AppState>,
+    service_id: String,
+    limit: Option<usize>,
+) -> Vec<ExecutionRecord> {
+    state
+        .storage
+        .get_execution_history(&service_id, limit.unwrap_or(50))
+}
+
+/// Compare two runs of the same script: parameters, exit, duration and a
+/// noise-filtered diff of their logs.
+#[tauri::command]
+pub fn compare_runs(
+    state: State<AppState>,
+    record_id_a: String,
+    record_id_b: String,
+) -> Result<RunComparison, String> {
+    run_compare::compare_recorded_runs(
+        &state.storage,
+        state.process_manager.runtime_store(),
+        &record_id_a,
+        &record_id_b,
+    )
+}
+
 #[tauri::command]
 pub fn clear_execution_history(
     state: State<AppState>,
@@ -1663,29 +2509,50 @@ pub fn update_scripts_config(
     state: State<AppState>,
     config: ScriptsConfig,
 ) -> Result<(), String> {
+    let echo_command = config.echo_command;
     let mut settings = state.storage.get_settings();
     settings.scripts_config = config;
     state
         .storage
         .update_settings(settings)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    state.process_manager.set_echo_command(echo_command);
+    Ok(())
 }
 
 // ============================================================================
 // Script Discovery / Scan
 // ============================================================================
 
+/// Scan a folder, or the configured main scripts folder when none is given,
+/// for scripts with the configured extensions, ignored patterns and scan
+/// limits. Runs off the main thread; a scan that hits its limits comes back
+/// with `truncated` set.
+///
+/// Auto-discovered scripts whose file was moved or renamed are pointed at
+/// the new path (`relocated`); possible moves that can't be told apart are
+/// left to the user (`unresolved_moves`), and ones whose file is gone are
+/// flagged (`vanished`).
+#[tauri::command]
+pub async fn scan_scripts_folder(
+    state: State<'_, AppState>,
+    folder: Option<String>,
+) -> Result<ScriptScanResult, String> {
+    let storage = state.storage.clone();
+    tauri::async_runtime::spawn_blocking(move || storage.scan_scripts_folder(folder.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Import files from a scan as auto-discovered global scripts. Paths that
+/// are already registered are skipped, so re-importing never duplicates.
 #[tauri::command]
-pub fn scan_scripts_folder(folder: String) -> Result<Vec<DiscoveredScript>, String> {
-    if folder.is_empty() {
-        return Err("No folder specified.".to_string());
-    }
-    let config = cortx_core::models::ScriptsConfig::default();
-    Ok(cortx_core::script_discovery::scan_folder(
-        &folder,
-        &config.scan_extensions,
-        &config.ignored_patterns,
-    ))
+pub fn import_discovered_scripts(state: State<AppState>, paths: Vec<String>) -> Result<DiscoveredImport, String> {
+    state
+        .storage
+        .import_discovered_scripts(&paths)
+        .map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -1701,15 +2568,113 @@ pub fn scan_installed_tools() -> Result<Vec<DiscoveredTool>, String> {
 // Help Parser / Auto-detect Parameters
 // ============================================================================
 
+/// Run the command's `--help` on a worker thread and parse the parameters.
+/// With a `token`, `cancel_parameter_detection` can stop it, killing the
+/// `--help` process; a cancelled detection returns `None`.
 #[tauri::command]
-pub fn auto_detect_script_params(command: String, script_path: Option<String>) -> Result<Vec<ScriptParameter>, String> {
+pub async fn auto_detect_script_params(
+    state: State<'_, AppState>,
+    command: String,
+    script_path: Option<String>,
+    token: Option<String>,
+) -> Result<Option<DetectedHelp>, String> {
     // Resolve {{SCRIPT_FILE}} placeholder before running --help
     let resolved = if let Some(ref path) = script_path {
         command.replace("{{SCRIPT_FILE}}", path)
     } else {
         command
     };
-    cortx_core::help_parser::detect_parameters(&resolved)
+
+    let detection = Arc::new(HelpDetection::new());
+    if let Some(ref token) = token {
+        state
+            .help_detections
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(token.clone(), detection.clone());
+    }
+    let worker = detection.clone();
+    let outcome = tauri::async_runtime::spawn_blocking(move || worker.run(&resolved))
+        .await
+        .map_err(|e| e.to_string());
+    if let Some(ref token) = token {
+        if let Ok(mut detections) = state.help_detections.lock() {
+            detections.remove(token);
+        }
+    }
+
+    match outcome?? {
+        HelpDetectionOutcome::Completed(detected) => Ok(Some(detected)),
+        HelpDetectionOutcome::Cancelled => Ok(None),
+    }
+}
+
+/// Run the command's `--help` and return the parsed parameters. A command
+/// that prints nothing (or hangs, and is killed after
+/// `help_parser::HELP_TIMEOUT`) yields no parameters rather than an error.
+/// With `merge_into`, the new parameters are also added to that script,
+/// leaving the ones it already has as they are, and the script's merged
+/// list is returned instead.
+#[tauri::command]
+pub async fn detect_script_parameters(
+    state: State<'_, AppState>,
+    command: String,
+    script_path: Option<String>,
+    merge_into: Option<String>,
+) -> Result<Vec<ScriptParameter>, String> {
+    let resolved = match script_path {
+        Some(ref path) => command.replace("{{SCRIPT_FILE}}", path),
+        None => command,
+    };
+    let storage = state.storage.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let detected = cortx_core::help_parser::detect_help(&resolved)?;
+        match merge_into {
+            Some(script_id) => cortx_core::help_parser::merge_into_script(&storage, &script_id, detected)
+                .map(|script| script.parameters),
+            None => Ok(detected.parameters),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Stop a running `auto_detect_script_params` and kill its `--help` process.
+#[tauri::command]
+pub fn cancel_parameter_detection(state: State<AppState>, token: String) -> Result<(), String> {
+    let detections = state.help_detections.lock().map_err(|e| e.to_string())?;
+    let detection = detections
+        .get(&token)
+        .ok_or_else(|| format!("No parameter detection running with token {}", token))?;
+    detection.cancel();
+    Ok(())
+}
+
+/// Re-run a script's `--help`: refreshes the stored raw help and adds any
+/// new parameters (existing ones are kept as edited).
+#[tauri::command]
+pub fn refresh_script_help(state: State<AppState>, id: String) -> Result<GlobalScript, String> {
+    cortx_core::help_parser::refresh_help(&state.storage, &id)
+}
+
+// ============================================================================
+// Run form fill commands
+// ============================================================================
+
+/// The clipboard's text when it's an existing path or a URL, for the run
+/// dialog's "use clipboard" chip on path parameters
+#[tauri::command]
+pub fn get_clipboard_text_if_pathlike(app_handle: AppHandle) -> Option<String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    let text = app_handle.clipboard().read_text().ok()?;
+    param_fill::pathlike(&text)
+}
+
+/// The value `param` gets from files dropped on the window
+/// (`file-dropped-for-param`), following its nargs
+#[tauri::command]
+pub fn join_dropped_paths(param: ScriptParameter, paths: Vec<String>) -> Option<String> {
+    param_fill::join_for_param(&param, &paths)
 }
 
 // ============================================================================
@@ -1914,6 +2879,71 @@ pub fn export_scripts_config(state: State<AppState>) -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
+/// Export through a native save dialog. Returns the written path, or `None`
+/// if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_scripts_config_dialog(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let settings = state.storage.get_settings();
+    let mut dialog = app_handle
+        .dialog()
+        .file()
+        .set_title("Export CortX data")
+        .set_file_name(export_file::default_export_filename(chrono::Local::now().date_naive()))
+        .add_filter("JSON", &["json"]);
+    if let Some(dir) = export_file::start_dir(settings.last_export_dir.as_deref()) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    let Some(picked) = dialog.blocking_save_file() else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| e.to_string())?;
+    state
+        .storage
+        .export_scripts_config_to(&path)
+        .map_err(|e| e.to_string())?;
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// Pick an import file through a native dialog and preview it. Returns
+/// `None` if the dialog was cancelled; nothing is imported here.
+#[tauri::command]
+pub async fn import_scripts_config_dialog(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<ImportFilePreview>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let settings = state.storage.get_settings();
+    let mut dialog = app_handle
+        .dialog()
+        .file()
+        .set_title("Import CortX data")
+        .add_filter("JSON", &["json"]);
+    if let Some(dir) = export_file::start_dir(settings.last_export_dir.as_deref()) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    let Some(picked) = dialog.blocking_pick_file() else {
+        return Ok(None);
+    };
+    let path = picked.into_path().map_err(|e| e.to_string())?;
+    let (json, summary) = state
+        .storage
+        .read_import_file(&path)
+        .map_err(|e| e.to_string())?;
+    Ok(Some(ImportFilePreview {
+        path: path.to_string_lossy().to_string(),
+        json,
+        summary,
+    }))
+}
+
 #[tauri::command]
 pub fn preview_import(json: String) -> Result<ExportSummary, String> {
     Storage::preview_import(&json).map_err(|e| e.to_string())
@@ -1931,6 +2961,88 @@ pub fn import_scripts_config(
         .map_err(|e| e.to_string())
 }
 
+/// Compare a newer version of an export with the scripts imported from it
+#[tauri::command]
+pub fn sync_with_export(state: State<AppState>, json: String) -> Result<SyncPlan, String> {
+    state.storage.sync_with_export(&json).map_err(|e| e.to_string())
+}
+
+/// Apply a sync plan; `resolutions` is keyed by source id
+#[tauri::command]
+pub fn apply_sync_plan(
+    state: State<AppState>,
+    plan: SyncPlan,
+    resolutions: std::collections::HashMap<String, SyncResolution>,
+) -> Result<SyncResult, String> {
+    state
+        .storage
+        .apply_sync_plan(&plan, &resolutions)
+        .map_err(|e| e.to_string())
+}
+
+/// Projects alone, as a portable JSON bundle (env values stripped)
+#[tauri::command]
+pub fn export_projects_config(state: State<AppState>) -> Result<String, String> {
+    state
+        .storage
+        .export_projects_config()
+        .map_err(|e| e.to_string())
+}
+
+/// Merge projects from `export_projects_config`; known ids are skipped and
+/// projects whose root is missing here come back in `needs_path`
+#[tauri::command]
+pub fn import_projects_config(state: State<AppState>, json: String) -> Result<ProjectImportResult, String> {
+    state
+        .storage
+        .import_projects_config(&json)
+        .map_err(|e| e.to_string())
+}
+
+/// Write one file per global script plus a manifest into `path`, for
+/// keeping scripts in git. Returns how many scripts were written.
+#[tauri::command]
+pub fn export_scripts_to_dir(state: State<AppState>, path: String) -> Result<usize, String> {
+    state
+        .storage
+        .export_scripts_to_dir(Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+/// Import scripts from a directory written by `export_scripts_to_dir`
+#[tauri::command]
+pub fn import_scripts_from_dir(state: State<AppState>, path: String) -> Result<ImportResult, String> {
+    state
+        .storage
+        .import_scripts_from_dir(Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn import_from_data_dir(
+    state: State<AppState>,
+    path: String,
+    options: ImportOptions,
+) -> Result<MigrationReport, String> {
+    state
+        .storage
+        .import_from_data_dir(Path::new(&path), &options)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn apply_path_mappings(
+    state: State<AppState>,
+    path: String,
+    options: ImportOptions,
+    mappings: Vec<PathMapping>,
+) -> Result<ImportResult, String> {
+    state
+        .storage
+        .apply_path_mappings(Path::new(&path), &options, &mappings)
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Git Backup
 // ============================================================================
@@ -1943,6 +3055,37 @@ pub fn backup_to_git(state: State<AppState>) -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// Zip Backups
+// ============================================================================
+
+#[tauri::command]
+pub fn create_backup(state: State<AppState>) -> Result<BackupInfo, String> {
+    state.storage.create_backup().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn list_backups(state: State<AppState>) -> Vec<BackupInfo> {
+    state.storage.list_backups()
+}
+
+/// Replace the data files with the backup at `path` (the current ones are
+/// backed up first)
+#[tauri::command]
+pub fn restore_backup(state: State<AppState>, path: String) -> Result<RestoreResult, String> {
+    let result = state
+        .storage
+        .restore_backup(Path::new(&path))
+        .map_err(|e| e.to_string())?;
+    // The restored settings may pick another shell / logging / run depth / echo
+    let settings = state.storage.get_settings();
+    state.process_manager.set_shell(settings.shell);
+    state.process_manager.set_logging(settings.logging);
+    state.process_manager.set_max_run_depth(settings.scripts_config.max_run_depth);
+    state.process_manager.set_echo_command(settings.scripts_config.echo_command);
+    Ok(result)
+}
+
 // ============================================================================
 // Execution History - Update Record on Exit
 // ============================================================================
@@ -1959,6 +3102,13 @@ pub fn update_execution_record(
         if record.finished_at.is_none() {
             let record_id = record.id.clone();
             let started_at = record.started_at;
+            let log_end = state.process_manager.runtime_store().log_len(&script_id);
+            let artifacts = match (success, &record.working_dir, state.storage.get_global_script(&script_id)) {
+                (true, Some(working_dir), Some(script)) if !script.artifacts.is_empty() => {
+                    script_artifacts::resolve_artifacts(Path::new(working_dir), &script.artifacts)
+                }
+                _ => Vec::new(),
+            };
             state
                 .storage
                 .update_execution_record(&record_id, |r| {
@@ -1968,6 +3118,8 @@ pub fn update_execution_record(
                     r.duration_ms = Some(
                         (Utc::now() - started_at).num_milliseconds().max(0) as u64
                     );
+                    r.log_end = Some(log_end);
+                    r.artifacts = artifacts;
                 })
                 .map_err(|e| e.to_string())?;
         }
@@ -6,7 +6,10 @@ mod tauri_emitter;
 
 use commands::AppState;
 use cortx_core::file_watcher;
-use cortx_core::runtime_state::RuntimeStore;
+use cortx_core::global_shortcuts::{self, ShortcutDispatch, ShortcutRegistry};
+use cortx_core::models::{AppSettings, ShortcutAction, ShortcutWarning, StartWait};
+use cortx_core::param_fill;
+use cortx_core::runtime_state::{EntityKind, RunningStatus, RuntimeStore};
 use process_manager::ProcessManager;
 use storage::Storage;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -15,6 +18,7 @@ use tauri::{AppHandle, Emitter, Manager};
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+use tauri_plugin_notification::NotificationExt;
 
 pub const DEFAULT_GLOBAL_HOTKEY: &str = "CmdOrCtrl+Shift+Space";
 
@@ -61,18 +65,133 @@ fn trigger_quit(app: &AppHandle) {
     }
 }
 
-/// (Re-)register the global palette hotkey. Empty / blank `combo` unregisters.
-pub fn register_hotkey(app: &AppHandle, combo: &str) -> Result<(), String> {
-    // Always clear before registering — keeps state consistent across calls.
-    let _ = app.global_shortcut().unregister_all();
-    let trimmed = combo.trim();
-    if trimmed.is_empty() {
-        return Ok(());
+/// Make the registered OS-wide shortcuts those `settings` asks for (the
+/// palette hotkey included), only (un)registering the ones that changed.
+/// Shortcuts that couldn't be registered are logged, kept for
+/// `get_global_shortcut_warnings` and, when they changed, sent as
+/// `global-shortcut-warnings`.
+pub fn apply_global_shortcuts(app: &AppHandle, settings: &AppSettings) -> Vec<ShortcutWarning> {
+    let Some(state) = app.try_state::<AppState>() else {
+        return Vec::new();
+    };
+    let wanted = global_shortcuts::wanted_shortcuts(settings, DEFAULT_GLOBAL_HOTKEY);
+    let warnings = {
+        let mut registry = state.shortcuts.lock().unwrap_or_else(|e| e.into_inner());
+        let manager = app.global_shortcut();
+        registry.update(
+            &wanted,
+            |accelerator| {
+                let shortcut: Shortcut = accelerator.to_string().parse().map_err(|e| format!("{}", e))?;
+                manager.register(shortcut).map_err(|e| e.to_string())
+            },
+            |accelerator| {
+                if let Ok(shortcut) = accelerator.to_string().parse::<Shortcut>() {
+                    let _ = manager.unregister(shortcut);
+                }
+            },
+        )
+    };
+
+    for warning in &warnings {
+        log::warn!("Global shortcut {} not registered: {}", warning.action, warning.message);
+    }
+    let changed = {
+        let mut last = state.shortcut_warnings.lock().unwrap_or_else(|e| e.into_inner());
+        let changed = *last != warnings;
+        *last = warnings.clone();
+        changed
+    };
+    if changed {
+        let _ = app.emit("global-shortcut-warnings", &warnings);
+    }
+    warnings
+}
+
+/// The action of the registered shortcut that just fired
+fn shortcut_action(app: &AppHandle, shortcut: &Shortcut) -> Option<ShortcutAction> {
+    let state = app.try_state::<AppState>()?;
+    let registry = state.shortcuts.lock().ok()?;
+    registry.find(|accelerator| {
+        accelerator
+            .to_string()
+            .parse::<Shortcut>()
+            .map(|s| s.id() == shortcut.id())
+            .unwrap_or(false)
+    })
+}
+
+/// Show a system notification; failures are only logged
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Could not show notification '{}': {}", title, e);
+    }
+}
+
+/// Run what a global shortcut is for. Stopping and starting services run on
+/// a worker thread and end with a notification of what they did.
+fn run_shortcut_action(app: &AppHandle, action: ShortcutAction) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let projects = state.storage.get_all_projects();
+    let dispatch = match global_shortcuts::dispatch(&action, &projects) {
+        Ok(dispatch) => dispatch,
+        Err(e) => {
+            notify(app, "CortX", &e);
+            return;
+        }
+    };
+
+    match dispatch {
+        ShortcutDispatch::OpenPalette => {
+            show_main_window(app);
+            let _ = app.emit("open-command-palette", ());
+        }
+        ShortcutDispatch::ToggleWindow => toggle_main_window(app),
+        ShortcutDispatch::StopAll => {
+            // Each project's services in its stop order
+            let service_order: Vec<String> = projects
+                .iter()
+                .flat_map(|p| cortx_core::service_order::stop_order(&p.services))
+                .map(|s| s.id.clone())
+                .collect();
+            let app = app.clone();
+            let storage = state.storage.clone();
+            let process_manager = state.process_manager.clone();
+            std::thread::spawn(move || {
+                let emitter = tauri_emitter::ServiceHistoryEmitter::new(app.clone(), storage);
+                let summary = process_manager.stop_owned(&emitter, &service_order);
+                notify(&app, "CortX: stopped everything", &summary.message());
+                let _ = app.emit("stop-all-complete", &summary);
+            });
+        }
+        ShortcutDispatch::StartProject { project_id, name } => {
+            let app = app.clone();
+            let storage = state.storage.clone();
+            let process_manager = state.process_manager.clone();
+            std::thread::spawn(move || {
+                let results = commands::start_project_services(
+                    app.clone(),
+                    &storage,
+                    &process_manager,
+                    &project_id,
+                    StartWait::default(),
+                );
+                let body = match results {
+                    Ok(results) => {
+                        let started = results.iter().filter(|r| r.pid.is_some()).count();
+                        let failed = results.iter().filter(|r| r.error.is_some()).count();
+                        match failed {
+                            0 => format!("Started {} service(s)", started),
+                            _ => format!("Started {} service(s), {} failed", started, failed),
+                        }
+                    }
+                    Err(e) => e,
+                };
+                notify(&app, &format!("CortX: {}", name), &body);
+            });
+        }
     }
-    let shortcut: Shortcut = trimmed.parse().map_err(|e| format!("Invalid hotkey '{}': {}", trimmed, e))?;
-    app.global_shortcut()
-        .register(shortcut)
-        .map_err(|e| e.to_string())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -83,11 +202,20 @@ pub fn run() {
         RuntimeStore::new(storage.app_dir()).expect("Failed to initialize runtime store"),
     );
     let process_manager = ProcessManager::new(runtime_store);
+    let settings = storage.get_settings();
+    process_manager.set_shell(settings.shell);
+    process_manager.set_logging(settings.logging);
+    process_manager.set_max_run_depth(settings.scripts_config.max_run_depth);
+    process_manager.set_echo_command(settings.scripts_config.echo_command);
 
     let app_state = AppState {
         storage: Arc::new(storage),
         process_manager: Arc::new(process_manager),
         quitting: Arc::new(AtomicBool::new(false)),
+        env_discoveries: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        help_detections: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        shortcuts: Arc::new(std::sync::Mutex::new(ShortcutRegistry::default())),
+        shortcut_warnings: Arc::new(std::sync::Mutex::new(Vec::new())),
     };
 
     #[allow(unused_mut)]
@@ -97,17 +225,15 @@ pub fn run() {
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, _shortcut, event| {
+                .with_handler(|app, shortcut, event| {
                     use tauri_plugin_global_shortcut::ShortcutState;
                     if event.state == ShortcutState::Pressed {
-                        if let Some(win) = app.get_webview_window("main") {
-                            let _ = win.show();
-                            let _ = win.unminimize();
-                            let _ = win.set_focus();
+                        if let Some(action) = shortcut_action(app, shortcut) {
+                            run_shortcut_action(app, action);
                         }
-                        let _ = app.emit("open-command-palette", ());
                     }
                 })
                 .build(),
@@ -148,7 +274,16 @@ pub fn run() {
             // Start file watcher for cross-process data sync
             let app_handle = app.handle().clone();
             let state: tauri::State<AppState> = app.state();
+
+            // One event per saved mutation (project-changed, ...), ids only,
+            // and save-status-changed when a save fails or goes through again
+            let change_handle = app.handle().clone();
+            state.storage.subscribe(move |event| {
+                let _ = change_handle.emit(event.name(), &event);
+            });
+
             let storage_ref = state.storage.clone();
+            let process_manager_ref = state.process_manager.clone();
             let watch_dir = storage_ref.app_dir().to_path_buf();
 
             let watcher_handle = file_watcher::start_watching(watch_dir, move |_changed| {
@@ -159,21 +294,34 @@ pub fn run() {
                     log::error!("File watcher reload failed: {}", e);
                     return;
                 }
+                // Settings may have been edited by the CLI; keep the spawn shell,
+                // log persistence, run depth limit and command echo in sync
+                let settings = storage_ref.get_settings();
+                process_manager_ref.set_shell(settings.shell);
+                process_manager_ref.set_logging(settings.logging);
+                process_manager_ref.set_max_run_depth(settings.scripts_config.max_run_depth);
+                process_manager_ref.set_echo_command(settings.scripts_config.echo_command);
+                // On the main thread, like every other (un)registration: the
+                // plugin hands those to it and its handler locks the registry
+                let shortcuts_handle = app_handle.clone();
+                let _ = app_handle.run_on_main_thread(move || {
+                    let settings = shortcuts_handle.state::<AppState>().storage.get_settings();
+                    apply_global_shortcuts(&shortcuts_handle, &settings);
+                });
                 let _ = app_handle.emit("data-changed", ());
             })?;
 
             // Keep watcher alive for the lifetime of the app
             app.manage(watcher_handle);
 
-            // Register the global hotkey from persisted settings (or default).
-            let combo = state
-                .storage
-                .get_settings()
-                .global_hotkey
-                .unwrap_or_else(|| DEFAULT_GLOBAL_HOTKEY.to_string());
-            if let Err(e) = register_hotkey(app.handle(), &combo) {
-                log::warn!("Could not register global hotkey '{}': {}", combo, e);
-            }
+            // Run global scripts on their schedules until stop_all
+            let scheduler_emitter: Arc<dyn process_manager::ProcessEventEmitter> =
+                Arc::new(tauri_emitter::TauriEmitter::new(app.handle().clone()));
+            cortx_core::scheduler::spawn(&state.process_manager, state.storage.clone(), scheduler_emitter);
+
+            // Register the global hotkey (or its default) and the other
+            // global shortcuts from persisted settings
+            apply_global_shortcuts(app.handle(), &state.storage.get_settings());
 
             // System tray icon — keeps the app alive after the window is
             // hidden via X, and provides Show / Open Palette / Quit actions.
@@ -218,6 +366,11 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
+            // Files dropped on the window go to the open run form, if any
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                let _ = window.emit("file-dropped-for-param", param_fill::dropped_payload(paths.as_slice()));
+                return;
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let app_handle = window.app_handle().clone();
                 let is_quitting = app_handle
@@ -249,6 +402,12 @@ pub fn run() {
                             std::thread::sleep(std::time::Duration::from_millis(100));
                         }
                         log::info!("Stopping all services...");
+                        // stop_all reports nothing, so close their history runs here
+                        for process in state.process_manager.list_running() {
+                            if process.target_kind == EntityKind::Service && process.status == RunningStatus::Running {
+                                let _ = state.storage.record_service_stopped(&process.target_id, true);
+                            }
+                        }
                         state.process_manager.stop_all();
                         log::info!("All services stopped, closing window...");
                     }
@@ -266,9 +425,13 @@ pub fn run() {
             commands::update_project,
             commands::delete_project,
             commands::update_project_last_opened,
+            commands::add_project_variant,
+            commands::remove_project_variant,
+            commands::set_active_variant,
             // Service commands
             commands::add_service,
             commands::update_service,
+            commands::update_and_restart_service,
             commands::delete_service,
             commands::reorder_services,
             // Script commands
@@ -283,38 +446,70 @@ pub fn run() {
             commands::get_launch_command,
             commands::launch_external_terminal,
             commands::start_integrated_service,
+            commands::run_service_group,
+            commands::start_all_services,
+            commands::stop_all_services,
             commands::stop_integrated_service,
             commands::is_service_running,
             commands::get_running_services,
+            commands::list_running,
+            commands::count_running,
+            commands::get_service_uptime,
+            commands::get_service_health,
+            commands::get_service_circuit,
+            commands::reset_service_circuit,
+            commands::get_effective_env,
+            commands::get_service_log_files,
+            commands::read_service_log,
             // Settings commands
             commands::get_settings,
             commands::update_settings,
+            commands::get_pending_saves,
+            commands::retry_pending_saves,
             commands::set_global_hotkey,
+            commands::get_global_shortcut_warnings,
             commands::quit_app,
             // Utility commands
             commands::open_in_explorer,
+            commands::reveal_in_explorer,
             commands::open_in_vscode,
             commands::validate_path,
             // Environment file commands
             commands::discover_env_files,
+            commands::cancel_env_discovery,
             commands::add_env_file,
             commands::remove_env_file,
             commands::refresh_env_file,
             commands::refresh_all_env_files,
             commands::get_env_files,
             commands::get_env_file_content,
+            commands::update_env_variable,
+            commands::add_env_variable,
+            commands::remove_env_variable,
             commands::compare_env_files,
+            commands::compare_all_env_files,
+            commands::scaffold_env_from_example,
+            commands::find_duplicate_env_keys,
+            commands::update_env_file,
             commands::link_env_to_service,
             // Global script commands
             commands::get_all_global_scripts,
             commands::get_global_script,
             commands::create_global_script,
+            commands::validate_global_script,
             commands::update_global_script,
             commands::delete_global_script,
             commands::reorder_global_scripts,
+            commands::apply_organize_rules,
             commands::run_global_script,
+            commands::run_script_dag,
+            commands::check_script_requirements,
+            commands::replay_execution,
             commands::stop_global_script,
             commands::is_global_script_running,
+            commands::stop_process,
+            commands::is_process_running,
+            commands::get_effective_env,
             // Tag definition commands
             commands::get_all_tag_definitions,
             commands::create_tag_definition,
@@ -322,18 +517,40 @@ pub fn run() {
             commands::delete_tag_definition,
             // Execution history commands
             commands::get_execution_history,
+            commands::get_service_history,
+            commands::compare_runs,
             commands::clear_execution_history,
             // Scripts config commands
             commands::get_scripts_config,
             commands::update_scripts_config,
             commands::scan_scripts_folder,
+            commands::import_discovered_scripts,
             // Help parser commands
             commands::auto_detect_script_params,
+            commands::detect_script_parameters,
+            commands::cancel_parameter_detection,
+            commands::refresh_script_help,
+            // Run form fill commands
+            commands::get_clipboard_text_if_pathlike,
+            commands::join_dropped_paths,
             // Import / Export / Backup
             commands::export_scripts_config,
+            commands::export_scripts_config_dialog,
+            commands::import_scripts_config_dialog,
             commands::preview_import,
             commands::import_scripts_config,
+            commands::sync_with_export,
+            commands::apply_sync_plan,
+            commands::export_projects_config,
+            commands::import_projects_config,
+            commands::export_scripts_to_dir,
+            commands::import_scripts_from_dir,
+            commands::import_from_data_dir,
+            commands::apply_path_mappings,
             commands::backup_to_git,
+            commands::create_backup,
+            commands::list_backups,
+            commands::restore_backup,
             // Tool commands
             commands::get_all_tools,
             commands::get_tool,
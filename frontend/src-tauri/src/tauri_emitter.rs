@@ -1,9 +1,12 @@
+use chrono::{DateTime, Utc};
 use cortx_core::models::{
     LogStream, ScriptExitPayload, ScriptLogPayload, ScriptStatus, ScriptStatusPayload,
     ServiceExitPayload, ServiceLogPayload, ServicePortsPayload, ServiceStatus,
     ServiceStatusPayload,
 };
-use cortx_core::process_manager::ProcessEventEmitter;
+use cortx_core::process_manager::{split_run_key, ProcessEventEmitter};
+use cortx_core::storage::Storage;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 
 pub struct TauriEmitter {
@@ -24,6 +27,7 @@ impl ProcessEventEmitter for TauriEmitter {
                 service_id: service_id.to_string(),
                 stream,
                 content,
+                timestamp: Utc::now(),
             },
         );
     }
@@ -35,6 +39,7 @@ impl ProcessEventEmitter for TauriEmitter {
         pid: Option<u32>,
         active_mode: Option<String>,
         active_arg_preset: Option<String>,
+        started_at: Option<DateTime<Utc>>,
     ) {
         let _ = self.app_handle.emit(
             "service-status",
@@ -44,16 +49,18 @@ impl ProcessEventEmitter for TauriEmitter {
                 pid,
                 active_mode,
                 active_arg_preset,
+                started_at,
             },
         );
     }
 
-    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>) {
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>) {
         let _ = self.app_handle.emit(
             "service-exit",
             ServiceExitPayload {
                 service_id: service_id.to_string(),
                 exit_code,
+                error_excerpt,
             },
         );
     }
@@ -65,6 +72,8 @@ impl ProcessEventEmitter for TauriEmitter {
                 script_id: script_id.to_string(),
                 stream,
                 content,
+                timestamp: Utc::now(),
+                run_id: None,
             },
         );
     }
@@ -76,6 +85,7 @@ impl ProcessEventEmitter for TauriEmitter {
                 script_id: script_id.to_string(),
                 status,
                 pid,
+                run_id: None,
             },
         );
     }
@@ -87,39 +97,47 @@ impl ProcessEventEmitter for TauriEmitter {
                 script_id: script_id.to_string(),
                 exit_code,
                 success,
+                run_id: None,
             },
         );
     }
 
     fn emit_global_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.app_handle.emit(
             "global-script-log",
             ScriptLogPayload {
                 script_id: script_id.to_string(),
                 stream,
                 content,
+                timestamp: Utc::now(),
+                run_id: run_id.map(String::from),
             },
         );
     }
 
     fn emit_global_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.app_handle.emit(
             "global-script-status",
             ScriptStatusPayload {
                 script_id: script_id.to_string(),
                 status,
                 pid,
+                run_id: run_id.map(String::from),
             },
         );
     }
 
     fn emit_global_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        let (script_id, run_id) = split_run_key(script_id);
         let _ = self.app_handle.emit(
             "global-script-exit",
             ScriptExitPayload {
                 script_id: script_id.to_string(),
                 exit_code,
                 success,
+                run_id: run_id.map(String::from),
             },
         );
     }
@@ -134,3 +152,89 @@ impl ProcessEventEmitter for TauriEmitter {
         );
     }
 }
+
+/// A [`TauriEmitter`] that also keeps the execution history of services:
+/// a run is recorded when a service reports running, and closed when it
+/// stops or exits. Used wherever services are started or stopped.
+pub struct ServiceHistoryEmitter {
+    inner: TauriEmitter,
+    storage: Arc<Storage>,
+}
+
+impl ServiceHistoryEmitter {
+    pub fn new(app_handle: AppHandle, storage: Arc<Storage>) -> Self {
+        Self { inner: TauriEmitter::new(app_handle), storage }
+    }
+}
+
+impl ProcessEventEmitter for ServiceHistoryEmitter {
+    fn emit_service_log(&self, service_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_service_log(service_id, stream, content);
+    }
+
+    fn emit_service_status(
+        &self,
+        service_id: &str,
+        status: ServiceStatus,
+        pid: Option<u32>,
+        active_mode: Option<String>,
+        active_arg_preset: Option<String>,
+        started_at: Option<DateTime<Utc>>,
+    ) {
+        let recorded = match status {
+            ServiceStatus::Running => self.storage.record_service_started(
+                service_id,
+                started_at.unwrap_or_else(Utc::now),
+                active_mode.as_deref(),
+                active_arg_preset.as_deref(),
+            ),
+            ServiceStatus::Stopped => self.storage.record_service_stopped(service_id, true),
+            // A crash, whether or not it's restarted
+            ServiceStatus::Error | ServiceStatus::Restarting | ServiceStatus::CircuitOpen => {
+                self.storage.record_service_stopped(service_id, false)
+            }
+            // Still the same run
+            ServiceStatus::Starting | ServiceStatus::Unhealthy | ServiceStatus::Stopping => Ok(()),
+        };
+        if let Err(e) = recorded {
+            log::warn!("Failed to record run of service {}: {}", service_id, e);
+        }
+        self.inner
+            .emit_service_status(service_id, status, pid, active_mode, active_arg_preset, started_at);
+    }
+
+    fn emit_service_exit(&self, service_id: &str, exit_code: Option<i32>, error_excerpt: Option<String>) {
+        if let Err(e) = self.storage.record_service_exit(service_id, exit_code) {
+            log::warn!("Failed to record exit of service {}: {}", service_id, e);
+        }
+        self.inner.emit_service_exit(service_id, exit_code, error_excerpt);
+    }
+
+    fn emit_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_script_log(script_id, stream, content);
+    }
+
+    fn emit_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        self.inner.emit_script_status(script_id, status, pid);
+    }
+
+    fn emit_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        self.inner.emit_script_exit(script_id, exit_code, success);
+    }
+
+    fn emit_global_script_log(&self, script_id: &str, stream: LogStream, content: String) {
+        self.inner.emit_global_script_log(script_id, stream, content);
+    }
+
+    fn emit_global_script_status(&self, script_id: &str, status: ScriptStatus, pid: Option<u32>) {
+        self.inner.emit_global_script_status(script_id, status, pid);
+    }
+
+    fn emit_global_script_exit(&self, script_id: &str, exit_code: Option<i32>, success: bool) {
+        self.inner.emit_global_script_exit(script_id, exit_code, success);
+    }
+
+    fn emit_service_ports(&self, service_id: &str, ports: Vec<u16>) {
+        self.inner.emit_service_ports(service_id, ports);
+    }
+}
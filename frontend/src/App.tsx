@@ -2,6 +2,7 @@ import { useEffect, useRef, useState } from 'react';
 import { SidebarProvider, SidebarInset, SidebarTrigger } from '@/components/ui/sidebar';
 import { TooltipProvider } from '@/components/ui/tooltip';
 import { Toaster } from '@/components/ui/sonner';
+import { toast } from 'sonner';
 import { TitleBar } from '@/components/layout/TitleBar';
 import { AppSidebar } from '@/components/layout/AppSidebar';
 import { TerminalPanel } from '@/components/layout/TerminalPanel';
@@ -36,6 +37,7 @@ import {
   onDataChanged,
   onOpenCommandPalette,
   getRunningServices,
+  getGlobalShortcutWarnings,
 } from '@/lib/tauri';
 import type { LogEntry } from '@/types';
 
@@ -114,6 +116,11 @@ function App() {
         updateServiceStatus(serviceId, 'running');
       });
     });
+
+    // OS-wide shortcuts the settings ask for but that couldn't be registered
+    getGlobalShortcutWarnings().then((warnings) => {
+      warnings.forEach((w) => toast.warning('Global shortcut not registered', { description: w.message }));
+    });
   }, [loadProjects, loadSettings, loadGlobalScripts, loadTagDefinitions, loadScriptsConfig, loadTools, loadAliases, loadStatusDefinitions, loadApps]);
 
   // Set up event listeners - only once
@@ -145,7 +152,7 @@ function App() {
         if (isCancelled) return;
         const { appendServiceLog } = useAppStore.getState();
         const logEntry: LogEntry = {
-          timestamp: new Date().toISOString(),
+          timestamp: payload.timestamp ?? new Date().toISOString(),
           stream: payload.stream,
           content: payload.content,
         };
@@ -155,12 +162,21 @@ function App() {
       unlistenServiceStatus = await onServiceStatus((payload) => {
         if (isCancelled) return;
         const { updateServiceStatus } = useAppStore.getState();
-        updateServiceStatus(payload.serviceId, payload.status, payload.pid, payload.activeMode, payload.activeArgPreset);
+        updateServiceStatus(payload.serviceId, payload.status, payload.pid, payload.activeMode, payload.activeArgPreset, payload.startedAt);
       });
 
       unlistenServiceExit = await onServiceExit((payload) => {
         if (isCancelled) return;
         console.log(`Service ${payload.serviceId} exited with code ${payload.exitCode}`);
+        if (payload.errorExcerpt !== undefined) {
+          const service = useAppStore
+            .getState()
+            .projects.flatMap((p) => p.services)
+            .find((s) => s.id === payload.serviceId);
+          toast.error(`${service?.name ?? 'Service'} failed to start (exit ${payload.exitCode ?? '?'})`, {
+            description: payload.errorExcerpt || 'No error output',
+          });
+        }
       });
 
       unlistenServicePorts = await onServicePorts((payload) => {
@@ -174,7 +190,7 @@ function App() {
         if (isCancelled) return;
         const { appendScriptLog } = useAppStore.getState();
         const logEntry: LogEntry = {
-          timestamp: new Date().toISOString(),
+          timestamp: payload.timestamp ?? new Date().toISOString(),
           stream: payload.stream,
           content: payload.content,
         };
@@ -199,7 +215,7 @@ function App() {
         if (isCancelled) return;
         const { appendGlobalScriptLog } = useAppStore.getState();
         const logEntry: LogEntry = {
-          timestamp: new Date().toISOString(),
+          timestamp: payload.timestamp ?? new Date().toISOString(),
           stream: payload.stream,
           content: payload.content,
         };
@@ -208,12 +224,15 @@ function App() {
 
       unlistenGlobalScriptStatus = await onGlobalScriptStatus((payload) => {
         if (isCancelled) return;
+        // The status shown is the first run's; concurrent runs only add output
+        if (payload.runId) return;
         const { updateGlobalScriptStatus } = useAppStore.getState();
         updateGlobalScriptStatus(payload.scriptId, payload.status, payload.pid);
       });
 
       unlistenGlobalScriptExit = await onGlobalScriptExit((payload) => {
         if (isCancelled) return;
+        if (payload.runId) return;
         console.log(`Global script ${payload.scriptId} exited with code ${payload.exitCode}, success: ${payload.success}`);
         const { setGlobalScriptExitResult, updateExecutionRecordOnExit } = useAppStore.getState();
         setGlobalScriptExitResult(payload.scriptId, payload.exitCode, payload.success);
@@ -11,6 +11,7 @@ import {
 } from '@/components/ui/command';
 import { useAppStore } from '@/stores/appStore';
 import { toast } from 'sonner';
+import { runErrorMessage } from '@/lib/utils';
 
 import { buildEntities } from './buildEntities';
 import { buildItemValue, commandFilter, parseQuery } from './searchFilter';
@@ -63,7 +64,7 @@ export function CommandPalette({ open, onOpenChange }: CommandPaletteProps) {
       try {
         await action.run();
       } catch (err) {
-        toast.error(`Failed: ${err}`);
+        toast.error(`Failed: ${runErrorMessage(err)}`);
       }
     },
     [closeAndReset],
@@ -18,6 +18,7 @@ import { createElement, type ReactNode } from 'react';
 
 import { openAppUrl, openInExplorer, openInVscode, openToolUrl } from '@/lib/tauri';
 import type { useAppStore } from '@/stores/appStore';
+import { effectiveRoot, isServiceUp } from '@/lib/utils';
 
 import { SHORTCUTS } from './shortcuts';
 import type { CommandEntity, EntityAction } from './types';
@@ -117,7 +118,7 @@ export function buildEntities(store: Store): CommandEntity[] {
   for (const project of store.projects) {
     for (const service of project.services) {
       const runtime = store.serviceRuntimes.get(service.id);
-      const isRunning = runtime?.status === 'running' || runtime?.status === 'starting';
+      const isRunning = isServiceUp(runtime?.status) || runtime?.status === 'starting';
 
       const primary: EntityAction = isRunning
         ? {
@@ -200,7 +201,7 @@ export function buildEntities(store: Store): CommandEntity[] {
         run: () => {
           for (const svc of services) {
             const rt = store.serviceRuntimes.get(svc.id);
-            const running = rt?.status === 'running' || rt?.status === 'starting';
+            const running = isServiceUp(rt?.status) || rt?.status === 'starting';
             if (!running) {
               void store.startService(svc.id);
             }
@@ -219,14 +220,14 @@ export function buildEntities(store: Store): CommandEntity[] {
         label: 'Open Folder',
         icon: createElement(FolderOpen, { className: iconSize }),
         shortcut: SHORTCUTS.openFolder,
-        run: () => openInExplorer(project.rootPath),
+        run: () => openInExplorer(effectiveRoot(project)),
       },
       {
         id: 'open-vscode',
         label: 'Open in VS Code',
         icon: createElement(Code, { className: iconSize }),
         shortcut: SHORTCUTS.openVSCode,
-        run: () => openInVscode(project.rootPath),
+        run: () => openInVscode(effectiveRoot(project)),
       },
     ];
     entities.push({
@@ -1,17 +1,21 @@
 import type { EnvComparison } from '@/types';
 import { Badge } from '@/components/ui/badge';
+import { Button } from '@/components/ui/button';
 import { CheckCircle, AlertTriangle, Info } from 'lucide-react';
 
 interface EnvComparisonBannerProps {
   comparison: EnvComparison;
   baseFileName: string;
   exampleFileName: string;
+  /** Append the missing keys, empty, to the base file */
+  onAddMissing?: () => void;
 }
 
 export function EnvComparisonBanner({
   comparison,
   baseFileName,
   exampleFileName,
+  onAddMissing,
 }: EnvComparisonBannerProps) {
   const hasMissing = comparison.missingInBase.length > 0;
   const hasExtra = comparison.extraInBase.length > 0;
@@ -36,6 +40,11 @@ export function EnvComparisonBanner({
             <span className="text-sm font-medium text-amber-700 dark:text-amber-400">
               Missing in {baseFileName} ({comparison.missingInBase.length})
             </span>
+            {onAddMissing && (
+              <Button variant="outline" size="sm" className="ml-auto h-6 text-xs" onClick={onAddMissing}>
+                Add missing keys
+              </Button>
+            )}
           </div>
           <div className="flex flex-wrap gap-1">
             {comparison.missingInBase.map((key) => (
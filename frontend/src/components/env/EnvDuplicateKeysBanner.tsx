@@ -0,0 +1,34 @@
+import { Badge } from '@/components/ui/badge';
+import { AlertTriangle } from 'lucide-react';
+
+interface EnvDuplicateKeysBannerProps {
+  /** `[key, ["filename=value", ...]]` */
+  duplicates: [string, string[]][];
+}
+
+export function EnvDuplicateKeysBanner({ duplicates }: EnvDuplicateKeysBannerProps) {
+  if (duplicates.length === 0) return null;
+
+  return (
+    <div className="p-3 bg-amber-500/10 rounded-lg">
+      <div className="flex items-center gap-2 mb-2">
+        <AlertTriangle className="size-4 text-amber-600 dark:text-amber-400 flex-shrink-0" />
+        <span className="text-sm font-medium text-amber-700 dark:text-amber-400">
+          Defined differently in several files ({duplicates.length}) — the effective value depends on load order
+        </span>
+      </div>
+      <div className="space-y-1">
+        {duplicates.map(([key, definitions]) => (
+          <div key={key} className="flex flex-wrap items-center gap-1">
+            <span className="font-mono text-xs font-medium mr-1">{key}</span>
+            {definitions.map((definition) => (
+              <Badge key={definition} variant="outline" className="font-mono text-xs">
+                {definition}
+              </Badge>
+            ))}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
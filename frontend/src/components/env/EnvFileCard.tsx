@@ -7,7 +7,16 @@ import { Collapsible, CollapsibleContent, CollapsibleTrigger } from '@/component
 import { Tooltip, TooltipContent, TooltipTrigger } from '@/components/ui/tooltip';
 import { TruncatedText } from '@/components/ui/TruncatedText';
 import { ScrollArea } from '@/components/ui/scroll-area';
-import type { EnvFile, EnvComparison, Service } from '@/types';
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuRadioGroup,
+  DropdownMenuRadioItem,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from '@/components/ui/dropdown-menu';
+import type { EnvFile, EnvFileVariant, EnvComparison, Service } from '@/types';
 import { EnvVariableRow } from './EnvVariableRow';
 import { EnvComparisonBanner } from './EnvComparisonBanner';
 import { getEnvFileContent } from '@/lib/tauri';
@@ -24,6 +33,7 @@ import {
   Download,
   List,
   Code,
+  Tag,
 } from 'lucide-react';
 import { toast } from 'sonner';
 
@@ -66,7 +76,14 @@ export function EnvFileCard({ envFile, projectId, services, exampleFile }: EnvFi
   const [rawContent, setRawContent] = useState<string | null>(null);
   const [isLoadingRaw, setIsLoadingRaw] = useState(false);
 
-  const { refreshEnvFile, removeEnvFile, compareEnvFiles, envFileComparisons } = useAppStore();
+  const {
+    refreshEnvFile,
+    removeEnvFile,
+    compareEnvFiles,
+    envFileComparisons,
+    updateEnvFileVariant,
+    scaffoldEnvFromExample,
+  } = useAppStore();
 
   const linkedService = services.find((s) => s.id === envFile.linkedServiceId);
 
@@ -101,6 +118,26 @@ export function EnvFileCard({ envFile, projectId, services, exampleFile }: EnvFi
     }
   };
 
+  const handleAddMissing = async () => {
+    if (!exampleFile) return;
+    try {
+      await scaffoldEnvFromExample(projectId, exampleFile.id, envFile.path);
+      setComparison(await compareEnvFiles(projectId, envFile.id, exampleFile.id));
+      toast.success(`Added the keys missing from ${exampleFile.filename}`);
+    } catch (error) {
+      toast.error(`Failed to add missing keys: ${error}`);
+    }
+  };
+
+  const handleSetVariant = async (variant: EnvFileVariant | null) => {
+    try {
+      await updateEnvFileVariant(projectId, envFile.id, variant);
+      toast.success(variant ? `Variant set to ${variantLabels[variant]}` : 'Variant auto-detected');
+    } catch (error) {
+      toast.error(`Failed to update variant: ${error}`);
+    }
+  };
+
   const handleRemove = async () => {
     try {
       await removeEnvFile(projectId, envFile.id);
@@ -204,6 +241,7 @@ export function EnvFileCard({ envFile, projectId, services, exampleFile }: EnvFi
                   <TruncatedText className="font-medium font-mono min-w-0">{envFile.filename}</TruncatedText>
                   <Badge className={`${variantColors[envFile.variant]} flex-shrink-0`}>
                     {variantLabels[envFile.variant]}
+                    {envFile.variantOverridden && '*'}
                   </Badge>
                   {envFile.isManuallyAdded && (
                     <Badge variant="outline" className="text-xs flex-shrink-0">
@@ -252,6 +290,38 @@ export function EnvFileCard({ envFile, projectId, services, exampleFile }: EnvFi
                   </TooltipContent>
                 </Tooltip>
 
+                <DropdownMenu>
+                  <Tooltip>
+                    <TooltipTrigger asChild>
+                      <DropdownMenuTrigger asChild>
+                        <Button variant="ghost" size="icon-sm">
+                          <Tag className="size-3.5" />
+                        </Button>
+                      </DropdownMenuTrigger>
+                    </TooltipTrigger>
+                    <TooltipContent>Set variant</TooltipContent>
+                  </Tooltip>
+                  <DropdownMenuContent align="end">
+                    <DropdownMenuRadioGroup
+                      value={envFile.variantOverridden ? envFile.variant : ''}
+                      onValueChange={(value) => handleSetVariant(value as EnvFileVariant)}
+                    >
+                      {Object.entries(variantLabels).map(([value, label]) => (
+                        <DropdownMenuRadioItem key={value} value={value}>
+                          {label}
+                        </DropdownMenuRadioItem>
+                      ))}
+                    </DropdownMenuRadioGroup>
+                    <DropdownMenuSeparator />
+                    <DropdownMenuItem
+                      disabled={!envFile.variantOverridden}
+                      onClick={() => handleSetVariant(null)}
+                    >
+                      Auto-detect from filename
+                    </DropdownMenuItem>
+                  </DropdownMenuContent>
+                </DropdownMenu>
+
                 <Tooltip>
                   <TooltipTrigger asChild>
                     <Button
@@ -321,6 +391,7 @@ export function EnvFileCard({ envFile, projectId, services, exampleFile }: EnvFi
                   comparison={comparison}
                   baseFileName={envFile.filename}
                   exampleFileName={exampleFile.filename}
+                  onAddMissing={handleAddMissing}
                 />
               </div>
             )}
@@ -0,0 +1,132 @@
+import { useState, useEffect } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Badge } from '@/components/ui/badge';
+import { Checkbox } from '@/components/ui/checkbox';
+import { compareAllEnvFiles } from '@/lib/tauri';
+import type { EnvMatrix, EnvMatrixRow, Project } from '@/types';
+
+interface EnvMatrixDialogProps {
+  project: Project;
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+// Worth a look: values that disagree, a key some file lacks, or a key a .env
+// is missing from its .env.example
+function isNotable(row: EnvMatrixRow): boolean {
+  return row.valuesDiffer || row.values.some((v) => v === null) || (row.missingInBase?.length ?? 0) > 0;
+}
+
+export function EnvMatrixDialog({ project, open, onOpenChange }: EnvMatrixDialogProps) {
+  const [matrix, setMatrix] = useState<EnvMatrix | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [onlyNotable, setOnlyNotable] = useState(false);
+
+  useEffect(() => {
+    if (!open) return;
+    setError(null);
+    compareAllEnvFiles(project.id)
+      .then(setMatrix)
+      .catch((e) => setError(String(e)));
+  }, [open, project.id, project.envFiles]);
+
+  const files = (matrix?.fileIds ?? []).map((id) => project.envFiles.find((f) => f.id === id));
+  const rows = matrix?.rows.filter((row) => !onlyNotable || isNotable(row)) ?? [];
+  const missing = matrix?.rows.filter((row) => (row.missingInBase?.length ?? 0) > 0).length ?? 0;
+  const differing = matrix?.rows.filter((row) => row.valuesDiffer).length ?? 0;
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-5xl max-h-[85vh] flex flex-col">
+        <DialogHeader>
+          <DialogTitle>Compare All Env Files</DialogTitle>
+          <DialogDescription>
+            Every variable of the project's {project.envFiles.length} env file(s). Example files are
+            left out when comparing values.
+          </DialogDescription>
+        </DialogHeader>
+
+        {error ? (
+          <p className="text-sm text-destructive">{error}</p>
+        ) : matrix && (
+          <>
+            <div className="flex items-center gap-2 text-xs">
+              <Badge variant="secondary">{matrix.rows.length} keys</Badge>
+              {differing > 0 && (
+                <Badge variant="outline" className="text-amber-600 dark:text-amber-400">
+                  {differing} differ
+                </Badge>
+              )}
+              {missing > 0 && (
+                <Badge variant="outline" className="text-destructive">
+                  {missing} missing from .env
+                </Badge>
+              )}
+              <label className="flex items-center gap-2 ml-auto cursor-pointer">
+                <Checkbox checked={onlyNotable} onCheckedChange={(checked) => setOnlyNotable(checked === true)} />
+                Only differences
+              </label>
+            </div>
+
+            <div className="flex-1 min-h-0 overflow-auto border rounded-md">
+              <table className="w-full text-xs">
+                <thead className="sticky top-0 bg-background">
+                  <tr className="text-muted-foreground text-left">
+                    <th className="font-normal p-2">Key</th>
+                    {files.map((file, i) => (
+                      <th key={matrix.fileIds[i]} className="font-normal p-2 font-mono whitespace-nowrap">
+                        {file?.relativePath ?? matrix.fileIds[i]}
+                      </th>
+                    ))}
+                  </tr>
+                </thead>
+                <tbody>
+                  {rows.map((row) => (
+                    <tr
+                      key={row.key}
+                      className={`border-t align-top ${row.valuesDiffer ? 'bg-amber-500/10' : ''}`}
+                    >
+                      <td className="p-2 font-mono font-medium whitespace-nowrap">{row.key}</td>
+                      {row.values.map((value, i) => {
+                        const fileId = matrix.fileIds[i];
+                        const missingHere = row.missingInBase?.includes(fileId) ?? false;
+                        return (
+                          <td
+                            key={fileId}
+                            className={`p-2 font-mono break-all ${missingHere ? 'bg-destructive/10 text-destructive' : ''}`}
+                            title={missingHere ? 'In .env.example but missing here' : undefined}
+                          >
+                            {value === null ? (
+                              <span className={missingHere ? '' : 'text-muted-foreground/50'}>
+                                {missingHere ? 'missing' : '—'}
+                              </span>
+                            ) : value === '' ? (
+                              <span className="text-muted-foreground italic">empty</span>
+                            ) : (
+                              value
+                            )}
+                          </td>
+                        );
+                      })}
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+              {rows.length === 0 && (
+                <p className="text-sm text-muted-foreground text-center py-6">
+                  {matrix.rows.length === 0 ? 'No variables defined' : 'All files agree'}
+                </p>
+              )}
+            </div>
+          </>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
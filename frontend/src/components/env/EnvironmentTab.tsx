@@ -5,7 +5,10 @@ import { Badge } from '@/components/ui/badge';
 import type { Project } from '@/types';
 import { EnvFileCard } from './EnvFileCard';
 import { AddEnvFileDialog } from './AddEnvFileDialog';
-import { RefreshCw, Plus, FileSearch, FolderOpen } from 'lucide-react';
+import { EnvDuplicateKeysBanner } from './EnvDuplicateKeysBanner';
+import { EnvMatrixDialog } from './EnvMatrixDialog';
+import { findDuplicateEnvKeys } from '@/lib/tauri';
+import { RefreshCw, Plus, FileSearch, FolderOpen, X, Table2 } from 'lucide-react';
 import { toast } from 'sonner';
 
 interface EnvironmentTabProps {
@@ -14,8 +17,10 @@ interface EnvironmentTabProps {
 
 export function EnvironmentTab({ project }: EnvironmentTabProps) {
   const [isAddDialogOpen, setIsAddDialogOpen] = useState(false);
+  const [isMatrixOpen, setIsMatrixOpen] = useState(false);
+  const [duplicates, setDuplicates] = useState<Record<string, [string, string[]][]>>({});
 
-  const { discoverEnvFiles, isDiscoveringEnvFiles } = useAppStore();
+  const { discoverEnvFiles, cancelEnvDiscovery, isDiscoveringEnvFiles, envDiscoveryProgress } = useAppStore();
 
   // Auto-discover env files if not already discovered
   useEffect(() => {
@@ -26,8 +31,12 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
 
   const handleRescan = async () => {
     try {
-      await discoverEnvFiles(project.id, true);
-      toast.success('Environment files rescanned');
+      const envFiles = await discoverEnvFiles(project.id, true);
+      if (envFiles === null) {
+        toast.info('Rescan cancelled');
+      } else {
+        toast.success('Environment files rescanned');
+      }
     } catch (error) {
       toast.error(`Failed to rescan: ${error}`);
     }
@@ -78,6 +87,14 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
     return a.localeCompare(b);
   });
 
+  // Keys defined with different values by several files in the same directory
+  useEffect(() => {
+    const dirs = Object.keys(groupedFiles).filter((dir) => groupedFiles[dir].length > 1);
+    Promise.all(dirs.map(async (dir) => [dir, await findDuplicateEnvKeys(project.id, dir)] as const))
+      .then((entries) => setDuplicates(Object.fromEntries(entries)))
+      .catch(console.error);
+  }, [project.id, groupedFiles]);
+
   return (
     <div className="space-y-4">
       {/* Header */}
@@ -87,6 +104,22 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
           <Badge variant="secondary">{project.envFiles.length}</Badge>
         </div>
         <div className="flex items-center gap-2">
+          {isDiscoveringEnvFiles && (
+            <>
+              {envDiscoveryProgress && (
+                <span
+                  className="text-xs text-muted-foreground max-w-64 truncate"
+                  title={envDiscoveryProgress.currentPath}
+                >
+                  {envDiscoveryProgress.dirsScanned} folders, {envDiscoveryProgress.filesFound} files
+                </span>
+              )}
+              <Button variant="ghost" size="sm" onClick={() => cancelEnvDiscovery().catch(console.error)}>
+                <X className="size-4 mr-2" />
+                Cancel
+              </Button>
+            </>
+          )}
           <Button
             variant="outline"
             size="sm"
@@ -98,6 +131,12 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
             />
             Rescan
           </Button>
+          {project.envFiles.length > 1 && (
+            <Button variant="outline" size="sm" onClick={() => setIsMatrixOpen(true)}>
+              <Table2 className="size-4 mr-2" />
+              Compare All
+            </Button>
+          )}
           <Button size="sm" onClick={() => setIsAddDialogOpen(true)}>
             <Plus className="size-4 mr-2" />
             Add File
@@ -110,6 +149,9 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
         <div className="flex flex-col items-center justify-center py-12 text-muted-foreground">
           <FileSearch className="size-12 mb-4 animate-pulse" />
           <p className="text-sm">Scanning for environment files...</p>
+          {envDiscoveryProgress && (
+            <p className="text-xs mt-1 font-mono max-w-md truncate">{envDiscoveryProgress.currentPath}</p>
+          )}
         </div>
       ) : project.envFiles.length === 0 ? (
         <div className="flex flex-col items-center justify-center py-12 text-muted-foreground border-2 border-dashed rounded-lg">
@@ -139,6 +181,7 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
 
               {/* Files in this directory */}
               <div className="space-y-2">
+                <EnvDuplicateKeysBanner duplicates={duplicates[dir] ?? []} />
                 {groupedFiles[dir].map((envFile) => (
                   <EnvFileCard
                     key={envFile.id}
@@ -163,6 +206,8 @@ export function EnvironmentTab({ project }: EnvironmentTabProps) {
         open={isAddDialogOpen}
         onOpenChange={setIsAddDialogOpen}
       />
+
+      <EnvMatrixDialog project={project} open={isMatrixOpen} onOpenChange={setIsMatrixOpen} />
     </div>
   );
 }
@@ -2,4 +2,6 @@ export { EnvironmentTab } from './EnvironmentTab';
 export { EnvFileCard } from './EnvFileCard';
 export { EnvVariableRow } from './EnvVariableRow';
 export { EnvComparisonBanner } from './EnvComparisonBanner';
+export { EnvDuplicateKeysBanner } from './EnvDuplicateKeysBanner';
+export { EnvMatrixDialog } from './EnvMatrixDialog';
 export { AddEnvFileDialog } from './AddEnvFileDialog';
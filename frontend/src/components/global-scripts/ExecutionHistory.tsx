@@ -2,8 +2,16 @@ import { useEffect, useState } from 'react';
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
 import { Button } from '@/components/ui/button';
 import { Badge } from '@/components/ui/badge';
-import { History, Trash2, CheckCircle2, XCircle, Clock } from 'lucide-react';
-import { getExecutionHistory, clearExecutionHistory } from '@/lib/tauri';
+import { History, Trash2, CheckCircle2, XCircle, Clock, RotateCcw, FileOutput, ExternalLink, FolderOpen } from 'lucide-react';
+import {
+  getExecutionHistory,
+  clearExecutionHistory,
+  replayExecution,
+  openInExplorer,
+  revealInExplorer,
+} from '@/lib/tauri';
+import { runErrorMessage } from '@/lib/utils';
+import { useAppStore } from '@/stores/appStore';
 import type { ExecutionRecord } from '@/types';
 import { toast } from 'sonner';
 
@@ -33,6 +41,7 @@ interface ExecutionHistoryProps {
 export function ExecutionHistory({ scriptId }: ExecutionHistoryProps) {
   const [records, setRecords] = useState<ExecutionRecord[]>([]);
   const [isLoading, setIsLoading] = useState(false);
+  const { openTerminal } = useAppStore();
 
   const loadHistory = async () => {
     setIsLoading(true);
@@ -59,6 +68,28 @@ export function ExecutionHistory({ scriptId }: ExecutionHistoryProps) {
     }
   };
 
+  // Run again with the exact parameters, extra args and working dir
+  const handleReplay = async (record: ExecutionRecord) => {
+    try {
+      const outcome = await replayExecution(record.id);
+      openTerminal('global-script', scriptId);
+      for (const warning of outcome.warnings) {
+        toast.warning(warning);
+      }
+      loadHistory();
+    } catch (e) {
+      toast.error('Failed to replay run', { description: runErrorMessage(e) });
+    }
+  };
+
+  const handleOpenArtifact = async (path: string, reveal: boolean) => {
+    try {
+      await (reveal ? revealInExplorer(path) : openInExplorer(path));
+    } catch (e) {
+      toast.error(reveal ? 'Failed to reveal file' : 'Failed to open file', { description: String(e) });
+    }
+  };
+
   return (
     <Card>
       <CardHeader className="pb-3">
@@ -83,10 +114,8 @@ export function ExecutionHistory({ scriptId }: ExecutionHistoryProps) {
         ) : (
           <div className="space-y-2">
             {records.map((record) => (
-              <div
-                key={record.id}
-                className="flex items-center gap-3 text-sm py-1.5 px-2 rounded hover:bg-muted/50"
-              >
+              <div key={record.id} className="py-1.5 px-2 rounded hover:bg-muted/50">
+              <div className="flex items-center gap-3 text-sm">
                 {record.success ? (
                   <CheckCircle2 className="size-4 text-green-500 flex-shrink-0" />
                 ) : (
@@ -111,6 +140,64 @@ export function ExecutionHistory({ scriptId }: ExecutionHistoryProps) {
                     {record.presetName}
                   </Badge>
                 )}
+                {record.envSnapshot && (
+                  <Badge
+                    variant="outline"
+                    className="text-xs py-0"
+                    title={Object.entries(record.envSnapshot)
+                      .sort(([a], [b]) => a.localeCompare(b))
+                      .map(([k, v]) => `${k}=${v}`)
+                      .join('\n')}
+                  >
+                    env {Object.keys(record.envSnapshot).length}
+                  </Badge>
+                )}
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  className="ml-auto h-6 px-2"
+                  title={
+                    Object.keys(record.parametersUsed).length > 0
+                      ? `Run again with ${Object.entries(record.parametersUsed)
+                          .map(([k, v]) => `${k}=${v}`)
+                          .join(', ')}`
+                      : 'Run again'
+                  }
+                  onClick={() => handleReplay(record)}
+                >
+                  <RotateCcw className="size-3.5" />
+                </Button>
+              </div>
+              {record.artifacts && record.artifacts.length > 0 && (
+                <div className="mt-1 ml-7 space-y-0.5">
+                  {record.artifacts.map((path) => (
+                    <div key={path} className="flex items-center gap-1.5 text-xs text-muted-foreground">
+                      <FileOutput className="size-3 flex-shrink-0" />
+                      <span className="truncate font-mono" title={path}>
+                        {path}
+                      </span>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        className="h-5 px-1.5 ml-auto"
+                        title="Open"
+                        onClick={() => handleOpenArtifact(path, false)}
+                      >
+                        <ExternalLink className="size-3" />
+                      </Button>
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        className="h-5 px-1.5"
+                        title="Reveal in file manager"
+                        onClick={() => handleOpenArtifact(path, true)}
+                      >
+                        <FolderOpen className="size-3" />
+                      </Button>
+                    </div>
+                  ))}
+                </div>
+              )}
               </div>
             ))}
           </div>
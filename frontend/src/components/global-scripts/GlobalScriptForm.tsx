@@ -12,17 +12,33 @@ import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { Textarea } from '@/components/ui/textarea';
 import { Badge } from '@/components/ui/badge';
+import { Checkbox } from '@/components/ui/checkbox';
 import { ComboboxInput } from '@/components/ui/combobox-input';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select';
 import { open as openDialog } from '@tauri-apps/plugin-dialog';
 import { FileSearch, X } from 'lucide-react';
 import { useAppStore } from '@/stores/appStore';
-import type { GlobalScript, CreateGlobalScriptInput, UpdateGlobalScriptInput } from '@/types';
+import { validateGlobalScript } from '@/lib/tauri';
+import { toast } from 'sonner';
+import type { GlobalScript, CreateGlobalScriptInput, UpdateGlobalScriptInput, OutputFormat } from '@/types';
 
 const SCRIPT_COLORS = [
   '#8b5cf6', '#06b6d4', '#f97316', '#22c55e',
   '#ec4899', '#eab308', '#3b82f6', '#ef4444',
 ];
 
+const OUTPUT_FORMATS: { value: OutputFormat; label: string }[] = [
+  { value: 'raw', label: 'Raw' },
+  { value: 'prettyJson', label: 'Pretty-print JSON' },
+  { value: 'table', label: 'JSON records as a table' },
+];
+
 interface GlobalScriptFormProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
@@ -38,6 +54,12 @@ export function GlobalScriptForm({ open, onOpenChange, script, onSubmit }: Globa
   const [command, setCommand] = useState('');
   const [scriptPath, setScriptPath] = useState('');
   const [workingDir, setWorkingDir] = useState('');
+  const [artifacts, setArtifacts] = useState('');
+  const [outputFormat, setOutputFormat] = useState<OutputFormat>('raw');
+  const [allowConcurrent, setAllowConcurrent] = useState(false);
+  const [mutexGroup, setMutexGroup] = useState('');
+  const [queueOnMutex, setQueueOnMutex] = useState(false);
+  const [schedule, setSchedule] = useState('');
   const [status, setStatus] = useState('');
   const [color, setColor] = useState(SCRIPT_COLORS[0]);
   const [tags, setTags] = useState<string[]>([]);
@@ -90,6 +112,12 @@ export function GlobalScriptForm({ open, onOpenChange, script, onSubmit }: Globa
         setCommand(script.command);
         setScriptPath(script.scriptPath || '');
         setWorkingDir(script.workingDir || '');
+        setArtifacts((script.artifacts ?? []).join('\n'));
+        setOutputFormat(script.outputFormat ?? 'raw');
+        setAllowConcurrent(script.allowConcurrent ?? false);
+        setMutexGroup(script.mutexGroup || '');
+        setQueueOnMutex(script.queueOnMutex ?? false);
+        setSchedule(script.schedule || '');
         setStatus(script.status || '');
         setColor(script.color || SCRIPT_COLORS[0]);
         setTags([...script.tags]);
@@ -99,6 +127,12 @@ export function GlobalScriptForm({ open, onOpenChange, script, onSubmit }: Globa
         setCommand('');
         setScriptPath('');
         setWorkingDir('');
+        setArtifacts('');
+        setOutputFormat('raw');
+        setAllowConcurrent(false);
+        setMutexGroup('');
+        setQueueOnMutex(false);
+        setSchedule('');
         setStatus('');
         setColor(SCRIPT_COLORS[Math.floor(Math.random() * SCRIPT_COLORS.length)]);
         setTags([]);
@@ -167,12 +201,29 @@ export function GlobalScriptForm({ open, onOpenChange, script, onSubmit }: Globa
         command: command.trim(),
         scriptPath: scriptPath.trim() || undefined,
         workingDir: workingDir.trim() || undefined,
+        // Always sent so clearing the field clears the patterns
+        artifacts: artifacts.split('\n').map((p) => p.trim()).filter(Boolean),
+        outputFormat,
+        allowConcurrent,
+        // Always sent so clearing the field leaves the group
+        mutexGroup: mutexGroup.trim(),
+        queueOnMutex,
+        // Always sent so clearing the field unschedules it
+        schedule: schedule.trim(),
         color,
         tags: tags.length > 0 ? tags : undefined,
         status: status.trim() || undefined,
       };
+      // Warnings only: scripts may be set up before their tool or file exists
+      const issues = await validateGlobalScript({ ...data, name: data.name!, command: data.command! })
+        .catch(() => []);
       await onSubmit(data);
       onOpenChange(false);
+      if (issues.length > 0) {
+        toast.warning('Saved with warnings', {
+          description: issues.map((i) => i.message).join('\n'),
+        });
+      }
     } catch (e) {
       setError(e instanceof Error ? e.message : String(e));
     } finally {
@@ -249,6 +300,91 @@ export function GlobalScriptForm({ open, onOpenChange, script, onSubmit }: Globa
               </p>
             </div>
 
+            <div className="grid gap-2">
+              <Label htmlFor="gs-artifacts">Artifacts (optional)</Label>
+              <Textarea
+                id="gs-artifacts"
+                value={artifacts}
+                onChange={(e) => setArtifacts(e.target.value)}
+                placeholder={'dist/*.zip\nreports/*.html'}
+                rows={2}
+                className="font-mono text-xs"
+              />
+              <p className="text-xs text-muted-foreground">
+                One glob per line, relative to the working directory. Matching files are listed in the run history after a successful run.
+              </p>
+            </div>
+
+            <div className="grid gap-2">
+              <Label htmlFor="gs-output-format">Output Format</Label>
+              <Select value={outputFormat} onValueChange={(v) => setOutputFormat(v as OutputFormat)}>
+                <SelectTrigger id="gs-output-format">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {OUTPUT_FORMATS.map((f) => (
+                    <SelectItem key={f.value} value={f.value}>
+                      {f.label}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <p className="text-xs text-muted-foreground">
+                Only changes how the output is shown; logs and exports keep it as printed.
+              </p>
+            </div>
+
+            <div className="flex items-center gap-3">
+              <Checkbox
+                id="gs-allow-concurrent"
+                checked={allowConcurrent}
+                onCheckedChange={(checked) => setAllowConcurrent(checked === true)}
+              />
+              <div className="grid gap-1">
+                <Label htmlFor="gs-allow-concurrent">Allow concurrent runs</Label>
+                <p className="text-xs text-muted-foreground">
+                  Running it while it runs starts another run instead of refusing
+                </p>
+              </div>
+            </div>
+
+            <div className="grid gap-2">
+              <Label htmlFor="gs-mutex-group">Mutex Group</Label>
+              <Input
+                id="gs-mutex-group"
+                value={mutexGroup}
+                onChange={(e) => setMutexGroup(e.target.value)}
+                placeholder="e.g., db"
+              />
+              <p className="text-xs text-muted-foreground">
+                Scripts in the same group never run at the same time.
+              </p>
+              {mutexGroup.trim() && (
+                <div className="flex items-center gap-3">
+                  <Checkbox
+                    id="gs-queue-on-mutex"
+                    checked={queueOnMutex}
+                    onCheckedChange={(checked) => setQueueOnMutex(checked === true)}
+                  />
+                  <Label htmlFor="gs-queue-on-mutex">Wait for the group instead of refusing to start</Label>
+                </div>
+              )}
+            </div>
+
+            <div className="grid gap-2">
+              <Label htmlFor="gs-schedule">Schedule</Label>
+              <Input
+                id="gs-schedule"
+                value={schedule}
+                onChange={(e) => setSchedule(e.target.value)}
+                placeholder="e.g., 0 3 * * * (every day at 3:00)"
+                className="font-mono"
+              />
+              <p className="text-xs text-muted-foreground">
+                Cron expression, in local time. Runs with the default preset while CortX is open.
+              </p>
+            </div>
+
             <div className="grid gap-2">
               <Label htmlFor="gs-tags">Tags</Label>
               <div className="relative">
@@ -38,6 +38,7 @@ import { GlobalScriptCompactItem } from './GlobalScriptCompactItem';
 import { GlobalScriptForm } from './GlobalScriptForm';
 import { ViewModeToggle } from '@/components/ui/view-mode-toggle';
 import { toast } from 'sonner';
+import { detectScriptParameters, importDiscoveredScripts } from '@/lib/tauri';
 import type { GlobalScript, ScriptStatus, CreateGlobalScriptInput, UpdateGlobalScriptInput, DiscoveredScript } from '@/types';
 
 type SortOption = 'name' | 'created';
@@ -48,7 +49,6 @@ export function GlobalScriptsView() {
     tagDefinitions,
     statusDefinitions,
     globalScriptRuntimes,
-    settings,
     createGlobalScript,
     updateGlobalScript,
     deleteGlobalScript,
@@ -56,6 +56,7 @@ export function GlobalScriptsView() {
     selectGlobalScript,
     openRunScriptDialog,
     scanScriptsFolder,
+    loadGlobalScripts,
   } = useAppStore();
   const { scriptsViewMode, setScriptsViewMode } = useViewPrefsStore();
 
@@ -72,6 +73,7 @@ export function GlobalScriptsView() {
   const [selectedDiscovered, setSelectedDiscovered] = useState<Set<string>>(new Set());
   const [showScanDialog, setShowScanDialog] = useState(false);
   const [isImporting, setIsImporting] = useState(false);
+  const [detectOnImport, setDetectOnImport] = useState(true);
 
   const sortedTagDefs = useMemo(
     () => [...tagDefinitions].sort((a, b) => (a.order ?? Infinity) - (b.order ?? Infinity)),
@@ -179,11 +181,38 @@ export function GlobalScriptsView() {
       if (!selected || typeof selected !== 'string') return;
 
       setIsScanning(true);
-      const results = await scanScriptsFolder(selected);
-      setScanTotal(results.length);
-      // Filter out scripts that already exist (by path)
-      const existingPaths = new Set(globalScripts.map((s) => s.scriptPath).filter(Boolean));
-      const newScripts = results.filter((s) => !existingPaths.has(s.path));
+      const result = await scanScriptsFolder(selected);
+      if (result.truncated) {
+        toast.warning('Scan stopped early', {
+          description: `Folder is too large; only the first ${result.entriesVisited} entries were scanned.`,
+        });
+      }
+      const relocated = result.relocated ?? [];
+      if (relocated.length > 0) {
+        await loadGlobalScripts();
+        toast.success(`Followed ${relocated.length} moved script(s)`, {
+          description: relocated.map((r) => r.scriptName).join(', '),
+        });
+      }
+      const unresolved = result.unresolvedMoves ?? [];
+      if (unresolved.length > 0) {
+        toast.warning(`${unresolved.length} file(s) may be moved scripts`, {
+          description: 'Not relinked: the match was ambiguous or the content changed. Update their paths by hand.',
+        });
+      }
+      const vanished = result.vanished ?? [];
+      if (vanished.length > 0) {
+        toast.warning(`${vanished.length} script file(s) are missing`, {
+          description: vanished.map((v) => v.scriptName).join(', '),
+        });
+      }
+      setScanTotal(result.scripts.length);
+      // Filter out scripts that already exist (by path), including the ones just relocated
+      const existingPaths = new Set([
+        ...globalScripts.map((s) => s.scriptPath).filter(Boolean),
+        ...relocated.map((r) => r.newPath),
+      ]);
+      const newScripts = result.scripts.filter((s) => !existingPaths.has(s.path));
       setDiscoveredScripts(newScripts);
       setSelectedDiscovered(new Set(newScripts.map((s) => s.path)));
       setShowScanDialog(true);
@@ -195,32 +224,42 @@ export function GlobalScriptsView() {
 
   const handleImportDiscovered = async () => {
     setIsImporting(true);
-    let imported = 0;
-    for (const script of discoveredScripts) {
-      if (!selectedDiscovered.has(script.path)) continue;
-      try {
-        // Determine the command from config templates
-        const ext = script.extension.toLowerCase().replace('.', '');
-        const templates = settings?.scriptsConfig?.commandTemplates ?? {};
-        const command = templates[ext] || `{{SCRIPT_FILE}}`;
-
-        await createGlobalScript({
-          name: script.name,
-          description: script.description || undefined,
-          command,
-          scriptPath: script.path,
-          tags: [],
-          parameters: [],
-          parameterPresets: [],
-        });
-        imported++;
-      } catch (e) {
-        console.error(`Failed to import ${script.name}:`, e);
+    try {
+      const result = await importDiscoveredScripts(
+        discoveredScripts.filter((s) => selectedDiscovered.has(s.path)).map((s) => s.path)
+      );
+      await loadGlobalScripts();
+      setShowScanDialog(false);
+      toast.success(`Imported ${result.imported.length} script(s)`, {
+        description: result.organized > 0 ? `${result.organized} organized by rules` : undefined,
+      });
+      if (detectOnImport && result.imported.length > 0) {
+        void detectImportedParameters(result.imported);
       }
+    } catch (e) {
+      toast.error('Import failed', { description: String(e) });
     }
     setIsImporting(false);
-    setShowScanDialog(false);
-    toast.success(`Imported ${imported} script(s)`);
+  };
+
+  // One script at a time: each --help may take up to the detection timeout
+  const detectImportedParameters = async (scripts: GlobalScript[]) => {
+    const toastId = toast.loading(`Detecting parameters of ${scripts.length} script(s)…`);
+    let withParams = 0;
+    const failed: string[] = [];
+    for (const script of scripts) {
+      try {
+        const params = await detectScriptParameters(script.command, script.scriptPath, script.id);
+        if (params.length > 0) withParams += 1;
+      } catch {
+        failed.push(script.name);
+      }
+    }
+    await loadGlobalScripts();
+    toast.success(`Parameters detected for ${withParams} of ${scripts.length} script(s)`, {
+      id: toastId,
+      description: failed.length > 0 ? `Could not run: ${failed.join(', ')}` : undefined,
+    });
   };
 
   const toggleDiscoveredScript = (path: string) => {
@@ -484,6 +523,13 @@ export function GlobalScriptsView() {
             </div>
           )}
 
+          {discoveredScripts.length > 0 && (
+            <label className="flex items-center gap-2 text-sm cursor-pointer">
+              <Checkbox checked={detectOnImport} onCheckedChange={(checked) => setDetectOnImport(checked === true)} />
+              Detect parameters from each script's --help
+            </label>
+          )}
+
           <DialogFooter className="shrink-0">
             <Button variant="outline" onClick={() => setShowScanDialog(false)}>
               Cancel
@@ -20,7 +20,7 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Collapsible, CollapsibleContent, CollapsibleTrigger } from '@/components/ui/collapsible';
-import { Plus, Trash2, Wand2, Loader2, Check, ChevronDown, ChevronRight } from 'lucide-react';
+import { Plus, Trash2, Wand2, Loader2, Check, ChevronDown, ChevronRight, X } from 'lucide-react';
 import { useAppStore } from '@/stores/appStore';
 import { toast } from 'sonner';
 import type { ScriptParameter, ScriptParamType, GlobalScript, UpdateGlobalScriptInput } from '@/types';
@@ -49,18 +49,21 @@ function emptyParam(): ScriptParameter {
 const AUTOSAVE_DELAY = 800;
 
 export function ParameterEditor({ script }: ParameterEditorProps) {
-  const { updateGlobalScript, autoDetectScriptParams } = useAppStore();
+  const { updateGlobalScript, autoDetectScriptParams, cancelParameterDetection } = useAppStore();
 
   const [params, setParams] = useState<ScriptParameter[]>([...script.parameters]);
   const [isDetecting, setIsDetecting] = useState(false);
   const [showDetectPreview, setShowDetectPreview] = useState(false);
   const [detectedParams, setDetectedParams] = useState<ScriptParameter[]>([]);
+  const [detectedRawHelp, setDetectedRawHelp] = useState<string | null>(null);
   const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved'>('idle');
   const [expandedParams, setExpandedParams] = useState<Set<number>>(new Set());
 
   const hasMountedRef = useRef(false);
   const debounceRef = useRef<ReturnType<typeof setTimeout> | null>(null);
   const savedTimerRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  // Token of the running --help detection, to cancel it
+  const detectTokenRef = useRef<string | null>(null);
 
   const doSave = useCallback(async (currentParams: ScriptParameter[]) => {
     const hasEmptyName = currentParams.some((p) => !p.name.trim());
@@ -106,6 +109,9 @@ export function ParameterEditor({ script }: ParameterEditorProps) {
     return () => {
       if (debounceRef.current) clearTimeout(debounceRef.current);
       if (savedTimerRef.current) clearTimeout(savedTimerRef.current);
+      // Don't leave the --help process running after the editor closes
+      const token = detectTokenRef.current;
+      if (token) useAppStore.getState().cancelParameterDetection(token).catch(() => {});
     };
   }, []);
 
@@ -122,23 +128,41 @@ export function ParameterEditor({ script }: ParameterEditorProps) {
   };
 
   const handleDetect = async () => {
+    const token = crypto.randomUUID();
+    detectTokenRef.current = token;
     setIsDetecting(true);
     try {
-      const detected = await autoDetectScriptParams(script.command, script.scriptPath);
-      if (detected.length === 0) {
+      const detected = await autoDetectScriptParams(script.command, script.scriptPath, token);
+      if (detected === null) return; // Cancelled
+      if (detected.parameters.length === 0) {
         toast.info('No parameters detected from --help output');
         return;
       }
-      setDetectedParams(detected);
+      setDetectedParams(detected.parameters);
+      setDetectedRawHelp(detected.rawHelp);
       setShowDetectPreview(true);
     } catch (e) {
       toast.error('Failed to detect parameters', { description: String(e) });
     } finally {
+      detectTokenRef.current = null;
       setIsDetecting(false);
     }
   };
 
+  const handleCancelDetect = () => {
+    if (!detectTokenRef.current) return;
+    cancelParameterDetection(detectTokenRef.current).catch(() => {});
+  };
+
+  // Keep the full --help text for the TUI's per-parameter help popup
+  const saveDetectedRawHelp = () => {
+    if (detectedRawHelp === null) return;
+    updateGlobalScript(script.id, { rawHelp: detectedRawHelp }).catch(() => {});
+    setDetectedRawHelp(null);
+  };
+
   const handleApplyDetected = () => {
+    saveDetectedRawHelp();
     const existingNames = new Set(params.map((p) => p.name));
     const newParams = detectedParams.filter((p) => !existingNames.has(p.name));
     setParams([...params, ...newParams]);
@@ -148,6 +172,7 @@ export function ParameterEditor({ script }: ParameterEditorProps) {
   };
 
   const handleReplaceWithDetected = () => {
+    saveDetectedRawHelp();
     setParams([...detectedParams]);
     setShowDetectPreview(false);
     setDetectedParams([]);
@@ -171,6 +196,12 @@ export function ParameterEditor({ script }: ParameterEditorProps) {
             )}
             Import from --help
           </Button>
+          {isDetecting && (
+            <Button variant="ghost" size="sm" onClick={handleCancelDetect}>
+              <X className="size-4 mr-1.5" />
+              Cancel
+            </Button>
+          )}
         </div>
         {/* Auto-save status indicator */}
         <div className="text-xs text-muted-foreground flex items-center gap-1.5">
@@ -337,6 +368,17 @@ export function ParameterEditor({ script }: ParameterEditorProps) {
                           />
                           <p className="text-[10px] text-muted-foreground">Number or + for variadic</p>
                         </div>
+                        {/* Mutex group */}
+                        <div className="space-y-1">
+                          <Label className="text-xs">Exclusive group</Label>
+                          <Input
+                            value={param.mutexGroup || ''}
+                            onChange={(e) => handleUpdateParam(index, 'mutexGroup', e.target.value || undefined)}
+                            placeholder="none"
+                            className="h-8 text-xs font-mono"
+                          />
+                          <p className="text-[10px] text-muted-foreground">Only one parameter per group is passed</p>
+                        </div>
                         {/* Enum values */}
                         {param.paramType === 'enum' && (
                           <div className="space-y-1 col-span-2">
@@ -1,4 +1,4 @@
-import { useState, useEffect } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
@@ -20,11 +20,18 @@ import {
   SelectTrigger,
   SelectValue,
 } from '@/components/ui/select';
-import { FolderOpen, Play } from 'lucide-react';
+import { ClipboardPaste, FolderOpen, Play } from 'lucide-react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useAppStore } from '@/stores/appStore';
 import { toast } from 'sonner';
-import type { GlobalScript } from '@/types';
+import {
+  checkScriptRequirements,
+  getClipboardTextIfPathlike,
+  joinDroppedPaths,
+  onFileDroppedForParam,
+} from '@/lib/tauri';
+import { isRequirementNotMet, runErrorMessage } from '@/lib/utils';
+import type { GlobalScript, RequirementStatus, ScriptParameter } from '@/types';
 
 const STORAGE_PREFIX = 'cortx-run:';
 
@@ -61,14 +68,78 @@ interface RunScriptDialogProps {
 }
 
 export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScriptDialogProps) {
-  const { runGlobalScript } = useAppStore();
+  const { runGlobalScript, startService } = useAppStore();
 
   const [workingDir, setWorkingDir] = useState('');
   const [paramValues, setParamValues] = useState<Record<string, string>>({});
+  const paramValuesRef = useRef(paramValues);
+  paramValuesRef.current = paramValues;
   const [paramEnabled, setParamEnabled] = useState<Record<string, boolean>>({});
   const [extraArgs, setExtraArgs] = useState('');
   const [selectedPresetId, setSelectedPresetId] = useState<string>('');
   const [isRunning, setIsRunning] = useState(false);
+  const [requirements, setRequirements] = useState<RequirementStatus[]>([]);
+  // Clipboard text offered to path parameters, when it's a path or URL
+  const [clipboardPath, setClipboardPath] = useState<string | null>(null);
+  // Last focused parameter: where dropped files go
+  const focusedParam = useRef<string | null>(null);
+
+  // Show whether required services are up before the user hits Run
+  const refreshRequirements = async () => {
+    if (!script?.requires?.length) {
+      setRequirements([]);
+      return;
+    }
+    try {
+      setRequirements(await checkScriptRequirements(script.id));
+    } catch {
+      setRequirements([]);
+    }
+  };
+
+  useEffect(() => {
+    if (isOpen) refreshRequirements();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen, script]);
+
+  // Re-read the clipboard when the dialog opens and when the window regains
+  // focus (the user may have copied a path elsewhere)
+  useEffect(() => {
+    if (!isOpen) return;
+    const refresh = () => {
+      getClipboardTextIfPathlike().then(setClipboardPath).catch(() => setClipboardPath(null));
+    };
+    refresh();
+    window.addEventListener('focus', refresh);
+    return () => window.removeEventListener('focus', refresh);
+  }, [isOpen]);
+
+  const fillParam = (param: ScriptParameter, value: string) => {
+    setParamValues((prev) => ({ ...prev, [param.name]: value }));
+    handleToggleParam(param.name, true);
+  };
+
+  // Files dropped on the window fill the focused path parameter, else the
+  // first empty one
+  useEffect(() => {
+    if (!isOpen || !script) return;
+    focusedParam.current = null;
+    const pathParams = script.parameters.filter((p) => p.paramType === 'path');
+    if (pathParams.length === 0) return;
+
+    const unlisten = onFileDroppedForParam(async ({ paths }) => {
+      const target =
+        pathParams.find((p) => p.name === focusedParam.current) ??
+        pathParams.find((p) => !paramValuesRef.current[p.name]) ??
+        pathParams[0];
+      const value = await joinDroppedPaths(target, paths).catch(() => null);
+      if (value) fillParam(target, value);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [isOpen, script]);
 
   // Restore form state when dialog opens
   useEffect(() => {
@@ -170,7 +241,19 @@ export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScrip
   };
 
   const handleToggleParam = (paramName: string, enabled: boolean) => {
-    setParamEnabled((prev) => ({ ...prev, [paramName]: enabled }));
+    setParamEnabled((prev) => {
+      const next = { ...prev, [paramName]: enabled };
+      // Enabling a member of a mutex group disables its optional siblings
+      const group = script?.parameters.find((p) => p.name === paramName)?.mutexGroup;
+      if (enabled && script && group) {
+        for (const param of script.parameters) {
+          if (param.name !== paramName && param.mutexGroup === group && !param.required) {
+            next[param.name] = false;
+          }
+        }
+      }
+      return next;
+    });
   };
 
   const handleBrowseDir = async () => {
@@ -227,12 +310,35 @@ export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScrip
 
       onOpenChange(false);
     } catch (e) {
-      toast.error('Failed to run script', { description: String(e) });
+      if (isRequirementNotMet(e)) {
+        refreshRequirements();
+        toast.error('Required services are not running', {
+          description: e.missing.map((m) => m.problem ? `${m.label}: ${m.problem}` : m.label).join(', '),
+          action: e.startAndRetry
+            ? { label: 'Start & retry', onClick: () => startMissingAndRetry(e.missing) }
+            : undefined,
+        });
+      } else {
+        toast.error('Failed to run script', { description: runErrorMessage(e) });
+      }
     } finally {
       setIsRunning(false);
     }
   };
 
+  const startMissingAndRetry = async (missing: RequirementStatus[]) => {
+    try {
+      for (const m of missing) {
+        if (m.serviceId) await startService(m.serviceId);
+      }
+    } catch (e) {
+      toast.error('Failed to start required service', { description: String(e) });
+      refreshRequirements();
+      return;
+    }
+    await handleRun();
+  };
+
   if (!script) return null;
 
   // Build preview command
@@ -302,6 +408,30 @@ export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScrip
             </div>
           </div>
 
+          {/* Required services */}
+          {requirements.length > 0 && (
+            <div className="space-y-1.5">
+              <Label>Requires</Label>
+              <div className="space-y-1">
+                {requirements.map((r) => (
+                  <div key={r.label} className="flex items-center gap-2 text-xs">
+                    <span
+                      className={`size-2 rounded-full ${
+                        r.state === 'running' ? 'bg-green-500' : 'bg-red-500'
+                      }`}
+                    />
+                    <span className="font-medium">{r.label}</span>
+                    {r.problem ? (
+                      <span className="text-muted-foreground">— {r.problem}</span>
+                    ) : r.state !== 'running' && r.requirement.autoStart ? (
+                      <span className="text-muted-foreground">— can be started for you</span>
+                    ) : null}
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+
           {/* Parameters */}
           {script.parameters.length > 0 && (
             <div className="space-y-3">
@@ -353,6 +483,11 @@ export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScrip
                               {param.nargs === '+' ? 'multi' : `${param.nargs} values`}
                             </span>
                           )}
+                          {param.mutexGroup && (
+                            <span className="ml-1.5 inline-flex items-center rounded-sm bg-muted px-1.5 py-0.5 text-[10px] font-medium text-muted-foreground">
+                              one of {param.mutexGroup}
+                            </span>
+                          )}
                           {param.longFlag && (
                             <code className="text-muted-foreground ml-1.5 font-mono">{param.longFlag}</code>
                           )}
@@ -400,22 +535,49 @@ export function RunScriptDialog({ script, open: isOpen, onOpenChange }: RunScrip
                               </SelectContent>
                             </Select>
                           ) : (
-                            <Input
-                              value={paramValues[param.name] || ''}
-                              onChange={(e) =>
-                                setParamValues((prev) => ({
-                                  ...prev,
-                                  [param.name]: e.target.value,
-                                }))
-                              }
-                              placeholder={
-                                param.defaultValue ||
-                                (param.nargs
-                                  ? `Enter values separated by spaces...`
-                                  : `Enter ${param.name}...`)
-                              }
-                              className="h-8 text-xs font-mono"
-                            />
+                            <>
+                              <Input
+                                value={paramValues[param.name] || ''}
+                                onChange={(e) =>
+                                  setParamValues((prev) => ({
+                                    ...prev,
+                                    [param.name]: e.target.value,
+                                  }))
+                                }
+                                onFocus={() => {
+                                  focusedParam.current = param.name;
+                                }}
+                                onKeyDown={(e) => {
+                                  // Alt+V: use the clipboard's path
+                                  if (param.paramType === 'path' && clipboardPath && e.altKey && e.key.toLowerCase() === 'v') {
+                                    e.preventDefault();
+                                    fillParam(param, clipboardPath);
+                                  }
+                                }}
+                                placeholder={
+                                  param.defaultValue ||
+                                  (param.nargs
+                                    ? /^\d+$/.test(param.nargs)
+                                      ? `Enter ${param.nargs} values separated by spaces...`
+                                      : `Enter values separated by spaces...`
+                                    : param.paramType === 'path'
+                                      ? `Enter or drop ${param.name}...`
+                                      : `Enter ${param.name}...`)
+                                }
+                                className="h-8 text-xs font-mono"
+                              />
+                              {param.paramType === 'path' && clipboardPath && paramValues[param.name] !== clipboardPath && (
+                                <button
+                                  type="button"
+                                  onClick={() => fillParam(param, clipboardPath)}
+                                  title="Use the clipboard (Alt+V)"
+                                  className="mt-1 inline-flex max-w-full items-center gap-1 rounded-sm bg-muted px-1.5 py-0.5 text-[10px] text-muted-foreground hover:text-foreground"
+                                >
+                                  <ClipboardPaste className="size-3 shrink-0" />
+                                  <span className="truncate font-mono">{clipboardPath}</span>
+                                </button>
+                              )}
+                            </>
                           )}
                         </div>
                       )}
@@ -17,7 +17,7 @@ import {
 } from '@/components/ui/sidebar';
 import { useAppStore } from '@/stores/appStore';
 import { FolderKanban, Settings, FolderOpen, Terminal, Circle, Play, X, Square, FileCode, ScrollText, Wrench, SquareTerminal, AppWindow } from 'lucide-react';
-import { cn } from '@/lib/utils';
+import { cn, isServiceUp } from '@/lib/utils';
 import type { View, ServiceStatus, ScriptStatus } from '@/types';
 import { getVersion } from '@tauri-apps/api/app';
 
@@ -121,7 +121,7 @@ export function AppSidebar() {
     0
   );
   const runningCount = projectsWithServices.reduce(
-    (acc, p) => acc + p.services.filter(s => s.status === 'running').length,
+    (acc, p) => acc + p.services.filter(s => isServiceUp(s.status)).length,
     0
   );
 
@@ -304,8 +304,8 @@ export function AppSidebar() {
             <SidebarGroupContent>
               <SidebarMenu>
                 {projectsWithServices.map(({ projectId, projectName, services }) => {
-                  const runningServices = services.filter(s => s.status === 'running');
-                  const stoppedServices = services.filter(s => s.status !== 'running');
+                  const runningServices = services.filter(s => isServiceUp(s.status));
+                  const stoppedServices = services.filter(s => !isServiceUp(s.status));
                   const hasRunning = runningServices.length > 0;
                   const hasStopped = stoppedServices.length > 0;
 
@@ -391,14 +391,18 @@ export function AppSidebar() {
                               {serviceName}
                             </span>
                             {/* Status indicator */}
-                            {status === 'running' ? (
+                            {status === 'unhealthy' ? (
+                              <Circle className="size-1.5 fill-orange-500 text-orange-500 animate-pulse" />
+                            ) : status === 'circuitOpen' ? (
+                              <Circle className="size-1.5 fill-red-500 text-red-500" />
+                            ) : status === 'running' ? (
                               <Circle className="size-1.5 fill-green-500 text-green-500 animate-pulse" />
                             ) : (
                               <Circle className="size-1.5 fill-muted-foreground text-muted-foreground" />
                             )}
                             {/* Action buttons - visible on hover */}
                             <div className="hidden group-hover/service:flex items-center gap-0.5 ml-1">
-                              {status === 'running' ? (
+                              {isServiceUp(status) ? (
                                 <div
                                   role="button"
                                   tabIndex={0}
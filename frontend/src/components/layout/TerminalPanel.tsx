@@ -25,7 +25,8 @@ import {
 } from 'lucide-react';
 import { cn } from '@/lib/utils';
 import AnsiToHtml from 'ansi-to-html';
-import type { LogEntry } from '@/types';
+import type { LogEntry, OutputFormat } from '@/types';
+import { formatLogs } from '@/lib/outputFormat';
 import { open } from '@tauri-apps/plugin-shell';
 import { TerminalDndContext, type TerminalItem, type TerminalType } from './terminal-dnd';
 import { SortableContext, horizontalListSortingStrategy } from '@dnd-kit/sortable';
@@ -356,7 +357,7 @@ function DroppablePaneContent({
                   {activeTerminal.logs.length === 0 ? (
                     <div className="text-muted-foreground">Waiting for output...</div>
                   ) : (
-                    <TerminalLogs logs={activeTerminal.logs} />
+                    <TerminalLogs logs={activeTerminal.logs} format={activeTerminal.outputFormat} />
                   )}
                 </div>
               </ScrollArea>
@@ -552,6 +553,7 @@ export function TerminalPanel() {
         detectedPorts: [],
         lastExitCode: runtime.lastExitCode,
         lastSuccess: runtime.lastSuccess,
+        outputFormat: script?.outputFormat,
       });
     }
 
@@ -928,7 +930,8 @@ const LogLine = memo(function LogLine({ log }: { log: LogEntry }) {
     <div
       className={cn(
         'whitespace-pre-wrap break-all',
-        log.stream === 'stderr' && 'text-red-400'
+        log.stream === 'stderr' && 'text-red-400',
+        log.stream === 'command' && 'text-cyan-400 font-semibold'
       )}
       dangerouslySetInnerHTML={{
         __html: processTerminalContent(log.content),
@@ -938,10 +941,11 @@ const LogLine = memo(function LogLine({ log }: { log: LogEntry }) {
 });
 
 // Memoized terminal logs component - only re-renders when logs array changes
-const TerminalLogs = memo(function TerminalLogs({ logs }: { logs: LogEntry[] }) {
+const TerminalLogs = memo(function TerminalLogs({ logs, format }: { logs: LogEntry[]; format?: OutputFormat }) {
+  const shown = useMemo(() => formatLogs(logs, format), [logs, format]);
   return (
     <>
-      {logs.map((log, index) => (
+      {shown.map((log, index) => (
         <LogLine key={index} log={log} />
       ))}
     </>
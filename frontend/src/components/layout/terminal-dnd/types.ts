@@ -1,4 +1,4 @@
-import type { ServiceStatus, ScriptStatus, LogEntry } from '@/types';
+import type { ServiceStatus, ScriptStatus, LogEntry, OutputFormat } from '@/types';
 
 export type TerminalType = 'service' | 'script' | 'global-script';
 
@@ -14,6 +14,7 @@ export interface TerminalItem {
   activeMode?: string;
   lastExitCode?: number;
   lastSuccess?: boolean;
+  outputFormat?: OutputFormat;  // Global scripts only
 }
 
 export interface DragData {
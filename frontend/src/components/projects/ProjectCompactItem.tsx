@@ -12,6 +12,7 @@ import {
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu';
 import { openInExplorer, openInVscode } from '@/lib/tauri';
+import { effectiveRoot, isServiceUp } from '@/lib/utils';
 import { toast } from 'sonner';
 
 interface ProjectCardProps {
@@ -39,12 +40,12 @@ export function ProjectCompactItem({ project, onEdit, onDelete }: ProjectCardPro
 
   const handleOpenFolder = (e: React.MouseEvent) => {
     e.stopPropagation();
-    openInExplorer(project.rootPath).catch(console.error);
+    openInExplorer(effectiveRoot(project)).catch(console.error);
   };
 
   const handleOpenInVscode = (e: React.MouseEvent) => {
     e.stopPropagation();
-    openInVscode(project.rootPath).catch((error) => {
+    openInVscode(effectiveRoot(project)).catch((error) => {
       toast.error('Failed to open VSCode', {
         description: String(error),
       });
@@ -53,7 +54,7 @@ export function ProjectCompactItem({ project, onEdit, onDelete }: ProjectCardPro
 
   const runningCount = project.services.filter((s) => {
     const runtime = serviceRuntimes.get(s.id);
-    return runtime?.status === 'running';
+    return isServiceUp(runtime?.status);
   }).length;
 
   return (
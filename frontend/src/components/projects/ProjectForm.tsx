@@ -12,6 +12,7 @@ import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { Badge } from '@/components/ui/badge';
 import { Textarea } from '@/components/ui/textarea';
+import { Checkbox } from '@/components/ui/checkbox';
 import { useAppStore } from '@/stores/appStore';
 import type { Project, CreateProjectInput, UpdateProjectInput } from '@/types';
 import { open } from '@tauri-apps/plugin-dialog';
@@ -38,6 +39,9 @@ export function ProjectForm({ open: isOpen, onOpenChange, project, onSubmit }: P
   const [showTagSuggestions, setShowTagSuggestions] = useState(false);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  // Set once the chosen folder turned out to be another project's root
+  const [isDuplicateRoot, setIsDuplicateRoot] = useState(false);
+  const [allowDuplicate, setAllowDuplicate] = useState(false);
   const tagInputRef = useRef<HTMLInputElement>(null);
 
   const isEditing = !!project;
@@ -53,6 +57,8 @@ export function ProjectForm({ open: isOpen, onOpenChange, project, onSubmit }: P
       setTagInput('');
       setShowTagSuggestions(false);
       setError(null);
+      setIsDuplicateRoot(false);
+      setAllowDuplicate(false);
     }
   }, [isOpen, project]);
 
@@ -147,6 +153,7 @@ export function ProjectForm({ open: isOpen, onOpenChange, project, onSubmit }: P
             tags,
             status: status.trim() || undefined,
             toolboxUrl: toolboxUrl.trim() || undefined,
+            allowDuplicate,
           };
       await onSubmit(data);
       onOpenChange(false);
@@ -155,7 +162,9 @@ export function ProjectForm({ open: isOpen, onOpenChange, project, onSubmit }: P
       setRootPath('');
       setDescription('');
     } catch (e) {
-      setError(e instanceof Error ? e.message : 'An error occurred');
+      const message = e instanceof Error ? e.message : typeof e === 'string' ? e : 'An error occurred';
+      setError(message);
+      if (message.includes('already has the root')) setIsDuplicateRoot(true);
     } finally {
       setIsSubmitting(false);
     }
@@ -313,6 +322,18 @@ export function ProjectForm({ open: isOpen, onOpenChange, project, onSubmit }: P
             {error && (
               <p className="text-sm text-destructive">{error}</p>
             )}
+            {isDuplicateRoot && (
+              <div className="flex items-center gap-2">
+                <Checkbox
+                  id="allow-duplicate-root"
+                  checked={allowDuplicate}
+                  onCheckedChange={(v) => setAllowDuplicate(!!v)}
+                />
+                <Label htmlFor="allow-duplicate-root" className="text-sm font-normal">
+                  Add it anyway, sharing the folder with that project
+                </Label>
+              </div>
+            )}
           </div>
 
           <DialogFooter>
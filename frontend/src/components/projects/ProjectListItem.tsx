@@ -13,7 +13,7 @@ import {
   DropdownMenuTrigger,
 } from '@/components/ui/dropdown-menu';
 import { openInExplorer, openInVscode } from '@/lib/tauri';
-import { cn } from '@/lib/utils';
+import { cn, effectiveRoot, isServiceUp } from '@/lib/utils';
 import { toast } from 'sonner';
 
 interface ProjectCardProps {
@@ -41,12 +41,12 @@ export function ProjectListItem({ project, onEdit, onDelete }: ProjectCardProps)
 
   const handleOpenFolder = (e: React.MouseEvent) => {
     e.stopPropagation();
-    openInExplorer(project.rootPath).catch(console.error);
+    openInExplorer(effectiveRoot(project)).catch(console.error);
   };
 
   const handleOpenInVscode = (e: React.MouseEvent) => {
     e.stopPropagation();
-    openInVscode(project.rootPath).catch((error) => {
+    openInVscode(effectiveRoot(project)).catch((error) => {
       toast.error('Failed to open VSCode', {
         description: String(error),
       });
@@ -55,7 +55,7 @@ export function ProjectListItem({ project, onEdit, onDelete }: ProjectCardProps)
 
   const runningCount = project.services.filter((s) => {
     const runtime = serviceRuntimes.get(s.id);
-    return runtime?.status === 'running';
+    return isServiceUp(runtime?.status);
   }).length;
 
   return (
@@ -166,6 +166,9 @@ function ServiceStatusDot({ status }: { status: string }) {
     stopped: 'text-muted-foreground',
     starting: 'text-yellow-500',
     running: 'text-green-500',
+    unhealthy: 'text-orange-500',
+    restarting: 'text-yellow-500',
+    stopping: 'text-yellow-500',
     error: 'text-red-500',
   };
 
@@ -11,7 +11,7 @@ import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
 import { Label } from '@/components/ui/label';
 import { open as openDialog } from '@tauri-apps/plugin-dialog';
-import { FolderOpen, Plus, X, Star } from 'lucide-react';
+import { FolderOpen, Plus, X, Star, RotateCw } from 'lucide-react';
 import { Tooltip, TooltipContent, TooltipTrigger } from '@/components/ui/tooltip';
 import type { Service, CreateServiceInput, UpdateServiceInput } from '@/types';
 
@@ -41,6 +41,8 @@ interface ServiceFormProps {
   service?: Service;
   projectPath?: string;
   onSubmit: (data: CreateServiceInput | UpdateServiceInput) => Promise<void>;
+  // Editing only: save, then restart the service with the new definition if it runs
+  onSubmitAndRestart?: (data: UpdateServiceInput) => Promise<void>;
 }
 
 const SERVICE_COLORS = [
@@ -81,7 +83,7 @@ function arrayToPresets(arr: { name: string; args: string }[]): Record<string, s
   return Object.fromEntries(filtered.map((p) => [p.name.trim(), p.args.trim()]));
 }
 
-export function ServiceForm({ open: isOpen, onOpenChange, service, projectPath, onSubmit }: ServiceFormProps) {
+export function ServiceForm({ open: isOpen, onOpenChange, service, projectPath, onSubmit, onSubmitAndRestart }: ServiceFormProps) {
   const [name, setName] = useState(service?.name || '');
   const [workingDir, setWorkingDir] = useState(service?.workingDir || '.');
   const [command, setCommand] = useState(service?.command || '');
@@ -152,8 +154,12 @@ export function ServiceForm({ open: isOpen, onOpenChange, service, projectPath,
     return !!(mode && mode.command.trim());
   })();
 
-  const handleSubmit = async (e: React.FormEvent) => {
+  const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
+    submit(false);
+  };
+
+  const submit = async (restart: boolean) => {
     setError(null);
 
     if (!name.trim()) {
@@ -189,7 +195,11 @@ export function ServiceForm({ open: isOpen, onOpenChange, service, projectPath,
         color,
         port: port ? parseInt(port, 10) : undefined,
       };
-      await onSubmit(data);
+      if (restart && onSubmitAndRestart) {
+        await onSubmitAndRestart(data as UpdateServiceInput);
+      } else {
+        await onSubmit(data);
+      }
       onOpenChange(false);
       // Reset form
       if (!isEditing) {
@@ -521,6 +531,12 @@ export function ServiceForm({ open: isOpen, onOpenChange, service, projectPath,
             >
               Cancel
             </Button>
+            {isEditing && onSubmitAndRestart && (
+              <Button type="button" variant="secondary" onClick={() => submit(true)} disabled={isSubmitting}>
+                <RotateCw className="h-4 w-4 mr-2" />
+                Save & Restart
+              </Button>
+            )}
             <Button type="submit" disabled={isSubmitting}>
               {isSubmitting ? 'Saving...' : isEditing ? 'Save Changes' : 'Add Service'}
             </Button>
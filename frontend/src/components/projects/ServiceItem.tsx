@@ -23,7 +23,7 @@ import {
 import { Label } from '@/components/ui/label';
 import { Tooltip, TooltipContent, TooltipTrigger } from '@/components/ui/tooltip';
 import { TruncatedText } from '@/components/ui/TruncatedText';
-import type { Service } from '@/types';
+import type { Service, ServiceCircuit } from '@/types';
 import {
   Play,
   Square,
@@ -32,8 +32,10 @@ import {
   MoreVertical,
   Terminal,
   ChevronDown,
+  RotateCcw,
 } from 'lucide-react';
-import { cn } from '@/lib/utils';
+import { cn, isServiceUp } from '@/lib/utils';
+import { getServiceUptime, getServiceCircuit, resetServiceCircuit } from '@/lib/tauri';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
 import { toast } from 'sonner';
 
@@ -56,8 +58,14 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
 
   const runtime = serviceRuntimes.get(service.id);
   const status = runtime?.status || 'stopped';
-  const isRunning = status === 'running';
+  const isRunning = isServiceUp(status);
   const isStarting = status === 'starting';
+  // Between two runs; stopping cancels the pending restart
+  const isRestarting = status === 'restarting';
+  // Asked to exit; the stop resolves once it did (or was killed)
+  const isStopping = status === 'stopping';
+  // Crashed too often; waits for a start, a reset or its one timed retry
+  const isCircuitOpen = status === 'circuitOpen';
   const activeMode = runtime?.activeMode;
   const activeArgPreset = runtime?.activeArgPreset;
   const hasModes = service.modes && Object.keys(service.modes).length > 0;
@@ -70,6 +78,7 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
   const [selectedMode, setSelectedMode] = useState<string | undefined>(service.defaultMode);
   const [selectedPreset, setSelectedPreset] = useState<string | null | undefined>(service.defaultArgPreset);
   const [popoverOpen, setPopoverOpen] = useState(false);
+  const [circuit, setCircuit] = useState<ServiceCircuit | null>(null);
 
   // Reset selections when service changes
   useEffect(() => {
@@ -77,6 +86,14 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
     setSelectedPreset(service.defaultArgPreset);
   }, [service.id, service.defaultMode, service.defaultArgPreset]);
 
+  useEffect(() => {
+    if (!isCircuitOpen) {
+      setCircuit(null);
+      return;
+    }
+    getServiceCircuit(service.id).then(setCircuit).catch(console.error);
+  }, [service.id, isCircuitOpen]);
+
   const handleStart = async (mode?: string, argPreset?: string) => {
     try {
       await startService(service.id, mode, argPreset);
@@ -97,6 +114,15 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
     }
   };
 
+  const handleResetCircuit = async () => {
+    try {
+      await resetServiceCircuit(service.id);
+      toast.success(`Reset ${service.name}`);
+    } catch (error) {
+      toast.error(`Failed to reset ${service.name}: ${error}`);
+    }
+  };
+
   const handleCopy = async () => {
     try {
       const command = await copyLaunchCommand(service.id);
@@ -143,6 +169,7 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
               <div className="flex-shrink-0">
                 <StatusBadge status={status} activeMode={activeMode} activeArgPreset={activeArgPreset} />
               </div>
+              {isRunning && <ServiceUptime serviceId={service.id} startedAt={runtime?.startedAt} />}
             </div>
             <div className="mt-1 space-y-0.5 text-xs text-muted-foreground font-mono">
               <div className="flex gap-1">
@@ -160,6 +187,14 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
                 <p>Port: {service.port}</p>
               ) : null}
             </div>
+            {isCircuitOpen && circuit && (
+              <div className="mt-2 rounded border border-red-500/30 bg-red-500/10 px-2 py-1 text-xs text-red-600 dark:text-red-400">
+                Restarts stopped: {circuit.failures} failed runs, last exited {circuit.lastExit}
+                {circuit.retryAt
+                  ? ` · retrying once at ${new Date(circuit.retryAt).toLocaleTimeString()}`
+                  : ' · start it to try again'}
+              </div>
+            )}
           </div>
 
           {/* Actions */}
@@ -190,7 +225,22 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
               <TooltipContent>Open in external terminal</TooltipContent>
             </Tooltip>
 
-            {isRunning || isStarting ? (
+            {isCircuitOpen && (
+              <Tooltip>
+                <TooltipTrigger asChild>
+                  <Button
+                    variant="outline"
+                    size="icon-sm"
+                    onClick={handleResetCircuit}
+                  >
+                    <RotateCcw className="size-3.5" />
+                  </Button>
+                </TooltipTrigger>
+                <TooltipContent>Reset without starting</TooltipContent>
+              </Tooltip>
+            )}
+
+            {isRunning || isStarting || isRestarting || isStopping ? (
               <>
                 <Tooltip>
                   <TooltipTrigger asChild>
@@ -210,7 +260,7 @@ export function ServiceItem({ service, projectPath, onEdit, onDelete }: ServiceI
                       variant="destructive"
                       size="icon-sm"
                       onClick={handleStop}
-                      disabled={isStarting}
+                      disabled={isStarting || isStopping}
                     >
                       <Square className="size-3.5" />
                     </Button>
@@ -353,20 +403,67 @@ function StatusOverlay({ status }: { status: string }) {
       <div className="size-full rounded-full animate-pulse bg-green-500/50" />
     );
   }
-  if (status === 'starting') {
+  if (status === 'starting' || status === 'restarting' || status === 'stopping') {
     return (
       <div className="size-full rounded-full animate-pulse bg-yellow-500/50" />
     );
   }
+  if (status === 'unhealthy') {
+    return (
+      <div className="size-full rounded-full animate-pulse bg-orange-500/50" />
+    );
+  }
+  if (status === 'circuitOpen') {
+    return (
+      <div className="size-full rounded-full bg-red-500/70" />
+    );
+  }
   return null;
 }
 
+function formatUptime(totalSeconds: number): string {
+  const s = Math.max(0, Math.floor(totalSeconds));
+  if (s < 60) return `${s}s`;
+  if (s < 3600) return `${Math.floor(s / 60)}m${String(s % 60).padStart(2, '0')}s`;
+  if (s < 86400) return `${Math.floor(s / 3600)}h${String(Math.floor((s % 3600) / 60)).padStart(2, '0')}m`;
+  return `${Math.floor(s / 86400)}d${String(Math.floor((s % 86400) / 3600)).padStart(2, '0')}h`;
+}
+
+/** "up 3m12s", ticking every second. Services started by another CortX
+ *  instance have no start event here, so their uptime is fetched once. */
+function ServiceUptime({ serviceId, startedAt }: { serviceId: string; startedAt?: string }) {
+  const [fetchedStart, setFetchedStart] = useState<number | null>(null);
+  const [now, setNow] = useState(() => Date.now());
+
+  useEffect(() => {
+    if (startedAt) return;
+    getServiceUptime(serviceId)
+      .then((secs) => setFetchedStart(secs === null ? null : Date.now() - secs * 1000))
+      .catch(console.error);
+  }, [serviceId, startedAt]);
+
+  useEffect(() => {
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, []);
+
+  const start = startedAt ? Date.parse(startedAt) : fetchedStart;
+  if (start === null || Number.isNaN(start)) return null;
+
+  return (
+    <span className="text-xs text-muted-foreground flex-shrink-0">
+      up {formatUptime((now - start) / 1000)}
+    </span>
+  );
+}
+
 function StatusBadge({ status, activeMode, activeArgPreset }: { status: string; activeMode?: string; activeArgPreset?: string }) {
   const styles = {
     stopped: 'bg-muted text-muted-foreground',
     starting: 'bg-yellow-500/10 text-yellow-600 dark:text-yellow-400',
     running: 'bg-green-500/10 text-green-600 dark:text-green-400',
     error: 'bg-red-500/10 text-red-600 dark:text-red-400',
+    circuitOpen: 'bg-red-500/15 text-red-600 dark:text-red-400 font-medium',
   };
 
   const labels = {
@@ -374,12 +471,13 @@ function StatusBadge({ status, activeMode, activeArgPreset }: { status: string;
     starting: 'Starting',
     running: 'Running',
     error: 'Error',
+    circuitOpen: 'Crash loop',
   };
 
   const label = labels[status as keyof typeof labels] || 'Unknown';
   // Build label from mode and preset
   const activeLabels = [activeMode, activeArgPreset].filter(Boolean);
-  const activeLabel = activeLabels.length > 0 && (status === 'running' || status === 'starting')
+  const activeLabel = activeLabels.length > 0 && (isServiceUp(status) || status === 'starting')
     ? ` (${activeLabels.join(' + ')})`
     : '';
 
@@ -0,0 +1,166 @@
+import { useState, useEffect } from 'react';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Badge } from '@/components/ui/badge';
+import { useAppStore } from '@/stores/appStore';
+import { toast } from 'sonner';
+import type { SyncEntry, SyncPlan, SyncResolution, SyncStatus } from '@/types';
+
+const STATUS_LABELS: Record<SyncStatus, string> = {
+  upToDate: 'Up to date',
+  upstreamUpdated: 'Updated upstream',
+  locallyModified: 'Changed locally',
+  conflict: 'Conflict',
+  added: 'New',
+};
+
+// What happens to an entry without an explicit resolution
+function defaultResolution(entry: SyncEntry): SyncResolution | undefined {
+  switch (entry.status) {
+    case 'upstreamUpdated':
+    case 'added':
+      return 'takeUpstream';
+    case 'locallyModified':
+      return 'keepLocal';
+    default:
+      return undefined;
+  }
+}
+
+function formatValue(value: unknown): string {
+  if (value === null || value === undefined) return '—';
+  return typeof value === 'string' ? value : JSON.stringify(value);
+}
+
+interface ScriptSyncDialogProps {
+  plan: SyncPlan | null;
+  onClose: () => void;
+}
+
+export function ScriptSyncDialog({ plan, onClose }: ScriptSyncDialogProps) {
+  const { applySyncPlan } = useAppStore();
+  const [resolutions, setResolutions] = useState<Record<string, SyncResolution>>({});
+  const [isApplying, setIsApplying] = useState(false);
+
+  useEffect(() => {
+    setResolutions({});
+  }, [plan]);
+
+  const entries = plan?.entries.filter((e) => e.status !== 'upToDate') ?? [];
+  const upToDate = (plan?.entries.length ?? 0) - entries.length;
+  const unresolved = entries.filter((e) => e.status === 'conflict' && !resolutions[e.sourceId]).length;
+
+  const resolve = (sourceId: string, resolution: SyncResolution) =>
+    setResolutions((r) => ({ ...r, [sourceId]: resolution }));
+
+  const handleApply = async () => {
+    if (!plan) return;
+    setIsApplying(true);
+    try {
+      const result = await applySyncPlan(plan, resolutions);
+      const parts: string[] = [];
+      if (result.updated.length > 0) parts.push(`${result.updated.length} updated`);
+      if (result.added.length > 0) parts.push(`${result.added.length} added`);
+      if (result.kept.length > 0) parts.push(`${result.kept.length} kept`);
+      toast.success(parts.length > 0 ? `Synced scripts: ${parts.join(', ')}` : 'Scripts already in sync');
+      if (result.stale.length > 0) {
+        toast.warning(`Changed since the comparison, not synced: ${result.stale.join(', ')}`);
+      }
+      onClose();
+    } catch (error) {
+      toast.error(`Failed to sync scripts: ${error}`);
+    } finally {
+      setIsApplying(false);
+    }
+  };
+
+  return (
+    <Dialog open={plan !== null} onOpenChange={(open) => { if (!open && !isApplying) onClose(); }}>
+      <DialogContent className="sm:max-w-2xl">
+        <DialogHeader>
+          <DialogTitle>Sync Scripts with Export</DialogTitle>
+          <DialogDescription>
+            {upToDate} up to date &middot; {entries.length} to review
+            {plan && plan.skipped.length > 0 && <> &middot; {plan.skipped.length} skipped (id used by a local script)</>}
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="max-h-[60vh] overflow-y-auto space-y-3 py-2">
+          {entries.length === 0 && (
+            <p className="text-sm text-muted-foreground">Every imported script matches the export.</p>
+          )}
+          {entries.map((entry) => {
+            const resolution = resolutions[entry.sourceId] ?? defaultResolution(entry);
+            return (
+              <div key={entry.sourceId} className="rounded-md border p-3 space-y-2">
+                <div className="flex items-center gap-2">
+                  <span className="text-sm font-medium truncate">{entry.name}</span>
+                  <Badge variant={entry.status === 'conflict' ? 'destructive' : 'secondary'}>
+                    {STATUS_LABELS[entry.status]}
+                  </Badge>
+                  <div className="ml-auto flex gap-1">
+                    <Button
+                      size="sm"
+                      variant={resolution === 'keepLocal' ? 'default' : 'outline'}
+                      onClick={() => resolve(entry.sourceId, 'keepLocal')}
+                    >
+                      {entry.status === 'added' ? 'Skip' : 'Keep mine'}
+                    </Button>
+                    <Button
+                      size="sm"
+                      variant={resolution === 'takeUpstream' ? 'default' : 'outline'}
+                      onClick={() => resolve(entry.sourceId, 'takeUpstream')}
+                    >
+                      {entry.status === 'added' ? 'Add' : 'Take upstream'}
+                    </Button>
+                  </div>
+                </div>
+                {entry.diff && entry.diff.length > 0 && (
+                  <table className="w-full text-xs">
+                    <thead>
+                      <tr className="text-muted-foreground text-left">
+                        <th className="font-normal pr-2 w-28">Field</th>
+                        <th className="font-normal pr-2">Local</th>
+                        <th className="font-normal">Upstream</th>
+                      </tr>
+                    </thead>
+                    <tbody>
+                      {entry.diff.map((d) => (
+                        <tr key={d.field} className="align-top">
+                          <td className="pr-2 text-muted-foreground">{d.field}</td>
+                          <td className="pr-2 font-mono break-all">{formatValue(d.local)}</td>
+                          <td className="font-mono break-all">{formatValue(d.upstream)}</td>
+                        </tr>
+                      ))}
+                    </tbody>
+                  </table>
+                )}
+              </div>
+            );
+          })}
+        </div>
+
+        <DialogFooter>
+          {unresolved > 0 && (
+            <span className="text-xs text-muted-foreground mr-auto self-center">
+              {unresolved} conflict{unresolved === 1 ? '' : 's'} left as is
+            </span>
+          )}
+          <Button variant="outline" onClick={onClose} disabled={isApplying}>
+            Cancel
+          </Button>
+          <Button onClick={handleApply} disabled={isApplying}>
+            {isApplying ? 'Syncing...' : 'Sync'}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,124 @@
+import type { LogEntry, OutputFormat } from '@/types';
+
+// Display formatting of a script's stdout, the GUI side of
+// cortx-core's output_format: JSON pretty-printed or JSON records as a
+// table. Logs themselves are never changed; anything that doesn't parse
+// is shown as is.
+
+const MAX_BLOCK_LINES = 500;
+const MAX_COLUMN_WIDTH = 40;
+
+type Json = null | boolean | number | string | Json[] | { [key: string]: Json };
+
+function tryParse(text: string): Json | undefined {
+  try {
+    return JSON.parse(text) as Json;
+  } catch {
+    return undefined;
+  }
+}
+
+function isRecord(value: Json | undefined): value is { [key: string]: Json } {
+  return typeof value === 'object' && value !== null && !Array.isArray(value);
+}
+
+// The JSON object or array starting at `start`, and the index past its last line
+function parseBlock(lines: LogEntry[], start: number): [Json, number] | undefined {
+  const first = lines[start].content.trim();
+  if (!first.startsWith('{') && !first.startsWith('[')) return undefined;
+  const single = tryParse(first);
+  if (single !== undefined) return [single, start + 1];
+
+  let block = first;
+  const last = Math.min(lines.length, start + 1 + MAX_BLOCK_LINES);
+  for (let i = start + 1; i < last && lines[i].stream === 'stdout'; i++) {
+    const line = lines[i].content;
+    block += '\n' + line;
+    const head = line.trimStart();
+    if (head.startsWith('}') || head.startsWith(']')) {
+      const value = tryParse(block);
+      if (value !== undefined) return [value, i + 1];
+    }
+  }
+  return undefined;
+}
+
+function cell(value: Json | undefined): string {
+  if (value === undefined || value === null) return '';
+  return typeof value === 'string' ? value : JSON.stringify(value);
+}
+
+function truncate(value: string, width: number): string {
+  return value.length <= width ? value : value.slice(0, width - 1) + '…';
+}
+
+function renderTable(rows: { [key: string]: Json }[]): string[] {
+  const columns: string[] = [];
+  for (const row of rows) {
+    for (const key of Object.keys(row)) {
+      if (!columns.includes(key)) columns.push(key);
+    }
+  }
+  const cells = rows.map((row) => columns.map((column) => cell(row[column])));
+  const widths = columns.map((column, c) =>
+    Math.min(MAX_COLUMN_WIDTH, Math.max(column.length, ...cells.map((row) => row[c].length))),
+  );
+  const renderRow = (values: string[]) =>
+    values.map((value, c) => truncate(value, widths[c]).padEnd(widths[c])).join('  ').trimEnd();
+  return [renderRow(columns), renderRow(widths.map((w) => '-'.repeat(w))), ...cells.map(renderRow)];
+}
+
+// A table of the records at `start`: an array of objects, or objects on
+// consecutive lines (JSON Lines)
+function tableAt(lines: LogEntry[], start: number): [string[], number] | undefined {
+  const parsed = parseBlock(lines, start);
+  if (!parsed) return undefined;
+  const [value] = parsed;
+  let end = parsed[1];
+  let rows: { [key: string]: Json }[];
+  if (Array.isArray(value) && value.length > 0 && value.every(isRecord)) {
+    rows = value as { [key: string]: Json }[];
+  } else if (isRecord(value)) {
+    rows = [value];
+    while (end < lines.length && lines[end].stream === 'stdout') {
+      const next = tryParse(lines[end].content.trim());
+      if (!isRecord(next)) break;
+      rows.push(next);
+      end++;
+    }
+  } else {
+    return undefined;
+  }
+  return [renderTable(rows), end];
+}
+
+// Formats each stretch of stdout; a reformatted block becomes one entry per
+// displayed line, timestamped like the line it starts on
+export function formatLogs(logs: LogEntry[], format: OutputFormat | undefined): LogEntry[] {
+  if (!format || format === 'raw') return logs;
+  const formatted: LogEntry[] = [];
+  let i = 0;
+  while (i < logs.length) {
+    const log = logs[i];
+    if (log.stream !== 'stdout') {
+      formatted.push(log);
+      i++;
+      continue;
+    }
+    let rendered: [string[], number] | undefined;
+    if (format === 'prettyJson') {
+      const parsed = parseBlock(logs, i);
+      if (parsed) rendered = [JSON.stringify(parsed[0], null, 2).split('\n'), parsed[1]];
+    } else {
+      rendered = tableAt(logs, i);
+    }
+    if (rendered) {
+      for (const content of rendered[0]) formatted.push({ ...log, content });
+      i = rendered[1];
+    } else {
+      formatted.push(log);
+      i++;
+    }
+  }
+  return formatted;
+}
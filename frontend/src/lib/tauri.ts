@@ -6,10 +6,19 @@ import type {
   Service,
   Script,
   AppSettings,
+  ShortcutWarning,
   CreateProjectInput,
   UpdateProjectInput,
+  CreateProjectVariantInput,
+  VariantSwitch,
   CreateServiceInput,
   UpdateServiceInput,
+  RestartResult,
+  ServiceGroupResult,
+  ServiceHealth,
+  ServiceCircuit,
+  PendingSave,
+  StartWait,
   CreateScriptInput,
   UpdateScriptInput,
   ServiceLogPayload,
@@ -20,23 +29,52 @@ import type {
   ScriptStatusPayload,
   ScriptExitPayload,
   EnvFile,
+  EnvFileVariant,
   EnvComparison,
+  EnvMatrix,
   DiscoverEnvFilesInput,
+  EnvDiscoveryProgress,
+  EnvDiscoveryComplete,
   AddEnvFileInput,
   LinkEnvToServiceInput,
   GlobalScript,
   CreateGlobalScriptInput,
   UpdateGlobalScriptInput,
+  ScriptDependency,
+  ScriptNodeResult,
   TagDefinition,
   CreateTagDefinitionInput,
   UpdateTagDefinitionInput,
   ExecutionRecord,
   ScriptsConfig,
-  ScriptParameter,
+  DetectedHelp,
   ImportOptions,
   ImportResult,
+  SyncPlan,
+  SyncResolution,
+  SyncResult,
+  BackupInfo,
+  RestoreResult,
   ExportSummary,
-  DiscoveredScript,
+  ImportFilePreview,
+  MigrationReport,
+  PathMapping,
+  ScriptScanResult,
+  DiscoveredImport,
+  RequirementStatus,
+  ValidationIssue,
+  OrganizeResult,
+  ReplayOutcome,
+  RunComparison,
+  ServiceLogFile,
+  ServiceLogPage,
+  RunningProcess,
+  RunningTargetKind,
+  SavedProject,
+  RunningCounts,
+  ProjectImportResult,
+  EntityChangedPayload,
+  ProjectItemChangedPayload,
   Tool,
   CreateToolInput,
   UpdateToolInput,
@@ -53,6 +91,8 @@ import type {
   App,
   CreateAppInput,
   UpdateAppInput,
+  ScriptParameter,
+  FileDroppedPayload,
 } from '@/types';
 
 // Project commands
@@ -64,11 +104,11 @@ export async function getProject(id: string): Promise<Project> {
   return invoke('get_project', { id });
 }
 
-export async function createProject(input: CreateProjectInput): Promise<Project> {
+export async function createProject(input: CreateProjectInput): Promise<SavedProject> {
   return invoke('create_project', { input });
 }
 
-export async function updateProject(id: string, input: UpdateProjectInput): Promise<Project> {
+export async function updateProject(id: string, input: UpdateProjectInput): Promise<SavedProject> {
   return invoke('update_project', { id, input });
 }
 
@@ -80,6 +120,21 @@ export async function updateProjectLastOpened(id: string): Promise<void> {
   return invoke('update_project_last_opened', { id });
 }
 
+// Project variant commands
+
+export async function addProjectVariant(projectId: string, input: CreateProjectVariantInput): Promise<Project> {
+  return invoke('add_project_variant', { projectId, input });
+}
+
+export async function removeProjectVariant(projectId: string, variantId: string): Promise<Project> {
+  return invoke('remove_project_variant', { projectId, variantId });
+}
+
+// variantId null: back to the project's own root
+export async function setActiveVariant(projectId: string, variantId: string | null): Promise<VariantSwitch> {
+  return invoke('set_active_variant', { projectId, variantId });
+}
+
 // Service commands
 export async function addService(projectId: string, input: CreateServiceInput): Promise<Service> {
   return invoke('add_service', { projectId, input });
@@ -89,6 +144,10 @@ export async function updateService(serviceId: string, input: UpdateServiceInput
   return invoke('update_service', { serviceId, input });
 }
 
+export async function updateAndRestartService(serviceId: string, input: UpdateServiceInput): Promise<RestartResult> {
+  return invoke('update_and_restart_service', { serviceId, input });
+}
+
 export async function deleteService(serviceId: string): Promise<void> {
   return invoke('delete_service', { serviceId });
 }
@@ -139,6 +198,22 @@ export async function startIntegratedService(serviceId: string, mode?: string, a
   return invoke('start_integrated_service', { serviceId, mode, argPreset });
 }
 
+export async function runServiceGroup(
+  projectId: string,
+  serviceIds: string[],
+  sequential: boolean,
+): Promise<ServiceGroupResult[]> {
+  return invoke('run_service_group', { projectId, serviceIds, sequential });
+}
+
+export async function startAllServices(projectId: string, wait?: StartWait): Promise<ServiceGroupResult[]> {
+  return invoke('start_all_services', { projectId, wait });
+}
+
+export async function stopAllServices(projectId: string): Promise<ServiceGroupResult[]> {
+  return invoke('stop_all_services', { projectId });
+}
+
 export async function stopIntegratedService(serviceId: string): Promise<void> {
   return invoke('stop_integrated_service', { serviceId });
 }
@@ -151,6 +226,53 @@ export async function getRunningServices(): Promise<string[]> {
   return invoke('get_running_services');
 }
 
+export async function listRunning(): Promise<RunningProcess[]> {
+  return invoke('list_running');
+}
+
+export async function countRunning(): Promise<RunningCounts> {
+  return invoke('count_running');
+}
+
+/** Seconds the service has been running, or null if it's stopped */
+export async function getServiceUptime(serviceId: string): Promise<number | null> {
+  return invoke('get_service_uptime', { serviceId });
+}
+
+// Null when the service has no health check or isn't running
+export async function getServiceHealth(serviceId: string): Promise<ServiceHealth | null> {
+  return invoke('get_service_health', { serviceId });
+}
+
+// Null unless the service's restart circuit is open
+export async function getServiceCircuit(serviceId: string): Promise<ServiceCircuit | null> {
+  return invoke('get_service_circuit', { serviceId });
+}
+
+// Closes an open circuit without starting the service; false if it wasn't open
+export async function resetServiceCircuit(serviceId: string): Promise<boolean> {
+  return invoke('reset_service_circuit', { serviceId });
+}
+
+/** Env vars the service's next start injects (linked env files + its own env vars) */
+export async function getEffectiveEnv(serviceId: string): Promise<Record<string, string>> {
+  return invoke('get_effective_env', { serviceId });
+}
+
+/** Persisted log files of past sessions, newest first */
+export async function getServiceLogFiles(serviceId: string): Promise<ServiceLogFile[]> {
+  return invoke('get_service_log_files', { serviceId });
+}
+
+export async function readServiceLog(
+  serviceId: string,
+  file: string,
+  offset?: number,
+  limit?: number
+): Promise<ServiceLogPage> {
+  return invoke('read_service_log', { serviceId, file, offset, limit });
+}
+
 // Settings commands
 export async function getSettings(): Promise<AppSettings> {
   return invoke('get_settings');
@@ -160,11 +282,24 @@ export async function updateSettings(settings: AppSettings): Promise<void> {
   return invoke('update_settings', { settings });
 }
 
+export async function getPendingSaves(): Promise<PendingSave[]> {
+  return invoke('get_pending_saves');
+}
+
+export async function retryPendingSaves(): Promise<PendingSave[]> {
+  return invoke('retry_pending_saves');
+}
+
 /** Register (or re-register) the OS-level global hotkey. Empty string disables. */
 export async function setGlobalHotkey(combo: string): Promise<void> {
   return invoke('set_global_hotkey', { combo });
 }
 
+/** Global shortcuts from the settings that aren't registered (invalid, used twice, taken by the OS) */
+export async function getGlobalShortcutWarnings(): Promise<ShortcutWarning[]> {
+  return invoke('get_global_shortcut_warnings');
+}
+
 /** Real quit — runs the service-cleanup flow and exits the process.
  *  In contrast, closing the window (X / window.close()) only hides to tray. */
 export async function quitApp(): Promise<void> {
@@ -176,6 +311,10 @@ export async function openInExplorer(path: string): Promise<void> {
   return invoke('open_in_explorer', { path });
 }
 
+export async function revealInExplorer(path: string): Promise<void> {
+  return invoke('reveal_in_explorer', { path });
+}
+
 export async function openInVscode(path: string): Promise<void> {
   return invoke('open_in_vscode', { path });
 }
@@ -243,13 +382,36 @@ export async function onScriptExit(
 }
 
 // Environment file commands
+
+/** Starts the discovery in the background and resolves to its id; results
+ *  arrive with `onEnvDiscoveryComplete` */
 export async function discoverEnvFiles(
   projectId: string,
   input: DiscoverEnvFilesInput
-): Promise<EnvFile[]> {
+): Promise<string> {
   return invoke('discover_env_files', { projectId, input });
 }
 
+export async function cancelEnvDiscovery(discoveryId: string): Promise<void> {
+  return invoke('cancel_env_discovery', { discoveryId });
+}
+
+export async function onEnvDiscoveryProgress(
+  callback: (payload: EnvDiscoveryProgress) => void
+): Promise<UnlistenFn> {
+  return listen<EnvDiscoveryProgress>('env-discovery-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
+export async function onEnvDiscoveryComplete(
+  callback: (payload: EnvDiscoveryComplete) => void
+): Promise<UnlistenFn> {
+  return listen<EnvDiscoveryComplete>('env-discovery-complete', (event) => {
+    callback(event.payload);
+  });
+}
+
 export async function addEnvFile(
   projectId: string,
   input: AddEnvFileInput
@@ -269,15 +431,51 @@ export async function refreshAllEnvFiles(projectId: string): Promise<EnvFile[]>
   return invoke('refresh_all_env_files', { projectId });
 }
 
-export async function getEnvFiles(projectId: string): Promise<EnvFile[]> {
-  return invoke('get_env_files', { projectId });
+export async function updateEnvFile(
+  projectId: string,
+  envFileId: string,
+  variant: EnvFileVariant | null
+): Promise<EnvFile> {
+  return invoke('update_env_file', { projectId, envFileId, variant });
+}
+
+// maskValues left out follows the maskEnvValues setting
+export async function getEnvFiles(projectId: string, maskValues?: boolean): Promise<EnvFile[]> {
+  return invoke('get_env_files', { projectId, maskValues });
 }
 
 export async function getEnvFileContent(
   projectId: string,
-  envFileId: string
+  envFileId: string,
+  maskValues?: boolean
 ): Promise<string> {
-  return invoke('get_env_file_content', { projectId, envFileId });
+  return invoke('get_env_file_content', { projectId, envFileId, maskValues });
+}
+
+export async function updateEnvVariable(
+  projectId: string,
+  envFileId: string,
+  key: string,
+  value: string
+): Promise<EnvFile> {
+  return invoke('update_env_variable', { projectId, envFileId, key, value });
+}
+
+export async function addEnvVariable(
+  projectId: string,
+  envFileId: string,
+  key: string,
+  value: string
+): Promise<EnvFile> {
+  return invoke('add_env_variable', { projectId, envFileId, key, value });
+}
+
+export async function removeEnvVariable(
+  projectId: string,
+  envFileId: string,
+  key: string
+): Promise<EnvFile> {
+  return invoke('remove_env_variable', { projectId, envFileId, key });
 }
 
 export async function compareEnvFiles(
@@ -288,6 +486,29 @@ export async function compareEnvFiles(
   return invoke('compare_env_files', { projectId, baseFileId, exampleFileId });
 }
 
+/** Every key of every env file of the project against every file */
+export async function compareAllEnvFiles(projectId: string, maskValues?: boolean): Promise<EnvMatrix> {
+  return invoke('compare_all_env_files', { projectId, maskValues });
+}
+
+/** Create (or complete) an env file with every key of the example, empty. `targetPath`
+ *  defaults to `.env` next to the example; relative paths are from the project root. */
+export async function scaffoldEnvFromExample(
+  projectId: string,
+  exampleFileId: string,
+  targetPath?: string
+): Promise<EnvFile> {
+  return invoke('scaffold_env_from_example', { projectId, exampleFileId, targetPath });
+}
+
+/** `[key, ["filename=value", ...]]` for keys defined differently by several files in `dir` */
+export async function findDuplicateEnvKeys(
+  projectId: string,
+  dir: string
+): Promise<[string, string[]][]> {
+  return invoke('find_duplicate_env_keys', { projectId, dir });
+}
+
 export async function linkEnvToService(
   projectId: string,
   envFileId: string,
@@ -309,6 +530,10 @@ export async function createGlobalScript(input: CreateGlobalScriptInput): Promis
   return invoke('create_global_script', { input });
 }
 
+export async function validateGlobalScript(input: CreateGlobalScriptInput): Promise<ValidationIssue[]> {
+  return invoke('validate_global_script', { input });
+}
+
 export async function updateGlobalScript(id: string, input: UpdateGlobalScriptInput): Promise<GlobalScript> {
   return invoke('update_global_script', { id, input });
 }
@@ -321,6 +546,14 @@ export async function reorderGlobalScripts(scriptIds: string[]): Promise<void> {
   return invoke('reorder_global_scripts', { scriptIds });
 }
 
+export async function applyOrganizeRules(
+  scriptIds: string[] | undefined,
+  dryRun: boolean
+): Promise<OrganizeResult> {
+  return invoke('apply_organize_rules', { scriptIds, dryRun });
+}
+
+/** Rejects with a `RunScriptError` (see `runErrorMessage`) */
 export async function runGlobalScript(
   scriptId: string,
   workingDir: string,
@@ -330,10 +563,31 @@ export async function runGlobalScript(
   return invoke('run_global_script', { scriptId, workingDir, parameterValues, extraArgs });
 }
 
+/** Runs each script once the scripts it depends on succeeded */
+export async function runScriptDag(
+  nodes: ScriptDependency[],
+  maxParallel?: number
+): Promise<ScriptNodeResult[]> {
+  return invoke('run_script_dag', { nodes, maxParallel });
+}
+
+export async function checkScriptRequirements(scriptId: string): Promise<RequirementStatus[]> {
+  return invoke('check_script_requirements', { scriptId });
+}
+
 export async function stopGlobalScript(scriptId: string): Promise<void> {
   return invoke('stop_global_script', { scriptId });
 }
 
+/** Stop anything running, by kind and id (e.g. a `listRunning` row) */
+export async function stopProcess(kind: RunningTargetKind, id: string): Promise<void> {
+  return invoke('stop_process', { kind, id });
+}
+
+export async function isProcessRunning(kind: RunningTargetKind, id: string): Promise<boolean> {
+  return invoke('is_process_running', { kind, id });
+}
+
 export async function isGlobalScriptRunning(scriptId: string): Promise<boolean> {
   return invoke('is_global_script_running', { scriptId });
 }
@@ -363,10 +617,27 @@ export async function getExecutionHistory(
   return invoke('get_execution_history', { scriptId, limit });
 }
 
+export async function getServiceHistory(
+  serviceId: string,
+  limit?: number
+): Promise<ExecutionRecord[]> {
+  return invoke('get_service_history', { serviceId, limit });
+}
+
+/** Run A is the reference (usually the older run) */
+export async function compareRuns(recordIdA: string, recordIdB: string): Promise<RunComparison> {
+  return invoke('compare_runs', { recordIdA, recordIdB });
+}
+
 export async function clearExecutionHistory(scriptId: string): Promise<void> {
   return invoke('clear_execution_history', { scriptId });
 }
 
+/** Rejects with a `RunScriptError` (see `runErrorMessage`) */
+export async function replayExecution(recordId: string): Promise<ReplayOutcome> {
+  return invoke('replay_execution', { recordId });
+}
+
 // Scripts config commands
 export async function getScriptsConfig(): Promise<ScriptsConfig> {
   return invoke('get_scripts_config');
@@ -376,13 +647,56 @@ export async function updateScriptsConfig(config: ScriptsConfig): Promise<void>
   return invoke('update_scripts_config', { config });
 }
 
-export async function scanScriptsFolder(folder: string): Promise<DiscoveredScript[]> {
+/** Scan `folder`, or the configured main scripts folder */
+export async function scanScriptsFolder(folder?: string): Promise<ScriptScanResult> {
   return invoke('scan_scripts_folder', { folder });
 }
 
+/** Import scanned files as global scripts; registered paths are skipped */
+export async function importDiscoveredScripts(paths: string[]): Promise<DiscoveredImport> {
+  return invoke('import_discovered_scripts', { paths });
+}
+
 // Help parser / auto-detect parameters
-export async function autoDetectScriptParams(command: string, scriptPath?: string): Promise<ScriptParameter[]> {
-  return invoke('auto_detect_script_params', { command, scriptPath });
+/** Resolves to null when cancelled with `cancelParameterDetection(token)` */
+export async function autoDetectScriptParams(
+  command: string,
+  scriptPath?: string,
+  token?: string
+): Promise<DetectedHelp | null> {
+  return invoke('auto_detect_script_params', { command, scriptPath, token });
+}
+
+/**
+ * Parsed --help parameters; empty when the command prints nothing or hangs.
+ * With `mergeInto` (a script id) new ones are added to that script and its
+ * merged list is returned.
+ */
+export async function detectScriptParameters(
+  command: string,
+  scriptPath?: string,
+  mergeInto?: string
+): Promise<ScriptParameter[]> {
+  return invoke('detect_script_parameters', { command, scriptPath, mergeInto });
+}
+
+export async function cancelParameterDetection(token: string): Promise<void> {
+  return invoke('cancel_parameter_detection', { token });
+}
+
+export async function refreshScriptHelp(id: string): Promise<GlobalScript> {
+  return invoke('refresh_script_help', { id });
+}
+
+// Run form fills
+/** The clipboard's text if it's an existing path or a URL, else null */
+export async function getClipboardTextIfPathlike(): Promise<string | null> {
+  return invoke('get_clipboard_text_if_pathlike');
+}
+
+/** The value `param` gets from dropped files, following its nargs */
+export async function joinDroppedPaths(param: ScriptParameter, paths: string[]): Promise<string | null> {
+  return invoke('join_dropped_paths', { param, paths });
 }
 
 // Import / Export
@@ -390,6 +704,16 @@ export async function exportScriptsConfig(): Promise<string> {
   return invoke('export_scripts_config');
 }
 
+/** Export through a native save dialog; resolves to the written path, or null if cancelled */
+export async function exportScriptsConfigDialog(): Promise<string | null> {
+  return invoke('export_scripts_config_dialog');
+}
+
+/** Pick and preview an import file through a native dialog; null if cancelled */
+export async function importScriptsConfigDialog(): Promise<ImportFilePreview | null> {
+  return invoke('import_scripts_config_dialog');
+}
+
 export async function previewImport(json: string): Promise<ExportSummary> {
   return invoke('preview_import', { json });
 }
@@ -398,10 +722,65 @@ export async function importScriptsConfig(json: string, options: ImportOptions):
   return invoke('import_scripts_config', { json, options });
 }
 
+/** Compare a newer version of an export with the scripts imported from it */
+export async function syncWithExport(json: string): Promise<SyncPlan> {
+  return invoke('sync_with_export', { json });
+}
+
+/** Apply a sync plan; `resolutions` is keyed by source id */
+export async function applySyncPlan(
+  plan: SyncPlan,
+  resolutions: Record<string, SyncResolution>
+): Promise<SyncResult> {
+  return invoke('apply_sync_plan', { plan, resolutions });
+}
+
+/** Projects alone, as a portable bundle (env values stripped) */
+export async function exportProjectsConfig(): Promise<string> {
+  return invoke('export_projects_config');
+}
+
+export async function importProjectsConfig(json: string): Promise<ProjectImportResult> {
+  return invoke('import_projects_config', { json });
+}
+
+/** One JSON file per script plus a manifest, for keeping scripts in git; returns the script count */
+export async function exportScriptsToDir(path: string): Promise<number> {
+  return invoke('export_scripts_to_dir', { path });
+}
+
+export async function importScriptsFromDir(path: string): Promise<ImportResult> {
+  return invoke('import_scripts_from_dir', { path });
+}
+
+export async function importFromDataDir(path: string, options: ImportOptions): Promise<MigrationReport> {
+  return invoke('import_from_data_dir', { path, options });
+}
+
+export async function applyPathMappings(
+  path: string,
+  options: ImportOptions,
+  mappings: PathMapping[],
+): Promise<ImportResult> {
+  return invoke('apply_path_mappings', { path, options, mappings });
+}
+
 export async function backupToGit(): Promise<string> {
   return invoke('backup_to_git');
 }
 
+export async function createBackup(): Promise<BackupInfo> {
+  return invoke('create_backup');
+}
+
+export async function listBackups(): Promise<BackupInfo[]> {
+  return invoke('list_backups');
+}
+
+export async function restoreBackup(path: string): Promise<RestoreResult> {
+  return invoke('restore_backup', { path });
+}
+
 // Execution history update
 export async function updateExecutionRecord(
   scriptId: string,
@@ -557,12 +936,53 @@ export async function onDataChanged(callback: () => void): Promise<UnlistenFn> {
   return listen('data-changed', () => callback());
 }
 
+// Storage change listeners: one event per saved mutation, carrying ids only
+export async function onProjectChanged(
+  callback: (payload: EntityChangedPayload) => void
+): Promise<UnlistenFn> {
+  return listen<EntityChangedPayload>('project-changed', (event) => callback(event.payload));
+}
+
+export async function onServiceChanged(
+  callback: (payload: ProjectItemChangedPayload) => void
+): Promise<UnlistenFn> {
+  return listen<ProjectItemChangedPayload>('service-changed', (event) => callback(event.payload));
+}
+
+export async function onScriptChanged(
+  callback: (payload: ProjectItemChangedPayload) => void
+): Promise<UnlistenFn> {
+  return listen<ProjectItemChangedPayload>('script-changed', (event) => callback(event.payload));
+}
+
+export async function onGlobalScriptChanged(
+  callback: (payload: EntityChangedPayload) => void
+): Promise<UnlistenFn> {
+  return listen<EntityChangedPayload>('global-script-changed', (event) => callback(event.payload));
+}
+
+export async function onSettingsChanged(callback: () => void): Promise<UnlistenFn> {
+  return listen('settings-changed', () => callback());
+}
+
+// A save failed, or a failed one went through; fetch getPendingSaves()
+export async function onSaveStatusChanged(callback: () => void): Promise<UnlistenFn> {
+  return listen('save-status-changed', () => callback());
+}
+
 /** Fires when the OS-level global hotkey is pressed, asking the UI to open
  *  the command palette (and ensure the window is shown + focused). */
 export async function onOpenCommandPalette(callback: () => void): Promise<UnlistenFn> {
   return listen('open-command-palette', () => callback());
 }
 
+/** Files dropped on the window; the open run form fills a parameter with them */
+export async function onFileDroppedForParam(
+  callback: (payload: FileDroppedPayload) => void
+): Promise<UnlistenFn> {
+  return listen<FileDroppedPayload>('file-dropped-for-param', (event) => callback(event.payload));
+}
+
 // Global script event listeners
 export async function onGlobalScriptLog(
   callback: (payload: ScriptLogPayload) => void
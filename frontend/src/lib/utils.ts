@@ -1,10 +1,27 @@
 import { clsx, type ClassValue } from "clsx"
 import { twMerge } from "tailwind-merge"
+import type { Project, RunScriptError } from "@/types"
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
 
+/**
+ * Whether a service's process is up: running, healthy or not
+ */
+export function isServiceUp(status?: string): boolean {
+  return status === 'running' || status === 'unhealthy'
+}
+
+/**
+ * Root the project's services and scripts run from: the active variant's
+ * (e.g. a git worktree), or the project's own
+ */
+export function effectiveRoot(project: Project): string {
+  const variant = project.variants?.find((v) => v.id === project.activeVariantId)
+  return variant?.rootPath ?? project.rootPath
+}
+
 /**
  * Format a command for display, replacing {{SCRIPT_FILE}} with {{SCRIPT_FILE:filename}}
  */
@@ -13,3 +30,22 @@ export function formatCommandDisplay(command: string, scriptPath?: string | null
   const filename = scriptPath.replace(/\\/g, '/').split('/').pop() || scriptPath;
   return command.replace('{{SCRIPT_FILE}}', `{{SCRIPT_FILE:${filename}}}`);
 }
+
+type RequirementNotMetError = Extract<RunScriptError, { kind: 'requirementNotMet' }>;
+
+/** Whether a rejected `runGlobalScript` call failed because required services are down */
+export function isRequirementNotMet(e: unknown): e is RequirementNotMetError {
+  return typeof e === 'object' && e !== null && (e as RunScriptError).kind === 'requirementNotMet';
+}
+
+/** Readable message for an error from `runGlobalScript` (or any plain error) */
+export function runErrorMessage(e: unknown): string {
+  if (isRequirementNotMet(e)) {
+    const missing = e.missing.map((m) => (m.problem ? `${m.label} (${m.problem})` : m.label));
+    return `Required services not running: ${missing.join(', ')}`;
+  }
+  if (typeof e === 'object' && e !== null && (e as RunScriptError).kind === 'failed') {
+    return (e as { message: string }).message;
+  }
+  return String(e);
+}
@@ -8,6 +8,7 @@ import type {
   UpdateProjectInput,
   CreateServiceInput,
   UpdateServiceInput,
+  RestartResult,
   CreateScriptInput,
   UpdateScriptInput,
   ServiceStatus,
@@ -15,7 +16,10 @@ import type {
   LogEntry,
   View,
   EnvFile,
+  EnvFileVariant,
   EnvComparison,
+  EnvDiscoveryProgress,
+  EnvDiscoveryComplete,
   AddEnvFileInput,
   GlobalScript,
   CreateGlobalScriptInput,
@@ -24,11 +28,17 @@ import type {
   CreateTagDefinitionInput,
   UpdateTagDefinitionInput,
   ScriptsConfig,
-  ScriptParameter,
+  DetectedHelp,
   ImportOptions,
   ImportResult,
+  SyncPlan,
+  SavedProject,
+  RootOverlap,
+  SyncResolution,
+  SyncResult,
   ExportSummary,
-  DiscoveredScript,
+  ImportFilePreview,
+  ScriptScanResult,
   Tool,
   CreateToolInput,
   UpdateToolInput,
@@ -43,6 +53,7 @@ import type {
   UpdateAppInput,
 } from '@/types';
 import * as api from '@/lib/tauri';
+import { isServiceUp } from '@/lib/utils';
 
 interface ServiceRuntime {
   status: ServiceStatus;
@@ -54,6 +65,8 @@ interface ServiceRuntime {
   detectedPorts: number[];
   activeMode?: string;
   activeArgPreset?: string;
+  /** ISO timestamp of when the running process was spawned */
+  startedAt?: string;
 }
 
 interface ScriptRuntime {
@@ -206,6 +219,8 @@ interface AppState {
 
   // Environment files state
   isDiscoveringEnvFiles: boolean;
+  envDiscoveryId: string | null;
+  envDiscoveryProgress: EnvDiscoveryProgress | null;
   envFileComparisons: Map<string, EnvComparison>;
 
   // Global scripts
@@ -257,14 +272,16 @@ interface AppState {
 
   // Actions - Projects
   loadProjects: () => Promise<void>;
-  createProject: (input: CreateProjectInput) => Promise<Project>;
-  updateProject: (id: string, input: UpdateProjectInput) => Promise<void>;
+  createProject: (input: CreateProjectInput) => Promise<SavedProject>;
+  updateProject: (id: string, input: UpdateProjectInput) => Promise<RootOverlap[]>;
   deleteProject: (id: string) => Promise<void>;
   selectProject: (id: string | null) => void;
 
   // Actions - Services
   addService: (projectId: string, input: CreateServiceInput) => Promise<Service>;
   updateService: (serviceId: string, input: UpdateServiceInput) => Promise<void>;
+  // Save, then restart it with the saved definition if it was running
+  updateAndRestartService: (serviceId: string, input: UpdateServiceInput) => Promise<RestartResult>;
   deleteService: (serviceId: string) => Promise<void>;
 
   // Actions - Scripts
@@ -287,19 +304,23 @@ interface AppState {
   launchExternal: (serviceId: string) => Promise<void>;
 
   // Actions - Runtime updates
-  updateServiceStatus: (serviceId: string, status: ServiceStatus, pid?: number, activeMode?: string, activeArgPreset?: string) => void;
+  updateServiceStatus: (serviceId: string, status: ServiceStatus, pid?: number, activeMode?: string, activeArgPreset?: string, startedAt?: string) => void;
   updateServiceDetectedPorts: (serviceId: string, ports: number[]) => void;
   appendServiceLog: (serviceId: string, log: LogEntry) => void;
   clearServiceLogs: (serviceId: string) => void;
 
   // Actions - Environment files
-  discoverEnvFiles: (projectId: string, force?: boolean) => Promise<EnvFile[]>;
+  // Resolves to null when cancelled
+  discoverEnvFiles: (projectId: string, force?: boolean) => Promise<EnvFile[] | null>;
+  cancelEnvDiscovery: () => Promise<void>;
   addEnvFile: (projectId: string, input: AddEnvFileInput) => Promise<EnvFile>;
   removeEnvFile: (projectId: string, envFileId: string) => Promise<void>;
   refreshEnvFile: (projectId: string, envFileId: string) => Promise<EnvFile>;
   refreshAllEnvFiles: (projectId: string) => Promise<EnvFile[]>;
   compareEnvFiles: (projectId: string, baseFileId: string, exampleFileId: string) => Promise<EnvComparison>;
+  scaffoldEnvFromExample: (projectId: string, exampleFileId: string, targetPath?: string) => Promise<EnvFile>;
   linkEnvToService: (projectId: string, envFileId: string, serviceId: string | null) => Promise<void>;
+  updateEnvFileVariant: (projectId: string, envFileId: string, variant: EnvFileVariant | null) => Promise<void>;
 
   // Actions - Settings
   loadSettings: () => Promise<void>;
@@ -330,10 +351,11 @@ interface AppState {
   // Actions - Scripts Config
   loadScriptsConfig: () => Promise<void>;
   updateScriptsConfig: (config: ScriptsConfig) => Promise<void>;
-  scanScriptsFolder: (folder: string) => Promise<DiscoveredScript[]>;
+  scanScriptsFolder: (folder?: string) => Promise<ScriptScanResult>;
 
   // Actions - Help Parser
-  autoDetectScriptParams: (command: string, scriptPath?: string) => Promise<ScriptParameter[]>;
+  autoDetectScriptParams: (command: string, scriptPath?: string, token?: string) => Promise<DetectedHelp | null>;
+  cancelParameterDetection: (token: string) => Promise<void>;
 
   // Actions - Tools
   loadTools: () => Promise<void>;
@@ -368,8 +390,12 @@ interface AppState {
 
   // Actions - Import / Export / Backup
   exportScriptsConfig: () => Promise<string>;
+  exportScriptsConfigDialog: () => Promise<string | null>;
+  importScriptsConfigDialog: () => Promise<ImportFilePreview | null>;
   previewImport: (json: string) => Promise<ExportSummary>;
   importScriptsConfig: (json: string, options: ImportOptions) => Promise<ImportResult>;
+  syncWithExport: (json: string) => Promise<SyncPlan>;
+  applySyncPlan: (plan: SyncPlan, resolutions: Record<string, SyncResolution>) => Promise<SyncResult>;
   backupToGit: () => Promise<string>;
 
   // Actions - Execution History Update
@@ -423,6 +449,8 @@ export const useAppStore = create<AppState>((set, get) => ({
   serviceRuntimes: new Map(),
   scriptRuntimes: new Map(),
   isDiscoveringEnvFiles: false,
+  envDiscoveryId: null,
+  envDiscoveryProgress: null,
   envFileComparisons: new Map(),
   globalScripts: [],
   tagDefinitions: [],
@@ -469,16 +497,17 @@ export const useAppStore = create<AppState>((set, get) => ({
   },
 
   createProject: async (input) => {
-    const project = await api.createProject(input);
+    const { overlaps, ...project } = await api.createProject(input);
     set((state) => ({ projects: [...state.projects, project] }));
-    return project;
+    return { ...project, overlaps };
   },
 
   updateProject: async (id, input) => {
-    const updated = await api.updateProject(id, input);
+    const { overlaps, ...updated } = await api.updateProject(id, input);
     set((state) => ({
       projects: state.projects.map((p) => (p.id === id ? updated : p)),
     }));
+    return overlaps ?? [];
   },
 
   deleteProject: async (id) => {
@@ -520,6 +549,17 @@ export const useAppStore = create<AppState>((set, get) => ({
     }));
   },
 
+  updateAndRestartService: async (serviceId, input) => {
+    const result = await api.updateAndRestartService(serviceId, input);
+    set((state) => ({
+      projects: state.projects.map((p) => ({
+        ...p,
+        services: p.services.map((s) => (s.id === serviceId ? result.service : s)),
+      })),
+    }));
+    return result;
+  },
+
   deleteService: async (serviceId) => {
     await api.deleteService(serviceId);
     set((state) => ({
@@ -659,7 +699,7 @@ export const useAppStore = create<AppState>((set, get) => ({
   },
 
   // Runtime updates
-  updateServiceStatus: (serviceId, status, pid, activeMode, activeArgPreset) => {
+  updateServiceStatus: (serviceId, status, pid, activeMode, activeArgPreset, startedAt) => {
     set((state) => {
       const runtimes = new Map(state.serviceRuntimes);
       const existing = runtimes.get(serviceId) || { status: 'stopped', logs: [], detectedPorts: [] };
@@ -668,7 +708,8 @@ export const useAppStore = create<AppState>((set, get) => ({
       const detectedPorts = status === 'stopped' ? [] : existing.detectedPorts;
       const mode = status === 'stopped' ? undefined : (activeMode ?? existing.activeMode);
       const preset = status === 'stopped' ? undefined : (activeArgPreset ?? existing.activeArgPreset);
-      runtimes.set(serviceId, { ...existing, status, pid, detectedPorts, activeMode: mode, activeArgPreset: preset });
+      const started = isServiceUp(status) ? (startedAt ?? existing.startedAt) : undefined;
+      runtimes.set(serviceId, { ...existing, status, pid, detectedPorts, activeMode: mode, activeArgPreset: preset, startedAt: started });
       // Auto-create Terminal entity (in hidden state) if this is the first time we see this runtime
       const terminals = ensureTerminal(state.terminals, 'service', serviceId);
       return { serviceRuntimes: runtimes, terminals };
@@ -861,20 +902,56 @@ export const useAppStore = create<AppState>((set, get) => ({
 
   // Environment file actions
   discoverEnvFiles: async (projectId, force = false) => {
-    set({ isDiscoveringEnvFiles: true });
+    set({ isDiscoveringEnvFiles: true, envDiscoveryProgress: null });
+    // The walk may finish before its id comes back: keep early results
+    let discoveryId: string | null = null;
+    let finish: ((complete: EnvDiscoveryComplete) => void) | null = null;
+    const early = new Map<string, EnvDiscoveryComplete>();
+    const unlistenComplete = await api.onEnvDiscoveryComplete((complete) => {
+      if (complete.discoveryId === discoveryId && finish) {
+        finish(complete);
+      } else {
+        early.set(complete.discoveryId, complete);
+      }
+    });
+    const unlistenProgress = await api.onEnvDiscoveryProgress((progress) => {
+      if (progress.discoveryId === discoveryId) {
+        set({ envDiscoveryProgress: progress });
+      }
+    });
     try {
-      const envFiles = await api.discoverEnvFiles(projectId, { force });
+      const id = await api.discoverEnvFiles(projectId, { force });
+      discoveryId = id;
+      set({ envDiscoveryId: id });
+      const complete = early.get(id) ?? await new Promise<EnvDiscoveryComplete>((resolve) => {
+        finish = resolve;
+      });
+      if (complete.error) {
+        throw new Error(complete.error);
+      }
+      if (complete.cancelled) {
+        return null;
+      }
       set((state) => ({
         projects: state.projects.map((p) =>
-          p.id === projectId ? { ...p, envFiles, envFilesDiscovered: true } : p
+          p.id === projectId ? { ...p, envFiles: complete.envFiles, envFilesDiscovered: true } : p
         ),
-        isDiscoveringEnvFiles: false,
       }));
-      return envFiles;
+      return complete.envFiles;
     } catch (error) {
       console.error('Failed to discover env files:', error);
-      set({ isDiscoveringEnvFiles: false });
       throw error;
+    } finally {
+      unlistenComplete();
+      unlistenProgress();
+      set({ isDiscoveringEnvFiles: false, envDiscoveryId: null, envDiscoveryProgress: null });
+    }
+  },
+
+  cancelEnvDiscovery: async () => {
+    const id = get().envDiscoveryId;
+    if (id) {
+      await api.cancelEnvDiscovery(id);
     }
   },
 
@@ -911,6 +988,23 @@ export const useAppStore = create<AppState>((set, get) => ({
     return envFile;
   },
 
+  scaffoldEnvFromExample: async (projectId, exampleFileId, targetPath) => {
+    const envFile = await api.scaffoldEnvFromExample(projectId, exampleFileId, targetPath);
+    set((state) => ({
+      projects: state.projects.map((p) => {
+        if (p.id !== projectId) return p;
+        const tracked = p.envFiles.some((f) => f.id === envFile.id);
+        return {
+          ...p,
+          envFiles: tracked
+            ? p.envFiles.map((f) => (f.id === envFile.id ? envFile : f))
+            : [...p.envFiles, envFile],
+        };
+      }),
+    }));
+    return envFile;
+  },
+
   refreshAllEnvFiles: async (projectId) => {
     const envFiles = await api.refreshAllEnvFiles(projectId);
     set((state) => ({
@@ -942,6 +1036,17 @@ export const useAppStore = create<AppState>((set, get) => ({
     }));
   },
 
+  updateEnvFileVariant: async (projectId, envFileId, variant) => {
+    const envFile = await api.updateEnvFile(projectId, envFileId, variant);
+    set((state) => ({
+      projects: state.projects.map((p) =>
+        p.id === projectId
+          ? { ...p, envFiles: p.envFiles.map((f) => (f.id === envFileId ? envFile : f)) }
+          : p
+      ),
+    }));
+  },
+
   // Settings actions
   loadSettings: async () => {
     set({ isLoadingSettings: true });
@@ -1123,8 +1228,12 @@ export const useAppStore = create<AppState>((set, get) => ({
   },
 
   // Help Parser actions
-  autoDetectScriptParams: async (command, scriptPath?) => {
-    return api.autoDetectScriptParams(command, scriptPath);
+  autoDetectScriptParams: async (command, scriptPath?, token?) => {
+    return api.autoDetectScriptParams(command, scriptPath, token);
+  },
+
+  cancelParameterDetection: async (token) => {
+    await api.cancelParameterDetection(token);
   },
 
   // Import / Export actions
@@ -1132,6 +1241,25 @@ export const useAppStore = create<AppState>((set, get) => ({
     return api.exportScriptsConfig();
   },
 
+  exportScriptsConfigDialog: async () => {
+    const path = await api.exportScriptsConfigDialog();
+    if (path) {
+      // The backend remembered the directory; keep it so saving settings doesn't drop it
+      const { lastExportDir } = await api.getSettings();
+      set((state) => ({ settings: state.settings && { ...state.settings, lastExportDir } }));
+    }
+    return path;
+  },
+
+  importScriptsConfigDialog: async () => {
+    const preview = await api.importScriptsConfigDialog();
+    if (preview) {
+      const { lastExportDir } = await api.getSettings();
+      set((state) => ({ settings: state.settings && { ...state.settings, lastExportDir } }));
+    }
+    return preview;
+  },
+
   previewImport: async (json) => {
     return api.previewImport(json);
   },
@@ -1153,6 +1281,16 @@ export const useAppStore = create<AppState>((set, get) => ({
     return result;
   },
 
+  syncWithExport: async (json) => {
+    return api.syncWithExport(json);
+  },
+
+  applySyncPlan: async (plan, resolutions) => {
+    const result = await api.applySyncPlan(plan, resolutions);
+    set({ globalScripts: await api.getAllGlobalScripts() });
+    return result;
+  },
+
   backupToGit: async () => {
     return api.backupToGit();
   },
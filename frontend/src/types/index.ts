@@ -1,3 +1,38 @@
+// How much of the parent environment a spawned process inherits.
+// Allowlist patterns support `*` / `?` globs; `!NAME` drops a variable from the minimal set.
+export type EnvMode =
+  | { kind: 'inherit' }
+  | { kind: 'clean' }
+  | { kind: 'allowlist'; patterns: string[] };
+
+// What happens when a service with an idle timeout prints nothing for that long
+export type IdleAction = 'warn' | 'stop';
+
+// What a service health check probes
+export type HealthProbe =
+  | { kind: 'tcp'; port?: number }  // Defaults to the service's port
+  | { kind: 'http'; url: string; expectedStatus?: number };  // Plain http; any 2xx by default
+
+export interface HealthCheckConfig {
+  probe: HealthProbe;
+  intervalSecs?: number;  // Default 10
+  timeoutMs?: number;  // Default 2000
+  failureThreshold?: number;  // Failed checks in a row before unhealthy (default 3)
+}
+
+// What happens when a running service exits on its own
+export type RestartPolicy =
+  | { kind: 'never' }
+  | { kind: 'onFailure'; maxRetries: number }  // Non-zero exit or crash
+  | { kind: 'always'; maxRetries: number };
+
+// When auto-restarts of a crash-looping service stop
+export interface RestartCircuitConfig {
+  maxFailures?: number;  // Failed runs that open the circuit (default 5)
+  windowSecs?: number;  // Rolling window they're counted in (default 60)
+  coolDownSecs?: number;  // Wait before one retry (default 600); 0 waits for a manual start
+}
+
 export interface Service {
   id: string;
   name: string;
@@ -11,6 +46,17 @@ export interface Service {
   color?: string;
   port?: number;
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
+  watchPaths?: string[];  // Restart on changes (relative to workingDir)
+  watchDebounceMs?: number;
+  idleTimeoutSecs?: number;  // Seconds without output before idleAction applies
+  idleAction?: IdleAction;
+  healthCheck?: HealthCheckConfig;
+  restartPolicy?: RestartPolicy;
+  restartBackoffMs?: number;  // Doubled per restart in a row (default 1000)
+  restartCircuit?: RestartCircuitConfig;
+  stopGraceSeconds?: number;  // Wait for it to exit when stopped before killing it (default 5)
+  dependsOn?: string[];  // Service ids start-all brings up first
   order: number;
 }
 
@@ -23,9 +69,19 @@ export interface Script {
   workingDir: string;
   color?: string;
   linkedServiceIds: string[];
+  mutexGroup?: string;  // Scripts sharing a group never run at the same time
+  queueOnMutex?: boolean;  // Wait for the group instead of refusing to start
   order: number;
 }
 
+// Another checkout of a project (e.g. a git worktree) sharing its services and scripts
+export interface ProjectVariant {
+  id: string;
+  name: string;
+  rootPath: string;
+  envVarOverrides?: Record<string, string>;  // Over linked env files, under a service's own vars
+}
+
 export interface Project {
   id: string;
   name: string;
@@ -42,6 +98,8 @@ export interface Project {
   tags: string[];
   status?: string;
   toolboxUrl?: string;
+  variants?: ProjectVariant[];
+  activeVariantId?: string;  // Unset: services run from rootPath
 }
 
 // Environment file types
@@ -52,6 +110,11 @@ export interface EnvVariable {
   lineNumber: number;
 }
 
+export interface EnvParseError {
+  lineNumber: number;
+  reason: string;
+}
+
 export type EnvFileVariant =
   | 'base'
   | 'local'
@@ -68,7 +131,9 @@ export interface EnvFile {
   relativePath: string;
   filename: string;
   variant: EnvFileVariant;
+  variantOverridden?: boolean;
   variables: EnvVariable[];
+  parseErrors?: EnvParseError[];
   isManuallyAdded: boolean;
   linkedServiceId?: string;
   discoveredAt: string;
@@ -83,6 +148,18 @@ export interface EnvComparison {
   commonKeys: string[];
 }
 
+export interface EnvMatrix {
+  fileIds: string[];  // the columns, in the project's order
+  rows: EnvMatrixRow[];  // sorted by key
+}
+
+export interface EnvMatrixRow {
+  key: string;
+  values: (string | null)[];  // per file of fileIds; null where not defined
+  valuesDiffer: boolean;  // example files aside
+  missingInBase?: string[];  // .env file ids lacking a key their .env.example has
+}
+
 export type TerminalPreset =
   | 'windowsterminal'
   | 'powershell'
@@ -106,6 +183,14 @@ export interface DefaultsConfig {
   launchMethod: 'clipboard' | 'external' | 'integrated';
 }
 
+export type ShellKind = 'default' | 'sh' | 'bash' | 'zsh' | 'cmd' | 'powershell' | 'custom';
+
+export interface ShellConfig {
+  kind: ShellKind;
+  /** Executable used when `kind` is 'custom' */
+  customPath?: string;
+}
+
 export interface AppSettings {
   terminal: TerminalConfig;
   appearance: AppearanceConfig;
@@ -119,9 +204,111 @@ export interface AppSettings {
   /** Directory where alias shims (real launcher files) are written. Empty/undefined
    *  means use the platform default (`%LOCALAPPDATA%\CortX\bin`, `~/.local/share/CortX/bin`). */
   shimDir?: string;
+  /** Shell used to run service and script commands */
+  shell?: ShellConfig;
+  /** TUI: start with running scripts pinned to the top of the Scripts list */
+  pinRunningScripts?: boolean;
+  /** Directory of the last export/import file; file dialogs start here */
+  lastExportDir?: string;
+  /** Persisting service output to disk */
+  logging?: LoggingConfig;
+  /** TUI: when finished script runs drop their in-memory logs */
+  scriptRetention?: ScriptRetentionConfig;
+  /** Zip backups of the data files under `<appDir>/backups` */
+  backups?: BackupConfig;
+  /** TUI: per-frame limits on queued events handled before a redraw */
+  tuiEventBudget?: TuiEventBudgetConfig;
+  /** TUI: options of its settings screen (`,`) */
+  tui?: TuiConfig;
+  /** Show sensitive-looking env values (API_KEY, DB_PASSWORD, ...) as •••• */
+  maskEnvValues?: boolean;
+  /** OS-wide shortcuts, accelerator by action (see `ShortcutAction`) */
+  globalShortcuts?: Record<string, string>;
+}
+
+/** Key of `AppSettings.globalShortcuts`; a workspace is a project, by name */
+export type ShortcutAction = 'stopAll' | 'toggleWindow' | 'openPalette' | `startWorkspace:${string}`;
+
+export type ShortcutWarningKind = 'invalid' | 'duplicate' | 'unavailable';
+
+/** A global shortcut that isn't registered, and why */
+export interface ShortcutWarning {
+  action: ShortcutAction;
+  accelerator: string;
+  kind: ShortcutWarningKind;
+  message: string;
 }
 
-export type ServiceStatus = 'stopped' | 'starting' | 'running' | 'error';
+export interface TuiConfig {
+  maxLogLines: number;  // output lines kept per script / service
+  confirmOnQuit: boolean;  // a second `q` quits while anything runs
+  focusOutputOnRun: boolean;
+  keepBookmarks: boolean;  // output bookmarks survive the next run
+  detectPrompts: boolean;  // open the input box when a script waits at a prompt
+  promptPatterns?: string[];  // regexes added to the built-in prompt patterns
+  quickSlots?: Record<string, string>;  // quick run bar: slot (1-9) → script id
+}
+
+export interface BackupConfig {
+  keep: number;  // older backups are pruned beyond this many
+}
+
+export interface ScriptRetentionConfig {
+  maxAgeMinutes: number;
+  maxRetained: number;
+}
+
+export interface TuiEventBudgetConfig {
+  maxEvents: number;
+  maxMillis: number;
+}
+
+/** Service output written to `<appDir>/logs/<serviceId>/`, one file per
+ *  session, rotated by size */
+export interface LoggingConfig {
+  persistServiceLogs: boolean;
+  maxFileBytes: number;
+  maxFiles: number;  // oldest files are deleted beyond this many
+}
+
+export interface ServiceLogFile {
+  name: string;
+  size: number;
+  modified: string;
+}
+
+export interface ServiceLogPage {
+  lines: string[];
+  offset: number;
+  nextOffset: number;
+  hasMore: boolean;
+}
+
+// 'unhealthy': running, but its health check keeps failing
+// 'restarting': exited on its own, started again after a backoff
+// 'stopping': asked to exit, killed if still running after its grace period
+// 'circuitOpen': failed too often in a row; no more auto-restarts until started or reset
+export type ServiceStatus = 'stopped' | 'starting' | 'running' | 'unhealthy' | 'restarting' | 'stopping' | 'error' | 'circuitOpen';
+
+// Why a service stopped restarting itself
+export interface ServiceCircuit {
+  serviceId: string;
+  failures: number;  // Failed runs within the window
+  lastExit: string;  // "code 1", or "killed"
+  openedAt: string;
+  retryAt?: string;  // None when only a manual start retries
+}
+
+// Latest health check of a running service
+export interface ServiceHealth {
+  serviceId: string;
+  pid: number;
+  healthy: boolean;
+  lastCheckAt?: string;  // None until the first check
+  latencyMs?: number;  // Of the last successful check
+  consecutiveFailures: number;
+  lastError?: string;
+}
 
 export interface ServiceState {
   serviceId: string;
@@ -129,9 +316,30 @@ export interface ServiceState {
   pid?: number;
   activeMode?: string;  // Track which mode is running
   activeArgPreset?: string;  // Track which arg preset is active
+  startedAt?: string;  // ISO timestamp of the running process's spawn
 }
 
-export type LogStream = 'stdout' | 'stderr';
+export type RunningTargetKind = 'service' | 'project_script' | 'global_script';
+
+export interface RunningProcess {
+  targetKind: RunningTargetKind;
+  targetId: string;
+  runId: string;  // differs between two starts of the same target
+  projectId: string | null;
+  name: string;
+  pid: number;
+  startedAt: string;
+  status: 'running' | 'external';  // external: started by another CortX instance
+  variantId?: string;  // Project variant it was started under
+}
+
+export interface RunningCounts {
+  services: number;
+  scripts: number;
+  globalScripts: number;
+}
+
+export type LogStream = 'stdout' | 'stderr' | 'command';  // command: the echoed run command
 
 export interface LogEntry {
   timestamp: string;
@@ -144,6 +352,7 @@ export interface ServiceLogPayload {
   serviceId: string;
   stream: LogStream;
   content: string;
+  timestamp?: string;  // when the line was read from the process
 }
 
 export interface ServiceStatusPayload {
@@ -152,11 +361,14 @@ export interface ServiceStatusPayload {
   pid?: number;
   activeMode?: string;
   activeArgPreset?: string;
+  startedAt?: string;
 }
 
 export interface ServiceExitPayload {
   serviceId: string;
   exitCode?: number;
+  /** Last stderr lines; only set when the service failed right after starting */
+  errorExcerpt?: string;
 }
 
 export interface ServicePortsPayload {
@@ -164,6 +376,39 @@ export interface ServicePortsPayload {
   ports: number[];
 }
 
+// Storage change events: ids only, fetch what you need
+export type ChangeKind = 'created' | 'updated' | 'deleted';
+
+export interface EntityChangedPayload {
+  id: string;
+  change: ChangeKind;
+}
+
+// service-changed / script-changed (scripts of a project)
+export interface ProjectItemChangedPayload {
+  projectId: string;
+  id: string;
+  change: ChangeKind;
+}
+
+// Data files whose latest changes couldn't be written (save-status-changed)
+export type StorageFile =
+  | 'projects'
+  | 'settings'
+  | 'globalScripts'
+  | 'tagDefinitions'
+  | 'executionHistory'
+  | 'tools'
+  | 'aliases'
+  | 'statusDefinitions'
+  | 'apps';
+
+export interface PendingSave {
+  file: StorageFile;
+  error: string;
+  failedAt: string;
+}
+
 // Script types
 export type ScriptStatus = 'idle' | 'running' | 'completed' | 'failed';
 
@@ -171,18 +416,27 @@ export interface ScriptLogPayload {
   scriptId: string;
   stream: LogStream;
   content: string;
+  timestamp?: string;  // when the line was read from the process
+  runId?: string;  // set on concurrent runs of a global script
 }
 
 export interface ScriptStatusPayload {
   scriptId: string;
   status: ScriptStatus;
   pid?: number;
+  runId?: string;  // set on concurrent runs of a global script
 }
 
 export interface ScriptExitPayload {
   scriptId: string;
   exitCode?: number;
   success: boolean;
+  runId?: string;  // set on concurrent runs of a global script
+}
+
+// Files dropped on the window, for the open run form
+export interface FileDroppedPayload {
+  paths: string[];
 }
 
 // Input types for commands
@@ -194,6 +448,7 @@ export interface CreateProjectInput {
   tags?: string[];
   status?: string;
   toolboxUrl?: string;
+  allowDuplicate?: boolean;  // Accept a root another project already has
 }
 
 export interface UpdateProjectInput {
@@ -204,8 +459,54 @@ export interface UpdateProjectInput {
   tags?: string[];
   status?: string;
   toolboxUrl?: string;
+  allowDuplicate?: boolean;
+}
+
+// How a project's root relates to another project's
+export type RootRelation = 'same' | 'inside' | 'contains';
+
+export interface RootOverlap {
+  projectId: string;
+  projectName: string;
+  rootPath: string;
+  relation: RootRelation;
+}
+
+// A created or updated project, with the projects its root overlaps
+export type SavedProject = Project & { overlaps?: RootOverlap[] };
+
+export interface CreateProjectVariantInput {
+  name: string;
+  rootPath: string;
+  envVarOverrides?: Record<string, string>;
 }
 
+// Result of switching variants; stillRunning lists services started under another one
+export interface VariantSwitch {
+  project: Project;
+  stillRunning: string[];
+}
+
+export interface ServiceGroupResult {
+  serviceId: string;
+  pid?: number;
+  error?: string;
+  skipped?: boolean;  // Already running (start-all) / not running (stop-all)
+}
+
+// Outcome of saving a service and restarting it with the saved definition
+export interface RestartResult {
+  service: Service;
+  restarted: boolean;  // False: it wasn't running, only saved
+  pid?: number;
+}
+
+// What start-all waits for after each service (default: settle)
+export type StartWait =
+  | { kind: 'settle' }
+  | { kind: 'delay'; ms: number }
+  | { kind: 'port'; timeoutMs: number };  // Services without a port settle
+
 export interface CreateServiceInput {
   name: string;
   workingDir: string;
@@ -218,6 +519,17 @@ export interface CreateServiceInput {
   color?: string;
   port?: number;
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
+  watchPaths?: string[];
+  watchDebounceMs?: number;
+  idleTimeoutSecs?: number;  // 0 turns it off on update
+  idleAction?: IdleAction;
+  healthCheck?: HealthCheckConfig;
+  restartPolicy?: RestartPolicy;
+  restartBackoffMs?: number;  // Doubled per restart in a row (default 1000)
+  restartCircuit?: RestartCircuitConfig;
+  stopGraceSeconds?: number;  // Wait for it to exit when stopped before killing it (default 5)
+  dependsOn?: string[];
 }
 
 export interface UpdateServiceInput {
@@ -232,6 +544,17 @@ export interface UpdateServiceInput {
   color?: string;
   port?: number;
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
+  watchPaths?: string[];
+  watchDebounceMs?: number;
+  idleTimeoutSecs?: number;  // 0 turns it off on update
+  idleAction?: IdleAction;
+  healthCheck?: HealthCheckConfig;
+  restartPolicy?: RestartPolicy;
+  restartBackoffMs?: number;  // Doubled per restart in a row (default 1000)
+  restartCircuit?: RestartCircuitConfig;
+  stopGraceSeconds?: number;  // Wait for it to exit when stopped before killing it (default 5)
+  dependsOn?: string[];
 }
 
 export interface CreateScriptInput {
@@ -242,6 +565,8 @@ export interface CreateScriptInput {
   workingDir: string;
   color?: string;
   linkedServiceIds?: string[];
+  mutexGroup?: string;
+  queueOnMutex?: boolean;
 }
 
 export interface UpdateScriptInput {
@@ -252,6 +577,8 @@ export interface UpdateScriptInput {
   workingDir?: string;
   color?: string;
   linkedServiceIds?: string[];
+  mutexGroup?: string;
+  queueOnMutex?: boolean;
 }
 
 // Environment file input types
@@ -260,6 +587,24 @@ export interface DiscoverEnvFilesInput {
   force: boolean;
 }
 
+// Sent at most every 200ms while an env file discovery walks the project
+export interface EnvDiscoveryProgress {
+  discoveryId: string;
+  dirsScanned: number;
+  filesFound: number;
+  currentPath: string;
+}
+
+// Sent once a discovery ended; the project is only updated when it completed
+export interface EnvDiscoveryComplete {
+  discoveryId: string;
+  projectId: string;
+  envFiles: EnvFile[];  // As saved; empty when cancelled or failed
+  truncated: boolean;  // Stopped at the scan limits
+  cancelled: boolean;
+  error?: string;
+}
+
 export interface AddEnvFileInput {
   path: string;
 }
@@ -284,6 +629,12 @@ export interface ScriptParameter {
   required: boolean;
   enumValues: string[];
   nargs?: string;
+  mutexGroup?: string;  // members of a group are mutually exclusive
+}
+
+export interface DetectedHelp {
+  parameters: ScriptParameter[];
+  rawHelp: string;
 }
 
 export interface ParameterPreset {
@@ -303,15 +654,82 @@ export interface GlobalScript {
   workingDir?: string;
   color?: string;
   tags: string[];
+  folder?: string;
   parameters: ScriptParameter[];
   parameterPresets: ParameterPreset[];
   defaultPresetId?: string;
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
   createdAt: string;
   updatedAt: string;
   order: number;
   autoDiscovered: boolean;
+  contentHash?: string;  // Content of scriptPath at import, to follow moves
   status?: string;
+  rawHelp?: string;  // Full --help output from the last detection
+  requires?: ServiceRequirement[];  // Services that must be running first
+  artifacts?: string[];  // Glob patterns (relative to the working dir) of files a run creates
+  origin?: ScriptOrigin;  // Set when imported from an export, to sync later versions of it
+  outputFormat?: OutputFormat;  // How its stdout is displayed (default: raw)
+  allowConcurrent?: boolean;  // Starting it while it runs starts another run
+  mutexGroup?: string;  // Scripts sharing a group never run at the same time
+  queueOnMutex?: boolean;  // Wait for the group instead of refusing to start
+  schedule?: string;  // Cron expression (5 or 6 fields) it runs on
+}
+
+// Display-only: logs and exports keep the raw output
+export type OutputFormat = 'raw' | 'prettyJson' | 'table';
+
+// Where an imported script came from
+export interface ScriptOrigin {
+  sourceId: string;  // Id of the script in the export
+  sourceName: string;
+  importedAt: string;
+  lastSyncedHash: string;  // Upstream version last applied
+}
+
+// A project service a global script needs running (ids or names)
+export interface ServiceRequirement {
+  projectNameOrId: string;
+  serviceNameOrId: string;
+  autoStart: boolean;
+}
+
+export type RequirementState = 'running' | 'stopped' | 'unresolved';
+
+export interface RequirementStatus {
+  requirement: ServiceRequirement;
+  projectId?: string;
+  serviceId?: string;
+  label: string;  // "project / service"
+  state: RequirementState;
+  problem?: string;  // Why it couldn't be resolved
+}
+
+// Error of run_global_script
+export type RunScriptError =
+  | {
+      kind: 'requirementNotMet';
+      scriptName: string;
+      missing: RequirementStatus[];
+      startAndRetry: boolean;  // Every missing service may be auto-started
+    }
+  | { kind: 'failed'; message: string };
+
+// A global script of a DAG run, and the scripts it waits for
+export interface ScriptDependency {
+  scriptId: string;
+  dependsOn?: string[];
+}
+
+export type ScriptNodeOutcome =
+  | { kind: 'succeeded' }
+  | { kind: 'failed'; error: string }
+  | { kind: 'skipped'; failedDependency: string };  // A script it depends on failed
+
+export interface ScriptNodeResult {
+  scriptId: string;
+  outcome: ScriptNodeOutcome;
 }
 
 export interface TagDefinition {
@@ -330,6 +748,65 @@ export interface ExecutionRecord {
   exitCode?: number;
   parametersUsed: Record<string, string>;
   presetName?: string;
+  workingDir?: string;
+  extraArgs?: string[];
+  parametersSignature?: string;
+  logStart?: number;  // byte range of the run's output in the script's log
+  logEnd?: number;
+  envSnapshot?: Record<string, string>;  // injected env vars, secrets masked or omitted
+  artifacts?: string[];  // files matching the script's artifact patterns after a successful run
+  scheduled?: boolean;  // started by the script's schedule
+}
+
+export interface ReplayOutcome {
+  pid: number;
+  warnings: string[];  // e.g. the script's parameters changed since the run
+}
+
+export interface RunSide {
+  recordId: string;
+  startedAt: string;
+  durationMs?: number;
+  exitCode?: number;
+  success: boolean;
+  presetName?: string;
+  hasLog: boolean;
+}
+
+export interface ValueChange {
+  key: string;
+  a?: string;  // unset in run A when missing
+  b?: string;
+}
+
+export interface DiffLine {
+  change: 'equal' | 'removed' | 'added';
+  text: string;
+}
+
+// Starts are 0-based line indices
+export interface DiffHunk {
+  aStart: number;
+  aLen: number;
+  bStart: number;
+  bLen: number;
+  lines: DiffLine[];
+}
+
+export interface LogDiff {
+  hunks: DiffHunk[];
+  added: number;
+  removed: number;
+}
+
+export interface RunComparison {
+  scriptId: string;
+  a: RunSide;
+  b: RunSide;
+  paramChanges: ValueChange[];
+  durationDeltaMs?: number;  // B minus A
+  exitChanged: boolean;
+  logDiff?: LogDiff;  // missing unless both logs are available
 }
 
 export interface ScriptsConfig {
@@ -338,6 +815,46 @@ export interface ScriptsConfig {
   ignoredPatterns: string[];
   autoScanOnStartup: boolean;
   commandTemplates: Record<string, string>;
+  scanMaxEntries?: number;
+  scanMaxSeconds?: number;
+  organizeRules?: OrganizeRule[];
+  logNoisePatterns?: string[];  // extra regexes masked before diffing run logs
+  envSnapshot?: EnvSnapshotConfig;
+  maxRunDepth?: number;  // nesting limit for scripts that invoke cortx (0 = none)
+  echoCommand?: boolean;  // start each run's output with `$ <command>`
+}
+
+// What execution history records of a run's injected env vars
+export interface EnvSnapshotConfig {
+  enabled: boolean;
+  secrets: 'mask' | 'omit';
+  secretPatterns: string[];  // globs, case-insensitive
+}
+
+export type OrganizeMatch = 'nameGlob' | 'pathGlob' | 'extensionIs' | 'commandContains';
+
+// Tags accumulate across matching rules; the last folder / color wins
+export interface OrganizeRule {
+  match: OrganizeMatch;
+  pattern: string;
+  assignFolder?: string;
+  addTags?: string[];
+  setColor?: string;
+}
+
+export interface PlannedChange {
+  scriptId: string;
+  scriptName: string;
+  folder?: string;
+  addTags?: string[];
+  color?: string;
+  createsFolder: boolean;
+}
+
+export interface OrganizeResult {
+  changes: PlannedChange[];
+  createdFolders: string[];
+  applied: boolean;  // false for a dry run
 }
 
 export interface DiscoveredScript {
@@ -345,6 +862,46 @@ export interface DiscoveredScript {
   name: string;
   description?: string;
   extension: string;
+  contentHash?: string;
+}
+
+// An auto-discovered script whose file moved, now pointing at it
+export interface ScriptRelocation {
+  scriptId: string;
+  scriptName: string;
+  oldPath: string;
+  newPath: string;
+}
+
+export type UnresolvedMoveReason = 'ambiguous' | 'contentChanged';
+
+// A file that may be a moved script, left to match up by hand
+export interface UnresolvedMove {
+  path: string;
+  candidateIds: string[];
+  reason: UnresolvedMoveReason;
+}
+
+export interface ScriptScanResult {
+  scripts: DiscoveredScript[];
+  entriesVisited: number;
+  truncated: boolean;
+  relocated?: ScriptRelocation[];
+  unresolvedMoves?: UnresolvedMove[];
+  vanished?: VanishedScript[];
+}
+
+// An auto-discovered script whose file is gone
+export interface VanishedScript {
+  scriptId: string;
+  scriptName: string;
+  path: string;
+}
+
+export interface DiscoveredImport {
+  imported: GlobalScript[];
+  skipped?: string[];  // already registered, or no longer a file
+  organized: number;  // changed by the organize rules
 }
 
 // Discovered tool (from package manager scanning)
@@ -418,6 +975,19 @@ export interface UpdateToolInput {
 
 // Input types for global script commands
 
+export type ValidationIssueKind =
+  | 'commandNotFound'
+  | 'scriptPathMissing'
+  | 'workingDirMissing'
+  | 'scriptFileNotReferenced';
+
+// Pre-save warning about a global script (never blocks saving)
+export interface ValidationIssue {
+  kind: ValidationIssueKind;
+  field: 'command' | 'scriptPath' | 'workingDir';
+  message: string;
+}
+
 export interface CreateGlobalScriptInput {
   name: string;
   description?: string;
@@ -426,10 +996,20 @@ export interface CreateGlobalScriptInput {
   workingDir?: string;
   color?: string;
   tags?: string[];
+  folder?: string;
   parameters?: ScriptParameter[];
   parameterPresets?: ParameterPreset[];
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
   status?: string;
+  requires?: ServiceRequirement[];
+  artifacts?: string[];
+  outputFormat?: OutputFormat;
+  autoDiscovered?: boolean;  // Imported from a folder scan
+  allowConcurrent?: boolean;
+  mutexGroup?: string;
+  queueOnMutex?: boolean;
+  schedule?: string;
 }
 
 export interface UpdateGlobalScriptInput {
@@ -440,11 +1020,21 @@ export interface UpdateGlobalScriptInput {
   workingDir?: string;
   color?: string;
   tags?: string[];
+  folder?: string;
   parameters?: ScriptParameter[];
   parameterPresets?: ParameterPreset[];
   defaultPresetId?: string;
   envVars?: Record<string, string>;
+  envMode?: EnvMode;
   status?: string;
+  rawHelp?: string;
+  requires?: ServiceRequirement[];
+  artifacts?: string[];
+  outputFormat?: OutputFormat;
+  allowConcurrent?: boolean;
+  mutexGroup?: string;
+  queueOnMutex?: boolean;
+  schedule?: string;
 }
 
 export interface CreateTagDefinitionInput {
@@ -459,6 +1049,19 @@ export interface UpdateTagDefinitionInput {
   order?: number;
 }
 
+// Zip backups (Storage::create_backup)
+export interface BackupInfo {
+  name: string;
+  path: string;
+  size: number;
+  createdAt: string;
+}
+
+export interface RestoreResult {
+  restoredFiles: string[];
+  previous: BackupInfo;  // backup of the files as they were before the restore
+}
+
 // Import/Export result
 export interface ImportResult {
   scriptsAdded: number;
@@ -472,6 +1075,47 @@ export interface ImportResult {
   settingsImported: boolean;
 }
 
+// Syncing imported scripts with a newer version of their export
+export type SyncStatus = 'upToDate' | 'upstreamUpdated' | 'locallyModified' | 'conflict' | 'added';
+
+export type SyncResolution = 'keepLocal' | 'takeUpstream';
+
+export interface FieldDiff {
+  field: string;  // camelCase field of GlobalScript
+  local: unknown;
+  upstream: unknown;
+}
+
+export interface SyncEntry {
+  sourceId: string;
+  name: string;
+  localId?: string;
+  status: SyncStatus;
+  localHash?: string;
+  upstreamHash: string;
+  diff?: FieldDiff[];
+  upstream: GlobalScript;
+}
+
+export interface SyncPlan {
+  entries: SyncEntry[];
+  skipped: string[];  // Incoming scripts whose id a local-only script has
+}
+
+export interface SyncResult {
+  updated: string[];
+  added: string[];
+  kept: string[];
+  unresolved: string[];  // Conflicts without a resolution
+  stale: string[];  // Changed locally since the plan was made
+}
+
+export interface ProjectImportResult {
+  projectsAdded: number;
+  skipped: number;  // Projects whose id is already known
+  needsPath: PathRemapRequest[];  // Added, but their root path is missing here
+}
+
 export interface ImportOptions {
   projects: boolean;
   scripts: boolean;
@@ -495,6 +1139,44 @@ export interface ExportSummary {
   hasSettings: boolean;
 }
 
+/** An import file picked through the native dialog; not imported yet */
+export interface ImportFilePreview {
+  path: string;
+  json: string;
+  summary: ExportSummary;
+}
+
+// Data-dir migration
+export type MigrationItemStatus = 'new' | 'identical' | 'conflict';
+
+export interface MigrationItem {
+  kind: 'project' | 'script' | 'tool' | 'app' | 'alias' | 'tag' | 'status';
+  id: string;
+  name: string;
+  status: MigrationItemStatus;
+}
+
+export interface PathRemapRequest {
+  projectId: string;
+  projectName: string;
+  oldRootPath: string;
+}
+
+export interface PathMapping {
+  projectId: string;
+  newRootPath: string;
+}
+
+export interface MigrationReport {
+  sourceDir: string;
+  items: MigrationItem[];
+  needsPath: PathRemapRequest[];
+  imagesToCopy: string[];
+  hasSettings: boolean;
+  problems: string[];
+  ignoredFiles: string[];
+}
+
 // View types
 export type View = 'dashboard' | 'project' | 'settings' | 'scripts' | 'script-detail' | 'tools' | 'tool-detail' | 'aliases' | 'alias-detail' | 'apps' | 'app-detail';
 
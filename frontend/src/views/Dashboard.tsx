@@ -27,7 +27,8 @@ import {
   AlertDialogTitle,
 } from '@/components/ui/alert-dialog';
 import { Plus, Search, LayoutGrid } from 'lucide-react';
-import type { Project, CreateProjectInput, UpdateProjectInput } from '@/types';
+import { toast } from 'sonner';
+import type { Project, CreateProjectInput, UpdateProjectInput, RootOverlap } from '@/types';
 
 type SortOption = 'recent' | 'name' | 'created' | 'status';
 
@@ -112,8 +113,21 @@ export function Dashboard() {
     });
   }, [projects, selectedTags, selectedStatus, search, sort, statusDefinitions]);
 
+  // Nested projects are allowed, but track some files twice
+  const warnRootOverlaps = (overlaps: RootOverlap[]) => {
+    for (const overlap of overlaps) {
+      const where = overlap.relation === 'same'
+        ? 'shares its folder with'
+        : overlap.relation === 'inside' ? 'is inside' : 'contains';
+      toast.warning(`This project ${where} project "${overlap.projectName}"`, {
+        description: overlap.rootPath,
+      });
+    }
+  };
+
   const handleAddProject = async (data: CreateProjectInput | UpdateProjectInput) => {
-    await createProject(data as CreateProjectInput);
+    const saved = await createProject(data as CreateProjectInput);
+    warnRootOverlaps(saved.overlaps ?? []);
   };
 
   const handleEditProject = async (data: CreateProjectInput | UpdateProjectInput) => {
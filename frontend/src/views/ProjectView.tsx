@@ -1,5 +1,6 @@
 import { useState } from 'react';
 import { useAppStore } from '@/stores/appStore';
+import { effectiveRoot, isServiceUp } from '@/lib/utils';
 import { ServiceItem } from '@/components/projects/ServiceItem';
 import { ServiceForm } from '@/components/projects/ServiceForm';
 import { ProjectForm } from '@/components/projects/ProjectForm';
@@ -59,6 +60,7 @@ export function ProjectView() {
     settings,
     addService,
     updateService,
+    updateAndRestartService,
     deleteService,
     updateProject,
     deleteProject,
@@ -94,7 +96,7 @@ export function ProjectView() {
 
   const runningServices = project.services.filter((s) => {
     const runtime = serviceRuntimes.get(s.id);
-    return runtime?.status === 'running';
+    return isServiceUp(runtime?.status);
   });
 
   const handleBack = () => {
@@ -102,11 +104,11 @@ export function ProjectView() {
   };
 
   const handleOpenFolder = () => {
-    openInExplorer(project.rootPath).catch(console.error);
+    openInExplorer(effectiveRoot(project)).catch(console.error);
   };
 
   const handleOpenInVscode = () => {
-    openInVscode(project.rootPath).catch((error) => {
+    openInVscode(effectiveRoot(project)).catch((error) => {
       toast.error('Failed to open VSCode', {
         description: String(error),
       });
@@ -126,6 +128,16 @@ export function ProjectView() {
     }
   };
 
+  const handleEditAndRestartService = async (data: UpdateServiceInput) => {
+    if (editingService) {
+      const result = await updateAndRestartService(editingService.id, data);
+      setEditingService(null);
+      toast.success(result.restarted ? 'Service updated and restarted' : 'Service updated', {
+        description: result.restarted ? undefined : 'It was not running',
+      });
+    }
+  };
+
   const handleDeleteService = async () => {
     if (deletingService) {
       await deleteService(deletingService.id);
@@ -187,7 +199,7 @@ export function ProjectView() {
   const handleStopAll = async () => {
     for (const service of project.services) {
       const runtime = serviceRuntimes.get(service.id);
-      if (runtime?.status === 'running') {
+      if (isServiceUp(runtime?.status)) {
         try {
           await stopService(service.id);
         } catch (error) {
@@ -464,6 +476,7 @@ export function ProjectView() {
           service={editingService}
           projectPath={project.rootPath}
           onSubmit={handleEditService}
+          onSubmitAndRestart={handleEditAndRestartService}
         />
       )}
 
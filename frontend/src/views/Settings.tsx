@@ -22,15 +22,17 @@ import {
   DialogTitle,
 } from '@/components/ui/dialog';
 import { Checkbox } from '@/components/ui/checkbox';
-import { FolderOpen, Save, Info, Download, Upload, Plus, Trash2, RotateCcw, Tags, Copy, Check, TerminalSquare, ChevronDown, ChevronUp, CircleDot, GitBranch, Globe } from 'lucide-react';
+import { FolderOpen, Save, Info, Download, Upload, Plus, Trash2, RotateCcw, Tags, Copy, Check, TerminalSquare, ChevronDown, ChevronUp, CircleDot, GitBranch, Globe, RefreshCw } from 'lucide-react';
 import { toast } from 'sonner';
 import { TagDefinitionManager } from '@/components/global-scripts/TagDefinitionManager';
 import { StatusDefinitionManager } from '@/components/settings/StatusDefinitionManager';
-import { generateShellInit, setGlobalHotkey as setGlobalHotkeyApi, getShimStatus, syncShims, installShimPath } from '@/lib/tauri';
+import { ScriptSyncDialog } from '@/components/settings/ScriptSyncDialog';
+import { generateShellInit, setGlobalHotkey as setGlobalHotkeyApi, getGlobalShortcutWarnings, getShimStatus, syncShims, installShimPath } from '@/lib/tauri';
 import { HotkeyInput } from '@/components/settings/HotkeyInput';
-import type { AppSettings, TerminalPreset, ExportSummary, ImportOptions, ShimStatus } from '@/types';
+import type { AppSettings, ShellKind, TerminalPreset, ExportSummary, ImportOptions, ShimStatus, ShortcutWarning, SyncPlan } from '@/types';
 
 const DEFAULT_GLOBAL_HOTKEY = 'CmdOrCtrl+Shift+Space';
+const WORKSPACE_PREFIX = 'startWorkspace:';
 
 // Terminal preset labels and descriptions
 const TERMINAL_PRESETS: {
@@ -92,7 +94,7 @@ const getPlatform = (): 'windows' | 'macos' | 'linux' => {
 };
 
 export function Settings() {
-  const { settings, loadSettings, updateSettings, isLoadingSettings, exportScriptsConfig, previewImport, importScriptsConfig, backupToGit } = useAppStore();
+  const { settings, projects, loadSettings, updateSettings, isLoadingSettings, exportScriptsConfigDialog, importScriptsConfigDialog, importScriptsConfig, syncWithExport, backupToGit } = useAppStore();
   const platform = getPlatform();
 
   const [showTagManager, setShowTagManager] = useState(false);
@@ -110,19 +112,26 @@ export function Settings() {
     settings: true,
   });
   const [isImporting, setIsImporting] = useState(false);
+  const [syncPlan, setSyncPlan] = useState<SyncPlan | null>(null);
   const [terminalPreset, setTerminalPreset] = useState<TerminalPreset>('windowsterminal');
   const [customPath, setCustomPath] = useState('');
   const [customArgs, setCustomArgs] = useState('');
   const [theme, setTheme] = useState<'light' | 'dark' | 'system'>('system');
   const [launchMethod, setLaunchMethod] = useState<'clipboard' | 'external' | 'integrated'>('integrated');
+  const [shellKind, setShellKind] = useState<ShellKind>('default');
+  const [shellCustomPath, setShellCustomPath] = useState('');
   const [toolboxBaseUrl, setToolboxBaseUrl] = useState('');
   const [backupRepoPath, setBackupRepoPath] = useState('');
   const [globalHotkey, setGlobalHotkey] = useState<string>(DEFAULT_GLOBAL_HOTKEY);
+  const [globalShortcuts, setGlobalShortcuts] = useState<Record<string, string>>({});
+  const [shortcutWarnings, setShortcutWarnings] = useState<ShortcutWarning[]>([]);
   const [isBackingUp, setIsBackingUp] = useState(false);
   const [shimDir, setShimDir] = useState('');
   const [shimStatus, setShimStatus] = useState<ShimStatus | null>(null);
   const [isInstallingPath, setIsInstallingPath] = useState(false);
   const [commandTemplates, setCommandTemplates] = useState<Record<string, string>>({});
+  const [echoCommand, setEchoCommand] = useState(false);
+  const [maskEnvValues, setMaskEnvValues] = useState(false);
   const [newExtension, setNewExtension] = useState('');
   const [hasChanges, setHasChanges] = useState(false);
 
@@ -133,6 +142,10 @@ export function Settings() {
     if (!settings) loadSettings();
   }, [settings, loadSettings]);
 
+  useEffect(() => {
+    getGlobalShortcutWarnings().then(setShortcutWarnings).catch(console.error);
+  }, []);
+
   useEffect(() => {
     if (settings) {
       setTerminalPreset(settings.terminal.preset);
@@ -140,11 +153,16 @@ export function Settings() {
       setCustomArgs(settings.terminal.customArgs.join(' '));
       setTheme(settings.appearance.theme);
       setLaunchMethod(settings.defaults.launchMethod);
+      setShellKind(settings.shell?.kind ?? 'default');
+      setShellCustomPath(settings.shell?.customPath ?? '');
       setToolboxBaseUrl(settings.toolboxBaseUrl ?? '');
       setBackupRepoPath(settings.backupRepoPath ?? '');
       setGlobalHotkey(settings.globalHotkey ?? DEFAULT_GLOBAL_HOTKEY);
+      setGlobalShortcuts(settings.globalShortcuts ?? {});
       setShimDir(settings.shimDir ?? '');
       setCommandTemplates(settings.scriptsConfig.commandTemplates ?? {});
+      setEchoCommand(settings.scriptsConfig.echoCommand ?? false);
+      setMaskEnvValues(settings.maskEnvValues ?? false);
       setHasChanges(false);
     }
   }, [settings]);
@@ -204,15 +222,8 @@ export function Settings() {
 
   const handleExport = async () => {
     try {
-      const json = await exportScriptsConfig();
-      const blob = new Blob([json], { type: 'application/json' });
-      const url = URL.createObjectURL(blob);
-      const a = document.createElement('a');
-      a.href = url;
-      a.download = 'cortx-export.json';
-      a.click();
-      URL.revokeObjectURL(url);
-      toast.success('Scripts config exported');
+      const path = await exportScriptsConfigDialog();
+      if (path) toast.success(`Exported to ${path}`);
     } catch (error) {
       toast.error(`Failed to export: ${error}`);
     }
@@ -220,15 +231,9 @@ export function Settings() {
 
   const handleImport = async () => {
     try {
-      const selected = await open({
-        multiple: false,
-        title: 'Import CortX Config',
-        filters: [{ name: 'JSON', extensions: ['json'] }],
-      });
-      if (selected && typeof selected === 'string') {
-        const { readTextFile } = await import('@tauri-apps/plugin-fs');
-        const json = await readTextFile(selected);
-        const summary = await previewImport(json);
+      const preview = await importScriptsConfigDialog();
+      if (preview) {
+        const { json, summary } = preview;
         setImportJson(json);
         setImportSummary(summary);
         setImportOptions({
@@ -247,6 +252,16 @@ export function Settings() {
     }
   };
 
+  // Compare a newer version of a shared export with the scripts imported from it
+  const handleSync = async () => {
+    try {
+      const preview = await importScriptsConfigDialog();
+      if (preview) setSyncPlan(await syncWithExport(preview.json));
+    } catch (error) {
+      toast.error(`Failed to compare with export: ${error}`);
+    }
+  };
+
   const handleConfirmImport = async () => {
     if (!importJson) return;
     setIsImporting(true);
@@ -302,6 +317,23 @@ export function Settings() {
     }
   };
 
+  const setShortcut = (action: string, combo: string) => {
+    setGlobalShortcuts((shortcuts) => ({ ...shortcuts, [action]: combo }));
+    setHasChanges(true);
+  };
+
+  const removeShortcut = (action: string) => {
+    setGlobalShortcuts((shortcuts) => {
+      const next = { ...shortcuts };
+      delete next[action];
+      return next;
+    });
+    setHasChanges(true);
+  };
+
+  const workspaceActions = Object.keys(globalShortcuts).filter((action) => action.startsWith(WORKSPACE_PREFIX));
+  const unboundProjects = projects.filter((p) => !(`${WORKSPACE_PREFIX}${p.name}` in globalShortcuts));
+
   const handleSave = async () => {
     if (!settings) return;
 
@@ -320,11 +352,21 @@ export function Settings() {
       scriptsConfig: {
         ...settings.scriptsConfig,
         commandTemplates,
+        echoCommand,
       },
       toolboxBaseUrl,
       backupRepoPath: backupRepoPath || undefined,
       globalHotkey: globalHotkey || undefined,
+      globalShortcuts: Object.fromEntries(Object.entries(globalShortcuts).filter(([, combo]) => combo)),
       shimDir: shimDir.trim() || undefined,
+      shell: {
+        kind: shellKind,
+        customPath: shellCustomPath.trim() || undefined,
+      },
+      pinRunningScripts: settings.pinRunningScripts,
+      lastExportDir: settings.lastExportDir,
+      tui: settings.tui,
+      maskEnvValues,
     };
 
     try {
@@ -342,6 +384,17 @@ export function Settings() {
       } catch (err) {
         toast.error(`Hotkey saved but registration failed: ${err}`);
       }
+      // Shortcuts that couldn't be registered don't fail the save (the
+      // palette's was reported just above)
+      try {
+        const warnings = await getGlobalShortcutWarnings();
+        setShortcutWarnings(warnings);
+        warnings
+          .filter((w) => w.action !== 'openPalette')
+          .forEach((w) => toast.warning('Global shortcut not registered', { description: w.message }));
+      } catch (err) {
+        console.error('Fetching global shortcut warnings failed:', err);
+      }
       setHasChanges(false);
       toast.success('Settings saved');
 
@@ -577,6 +630,74 @@ export function Settings() {
               The default method used when starting services
             </p>
           </div>
+          <div className="grid gap-2">
+            <Label htmlFor="command-shell">Command Shell</Label>
+            <Select
+              value={shellKind}
+              onValueChange={(value: ShellKind) => {
+                setShellKind(value);
+                setHasChanges(true);
+              }}
+            >
+              <SelectTrigger id="command-shell">
+                <SelectValue placeholder="Select shell" />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="default">System Default</SelectItem>
+                <SelectItem value="sh">sh</SelectItem>
+                <SelectItem value="bash">bash</SelectItem>
+                <SelectItem value="zsh">zsh</SelectItem>
+                <SelectItem value="cmd">cmd</SelectItem>
+                <SelectItem value="powershell">PowerShell</SelectItem>
+                <SelectItem value="custom">Custom</SelectItem>
+              </SelectContent>
+            </Select>
+            {shellKind === 'custom' && (
+              <Input
+                value={shellCustomPath}
+                onChange={(e) => {
+                  setShellCustomPath(e.target.value);
+                  setHasChanges(true);
+                }}
+                placeholder="/usr/local/bin/fish"
+              />
+            )}
+            <p className="text-xs text-muted-foreground">
+              The shell used to run service and script commands
+            </p>
+          </div>
+          <div className="flex items-center gap-3">
+            <Checkbox
+              id="echo-command"
+              checked={echoCommand}
+              onCheckedChange={(checked) => {
+                setEchoCommand(checked === true);
+                setHasChanges(true);
+              }}
+            />
+            <div className="grid gap-1">
+              <Label htmlFor="echo-command">Echo command before running</Label>
+              <p className="text-xs text-muted-foreground">
+                Start each script run's output with the resolved command and its working directory
+              </p>
+            </div>
+          </div>
+          <div className="flex items-center gap-3">
+            <Checkbox
+              id="mask-env-values"
+              checked={maskEnvValues}
+              onCheckedChange={(checked) => {
+                setMaskEnvValues(checked === true);
+                setHasChanges(true);
+              }}
+            />
+            <div className="grid gap-1">
+              <Label htmlFor="mask-env-values">Mask secrets in env files</Label>
+              <p className="text-xs text-muted-foreground">
+                Show values of keys like API_KEY, *_TOKEN or DB_PASSWORD as •••• in env file content
+              </p>
+            </div>
+          </div>
         </CardContent>
       </Card>
 
@@ -608,6 +729,73 @@ export function Settings() {
         </CardContent>
       </Card>
 
+      {/* Global Shortcuts */}
+      <Card>
+        <CardHeader>
+          <CardTitle>Global Shortcuts</CardTitle>
+          <CardDescription>
+            System-wide shortcuts that work while CortX is in the background. Stopping everything stops
+            what CortX started (services gracefully, in order) and shows a notification of what was stopped.
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="grid gap-2">
+            <Label>Stop everything</Label>
+            <HotkeyInput
+              value={globalShortcuts.stopAll ?? ''}
+              defaultCombo=""
+              onChange={(combo) => setShortcut('stopAll', combo)}
+            />
+          </div>
+          <div className="grid gap-2">
+            <Label>Show / hide window</Label>
+            <HotkeyInput
+              value={globalShortcuts.toggleWindow ?? ''}
+              defaultCombo=""
+              onChange={(combo) => setShortcut('toggleWindow', combo)}
+            />
+          </div>
+          {workspaceActions.map((action) => (
+            <div key={action} className="grid gap-2">
+              <div className="flex items-center justify-between">
+                <Label>Start {action.slice(WORKSPACE_PREFIX.length)}</Label>
+                <Button variant="ghost" size="sm" onClick={() => removeShortcut(action)} title="Remove shortcut">
+                  <Trash2 className="size-3.5" />
+                </Button>
+              </div>
+              <HotkeyInput
+                value={globalShortcuts[action]}
+                defaultCombo=""
+                onChange={(combo) => setShortcut(action, combo)}
+              />
+            </div>
+          ))}
+          {unboundProjects.length > 0 && (
+            <Select value="" onValueChange={(name) => setShortcut(`${WORKSPACE_PREFIX}${name}`, '')}>
+              <SelectTrigger>
+                <SelectValue placeholder="Add a shortcut that starts a project's services…" />
+              </SelectTrigger>
+              <SelectContent>
+                {unboundProjects.map((p) => (
+                  <SelectItem key={p.id} value={p.name}>
+                    {p.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          )}
+          {shortcutWarnings.length > 0 && (
+            <div className="space-y-1">
+              {shortcutWarnings.map((w) => (
+                <p key={`${w.action}-${w.accelerator}`} className="text-xs text-destructive">
+                  {w.message}
+                </p>
+              ))}
+            </div>
+          )}
+        </CardContent>
+      </Card>
+
       {/* Toolbox Base URL */}
       <Card>
         <CardHeader>
@@ -753,6 +941,10 @@ export function Settings() {
                 <Upload className="size-4 mr-2" />
                 Import
               </Button>
+              <Button variant="outline" onClick={handleSync}>
+                <RefreshCw className="size-4 mr-2" />
+                Sync Scripts
+              </Button>
             </div>
           </div>
 
@@ -887,6 +1079,8 @@ export function Settings() {
         onOpenChange={setShowStatusManager}
       />
 
+      <ScriptSyncDialog plan={syncPlan} onClose={() => setSyncPlan(null)} />
+
       {/* Import Preview Dialog */}
       <Dialog open={importDialogOpen} onOpenChange={(open) => {
         if (!isImporting) {